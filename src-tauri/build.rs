@@ -20,6 +20,23 @@ fn main() {
     #[cfg(target_os = "macos")]
     println!("cargo:rustc-link-arg=-Wl,-rpath,/usr/lib/swift");
 
+    // Sleep/wake notifications and thermal state for the capture loop
+    #[cfg(target_os = "macos")]
+    {
+        cc::Build::new()
+            .file("src/capture/power_bridge.m")
+            .flag("-fobjc-arc")
+            .compile("power_bridge");
+        println!("cargo:rustc-link-lib=framework=AppKit");
+
+        // Dedicated mouse for the laser pointer and clickers for the keymap
+        cc::Build::new()
+            .file("src/input/tap_bridge.m")
+            .flag("-fobjc-arc")
+            .compile("tap_bridge");
+        println!("cargo:rustc-link-lib=framework=IOKit");
+    }
+
     // Compile Syphon Objective-C bridge when the syphon feature is enabled
     #[cfg(target_os = "macos")]
     if std::env::var("CARGO_FEATURE_SYPHON").is_ok() {
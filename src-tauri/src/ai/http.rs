@@ -0,0 +1,86 @@
+/*
+ * This file is part of StreamSlate.
+ * Copyright (C) 2025 StreamSlate Contributors
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+
+//! Summarizer backed by an HTTP endpoint
+//!
+//! The endpoint receives a `SummaryRequest` as JSON and answers with
+//! `{"summary": "..."}`. A small local proxy can adapt this to any model
+//! server.
+
+use super::{Summarizer, SummarizerConfig, SummaryRequest};
+use crate::error::{Result, StreamSlateError};
+use futures_util::future::BoxFuture;
+use serde::Deserialize;
+use std::time::Duration;
+
+/// Give up on an endpoint that doesn't answer within this time
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// Posts page text to the configured endpoint
+pub struct HttpSummarizer {
+    endpoint: String,
+    api_key: Option<String>,
+}
+
+impl HttpSummarizer {
+    /// Summarizer for `config`, or `None` if no endpoint is set
+    pub fn from_config(config: &SummarizerConfig) -> Option<Self> {
+        Some(Self {
+            endpoint: config.endpoint.clone()?,
+            api_key: config.api_key.clone(),
+        })
+    }
+}
+
+impl Summarizer for HttpSummarizer {
+    fn summarize<'a>(&'a self, request: &'a SummaryRequest) -> BoxFuture<'a, Result<String>> {
+        #[derive(Deserialize)]
+        struct SummaryResponse {
+            summary: String,
+        }
+
+        Box::pin(async move {
+            let mut builder = tauri_plugin_http::reqwest::Client::new()
+                .post(&self.endpoint)
+                .header("Content-Type", "application/json")
+                .timeout(REQUEST_TIMEOUT)
+                .body(serde_json::to_vec(request)?);
+            if let Some(key) = &self.api_key {
+                builder = builder.bearer_auth(key);
+            }
+
+            let response = builder
+                .send()
+                .await
+                .map_err(|e| StreamSlateError::Other(format!("Summarizer request failed: {e}")))?;
+            let status = response.status();
+            if !status.is_success() {
+                return Err(StreamSlateError::Other(format!(
+                    "Summarizer returned {status}"
+                )));
+            }
+
+            let body = response
+                .bytes()
+                .await
+                .map_err(|e| StreamSlateError::Other(format!("Summarizer request failed: {e}")))?;
+            let parsed: SummaryResponse = serde_json::from_slice(&body)?;
+            Ok(parsed.summary.trim().to_string())
+        })
+    }
+}
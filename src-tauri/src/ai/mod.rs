@@ -0,0 +1,262 @@
+/*
+ * This file is part of StreamSlate.
+ * Copyright (C) 2025 StreamSlate Contributors
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+
+//! Slide summarization hooks
+//!
+//! Extracted page text can be handed to a `Summarizer` and the results are
+//! cached per document and page, keyed by a digest of the text so edited
+//! slides are summarized again. Nothing is sent anywhere until the user
+//! configures an endpoint, and the HTTP summarizer is only compiled in with
+//! the `ai` feature.
+
+#[cfg(feature = "ai")]
+pub mod http;
+
+use crate::error::{Result, StreamSlateError};
+use crate::settings::Settings;
+use futures_util::future::BoxFuture;
+use ring::digest::{digest, SHA256};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use tauri::{AppHandle, Manager};
+
+/// File name of the summarizer settings inside the app data directory
+pub const CONFIG_FILE: &str = "summarizer.json";
+
+/// Directory of the per-document summary caches inside the app data directory
+const CACHE_DIR: &str = "summaries";
+
+/// Default cap on the page text sent to the endpoint
+pub const DEFAULT_MAX_CHARS: usize = 8000;
+
+/// Where page text is sent for summarization
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SummarizerConfig {
+    /// URL that receives `SummaryRequest`s as a JSON `POST`, local or remote
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub endpoint: Option<String>,
+    /// Sent as a bearer token, for hosted endpoints
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub api_key: Option<String>,
+    /// Model name passed through to the endpoint
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub model: Option<String>,
+    /// Summarize each page as it is shown
+    #[serde(default)]
+    pub auto_summarize: bool,
+    /// Longest page text sent, in characters (default 8000)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_chars: Option<usize>,
+}
+
+impl SummarizerConfig {
+    /// Whether summaries can be requested at all
+    pub fn is_enabled(&self) -> bool {
+        self.endpoint.is_some()
+    }
+
+    /// Reject URLs that can't be posted to
+    pub fn validate(&self) -> Result<()> {
+        if let Some(url) = &self.endpoint {
+            if !(url.starts_with("http://") || url.starts_with("https://")) {
+                return Err(StreamSlateError::Other(format!(
+                    "Summarizer endpoint must be an http(s) URL: {url}"
+                )));
+            }
+        }
+        if self.max_chars == Some(0) {
+            return Err(StreamSlateError::Other(
+                "Summarizer text limit must be at least 1 character".to_string(),
+            ));
+        }
+        Ok(())
+    }
+}
+
+impl Settings for SummarizerConfig {
+    fn check(&self) -> Result<()> {
+        self.validate()
+    }
+}
+
+/// Page text handed to a summarizer
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SummaryRequest {
+    /// File name of the document
+    pub document: String,
+    pub page: u32,
+    pub text: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub model: Option<String>,
+}
+
+/// Produces a summary of one page's text
+///
+/// Implement this to plug in another backend; `http::HttpSummarizer` talks
+/// to a configured endpoint.
+pub trait Summarizer: Send + Sync {
+    fn summarize<'a>(&'a self, request: &'a SummaryRequest) -> BoxFuture<'a, Result<String>>;
+}
+
+/// Cached summary of a page
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PageSummary {
+    pub page: u32,
+    pub summary: String,
+    /// Digest of the text that was summarized
+    pub text_hash: String,
+    /// When the summary was made (RFC 3339)
+    pub created_at: String,
+}
+
+/// Summaries of one document's pages
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SummaryCache {
+    pub document: String,
+    pub pages: BTreeMap<u32, PageSummary>,
+}
+
+impl SummaryCache {
+    pub fn new(document: &str) -> Self {
+        Self {
+            document: document.to_string(),
+            pages: BTreeMap::new(),
+        }
+    }
+
+    /// The page's summary, if it was made from the same text
+    pub fn get(&self, page: u32, text_hash: &str) -> Option<&PageSummary> {
+        self.pages
+            .get(&page)
+            .filter(|summary| summary.text_hash == text_hash)
+    }
+
+    pub fn insert(&mut self, summary: PageSummary) {
+        self.pages.insert(summary.page, summary);
+    }
+}
+
+/// Hex SHA-256 of page text
+pub fn text_hash(text: &str) -> String {
+    digest(&SHA256, text.as_bytes())
+        .as_ref()
+        .iter()
+        .map(|b| format!("{b:02x}"))
+        .collect()
+}
+
+/// Collapse whitespace and cut the text to `max_chars` characters
+pub fn prepare_text(text: &str, max_chars: usize) -> String {
+    text.split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .chars()
+        .take(max_chars)
+        .collect()
+}
+
+/// Cache file for `document`, named by a digest of its path
+pub fn cache_path(app_handle: &AppHandle, document: &str) -> Option<PathBuf> {
+    let name = format!("{}.json", &text_hash(document)[..16]);
+    app_handle
+        .path()
+        .app_data_dir()
+        .ok()
+        .map(|dir| dir.join(CACHE_DIR).join(name))
+}
+
+/// Load the summaries stored for `document`, or an empty cache
+pub fn load_cache(path: &Path, document: &str) -> SummaryCache {
+    std::fs::read_to_string(path)
+        .ok()
+        .and_then(|content| serde_json::from_str::<SummaryCache>(&content).ok())
+        .filter(|cache| cache.document == document)
+        .unwrap_or_else(|| SummaryCache::new(document))
+}
+
+/// Persist a document's summaries
+pub fn save_cache(path: &Path, cache: &SummaryCache) -> Result<()> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    std::fs::write(path, serde_json::to_string_pretty(cache)?)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn summary(page: u32, text: &str) -> PageSummary {
+        PageSummary {
+            page,
+            summary: format!("About {text}"),
+            text_hash: text_hash(text),
+            created_at: "2025-01-01T00:00:00Z".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_cache_keyed_by_text() {
+        let mut cache = SummaryCache::new("/talks/deck.pdf");
+        cache.insert(summary(3, "Quarterly results"));
+
+        assert!(cache.get(3, &text_hash("Quarterly results")).is_some());
+        // Edited slide text invalidates the summary
+        assert!(cache
+            .get(3, &text_hash("Quarterly results (revised)"))
+            .is_none());
+        assert!(cache.get(4, &text_hash("Quarterly results")).is_none());
+    }
+
+    #[test]
+    fn test_cache_round_trip() {
+        let dir =
+            std::env::temp_dir().join(format!("streamslate-summaries-{}", uuid::Uuid::new_v4()));
+        let path = dir.join("cache.json");
+        let mut cache = SummaryCache::new("/talks/deck.pdf");
+        cache.insert(summary(1, "Intro"));
+        save_cache(&path, &cache).unwrap();
+
+        assert_eq!(load_cache(&path, "/talks/deck.pdf"), cache);
+        // A digest collision with another document starts afresh
+        assert!(load_cache(&path, "/talks/other.pdf").pages.is_empty());
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_prepare_text_and_config() {
+        assert_eq!(
+            prepare_text("  Agenda\n\n• One   • Two ", 100),
+            "Agenda • One • Two"
+        );
+        assert_eq!(prepare_text("héllo world", 4), "héll");
+
+        let mut config = SummarizerConfig::default();
+        assert!(!config.is_enabled());
+        config.endpoint = Some("ftp://example.com".to_string());
+        assert!(config.validate().is_err());
+        config.endpoint = Some("http://127.0.0.1:8080/summarize".to_string());
+        assert!(config.validate().is_ok() && config.is_enabled());
+    }
+}
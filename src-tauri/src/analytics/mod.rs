@@ -0,0 +1,461 @@
+/*
+ * This file is part of StreamSlate.
+ * Copyright (C) 2025 StreamSlate Contributors
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+
+//! Page timing analytics
+//!
+//! Records how long each page was on screen during a presentation session
+//! (from opening a document until it is closed or the session is ended) and
+//! delivers the per-page dwell times to a webhook and/or a local CSV file,
+//! so webinar platforms can line audience drop-off up with specific slides.
+//! When OBS is recording, pages also carry their offset into the recording
+//! so chapters can be placed on the video.
+
+use crate::error::{Result, StreamSlateError};
+use crate::obs::RecordingSync;
+use crate::settings::Settings;
+use chrono::{DateTime, SecondsFormat, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::io::Write;
+use std::path::Path;
+use std::time::{Duration, Instant};
+use tracing::{debug, warn};
+
+/// File name of the analytics settings inside the app data directory
+pub const CONFIG_FILE: &str = "analytics.json";
+
+/// Give up on a webhook that doesn't answer within this time
+const WEBHOOK_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Header row of the CSV export
+const CSV_HEADER: &str = "session_id,document,page,visits,dwell_ms,first_viewed_at";
+
+/// Where session reports are delivered
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AnalyticsConfig {
+    /// URL that receives each report as a JSON `POST`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub webhook_url: Option<String>,
+    /// CSV file that each report's pages are appended to
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub csv_path: Option<String>,
+}
+
+impl AnalyticsConfig {
+    /// Whether page timing is recorded at all
+    pub fn is_enabled(&self) -> bool {
+        self.webhook_url.is_some() || self.csv_path.is_some()
+    }
+
+    /// Reject URLs that can't be posted to
+    pub fn validate(&self) -> Result<()> {
+        if let Some(url) = &self.webhook_url {
+            if !(url.starts_with("http://") || url.starts_with("https://")) {
+                return Err(StreamSlateError::Other(format!(
+                    "Analytics webhook must be an http(s) URL: {url}"
+                )));
+            }
+        }
+        if self.csv_path.as_ref().is_some_and(|p| p.trim().is_empty()) {
+            return Err(StreamSlateError::Other(
+                "Analytics CSV path is empty".to_string(),
+            ));
+        }
+        Ok(())
+    }
+}
+
+impl Settings for AnalyticsConfig {}
+
+/// Time spent on one page during a session
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PageDwell {
+    pub page: u32,
+    /// Number of times the page was navigated to
+    pub visits: u32,
+    pub dwell_ms: u64,
+    /// When the page was first shown (RFC 3339)
+    pub first_viewed_at: String,
+    /// Milliseconds into the OBS recording when the page was first shown,
+    /// negative if it was shown before recording started
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub recording_offset_ms: Option<i64>,
+}
+
+/// Per-page dwell times of a finished session
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionReport {
+    pub session_id: String,
+    pub document: String,
+    pub started_at: String,
+    pub ended_at: String,
+    pub duration_ms: u64,
+    /// Pages that were shown, in page order
+    pub pages: Vec<PageDwell>,
+    /// OBS recording the session was presented during
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub recording: Option<RecordingSync>,
+}
+
+#[derive(Debug, Clone)]
+struct PageTotals {
+    visits: u32,
+    dwell: Duration,
+    first_viewed: Duration,
+}
+
+/// Accumulates page dwell times for one document
+#[derive(Debug, Clone)]
+pub struct PageTimer {
+    session_id: String,
+    document: String,
+    started_at: DateTime<Utc>,
+    started: Instant,
+    current: Option<(u32, Instant)>,
+    pages: BTreeMap<u32, PageTotals>,
+    recording: Option<RecordingSync>,
+}
+
+impl PageTimer {
+    /// Start timing `document`, showing `page` from `now`
+    pub fn start(document: &str, page: u32, now: Instant) -> Self {
+        let mut timer = Self {
+            session_id: uuid::Uuid::new_v4().to_string(),
+            document: document.to_string(),
+            started_at: Utc::now(),
+            started: now,
+            current: None,
+            pages: BTreeMap::new(),
+            recording: None,
+        };
+        timer.enter_page(page, now);
+        timer
+    }
+
+    /// Document being timed
+    pub fn document(&self) -> &str {
+        &self.document
+    }
+
+    /// Relate the session to the OBS recording, or forget it with `None`
+    pub fn set_recording(&mut self, recording: Option<RecordingSync>) {
+        self.recording = recording;
+    }
+
+    /// Switch to `page`, closing the time spent on the previous one
+    ///
+    /// Re-entering the page already shown is ignored.
+    pub fn enter_page(&mut self, page: u32, now: Instant) {
+        if self.current.is_some_and(|(current, _)| current == page) {
+            return;
+        }
+        self.close_current(now);
+        let offset = now.saturating_duration_since(self.started);
+        let totals = self.pages.entry(page).or_insert(PageTotals {
+            visits: 0,
+            dwell: Duration::ZERO,
+            first_viewed: offset,
+        });
+        totals.visits += 1;
+        self.current = Some((page, now));
+    }
+
+    /// End the session and summarise it
+    pub fn finish(mut self, now: Instant) -> SessionReport {
+        self.close_current(now);
+        let time = |offset: Duration| {
+            self.started_at + chrono::Duration::from_std(offset).unwrap_or_default()
+        };
+        let at = |offset: Duration| time(offset).to_rfc3339_opts(SecondsFormat::Millis, true);
+        let elapsed = now.saturating_duration_since(self.started);
+
+        SessionReport {
+            session_id: self.session_id.clone(),
+            document: self.document.clone(),
+            started_at: at(Duration::ZERO),
+            ended_at: at(elapsed),
+            duration_ms: elapsed.as_millis() as u64,
+            pages: self
+                .pages
+                .iter()
+                .map(|(&page, totals)| PageDwell {
+                    page,
+                    visits: totals.visits,
+                    dwell_ms: totals.dwell.as_millis() as u64,
+                    first_viewed_at: at(totals.first_viewed),
+                    recording_offset_ms: self
+                        .recording
+                        .as_ref()
+                        .map(|sync| sync.offset_ms(time(totals.first_viewed))),
+                })
+                .collect(),
+            recording: self.recording.clone(),
+        }
+    }
+
+    fn close_current(&mut self, now: Instant) {
+        if let Some((page, since)) = self.current.take() {
+            if let Some(totals) = self.pages.get_mut(&page) {
+                totals.dwell += now.saturating_duration_since(since);
+            }
+        }
+    }
+}
+
+/// CSV rows for `report`, one per page, without the header
+pub fn csv_rows(report: &SessionReport) -> String {
+    let document = csv_field(&report.document);
+    report
+        .pages
+        .iter()
+        .map(|p| {
+            format!(
+                "{},{},{},{},{},{}\n",
+                report.session_id, document, p.page, p.visits, p.dwell_ms, p.first_viewed_at
+            )
+        })
+        .collect()
+}
+
+/// Quote a CSV field if it contains separators, quotes or newlines
+pub(crate) fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+/// Append `report` to the CSV at `path`, writing the header to a new file
+pub fn append_csv(path: &Path, report: &SessionReport) -> Result<()> {
+    if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+        std::fs::create_dir_all(parent)?;
+    }
+    let is_new = std::fs::metadata(path).map_or(true, |m| m.len() == 0);
+    let mut file = std::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)?;
+    if is_new {
+        writeln!(file, "{CSV_HEADER}")?;
+    }
+    file.write_all(csv_rows(report).as_bytes())?;
+    Ok(())
+}
+
+/// Video chapter list for `report`: a `m:ss Page N` line for each page,
+/// at the time it was first shown
+///
+/// Times are offsets into the OBS recording when there was one, otherwise
+/// into the session. Of pages shown within the same second, or before
+/// recording started, only the last gets a chapter. The list starts at 0:00,
+/// as YouTube requires.
+pub fn chapters(report: &SessionReport) -> String {
+    let started = DateTime::parse_from_rfc3339(&report.started_at).ok();
+    let mut marks: Vec<(i64, u32)> = report
+        .pages
+        .iter()
+        .filter_map(|p| {
+            let offset = match report.recording {
+                Some(_) => p.recording_offset_ms?,
+                None => {
+                    let shown = DateTime::parse_from_rfc3339(&p.first_viewed_at).ok()?;
+                    (shown - started?).num_milliseconds()
+                }
+            };
+            Some((offset, p.page))
+        })
+        .collect();
+    marks.sort_unstable();
+
+    let mut chapters: Vec<(u64, u32)> = Vec::new();
+    for (offset, page) in marks {
+        let at = offset.max(0) as u64 / 1000 * 1000;
+        match chapters.last_mut() {
+            Some(last) if last.0 == at => last.1 = page,
+            _ => chapters.push((at, page)),
+        }
+    }
+    if let Some(first) = chapters.first_mut() {
+        first.0 = 0;
+    }
+    chapters
+        .into_iter()
+        .map(|(at, page)| format!("{} Page {page}\n", format_duration(at)))
+        .collect()
+}
+
+/// `m:ss`, or `h:mm:ss` from an hour
+pub fn format_duration(ms: u64) -> String {
+    let seconds = ms / 1000;
+    let (hours, minutes, seconds) = (seconds / 3600, seconds / 60 % 60, seconds % 60);
+    if hours > 0 {
+        format!("{hours}:{minutes:02}:{seconds:02}")
+    } else {
+        format!("{minutes}:{seconds:02}")
+    }
+}
+
+/// `POST` `body` as JSON to `url`
+pub async fn post_webhook<T: Serialize>(url: &str, body: &T) -> Result<()> {
+    let body = serde_json::to_vec(body)?;
+    let response = tauri_plugin_http::reqwest::Client::new()
+        .post(url)
+        .header("Content-Type", "application/json")
+        .timeout(WEBHOOK_TIMEOUT)
+        .body(body)
+        .send()
+        .await
+        .map_err(|e| StreamSlateError::Other(format!("Webhook failed: {e}")))?;
+
+    let status = response.status();
+    if !status.is_success() {
+        return Err(StreamSlateError::Other(format!(
+            "Webhook returned {status}"
+        )));
+    }
+    Ok(())
+}
+
+/// Send `report` to every configured destination in the background
+///
+/// Failures are logged; analytics never interrupt the presentation.
+pub fn deliver(config: &AnalyticsConfig, report: SessionReport) {
+    if report.pages.is_empty() {
+        return;
+    }
+    if let Some(path) = &config.csv_path {
+        match append_csv(Path::new(path), &report) {
+            Ok(()) => debug!(path = %path, "Appended page timings to CSV"),
+            Err(e) => warn!(path = %path, error = %e, "Failed to write page timings"),
+        }
+    }
+    if let Some(url) = config.webhook_url.clone() {
+        tauri::async_runtime::spawn(async move {
+            match post_webhook(&url, &report).await {
+                Ok(()) => debug!(url = %url, "Posted page timings"),
+                Err(e) => warn!(url = %url, error = %e, "Failed to post page timings"),
+            }
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_page_timer_accumulates_dwell() {
+        let start = Instant::now();
+        let at = |ms| start + Duration::from_millis(ms);
+
+        let mut timer = PageTimer::start("talk, final.pdf", 1, start);
+        timer.enter_page(2, at(3_000));
+        timer.enter_page(2, at(4_000));
+        timer.enter_page(1, at(10_000));
+        let report = timer.finish(at(11_500));
+
+        assert_eq!(report.duration_ms, 11_500);
+        let dwell: Vec<_> = report
+            .pages
+            .iter()
+            .map(|p| (p.page, p.visits, p.dwell_ms))
+            .collect();
+        assert_eq!(dwell, [(1, 2, 4_500), (2, 1, 7_000)]);
+        assert_eq!(report.pages[0].first_viewed_at, report.started_at);
+
+        let rows = csv_rows(&report);
+        assert!(rows.starts_with(&format!(
+            "{},\"talk, final.pdf\",1,2,4500,",
+            report.session_id
+        )));
+        assert_eq!(rows.lines().count(), 2);
+        assert!(report.recording.is_none());
+        assert!(report.pages[0].recording_offset_ms.is_none());
+    }
+
+    #[test]
+    fn test_page_timer_recording_offsets() {
+        let start = Instant::now();
+        let mut timer = PageTimer::start("talk.pdf", 1, start);
+        timer.enter_page(2, start + Duration::from_secs(30));
+        // Recording began 10s after the session
+        timer.set_recording(Some(RecordingSync {
+            started_at: timer.started_at + chrono::Duration::seconds(10),
+            round_trip_ms: 12,
+        }));
+        let report = timer.finish(start + Duration::from_secs(60));
+
+        let offsets: Vec<_> = report.pages.iter().map(|p| p.recording_offset_ms).collect();
+        assert_eq!(offsets, [Some(-10_000), Some(20_000)]);
+        assert_eq!(report.recording.unwrap().round_trip_ms, 12);
+    }
+
+    #[test]
+    fn test_chapters() {
+        let start = Instant::now();
+        let at = |s| start + Duration::from_secs(s);
+        let mut timer = PageTimer::start("talk.pdf", 1, start);
+        timer.enter_page(2, at(5));
+        timer.enter_page(3, at(75));
+        timer.enter_page(2, at(90));
+        timer.enter_page(7, at(3_700));
+        let mut report = timer.finish(at(3_800));
+        assert_eq!(
+            chapters(&report),
+            "0:00 Page 1\n0:05 Page 2\n1:15 Page 3\n1:01:40 Page 7\n"
+        );
+
+        // Page 1 and 2 were shown before recording started at 10s
+        report.recording = Some(RecordingSync {
+            started_at: Utc::now(),
+            round_trip_ms: 0,
+        });
+        for (page, offset) in report
+            .pages
+            .iter_mut()
+            .zip([-10_000, -5_000, 65_400, 3_690_000])
+        {
+            page.recording_offset_ms = Some(offset);
+        }
+        assert_eq!(
+            chapters(&report),
+            "0:00 Page 2\n1:05 Page 3\n1:01:30 Page 7\n"
+        );
+    }
+
+    #[test]
+    fn test_config_validation() {
+        let config = AnalyticsConfig {
+            webhook_url: Some("ftp://example.com".to_string()),
+            csv_path: None,
+        };
+        assert!(config.validate().is_err());
+        assert!(!AnalyticsConfig::default().is_enabled());
+
+        let config = AnalyticsConfig {
+            webhook_url: Some("https://example.com/hooks/slides".to_string()),
+            csv_path: None,
+        };
+        assert!(config.validate().is_ok());
+        assert!(config.is_enabled());
+    }
+}
@@ -0,0 +1,190 @@
+/*
+ * This file is part of StreamSlate.
+ * Copyright (C) 2025 StreamSlate Contributors
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+
+//! Linked media cache
+//!
+//! Downloads the videos and images a deck links to into the app cache
+//! directory ahead of a show, so opening a link mid-presentation reads a
+//! local file instead of waiting on the venue network.
+
+use crate::error::{Result, StreamSlateError};
+use ring::digest::{digest, SHA256};
+use std::io::Write;
+use std::path::PathBuf;
+use std::time::Duration;
+use tauri::{AppHandle, Manager};
+
+/// Subdirectory of the app cache directory holding downloaded assets
+const CACHE_DIR: &str = "assets";
+
+/// Refuse assets larger than this
+pub const MAX_ASSET_BYTES: u64 = 2 * 1024 * 1024 * 1024;
+
+/// Give up on a server that doesn't start answering within this time
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(15);
+
+/// Cache of downloaded assets, keyed by URL
+#[derive(Debug, Clone)]
+pub struct AssetCache {
+    dir: PathBuf,
+}
+
+impl AssetCache {
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self { dir: dir.into() }
+    }
+
+    /// Cache in the app cache directory
+    pub fn for_app(app_handle: &AppHandle) -> Result<Self> {
+        let dir = app_handle
+            .path()
+            .app_cache_dir()
+            .map_err(|e| StreamSlateError::Other(format!("No cache directory: {e}")))?
+            .join(CACHE_DIR);
+        Ok(Self::new(dir))
+    }
+
+    /// Where `url` is stored once downloaded
+    ///
+    /// The file name is the URL's SHA-256 plus its extension, so players
+    /// can still tell the media type from the path.
+    pub fn path_for(&self, url: &str) -> PathBuf {
+        let hash: String = digest(&SHA256, url.as_bytes())
+            .as_ref()
+            .iter()
+            .map(|b| format!("{b:02x}"))
+            .collect();
+        let extension = url
+            .split(['?', '#'])
+            .next()
+            .and_then(|path| path.rsplit('/').next())
+            .and_then(|file| file.rsplit_once('.'))
+            .map(|(_, ext)| ext.to_ascii_lowercase())
+            .filter(|ext| {
+                !ext.is_empty() && ext.len() <= 5 && ext.bytes().all(|b| b.is_ascii_alphanumeric())
+            });
+        match extension {
+            Some(ext) => self.dir.join(format!("{hash}.{ext}")),
+            None => self.dir.join(hash),
+        }
+    }
+
+    /// Local copy of `url`, if it has been downloaded
+    pub fn cached(&self, url: &str) -> Option<PathBuf> {
+        let path = self.path_for(url);
+        path.is_file().then_some(path)
+    }
+
+    /// Download `url` into the cache, returning the number of bytes stored
+    ///
+    /// `on_progress` is called with the bytes received so far and the
+    /// expected total, when the server reports one. The download is written
+    /// to a temporary file and only moved into place once complete, so an
+    /// interrupted transfer never leaves a truncated asset behind.
+    pub async fn fetch(
+        &self,
+        url: &str,
+        mut on_progress: impl FnMut(u64, Option<u64>),
+    ) -> Result<u64> {
+        std::fs::create_dir_all(&self.dir)?;
+        let mut response = tauri_plugin_http::reqwest::Client::builder()
+            .connect_timeout(CONNECT_TIMEOUT)
+            .build()
+            .map_err(|e| StreamSlateError::Other(format!("HTTP client: {e}")))?
+            .get(url)
+            .send()
+            .await
+            .map_err(|e| StreamSlateError::Other(format!("Download failed: {e}")))?;
+
+        let status = response.status();
+        if !status.is_success() {
+            return Err(StreamSlateError::Other(format!(
+                "Download returned {status}"
+            )));
+        }
+        let expected = response.content_length();
+        if expected.is_some_and(|len| len > MAX_ASSET_BYTES) {
+            return Err(StreamSlateError::Other(format!(
+                "Asset is larger than {} MiB",
+                MAX_ASSET_BYTES / (1024 * 1024)
+            )));
+        }
+
+        let path = self.path_for(url);
+        let partial = path.with_extension("part");
+        let result = async {
+            let mut file = std::fs::File::create(&partial)?;
+            let mut received = 0u64;
+            while let Some(chunk) = response
+                .chunk()
+                .await
+                .map_err(|e| StreamSlateError::Other(format!("Download failed: {e}")))?
+            {
+                received += chunk.len() as u64;
+                if received > MAX_ASSET_BYTES {
+                    return Err(StreamSlateError::Other(format!(
+                        "Asset is larger than {} MiB",
+                        MAX_ASSET_BYTES / (1024 * 1024)
+                    )));
+                }
+                file.write_all(&chunk)?;
+                on_progress(received, expected);
+            }
+            file.sync_all()?;
+            Ok(received)
+        }
+        .await;
+
+        match result {
+            Ok(received) => {
+                std::fs::rename(&partial, &path)?;
+                Ok(received)
+            }
+            Err(e) => {
+                let _ = std::fs::remove_file(&partial);
+                Err(e)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::Path;
+
+    #[test]
+    fn test_path_for() {
+        let cache = AssetCache::new("/cache");
+        let video = cache.path_for("https://cdn.example.com/clips/Intro.MP4?token=abc");
+        assert_eq!(video.parent(), Some(Path::new("/cache")));
+        assert!(video.to_string_lossy().ends_with(".mp4"));
+        assert_eq!(
+            video,
+            cache.path_for("https://cdn.example.com/clips/Intro.MP4?token=abc")
+        );
+        assert_ne!(
+            video,
+            cache.path_for("https://cdn.example.com/clips/Intro.MP4?token=def")
+        );
+
+        let bare = cache.path_for("https://example.com/download");
+        assert_eq!(bare.extension(), None);
+        assert_eq!(bare.file_name().unwrap().len(), 64);
+    }
+}
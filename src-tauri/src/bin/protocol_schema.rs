@@ -0,0 +1,42 @@
+/*
+ * This file is part of StreamSlate.
+ * Copyright (C) 2025 StreamSlate Contributors
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+
+//! Regenerate the protocol JSON Schema and TypeScript definitions
+//!
+//! Usage: `cargo run --bin protocol_schema [repo-root]`
+
+use std::path::PathBuf;
+
+fn main() {
+    let repo_root = std::env::args()
+        .nth(1)
+        .map(PathBuf::from)
+        .unwrap_or_else(|| PathBuf::from(env!("CARGO_MANIFEST_DIR")).join(".."));
+
+    match streamslate::websocket::schema::write_generated_files(&repo_root) {
+        Ok(paths) => {
+            for path in paths {
+                println!("wrote {}", path.display());
+            }
+        }
+        Err(e) => {
+            eprintln!("failed to write protocol schema: {e}");
+            std::process::exit(1);
+        }
+    }
+}
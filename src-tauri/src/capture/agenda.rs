@@ -0,0 +1,220 @@
+/*
+ * This file is part of StreamSlate.
+ * Copyright (C) 2025 StreamSlate Contributors
+ *
+ * Agenda/progress strip built from the document outline.
+ * Each top-level outline entry becomes a section spanning the pages up to
+ * the next one; the strip along the bottom of the output shows finished,
+ * current and upcoming sections so viewers can see where the talk is.
+ */
+
+use super::CapturedFrame;
+use crate::pdf::outline::OutlineEntry;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// Where an agenda section stands relative to the current page
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum AgendaStatus {
+    Done,
+    Current,
+    Upcoming,
+}
+
+/// One section of the agenda
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub struct AgendaItem {
+    pub title: String,
+    /// First page of the section
+    pub page: u32,
+    /// Last page of the section
+    pub end_page: u32,
+    pub status: AgendaStatus,
+}
+
+/// Sections of the open document with the current position
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub struct Agenda {
+    pub items: Vec<AgendaItem>,
+    /// Index of the current section in `items`
+    pub current: Option<usize>,
+    pub page: u32,
+    pub total_pages: u32,
+}
+
+/// Position within the current section, e.g. "Section 3 of 7, 45% through"
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub struct SectionProgress {
+    /// 1-based number of the current section
+    pub index: u32,
+    pub count: u32,
+    pub title: String,
+    /// Share of the section's pages presented, including the current one
+    pub percent: u8,
+}
+
+impl Agenda {
+    /// Progress through the current section, if the page is in one
+    pub fn progress(&self) -> Option<SectionProgress> {
+        let index = self.current?;
+        let item = &self.items[index];
+        let len = item.end_page - item.page + 1;
+        let done = (self.page - item.page + 1).min(len);
+        Some(SectionProgress {
+            index: index as u32 + 1,
+            count: self.items.len() as u32,
+            title: item.title.clone(),
+            percent: ((done * 100 + len / 2) / len) as u8,
+        })
+    }
+}
+
+/// Build the agenda for `page` from the outline's top-level entries
+pub fn build(entries: &[OutlineEntry], page: u32, total_pages: u32) -> Agenda {
+    let mut starts: Vec<&OutlineEntry> = entries
+        .iter()
+        .filter(|e| e.page >= 1 && e.page <= total_pages)
+        .collect();
+    starts.sort_by_key(|e| e.page);
+    // Several entries on one page would make empty sections; keep the first
+    starts.dedup_by_key(|e| e.page);
+
+    let current = starts.iter().rposition(|e| e.page <= page);
+    let items = starts
+        .iter()
+        .enumerate()
+        .map(|(i, entry)| AgendaItem {
+            title: entry.title.clone(),
+            page: entry.page,
+            end_page: starts.get(i + 1).map_or(total_pages, |next| next.page - 1),
+            status: match current {
+                Some(c) if i < c => AgendaStatus::Done,
+                Some(c) if i == c => AgendaStatus::Current,
+                _ => AgendaStatus::Upcoming,
+            },
+        })
+        .collect();
+
+    Agenda {
+        items,
+        current,
+        page,
+        total_pages,
+    }
+}
+
+/// BGRA colours of the strip
+const DONE: [u8; 3] = [0xE0, 0xE0, 0xE0];
+const CURRENT: [u8; 3] = [0xFF, 0x8C, 0x4F];
+const UPCOMING: [u8; 3] = [0x30, 0x30, 0x30];
+
+/// Draw the agenda as a segmented strip along the bottom of `frame`
+///
+/// Segment widths follow the sections' page counts; the current section
+/// fills as its pages are presented.
+pub fn draw_strip(frame: &mut CapturedFrame, agenda: &Agenda) {
+    let (width, height) = (frame.width as usize, frame.height as usize);
+    let stride = frame.bytes_per_row as usize;
+    let pages = agenda.total_pages as usize;
+    if agenda.items.is_empty() || width == 0 || pages == 0 || frame.data.len() < stride * height {
+        return;
+    }
+
+    let bar = (height / 60).max(4).min(height);
+    let gap = (width / 480).max(1);
+    let first_page = agenda.items[0].page as usize;
+    let span = pages + 1 - first_page;
+
+    for item in &agenda.items {
+        let x0 = (item.page as usize - first_page) * width / span;
+        let x1 = ((item.end_page as usize + 1 - first_page) * width / span).min(width);
+        let x1 = x1.saturating_sub(gap).max(x0);
+        let filled = match item.status {
+            AgendaStatus::Done => x1,
+            AgendaStatus::Upcoming => x0,
+            AgendaStatus::Current => {
+                let done = agenda.page.saturating_sub(item.page) as usize + 1;
+                let len = (item.end_page - item.page) as usize + 1;
+                x0 + (x1 - x0) * done.min(len) / len
+            }
+        };
+
+        for y in height - bar..height {
+            let row = &mut frame.data[y * stride..][..width * 4];
+            for x in x0..x1 {
+                let colour = match item.status {
+                    AgendaStatus::Done => DONE,
+                    _ if x < filled => CURRENT,
+                    _ => UPCOMING,
+                };
+                row[x * 4..][..3].copy_from_slice(&colour);
+                row[x * 4 + 3] = 0xFF;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(title: &str, page: u32) -> OutlineEntry {
+        OutlineEntry {
+            title: title.to_string(),
+            page,
+        }
+    }
+
+    #[test]
+    fn test_build_agenda() {
+        let entries = [entry("Intro", 1), entry("Demo", 4), entry("Q&A", 9)];
+        let agenda = build(&entries, 5, 10);
+
+        assert_eq!(agenda.current, Some(1));
+        let spans: Vec<_> = agenda
+            .items
+            .iter()
+            .map(|i| (i.page, i.end_page, i.status))
+            .collect();
+        assert_eq!(
+            spans,
+            [
+                (1, 3, AgendaStatus::Done),
+                (4, 8, AgendaStatus::Current),
+                (9, 10, AgendaStatus::Upcoming),
+            ]
+        );
+
+        let progress = agenda.progress().unwrap();
+        assert_eq!(
+            (progress.index, progress.count, progress.percent),
+            (2, 3, 40)
+        );
+
+        // Before the first section nothing is current
+        let before = build(&[entry("Body", 3)], 1, 5);
+        assert_eq!(before.current, None);
+        assert_eq!(before.progress(), None);
+    }
+
+    #[test]
+    fn test_draw_strip() {
+        let mut frame = CapturedFrame {
+            data: vec![0; 100 * 100 * 4],
+            width: 100,
+            height: 100,
+            bytes_per_row: 400,
+            timestamp_ns: 0,
+        };
+        let agenda = build(&[entry("A", 1), entry("B", 3)], 3, 4);
+        draw_strip(&mut frame, &agenda);
+
+        let pixel = |x: usize, y: usize| &frame.data[(y * 100 + x) * 4..][..3];
+        assert_eq!(pixel(10, 99), DONE);
+        // Page 3 of 3-4: the first half of the current section is filled
+        assert_eq!(pixel(55, 99), CURRENT);
+        assert_eq!(pixel(90, 99), UPCOMING);
+        assert_eq!(pixel(10, 50), [0, 0, 0]);
+    }
+}
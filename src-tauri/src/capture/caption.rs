@@ -0,0 +1,152 @@
+/*
+ * This file is part of StreamSlate.
+ * Copyright (C) 2025 StreamSlate Contributors
+ *
+ * Caption box drawn over the output frames.
+ * Text is set in a built-in 5x7 pixel font (A-Z, 0-9 and space) scaled to
+ * the frame, so short codes such as checkpoint words can be shown on NDI and
+ * Syphon without a font renderer.
+ */
+
+use super::CapturedFrame;
+
+/// Glyph rows, most significant of the low five bits on the left
+const GLYPH_ROWS: usize = 7;
+const GLYPH_COLS: usize = 5;
+
+/// Glyphs for `A`-`Z`
+const LETTERS: [[u8; GLYPH_ROWS]; 26] = [
+    [0x0E, 0x11, 0x11, 0x1F, 0x11, 0x11, 0x11],
+    [0x1E, 0x11, 0x11, 0x1E, 0x11, 0x11, 0x1E],
+    [0x0E, 0x11, 0x10, 0x10, 0x10, 0x11, 0x0E],
+    [0x1E, 0x11, 0x11, 0x11, 0x11, 0x11, 0x1E],
+    [0x1F, 0x10, 0x10, 0x1E, 0x10, 0x10, 0x1F],
+    [0x1F, 0x10, 0x10, 0x1E, 0x10, 0x10, 0x10],
+    [0x0E, 0x11, 0x10, 0x17, 0x11, 0x11, 0x0F],
+    [0x11, 0x11, 0x11, 0x1F, 0x11, 0x11, 0x11],
+    [0x0E, 0x04, 0x04, 0x04, 0x04, 0x04, 0x0E],
+    [0x07, 0x02, 0x02, 0x02, 0x02, 0x12, 0x0C],
+    [0x11, 0x12, 0x14, 0x18, 0x14, 0x12, 0x11],
+    [0x10, 0x10, 0x10, 0x10, 0x10, 0x10, 0x1F],
+    [0x11, 0x1B, 0x15, 0x15, 0x11, 0x11, 0x11],
+    [0x11, 0x11, 0x19, 0x15, 0x13, 0x11, 0x11],
+    [0x0E, 0x11, 0x11, 0x11, 0x11, 0x11, 0x0E],
+    [0x1E, 0x11, 0x11, 0x1E, 0x10, 0x10, 0x10],
+    [0x0E, 0x11, 0x11, 0x11, 0x15, 0x12, 0x0D],
+    [0x1E, 0x11, 0x11, 0x1E, 0x14, 0x12, 0x11],
+    [0x0F, 0x10, 0x10, 0x0E, 0x01, 0x01, 0x1E],
+    [0x1F, 0x04, 0x04, 0x04, 0x04, 0x04, 0x04],
+    [0x11, 0x11, 0x11, 0x11, 0x11, 0x11, 0x0E],
+    [0x11, 0x11, 0x11, 0x11, 0x11, 0x0A, 0x04],
+    [0x11, 0x11, 0x11, 0x15, 0x15, 0x15, 0x0A],
+    [0x11, 0x11, 0x0A, 0x04, 0x0A, 0x11, 0x11],
+    [0x11, 0x11, 0x0A, 0x04, 0x04, 0x04, 0x04],
+    [0x1F, 0x01, 0x02, 0x04, 0x08, 0x10, 0x1F],
+];
+
+/// Glyphs for `0`-`9`
+const DIGITS: [[u8; GLYPH_ROWS]; 10] = [
+    [0x0E, 0x11, 0x13, 0x15, 0x19, 0x11, 0x0E],
+    [0x04, 0x0C, 0x04, 0x04, 0x04, 0x04, 0x0E],
+    [0x0E, 0x11, 0x01, 0x02, 0x04, 0x08, 0x1F],
+    [0x1F, 0x02, 0x04, 0x02, 0x01, 0x11, 0x0E],
+    [0x02, 0x06, 0x0A, 0x12, 0x1F, 0x02, 0x02],
+    [0x1F, 0x10, 0x1E, 0x01, 0x01, 0x11, 0x0E],
+    [0x06, 0x08, 0x10, 0x1E, 0x11, 0x11, 0x0E],
+    [0x1F, 0x01, 0x02, 0x04, 0x08, 0x08, 0x08],
+    [0x0E, 0x11, 0x11, 0x0E, 0x11, 0x11, 0x0E],
+    [0x0E, 0x11, 0x11, 0x0F, 0x01, 0x02, 0x0C],
+];
+
+/// BGRA colours of the caption
+const BOX: [u8; 3] = [0x20, 0x20, 0x20];
+const TEXT: [u8; 3] = [0xFF, 0xFF, 0xFF];
+
+/// Glyph of a character; anything outside the font is blank
+fn glyph(c: char) -> [u8; GLYPH_ROWS] {
+    match c.to_ascii_uppercase() {
+        c @ 'A'..='Z' => LETTERS[c as usize - 'A' as usize],
+        c @ '0'..='9' => DIGITS[c as usize - '0' as usize],
+        _ => [0; GLYPH_ROWS],
+    }
+}
+
+/// Draw `text` in a box centred near the top of `frame`
+///
+/// The text is as large as fits: up to a tenth of the frame height, and
+/// never wider than 90% of the frame.
+pub fn draw(frame: &mut CapturedFrame, text: &str) {
+    let (width, height) = (frame.width as usize, frame.height as usize);
+    let stride = frame.bytes_per_row as usize;
+    let chars: Vec<char> = text.chars().collect();
+    if chars.is_empty() || width == 0 || frame.data.len() < stride * height {
+        return;
+    }
+
+    // One blank column between glyphs and a one-cell margin around the text
+    let cells_x = chars.len() * (GLYPH_COLS + 1) + 1;
+    let cells_y = GLYPH_ROWS + 2;
+    let scale = (height / 10 / cells_y).min(width * 9 / 10 / cells_x);
+    if scale == 0 {
+        return;
+    }
+
+    let (box_w, box_h) = (cells_x * scale, cells_y * scale);
+    let x0 = (width - box_w) / 2;
+    let y0 = (height / 12).min(height - box_h);
+
+    for y in 0..box_h {
+        let row = &mut frame.data[(y0 + y) * stride..][..width * 4];
+        let cell_y = y / scale;
+        for x in 0..box_w {
+            let cell_x = x / scale;
+            let lit = (1..=GLYPH_ROWS).contains(&cell_y)
+                && cell_x >= 1
+                && (cell_x - 1) % (GLYPH_COLS + 1) < GLYPH_COLS
+                && chars
+                    .get((cell_x - 1) / (GLYPH_COLS + 1))
+                    .is_some_and(|&c| {
+                        let column = (cell_x - 1) % (GLYPH_COLS + 1);
+                        glyph(c)[cell_y - 1] & (0x10 >> column) != 0
+                    });
+            let pixel = &mut row[(x0 + x) * 4..][..4];
+            pixel[..3].copy_from_slice(if lit { &TEXT } else { &BOX });
+            pixel[3] = 0xFF;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_draw_caption() {
+        let mut frame = CapturedFrame {
+            data: vec![0; 400 * 200 * 4],
+            width: 400,
+            height: 200,
+            bytes_per_row: 1600,
+            timestamp_ns: 0,
+        };
+        draw(&mut frame, "T");
+
+        // Scale 2 (200 / 10 / 9): a 14x18 box at x 193, y 16
+        let pixel = |x: usize, y: usize| &frame.data[(y * 400 + x) * 4..][..3];
+        assert_eq!(pixel(193, 16), BOX);
+        // Top bar of the T spans the first glyph row
+        assert_eq!(pixel(195, 18), TEXT);
+        assert_eq!(pixel(203, 18), TEXT);
+        // Stem only in the middle column below it
+        assert_eq!(pixel(199, 24), TEXT);
+        assert_eq!(pixel(195, 24), BOX);
+        assert_eq!(pixel(10, 100), [0, 0, 0]);
+    }
+
+    #[test]
+    fn test_glyph_lookup() {
+        assert_eq!(glyph('a'), LETTERS[0]);
+        assert_eq!(glyph('7'), DIGITS[7]);
+        assert_eq!(glyph('!'), [0; GLYPH_ROWS]);
+    }
+}
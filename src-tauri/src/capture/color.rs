@@ -0,0 +1,417 @@
+/*
+ * This file is part of StreamSlate.
+ * Copyright (C) 2025 StreamSlate Contributors
+ *
+ * Display color profile correction for captured frames.
+ * ScreenCaptureKit delivers pixels in the captured display's color space, so
+ * the same slide differs between a P3 built-in panel and an sRGB monitor.
+ * Frames are converted to sRGB using the display's ICC matrix/TRC profile.
+ */
+
+use super::CapturedFrame;
+
+/// Matrix entries closer than this to sRGB's count as already sRGB
+const SRGB_TOLERANCE: f64 = 0.01;
+
+/// Entries in the linear-to-sRGB output table
+const OUTPUT_LUT_SIZE: usize = 4096;
+
+/// sRGB primaries in XYZ (D50, as in ICC profiles), columns r, g, b
+const SRGB_TO_XYZ: [[f64; 3]; 3] = [
+    [0.436_074_7, 0.385_064_9, 0.143_080_4],
+    [0.222_504_5, 0.716_878_6, 0.060_616_9],
+    [0.013_932_2, 0.097_104_5, 0.714_173_3],
+];
+
+/// Inverse of [`SRGB_TO_XYZ`]
+const XYZ_TO_SRGB: [[f64; 3]; 3] = [
+    [3.133_856_1, -1.616_866_7, -0.490_614_6],
+    [-0.978_768_4, 1.916_141_5, 0.033_454_0],
+    [0.071_945_3, -0.228_991_4, 1.405_242_7],
+];
+
+/// Tone response curve of one channel
+#[derive(Debug, Clone, PartialEq)]
+enum Curve {
+    Gamma(f64),
+    /// ICC parametric curve (function type 0-4)
+    Parametric(u16, Vec<f64>),
+    /// Sampled curve, evenly spaced over 0.0-1.0
+    Table(Vec<f64>),
+}
+
+impl Curve {
+    /// Map an encoded value (0.0-1.0) to linear light
+    fn eval(&self, x: f64) -> f64 {
+        match self {
+            Curve::Gamma(g) => x.powf(*g),
+            Curve::Parametric(kind, p) => {
+                let param = |i: usize| p.get(i).copied().unwrap_or(0.0);
+                let (g, a, b, c, d, e, f) = (
+                    param(0),
+                    param(1),
+                    param(2),
+                    param(3),
+                    param(4),
+                    param(5),
+                    param(6),
+                );
+                match kind {
+                    0 => x.powf(g),
+                    1 if x >= -b / a => (a * x + b).powf(g),
+                    1 => 0.0,
+                    2 if x >= -b / a => (a * x + b).powf(g) + c,
+                    2 => c,
+                    3 if x >= d => (a * x + b).powf(g),
+                    3 => c * x,
+                    4 if x >= d => (a * x + b).powf(g) + e,
+                    4 => c * x + f,
+                    _ => x,
+                }
+            }
+            Curve::Table(samples) => {
+                let last = samples.len().saturating_sub(1);
+                if last == 0 {
+                    return samples.first().copied().unwrap_or(x);
+                }
+                let pos = x.clamp(0.0, 1.0) * last as f64;
+                let i = (pos.floor() as usize).min(last - 1);
+                let t = pos - i as f64;
+                samples[i] * (1.0 - t) + samples[i + 1] * t
+            }
+        }
+    }
+}
+
+/// The parts of an RGB matrix/TRC ICC profile needed for conversion
+#[derive(Debug, Clone, PartialEq)]
+pub struct DisplayProfile {
+    /// Primaries in XYZ (D50), columns r, g, b
+    to_xyz: [[f64; 3]; 3],
+    curves: [Curve; 3],
+}
+
+impl DisplayProfile {
+    /// Parse an ICC profile; `None` for non-RGB or LUT-based profiles
+    pub fn parse(icc: &[u8]) -> Option<Self> {
+        if icc.len() < 132 || &icc[16..20] != b"RGB " {
+            return None;
+        }
+        let tag_count = be_u32(icc, 128)? as usize;
+        let tag = |signature: &[u8; 4]| -> Option<&[u8]> {
+            (0..tag_count).find_map(|i| {
+                let entry = 132 + i * 12;
+                if icc.get(entry..entry + 4)? != signature {
+                    return None;
+                }
+                let offset = be_u32(icc, entry + 4)? as usize;
+                let size = be_u32(icc, entry + 8)? as usize;
+                icc.get(offset..offset.checked_add(size)?)
+            })
+        };
+
+        let mut to_xyz = [[0.0; 3]; 3];
+        for (column, signature) in [b"rXYZ", b"gXYZ", b"bXYZ"].into_iter().enumerate() {
+            let xyz = parse_xyz(tag(signature)?)?;
+            for row in 0..3 {
+                to_xyz[row][column] = xyz[row];
+            }
+        }
+        let curves = [
+            parse_curve(tag(b"rTRC")?)?,
+            parse_curve(tag(b"gTRC")?)?,
+            parse_curve(tag(b"bTRC")?)?,
+        ];
+        Some(Self { to_xyz, curves })
+    }
+
+    /// Whether the profile is (close enough to) sRGB already
+    pub fn is_srgb(&self) -> bool {
+        let primaries_match = (0..3).all(|r| {
+            (0..3).all(|c| (self.to_xyz[r][c] - SRGB_TO_XYZ[r][c]).abs() < SRGB_TOLERANCE)
+        });
+        primaries_match
+            && self.curves.iter().all(|curve| {
+                [0.1, 0.25, 0.5, 0.75, 0.9]
+                    .iter()
+                    .all(|&x| (curve.eval(x) - srgb_to_linear(x)).abs() < SRGB_TOLERANCE)
+            })
+    }
+}
+
+/// Precomputed conversion from a display profile to sRGB
+#[derive(Debug, Clone)]
+pub struct ColorCorrection {
+    /// Per-channel 8-bit value to linear light (r, g, b)
+    input: [[f32; 256]; 3],
+    /// Display linear RGB to sRGB linear RGB
+    matrix: [[f32; 3]; 3],
+    /// Linear light to 8-bit sRGB
+    output: Vec<u8>,
+}
+
+impl ColorCorrection {
+    /// Build a correction for `profile`, or `None` if it is already sRGB
+    pub fn new(profile: &DisplayProfile) -> Option<Self> {
+        if profile.is_srgb() {
+            return None;
+        }
+
+        let mut input = [[0.0f32; 256]; 3];
+        for (table, curve) in input.iter_mut().zip(&profile.curves) {
+            for (value, entry) in table.iter_mut().enumerate() {
+                *entry = curve.eval(value as f64 / 255.0).clamp(0.0, 1.0) as f32;
+            }
+        }
+
+        let mut matrix = [[0.0f32; 3]; 3];
+        for (r, row) in matrix.iter_mut().enumerate() {
+            for (c, entry) in row.iter_mut().enumerate() {
+                *entry = (0..3)
+                    .map(|k| XYZ_TO_SRGB[r][k] * profile.to_xyz[k][c])
+                    .sum::<f64>() as f32;
+            }
+        }
+
+        let output = (0..OUTPUT_LUT_SIZE)
+            .map(|i| {
+                let linear = i as f64 / (OUTPUT_LUT_SIZE - 1) as f64;
+                (linear_to_srgb(linear) * 255.0).round() as u8
+            })
+            .collect();
+
+        Some(Self {
+            input,
+            matrix,
+            output,
+        })
+    }
+
+    /// Build a correction straight from ICC profile bytes
+    pub fn from_icc(icc: &[u8]) -> Option<Self> {
+        Self::new(&DisplayProfile::parse(icc)?)
+    }
+
+    /// Convert a BGRA frame to sRGB in place
+    pub fn apply(&self, frame: &mut CapturedFrame) {
+        let row_bytes = frame.width as usize * 4;
+        let stride = (frame.bytes_per_row as usize).max(row_bytes);
+        for row in frame.data.chunks_mut(stride) {
+            let end = row_bytes.min(row.len());
+            for pixel in row[..end].chunks_exact_mut(4) {
+                let (b, g, r) = self.convert(pixel[2], pixel[1], pixel[0]);
+                pixel[0] = b;
+                pixel[1] = g;
+                pixel[2] = r;
+            }
+        }
+    }
+
+    /// Convert one RGB pixel, returning (b, g, r)
+    fn convert(&self, r: u8, g: u8, b: u8) -> (u8, u8, u8) {
+        let linear = [
+            self.input[0][r as usize],
+            self.input[1][g as usize],
+            self.input[2][b as usize],
+        ];
+        let encode = |row: &[f32; 3]| {
+            let v = row[0] * linear[0] + row[1] * linear[1] + row[2] * linear[2];
+            let index = (v.clamp(0.0, 1.0) * (OUTPUT_LUT_SIZE - 1) as f32).round() as usize;
+            self.output[index]
+        };
+        (
+            encode(&self.matrix[2]),
+            encode(&self.matrix[1]),
+            encode(&self.matrix[0]),
+        )
+    }
+}
+
+/// Copy the ICC profile of a display's current color space
+#[cfg(target_os = "macos")]
+pub fn display_icc_profile(display_id: u32) -> Option<Vec<u8>> {
+    use std::os::raw::c_void;
+
+    #[link(name = "CoreGraphics", kind = "framework")]
+    extern "C" {
+        fn CGDisplayCopyColorSpace(display: u32) -> *const c_void;
+        fn CGColorSpaceCopyICCData(space: *const c_void) -> *const c_void;
+    }
+    #[link(name = "CoreFoundation", kind = "framework")]
+    extern "C" {
+        fn CFDataGetLength(data: *const c_void) -> isize;
+        fn CFDataGetBytePtr(data: *const c_void) -> *const u8;
+        fn CFRelease(object: *const c_void);
+    }
+
+    // SAFETY: Copy/Create results are owned here and released exactly once;
+    // the byte pointer is only read while `data` is alive.
+    unsafe {
+        let space = CGDisplayCopyColorSpace(display_id);
+        if space.is_null() {
+            return None;
+        }
+        let data = CGColorSpaceCopyICCData(space);
+        CFRelease(space);
+        if data.is_null() {
+            return None;
+        }
+        let len = CFDataGetLength(data).max(0) as usize;
+        let bytes = CFDataGetBytePtr(data);
+        let icc = (!bytes.is_null()).then(|| std::slice::from_raw_parts(bytes, len).to_vec());
+        CFRelease(data);
+        icc
+    }
+}
+
+fn srgb_to_linear(x: f64) -> f64 {
+    if x <= 0.04045 {
+        x / 12.92
+    } else {
+        ((x + 0.055) / 1.055).powf(2.4)
+    }
+}
+
+fn linear_to_srgb(x: f64) -> f64 {
+    if x <= 0.003_130_8 {
+        x * 12.92
+    } else {
+        1.055 * x.powf(1.0 / 2.4) - 0.055
+    }
+}
+
+fn be_u32(data: &[u8], at: usize) -> Option<u32> {
+    Some(u32::from_be_bytes(data.get(at..at + 4)?.try_into().ok()?))
+}
+
+fn s15_fixed16(data: &[u8], at: usize) -> Option<f64> {
+    Some(be_u32(data, at)? as i32 as f64 / 65536.0)
+}
+
+fn parse_xyz(tag: &[u8]) -> Option<[f64; 3]> {
+    if tag.get(..4)? != b"XYZ " {
+        return None;
+    }
+    Some([
+        s15_fixed16(tag, 8)?,
+        s15_fixed16(tag, 12)?,
+        s15_fixed16(tag, 16)?,
+    ])
+}
+
+fn parse_curve(tag: &[u8]) -> Option<Curve> {
+    match tag.get(..4)? {
+        b"curv" => {
+            let count = be_u32(tag, 8)? as usize;
+            let entry = |i: usize| {
+                let at = 12 + i * 2;
+                Some(u16::from_be_bytes(tag.get(at..at + 2)?.try_into().ok()?))
+            };
+            match count {
+                0 => Some(Curve::Gamma(1.0)),
+                1 => Some(Curve::Gamma(f64::from(entry(0)?) / 256.0)),
+                _ => (0..count)
+                    .map(|i| entry(i).map(|v| f64::from(v) / 65535.0))
+                    .collect::<Option<Vec<_>>>()
+                    .map(Curve::Table),
+            }
+        }
+        b"para" => {
+            let kind = u16::from_be_bytes(tag.get(8..10)?.try_into().ok()?);
+            let count = match kind {
+                0 => 1,
+                1 => 3,
+                2 => 4,
+                3 => 5,
+                4 => 7,
+                _ => return None,
+            };
+            let params = (0..count)
+                .map(|i| s15_fixed16(tag, 12 + i * 4))
+                .collect::<Option<Vec<_>>>()?;
+            Some(Curve::Parametric(kind, params))
+        }
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Display P3 primaries in XYZ (D50), columns r, g, b
+    const P3_TO_XYZ: [[f64; 3]; 3] = [
+        [0.515_1, 0.292_0, 0.157_1],
+        [0.241_2, 0.692_2, 0.066_6],
+        [-0.001_1, 0.041_9, 0.784_1],
+    ];
+
+    fn fixed(v: f64) -> [u8; 4] {
+        ((v * 65536.0).round() as i32).to_be_bytes()
+    }
+
+    /// Minimal matrix/TRC profile with an sRGB parametric curve
+    fn profile(to_xyz: [[f64; 3]; 3]) -> Vec<u8> {
+        let mut tags: Vec<(&[u8; 4], Vec<u8>)> = Vec::new();
+        for (column, signature) in [b"rXYZ", b"gXYZ", b"bXYZ"].into_iter().enumerate() {
+            let mut xyz = b"XYZ \0\0\0\0".to_vec();
+            for row in to_xyz {
+                xyz.extend_from_slice(&fixed(row[column]));
+            }
+            tags.push((signature, xyz));
+        }
+        let mut para = b"para\0\0\0\0\0\x03\0\0".to_vec();
+        for v in [2.4, 1.0 / 1.055, 0.055 / 1.055, 1.0 / 12.92, 0.04045] {
+            para.extend_from_slice(&fixed(v));
+        }
+        for signature in [b"rTRC", b"gTRC", b"bTRC"] {
+            tags.push((signature, para.clone()));
+        }
+
+        let mut icc = vec![0u8; 128];
+        icc[16..20].copy_from_slice(b"RGB ");
+        icc.extend_from_slice(&(tags.len() as u32).to_be_bytes());
+        let mut offset = 132 + tags.len() * 12;
+        let mut data = Vec::new();
+        for (signature, body) in &tags {
+            icc.extend_from_slice(*signature);
+            icc.extend_from_slice(&(offset as u32).to_be_bytes());
+            icc.extend_from_slice(&(body.len() as u32).to_be_bytes());
+            offset += body.len();
+            data.extend_from_slice(body);
+        }
+        icc.extend_from_slice(&data);
+        icc
+    }
+
+    #[test]
+    fn test_srgb_profile_needs_no_correction() {
+        let parsed = DisplayProfile::parse(&profile(SRGB_TO_XYZ)).unwrap();
+        assert!(parsed.is_srgb());
+        assert!(ColorCorrection::new(&parsed).is_none());
+        assert!(DisplayProfile::parse(b"not a profile").is_none());
+    }
+
+    #[test]
+    fn test_p3_frame_converted_to_srgb() {
+        let correction = ColorCorrection::from_icc(&profile(P3_TO_XYZ)).unwrap();
+
+        // Gray and white stay neutral
+        assert_eq!(correction.convert(255, 255, 255), (255, 255, 255));
+        let (b, g, r) = correction.convert(128, 128, 128);
+        assert!(r.abs_diff(128) <= 1 && g.abs_diff(128) <= 1 && b.abs_diff(128) <= 1);
+
+        // A muted P3 red is more saturated in sRGB
+        let mut frame = CapturedFrame {
+            data: vec![60, 60, 200, 255],
+            width: 1,
+            height: 1,
+            bytes_per_row: 4,
+            timestamp_ns: 0,
+        };
+        correction.apply(&mut frame);
+        assert!(frame.data[2] > 200, "red: {}", frame.data[2]);
+        assert!(frame.data[1] < 60, "green: {}", frame.data[1]);
+        assert_eq!(frame.data[3], 255);
+    }
+}
@@ -0,0 +1,85 @@
+/*
+ * This file is part of StreamSlate.
+ * Copyright (C) 2025 StreamSlate Contributors
+ *
+ * Detection of capture windows moved into native fullscreen.
+ * A window in fullscreen on another Space is reported off-screen and fills
+ * a display exactly; window capture of it usually delivers black frames.
+ */
+
+use serde::{Deserialize, Serialize};
+
+/// Event emitted when the captured window enters fullscreen on another Space
+pub const FULLSCREEN_WARNING_EVENT: &str = "capture-fullscreen-warning";
+
+/// Slack in points when comparing window and display frames
+const FRAME_TOLERANCE: f64 = 2.0;
+
+/// Frame in global display coordinates (x, y, width, height)
+pub type Frame = (f64, f64, f64, f64);
+
+/// Payload of [`FULLSCREEN_WARNING_EVENT`]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FullscreenWarning {
+    pub window_id: u32,
+    /// Display whose fullscreen Space holds the window
+    pub display_id: u32,
+    /// Whether capture switched to that display automatically
+    pub fallback_active: bool,
+}
+
+/// Find the display an off-screen window fills, i.e. its fullscreen Space
+pub fn fullscreen_display(
+    window: Frame,
+    on_screen: bool,
+    displays: &[(u32, Frame)],
+) -> Option<u32> {
+    if on_screen {
+        return None;
+    }
+    displays
+        .iter()
+        .find(|(_, display)| frames_match(window, *display))
+        .map(|(id, _)| *id)
+}
+
+fn frames_match(a: Frame, b: Frame) -> bool {
+    [a.0 - b.0, a.1 - b.1, a.2 - b.2, a.3 - b.3]
+        .iter()
+        .all(|delta| delta.abs() <= FRAME_TOLERANCE)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DISPLAYS: [(u32, Frame); 2] = [
+        (1, (0.0, 0.0, 1512.0, 982.0)),
+        (2, (1512.0, 0.0, 1920.0, 1080.0)),
+    ];
+
+    #[test]
+    fn test_detects_fullscreen_on_other_space() {
+        assert_eq!(
+            fullscreen_display((1512.0, 0.0, 1920.0, 1080.0), false, &DISPLAYS),
+            Some(2)
+        );
+        // Menu bar rounding still matches
+        assert_eq!(
+            fullscreen_display((0.0, 1.0, 1512.0, 981.0), false, &DISPLAYS),
+            Some(1)
+        );
+    }
+
+    #[test]
+    fn test_ignores_visible_and_windowed() {
+        assert_eq!(
+            fullscreen_display((0.0, 0.0, 1512.0, 982.0), true, &DISPLAYS),
+            None
+        );
+        assert_eq!(
+            fullscreen_display((100.0, 80.0, 800.0, 600.0), false, &DISPLAYS),
+            None
+        );
+    }
+}
@@ -0,0 +1,97 @@
+/*
+ * This file is part of StreamSlate.
+ * Copyright (C) 2025 StreamSlate Contributors
+ *
+ * Application icon lookup for the capture source picker.
+ * Icons are resolved from the app bundle and converted to PNG with `sips`.
+ */
+
+use super::preview::png_data_url;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::sync::{Mutex, OnceLock};
+use tracing::debug;
+
+/// Edge length of the generated icons in pixels
+const ICON_SIZE: u32 = 64;
+
+/// Icons keyed by bundle ID; `None` records a failed lookup so it isn't retried
+static ICON_CACHE: OnceLock<Mutex<HashMap<String, Option<String>>>> = OnceLock::new();
+
+/// Get an app's icon as a PNG data URL, or `None` if it can't be resolved
+pub fn app_icon_data_url(bundle_id: &str) -> Option<String> {
+    let cache = ICON_CACHE.get_or_init(|| Mutex::new(HashMap::new()));
+    if let Some(cached) = cache.lock().ok()?.get(bundle_id) {
+        return cached.clone();
+    }
+
+    let icon = load_icon(bundle_id);
+    if icon.is_none() {
+        debug!(bundle_id, "No icon found for application");
+    }
+    if let Ok(mut cache) = cache.lock() {
+        cache.insert(bundle_id.to_string(), icon.clone());
+    }
+    icon
+}
+
+fn load_icon(bundle_id: &str) -> Option<String> {
+    let bundle = find_bundle(bundle_id)?;
+    let icns = icon_file(&bundle)?;
+
+    let out = std::env::temp_dir().join(format!("streamslate-icon-{}.png", uuid::Uuid::new_v4()));
+    let converted = Command::new("sips")
+        .args(["-s", "format", "png", "-Z", &ICON_SIZE.to_string()])
+        .arg(&icns)
+        .arg("--out")
+        .arg(&out)
+        .output()
+        .is_ok_and(|output| output.status.success());
+    let png = converted.then(|| std::fs::read(&out).ok()).flatten();
+    let _ = std::fs::remove_file(&out);
+
+    png.map(|png| png_data_url(&png))
+}
+
+/// Locate an application bundle by its identifier via Spotlight
+fn find_bundle(bundle_id: &str) -> Option<PathBuf> {
+    if !bundle_id
+        .chars()
+        .all(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '-' | '_'))
+    {
+        return None;
+    }
+
+    let output = Command::new("mdfind")
+        .arg(format!("kMDItemCFBundleIdentifier == '{bundle_id}'"))
+        .output()
+        .ok()?;
+    String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .map(PathBuf::from)
+        .find(|path| path.extension().is_some_and(|ext| ext == "app"))
+}
+
+/// Resolve the `.icns` file named by the bundle's Info.plist
+fn icon_file(bundle: &Path) -> Option<PathBuf> {
+    let output = Command::new("defaults")
+        .arg("read")
+        .arg(bundle.join("Contents/Info"))
+        .arg("CFBundleIconFile")
+        .output()
+        .ok()?;
+    let name = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    if !output.status.success() || name.is_empty() {
+        return None;
+    }
+
+    let resources = bundle.join("Contents/Resources");
+    let path = resources.join(&name);
+    if path.extension().is_some() {
+        Some(path)
+    } else {
+        Some(resources.join(format!("{name}.icns")))
+    }
+    .filter(|path| path.exists())
+}
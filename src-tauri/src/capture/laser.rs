@@ -0,0 +1,166 @@
+/*
+ * This file is part of StreamSlate.
+ * Copyright (C) 2025 StreamSlate Contributors
+ *
+ * Laser pointer drawn on the output frames.
+ * Driven by relative movement from a dedicated pointing device (see
+ * `pointer`), so a co-host can point at the slides while the presenter
+ * keeps the host cursor. The dot fades out once the device stops moving.
+ */
+
+use super::pointer::PointerEvent;
+use super::CapturedFrame;
+use serde::{Deserialize, Serialize};
+use std::time::{Duration, Instant};
+
+/// Hide the dot after the device has been still this long
+pub const IDLE_TIMEOUT: Duration = Duration::from_secs(3);
+
+/// Device counts to cross the whole frame width at sensitivity 1.0
+pub const COUNTS_PER_WIDTH: f64 = 1500.0;
+
+/// Laser colour (BGR)
+const LASER: [u8; 3] = [0x30, 0x30, 0xFF];
+
+/// Laser pointer position and appearance
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LaserPointer {
+    /// Position as fractions of the output frame (0.5 is the middle)
+    pub x: f64,
+    pub y: f64,
+    /// Movement multiplier; 1.0 crosses the frame in about 1500 counts
+    pub sensitivity: f64,
+    /// Primary button held: the dot is drawn larger
+    pub pressed: bool,
+    /// Output frame width / height, so motion is equally fast on both axes
+    #[serde(skip)]
+    aspect: f64,
+    #[serde(skip)]
+    last_active: Option<Instant>,
+}
+
+impl LaserPointer {
+    pub fn new(sensitivity: f64) -> Self {
+        Self {
+            x: 0.5,
+            y: 0.5,
+            sensitivity,
+            pressed: false,
+            aspect: 16.0 / 9.0,
+            last_active: None,
+        }
+    }
+
+    /// Track the size of the frames the dot is drawn on
+    pub fn set_frame_size(&mut self, width: u32, height: u32) {
+        if width > 0 && height > 0 {
+            self.aspect = width as f64 / height as f64;
+        }
+    }
+
+    /// Apply a device event
+    pub fn apply(&mut self, event: PointerEvent, now: Instant) {
+        match event {
+            PointerEvent::Move { dx, dy } => {
+                let step = self.sensitivity / COUNTS_PER_WIDTH;
+                self.x = (self.x + dx as f64 * step).clamp(0.0, 1.0);
+                self.y = (self.y + dy as f64 * step * self.aspect).clamp(0.0, 1.0);
+            }
+            PointerEvent::Button { pressed } => self.pressed = pressed,
+        }
+        self.last_active = Some(now);
+    }
+
+    /// Whether the dot is shown at `now`
+    pub fn is_visible(&self, now: Instant) -> bool {
+        self.pressed
+            || self
+                .last_active
+                .is_some_and(|at| now.saturating_duration_since(at) < IDLE_TIMEOUT)
+    }
+}
+
+/// Draw the laser dot (a solid core with a soft glow) onto a BGRA frame
+pub fn draw(frame: &mut CapturedFrame, pointer: &LaserPointer) {
+    let (width, height) = (frame.width as usize, frame.height as usize);
+    let stride = frame.bytes_per_row as usize;
+    if width == 0 || height == 0 || frame.data.len() < stride * height {
+        return;
+    }
+
+    let core = (height as f64 / if pointer.pressed { 70.0 } else { 110.0 }).max(2.0);
+    let radius = core * 2.5;
+    let cx = pointer.x * (width - 1) as f64;
+    let cy = pointer.y * (height - 1) as f64;
+
+    let x0 = (cx - radius).floor().max(0.0) as usize;
+    let x1 = ((cx + radius).ceil() as usize).min(width - 1);
+    let y0 = (cy - radius).floor().max(0.0) as usize;
+    let y1 = ((cy + radius).ceil() as usize).min(height - 1);
+
+    for y in y0..=y1 {
+        let row = &mut frame.data[y * stride..][..width * 4];
+        for x in x0..=x1 {
+            let distance = ((x as f64 - cx).powi(2) + (y as f64 - cy).powi(2)).sqrt();
+            let alpha = if distance <= core {
+                1.0
+            } else if distance < radius {
+                0.6 * (1.0 - (distance - core) / (radius - core)).powi(2)
+            } else {
+                continue;
+            };
+            let pixel = &mut row[x * 4..][..4];
+            for (channel, laser) in pixel[..3].iter_mut().zip(LASER) {
+                *channel = (*channel as f64 * (1.0 - alpha) + laser as f64 * alpha).round() as u8;
+            }
+            pixel[3] = 0xFF;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pointer_movement() {
+        let start = Instant::now();
+        let mut pointer = LaserPointer::new(1.0);
+        assert!(!pointer.is_visible(start));
+
+        pointer.set_frame_size(1920, 1080);
+        pointer.apply(PointerEvent::Move { dx: 150, dy: 0 }, start);
+        assert!((pointer.x - 0.6).abs() < 1e-9);
+        assert!(pointer.is_visible(start + Duration::from_secs(1)));
+        assert!(!pointer.is_visible(start + IDLE_TIMEOUT));
+
+        pointer.apply(
+            PointerEvent::Move {
+                dx: -5000,
+                dy: 5000,
+            },
+            start,
+        );
+        assert_eq!((pointer.x, pointer.y), (0.0, 1.0));
+
+        pointer.apply(PointerEvent::Button { pressed: true }, start);
+        assert!(pointer.is_visible(start + IDLE_TIMEOUT * 2));
+    }
+
+    #[test]
+    fn test_draw() {
+        let mut frame = CapturedFrame {
+            data: vec![0; 200 * 100 * 4],
+            width: 200,
+            height: 100,
+            bytes_per_row: 200 * 4,
+            timestamp_ns: 0,
+        };
+        let pointer = LaserPointer::new(1.0);
+        draw(&mut frame, &pointer);
+
+        let pixel = |x: usize, y: usize| &frame.data[(y * 200 + x) * 4..][..4];
+        assert_eq!(pixel(100, 50), &[0x30, 0x30, 0xFF, 0xFF]);
+        assert_eq!(pixel(0, 0), &[0, 0, 0, 0]);
+    }
+}
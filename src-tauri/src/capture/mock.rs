@@ -0,0 +1,282 @@
+/*
+ * This file is part of StreamSlate.
+ * Copyright (C) 2025 StreamSlate Contributors
+ *
+ * Mock capture backend that synthesizes test-pattern frames.
+ * Lets the NDI/Syphon/recording pipelines run without Screen Recording
+ * permission, on non-macOS machines, and in CI.
+ */
+
+use super::{CaptureConfig, CapturedFrame, FrameCallback};
+use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::Arc;
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant};
+use tracing::{debug, info};
+
+/// Runtime flag selecting the mock backend: `1`/`true`, or a frame rate such as `60`
+pub const MOCK_CAPTURE_ENV: &str = "STREAMSLATE_MOCK_CAPTURE";
+
+/// Size used when the config requests native resolution (0)
+const DEFAULT_WIDTH: u32 = 1920;
+const DEFAULT_HEIGHT: u32 = 1080;
+
+/// Width of the moving sweep line, in pixels
+const SWEEP_WIDTH: u32 = 8;
+
+/// Checkerboard square size, in pixels
+const CHECKER_SIZE: u32 = 64;
+
+/// 75% SMPTE-style color bars as BGRA
+const COLOR_BARS: [[u8; 4]; 7] = [
+    [191, 191, 191, 255],
+    [0, 191, 191, 255],
+    [191, 191, 0, 255],
+    [0, 191, 0, 255],
+    [191, 0, 191, 255],
+    [0, 0, 191, 255],
+    [191, 0, 0, 255],
+];
+
+/// Pattern drawn by the mock backend
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TestPattern {
+    /// Vertical color bars with a white line sweeping across
+    #[default]
+    ColorBars,
+    /// Black and white checkerboard scrolling diagonally
+    Checkerboard,
+}
+
+/// Whether the mock backend was requested via `STREAMSLATE_MOCK_CAPTURE`
+pub fn is_enabled() -> bool {
+    std::env::var(MOCK_CAPTURE_ENV)
+        .ok()
+        .and_then(|value| parse_flag(&value))
+        .is_some()
+}
+
+/// Capture config for the mock backend, honoring a frame rate from the flag
+pub fn config_from_env() -> CaptureConfig {
+    let fps = std::env::var(MOCK_CAPTURE_ENV)
+        .ok()
+        .and_then(|value| parse_flag(&value))
+        .flatten();
+    CaptureConfig {
+        fps: fps.unwrap_or(CaptureConfig::default().fps),
+        ..CaptureConfig::default()
+    }
+}
+
+/// Parse the flag value: `None` if disabled, `Some(fps)` if enabled
+fn parse_flag(value: &str) -> Option<Option<u8>> {
+    match value.trim().to_ascii_lowercase().as_str() {
+        "" | "0" | "false" | "off" | "no" => None,
+        "1" | "true" | "on" | "yes" => Some(None),
+        other => other.parse::<u8>().ok().filter(|fps| *fps > 0).map(Some),
+    }
+}
+
+/// Render one BGRA frame of the given pattern
+pub fn render_frame(
+    config: &CaptureConfig,
+    pattern: TestPattern,
+    frame_index: u64,
+    timestamp_ns: u64,
+) -> CapturedFrame {
+    let width = if config.width == 0 {
+        DEFAULT_WIDTH
+    } else {
+        config.width
+    };
+    let height = if config.height == 0 {
+        DEFAULT_HEIGHT
+    } else {
+        config.height
+    };
+    let bytes_per_row = width * 4;
+    let mut data = vec![0u8; (bytes_per_row * height) as usize];
+
+    match pattern {
+        TestPattern::ColorBars => {
+            // Every row is identical, so build one and copy it
+            let sweep_x = ((frame_index * u64::from(SWEEP_WIDTH)) % u64::from(width)) as u32;
+            let row: Vec<u8> = (0..width)
+                .flat_map(|x| {
+                    if (sweep_x..sweep_x + SWEEP_WIDTH).contains(&x) {
+                        [255, 255, 255, 255]
+                    } else {
+                        COLOR_BARS[(x * COLOR_BARS.len() as u32 / width) as usize]
+                    }
+                })
+                .collect();
+            for chunk in data.chunks_exact_mut(bytes_per_row as usize) {
+                chunk.copy_from_slice(&row);
+            }
+        }
+        TestPattern::Checkerboard => {
+            let offset = (frame_index % u64::from(CHECKER_SIZE * 2)) as u32;
+            for (y, chunk) in data.chunks_exact_mut(bytes_per_row as usize).enumerate() {
+                let row_parity = ((y as u32 + offset) / CHECKER_SIZE) % 2;
+                for (x, pixel) in chunk.chunks_exact_mut(4).enumerate() {
+                    let parity = ((x as u32 + offset) / CHECKER_SIZE) % 2;
+                    let value = if parity == row_parity { 255 } else { 0 };
+                    pixel.copy_from_slice(&[value, value, value, 255]);
+                }
+            }
+        }
+    }
+
+    CapturedFrame {
+        data,
+        width,
+        height,
+        bytes_per_row,
+        timestamp_ns,
+    }
+}
+
+/// A running mock capture that delivers frames on a background thread
+pub struct MockCapture {
+    running: Arc<AtomicBool>,
+    frame_count: Arc<AtomicU64>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl MockCapture {
+    /// Start producing frames at `config.fps`, passing each to `callback`
+    pub fn start(config: CaptureConfig, pattern: TestPattern, callback: FrameCallback) -> Self {
+        let running = Arc::new(AtomicBool::new(true));
+        let frame_count = Arc::new(AtomicU64::new(0));
+
+        let thread = {
+            let running = running.clone();
+            let frame_count = frame_count.clone();
+            std::thread::spawn(move || {
+                let interval = Duration::from_secs(1) / u32::from(config.fps.max(1));
+                let started = Instant::now();
+                info!(fps = config.fps, ?pattern, "Mock capture started");
+
+                let mut index = 0u64;
+                while running.load(Ordering::SeqCst) {
+                    let timestamp_ns = started.elapsed().as_nanos() as u64;
+                    callback(render_frame(&config, pattern, index, timestamp_ns));
+                    index += 1;
+                    frame_count.store(index, Ordering::SeqCst);
+
+                    if index % 60 == 0 {
+                        debug!("Mock capture produced {} frames", index);
+                    }
+
+                    // Schedule against the start time so rendering cost doesn't drift the rate
+                    let next = started + interval * index as u32;
+                    if let Some(wait) = next.checked_duration_since(Instant::now()) {
+                        std::thread::sleep(wait);
+                    }
+                }
+            })
+        };
+
+        Self {
+            running,
+            frame_count,
+            thread: Some(thread),
+        }
+    }
+
+    /// Stop producing frames and wait for the worker thread
+    pub fn stop(&mut self) {
+        self.running.store(false, Ordering::SeqCst);
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+            info!("Mock capture stopped after {} frames", self.frame_count());
+        }
+    }
+
+    pub fn is_running(&self) -> bool {
+        self.running.load(Ordering::SeqCst)
+    }
+
+    pub fn frame_count(&self) -> u64 {
+        self.frame_count.load(Ordering::SeqCst)
+    }
+}
+
+impl Drop for MockCapture {
+    fn drop(&mut self) {
+        self.stop();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::{mpsc, Mutex};
+
+    fn small_config(fps: u8) -> CaptureConfig {
+        CaptureConfig {
+            fps,
+            width: 70,
+            height: 4,
+            show_cursor: false,
+        }
+    }
+
+    #[test]
+    fn test_parse_flag() {
+        assert_eq!(parse_flag("0"), None);
+        assert_eq!(parse_flag("false"), None);
+        assert_eq!(parse_flag("true"), Some(None));
+        assert_eq!(parse_flag("60"), Some(Some(60)));
+        assert_eq!(parse_flag("fast"), None);
+    }
+
+    #[test]
+    fn test_render_color_bars() {
+        let frame = render_frame(&small_config(30), TestPattern::ColorBars, 0, 0);
+        assert_eq!(frame.data.len(), 70 * 4 * 4);
+        assert_eq!(frame.bytes_per_row, 280);
+
+        // Sweep line sits at x = 0 on the first frame, bars after it
+        assert_eq!(&frame.data[0..4], &[255, 255, 255, 255]);
+        assert_eq!(&frame.data[12 * 4..13 * 4], &COLOR_BARS[1]);
+        assert_eq!(&frame.data[69 * 4..70 * 4], &COLOR_BARS[6]);
+
+        let default_size = render_frame(
+            &CaptureConfig {
+                width: 0,
+                height: 0,
+                ..small_config(30)
+            },
+            TestPattern::Checkerboard,
+            3,
+            0,
+        );
+        assert_eq!((default_size.width, default_size.height), (1920, 1080));
+    }
+
+    #[test]
+    fn test_mock_capture_delivers_frames() {
+        let (tx, rx) = mpsc::channel();
+        let sink = Mutex::new(tx);
+        let mut capture = MockCapture::start(
+            small_config(100),
+            TestPattern::ColorBars,
+            Arc::new(move |frame| {
+                let _ = sink.lock().unwrap().send(frame.timestamp_ns);
+            }),
+        );
+
+        let mut received: Vec<u64> = (0..3)
+            .map(|_| rx.recv_timeout(Duration::from_secs(5)).expect("frame"))
+            .collect();
+        capture.stop();
+        received.extend(rx.try_iter());
+
+        assert!(!capture.is_running());
+        assert_eq!(received.len() as u64, capture.frame_count());
+        assert!(received.windows(2).all(|w| w[0] <= w[1]));
+    }
+}
@@ -0,0 +1,140 @@
+/*
+ * This file is part of StreamSlate.
+ * Copyright (C) 2025 StreamSlate Contributors
+ *
+ * Exclusive input from a dedicated pointing device.
+ * The device is grabbed through the shared input tap so its movement no
+ * longer moves the host cursor, and its relative motion and primary button
+ * are decoded into pointer events.
+ */
+
+use crate::error::Result;
+use crate::input::evdev::{BTN_LEFT, EV_KEY, EV_REL, EV_SYN, REL_X, REL_Y};
+use crate::input::hid::{PAGE_BUTTON, PAGE_GENERIC_DESKTOP, USAGE_X, USAGE_Y};
+use crate::input::{self, DeviceKind, InputDevice, InputTap, RawInput};
+
+/// A pointing device that can drive the laser pointer
+pub type PointerDevice = InputDevice;
+
+/// Input from a tapped device
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PointerEvent {
+    /// Relative motion in device counts
+    Move { dx: i32, dy: i32 },
+    /// Primary button pressed or released
+    Button { pressed: bool },
+}
+
+/// Pointing devices connected to this machine
+pub fn list_devices() -> Result<Vec<PointerDevice>> {
+    input::list_devices(DeviceKind::Pointer)
+}
+
+/// A grabbed device delivering its input until dropped
+#[derive(Debug)]
+pub struct PointerTap(InputTap);
+
+impl PointerTap {
+    /// Grab the device with `id` and call `on_event` for each of its inputs
+    pub fn start(
+        id: &str,
+        mut on_event: impl FnMut(PointerEvent) + Send + 'static,
+    ) -> Result<Self> {
+        let mut pending = (0, 0);
+        InputTap::start(DeviceKind::Pointer, id, move |input| {
+            if let Some(event) = decode(input, &mut pending) {
+                on_event(event);
+            }
+        })
+        .map(Self)
+    }
+
+    pub fn device(&self) -> &PointerDevice {
+        self.0.device()
+    }
+}
+
+/// Decode one raw input into a pointer event
+///
+/// evdev motion is accumulated until the `EV_SYN` closing each report, with
+/// `pending` carrying a partial report over; HID reports each axis on its own.
+fn decode(input: RawInput, pending: &mut (i32, i32)) -> Option<PointerEvent> {
+    let RawInput { page, code, value } = input;
+    if cfg!(target_os = "macos") {
+        return match (page, code) {
+            (PAGE_GENERIC_DESKTOP, USAGE_X) => Some(PointerEvent::Move { dx: value, dy: 0 }),
+            (PAGE_GENERIC_DESKTOP, USAGE_Y) => Some(PointerEvent::Move { dx: 0, dy: value }),
+            (PAGE_BUTTON, 1) => Some(PointerEvent::Button {
+                pressed: value != 0,
+            }),
+            _ => None,
+        };
+    }
+
+    let (Ok(kind), Ok(code)) = (u16::try_from(page), u16::try_from(code)) else {
+        return None;
+    };
+    match (kind, code) {
+        (EV_REL, REL_X) => pending.0 += value,
+        (EV_REL, REL_Y) => pending.1 += value,
+        // Value 2 is auto-repeat
+        (EV_KEY, BTN_LEFT) if value != 2 => {
+            return Some(PointerEvent::Button {
+                pressed: value == 1,
+            })
+        }
+        (EV_SYN, _) if *pending != (0, 0) => {
+            let (dx, dy) = std::mem::take(pending);
+            return Some(PointerEvent::Move { dx, dy });
+        }
+        _ => {}
+    }
+    None
+}
+
+#[cfg(all(test, not(target_os = "macos")))]
+mod tests {
+    use super::*;
+
+    fn record(kind: u16, code: u16, value: i32) -> RawInput {
+        RawInput {
+            page: kind.into(),
+            code: code.into(),
+            value,
+        }
+    }
+
+    fn decode_all(inputs: &[RawInput], pending: &mut (i32, i32)) -> Vec<PointerEvent> {
+        inputs
+            .iter()
+            .filter_map(|&input| decode(input, pending))
+            .collect()
+    }
+
+    #[test]
+    fn test_decode_events() {
+        let mut pending = (0, 0);
+        let first = [
+            record(EV_REL, REL_X, 3),
+            record(EV_REL, REL_Y, -2),
+            record(EV_SYN, 0, 0),
+            record(EV_KEY, BTN_LEFT, 1),
+            record(EV_SYN, 0, 0),
+            record(EV_REL, REL_X, 5),
+        ];
+        assert_eq!(
+            decode_all(&first, &mut pending),
+            vec![
+                PointerEvent::Move { dx: 3, dy: -2 },
+                PointerEvent::Button { pressed: true },
+            ]
+        );
+        assert_eq!(pending, (5, 0));
+
+        let second = [record(EV_REL, REL_X, 1), record(EV_SYN, 0, 0)];
+        assert_eq!(
+            decode_all(&second, &mut pending),
+            vec![PointerEvent::Move { dx: 6, dy: 0 }]
+        );
+    }
+}
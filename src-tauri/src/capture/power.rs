@@ -0,0 +1,134 @@
+/*
+ * This file is part of StreamSlate.
+ * Copyright (C) 2025 StreamSlate Contributors
+ *
+ * System sleep/wake tracking for the capture loop.
+ * ScreenCaptureKit streams don't survive sleep: they keep reporting as
+ * running but deliver no frames, so capture is torn down before sleep and
+ * rebuilt after wake.
+ */
+
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+
+/// Set between will-sleep and did-wake
+static SLEEPING: AtomicBool = AtomicBool::new(false);
+
+/// Number of wakes seen since launch
+static WAKES: AtomicU64 = AtomicU64::new(0);
+
+/// A system power transition
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PowerEvent {
+    WillSleep,
+    DidWake,
+}
+
+/// Record a power transition reported by the OS
+pub fn record(event: PowerEvent) {
+    match event {
+        PowerEvent::WillSleep => SLEEPING.store(true, Ordering::SeqCst),
+        PowerEvent::DidWake => {
+            WAKES.fetch_add(1, Ordering::SeqCst);
+            SLEEPING.store(false, Ordering::SeqCst);
+        }
+    }
+}
+
+/// Register for the OS sleep/wake notifications (once per process)
+#[cfg(target_os = "macos")]
+pub fn start_monitor() {
+    use std::os::raw::c_int;
+    use std::sync::Once;
+
+    extern "C" fn on_power_event(event: c_int) {
+        let event = if event == 0 {
+            PowerEvent::WillSleep
+        } else {
+            PowerEvent::DidWake
+        };
+        tracing::info!(?event, "System power transition");
+        record(event);
+    }
+
+    extern "C" {
+        fn power_monitor_start(callback: extern "C" fn(c_int));
+    }
+
+    static START: Once = Once::new();
+    START.call_once(|| unsafe { power_monitor_start(on_power_event) });
+}
+
+/// Tracks power transitions from the point of view of one capture session
+#[derive(Debug)]
+pub struct SleepWatcher {
+    suspended: bool,
+    wakes_seen: u64,
+}
+
+impl SleepWatcher {
+    pub fn new() -> Self {
+        Self {
+            suspended: false,
+            wakes_seen: WAKES.load(Ordering::SeqCst),
+        }
+    }
+
+    /// Check for a transition since the last poll
+    pub fn poll(&mut self) -> Option<PowerEvent> {
+        self.observe(
+            SLEEPING.load(Ordering::SeqCst),
+            WAKES.load(Ordering::SeqCst),
+        )
+    }
+
+    /// Compare the observed power state against what the session last saw.
+    ///
+    /// A wake the session never saw the sleep for (both happened between
+    /// polls) still counts: the stream is dead either way.
+    fn observe(&mut self, sleeping: bool, wakes: u64) -> Option<PowerEvent> {
+        if sleeping && !self.suspended {
+            self.suspended = true;
+            return Some(PowerEvent::WillSleep);
+        }
+        if !sleeping && (self.suspended || wakes != self.wakes_seen) {
+            self.suspended = false;
+            self.wakes_seen = wakes;
+            return Some(PowerEvent::DidWake);
+        }
+        None
+    }
+}
+
+impl Default for SleepWatcher {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sleep_then_wake() {
+        let mut watcher = SleepWatcher {
+            suspended: false,
+            wakes_seen: 3,
+        };
+        assert_eq!(watcher.observe(false, 3), None);
+        assert_eq!(watcher.observe(true, 3), Some(PowerEvent::WillSleep));
+        assert_eq!(watcher.observe(true, 3), None);
+        assert_eq!(watcher.observe(false, 4), Some(PowerEvent::DidWake));
+        assert_eq!(watcher.observe(false, 4), None);
+    }
+
+    #[test]
+    fn test_wake_missed_sleep() {
+        let mut watcher = SleepWatcher {
+            suspended: false,
+            wakes_seen: 0,
+        };
+        assert_eq!(watcher.observe(false, 1), Some(PowerEvent::DidWake));
+        assert_eq!(watcher.observe(false, 1), None);
+    }
+}
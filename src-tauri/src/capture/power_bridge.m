@@ -0,0 +1,43 @@
+/*
+ * This file is part of StreamSlate.
+ * Copyright (C) 2025 StreamSlate Contributors
+ *
+ * Objective-C bridge forwarding NSWorkspace sleep/wake notifications
+ * and the system thermal state to the Rust capture code.
+ */
+
+#import <AppKit/AppKit.h>
+
+/// Callback receiving 0 before the system sleeps and 1 after it wakes.
+typedef void (*power_callback)(int event);
+
+static id sleepObserver = nil;
+static id wakeObserver = nil;
+
+/// Start observing sleep/wake. Safe to call once per process.
+void power_monitor_start(power_callback callback) {
+    @autoreleasepool {
+        if (!callback || sleepObserver) return;
+
+        NSNotificationCenter *center = [[NSWorkspace sharedWorkspace] notificationCenter];
+        sleepObserver = [center addObserverForName:NSWorkspaceWillSleepNotification
+                                            object:nil
+                                             queue:nil
+                                        usingBlock:^(NSNotification *note) {
+            callback(0);
+        }];
+        wakeObserver = [center addObserverForName:NSWorkspaceDidWakeNotification
+                                           object:nil
+                                            queue:nil
+                                       usingBlock:^(NSNotification *note) {
+            callback(1);
+        }];
+
+        NSLog(@"[StreamSlate] Sleep/wake monitor registered");
+    }
+}
+
+/// Current NSProcessInfoThermalState (0 nominal ... 3 critical).
+int power_thermal_state(void) {
+    return (int)[[NSProcessInfo processInfo] thermalState];
+}
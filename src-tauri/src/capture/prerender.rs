@@ -0,0 +1,100 @@
+/*
+ * This file is part of StreamSlate.
+ * Copyright (C) 2025 StreamSlate Contributors
+ *
+ * Page pre-render scheduling for the capture output.
+ * Pages are rasterized by the views (PDF.js); whenever the current page
+ * changes, the neighbouring pages are queued so the views can render them
+ * ahead of time and a page flip never shows a blank or low-res frame.
+ */
+
+use crate::error::{Result, StreamSlateError};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// Upper bound on pages kept pre-rendered around the current one
+pub const MAX_PRERENDER_PAGES: u32 = 8;
+
+/// How many neighbouring pages to pre-render
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct PrerenderConfig {
+    pub enabled: bool,
+    /// Pages after the current one
+    pub ahead: u32,
+    /// Pages before the current one
+    pub behind: u32,
+}
+
+impl Default for PrerenderConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            ahead: 2,
+            behind: 1,
+        }
+    }
+}
+
+impl PrerenderConfig {
+    pub fn validate(&self) -> Result<()> {
+        if self.ahead + self.behind > MAX_PRERENDER_PAGES {
+            return Err(StreamSlateError::Other(format!(
+                "At most {MAX_PRERENDER_PAGES} pages can be pre-rendered"
+            )));
+        }
+        Ok(())
+    }
+
+    /// Pages to pre-render around `current`, most likely next first
+    ///
+    /// Pages are ordered by distance from `current`; at equal distance the
+    /// following page comes before the preceding one.
+    pub fn queue(&self, current: u32, total_pages: u32) -> Vec<u32> {
+        if !self.enabled || current == 0 || current > total_pages {
+            return Vec::new();
+        }
+        let mut pages = Vec::new();
+        for distance in 1..=self.ahead.max(self.behind) {
+            if distance <= self.ahead && current + distance <= total_pages {
+                pages.push(current + distance);
+            }
+            if distance <= self.behind && distance < current {
+                pages.push(current - distance);
+            }
+        }
+        pages
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_queue() {
+        let config = PrerenderConfig {
+            enabled: true,
+            ahead: 3,
+            behind: 1,
+        };
+        assert_eq!(config.queue(5, 10), vec![6, 4, 7, 8]);
+        assert_eq!(config.queue(1, 10), vec![2, 3, 4]);
+        assert_eq!(config.queue(9, 10), vec![10, 8]);
+        assert!(config.queue(11, 10).is_empty());
+
+        let disabled = PrerenderConfig {
+            enabled: false,
+            ..config
+        };
+        assert!(disabled.queue(5, 10).is_empty());
+
+        let too_many = PrerenderConfig {
+            enabled: true,
+            ahead: 6,
+            behind: 3,
+        };
+        assert!(too_many.validate().is_err());
+        assert!(PrerenderConfig::default().validate().is_ok());
+    }
+}
@@ -0,0 +1,234 @@
+/*
+ * This file is part of StreamSlate.
+ * Copyright (C) 2025 StreamSlate Contributors
+ *
+ * Low-rate preview of captured frames for the webview.
+ * Frames are throttled, downsampled, and PNG-encoded so users can check
+ * exactly what goes out over NDI/Syphon from inside the app.
+ */
+
+use super::CapturedFrame;
+use crate::state::FrameOutput;
+use base64::Engine;
+use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// Preview rate and size limits
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct PreviewConfig {
+    /// Maximum preview frames per second
+    pub fps: f64,
+    /// Maximum preview width in pixels (height follows the aspect ratio)
+    pub max_width: u32,
+}
+
+impl Default for PreviewConfig {
+    fn default() -> Self {
+        Self {
+            fps: 2.0,
+            max_width: 480,
+        }
+    }
+}
+
+/// A preview frame as delivered to the webview
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PreviewFrame {
+    pub width: u32,
+    pub height: u32,
+    /// `data:image/png;base64,...`, usable directly as an `<img>` source
+    pub data_url: String,
+    pub timestamp_ns: u64,
+}
+
+/// Callback receiving encoded preview frames
+pub type PreviewSink = Box<dyn Fn(PreviewFrame) + Send + Sync>;
+
+/// Frame output that forwards throttled, downsampled frames to a sink
+pub struct PreviewOutput {
+    config: PreviewConfig,
+    sink: PreviewSink,
+    last_sent: Mutex<Option<Instant>>,
+    running: AtomicBool,
+}
+
+impl PreviewOutput {
+    pub fn new(config: PreviewConfig, sink: PreviewSink) -> Self {
+        Self {
+            config,
+            sink,
+            last_sent: Mutex::new(None),
+            running: AtomicBool::new(true),
+        }
+    }
+
+    /// Whether enough time has passed since the last preview frame
+    fn take_slot(&self) -> bool {
+        take_slot(&self.last_sent, self.config.fps)
+    }
+}
+
+/// Whether a frame may go out now at no more than `fps`, recording it in
+/// `last_sent` if so
+pub(super) fn take_slot(last_sent: &Mutex<Option<Instant>>, fps: f64) -> bool {
+    let interval = Duration::from_secs_f64(1.0 / fps.max(0.1));
+    let Ok(mut last_sent) = last_sent.lock() else {
+        return false;
+    };
+    let now = Instant::now();
+    match *last_sent {
+        Some(last) if now.duration_since(last) < interval => false,
+        _ => {
+            *last_sent = Some(now);
+            true
+        }
+    }
+}
+
+impl FrameOutput for PreviewOutput {
+    fn send_frame(&self, frame: &CapturedFrame) -> Result<(), String> {
+        if !self.is_running() || !self.take_slot() {
+            return Ok(());
+        }
+        let (width, height, data_url) = frame_to_data_url(frame, self.config.max_width)?;
+        (self.sink)(PreviewFrame {
+            width,
+            height,
+            data_url,
+            timestamp_ns: frame.timestamp_ns,
+        });
+        Ok(())
+    }
+
+    fn stop(&self) {
+        self.running.store(false, Ordering::SeqCst);
+    }
+
+    fn is_running(&self) -> bool {
+        self.running.load(Ordering::SeqCst)
+    }
+
+    fn resume(&self) -> Result<(), String> {
+        self.running.store(true, Ordering::SeqCst);
+        Ok(())
+    }
+}
+
+/// Nearest-neighbour downsample of a BGRA frame to RGBA, at most `max_width` wide
+pub fn downsample_bgra(
+    frame: &CapturedFrame,
+    max_width: u32,
+) -> Result<(u32, u32, Vec<u8>), String> {
+    if frame.width == 0 || frame.height == 0 {
+        return Err("Empty frame".to_string());
+    }
+    let stride = frame.bytes_per_row as usize;
+    if stride < frame.width as usize * 4 || frame.data.len() < stride * frame.height as usize {
+        return Err("Frame buffer is smaller than its dimensions".to_string());
+    }
+
+    let out_width = frame.width.min(max_width.max(1));
+    let out_height =
+        ((u64::from(frame.height) * u64::from(out_width)) / u64::from(frame.width)).max(1) as u32;
+
+    let mut rgba = Vec::with_capacity((out_width * out_height * 4) as usize);
+    for y in 0..out_height {
+        let src_y = (u64::from(y) * u64::from(frame.height) / u64::from(out_height)) as usize;
+        let row = &frame.data[src_y * stride..];
+        for x in 0..out_width {
+            let src_x = (u64::from(x) * u64::from(frame.width) / u64::from(out_width)) as usize;
+            let px = &row[src_x * 4..src_x * 4 + 4];
+            rgba.extend_from_slice(&[px[2], px[1], px[0], px[3]]);
+        }
+    }
+
+    Ok((out_width, out_height, rgba))
+}
+
+/// Downsample a BGRA frame and encode it as a `data:image/png` URL
+pub fn frame_to_data_url(
+    frame: &CapturedFrame,
+    max_width: u32,
+) -> Result<(u32, u32, String), String> {
+    let (width, height, rgba) = downsample_bgra(frame, max_width)?;
+    Ok((
+        width,
+        height,
+        png_data_url(&encode_png(width, height, &rgba)?),
+    ))
+}
+
+/// Wrap encoded PNG bytes in a `data:image/png` URL
+pub fn png_data_url(png: &[u8]) -> String {
+    format!(
+        "data:image/png;base64,{}",
+        base64::engine::general_purpose::STANDARD.encode(png)
+    )
+}
+
+fn encode_png(width: u32, height: u32, rgba: &[u8]) -> Result<Vec<u8>, String> {
+    let mut out = Vec::new();
+    let mut encoder = png::Encoder::new(&mut out, width, height);
+    encoder.set_color(png::ColorType::Rgba);
+    encoder.set_depth(png::BitDepth::Eight);
+    encoder.set_compression(png::Compression::Fast);
+    let mut writer = encoder.write_header().map_err(|e| e.to_string())?;
+    writer.write_image_data(rgba).map_err(|e| e.to_string())?;
+    writer.finish().map_err(|e| e.to_string())?;
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::capture::mock::{render_frame, TestPattern};
+    use crate::capture::CaptureConfig;
+    use std::sync::Arc;
+
+    fn test_frame() -> CapturedFrame {
+        let config = CaptureConfig {
+            fps: 30,
+            width: 1280,
+            height: 720,
+            show_cursor: false,
+        };
+        render_frame(&config, TestPattern::ColorBars, 0, 42)
+    }
+
+    #[test]
+    fn test_downsample_keeps_aspect_ratio() {
+        let (width, height, rgba) = downsample_bgra(&test_frame(), 320).unwrap();
+        assert_eq!((width, height), (320, 180));
+        assert_eq!(rgba.len(), 320 * 180 * 4);
+        // Second bar is BGRA [0, 191, 191] -> RGBA [191, 191, 0]
+        assert_eq!(&rgba[60 * 4..61 * 4], &[191, 191, 0, 255]);
+    }
+
+    #[test]
+    fn test_preview_output_throttles() {
+        let frames = Arc::new(Mutex::new(Vec::new()));
+        let sink = frames.clone();
+        let output = PreviewOutput::new(
+            PreviewConfig {
+                fps: 1.0,
+                max_width: 64,
+            },
+            Box::new(move |frame| sink.lock().unwrap().push(frame)),
+        );
+
+        let frame = test_frame();
+        for _ in 0..5 {
+            output.send_frame(&frame).unwrap();
+        }
+
+        let frames = frames.lock().unwrap();
+        assert_eq!(frames.len(), 1);
+        assert_eq!(frames[0].width, 64);
+        assert_eq!(frames[0].timestamp_ns, 42);
+        assert!(frames[0]
+            .data_url
+            .starts_with("data:image/png;base64,iVBOR"));
+    }
+}
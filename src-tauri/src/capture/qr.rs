@@ -0,0 +1,611 @@
+/*
+ * This file is part of StreamSlate.
+ * Copyright (C) 2025 StreamSlate Contributors
+ *
+ * QR codes flashed on the output frames.
+ * A small encoder for byte-mode symbols at error correction level M
+ * (versions 1-10, up to 213 bytes: denser codes don't scan off a video
+ * stream anyway) and the overlay that draws one in the bottom-right corner.
+ */
+
+use super::CapturedFrame;
+use crate::error::{Result, StreamSlateError};
+use serde::Serialize;
+use std::time::Instant;
+
+/// Largest version generated (57x57 modules)
+const MAX_VERSION: u8 = 10;
+
+/// Error correction codewords per block at level M, by version
+const ECC_PER_BLOCK: [usize; 11] = [0, 10, 16, 26, 18, 24, 16, 18, 22, 22, 26];
+
+/// Error correction blocks at level M, by version
+const ECC_BLOCKS: [usize; 11] = [0, 1, 1, 1, 2, 2, 4, 4, 4, 5, 5];
+
+/// Format bits identifying level M (`00`) before masking
+const ECC_LEVEL_M: u32 = 0;
+
+/// Light modules around the symbol, as the standard requires
+const QUIET_ZONE: usize = 4;
+
+/// BGR colours of the symbol
+const DARK: [u8; 3] = [0x00, 0x00, 0x00];
+const LIGHT: [u8; 3] = [0xFF, 0xFF, 0xFF];
+
+/// An encoded QR symbol
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct QrCode {
+    pub version: u8,
+    /// Modules per side
+    pub size: usize,
+    /// Dark modules, row by row
+    pub modules: Vec<bool>,
+}
+
+/// QR code drawn on every output frame while shown
+#[derive(Debug, Clone)]
+pub struct QrOverlay {
+    /// Text encoded in the symbol
+    pub data: String,
+    pub code: QrCode,
+    /// When the overlay was put up; timers only clear the overlay they started
+    pub shown_at: Instant,
+}
+
+/// Encode `data` as a QR code in the smallest version that fits
+pub fn generate_qr(data: &str) -> Result<QrCode> {
+    let bytes = data.as_bytes();
+    let version = (1..=MAX_VERSION)
+        .find(|&v| 4 + char_count_bits(v) + bytes.len() * 8 <= data_codewords(v) * 8)
+        .ok_or_else(|| {
+            StreamSlateError::Other(format!(
+                "QR data is too long: {} bytes, at most {}",
+                bytes.len(),
+                data_codewords(MAX_VERSION) - 3
+            ))
+        })?;
+
+    let codewords = add_error_correction(&encode_data(bytes, version), version);
+    let mut symbol = Symbol::new(version);
+    symbol.draw_function_patterns();
+    symbol.draw_codewords(&codewords);
+
+    // Keep the mask the standard's penalty rules rate best
+    let best = (0..8)
+        .map(|mask| {
+            let mut candidate = symbol.clone();
+            candidate.apply_mask(mask);
+            candidate.draw_format_bits(mask);
+            candidate
+        })
+        .min_by_key(Symbol::penalty)
+        .expect("eight masks");
+
+    Ok(QrCode {
+        version,
+        size: best.size,
+        modules: best.modules,
+    })
+}
+
+impl QrCode {
+    /// Whether the module at column `x`, row `y` is dark
+    pub fn is_dark(&self, x: usize, y: usize) -> bool {
+        x < self.size && y < self.size && self.modules[y * self.size + x]
+    }
+}
+
+/// Draw `code` in the bottom-right corner of `frame`, on a light quiet zone
+///
+/// The symbol takes up to 40% of the frame height, with whole-pixel modules
+/// so it stays sharp.
+pub fn draw(frame: &mut CapturedFrame, code: &QrCode) {
+    let (width, height) = (frame.width as usize, frame.height as usize);
+    let stride = frame.bytes_per_row as usize;
+    if width == 0 || frame.data.len() < stride * height {
+        return;
+    }
+
+    let cells = code.size + 2 * QUIET_ZONE;
+    let scale = (height * 2 / 5 / cells).min(width / cells);
+    if scale == 0 {
+        return;
+    }
+
+    let side = cells * scale;
+    let margin = height / 20;
+    let x0 = width.saturating_sub(side + margin);
+    let y0 = height.saturating_sub(side + margin);
+
+    for y in 0..side {
+        let row = &mut frame.data[(y0 + y) * stride..][..width * 4];
+        let module_y = (y / scale).wrapping_sub(QUIET_ZONE);
+        for x in 0..side {
+            let module_x = (x / scale).wrapping_sub(QUIET_ZONE);
+            let dark = code.is_dark(module_x, module_y);
+            let pixel = &mut row[(x0 + x) * 4..][..4];
+            pixel[..3].copy_from_slice(if dark { &DARK } else { &LIGHT });
+            pixel[3] = 0xFF;
+        }
+    }
+}
+
+/// Bits of the byte-mode character count
+fn char_count_bits(version: u8) -> usize {
+    if version < 10 {
+        8
+    } else {
+        16
+    }
+}
+
+/// Modules left for data and error correction after the function patterns
+fn raw_data_modules(version: u8) -> usize {
+    let v = usize::from(version);
+    let mut modules = (16 * v + 128) * v + 64;
+    if v >= 2 {
+        let alignments = v / 7 + 2;
+        modules -= (25 * alignments - 10) * alignments - 55;
+        if v >= 7 {
+            modules -= 36;
+        }
+    }
+    modules
+}
+
+fn data_codewords(version: u8) -> usize {
+    let v = usize::from(version);
+    raw_data_modules(version) / 8 - ECC_PER_BLOCK[v] * ECC_BLOCKS[v]
+}
+
+/// Centre coordinates of the alignment patterns on each axis
+fn alignment_positions(version: u8) -> Vec<usize> {
+    if version == 1 {
+        return Vec::new();
+    }
+    let v = usize::from(version);
+    let count = v / 7 + 2;
+    let step = (v * 8 + count * 3 + 5) / (count * 4 - 4) * 2;
+    let last = v * 4 + 10;
+    let mut positions: Vec<usize> = (0..count - 1).map(|i| last - i * step).collect();
+    positions.push(6);
+    positions.reverse();
+    positions
+}
+
+/// Mode, count, data, terminator and padding as data codewords
+fn encode_data(bytes: &[u8], version: u8) -> Vec<u8> {
+    let capacity = data_codewords(version) * 8;
+    let mut bits = BitBuffer::default();
+    bits.push(0b0100, 4);
+    bits.push(bytes.len() as u32, char_count_bits(version));
+    for &byte in bytes {
+        bits.push(u32::from(byte), 8);
+    }
+    bits.push(0, (capacity - bits.len).min(4));
+    bits.push(0, (8 - bits.len % 8) % 8);
+
+    let mut codewords = bits.bytes;
+    for pad in [0xEC, 0x11].into_iter().cycle() {
+        if codewords.len() * 8 >= capacity {
+            break;
+        }
+        codewords.push(pad);
+    }
+    codewords
+}
+
+/// Split the data into blocks, append each block's error correction and
+/// interleave the result
+fn add_error_correction(data: &[u8], version: u8) -> Vec<u8> {
+    let v = usize::from(version);
+    let (blocks, ecc_len) = (ECC_BLOCKS[v], ECC_PER_BLOCK[v]);
+    let raw = raw_data_modules(version) / 8;
+    let short_blocks = blocks - raw % blocks;
+    let short_len = raw / blocks;
+    let divisor = rs_divisor(ecc_len);
+
+    let mut split = Vec::with_capacity(blocks);
+    let mut offset = 0;
+    for i in 0..blocks {
+        let len = short_len - ecc_len + usize::from(i >= short_blocks);
+        let mut block = data[offset..offset + len].to_vec();
+        offset += len;
+        let ecc = rs_remainder(&block, &divisor);
+        if i < short_blocks {
+            // Placeholder so every block has the same length
+            block.push(0);
+        }
+        block.extend(ecc);
+        split.push(block);
+    }
+
+    let mut result = Vec::with_capacity(raw);
+    for i in 0..split[0].len() {
+        for (j, block) in split.iter().enumerate() {
+            if i != short_len - ecc_len || j >= short_blocks {
+                result.push(block[i]);
+            }
+        }
+    }
+    result
+}
+
+/// Multiply in GF(2^8) modulo x^8 + x^4 + x^3 + x^2 + 1
+fn gf_mul(x: u8, y: u8) -> u8 {
+    let mut z: u32 = 0;
+    for i in (0..8).rev() {
+        z = (z << 1) ^ ((z >> 7) * 0x11D);
+        z ^= u32::from((y >> i) & 1) * u32::from(x);
+    }
+    z as u8
+}
+
+/// Reed-Solomon generator polynomial of `degree`, highest term omitted
+fn rs_divisor(degree: usize) -> Vec<u8> {
+    let mut result = vec![0u8; degree];
+    result[degree - 1] = 1;
+    let mut root = 1u8;
+    for _ in 0..degree {
+        for j in 0..degree {
+            result[j] = gf_mul(result[j], root);
+            if j + 1 < degree {
+                result[j] ^= result[j + 1];
+            }
+        }
+        root = gf_mul(root, 0x02);
+    }
+    result
+}
+
+/// Error correction codewords for `data`
+fn rs_remainder(data: &[u8], divisor: &[u8]) -> Vec<u8> {
+    let mut result = vec![0u8; divisor.len()];
+    for &byte in data {
+        let factor = byte ^ result.remove(0);
+        result.push(0);
+        for (r, &d) in result.iter_mut().zip(divisor) {
+            *r ^= gf_mul(d, factor);
+        }
+    }
+    result
+}
+
+#[derive(Default)]
+struct BitBuffer {
+    bytes: Vec<u8>,
+    len: usize,
+}
+
+impl BitBuffer {
+    /// Append the low `count` bits of `value`, most significant first
+    fn push(&mut self, value: u32, count: usize) {
+        for i in (0..count).rev() {
+            if self.len % 8 == 0 {
+                self.bytes.push(0);
+            }
+            if (value >> i) & 1 != 0 {
+                *self.bytes.last_mut().expect("byte pushed") |= 0x80 >> (self.len % 8);
+            }
+            self.len += 1;
+        }
+    }
+}
+
+/// Module grid while the symbol is being built
+#[derive(Clone)]
+struct Symbol {
+    version: u8,
+    size: usize,
+    modules: Vec<bool>,
+    /// Finder, timing, alignment, format and version modules
+    function: Vec<bool>,
+}
+
+impl Symbol {
+    fn new(version: u8) -> Self {
+        let size = usize::from(version) * 4 + 17;
+        Self {
+            version,
+            size,
+            modules: vec![false; size * size],
+            function: vec![false; size * size],
+        }
+    }
+
+    fn set_function(&mut self, x: usize, y: usize, dark: bool) {
+        self.modules[y * self.size + x] = dark;
+        self.function[y * self.size + x] = true;
+    }
+
+    fn draw_function_patterns(&mut self) {
+        let size = self.size;
+        for i in 0..size {
+            self.set_function(6, i, i % 2 == 0);
+            self.set_function(i, 6, i % 2 == 0);
+        }
+
+        for (cx, cy) in [(3, 3), (size - 4, 3), (3, size - 4)] {
+            for dy in -4i32..=4 {
+                for dx in -4i32..=4 {
+                    let (x, y) = (cx as i32 + dx, cy as i32 + dy);
+                    if (0..size as i32).contains(&x) && (0..size as i32).contains(&y) {
+                        let distance = dx.abs().max(dy.abs());
+                        self.set_function(x as usize, y as usize, distance != 2 && distance != 4);
+                    }
+                }
+            }
+        }
+
+        let positions = alignment_positions(self.version);
+        let last = positions.len().saturating_sub(1);
+        for (i, &cx) in positions.iter().enumerate() {
+            for (j, &cy) in positions.iter().enumerate() {
+                // The three corners are taken by finder patterns
+                if [(0, 0), (0, last), (last, 0)].contains(&(i, j)) {
+                    continue;
+                }
+                for dy in -2i32..=2 {
+                    for dx in -2i32..=2 {
+                        let (x, y) = ((cx as i32 + dx) as usize, (cy as i32 + dy) as usize);
+                        self.set_function(x, y, dx.abs().max(dy.abs()) != 1);
+                    }
+                }
+            }
+        }
+
+        // Reserve the format area; the real bits are drawn once the mask is chosen
+        self.draw_format_bits(0);
+        self.draw_version_bits();
+    }
+
+    fn draw_format_bits(&mut self, mask: u32) {
+        let data = (ECC_LEVEL_M << 3) | mask;
+        let mut rem = data;
+        for _ in 0..10 {
+            rem = (rem << 1) ^ ((rem >> 9) * 0x537);
+        }
+        let bits = ((data << 10) | rem) ^ 0x5412;
+        let bit = |i: usize| (bits >> i) & 1 != 0;
+        let size = self.size;
+
+        // Around the top-left finder
+        for i in 0..=5 {
+            self.set_function(8, i, bit(i));
+        }
+        self.set_function(8, 7, bit(6));
+        self.set_function(8, 8, bit(7));
+        self.set_function(7, 8, bit(8));
+        for i in 9..15 {
+            self.set_function(14 - i, 8, bit(i));
+        }
+
+        // Split between the other two finders
+        for i in 0..8 {
+            self.set_function(size - 1 - i, 8, bit(i));
+        }
+        for i in 8..15 {
+            self.set_function(8, size - 15 + i, bit(i));
+        }
+        self.set_function(8, size - 8, true);
+    }
+
+    fn draw_version_bits(&mut self) {
+        if self.version < 7 {
+            return;
+        }
+        let version = u32::from(self.version);
+        let mut rem = version;
+        for _ in 0..12 {
+            rem = (rem << 1) ^ ((rem >> 11) * 0x1F25);
+        }
+        let bits = (version << 12) | rem;
+        for i in 0..18 {
+            let dark = (bits >> i) & 1 != 0;
+            let (a, b) = (self.size - 11 + i % 3, i / 3);
+            self.set_function(a, b, dark);
+            self.set_function(b, a, dark);
+        }
+    }
+
+    /// Place codewords in the two-column zigzag from the bottom-right corner
+    fn draw_codewords(&mut self, codewords: &[u8]) {
+        let size = self.size;
+        let total_bits = codewords.len() * 8;
+        let mut i = 0;
+        let mut right = size - 1;
+        while right >= 1 {
+            if right == 6 {
+                // Skip the vertical timing pattern
+                right = 5;
+            }
+            for vertical in 0..size {
+                for j in 0..2 {
+                    let x = right - j;
+                    let upward = (right + 1) & 2 == 0;
+                    let y = if upward {
+                        size - 1 - vertical
+                    } else {
+                        vertical
+                    };
+                    if !self.function[y * size + x] && i < total_bits {
+                        self.modules[y * size + x] = (codewords[i >> 3] >> (7 - (i & 7))) & 1 != 0;
+                        i += 1;
+                    }
+                }
+            }
+            if right < 2 {
+                break;
+            }
+            right -= 2;
+        }
+    }
+
+    fn apply_mask(&mut self, mask: u32) {
+        for y in 0..self.size {
+            for x in 0..self.size {
+                let flip = match mask {
+                    0 => (x + y) % 2 == 0,
+                    1 => y % 2 == 0,
+                    2 => x % 3 == 0,
+                    3 => (x + y) % 3 == 0,
+                    4 => (x / 3 + y / 2) % 2 == 0,
+                    5 => x * y % 2 + x * y % 3 == 0,
+                    6 => (x * y % 2 + x * y % 3) % 2 == 0,
+                    _ => ((x + y) % 2 + x * y % 3) % 2 == 0,
+                };
+                let index = y * self.size + x;
+                if flip && !self.function[index] {
+                    self.modules[index] = !self.modules[index];
+                }
+            }
+        }
+    }
+
+    /// Penalty score from the four rules of ISO/IEC 18004 section 7.8.3
+    fn penalty(&self) -> usize {
+        let size = self.size;
+        let at = |x: usize, y: usize| self.modules[y * size + x];
+        let mut penalty = 0;
+
+        // Runs of five or more same-coloured modules in a row or column
+        for transpose in [false, true] {
+            for a in 0..size {
+                let mut run = 1;
+                for b in 1..size {
+                    let (cur, prev) = if transpose {
+                        (at(a, b), at(a, b - 1))
+                    } else {
+                        (at(b, a), at(b - 1, a))
+                    };
+                    if cur == prev {
+                        run += 1;
+                        if run == 5 {
+                            penalty += 3;
+                        } else if run > 5 {
+                            penalty += 1;
+                        }
+                    } else {
+                        run = 1;
+                    }
+                }
+            }
+        }
+
+        // 2x2 blocks of one colour
+        for y in 0..size - 1 {
+            for x in 0..size - 1 {
+                let c = at(x, y);
+                if c == at(x + 1, y) && c == at(x, y + 1) && c == at(x + 1, y + 1) {
+                    penalty += 3;
+                }
+            }
+        }
+
+        // Finder-like 1:1:3:1:1 patterns with four light modules on one side
+        const PATTERN: [bool; 7] = [true, false, true, true, true, false, true];
+        for transpose in [false, true] {
+            for a in 0..size {
+                let line: Vec<bool> = (0..size)
+                    .map(|b| if transpose { at(a, b) } else { at(b, a) })
+                    .collect();
+                for start in 0..=size - 7 {
+                    if line[start..start + 7] != PATTERN {
+                        continue;
+                    }
+                    let light = |range: std::ops::Range<usize>| range.into_iter().all(|i| !line[i]);
+                    let before = start >= 4 && light(start - 4..start);
+                    let after = start + 11 <= size && light(start + 7..start + 11);
+                    if before || after {
+                        penalty += 40;
+                    }
+                }
+            }
+        }
+
+        // Balance of dark and light modules
+        let dark = self.modules.iter().filter(|&&m| m).count();
+        let total = size * size;
+        let deviation = (dark * 20).abs_diff(total * 10);
+        penalty += ((deviation + total - 1) / total).saturating_sub(1) * 10;
+
+        penalty
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_reed_solomon_known_vector() {
+        // "HELLO WORLD" at 1-M, from the ISO/IEC 18004 worked example
+        let data = [
+            32, 91, 11, 120, 209, 114, 220, 77, 67, 64, 236, 17, 236, 17, 236, 17,
+        ];
+        assert_eq!(
+            rs_remainder(&data, &rs_divisor(10)),
+            [196, 35, 39, 119, 235, 215, 231, 226, 93, 23]
+        );
+    }
+
+    #[test]
+    fn test_version_selection_and_layout() {
+        let code = generate_qr("https://example.com").unwrap();
+        assert_eq!(code.version, 2);
+        assert_eq!(code.size, 25);
+        assert_eq!(code.modules.len(), 625);
+
+        // Finder pattern: dark ring, light ring, dark centre
+        assert!(code.is_dark(0, 0) && code.is_dark(6, 6) && code.is_dark(3, 3));
+        assert!(!code.is_dark(1, 1) && !code.is_dark(7, 7));
+        // Timing pattern and the always-dark module
+        assert!(code.is_dark(8, 6) && !code.is_dark(9, 6));
+        assert!(code.is_dark(8, code.size - 8));
+
+        assert_eq!(generate_qr(&"x".repeat(213)).unwrap().version, 10);
+        assert!(generate_qr(&"x".repeat(214)).is_err());
+    }
+
+    #[test]
+    fn test_capacities() {
+        // Byte-mode capacities at level M from the standard's tables
+        let capacity = |v: u8| (data_codewords(v) * 8 - 4 - char_count_bits(v)) / 8;
+        assert_eq!(capacity(1), 14);
+        assert_eq!(capacity(5), 84);
+        assert_eq!(capacity(7), 122);
+        assert_eq!(capacity(10), 213);
+        assert_eq!(alignment_positions(7), [6, 22, 38]);
+        assert_eq!(alignment_positions(10), [6, 28, 50]);
+    }
+
+    #[test]
+    fn test_format_bits() {
+        let mut symbol = Symbol::new(1);
+        symbol.draw_format_bits(0);
+        // Level M, mask 0 is 101010000010010
+        let bits: Vec<bool> = (0..=5).map(|i| symbol.modules[i * 21 + 8]).collect();
+        assert_eq!(bits, [false, true, false, false, true, false]);
+    }
+
+    #[test]
+    fn test_draw_overlay() {
+        let code = generate_qr("A").unwrap();
+        let mut frame = CapturedFrame {
+            data: vec![0x80; 640 * 360 * 4],
+            width: 640,
+            height: 360,
+            bytes_per_row: 640 * 4,
+            timestamp_ns: 0,
+        };
+        draw(&mut frame, &code);
+
+        // 29 cells at scale 4 (144 / 29), 18px margin: box from (506, 226)
+        let pixel = |x: usize, y: usize| &frame.data[(y * 640 + x) * 4..][..3];
+        assert_eq!(pixel(506, 226), LIGHT);
+        assert_eq!(pixel(506 + 16, 226 + 16), DARK);
+        assert_eq!(pixel(505, 226), [0x80; 3]);
+        assert_eq!(pixel(10, 10), [0x80; 3]);
+    }
+}
@@ -0,0 +1,176 @@
+/*
+ * This file is part of StreamSlate.
+ * Copyright (C) 2025 StreamSlate Contributors
+ *
+ * Low-bandwidth remote view of the output.
+ * Frames are throttled, downsampled and JPEG-encoded at a configurable
+ * quality so producers can monitor the show from another site, where an NDI
+ * feed won't reach. Only the latest frame is kept; slow viewers skip frames
+ * rather than queue them.
+ */
+
+use super::preview::{downsample_bgra, take_slot};
+use super::CapturedFrame;
+use crate::state::FrameOutput;
+use jpeg_encoder::{ColorType, Encoder};
+use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
+use tokio::sync::watch;
+
+/// Remote view rate, size and quality
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct RemoteViewConfig {
+    /// JPEG quality from 1 (smallest) to 100 (best)
+    pub quality: u8,
+    /// Maximum frames per second
+    pub fps: f64,
+    /// Maximum frame width in pixels (height follows the aspect ratio)
+    pub max_width: u32,
+}
+
+impl Default for RemoteViewConfig {
+    fn default() -> Self {
+        Self {
+            quality: 60,
+            fps: 1.0,
+            max_width: 960,
+        }
+    }
+}
+
+/// An encoded remote view frame
+#[derive(Debug, Clone)]
+pub struct RemoteFrame {
+    pub width: u32,
+    pub height: u32,
+    pub timestamp_ns: u64,
+    /// JPEG file bytes, shared by every viewer
+    pub jpeg: Arc<[u8]>,
+}
+
+/// Latest remote view frame, `None` while the remote view is off
+pub type RemoteFrames = watch::Sender<Option<RemoteFrame>>;
+
+/// Frame output that publishes throttled JPEG frames to remote viewers
+pub struct RemoteViewOutput {
+    config: RemoteViewConfig,
+    frames: Arc<RemoteFrames>,
+    last_sent: Mutex<Option<Instant>>,
+    running: AtomicBool,
+}
+
+impl RemoteViewOutput {
+    pub fn new(config: RemoteViewConfig, frames: Arc<RemoteFrames>) -> Self {
+        Self {
+            config,
+            frames,
+            last_sent: Mutex::new(None),
+            running: AtomicBool::new(true),
+        }
+    }
+}
+
+impl FrameOutput for RemoteViewOutput {
+    fn send_frame(&self, frame: &CapturedFrame) -> Result<(), String> {
+        if !self.is_running() || !take_slot(&self.last_sent, self.config.fps) {
+            return Ok(());
+        }
+        let (width, height, rgba) = downsample_bgra(frame, self.config.max_width)?;
+        let jpeg = encode_jpeg(width, height, &rgba, self.config.quality)?;
+        self.frames.send_replace(Some(RemoteFrame {
+            width,
+            height,
+            timestamp_ns: frame.timestamp_ns,
+            jpeg: jpeg.into(),
+        }));
+        Ok(())
+    }
+
+    /// Viewers are told the view is off rather than left on a frozen frame
+    fn stop(&self) {
+        self.running.store(false, Ordering::SeqCst);
+        self.frames.send_replace(None);
+    }
+
+    fn is_running(&self) -> bool {
+        self.running.load(Ordering::SeqCst)
+    }
+
+    fn resume(&self) -> Result<(), String> {
+        self.running.store(true, Ordering::SeqCst);
+        Ok(())
+    }
+}
+
+/// Encode RGBA pixels as a baseline JPEG at `quality` (clamped to 1-100)
+pub fn encode_jpeg(width: u32, height: u32, rgba: &[u8], quality: u8) -> Result<Vec<u8>, String> {
+    let (Ok(width), Ok(height)) = (u16::try_from(width), u16::try_from(height)) else {
+        return Err(format!("Frame too large for JPEG: {width}x{height}"));
+    };
+    let mut out = Vec::new();
+    Encoder::new(&mut out, quality.clamp(1, 100))
+        .encode(rgba, width, height, ColorType::Rgba)
+        .map_err(|e| e.to_string())?;
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::capture::mock::{render_frame, TestPattern};
+    use crate::capture::CaptureConfig;
+
+    fn test_frame() -> CapturedFrame {
+        let config = CaptureConfig {
+            fps: 30,
+            width: 1280,
+            height: 720,
+            show_cursor: false,
+        };
+        render_frame(&config, TestPattern::ColorBars, 0, 42)
+    }
+
+    #[test]
+    fn test_encode_jpeg_quality() {
+        let (width, height, rgba) = downsample_bgra(&test_frame(), 320).unwrap();
+        let low = encode_jpeg(width, height, &rgba, 10).unwrap();
+        let high = encode_jpeg(width, height, &rgba, 95).unwrap();
+        for jpeg in [&low, &high] {
+            assert_eq!(&jpeg[..2], &[0xFF, 0xD8]);
+            assert_eq!(&jpeg[jpeg.len() - 2..], &[0xFF, 0xD9]);
+        }
+        assert!(low.len() < high.len());
+    }
+
+    #[test]
+    fn test_remote_view_output_publishes_latest_frame() {
+        let frames = Arc::new(watch::channel(None).0);
+        let mut viewer = frames.subscribe();
+        let output = RemoteViewOutput::new(
+            RemoteViewConfig {
+                quality: 50,
+                fps: 1.0,
+                max_width: 64,
+            },
+            frames.clone(),
+        );
+
+        let frame = test_frame();
+        for _ in 0..5 {
+            output.send_frame(&frame).unwrap();
+        }
+        assert!(viewer.has_changed().unwrap());
+        let published = viewer.borrow_and_update().clone().unwrap();
+        assert_eq!((published.width, published.height), (64, 36));
+        assert_eq!(published.timestamp_ns, 42);
+        assert_eq!(&published.jpeg[..2], &[0xFF, 0xD8]);
+
+        // Throttled: the other four frames were dropped
+        assert!(!viewer.has_changed().unwrap());
+
+        output.stop();
+        assert!(viewer.borrow_and_update().is_none());
+    }
+}
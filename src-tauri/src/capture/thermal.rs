@@ -0,0 +1,219 @@
+/*
+ * This file is part of StreamSlate.
+ * Copyright (C) 2025 StreamSlate Contributors
+ *
+ * Thermal pressure and battery monitoring for laptops.
+ * Sustained capture and encoding on a hot or nearly flat MacBook ends in
+ * heavy throttling; dropping the output frame rate early keeps it smooth.
+ */
+
+use serde::{Deserialize, Serialize};
+
+/// Event emitted when thermal or battery conditions change severity
+pub const POWER_WARNING_EVENT: &str = "output-power-warning";
+
+/// Battery percentage at or below which running on battery is critical
+pub const LOW_BATTERY_PERCENT: u8 = 15;
+
+/// Lowest frame rate automatic throttling drops to
+pub const MIN_THROTTLED_FPS: u8 = 10;
+
+/// System thermal pressure (mirrors `NSProcessInfoThermalState`)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ThermalState {
+    Nominal,
+    Fair,
+    Serious,
+    Critical,
+}
+
+/// Battery charge and power source
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BatteryStatus {
+    pub percent: u8,
+    /// Whether the machine is on AC power
+    pub on_ac_power: bool,
+}
+
+/// Snapshot of thermal and battery state; `None` where unavailable
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PowerStatus {
+    pub thermal: Option<ThermalState>,
+    pub battery: Option<BatteryStatus>,
+}
+
+impl PowerStatus {
+    /// Whether the battery is low and not charging
+    pub fn battery_low(&self) -> bool {
+        self.battery
+            .is_some_and(|b| !b.on_ac_power && b.percent <= LOW_BATTERY_PERCENT)
+    }
+
+    /// Whether output should be reduced to avoid throttling
+    pub fn is_critical(&self) -> bool {
+        self.thermal.is_some_and(|t| t >= ThermalState::Serious) || self.battery_low()
+    }
+
+    /// Frame rate to fall back to from `fps`, or `None` if no reduction is needed
+    pub fn throttled_fps(&self, fps: u8) -> Option<u8> {
+        let divisor = match self.thermal {
+            Some(ThermalState::Critical) => 3,
+            _ if self.is_critical() => 2,
+            _ => return None,
+        };
+        Some((fps / divisor).max(MIN_THROTTLED_FPS).min(fps))
+    }
+}
+
+/// Payload of [`POWER_WARNING_EVENT`]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PowerWarning {
+    pub status: PowerStatus,
+    /// `false` once conditions have recovered
+    pub critical: bool,
+    /// Output frame rate cap applied by automatic throttling
+    pub fps_limit: Option<u8>,
+}
+
+/// Read the current thermal and battery state
+pub fn read_status() -> PowerStatus {
+    PowerStatus {
+        thermal: read_thermal(),
+        battery: read_battery(),
+    }
+}
+
+#[cfg(target_os = "macos")]
+fn read_thermal() -> Option<ThermalState> {
+    extern "C" {
+        fn power_thermal_state() -> std::os::raw::c_int;
+    }
+    match unsafe { power_thermal_state() } {
+        0 => Some(ThermalState::Nominal),
+        1 => Some(ThermalState::Fair),
+        2 => Some(ThermalState::Serious),
+        3 => Some(ThermalState::Critical),
+        _ => None,
+    }
+}
+
+#[cfg(not(target_os = "macos"))]
+fn read_thermal() -> Option<ThermalState> {
+    None
+}
+
+#[cfg(target_os = "macos")]
+fn read_battery() -> Option<BatteryStatus> {
+    let output = std::process::Command::new("pmset")
+        .args(["-g", "batt"])
+        .output()
+        .ok()?;
+    parse_pmset(&String::from_utf8_lossy(&output.stdout))
+}
+
+#[cfg(target_os = "linux")]
+fn read_battery() -> Option<BatteryStatus> {
+    let entries = std::fs::read_dir("/sys/class/power_supply").ok()?;
+    entries.flatten().find_map(|entry| {
+        let dir = entry.path();
+        let read = |name: &str| std::fs::read_to_string(dir.join(name)).ok();
+        if read("type")?.trim() != "Battery" {
+            return None;
+        }
+        parse_sysfs(&read("capacity")?, &read("status")?)
+    })
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "linux")))]
+fn read_battery() -> Option<BatteryStatus> {
+    None
+}
+
+/// Parse `pmset -g batt` output; `None` on machines without a battery
+#[cfg_attr(not(target_os = "macos"), allow(dead_code))]
+fn parse_pmset(output: &str) -> Option<BatteryStatus> {
+    let on_ac_power = output.contains("'AC Power'");
+    let line = output
+        .lines()
+        .find(|line| line.contains("InternalBattery"))?;
+    let percent = line
+        .split(|c: char| c.is_whitespace() || c == ';')
+        .find_map(|field| field.strip_suffix('%')?.parse::<u8>().ok())?;
+    Some(BatteryStatus {
+        percent: percent.min(100),
+        on_ac_power,
+    })
+}
+
+/// Parse the sysfs `capacity` and `status` attributes of a battery
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+fn parse_sysfs(capacity: &str, status: &str) -> Option<BatteryStatus> {
+    Some(BatteryStatus {
+        percent: capacity.trim().parse::<u8>().ok()?.min(100),
+        on_ac_power: status.trim() != "Discharging",
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_pmset() {
+        let output = "Now drawing from 'Battery Power'\n \
+            -InternalBattery-0 (id=4587619)\t12%; discharging; 0:41 remaining present: true\n";
+        assert_eq!(
+            parse_pmset(output),
+            Some(BatteryStatus {
+                percent: 12,
+                on_ac_power: false
+            })
+        );
+        assert_eq!(parse_pmset("Now drawing from 'AC Power'\n"), None);
+        assert_eq!(
+            parse_sysfs("87\n", "Charging\n"),
+            Some(BatteryStatus {
+                percent: 87,
+                on_ac_power: true
+            })
+        );
+    }
+
+    #[test]
+    fn test_throttled_fps() {
+        let nominal = PowerStatus {
+            thermal: Some(ThermalState::Fair),
+            battery: Some(BatteryStatus {
+                percent: 40,
+                on_ac_power: false,
+            }),
+        };
+        assert!(!nominal.is_critical());
+        assert_eq!(nominal.throttled_fps(30), None);
+
+        let serious = PowerStatus {
+            thermal: Some(ThermalState::Serious),
+            ..nominal
+        };
+        assert_eq!(serious.throttled_fps(60), Some(30));
+        assert_eq!(serious.throttled_fps(15), Some(10));
+
+        let critical = PowerStatus {
+            thermal: Some(ThermalState::Critical),
+            ..nominal
+        };
+        assert_eq!(critical.throttled_fps(30), Some(10));
+
+        let low_battery = PowerStatus {
+            thermal: None,
+            battery: Some(BatteryStatus {
+                percent: 9,
+                on_ac_power: false,
+            }),
+        };
+        assert!(low_battery.battery_low());
+        assert_eq!(low_battery.throttled_fps(30), Some(15));
+        assert_eq!(low_battery.throttled_fps(8), Some(8));
+    }
+}
@@ -0,0 +1,224 @@
+/*
+ * This file is part of StreamSlate.
+ * Copyright (C) 2025 StreamSlate Contributors
+ *
+ * Digital pan/zoom over the output frame.
+ * A virtual camera that crops into the captured frame and scales it back to
+ * full size, easing between positions so a producer can punch in on a chart
+ * like a PTZ camera.
+ */
+
+use super::CapturedFrame;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::time::{Duration, Instant};
+
+/// Deepest zoom the viewport allows
+pub const MAX_ZOOM: f64 = 8.0;
+
+/// Region of the frame sent to the outputs
+///
+/// `x`/`y` are the viewport centre as fractions of the frame (0.5 is the
+/// middle); `zoom` is the magnification, 1.0 showing the whole frame.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct OutputViewport {
+    pub x: f64,
+    pub y: f64,
+    pub zoom: f64,
+}
+
+impl Default for OutputViewport {
+    fn default() -> Self {
+        Self {
+            x: 0.5,
+            y: 0.5,
+            zoom: 1.0,
+        }
+    }
+}
+
+impl OutputViewport {
+    /// Clamp zoom to 1..=MAX_ZOOM and keep the view inside the frame
+    pub fn clamped(self) -> Self {
+        let zoom = if self.zoom.is_finite() {
+            self.zoom.clamp(1.0, MAX_ZOOM)
+        } else {
+            1.0
+        };
+        let half = 0.5 / zoom;
+        let centre = |v: f64| {
+            if v.is_finite() {
+                v.clamp(half, 1.0 - half)
+            } else {
+                0.5
+            }
+        };
+        Self {
+            x: centre(self.x),
+            y: centre(self.y),
+            zoom,
+        }
+    }
+
+    /// Whether this shows the whole frame unchanged
+    pub fn is_identity(&self) -> bool {
+        self.zoom <= 1.0
+    }
+
+    fn lerp(self, to: Self, t: f64) -> Self {
+        Self {
+            x: self.x + (to.x - self.x) * t,
+            y: self.y + (to.y - self.y) * t,
+            zoom: self.zoom + (to.zoom - self.zoom) * t,
+        }
+    }
+}
+
+/// A viewport moving towards a target over time
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ViewportAnimation {
+    from: OutputViewport,
+    to: OutputViewport,
+    started: Option<Instant>,
+    duration: Duration,
+}
+
+impl ViewportAnimation {
+    /// Start moving from wherever the viewport is at `now` to `target`
+    pub fn set_target(&mut self, target: OutputViewport, duration: Duration, now: Instant) {
+        self.from = self.current(now);
+        self.to = target.clamped();
+        self.started = Some(now);
+        self.duration = duration;
+    }
+
+    /// Where the animation finishes
+    pub fn target(&self) -> OutputViewport {
+        self.to
+    }
+
+    /// Viewport at `now`, eased in and out
+    pub fn current(&self, now: Instant) -> OutputViewport {
+        let Some(started) = self.started else {
+            return self.to;
+        };
+        if self.duration.is_zero() {
+            return self.to;
+        }
+        let t = (now.saturating_duration_since(started).as_secs_f64()
+            / self.duration.as_secs_f64())
+        .min(1.0);
+        // Smoothstep, so moves start and stop gently like a camera head
+        self.from.lerp(self.to, t * t * (3.0 - 2.0 * t))
+    }
+}
+
+/// Crop `frame` to `viewport` and scale it back to the frame's size
+///
+/// Uses bilinear sampling so text stays legible while zooming.
+pub fn apply(frame: &CapturedFrame, viewport: OutputViewport) -> CapturedFrame {
+    let (width, height) = (frame.width as usize, frame.height as usize);
+    let stride = frame.bytes_per_row as usize;
+    if width == 0 || height == 0 || frame.data.len() < stride * height {
+        return frame.clone();
+    }
+    let out_stride = width * 4;
+    let mut data = vec![0u8; out_stride * height];
+
+    // Source region in 16.16 fixed point
+    let view = viewport.clamped();
+    let scale = 1.0 / view.zoom;
+    let left = (view.x - scale / 2.0) * width as f64;
+    let top = (view.y - scale / 2.0) * height as f64;
+    let step = (scale * 65536.0) as i64;
+    let origin_x = (left * 65536.0) as i64 + step / 2 - 32768;
+    let origin_y = (top * 65536.0) as i64 + step / 2 - 32768;
+    let (max_x, max_y) = (width as i64 - 1, height as i64 - 1);
+
+    for y in 0..height {
+        let sy = (origin_y + step * y as i64).max(0);
+        let y0 = (sy >> 16).min(max_y) as usize;
+        let y1 = ((sy >> 16) + 1).min(max_y) as usize;
+        let fy = (sy & 0xFFFF) as u32 >> 8;
+        let (row0, row1) = (&frame.data[y0 * stride..], &frame.data[y1 * stride..]);
+        let out = &mut data[y * out_stride..][..out_stride];
+
+        for x in 0..width {
+            let sx = (origin_x + step * x as i64).max(0);
+            let x0 = (sx >> 16).min(max_x) as usize * 4;
+            let x1 = ((sx >> 16) + 1).min(max_x) as usize * 4;
+            let fx = (sx & 0xFFFF) as u32 >> 8;
+            for c in 0..4 {
+                let top = u32::from(row0[x0 + c]) * (256 - fx) + u32::from(row0[x1 + c]) * fx;
+                let bottom = u32::from(row1[x0 + c]) * (256 - fx) + u32::from(row1[x1 + c]) * fx;
+                out[x * 4 + c] = ((top * (256 - fy) + bottom * fy + 32768) >> 16) as u8;
+            }
+        }
+    }
+
+    CapturedFrame {
+        data,
+        width: frame.width,
+        height: frame.height,
+        bytes_per_row: out_stride as u32,
+        timestamp_ns: frame.timestamp_ns,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_animation_eases_to_target() {
+        let start = Instant::now();
+        let mut animation = ViewportAnimation::default();
+        let target = OutputViewport {
+            x: 0.75,
+            y: 0.75,
+            zoom: 2.0,
+        };
+        animation.set_target(target, Duration::from_secs(1), start);
+
+        let halfway = animation.current(start + Duration::from_millis(500));
+        assert!((halfway.zoom - 1.5).abs() < 1e-9);
+        assert_eq!(animation.current(start + Duration::from_secs(2)), target);
+
+        // Centre is pulled back so the view never leaves the frame
+        let edge = OutputViewport {
+            x: 1.0,
+            y: 0.0,
+            zoom: 4.0,
+        }
+        .clamped();
+        assert_eq!((edge.x, edge.y), (0.875, 0.125));
+    }
+
+    #[test]
+    fn test_apply_zooms_into_quadrant() {
+        // 4x4 frame whose top-left 2x2 block is white
+        let mut data = vec![0u8; 4 * 4 * 4];
+        for y in 0..2 {
+            for x in 0..2 {
+                data[(y * 4 + x) * 4..][..4].copy_from_slice(&[255; 4]);
+            }
+        }
+        let frame = CapturedFrame {
+            data,
+            width: 4,
+            height: 4,
+            bytes_per_row: 16,
+            timestamp_ns: 0,
+        };
+        let zoomed = apply(
+            &frame,
+            OutputViewport {
+                x: 0.25,
+                y: 0.25,
+                zoom: 2.0,
+            },
+        );
+        assert_eq!(zoomed.data[0], 255);
+        assert!(zoomed.data.iter().all(|&v| v > 128));
+    }
+}
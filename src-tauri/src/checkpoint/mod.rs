@@ -0,0 +1,289 @@
+/*
+ * This file is part of StreamSlate.
+ * Copyright (C) 2025 StreamSlate Contributors
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+
+//! Attendance checkpoints
+//!
+//! A checkpoint shows a code word on the live output; viewers prove they are
+//! watching by submitting their name with the code over the HTTP endpoint.
+//! Every checkpoint of an app session is kept in one results file in the app
+//! data directory, so a lecture's attendance can be reviewed afterwards.
+
+use crate::error::{Result, StreamSlateError};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use tauri::{AppHandle, Manager};
+use tracing::warn;
+
+/// Directory of the session results inside the app data directory
+const RESULTS_DIR: &str = "checkpoints";
+
+/// Longest name accepted from a viewer, in characters
+pub const MAX_NAME_LEN: usize = 64;
+
+/// Responses accepted per checkpoint before further ones are turned away
+pub const MAX_RESPONSES: usize = 2000;
+
+/// Code words shown on the output; short, common and hard to mistype
+const CODE_WORDS: &[&str] = &[
+    "AMBER", "ANCHOR", "APPLE", "BADGE", "BAMBOO", "BEACON", "BRIDGE", "CACTUS", "CANDLE",
+    "CANYON", "COMET", "CORAL", "DELTA", "DRAGON", "EMBER", "FALCON", "FOREST", "GALAXY", "GARDEN",
+    "GLACIER", "HARBOR", "HONEY", "ISLAND", "JASMINE", "JUNGLE", "KETTLE", "LANTERN", "LEMON",
+    "MAPLE", "MEADOW", "MIRROR", "NEBULA", "OLIVE", "ORBIT", "PEBBLE", "PEPPER", "PLANET",
+    "QUARTZ", "RAVEN", "RIVER", "ROCKET", "SAFFRON", "SPARROW", "SUMMIT", "THUNDER", "TULIP",
+    "VELVET", "WALNUT", "WILLOW", "ZEPHYR",
+];
+
+/// A viewer who answered a checkpoint
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CheckpointResponse {
+    pub name: String,
+    /// When the response arrived (RFC 3339)
+    pub received_at: String,
+}
+
+/// One checkpoint and the viewers who answered it
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Checkpoint {
+    pub id: String,
+    /// Code word shown on the output
+    pub code: String,
+    /// Question or instruction shown with the code
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub prompt: Option<String>,
+    /// Page on screen when the checkpoint opened
+    pub page: u32,
+    /// When the checkpoint opened (RFC 3339)
+    pub opened_at: String,
+    /// When the checkpoint stopped taking responses (RFC 3339)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub closed_at: Option<String>,
+    #[serde(default)]
+    pub responses: Vec<CheckpointResponse>,
+}
+
+impl Checkpoint {
+    /// Open a checkpoint with a freshly picked code word
+    pub fn open(prompt: Option<String>, page: u32, opened_at: &str) -> Self {
+        let id = uuid::Uuid::new_v4();
+        Self {
+            id: id.to_string(),
+            code: pick_code_word(id.as_bytes()).to_string(),
+            prompt: prompt
+                .map(|p| p.trim().to_string())
+                .filter(|p| !p.is_empty()),
+            page,
+            opened_at: opened_at.to_string(),
+            closed_at: None,
+            responses: Vec::new(),
+        }
+    }
+
+    /// Whether responses are still accepted
+    pub fn is_open(&self) -> bool {
+        self.closed_at.is_none()
+    }
+
+    /// Record `name` as present if `code` matches
+    ///
+    /// Returns `false` if the name already answered (compared without case).
+    pub fn respond(&mut self, name: &str, code: &str, received_at: &str) -> Result<bool> {
+        if !self.is_open() {
+            return Err(StreamSlateError::Other(
+                "The checkpoint is closed".to_string(),
+            ));
+        }
+        if !code.trim().eq_ignore_ascii_case(&self.code) {
+            return Err(StreamSlateError::Other("Wrong code word".to_string()));
+        }
+        let name = validate_name(name)?;
+        if self
+            .responses
+            .iter()
+            .any(|r| r.name.to_lowercase() == name.to_lowercase())
+        {
+            return Ok(false);
+        }
+        if self.responses.len() >= MAX_RESPONSES {
+            return Err(StreamSlateError::Other(
+                "The checkpoint is full".to_string(),
+            ));
+        }
+        self.responses.push(CheckpointResponse {
+            name,
+            received_at: received_at.to_string(),
+        });
+        Ok(true)
+    }
+
+    /// Stop taking responses
+    pub fn close(&mut self, closed_at: &str) {
+        if self.closed_at.is_none() {
+            self.closed_at = Some(closed_at.to_string());
+        }
+    }
+}
+
+/// Checkpoints held during one run of the app
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CheckpointSession {
+    pub session_id: String,
+    /// When the first checkpoint opened (RFC 3339)
+    pub started_at: String,
+    pub checkpoints: Vec<Checkpoint>,
+}
+
+impl CheckpointSession {
+    pub fn new(started_at: &str) -> Self {
+        Self {
+            session_id: uuid::Uuid::new_v4().to_string(),
+            started_at: started_at.to_string(),
+            checkpoints: Vec::new(),
+        }
+    }
+
+    /// The checkpoint taking responses, if any
+    pub fn current(&self) -> Option<&Checkpoint> {
+        self.checkpoints.last().filter(|c| c.is_open())
+    }
+
+    /// Mutable access to the checkpoint taking responses, if any
+    pub fn current_mut(&mut self) -> Option<&mut Checkpoint> {
+        self.checkpoints.last_mut().filter(|c| c.is_open())
+    }
+}
+
+/// Trimmed viewer name, rejecting empty, overlong or control characters
+fn validate_name(name: &str) -> Result<String> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err(StreamSlateError::Other("Name is required".to_string()));
+    }
+    if name.chars().count() > MAX_NAME_LEN {
+        return Err(StreamSlateError::Other(format!(
+            "Name is longer than {MAX_NAME_LEN} characters"
+        )));
+    }
+    if name.chars().any(char::is_control) {
+        return Err(StreamSlateError::Other(
+            "Name contains control characters".to_string(),
+        ));
+    }
+    Ok(name.to_string())
+}
+
+/// Pick a code word from random bytes
+fn pick_code_word(random: &[u8]) -> &'static str {
+    let n = random
+        .iter()
+        .take(4)
+        .fold(0usize, |acc, b| (acc << 8) | usize::from(*b));
+    CODE_WORDS[n % CODE_WORDS.len()]
+}
+
+/// Resolve the results file of a session in the app data directory
+pub fn session_path(app_handle: &AppHandle, session_id: &str) -> Option<PathBuf> {
+    app_handle
+        .path()
+        .app_data_dir()
+        .ok()
+        .map(|dir| dir.join(RESULTS_DIR).join(format!("{session_id}.json")))
+}
+
+/// Persist a session's checkpoints
+pub fn save_session(path: &Path, session: &CheckpointSession) -> Result<()> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    std::fs::write(path, serde_json::to_string_pretty(session)?)?;
+    Ok(())
+}
+
+/// Load every stored session, oldest first
+pub fn load_sessions(app_handle: &AppHandle) -> Vec<CheckpointSession> {
+    let Some(dir) = app_handle
+        .path()
+        .app_data_dir()
+        .ok()
+        .map(|dir| dir.join(RESULTS_DIR))
+    else {
+        return Vec::new();
+    };
+    let Ok(entries) = std::fs::read_dir(&dir) else {
+        return Vec::new();
+    };
+
+    let mut sessions: Vec<CheckpointSession> = entries
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "json"))
+        .filter_map(|path| {
+            let content = std::fs::read_to_string(&path).ok()?;
+            serde_json::from_str(&content)
+                .map_err(|e| {
+                    warn!(path = %path.display(), error = %e, "Failed to parse checkpoint results");
+                })
+                .ok()
+        })
+        .collect();
+    // RFC 3339 timestamps in UTC sort chronologically
+    sessions.sort_by(|a, b| a.started_at.cmp(&b.started_at));
+    sessions
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const T: &str = "2025-03-01T10:00:00Z";
+
+    #[test]
+    fn test_respond() {
+        let mut checkpoint = Checkpoint::open(Some("  Who's here?  ".to_string()), 4, T);
+        assert_eq!(checkpoint.prompt.as_deref(), Some("Who's here?"));
+        assert!(CODE_WORDS.contains(&checkpoint.code.as_str()));
+
+        let code = checkpoint.code.to_lowercase();
+        assert!(checkpoint.respond(" Ada Lovelace ", &code, T).unwrap());
+        assert!(!checkpoint.respond("ada lovelace", &code, T).unwrap());
+        assert!(checkpoint.respond("Alan", "nope", T).is_err());
+        assert!(checkpoint.respond("   ", &code, T).is_err());
+        assert!(checkpoint.respond(&"x".repeat(65), &code, T).is_err());
+        assert_eq!(checkpoint.responses.len(), 1);
+        assert_eq!(checkpoint.responses[0].name, "Ada Lovelace");
+
+        checkpoint.close(T);
+        assert!(checkpoint.respond("Alan", &code, T).is_err());
+    }
+
+    #[test]
+    fn test_current_checkpoint() {
+        let mut session = CheckpointSession::new(T);
+        assert!(session.current().is_none());
+
+        session.checkpoints.push(Checkpoint::open(None, 1, T));
+        assert!(session.current().is_some());
+        session.current_mut().unwrap().close(T);
+        assert!(session.current().is_none());
+    }
+
+    #[test]
+    fn test_pick_code_word() {
+        assert_eq!(pick_code_word(&[0, 0, 0, 0]), "AMBER");
+        assert_eq!(pick_code_word(&[0, 0, 0, 51]), "ANCHOR");
+    }
+}
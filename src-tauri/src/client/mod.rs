@@ -0,0 +1,382 @@
+/*
+ * This file is part of StreamSlate.
+ * Copyright (C) 2025 StreamSlate Contributors
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+
+//! WebSocket client for connecting out to a desktop StreamSlate instance
+//!
+//! Mobile builds don't run their own control server; instead they act as a
+//! pure remote that connects to a desktop instance. The client speaks the
+//! same protocol types as the server and reconnects with exponential backoff.
+
+use crate::protocol::{WebSocketCommand, WebSocketEvent};
+use futures_util::{SinkExt, StreamExt};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::{mpsc, watch};
+use tokio_tungstenite::tungstenite::client::IntoClientRequest;
+use tokio_tungstenite::tungstenite::handshake::client::Request;
+use tokio_tungstenite::tungstenite::http::header::{HeaderValue, AUTHORIZATION};
+use tokio_tungstenite::{connect_async, tungstenite::Message};
+use tracing::{debug, info, warn};
+
+/// Capacity of the outgoing command queue
+const COMMAND_QUEUE_SIZE: usize = 32;
+
+/// Backoff policy applied between reconnect attempts
+#[derive(Debug, Clone)]
+pub struct ReconnectPolicy {
+    pub initial_delay: Duration,
+    pub max_delay: Duration,
+    pub multiplier: f64,
+}
+
+impl Default for ReconnectPolicy {
+    fn default() -> Self {
+        Self {
+            initial_delay: Duration::from_millis(500),
+            max_delay: Duration::from_secs(30),
+            multiplier: 2.0,
+        }
+    }
+}
+
+impl ReconnectPolicy {
+    /// Delay before the given (zero-based) reconnect attempt
+    pub fn delay(&self, attempt: u32) -> Duration {
+        let factor = self.multiplier.powi(attempt.min(32) as i32);
+        self.initial_delay.mul_f64(factor).min(self.max_delay)
+    }
+}
+
+/// Configuration for a remote client connection
+#[derive(Debug, Clone)]
+pub struct ClientConfig {
+    /// Server URL, e.g. `ws://192.168.1.20:11451`
+    pub url: String,
+    /// Stable client ID sent via `IDENTIFY` after every (re)connect
+    pub client_id: Option<String>,
+    /// Display name sent with the client ID, shown as the author of this
+    /// client's annotations
+    pub name: Option<String>,
+    /// Bearer token sent in the `Authorization` header of the handshake
+    pub auth_token: Option<String>,
+    pub reconnect: ReconnectPolicy,
+}
+
+impl ClientConfig {
+    pub fn new(url: impl Into<String>) -> Self {
+        Self {
+            url: url.into(),
+            client_id: None,
+            name: None,
+            auth_token: None,
+            reconnect: ReconnectPolicy::default(),
+        }
+    }
+
+    /// Build the handshake request for this configuration
+    fn request(&self) -> std::result::Result<Request, String> {
+        let mut request = self
+            .url
+            .as_str()
+            .into_client_request()
+            .map_err(|e| format!("Invalid server URL: {e}"))?;
+
+        if let Some(token) = &self.auth_token {
+            let value = HeaderValue::from_str(&format!("Bearer {token}"))
+                .map_err(|e| format!("Invalid auth token: {e}"))?;
+            request.headers_mut().insert(AUTHORIZATION, value);
+        }
+
+        Ok(request)
+    }
+}
+
+/// Notifications delivered to the client's event handler
+#[derive(Debug, Clone)]
+pub enum ClientNotification {
+    /// Connection (re)established
+    Connected,
+    /// Connection lost; the client will retry after `retry_in`
+    Disconnected { reason: String, retry_in: Duration },
+    /// Event received from the server
+    Event(WebSocketEvent),
+}
+
+/// Handle to a running client task
+///
+/// Dropping the handle does not stop the client; call `shutdown`.
+#[derive(Debug, Clone)]
+pub struct ClientHandle {
+    command_tx: mpsc::Sender<WebSocketCommand>,
+    shutdown_tx: watch::Sender<bool>,
+    connected: Arc<AtomicBool>,
+}
+
+impl ClientHandle {
+    /// Queue a command for the server
+    ///
+    /// Fails while disconnected rather than replaying stale commands
+    /// (e.g. a burst of NEXT_PAGE) once the connection comes back.
+    pub fn send(&self, command: WebSocketCommand) -> Result<(), String> {
+        if !self.is_connected() {
+            return Err("Not connected to StreamSlate".to_string());
+        }
+        self.command_tx
+            .try_send(command)
+            .map_err(|e| format!("Failed to queue command: {e}"))
+    }
+
+    /// Whether the client currently has a live connection
+    pub fn is_connected(&self) -> bool {
+        self.connected.load(Ordering::SeqCst)
+    }
+
+    /// Stop the client and close the connection
+    pub fn shutdown(&self) {
+        let _ = self.shutdown_tx.send(true);
+    }
+}
+
+/// Spawn a client task that connects to `config.url` and keeps reconnecting
+pub fn spawn<F>(config: ClientConfig, on_notification: F) -> ClientHandle
+where
+    F: Fn(ClientNotification) + Send + Sync + 'static,
+{
+    let (command_tx, command_rx) = mpsc::channel(COMMAND_QUEUE_SIZE);
+    let (shutdown_tx, shutdown_rx) = watch::channel(false);
+    let connected = Arc::new(AtomicBool::new(false));
+
+    let handle = ClientHandle {
+        command_tx,
+        shutdown_tx,
+        connected: connected.clone(),
+    };
+
+    tauri::async_runtime::spawn(run_client(
+        config,
+        command_rx,
+        shutdown_rx,
+        connected,
+        on_notification,
+    ));
+
+    handle
+}
+
+/// Spawn a client task and deliver notifications through a channel
+///
+/// Convenience over `spawn` for callers that prefer to `.await` events.
+pub fn spawn_with_channel(
+    config: ClientConfig,
+) -> (ClientHandle, mpsc::UnboundedReceiver<ClientNotification>) {
+    let (notification_tx, notification_rx) = mpsc::unbounded_channel();
+    let handle = spawn(config, move |notification| {
+        let _ = notification_tx.send(notification);
+    });
+    (handle, notification_rx)
+}
+
+async fn run_client<F>(
+    config: ClientConfig,
+    mut command_rx: mpsc::Receiver<WebSocketCommand>,
+    mut shutdown_rx: watch::Receiver<bool>,
+    connected: Arc<AtomicBool>,
+    on_notification: F,
+) where
+    F: Fn(ClientNotification) + Send + Sync + 'static,
+{
+    let mut attempt = 0u32;
+
+    loop {
+        if *shutdown_rx.borrow() {
+            break;
+        }
+
+        let request = match config.request() {
+            Ok(request) => request,
+            Err(reason) => {
+                // A malformed URL or token will never succeed; don't retry
+                warn!(url = %config.url, reason = %reason, "Remote client not started");
+                on_notification(ClientNotification::Disconnected {
+                    reason,
+                    retry_in: Duration::ZERO,
+                });
+                break;
+            }
+        };
+
+        let reason = match connect_async(request).await {
+            Ok((ws_stream, _)) => {
+                info!(url = %config.url, "Connected to StreamSlate server");
+                attempt = 0;
+                connected.store(true, Ordering::SeqCst);
+                on_notification(ClientNotification::Connected);
+
+                let reason = run_connection(
+                    ws_stream,
+                    &config,
+                    &mut command_rx,
+                    &mut shutdown_rx,
+                    &on_notification,
+                )
+                .await;
+
+                connected.store(false, Ordering::SeqCst);
+                // Drop anything queued for the dead connection
+                while command_rx.try_recv().is_ok() {}
+                reason
+            }
+            Err(e) => format!("Connect failed: {e}"),
+        };
+
+        if *shutdown_rx.borrow() {
+            break;
+        }
+
+        let retry_in = config.reconnect.delay(attempt);
+        attempt = attempt.saturating_add(1);
+        warn!(url = %config.url, reason = %reason, ?retry_in, "Disconnected from server");
+        on_notification(ClientNotification::Disconnected { reason, retry_in });
+
+        tokio::select! {
+            _ = tokio::time::sleep(retry_in) => {}
+            _ = shutdown_rx.changed() => {}
+        }
+    }
+
+    info!(url = %config.url, "Remote client stopped");
+}
+
+/// Drive a single connection until it drops, returning the reason
+async fn run_connection<S, F>(
+    ws_stream: tokio_tungstenite::WebSocketStream<S>,
+    config: &ClientConfig,
+    command_rx: &mut mpsc::Receiver<WebSocketCommand>,
+    shutdown_rx: &mut watch::Receiver<bool>,
+    on_notification: &F,
+) -> String
+where
+    S: tokio::io::AsyncRead + tokio::io::AsyncWrite + Unpin,
+    F: Fn(ClientNotification),
+{
+    let (mut ws_sender, mut ws_receiver) = ws_stream.split();
+
+    if let Some(client_id) = &config.client_id {
+        let identify = WebSocketCommand::Identify {
+            client_id: client_id.clone(),
+            name: config.name.clone(),
+        };
+        if let Err(e) = send_command(&mut ws_sender, &identify).await {
+            return e;
+        }
+    }
+
+    loop {
+        tokio::select! {
+            msg = ws_receiver.next() => {
+                match msg {
+                    Some(Ok(Message::Text(text))) => {
+                        match serde_json::from_str::<WebSocketEvent>(&text) {
+                            Ok(event) => on_notification(ClientNotification::Event(event)),
+                            Err(e) => debug!(error = %e, "Ignoring unrecognized server message"),
+                        }
+                    }
+                    Some(Ok(Message::Ping(data))) => {
+                        if let Err(e) = ws_sender.send(Message::Pong(data)).await {
+                            return e.to_string();
+                        }
+                    }
+                    Some(Ok(Message::Close(_))) | None => return "Server closed the connection".to_string(),
+                    Some(Err(e)) => return e.to_string(),
+                    _ => {}
+                }
+            }
+
+            command = command_rx.recv() => {
+                let Some(command) = command else {
+                    return "Command channel closed".to_string();
+                };
+                if let Err(e) = send_command(&mut ws_sender, &command).await {
+                    return e;
+                }
+            }
+
+            _ = shutdown_rx.changed() => {
+                let _ = ws_sender.send(Message::Close(None)).await;
+                return "Client shut down".to_string();
+            }
+        }
+    }
+}
+
+async fn send_command<W>(ws_sender: &mut W, command: &WebSocketCommand) -> Result<(), String>
+where
+    W: futures_util::Sink<Message> + Unpin,
+    W::Error: std::fmt::Display,
+{
+    let json = serde_json::to_string(command).map_err(|e| e.to_string())?;
+    ws_sender
+        .send(Message::Text(json))
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_reconnect_backoff() {
+        let policy = ReconnectPolicy::default();
+        assert_eq!(policy.delay(0), Duration::from_millis(500));
+        assert_eq!(policy.delay(1), Duration::from_secs(1));
+        assert_eq!(policy.delay(3), Duration::from_secs(4));
+        assert_eq!(policy.delay(100), Duration::from_secs(30));
+    }
+
+    #[test]
+    fn test_send_fails_while_disconnected() {
+        let (command_tx, _command_rx) = mpsc::channel(1);
+        let (shutdown_tx, _shutdown_rx) = watch::channel(false);
+        let handle = ClientHandle {
+            command_tx,
+            shutdown_tx,
+            connected: Arc::new(AtomicBool::new(false)),
+        };
+
+        assert!(handle.send(WebSocketCommand::NextPage).is_err());
+    }
+
+    #[test]
+    fn test_auth_header() {
+        let mut config = ClientConfig::new("ws://127.0.0.1:11451");
+        assert!(config
+            .request()
+            .unwrap()
+            .headers()
+            .get(AUTHORIZATION)
+            .is_none());
+
+        config.auth_token = Some("secret".to_string());
+        let request = config.request().unwrap();
+        assert_eq!(request.headers()[AUTHORIZATION], "Bearer secret");
+
+        assert!(ClientConfig::new("not a url").request().is_err());
+    }
+}
@@ -0,0 +1,119 @@
+/*
+ * This file is part of StreamSlate.
+ * Copyright (C) 2025 StreamSlate Contributors
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+
+//! Agenda commands
+//!
+//! Builds an agenda from the open document's outline and keeps it current
+//! as pages change: pushed to the webview (`agenda-changed`) and remote
+//! overlays (`AGENDA`) when the current section changes, and optionally drawn
+//! as a progress strip on the outputs.
+
+use super::pdf::listen_page_changes;
+use crate::capture::agenda::{self, Agenda, SectionProgress};
+use crate::error::{Result, StreamSlateError};
+use crate::pdf::outline;
+use crate::state::AppState;
+use crate::websocket::WebSocketEvent;
+use std::sync::Mutex;
+use tauri::{AppHandle, Emitter, State};
+use tracing::{info, instrument, warn};
+
+/// Agenda of the open document at the current page
+#[tauri::command]
+#[instrument(skip(state))]
+pub async fn get_agenda(state: State<'_, AppState>) -> Result<Agenda> {
+    current_agenda(&state)
+}
+
+/// Draw the agenda as a progress strip along the bottom of the outputs
+#[tauri::command]
+#[instrument(skip(state))]
+pub async fn set_agenda_strip(enabled: bool, state: State<'_, AppState>) -> Result<Agenda> {
+    let agenda = current_agenda(&state)?;
+    state
+        .outputs
+        .lock()
+        .map_err(|e| StreamSlateError::StateLock(format!("Outputs: {e}")))?
+        .agenda_strip = enabled.then(|| agenda.clone());
+    info!(
+        enabled,
+        sections = agenda.items.len(),
+        "Agenda strip updated"
+    );
+    Ok(agenda)
+}
+
+/// Agenda of the open document, empty without an outline
+pub(crate) fn current_agenda(state: &AppState) -> Result<Agenda> {
+    agenda_at(state, state.get_pdf_state()?.current_page)
+}
+
+/// Progress through the outline section containing `page`
+pub(crate) fn section_progress(state: &AppState, page: u32) -> Option<SectionProgress> {
+    agenda_at(state, page).ok()?.progress()
+}
+
+fn agenda_at(state: &AppState, page: u32) -> Result<Agenda> {
+    let total_pages = state.get_pdf_state()?.total_pages;
+    let entries = state
+        .pdf_document
+        .read()
+        .map_err(|e| StreamSlateError::StateLock(format!("PDF document: {e}")))?
+        .as_ref()
+        .map(outline::top_level_entries)
+        .unwrap_or_default();
+    Ok(agenda::build(&entries, page, total_pages))
+}
+
+/// Keep the agenda current on every page change
+pub fn spawn_agenda_listener(app: &AppHandle, state: AppState) {
+    let handle = app.clone();
+    // Document and section last announced, so paging within a section is quiet
+    let announced: Mutex<Option<(Option<String>, Option<usize>)>> = Mutex::new(None);
+    listen_page_changes(app, move |page| {
+        // The event can arrive before the page is stored in state
+        let agenda = match agenda_at(&state, page) {
+            Ok(agenda) => agenda,
+            Err(e) => {
+                warn!(error = %e, "Failed to build agenda");
+                return;
+            }
+        };
+
+        if let Ok(mut outputs) = state.outputs.lock() {
+            if outputs.agenda_strip.is_some() {
+                outputs.agenda_strip = Some(agenda.clone());
+            }
+        }
+
+        let key = (
+            state.get_pdf_state().ok().and_then(|s| s.current_file),
+            agenda.current,
+        );
+        let changed = announced
+            .lock()
+            .map(|mut last| last.replace(key.clone()) != Some(key))
+            .unwrap_or(false);
+        if changed && !agenda.items.is_empty() {
+            if let Err(e) = handle.emit("agenda-changed", &agenda) {
+                warn!(error = %e, "Failed to emit agenda-changed event");
+            }
+            let _ = state.broadcast(WebSocketEvent::Agenda { agenda });
+        }
+    });
+}
@@ -0,0 +1,257 @@
+/*
+ * This file is part of StreamSlate.
+ * Copyright (C) 2025 StreamSlate Contributors
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+
+//! Slide summarization commands
+//!
+//! Page summaries come from the endpoint set with `set_summarizer_config`
+//! (requires the `ai` feature) and are cached per document. New summaries
+//! are emitted as `page-summary` and broadcast as `PAGE_SUMMARY` for
+//! overlays and speaker notes.
+
+use super::pdf::listen_page_changes;
+#[cfg(feature = "ai")]
+use super::pdf::words_for_page;
+use crate::ai::{self, PageSummary, SummarizerConfig, SummaryCache};
+#[cfg(feature = "ai")]
+use crate::ai::{http::HttpSummarizer, Summarizer, SummaryRequest};
+use crate::error::{Result, StreamSlateError};
+use crate::settings::{load_settings, save_settings};
+use crate::state::AppState;
+#[cfg(feature = "ai")]
+use crate::websocket::WebSocketEvent;
+#[cfg(feature = "ai")]
+use tauri::Emitter;
+use tauri::{AppHandle, State};
+#[cfg(feature = "ai")]
+use tracing::warn;
+use tracing::{info, instrument};
+
+/// Check if summarization support is compiled in
+#[tauri::command]
+pub async fn is_summarizer_available() -> Result<bool> {
+    Ok(cfg!(feature = "ai"))
+}
+
+/// Current summarizer settings
+#[tauri::command]
+#[instrument(skip(state))]
+pub async fn get_summarizer_config(state: State<'_, AppState>) -> Result<SummarizerConfig> {
+    Ok(summary_state(&state)?.config.clone())
+}
+
+/// Set the summarization endpoint and persist the choice
+///
+/// Clearing the endpoint stops all summarization requests.
+#[tauri::command]
+#[instrument(skip(app, state, config))]
+pub async fn set_summarizer_config(
+    config: SummarizerConfig,
+    app: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<()> {
+    config.validate()?;
+    save_settings(&app, ai::CONFIG_FILE, &config)?;
+    summary_state(&state)?.config = config;
+    info!("Summarizer settings updated");
+    Ok(())
+}
+
+/// Cached summaries of the open document's pages, in page order
+#[tauri::command]
+#[instrument(skip(app, state))]
+pub async fn get_page_summaries(
+    app: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<Vec<PageSummary>> {
+    let document = open_document(&state)?;
+    let mut summaries = summary_state(&state)?;
+    let cache = document_cache(&mut summaries.cache, &app, &document);
+    Ok(cache.pages.values().cloned().collect())
+}
+
+/// Summarize a page (1-indexed) of the open document
+///
+/// Returns the cached summary while the page text is unchanged, unless
+/// `refresh` is set.
+#[tauri::command]
+#[instrument(skip(app, state))]
+#[cfg(feature = "ai")]
+pub async fn summarize_page(
+    page: u32,
+    refresh: Option<bool>,
+    app: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<PageSummary> {
+    summarize(&state, &app, page, refresh.unwrap_or(false)).await
+}
+
+/// Summarize a page (stub when built without the `ai` feature)
+#[tauri::command]
+#[instrument(skip(app, state))]
+#[cfg(not(feature = "ai"))]
+pub async fn summarize_page(
+    page: u32,
+    refresh: Option<bool>,
+    app: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<PageSummary> {
+    let _ = (refresh, app, state);
+    Err(StreamSlateError::Other(format!(
+        "Cannot summarize page {page}: StreamSlate was built without the 'ai' feature"
+    )))
+}
+
+/// Load stored settings and, with `autoSummarize`, summarize pages as
+/// they are shown
+pub fn spawn_summary_listener(app: &AppHandle, state: AppState) {
+    let config = load_settings(app, ai::CONFIG_FILE);
+    if let Ok(mut summaries) = state.summaries.lock() {
+        summaries.config = config;
+    }
+
+    let handle = app.clone();
+    listen_page_changes(app, move |page| {
+        let auto = state
+            .summaries
+            .lock()
+            .is_ok_and(|s| s.config.is_enabled() && s.config.auto_summarize);
+        if !auto {
+            return;
+        }
+        #[cfg(feature = "ai")]
+        {
+            let (state, app) = (state.clone(), handle.clone());
+            tauri::async_runtime::spawn(async move {
+                if let Err(e) = summarize(&state, &app, page, false).await {
+                    warn!(page, error = %e, "Failed to summarize page");
+                }
+            });
+        }
+        #[cfg(not(feature = "ai"))]
+        let _ = (page, &handle);
+    });
+}
+
+/// Summarize `page`, using the cache unless `refresh` is set
+#[cfg(feature = "ai")]
+async fn summarize(
+    state: &AppState,
+    app: &AppHandle,
+    page: u32,
+    refresh: bool,
+) -> Result<PageSummary> {
+    let document = open_document(state)?;
+    let config = summary_state(state)?.config.clone();
+    let summarizer = HttpSummarizer::from_config(&config).ok_or_else(|| {
+        StreamSlateError::Other("No summarization endpoint is configured".to_string())
+    })?;
+
+    let words = words_for_page(state, page)?;
+    let text = ai::prepare_text(
+        &words
+            .iter()
+            .map(|w| w.text.as_str())
+            .collect::<Vec<_>>()
+            .join(" "),
+        config.max_chars.unwrap_or(ai::DEFAULT_MAX_CHARS),
+    );
+    if text.is_empty() {
+        return Err(StreamSlateError::Other(format!(
+            "Page {page} has no text to summarize"
+        )));
+    }
+    let text_hash = ai::text_hash(&text);
+
+    if !refresh {
+        let mut summaries = summary_state(state)?;
+        if let Some(cached) =
+            document_cache(&mut summaries.cache, app, &document).get(page, &text_hash)
+        {
+            return Ok(cached.clone());
+        }
+    }
+
+    let request = SummaryRequest {
+        document: std::path::Path::new(&document)
+            .file_name()
+            .map_or_else(|| document.clone(), |n| n.to_string_lossy().into_owned()),
+        page,
+        text,
+        model: config.model.clone(),
+    };
+    let summary = PageSummary {
+        page,
+        summary: summarizer.summarize(&request).await?,
+        text_hash,
+        created_at: chrono::Utc::now().to_rfc3339(),
+    };
+    info!(page, chars = summary.summary.len(), "Page summarized");
+
+    {
+        let mut summaries = summary_state(state)?;
+        let cache = document_cache(&mut summaries.cache, app, &document);
+        cache.insert(summary.clone());
+        if let Some(path) = ai::cache_path(app, &document) {
+            if let Err(e) = ai::save_cache(&path, cache) {
+                warn!(error = %e, "Failed to save page summaries");
+            }
+        }
+    }
+
+    if let Err(e) = app.emit("page-summary", &summary) {
+        warn!("Failed to emit page summary: {}", e);
+    }
+    state.broadcast(WebSocketEvent::PageSummary {
+        page,
+        summary: summary.summary.clone(),
+    })?;
+    Ok(summary)
+}
+
+/// Path of the open document
+fn open_document(state: &AppState) -> Result<String> {
+    state
+        .get_pdf_state()?
+        .current_file
+        .ok_or(StreamSlateError::NoDocument)
+}
+
+/// The cache for `document`, loading it from disk when another is held
+fn document_cache<'a>(
+    cache: &'a mut Option<SummaryCache>,
+    app: &AppHandle,
+    document: &str,
+) -> &'a mut SummaryCache {
+    if cache.as_ref().map_or(true, |c| c.document != document) {
+        *cache = Some(
+            ai::cache_path(app, document)
+                .map(|path| ai::load_cache(&path, document))
+                .unwrap_or_else(|| SummaryCache::new(document)),
+        );
+    }
+    cache.get_or_insert_with(|| SummaryCache::new(document))
+}
+
+fn summary_state(
+    state: &AppState,
+) -> Result<std::sync::MutexGuard<'_, crate::state::SummaryState>> {
+    state
+        .summaries
+        .lock()
+        .map_err(|e| StreamSlateError::StateLock(format!("Summaries: {e}")))
+}
@@ -0,0 +1,140 @@
+/*
+ * This file is part of StreamSlate.
+ * Copyright (C) 2025 StreamSlate Contributors
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+
+//! Page timing analytics commands
+//!
+//! A timing session starts when a document is opened and ends when it is
+//! closed, another document is opened, or `end_page_timing_session` is
+//! called; its per-page dwell times are then delivered to the configured
+//! webhook and/or CSV file.
+
+use super::obs::current_recording;
+use super::pdf::listen_page_changes;
+use crate::analytics::{self, AnalyticsConfig, PageTimer, SessionReport};
+use crate::error::{Result, StreamSlateError};
+use crate::settings::{load_settings, save_settings};
+use crate::state::AppState;
+use std::time::Instant;
+use tauri::{AppHandle, State};
+use tracing::{info, instrument};
+
+/// Current analytics destinations
+#[tauri::command]
+#[instrument(skip(state))]
+pub async fn get_analytics_config(state: State<'_, AppState>) -> Result<AnalyticsConfig> {
+    Ok(analytics_state(&state)?.config.clone())
+}
+
+/// Set where page timings are delivered and persist the choice
+///
+/// Clearing both destinations stops recording page timings.
+#[tauri::command]
+#[instrument(skip(app, state))]
+pub async fn set_analytics_config(
+    config: AnalyticsConfig,
+    app: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<()> {
+    config.validate()?;
+    save_settings(&app, analytics::CONFIG_FILE, &config)?;
+
+    let recording = current_recording(&state);
+    let mut analytics = analytics_state(&state)?;
+    if !config.is_enabled() {
+        analytics.session = None;
+    } else if analytics.session.is_none() {
+        // Start timing a document that is already open
+        let pdf_state = state.get_pdf_state()?;
+        if let Some(path) = pdf_state.current_file.filter(|_| pdf_state.is_loaded) {
+            let mut timer = PageTimer::start(&path, pdf_state.current_page, Instant::now());
+            timer.set_recording(recording);
+            analytics.session = Some(timer);
+        }
+    }
+    analytics.config = config;
+    info!("Analytics settings updated");
+    Ok(())
+}
+
+/// End the current timing session and deliver its report
+///
+/// Returns the report, or nothing if no session was running.
+#[tauri::command]
+#[instrument(skip(state))]
+pub async fn end_page_timing_session(state: State<'_, AppState>) -> Result<Option<SessionReport>> {
+    Ok(finish_page_timing(&state))
+}
+
+/// Start timing `document`, delivering any session still running
+pub(crate) fn start_page_timing(state: &AppState, document: &str) {
+    finish_page_timing(state);
+    let recording = current_recording(state);
+    if let Ok(mut analytics) = state.analytics.lock() {
+        if analytics.config.is_enabled() {
+            let mut timer = PageTimer::start(document, 1, Instant::now());
+            timer.set_recording(recording);
+            analytics.session = Some(timer);
+        }
+    }
+}
+
+/// Finish the running session, if any, and deliver its report
+pub(crate) fn finish_page_timing(state: &AppState) -> Option<SessionReport> {
+    let (config, timer) = {
+        let mut analytics = state.analytics.lock().ok()?;
+        let timer = analytics.session.take()?;
+        (analytics.config.clone(), timer)
+    };
+
+    let report = timer.finish(Instant::now());
+    info!(
+        document = %report.document,
+        pages = report.pages.len(),
+        "Page timing session ended"
+    );
+    analytics::deliver(&config, report.clone());
+    if let Ok(mut analytics) = state.analytics.lock() {
+        analytics.last_report = Some(report.clone());
+    }
+    Some(report)
+}
+
+/// Load stored settings and time pages on every page change
+pub fn spawn_page_timing_listener(app: &AppHandle, state: AppState) {
+    let config = load_settings(app, analytics::CONFIG_FILE);
+    if let Ok(mut analytics) = state.analytics.lock() {
+        analytics.config = config;
+    }
+
+    listen_page_changes(app, move |page| {
+        if let Ok(mut analytics) = state.analytics.lock() {
+            if let Some(timer) = analytics.session.as_mut() {
+                timer.enter_page(page, Instant::now());
+            }
+        }
+    });
+}
+
+fn analytics_state(
+    state: &AppState,
+) -> Result<std::sync::MutexGuard<'_, crate::state::AnalyticsState>> {
+    state
+        .analytics
+        .lock()
+        .map_err(|e| StreamSlateError::StateLock(format!("Analytics: {e}")))
+}
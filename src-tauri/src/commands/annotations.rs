@@ -18,15 +18,27 @@
 
 //! Annotation persistence commands
 //!
-//! Annotations are stored in JSON sidecar files alongside the PDF.
-//! For example, `document.pdf` would have annotations in `document.pdf.annotations.json`.
-
+//! Annotations are stored in JSON sidecar files keyed by the document's
+//! content; see [`super::sidecar`] for where they are kept.
+
+use super::ephemeral::mark_new_ephemeral;
+use super::ink::clean_new_strokes;
+use super::layers::Layer;
+use super::locking::{is_locked, keep_locked, restore_locked};
+use super::pdf::validate_output_path;
+use super::sections::Section;
+use super::shapes::AnnotationKind;
+use super::sidecar::{encode, read_recovering, save_sidecar, sidecar_path};
+use super::stamps::check_stamp_reference;
 use crate::error::{Result, StreamSlateError};
-use crate::state::AppState;
+use crate::pdf::media::MediaItem;
+use crate::pdf::text::page_origin;
+use crate::pdf::xfdf::{self, PageOrigin};
+use crate::state::{history, AppState};
+use crate::websocket::WebSocketEvent;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
-use std::path::PathBuf;
-use tauri::State;
+use std::collections::{BTreeMap, HashMap};
+use tauri::{AppHandle, Emitter, State};
 use tracing::{debug, info, instrument, warn};
 
 /// Annotation data structure matching the frontend type
@@ -35,7 +47,7 @@ use tracing::{debug, info, instrument, warn};
 pub struct Annotation {
     pub id: String,
     #[serde(rename = "type")]
-    pub annotation_type: String,
+    pub annotation_type: AnnotationKind,
     pub page_number: u32,
     pub x: f64,
     pub y: f64,
@@ -62,12 +74,77 @@ pub struct Annotation {
     /// Optional points for free-draw annotations
     #[serde(skip_serializing_if = "Option::is_none")]
     pub points: Option<Vec<Point>>,
+    /// Who drew the annotation; remote clients are stamped from `IDENTIFY`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub author: Option<String>,
+    /// ID of the layer the annotation is on, if any
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub layer: Option<String>,
+    /// Stored stamp drawn by `image` annotations
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub image: Option<String>,
+    /// Kept only until the document is closed, never written to the sidecar
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub ephemeral: bool,
+    /// Read-only until the page is unlocked
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub locked: bool,
 }
 
+/// A stroke sample; pen input also records how it was held
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
 pub struct Point {
     pub x: f64,
     pub y: f64,
+    /// Pen pressure from 0 to 1
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pressure: Option<f64>,
+    /// Pen tilt from -90 to 90 degrees, positive to the right
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tilt_x: Option<f64>,
+    /// Pen tilt from -90 to 90 degrees, positive towards the user
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tilt_y: Option<f64>,
+}
+
+impl Point {
+    /// A sample without pen data
+    pub fn new(x: f64, y: f64) -> Self {
+        Self {
+            x,
+            y,
+            pressure: None,
+            tilt_x: None,
+            tilt_y: None,
+        }
+    }
+
+    /// Whether the coordinates are finite and any pen data is in range
+    pub fn is_valid(&self) -> bool {
+        let within =
+            |value: Option<f64>, limit: f64| value.map_or(true, |v| (-limit..=limit).contains(&v));
+        self.x.is_finite()
+            && self.y.is_finite()
+            && self.pressure.map_or(true, |p| (0.0..=1.0).contains(&p))
+            && within(self.tilt_x, 90.0)
+            && within(self.tilt_y, 90.0)
+    }
+
+    /// The sample a fraction `t` of the way to `other`, pen data included
+    pub fn lerp(&self, other: &Point, t: f64) -> Point {
+        let mix = |a: Option<f64>, b: Option<f64>| match (a, b) {
+            (Some(a), Some(b)) => Some(a + (b - a) * t),
+            (a, b) => a.or(b),
+        };
+        Point {
+            x: self.x + (other.x - self.x) * t,
+            y: self.y + (other.y - self.y) * t,
+            pressure: mix(self.pressure, other.pressure),
+            tilt_x: mix(self.tilt_x, other.tilt_x),
+            tilt_y: mix(self.tilt_y, other.tilt_y),
+        }
+    }
 }
 
 /// Annotations file format
@@ -77,6 +154,18 @@ pub struct AnnotationsFile {
     pub version: u32,
     pub pdf_path: String,
     pub annotations: HashMap<u32, Vec<Annotation>>,
+    /// Named section markers, independent of PDF bookmarks
+    #[serde(default)]
+    pub sections: Vec<Section>,
+    /// Companion video clips played alongside pages
+    #[serde(default)]
+    pub media: Vec<MediaItem>,
+    /// Annotation layers and whether each is shown
+    #[serde(default)]
+    pub layers: Vec<Layer>,
+    /// Speaker notes by page
+    #[serde(default)]
+    pub notes: BTreeMap<u32, String>,
     pub created_at: String,
     pub updated_at: String,
 }
@@ -88,31 +177,60 @@ impl AnnotationsFile {
             version: 1,
             pdf_path: pdf_path.to_string(),
             annotations: HashMap::new(),
+            sections: Vec::new(),
+            media: Vec::new(),
+            layers: Vec::new(),
+            notes: BTreeMap::new(),
             created_at: now.clone(),
             updated_at: now,
         }
     }
 }
 
-/// Get the sidecar file path for annotations
-fn get_annotations_path(pdf_path: &str) -> PathBuf {
-    PathBuf::from(format!("{}.annotations.json", pdf_path))
+/// Read the sidecar for `pdf_path`, or start a new one if missing or unreadable
+pub(crate) fn read_sidecar(pdf_path: &str) -> Result<AnnotationsFile> {
+    let mut file = match read_recovering::<AnnotationsFile>(&sidecar_path(pdf_path)) {
+        Ok(Some(file)) => file,
+        Ok(None) => return Ok(AnnotationsFile::new(pdf_path)),
+        Err(StreamSlateError::Json(_)) => {
+            warn!("Failed to parse existing annotations file, creating new");
+            AnnotationsFile::new(pdf_path)
+        }
+        Err(e) => return Err(e),
+    };
+    // The document may have been renamed or moved since
+    file.pdf_path = pdf_path.to_string();
+    Ok(file)
+}
+
+/// Write the sidecar for `file.pdf_path`, stamping `updated_at`
+pub(crate) fn write_sidecar(file: &mut AnnotationsFile) -> Result<()> {
+    file.updated_at = chrono::Utc::now().to_rfc3339();
+
+    // Session-only annotations stay in memory
+    let mut stored = file.clone();
+    for list in stored.annotations.values_mut() {
+        list.retain(|a| !a.ephemeral);
+    }
+
+    // Write with pretty formatting for debugging
+    let json = serde_json::to_string_pretty(&stored)?;
+    save_sidecar(&file.pdf_path, &encode(json)?)
 }
 
 /// Save annotations to a JSON sidecar file
 #[tauri::command]
-#[instrument(skip(state))]
+#[instrument(skip(state, app))]
 pub async fn save_annotations(
     state: State<'_, AppState>,
-    annotations: HashMap<u32, Vec<Annotation>>,
+    app: AppHandle,
+    mut annotations: HashMap<u32, Vec<Annotation>>,
 ) -> Result<()> {
     let pdf_state = state.get_pdf_state()?;
 
-    let pdf_path = pdf_state
-        .current_file
-        .ok_or_else(|| StreamSlateError::InvalidPdf("No PDF is currently open".to_string()))?;
+    let pdf_path = pdf_state.current_file.ok_or(StreamSlateError::NoDocument)?;
 
-    let annotations_path = get_annotations_path(&pdf_path);
+    let annotations_path = sidecar_path(&pdf_path);
 
     info!(
         path = %annotations_path.display(),
@@ -120,34 +238,36 @@ pub async fn save_annotations(
         "Saving annotations"
     );
 
-    let now = chrono::Utc::now().to_rfc3339();
-
-    // Load existing file to preserve created_at, or create new
-    let mut file = if annotations_path.exists() {
-        let content = std::fs::read_to_string(&annotations_path)?;
-        serde_json::from_str::<AnnotationsFile>(&content).unwrap_or_else(|_| {
-            warn!("Failed to parse existing annotations file, creating new");
-            AnnotationsFile::new(&pdf_path)
-        })
-    } else {
-        AnnotationsFile::new(&pdf_path)
-    };
+    for annotation in annotations.values().flatten() {
+        check_stamp_reference(
+            &pdf_path,
+            Some(annotation.annotation_type),
+            annotation.image.as_deref(),
+        )?;
+    }
 
+    // Locked annotations stay as stored, new strokes are thinned out before
+    // they reach the sidecar, and new annotations are kept out of it in
+    // session-only mode
+    let mut cleaned_pages = restore_locked(&state, &mut annotations)?;
+    cleaned_pages.extend(clean_new_strokes(&state, &mut annotations)?);
+    cleaned_pages.extend(mark_new_ephemeral(&state, &mut annotations)?);
+    cleaned_pages.sort_unstable();
+    cleaned_pages.dedup();
+
+    // Load existing file to preserve created_at and sections, or create new
+    let mut file = read_sidecar(&pdf_path)?;
     file.annotations = annotations;
-    file.updated_at = now;
-
-    // Write with pretty formatting for debugging
-    let json = serde_json::to_string_pretty(&file)?;
-    std::fs::write(&annotations_path, json)?;
+    write_sidecar(&mut file)?;
 
     // Also store in app state for quick access
-    {
+    let (edits, cleaned) = {
         let mut state_annotations = state
             .annotations
             .write()
             .map_err(|e| StreamSlateError::StateLock(format!("Annotations: {e}")))?;
 
-        state_annotations.clear();
+        let previous = std::mem::take(&mut *state_annotations);
         for (page, page_annotations) in &file.annotations {
             let serialized: Vec<String> = page_annotations
                 .iter()
@@ -155,24 +275,35 @@ pub async fn save_annotations(
                 .collect();
             state_annotations.insert(*page, serialized);
         }
+
+        (
+            history::diff(&previous, &state_annotations),
+            page_updates(&state_annotations, cleaned_pages),
+        )
+    };
+    // Each stroke becomes its own undo step, even when a save batches several
+    for edit in &edits {
+        state.record_annotation_edits(vec![edit.clone()]);
     }
 
+    state.snapshot_annotations("save");
     debug!(path = %annotations_path.display(), "Annotations saved successfully");
 
-    // Broadcast update to all connected clients (Live Collaboration)
-    let mut broadcast_annotations = HashMap::new();
-    for (page, page_annotations) in &file.annotations {
-        let values: Vec<serde_json::Value> = page_annotations
-            .iter()
-            .filter_map(|a| serde_json::to_value(a).ok())
-            .collect();
-        broadcast_annotations.insert(*page, values);
+    // Broadcast only what changed to all connected clients (Live Collaboration)
+    let clock = state.annotation_clock();
+    for edit in &edits {
+        if let Err(e) = state.broadcast(edit_event(edit, clock)) {
+            warn!("Failed to broadcast annotation change: {}", e);
+        }
     }
-
-    if let Err(e) = state.broadcast(crate::websocket::WebSocketEvent::AnnotationsUpdated {
-        annotations: broadcast_annotations,
-    }) {
-        warn!("Failed to broadcast annotations update: {}", e);
+    // The webview still has the annotations as drawn
+    if !cleaned.is_empty() {
+        if let Err(e) = app.emit(
+            "annotations-updated",
+            serde_json::json!({ "annotations": cleaned }),
+        ) {
+            warn!("Failed to emit annotations-updated event: {}", e);
+        }
     }
 
     Ok(())
@@ -184,28 +315,23 @@ pub async fn save_annotations(
 pub async fn load_annotations(state: State<'_, AppState>) -> Result<HashMap<u32, Vec<Annotation>>> {
     let pdf_state = state.get_pdf_state()?;
 
-    let pdf_path = pdf_state
-        .current_file
-        .ok_or_else(|| StreamSlateError::InvalidPdf("No PDF is currently open".to_string()))?;
+    let pdf_path = pdf_state.current_file.ok_or(StreamSlateError::NoDocument)?;
 
-    let annotations_path = get_annotations_path(&pdf_path);
+    let annotations_path = sidecar_path(&pdf_path);
 
-    if !annotations_path.exists() {
+    // A damaged file falls back to the version before the last save
+    let Some(file) = read_recovering::<AnnotationsFile>(&annotations_path)? else {
         debug!(path = %annotations_path.display(), "No annotations file found");
         return Ok(HashMap::new());
-    }
-
+    };
     info!(path = %annotations_path.display(), "Loading annotations");
 
-    let content = std::fs::read_to_string(&annotations_path)?;
-    let file: AnnotationsFile = serde_json::from_str(&content).map_err(StreamSlateError::Json)?;
-
-    // Verify the annotations match the current PDF
+    // Sidecars follow the document's content, so this is a rename or move
     if file.pdf_path != pdf_path {
-        warn!(
-            expected = %pdf_path,
-            found = %file.pdf_path,
-            "Annotations file PDF path mismatch"
+        info!(
+            from = %file.pdf_path,
+            to = %pdf_path,
+            "Annotations followed the document"
         );
     }
 
@@ -226,6 +352,7 @@ pub async fn load_annotations(state: State<'_, AppState>) -> Result<HashMap<u32,
         }
     }
 
+    state.snapshot_annotations("load");
     debug!(
         count = file.annotations.values().map(|v| v.len()).sum::<usize>(),
         "Annotations loaded successfully"
@@ -234,12 +361,13 @@ pub async fn load_annotations(state: State<'_, AppState>) -> Result<HashMap<u32,
     Ok(file.annotations)
 }
 
-/// Get annotations for a specific page
+/// Get annotations for a specific page, optionally only those by `author`
 #[tauri::command]
 #[instrument(skip(state))]
 pub async fn get_page_annotations(
     state: State<'_, AppState>,
     page_number: u32,
+    author: Option<String>,
 ) -> Result<Vec<Annotation>> {
     let state_annotations = state
         .annotations
@@ -252,6 +380,7 @@ pub async fn get_page_annotations(
             serialized
                 .iter()
                 .filter_map(|s| serde_json::from_str::<Annotation>(s).ok())
+                .filter(|a| author.is_none() || a.author == author)
                 .collect()
         })
         .unwrap_or_default();
@@ -265,43 +394,423 @@ pub async fn get_page_annotations(
     Ok(annotations)
 }
 
-/// Delete all annotations for the current PDF
+/// Delete all annotations for the current PDF, except locked ones
 #[tauri::command]
-#[instrument(skip(state))]
-pub async fn clear_annotations(state: State<'_, AppState>) -> Result<()> {
+#[instrument(skip(state, app))]
+pub async fn clear_annotations(state: State<'_, AppState>, app: AppHandle) -> Result<()> {
     let pdf_state = state.get_pdf_state()?;
 
-    let pdf_path = pdf_state
-        .current_file
-        .ok_or_else(|| StreamSlateError::InvalidPdf("No PDF is currently open".to_string()))?;
+    let pdf_path = pdf_state.current_file.ok_or(StreamSlateError::NoDocument)?;
 
-    let annotations_path = get_annotations_path(&pdf_path);
-
-    if annotations_path.exists() {
-        info!(path = %annotations_path.display(), "Deleting annotations file");
-        std::fs::remove_file(&annotations_path)?;
-    }
+    let annotations_path = sidecar_path(&pdf_path);
 
     // Clear from state
-    {
+    let kept = {
         let mut state_annotations = state
             .annotations
             .write()
             .map_err(|e| StreamSlateError::StateLock(format!("Annotations: {e}")))?;
-        state_annotations.clear();
+        let previous = std::mem::take(&mut *state_annotations);
+        let pages = keep_locked(&previous, &mut state_annotations);
+        state.record_annotation_edits(history::diff(&previous, &state_annotations));
+        if !pages.is_empty() {
+            info!(path = %annotations_path.display(), pages = pages.len(), "Clearing annotations, keeping locked ones");
+            persist_annotations(&state, &state_annotations)?;
+        }
+        page_updates(&state_annotations, pages)
+    };
+
+    if kept.is_empty() && annotations_path.exists() {
+        // Keep the file if it still holds section markers, companion media
+        // or layers
+        let mut file = read_sidecar(&pdf_path)?;
+        if file.sections.is_empty() && file.media.is_empty() && file.layers.is_empty() {
+            info!(path = %annotations_path.display(), "Deleting annotations file");
+            std::fs::remove_file(&annotations_path)?;
+        } else {
+            info!(path = %annotations_path.display(), "Clearing annotations, keeping sections, media and layers");
+            file.annotations.clear();
+            write_sidecar(&mut file)?;
+        }
+    }
+    state.snapshot_annotations("clear");
+
+    // The webview cleared everything
+    if !kept.is_empty() {
+        publish_annotation_updates(&state, &app, &kept);
     }
 
     Ok(())
 }
 
+/// Undo or redo one annotation change and sync every view of it
+///
+/// Returns the full annotation list of each page the change touched.
+pub(crate) fn step_annotation_history(
+    state: &AppState,
+    app: &AppHandle,
+    redo: bool,
+) -> Result<HashMap<u32, Vec<serde_json::Value>>> {
+    let updates = {
+        let mut state_annotations = state
+            .annotations
+            .write()
+            .map_err(|e| StreamSlateError::StateLock(format!("Annotations: {e}")))?;
+        let mut history = state
+            .annotation_history
+            .lock()
+            .map_err(|e| StreamSlateError::StateLock(format!("Annotation history: {e}")))?;
+
+        let before = state_annotations.clone();
+        let pages = if redo {
+            history.redo(&mut state_annotations)
+        } else {
+            history.undo(&mut state_annotations)
+        }
+        .ok_or_else(|| {
+            StreamSlateError::Other(
+                if redo {
+                    "Nothing to redo"
+                } else {
+                    "Nothing to undo"
+                }
+                .into(),
+            )
+        })?;
+        // Locked annotations can't be undone or redone; put the operation
+        // back on its stack
+        if !keep_locked(&before, &mut state_annotations.clone()).is_empty() {
+            let mut scratch = state_annotations.clone();
+            if redo {
+                history.undo(&mut scratch);
+            } else {
+                history.redo(&mut scratch);
+            }
+            *state_annotations = before;
+            return Err(StreamSlateError::Other(
+                "The change touches locked annotations".to_string(),
+            ));
+        }
+        // Undoing is a new edit as far as merging and replay are concerned
+        let edits = history::diff(&before, &state_annotations);
+        if let Ok(mut crdt) = state.annotation_crdt.lock() {
+            crdt.record_local(&edits);
+        }
+        state.log_annotation_edits(&edits);
+        persist_annotations(state, &state_annotations)?;
+        page_updates(&state_annotations, pages)
+    };
+
+    debug!(redo, pages = updates.len(), "Stepped annotation history");
+    state.snapshot_annotations(if redo { "redo" } else { "undo" });
+    publish_annotation_updates(state, app, &updates);
+    Ok(updates)
+}
+
+/// Write the annotations to the open document's sidecar, if one is open
+pub(crate) fn persist_annotations(
+    state: &AppState,
+    annotations: &history::PageAnnotations,
+) -> Result<()> {
+    let Some(pdf_path) = state.get_pdf_state()?.current_file else {
+        return Ok(());
+    };
+    let mut file = read_sidecar(&pdf_path)?;
+    file.annotations = annotations
+        .iter()
+        .map(|(page, list)| {
+            let parsed = list
+                .iter()
+                .filter_map(|s| serde_json::from_str::<Annotation>(s).ok())
+                .collect();
+            (*page, parsed)
+        })
+        .collect();
+    write_sidecar(&mut file)
+}
+
+/// Full annotation lists of `pages`, empty for pages without annotations
+pub(crate) fn page_updates(
+    annotations: &history::PageAnnotations,
+    pages: impl IntoIterator<Item = u32>,
+) -> HashMap<u32, Vec<serde_json::Value>> {
+    pages
+        .into_iter()
+        .map(|page| {
+            let values = annotations
+                .get(&page)
+                .map(|list| {
+                    list.iter()
+                        .filter_map(|s| serde_json::from_str(s).ok())
+                        .collect()
+                })
+                .unwrap_or_default();
+            (page, values)
+        })
+        .collect()
+}
+
+/// The single-annotation event announcing `edit`
+pub(crate) fn edit_event(edit: &history::AnnotationEdit, clock: u64) -> WebSocketEvent {
+    let value =
+        |annotation: &String| serde_json::from_str(annotation).unwrap_or(serde_json::Value::Null);
+    match (&edit.before, &edit.after) {
+        (None, Some(after)) => WebSocketEvent::AnnotationAdded {
+            page: edit.page,
+            annotation: value(after),
+            clock,
+        },
+        (_, Some(after)) => WebSocketEvent::AnnotationUpdated {
+            page: edit.page,
+            annotation: value(after),
+            clock,
+        },
+        (_, None) => WebSocketEvent::AnnotationDeleted {
+            page: edit.page,
+            id: edit.id.clone(),
+            clock,
+        },
+    }
+}
+
+/// Push replaced page annotations to the webview and remote clients
+pub(crate) fn publish_annotation_updates(
+    state: &AppState,
+    app: &AppHandle,
+    updates: &HashMap<u32, Vec<serde_json::Value>>,
+) {
+    if let Err(e) = app.emit(
+        "annotations-updated",
+        serde_json::json!({ "annotations": updates }),
+    ) {
+        warn!("Failed to emit annotations-updated event: {}", e);
+    }
+    if let Err(e) = state.broadcast(WebSocketEvent::AnnotationsUpdated {
+        annotations: updates.clone(),
+    }) {
+        warn!("Failed to broadcast annotations update: {}", e);
+    }
+}
+
+/// Revert the most recent annotation change
+#[tauri::command]
+#[instrument(skip(state, app))]
+pub async fn undo_annotation(
+    state: State<'_, AppState>,
+    app: AppHandle,
+) -> Result<HashMap<u32, Vec<serde_json::Value>>> {
+    step_annotation_history(&state, &app, false)
+}
+
+/// Reapply the most recently undone annotation change
+#[tauri::command]
+#[instrument(skip(state, app))]
+pub async fn redo_annotation(
+    state: State<'_, AppState>,
+    app: AppHandle,
+) -> Result<HashMap<u32, Vec<serde_json::Value>>> {
+    step_annotation_history(&state, &app, true)
+}
+
+/// Restore points of the open document's annotations, newest first
+#[tauri::command]
+#[instrument(skip(state))]
+pub async fn list_annotation_versions(
+    state: State<'_, AppState>,
+) -> Result<Vec<history::AnnotationVersion>> {
+    Ok(state
+        .annotation_versions
+        .lock()
+        .map_err(|e| StreamSlateError::StateLock(format!("Annotation versions: {e}")))?
+        .list())
+}
+
+/// Bring back the annotations of a restore point
+///
+/// The restore itself can be undone. Returns the full annotation list of
+/// each page that changed. Locked annotations stay as they are.
+#[tauri::command]
+#[instrument(skip(state, app))]
+pub async fn restore_annotation_version(
+    id: u64,
+    state: State<'_, AppState>,
+    app: AppHandle,
+) -> Result<HashMap<u32, Vec<serde_json::Value>>> {
+    let mut snapshot = state
+        .annotation_versions
+        .lock()
+        .map_err(|e| StreamSlateError::StateLock(format!("Annotation versions: {e}")))?
+        .get(id)
+        .cloned()
+        .ok_or_else(|| StreamSlateError::Other(format!("No annotation version {id}")))?;
+
+    let updates = {
+        let mut state_annotations = state
+            .annotations
+            .write()
+            .map_err(|e| StreamSlateError::StateLock(format!("Annotations: {e}")))?;
+        keep_locked(&state_annotations, &mut snapshot);
+        let edits = history::diff(&state_annotations, &snapshot);
+        let mut pages: Vec<u32> = edits.iter().map(|edit| edit.page).collect();
+        pages.dedup();
+        *state_annotations = snapshot;
+        state.record_annotation_edits(edits);
+        persist_annotations(&state, &state_annotations)?;
+        page_updates(&state_annotations, pages)
+    };
+
+    info!(id, pages = updates.len(), "Restored annotation version");
+    state.snapshot_annotations("restore");
+    publish_annotation_updates(&state, &app, &updates);
+    Ok(updates)
+}
+
 /// Check if annotations exist for a PDF
 #[tauri::command]
 #[instrument]
 pub async fn has_annotations(pdf_path: String) -> Result<bool> {
-    let annotations_path = get_annotations_path(&pdf_path);
+    let annotations_path = sidecar_path(&pdf_path);
     Ok(annotations_path.exists())
 }
 
+/// Export the open PDF's sidecar annotations to an XFDF file
+///
+/// The file references the PDF by name and can be imported into Acrobat
+/// and other viewers. Returns the number of annotations in the sidecar.
+#[tauri::command]
+#[instrument(skip(state))]
+pub async fn export_annotations_xfdf(
+    output_path: String,
+    state: State<'_, AppState>,
+) -> Result<usize> {
+    let pdf_path = state
+        .get_pdf_state()?
+        .current_file
+        .ok_or(StreamSlateError::NoDocument)?;
+    let output = validate_output_path(&output_path, Some(&pdf_path))?;
+
+    let file = read_sidecar(&pdf_path)?;
+    let origins = page_origins(&state)?;
+
+    let href = std::path::Path::new(&pdf_path)
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned());
+    let xfdf = xfdf::write_xfdf(&file.annotations, &origins, href.as_deref());
+    std::fs::write(&output, xfdf)?;
+
+    let count = file.annotations.values().map(Vec::len).sum();
+    info!(path = %output.display(), count, "Exported annotations to XFDF");
+    Ok(count)
+}
+
+/// Outcome of an annotation import
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AnnotationImport {
+    /// Annotations added or replaced
+    pub imported: usize,
+    /// Entries with no sidecar equivalent or outside the document
+    pub skipped: usize,
+}
+
+/// Import annotations from an XFDF or FDF file into the open PDF
+///
+/// Entries replace sidecar annotations with the same id and are added
+/// otherwise; those for locked annotations are skipped. The whole import is
+/// a single undo step.
+#[tauri::command]
+#[instrument(skip(app, state))]
+pub async fn import_annotations(
+    path: String,
+    app: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<AnnotationImport> {
+    if state.get_pdf_state()?.current_file.is_none() {
+        return Err(StreamSlateError::NoDocument);
+    }
+    let bytes = std::fs::read(&path)?;
+
+    let origins = page_origins(&state)?;
+    let parsed = if bytes.starts_with(b"%FDF") {
+        xfdf::read_fdf(&bytes, &origins)?
+    } else {
+        let text = String::from_utf8(bytes)
+            .map_err(|_| StreamSlateError::Other("XFDF file is not valid UTF-8".to_string()))?;
+        xfdf::read_xfdf(&text, &origins)?
+    };
+
+    let mut skipped = parsed.skipped;
+    let mut imported = 0;
+    let updates = {
+        let mut state_annotations = state
+            .annotations
+            .write()
+            .map_err(|e| StreamSlateError::StateLock(format!("Annotations: {e}")))?;
+        let previous = state_annotations.clone();
+
+        for annotation in parsed.annotations {
+            if !origins.is_empty() && !origins.contains_key(&annotation.page_number) {
+                skipped += 1;
+                continue;
+            }
+            let locked = previous
+                .values()
+                .flatten()
+                .any(|s| is_locked(s) && history::annotation_id(s) == annotation.id);
+            if locked {
+                skipped += 1;
+                continue;
+            }
+            let Ok(serialized) = serde_json::to_string(&annotation) else {
+                skipped += 1;
+                continue;
+            };
+            // An id moved to another page is dropped from its old one
+            for (page, list) in state_annotations.iter_mut() {
+                if *page != annotation.page_number {
+                    list.retain(|s| history::annotation_id(s) != annotation.id);
+                }
+            }
+            let page = state_annotations.entry(annotation.page_number).or_default();
+            match page
+                .iter()
+                .position(|s| history::annotation_id(s) == annotation.id)
+            {
+                Some(index) => page[index] = serialized,
+                None => page.push(serialized),
+            }
+            imported += 1;
+        }
+
+        let edits = history::diff(&previous, &state_annotations);
+        let mut pages: Vec<u32> = edits.iter().map(|edit| edit.page).collect();
+        pages.dedup();
+        state.record_annotation_edits(edits);
+        persist_annotations(&state, &state_annotations)?;
+        page_updates(&state_annotations, pages)
+    };
+
+    info!(path = %path, imported, skipped, "Imported annotations");
+    state.snapshot_annotations("import");
+    publish_annotation_updates(&state, &app, &updates);
+    Ok(AnnotationImport { imported, skipped })
+}
+
+/// Origin of each page of the open document, empty if none is loaded
+pub(crate) fn page_origins(state: &AppState) -> Result<HashMap<u32, PageOrigin>> {
+    Ok(match state.get_pdf_document()? {
+        Some(document) => document
+            .get_pages()
+            .into_iter()
+            .map(|(page, page_id)| {
+                let (left, top) = page_origin(&document, page_id);
+                (page, PageOrigin { left, top })
+            })
+            .collect(),
+        None => HashMap::new(),
+    })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -310,7 +819,7 @@ mod tests {
     fn test_annotation_serialization() {
         let annotation = Annotation {
             id: "test-123".to_string(),
-            annotation_type: "highlight".to_string(),
+            annotation_type: AnnotationKind::Highlight,
             page_number: 1,
             x: 100.0,
             y: 200.0,
@@ -327,6 +836,11 @@ mod tests {
             modified: "2025-01-01T00:00:00Z".to_string(),
             visible: true,
             points: None,
+            author: None,
+            layer: None,
+            image: None,
+            ephemeral: false,
+            locked: false,
         };
 
         let json = serde_json::to_string(&annotation).unwrap();
@@ -334,6 +848,35 @@ mod tests {
         assert!(json.contains("pageNumber"));
     }
 
+    #[test]
+    fn test_point_pen_data() {
+        // Points saved before pen data still load
+        let plain: Point = serde_json::from_str(r#"{"x":1,"y":2}"#).unwrap();
+        assert!(plain.pressure.is_none() && plain.is_valid());
+        assert_eq!(
+            serde_json::to_string(&plain).unwrap(),
+            r#"{"x":1.0,"y":2.0}"#
+        );
+
+        let pen: Point =
+            serde_json::from_str(r#"{"x":1,"y":2,"pressure":0.5,"tiltX":-30}"#).unwrap();
+        assert_eq!(
+            serde_json::to_value(&pen).unwrap(),
+            serde_json::json!({ "x": 1.0, "y": 2.0, "pressure": 0.5, "tiltX": -30.0 })
+        );
+
+        let mut end = Point::new(3.0, 2.0);
+        end.pressure = Some(1.0);
+        let middle = pen.lerp(&end, 0.5);
+        assert_eq!(
+            (middle.x, middle.pressure, middle.tilt_x),
+            (2.0, Some(0.75), Some(-30.0))
+        );
+
+        end.pressure = Some(1.5);
+        assert!(!end.is_valid());
+    }
+
     #[test]
     fn test_annotations_file_new() {
         let file = AnnotationsFile::new("/path/to/test.pdf");
@@ -341,4 +884,41 @@ mod tests {
         assert_eq!(file.pdf_path, "/path/to/test.pdf");
         assert!(file.annotations.is_empty());
     }
+
+    #[test]
+    fn test_ephemeral_not_written() {
+        let dir =
+            std::env::temp_dir().join(format!("streamslate-session-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let pdf_path = dir.join("deck.pdf").to_string_lossy().into_owned();
+        let annotation = |id: &str, ephemeral: bool| -> Annotation {
+            serde_json::from_value(serde_json::json!({
+                "id": id, "type": "rectangle", "pageNumber": 1, "x": 0, "y": 0,
+                "width": 10, "height": 10, "content": "", "color": "#000000",
+                "opacity": 1, "created": "", "modified": "", "visible": true,
+                "ephemeral": ephemeral,
+            }))
+            .unwrap()
+        };
+
+        let mut file = AnnotationsFile::new(&pdf_path);
+        file.annotations.insert(
+            1,
+            vec![annotation("kept", false), annotation("scribble", true)],
+        );
+        write_sidecar(&mut file).unwrap();
+        assert_eq!(file.annotations[&1].len(), 2);
+
+        let stored = read_sidecar(&pdf_path).unwrap();
+        let ids: Vec<_> = stored.annotations[&1]
+            .iter()
+            .map(|a| a.id.as_str())
+            .collect();
+        assert_eq!(ids, ["kept"]);
+        assert!(!serde_json::to_string(&stored)
+            .unwrap()
+            .contains("ephemeral"));
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
 }
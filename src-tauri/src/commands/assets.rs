@@ -0,0 +1,213 @@
+/*
+ * This file is part of StreamSlate.
+ * Copyright (C) 2025 StreamSlate Contributors
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+
+//! Linked media preloading commands
+//!
+//! `preload_assets` downloads every video and image the open deck links to
+//! into the asset cache, reporting each step as an `asset-preload-progress`
+//! event. `get_cached_asset` then hands the webview the local copy.
+
+use crate::assets::AssetCache;
+use crate::error::{Result, StreamSlateError};
+use crate::pdf::links::{self, AssetKind, LinkedAsset};
+use crate::state::AppState;
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Emitter, State};
+use tracing::{info, instrument, warn};
+
+/// Emit download progress at most this often (in percent of the asset)
+const PROGRESS_STEP: u64 = 5;
+
+/// Stage of a single asset's preload
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AssetPreloadStatus {
+    Downloading,
+    Cached,
+    Downloaded,
+    Failed,
+}
+
+/// Payload of the `asset-preload-progress` event
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AssetPreloadProgress {
+    pub url: String,
+    /// Position of this asset in the preload (0-based)
+    pub index: usize,
+    pub total: usize,
+    pub status: AssetPreloadStatus,
+    pub bytes: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub total_bytes: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// Outcome of preloading one asset
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PreloadedAsset {
+    pub url: String,
+    pub page: u32,
+    pub kind: AssetKind,
+    /// Local copy, if the asset is now cached
+    pub path: Option<String>,
+    pub error: Option<String>,
+}
+
+/// Summary returned by `preload_assets`
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PreloadReport {
+    pub assets: Vec<PreloadedAsset>,
+    pub downloaded: usize,
+    pub cached: usize,
+    pub failed: usize,
+}
+
+/// Media the open document links to
+#[tauri::command]
+#[instrument(skip(state))]
+pub async fn list_linked_assets(state: State<'_, AppState>) -> Result<Vec<LinkedAsset>> {
+    open_document_assets(&state)
+}
+
+/// Download all media the open document links to into the asset cache
+///
+/// Assets already in the cache are not fetched again. A failed download is
+/// reported and skipped; it does not stop the remaining assets.
+#[tauri::command]
+#[instrument(skip(app, state))]
+pub async fn preload_assets(app: AppHandle, state: State<'_, AppState>) -> Result<PreloadReport> {
+    let assets = open_document_assets(&state)?;
+    let cache = AssetCache::for_app(&app)?;
+    let total = assets.len();
+    let mut report = PreloadReport::default();
+
+    for (index, asset) in assets.into_iter().enumerate() {
+        let progress = |status, bytes, total_bytes, error| AssetPreloadProgress {
+            url: asset.url.clone(),
+            index,
+            total,
+            status,
+            bytes,
+            total_bytes,
+            error,
+        };
+
+        let outcome = match cache.cached(&asset.url) {
+            Some(path) => {
+                let bytes = std::fs::metadata(&path).map(|m| m.len()).unwrap_or(0);
+                emit_progress(
+                    &app,
+                    progress(AssetPreloadStatus::Cached, bytes, None, None),
+                );
+                report.cached += 1;
+                Ok(path)
+            }
+            None => {
+                emit_progress(
+                    &app,
+                    progress(AssetPreloadStatus::Downloading, 0, None, None),
+                );
+                let mut last_step = 0;
+                let fetched = cache
+                    .fetch(&asset.url, |bytes, total_bytes| {
+                        let step = total_bytes
+                            .filter(|len| *len > 0)
+                            .map(|len| bytes * 100 / len / PROGRESS_STEP)
+                            .unwrap_or(bytes / (8 * 1024 * 1024));
+                        if step > last_step {
+                            last_step = step;
+                            emit_progress(
+                                &app,
+                                progress(AssetPreloadStatus::Downloading, bytes, total_bytes, None),
+                            );
+                        }
+                    })
+                    .await;
+                match fetched {
+                    Ok(bytes) => {
+                        emit_progress(
+                            &app,
+                            progress(AssetPreloadStatus::Downloaded, bytes, Some(bytes), None),
+                        );
+                        report.downloaded += 1;
+                        Ok(cache.path_for(&asset.url))
+                    }
+                    Err(e) => {
+                        warn!(url = %asset.url, error = %e, "Failed to preload asset");
+                        emit_progress(
+                            &app,
+                            progress(AssetPreloadStatus::Failed, 0, None, Some(e.to_string())),
+                        );
+                        report.failed += 1;
+                        Err(e)
+                    }
+                }
+            }
+        };
+
+        let (path, error) = match outcome {
+            Ok(path) => (Some(path.to_string_lossy().to_string()), None),
+            Err(e) => (None, Some(e.to_string())),
+        };
+        report.assets.push(PreloadedAsset {
+            url: asset.url,
+            page: asset.page,
+            kind: asset.kind,
+            path,
+            error,
+        });
+    }
+
+    info!(
+        total,
+        downloaded = report.downloaded,
+        cached = report.cached,
+        failed = report.failed,
+        "Asset preload finished"
+    );
+    Ok(report)
+}
+
+/// Local copy of a preloaded asset, if there is one
+#[tauri::command]
+#[instrument(skip(app))]
+pub async fn get_cached_asset(url: String, app: AppHandle) -> Result<Option<String>> {
+    Ok(AssetCache::for_app(&app)?
+        .cached(&url)
+        .map(|path| path.to_string_lossy().to_string()))
+}
+
+fn open_document_assets(state: &AppState) -> Result<Vec<LinkedAsset>> {
+    Ok(state
+        .pdf_document
+        .read()
+        .map_err(|e| StreamSlateError::StateLock(format!("PDF document: {e}")))?
+        .as_ref()
+        .map(links::linked_assets)
+        .unwrap_or_default())
+}
+
+fn emit_progress(app: &AppHandle, progress: AssetPreloadProgress) {
+    if let Err(e) = app.emit("asset-preload-progress", &progress) {
+        warn!(error = %e, "Failed to emit asset preload progress");
+    }
+}
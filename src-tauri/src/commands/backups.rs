@@ -0,0 +1,431 @@
+/*
+ * This file is part of StreamSlate.
+ * Copyright (C) 2025 StreamSlate Contributors
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+
+//! Scheduled annotation backups
+//!
+//! Every so often the sidecar store (see [`super::sidecar`]) is copied to a
+//! timestamped folder in `annotation-backups` in the app data directory,
+//! unless nothing changed since the last copy, and only the newest copies
+//! are kept. Sidecars kept next to their documents aren't included.
+
+use super::annotations::{
+    page_updates, persist_annotations, publish_annotation_updates, read_sidecar,
+};
+use super::locking::keep_locked;
+use super::sidecar::{store_dir, write_atomic};
+use crate::error::{Result, StreamSlateError};
+use crate::settings::{load_settings, save_settings, Settings};
+use crate::state::history::{self, PageAnnotations};
+use crate::state::AppState;
+use chrono::{DateTime, NaiveDateTime, SubsecRound, Utc};
+use serde::{Deserialize, Serialize};
+use std::ops::RangeInclusive;
+use std::path::{Path, PathBuf};
+use std::sync::{OnceLock, RwLock};
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Manager, State};
+use tracing::{debug, info, instrument, warn};
+
+/// Folder of the backups inside the app data directory
+const BACKUP_FOLDER: &str = "annotation-backups";
+
+/// File name of the settings inside the app data directory
+pub const CONFIG_FILE: &str = "backups.json";
+
+/// Name of a backup folder, and its ID
+const ID_FORMAT: &str = "%Y%m%d-%H%M%S%.3f";
+
+/// How often the schedule is checked
+const CHECK_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Accepted minutes between backups
+pub const INTERVAL_RANGE: RangeInclusive<u32> = 1..=1440;
+
+/// Accepted numbers of backups to keep
+pub const KEEP_RANGE: RangeInclusive<usize> = 1..=100;
+
+/// Backup directory, set once during app setup
+static BACKUP_DIR: OnceLock<PathBuf> = OnceLock::new();
+
+/// The backup schedule
+static CONFIG: OnceLock<RwLock<BackupConfig>> = OnceLock::new();
+
+/// When and how many annotation backups are made
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BackupConfig {
+    pub enabled: bool,
+    /// Minutes between backups
+    pub interval_minutes: u32,
+    /// Backups kept; older ones are deleted
+    pub keep: usize,
+}
+
+impl Default for BackupConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            interval_minutes: 30,
+            keep: 10,
+        }
+    }
+}
+
+impl BackupConfig {
+    /// Check the interval and count are usable
+    pub fn validate(&self) -> Result<()> {
+        if !INTERVAL_RANGE.contains(&self.interval_minutes) {
+            return Err(StreamSlateError::Other(format!(
+                "Backup interval must be between {} and {} minutes",
+                INTERVAL_RANGE.start(),
+                INTERVAL_RANGE.end()
+            )));
+        }
+        if !KEEP_RANGE.contains(&self.keep) {
+            return Err(StreamSlateError::Other(format!(
+                "Backups kept must be between {} and {}",
+                KEEP_RANGE.start(),
+                KEEP_RANGE.end()
+            )));
+        }
+        Ok(())
+    }
+}
+
+impl Settings for BackupConfig {
+    fn check(&self) -> Result<()> {
+        self.validate()
+    }
+}
+
+/// A copy of the sidecar store
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AnnotationBackup {
+    pub id: String,
+    /// When the backup was made, RFC 3339
+    pub created: String,
+    /// Sidecars in the backup
+    pub files: usize,
+    pub bytes: u64,
+}
+
+/// The backup schedule
+#[tauri::command]
+#[instrument]
+pub async fn get_backup_config() -> Result<BackupConfig> {
+    Ok(*schedule()
+        .read()
+        .map_err(|e| StreamSlateError::StateLock(format!("Backup config: {e}")))?)
+}
+
+/// Change the backup schedule, deleting backups beyond the new count
+#[tauri::command]
+#[instrument(skip(app))]
+pub async fn set_backup_config(config: BackupConfig, app: AppHandle) -> Result<()> {
+    config.validate()?;
+    save_settings(&app, CONFIG_FILE, &config)?;
+    *schedule()
+        .write()
+        .map_err(|e| StreamSlateError::StateLock(format!("Backup config: {e}")))? = config;
+    if let Some(dir) = BACKUP_DIR.get() {
+        prune(dir, config.keep)?;
+    }
+    info!(
+        enabled = config.enabled,
+        interval = config.interval_minutes,
+        keep = config.keep,
+        "Backup schedule updated"
+    );
+    Ok(())
+}
+
+/// The annotation backups, newest first
+#[tauri::command]
+#[instrument]
+pub async fn list_annotation_backups() -> Result<Vec<AnnotationBackup>> {
+    Ok(BACKUP_DIR
+        .get()
+        .map(|dir| list_backups(dir))
+        .unwrap_or_default())
+}
+
+/// Put the sidecars of backup `id` back into the store, returning how many
+/// were restored
+///
+/// The store is backed up first, so the restore can itself be reverted.
+/// Sidecars made since the backup are kept. The open document's annotations
+/// are reloaded; locked ones stay as they are.
+#[tauri::command]
+#[instrument(skip(state, app))]
+pub async fn restore_annotation_backup(
+    id: String,
+    state: State<'_, AppState>,
+    app: AppHandle,
+) -> Result<usize> {
+    let (Some(store), Some(dir)) = (store_dir(), BACKUP_DIR.get()) else {
+        return Err(StreamSlateError::Other(
+            "No app data directory for backups".to_string(),
+        ));
+    };
+    if !list_backups(dir).iter().any(|backup| backup.id == id) {
+        return Err(StreamSlateError::Other(format!(
+            "No annotation backup {id}"
+        )));
+    }
+    create_backup(store, dir, Utc::now())?;
+    let restored = restore_backup(store, &dir.join(&id))?;
+    info!(id = %id, restored, "Restored annotation backup");
+
+    reload_open_annotations(&state, &app)?;
+    Ok(restored)
+}
+
+/// Restore the backup schedule and start making backups
+pub fn spawn_annotation_backups(app_handle: &AppHandle) {
+    match app_handle.path().app_data_dir() {
+        Ok(dir) => {
+            let _ = BACKUP_DIR.set(dir.join(BACKUP_FOLDER));
+        }
+        Err(e) => {
+            warn!(error = %e, "No app data directory; annotations won't be backed up");
+            return;
+        }
+    }
+    if let Ok(mut config) = schedule().write() {
+        *config = load_settings(app_handle, CONFIG_FILE);
+    }
+
+    tauri::async_runtime::spawn(async move {
+        let mut last = Instant::now();
+        loop {
+            tokio::time::sleep(CHECK_INTERVAL).await;
+            let Ok(current) = schedule().read().map(|config| *config) else {
+                continue;
+            };
+            let interval = Duration::from_secs(u64::from(current.interval_minutes) * 60);
+            if !current.enabled || last.elapsed() < interval {
+                continue;
+            }
+            last = Instant::now();
+            run_backup(current.keep);
+        }
+    });
+}
+
+fn run_backup(keep: usize) {
+    let (Some(store), Some(dir)) = (store_dir(), BACKUP_DIR.get()) else {
+        return;
+    };
+    match create_backup(store, dir, Utc::now()) {
+        Ok(Some(id)) => info!(id = %id, "Backed up annotations"),
+        Ok(None) => debug!("Annotations unchanged since the last backup"),
+        Err(e) => warn!(error = %e, "Failed to back up annotations"),
+    }
+    if let Err(e) = prune(dir, keep) {
+        warn!(error = %e, "Failed to delete old annotation backups");
+    }
+}
+
+/// Load the open document's restored sidecar into the app state
+fn reload_open_annotations(state: &AppState, app: &AppHandle) -> Result<()> {
+    let Some(pdf_path) = state.get_pdf_state()?.current_file else {
+        return Ok(());
+    };
+    let file = read_sidecar(&pdf_path)?;
+
+    let updates = {
+        let mut state_annotations = state
+            .annotations
+            .write()
+            .map_err(|e| StreamSlateError::StateLock(format!("Annotations: {e}")))?;
+        let mut restored: PageAnnotations = file
+            .annotations
+            .iter()
+            .map(|(page, list)| {
+                let serialized = list
+                    .iter()
+                    .filter_map(|a| serde_json::to_string(a).ok())
+                    .collect();
+                (*page, serialized)
+            })
+            .collect();
+        if !keep_locked(&state_annotations, &mut restored).is_empty() {
+            persist_annotations(state, &restored)?;
+        }
+        let edits = history::diff(&state_annotations, &restored);
+        let mut pages: Vec<u32> = edits.iter().map(|edit| edit.page).collect();
+        pages.dedup();
+        *state_annotations = restored;
+        state.record_annotation_edits(edits);
+        page_updates(&state_annotations, pages)
+    };
+
+    if !updates.is_empty() {
+        state.snapshot_annotations("restore backup");
+        publish_annotation_updates(state, app, &updates);
+    }
+    Ok(())
+}
+
+/// Copy the sidecars in `store` to a new backup in `dir`, returning its ID,
+/// or `None` if there are none or none changed since the newest backup
+pub fn create_backup(store: &Path, dir: &Path, now: DateTime<Utc>) -> Result<Option<String>> {
+    let sidecars = sidecars(store);
+    let Some(changed) = sidecars
+        .iter()
+        .filter_map(|path| std::fs::metadata(path).and_then(|m| m.modified()).ok())
+        .max()
+    else {
+        return Ok(None);
+    };
+    let newest = list_backups(dir)
+        .first()
+        .and_then(|backup| backup_time(&backup.id));
+    // Backup IDs are to the millisecond
+    let changed = DateTime::<Utc>::from(changed).trunc_subsecs(3);
+    if newest.is_some_and(|newest| changed <= newest) {
+        return Ok(None);
+    }
+
+    // Copied under another name first, so a partial backup is never listed
+    let id = now.format(ID_FORMAT).to_string();
+    let partial = dir.join(format!("{id}.partial"));
+    std::fs::create_dir_all(&partial)?;
+    for path in &sidecars {
+        if let Some(name) = path.file_name() {
+            std::fs::copy(path, partial.join(name))?;
+        }
+    }
+    std::fs::rename(&partial, dir.join(&id))?;
+    Ok(Some(id))
+}
+
+/// The backups in `dir`, newest first
+pub fn list_backups(dir: &Path) -> Vec<AnnotationBackup> {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    let mut backups: Vec<AnnotationBackup> = entries
+        .filter_map(|entry| entry.ok())
+        .filter_map(|entry| {
+            let id = entry.file_name().to_str()?.to_string();
+            let created = backup_time(&id)?.to_rfc3339();
+            let sidecars = sidecars(&entry.path());
+            Some(AnnotationBackup {
+                id,
+                created,
+                files: sidecars.len(),
+                bytes: sidecars
+                    .iter()
+                    .filter_map(|path| std::fs::metadata(path).ok())
+                    .map(|meta| meta.len())
+                    .sum(),
+            })
+        })
+        .collect();
+    backups.sort_by(|a, b| b.id.cmp(&a.id));
+    backups
+}
+
+/// Delete all but the newest `keep` backups in `dir`, returning how many
+/// were deleted
+pub fn prune(dir: &Path, keep: usize) -> Result<usize> {
+    let old = list_backups(dir).into_iter().skip(keep);
+    let mut deleted = 0;
+    for backup in old {
+        std::fs::remove_dir_all(dir.join(&backup.id))?;
+        deleted += 1;
+    }
+    Ok(deleted)
+}
+
+/// Copy the sidecars of `backup` into `store`, returning how many
+pub fn restore_backup(store: &Path, backup: &Path) -> Result<usize> {
+    let sidecars = sidecars(backup);
+    for path in &sidecars {
+        if let Some(name) = path.file_name() {
+            write_atomic(&store.join(name), &std::fs::read(path)?)?;
+        }
+    }
+    Ok(sidecars.len())
+}
+
+/// When the backup with `id` was made
+fn backup_time(id: &str) -> Option<DateTime<Utc>> {
+    NaiveDateTime::parse_from_str(id, ID_FORMAT)
+        .ok()
+        .map(|time| time.and_utc())
+}
+
+/// The sidecars in `dir`, without their backups and temporary files
+fn sidecars(dir: &Path) -> Vec<PathBuf> {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    entries
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .filter(|path| path.is_file() && path.extension().is_some_and(|ext| ext == "json"))
+        .collect()
+}
+
+fn schedule() -> &'static RwLock<BackupConfig> {
+    CONFIG.get_or_init(|| RwLock::new(BackupConfig::default()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_backup_and_restore() {
+        let root =
+            std::env::temp_dir().join(format!("streamslate-backups-{}", uuid::Uuid::new_v4()));
+        let (store, dir) = (root.join("annotations"), root.join(BACKUP_FOLDER));
+        std::fs::create_dir_all(&store).unwrap();
+        assert_eq!(create_backup(&store, &dir, Utc::now()).unwrap(), None);
+
+        std::fs::write(store.join("a.json"), "1").unwrap();
+        std::fs::write(store.join("a.json.bak"), "0").unwrap();
+        let hour = chrono::Duration::hours(1);
+        let first = create_backup(&store, &dir, Utc::now() - hour)
+            .unwrap()
+            .unwrap();
+        let second = create_backup(&store, &dir, Utc::now()).unwrap().unwrap();
+        // Nothing changed since the second
+        assert_eq!(
+            create_backup(&store, &dir, Utc::now() + hour).unwrap(),
+            None
+        );
+
+        let backups = list_backups(&dir);
+        let ids: Vec<_> = backups.iter().map(|b| b.id.as_str()).collect();
+        assert_eq!(ids, [second.as_str(), first.as_str()]);
+        assert_eq!((backups[0].files, backups[0].bytes), (1, 1));
+
+        std::fs::write(store.join("a.json"), "2").unwrap();
+        assert_eq!(restore_backup(&store, &dir.join(&first)).unwrap(), 1);
+        assert_eq!(std::fs::read_to_string(store.join("a.json")).unwrap(), "1");
+
+        assert_eq!(prune(&dir, 1).unwrap(), 1);
+        assert_eq!(list_backups(&dir).len(), 1);
+
+        std::fs::remove_dir_all(root).unwrap();
+    }
+}
@@ -0,0 +1,240 @@
+/*
+ * This file is part of StreamSlate.
+ * Copyright (C) 2025 StreamSlate Contributors
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+
+//! Attendance checkpoint commands
+//!
+//! `start_checkpoint` shows a code word on every output; viewers answer with
+//! `POST /checkpoint` on the local HTTP endpoint until the checkpoint is
+//! closed. Results are saved per app session after every change.
+
+use crate::checkpoint::{self, Checkpoint, CheckpointSession};
+use crate::error::{Result, StreamSlateError};
+use crate::state::AppState;
+use crate::websocket::WebSocketEvent;
+use serde::Serialize;
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, State};
+use tracing::{info, instrument, warn};
+
+/// Longest a checkpoint may stay open on a timer
+const MAX_CHECKPOINT_SECONDS: u32 = 3600;
+
+/// Open a checkpoint, closing any that is still open
+///
+/// The code word is drawn on every output until the checkpoint closes,
+/// after `duration_seconds` if given or on `close_checkpoint`.
+#[tauri::command]
+#[instrument(skip(app, state))]
+pub async fn start_checkpoint(
+    prompt: Option<String>,
+    duration_seconds: Option<u32>,
+    app: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<Checkpoint> {
+    if duration_seconds.is_some_and(|s| !(1..=MAX_CHECKPOINT_SECONDS).contains(&s)) {
+        return Err(StreamSlateError::Other(format!(
+            "Checkpoint duration must be 1-{MAX_CHECKPOINT_SECONDS} seconds"
+        )));
+    }
+    finish_checkpoint(&state, &app)?;
+
+    let page = state.get_pdf_state()?.current_page;
+    let now = chrono::Utc::now().to_rfc3339();
+    let opened = Checkpoint::open(prompt, page, &now);
+    {
+        let mut checkpoints = checkpoint_state(&state)?;
+        let session = checkpoints
+            .session
+            .get_or_insert_with(|| CheckpointSession::new(&now));
+        session.checkpoints.push(opened.clone());
+        save(&app, session);
+    }
+    set_caption(&state, Some(opened.code.clone()))?;
+
+    info!(id = %opened.id, code = %opened.code, "Checkpoint started");
+    emit_checkpoint_changed(&app, &opened);
+    state.broadcast(WebSocketEvent::CheckpointStarted {
+        id: opened.id.clone(),
+        prompt: opened.prompt.clone(),
+    })?;
+
+    if let Some(seconds) = duration_seconds {
+        let (state, app, id) = (state.inner().clone(), app.clone(), opened.id.clone());
+        tauri::async_runtime::spawn(async move {
+            tokio::time::sleep(Duration::from_secs(u64::from(seconds))).await;
+            let still_open = state.checkpoints.lock().is_ok_and(|c| {
+                c.session
+                    .as_ref()
+                    .and_then(CheckpointSession::current)
+                    .is_some_and(|open| open.id == id)
+            });
+            if still_open {
+                if let Err(e) = finish_checkpoint(&state, &app) {
+                    warn!(error = %e, "Failed to close checkpoint");
+                }
+            }
+        });
+    }
+    Ok(opened)
+}
+
+/// Stop taking responses and remove the code word from the outputs
+///
+/// Returns the closed checkpoint with its responses, or nothing if no
+/// checkpoint was open.
+#[tauri::command]
+#[instrument(skip(app, state))]
+pub async fn close_checkpoint(
+    app: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<Option<Checkpoint>> {
+    finish_checkpoint(&state, &app)
+}
+
+/// Checkpoints held so far in this app session
+#[tauri::command]
+#[instrument(skip(state))]
+pub async fn get_checkpoint_session(
+    state: State<'_, AppState>,
+) -> Result<Option<CheckpointSession>> {
+    Ok(checkpoint_state(&state)?.session.clone())
+}
+
+/// Stored results of every session, oldest first
+#[tauri::command]
+#[instrument(skip(app))]
+pub async fn list_checkpoint_sessions(app: AppHandle) -> Result<Vec<CheckpointSession>> {
+    Ok(checkpoint::load_sessions(&app))
+}
+
+/// Record a viewer's answer to the open checkpoint
+///
+/// Returns `false` if the name had already answered. Used by the HTTP
+/// endpoint.
+pub(crate) fn submit_checkpoint_response(
+    state: &AppState,
+    app: &AppHandle,
+    name: &str,
+    code: &str,
+) -> Result<bool> {
+    let updated = {
+        let mut checkpoints = checkpoint_state(state)?;
+        let Some(session) = checkpoints.session.as_mut() else {
+            return Err(StreamSlateError::Other("No checkpoint is open".to_string()));
+        };
+        let Some(open) = session.current_mut() else {
+            return Err(StreamSlateError::Other("No checkpoint is open".to_string()));
+        };
+        if !open.respond(name, code, &chrono::Utc::now().to_rfc3339())? {
+            return Ok(false);
+        }
+        let updated = open.clone();
+        save(app, session);
+        updated
+    };
+
+    emit_checkpoint_changed(app, &updated);
+    Ok(true)
+}
+
+/// The checkpoint taking responses, if any
+pub(crate) fn current_checkpoint(state: &AppState) -> Result<Option<Checkpoint>> {
+    Ok(checkpoint_state(state)?
+        .session
+        .as_ref()
+        .and_then(CheckpointSession::current)
+        .cloned())
+}
+
+/// Close the open checkpoint, if any, and clear its caption
+fn finish_checkpoint(state: &AppState, app: &AppHandle) -> Result<Option<Checkpoint>> {
+    let closed = {
+        let mut checkpoints = checkpoint_state(state)?;
+        let Some(session) = checkpoints.session.as_mut() else {
+            return Ok(None);
+        };
+        let Some(open) = session.current_mut() else {
+            return Ok(None);
+        };
+        open.close(&chrono::Utc::now().to_rfc3339());
+        let closed = open.clone();
+        save(app, session);
+        closed
+    };
+    set_caption(state, None)?;
+
+    info!(
+        id = %closed.id,
+        responses = closed.responses.len(),
+        "Checkpoint closed"
+    );
+    emit_checkpoint_changed(app, &closed);
+    state.broadcast(WebSocketEvent::CheckpointClosed {
+        id: closed.id.clone(),
+        responses: closed.responses.len() as u32,
+    })?;
+    Ok(Some(closed))
+}
+
+fn set_caption(state: &AppState, code: Option<String>) -> Result<()> {
+    state
+        .outputs
+        .lock()
+        .map_err(|e| StreamSlateError::StateLock(e.to_string()))?
+        .checkpoint_code = code;
+    Ok(())
+}
+
+/// Persist the session; a failed write is logged, the results stay in memory
+fn save(app: &AppHandle, session: &CheckpointSession) {
+    let Some(path) = checkpoint::session_path(app, &session.session_id) else {
+        return;
+    };
+    if let Err(e) = checkpoint::save_session(&path, session) {
+        warn!(error = %e, "Failed to save checkpoint results");
+    }
+}
+
+fn emit_checkpoint_changed(app: &AppHandle, checkpoint: &Checkpoint) {
+    #[derive(Serialize, Clone)]
+    struct CheckpointPayload<'a> {
+        id: &'a str,
+        code: &'a str,
+        open: bool,
+        responses: usize,
+    }
+
+    let payload = CheckpointPayload {
+        id: &checkpoint.id,
+        code: &checkpoint.code,
+        open: checkpoint.is_open(),
+        responses: checkpoint.responses.len(),
+    };
+    if let Err(e) = app.emit("checkpoint-changed", payload) {
+        warn!("Failed to emit checkpoint change: {}", e);
+    }
+}
+
+fn checkpoint_state(
+    state: &AppState,
+) -> Result<std::sync::MutexGuard<'_, crate::state::CheckpointState>> {
+    state
+        .checkpoints
+        .lock()
+        .map_err(|e| StreamSlateError::StateLock(format!("Checkpoints: {e}")))
+}
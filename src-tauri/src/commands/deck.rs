@@ -0,0 +1,97 @@
+/*
+ * This file is part of StreamSlate.
+ * Copyright (C) 2025 StreamSlate Contributors
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+
+//! Image deck commands
+//!
+//! Opens a folder of slide images as the current document. The deck shares
+//! `PdfState` with PDFs, so page navigation, presenter sync, and WebSocket
+//! control work unchanged.
+
+use super::analytics::start_page_timing;
+use super::pdf::PdfInfo;
+use crate::deck::{DeckPage, ImageDeck};
+use crate::error::{Result, StreamSlateError};
+use crate::state::{AppState, DocumentKind};
+use std::path::PathBuf;
+use tauri::State;
+use tracing::{info, instrument};
+
+/// Open a directory of PNG/JPEG files as a paged document
+#[tauri::command]
+#[instrument(skip(state))]
+pub async fn open_image_deck(folder_path: String, state: State<'_, AppState>) -> Result<PdfInfo> {
+    let folder = PathBuf::from(&folder_path);
+    if !folder.exists() {
+        return Err(StreamSlateError::FileNotFound(folder_path));
+    }
+
+    let deck = ImageDeck::load(&folder)?;
+    let page_count = deck.pages.len() as u32;
+    let file_size = deck.total_size;
+
+    state.set_pdf_document(None)?;
+    state.set_image_deck(Some(deck))?;
+
+    state.update_pdf_state(|pdf_state| {
+        pdf_state.current_file = Some(folder_path.clone());
+        pdf_state.total_pages = page_count;
+        pdf_state.current_page = 1;
+        pdf_state.is_loaded = true;
+        pdf_state.document_kind = DocumentKind::ImageDeck;
+        pdf_state.crop = None;
+        pdf_state.smart_invert = false;
+        pdf_state.linked = None;
+    })?;
+    state.clear_reactions();
+    state.clear_media();
+    state.clear_annotation_history();
+
+    info!(folder = %folder_path, pages = page_count, "Image deck opened");
+    start_page_timing(&state, &folder_path);
+
+    Ok(PdfInfo {
+        title: folder
+            .file_name()
+            .and_then(|name| name.to_str())
+            .map(String::from),
+        path: folder_path,
+        author: None,
+        page_count,
+        file_size,
+        created: None,
+        modified: None,
+        restored: None,
+    })
+}
+
+/// Get the image file backing a page of the open image deck
+#[tauri::command]
+#[instrument(skip(state))]
+pub async fn get_image_deck_page(page_number: u32, state: State<'_, AppState>) -> Result<DeckPage> {
+    let deck = state
+        .get_image_deck()?
+        .ok_or_else(|| StreamSlateError::Other("No image deck is currently open".to_string()))?;
+
+    deck.page(page_number).cloned().ok_or_else(|| {
+        StreamSlateError::Other(format!(
+            "Page {} not found (deck has {} pages)",
+            page_number,
+            deck.pages.len()
+        ))
+    })
+}
@@ -0,0 +1,182 @@
+/*
+ * This file is part of StreamSlate.
+ * Copyright (C) 2025 StreamSlate Contributors
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+
+//! Sidecar encryption
+//!
+//! Encrypted sidecars are sealed with AES-256-GCM under a random key kept in
+//! the OS keychain (Keychain, Credential Manager or the Secret Service), so
+//! they can't be read by another user of the machine or on another machine.
+//! The key is created the first time encryption is turned on. A sealed
+//! sidecar is a small JSON envelope; plain sidecars are read as before.
+
+use crate::error::{Result, StreamSlateError};
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use ring::aead::{Aad, LessSafeKey, Nonce, UnboundKey, AES_256_GCM, NONCE_LEN};
+use ring::rand::{SecureRandom, SystemRandom};
+use serde::{Deserialize, Serialize};
+use std::sync::{Mutex, OnceLock};
+use tracing::info;
+
+/// Keychain service the key is stored under
+const KEYCHAIN_SERVICE: &str = "StreamSlate";
+
+/// Keychain account the key is stored under
+const KEYCHAIN_ACCOUNT: &str = "annotation-key";
+
+/// Version of the envelope format
+const FORMAT_VERSION: u32 = 1;
+
+/// Key loaded from the keychain
+static KEY: OnceLock<LessSafeKey> = OnceLock::new();
+
+/// Held while the key is loaded, so only one is ever created
+static KEY_LOCK: Mutex<()> = Mutex::new(());
+
+/// An encrypted sidecar
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct Sealed {
+    encrypted: u32,
+    nonce: String,
+    data: String,
+}
+
+/// Encrypt sidecar content with the keychain key
+pub(crate) fn seal(plain: &[u8]) -> Result<Vec<u8>> {
+    seal_with(key()?, plain)
+}
+
+/// Decrypt sidecar content if it is encrypted, passing plain content through
+pub(crate) fn open(content: String) -> Result<String> {
+    match serde_json::from_str::<Sealed>(&content) {
+        Ok(sealed) => open_with(key()?, &sealed),
+        Err(_) => Ok(content),
+    }
+}
+
+/// The annotation key, read from the keychain or created there
+pub(crate) fn key() -> Result<&'static LessSafeKey> {
+    let _guard = KEY_LOCK
+        .lock()
+        .map_err(|e| StreamSlateError::StateLock(format!("Annotation key: {e}")))?;
+    if let Some(key) = KEY.get() {
+        return Ok(key);
+    }
+
+    let entry = keyring::Entry::new(KEYCHAIN_SERVICE, KEYCHAIN_ACCOUNT).map_err(keychain_error)?;
+    let bytes = match entry.get_password() {
+        Ok(encoded) => STANDARD.decode(encoded).unwrap_or_default(),
+        Err(keyring::Error::NoEntry) => {
+            let mut bytes = vec![0; AES_256_GCM.key_len()];
+            SystemRandom::new().fill(&mut bytes).map_err(|_| {
+                StreamSlateError::Other("No secure randomness available".to_string())
+            })?;
+            entry
+                .set_password(&STANDARD.encode(&bytes))
+                .map_err(keychain_error)?;
+            info!("Created the annotation key in the keychain");
+            bytes
+        }
+        Err(e) => return Err(keychain_error(e)),
+    };
+    let key = new_key(&bytes)?;
+    Ok(KEY.get_or_init(|| key))
+}
+
+fn new_key(bytes: &[u8]) -> Result<LessSafeKey> {
+    UnboundKey::new(&AES_256_GCM, bytes)
+        .map(LessSafeKey::new)
+        .map_err(|_| {
+            StreamSlateError::Other("The annotation key in the keychain is damaged".to_string())
+        })
+}
+
+fn keychain_error(e: keyring::Error) -> StreamSlateError {
+    StreamSlateError::Other(format!("Keychain unavailable: {e}"))
+}
+
+fn seal_with(key: &LessSafeKey, plain: &[u8]) -> Result<Vec<u8>> {
+    let mut nonce = [0; NONCE_LEN];
+    SystemRandom::new()
+        .fill(&mut nonce)
+        .map_err(|_| StreamSlateError::Other("No secure randomness available".to_string()))?;
+    let mut data = plain.to_vec();
+    key.seal_in_place_append_tag(Nonce::assume_unique_for_key(nonce), Aad::empty(), &mut data)
+        .map_err(|_| StreamSlateError::Other("Failed to encrypt annotations".to_string()))?;
+
+    let sealed = Sealed {
+        encrypted: FORMAT_VERSION,
+        nonce: STANDARD.encode(nonce),
+        data: STANDARD.encode(data),
+    };
+    Ok(serde_json::to_vec_pretty(&sealed)?)
+}
+
+fn open_with(key: &LessSafeKey, sealed: &Sealed) -> Result<String> {
+    let unreadable = || {
+        StreamSlateError::Other(
+            "Annotations can't be decrypted with this keychain's key".to_string(),
+        )
+    };
+    if sealed.encrypted != FORMAT_VERSION {
+        return Err(StreamSlateError::Other(format!(
+            "Unsupported annotation encryption version {}",
+            sealed.encrypted
+        )));
+    }
+    let nonce: [u8; NONCE_LEN] = STANDARD
+        .decode(&sealed.nonce)
+        .ok()
+        .and_then(|nonce| nonce.try_into().ok())
+        .ok_or_else(unreadable)?;
+    let mut data = STANDARD.decode(&sealed.data).map_err(|_| unreadable())?;
+    let plain = key
+        .open_in_place(Nonce::assume_unique_for_key(nonce), Aad::empty(), &mut data)
+        .map_err(|_| unreadable())?;
+    String::from_utf8(plain.to_vec()).map_err(|_| unreadable())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_seal_and_open() {
+        let key = new_key(&[7; 32]).unwrap();
+        let plain = r#"{"version":1,"annotations":{}}"#;
+
+        let sealed = seal_with(&key, plain.as_bytes()).unwrap();
+        let sealed = String::from_utf8(sealed).unwrap();
+        assert!(serde_json::from_str::<Sealed>(plain).is_err());
+        assert!(!sealed.contains("annotations"));
+        let envelope: Sealed = serde_json::from_str(&sealed).unwrap();
+        assert_eq!(open_with(&key, &envelope).unwrap(), plain);
+
+        // Another key, or a changed byte, can't open it
+        let other = new_key(&[8; 32]).unwrap();
+        assert!(open_with(&other, &envelope).is_err());
+        let mut data = STANDARD.decode(&envelope.data).unwrap();
+        data[0] ^= 1;
+        let tampered = Sealed {
+            data: STANDARD.encode(data),
+            ..envelope
+        };
+        assert!(open_with(&key, &tampered).is_err());
+    }
+}
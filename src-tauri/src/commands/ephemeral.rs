@@ -0,0 +1,101 @@
+/*
+ * This file is part of StreamSlate.
+ * Copyright (C) 2025 StreamSlate Contributors
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+
+//! Session-only annotations
+//!
+//! An annotation with `ephemeral` set is shown, broadcast and undone like any
+//! other but never written to the sidecar, so it is gone once the document
+//! is closed. Any annotation can be flagged; in session-only mode every new
+//! one is, so live scribbles vanish while prepared notes persist. The mode
+//! lasts until the app quits.
+
+use super::annotations::Annotation;
+use crate::error::{Result, StreamSlateError};
+use crate::state::{history, AppState};
+use std::collections::{HashMap, HashSet};
+use std::sync::atomic::Ordering;
+use tauri::State;
+use tracing::{info, instrument};
+
+/// Whether new annotations are kept only until the document is closed
+#[tauri::command]
+#[instrument(skip(state))]
+pub async fn get_ephemeral_annotations(state: State<'_, AppState>) -> Result<bool> {
+    Ok(state.ephemeral_annotations.load(Ordering::Relaxed))
+}
+
+/// Turn session-only mode for new annotations on or off
+///
+/// Annotations already drawn keep their flag.
+#[tauri::command]
+#[instrument(skip(state))]
+pub async fn set_ephemeral_annotations(enabled: bool, state: State<'_, AppState>) -> Result<()> {
+    state
+        .ephemeral_annotations
+        .store(enabled, Ordering::Relaxed);
+    info!(enabled, "Session-only annotations updated");
+    Ok(())
+}
+
+/// Flag annotations not stored yet in session-only mode, returning the pages
+/// that changed
+pub(crate) fn mark_new_ephemeral(
+    state: &AppState,
+    annotations: &mut HashMap<u32, Vec<Annotation>>,
+) -> Result<Vec<u32>> {
+    if !state.ephemeral_annotations.load(Ordering::Relaxed) {
+        return Ok(Vec::new());
+    }
+    let stored: HashSet<String> = state
+        .annotations
+        .read()
+        .map_err(|e| StreamSlateError::StateLock(format!("Annotations: {e}")))?
+        .values()
+        .flatten()
+        .map(|a| history::annotation_id(a))
+        .collect();
+
+    let mut pages = Vec::new();
+    for (page, list) in annotations.iter_mut() {
+        let mut changed = false;
+        for annotation in list
+            .iter_mut()
+            .filter(|a| !a.ephemeral && !stored.contains(&a.id))
+        {
+            annotation.ephemeral = true;
+            changed = true;
+        }
+        if changed {
+            pages.push(*page);
+        }
+    }
+    Ok(pages)
+}
+
+/// Flag an annotation added by a remote client in session-only mode, unless
+/// the client chose
+pub(crate) fn mark_ephemeral_json(
+    state: &AppState,
+    annotation: &mut serde_json::Map<String, serde_json::Value>,
+) {
+    if state.ephemeral_annotations.load(Ordering::Relaxed) {
+        annotation
+            .entry("ephemeral")
+            .or_insert(serde_json::Value::Bool(true));
+    }
+}
@@ -0,0 +1,287 @@
+/*
+ * This file is part of StreamSlate.
+ * Copyright (C) 2025 StreamSlate Contributors
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+
+//! Partial stroke eraser
+//!
+//! `erase_at` removes the parts of free-draw strokes under a circular
+//! eraser. A stroke crossed by the eraser is cut in two: the first piece
+//! keeps the stroke's ID and the rest become new annotations, so clients
+//! only need to send where the eraser is.
+
+use super::annotations::{page_updates, persist_annotations, publish_annotation_updates};
+use super::annotations::{Annotation, Point};
+use super::shapes::AnnotationKind;
+use crate::error::{Result, StreamSlateError};
+use crate::state::ink::set_points;
+use crate::state::{history, AppState};
+use std::collections::HashMap;
+use tauri::{AppHandle, State};
+use tracing::{debug, instrument};
+
+/// Erase the parts of free-draw strokes on `page` within `radius` of
+/// (`x`, `y`), in annotation coordinates
+///
+/// Returns the page's full annotation list, or nothing if no stroke was hit.
+#[tauri::command]
+#[instrument(skip(state, app))]
+pub async fn erase_at(
+    page: u32,
+    x: f64,
+    y: f64,
+    radius: f64,
+    state: State<'_, AppState>,
+    app: AppHandle,
+) -> Result<HashMap<u32, Vec<serde_json::Value>>> {
+    erase_at_state(&state, &app, page, Point::new(x, y), radius)
+}
+
+/// Shared eraser logic for the Tauri command and WebSocket handler
+pub(crate) fn erase_at_state(
+    state: &AppState,
+    app: &AppHandle,
+    page: u32,
+    center: Point,
+    radius: f64,
+) -> Result<HashMap<u32, Vec<serde_json::Value>>> {
+    if !(center.x.is_finite() && center.y.is_finite() && radius.is_finite() && radius > 0.0) {
+        return Err(StreamSlateError::Other(
+            "Eraser position must be finite and its radius positive".to_string(),
+        ));
+    }
+
+    let updates = {
+        let mut state_annotations = state
+            .annotations
+            .write()
+            .map_err(|e| StreamSlateError::StateLock(format!("Annotations: {e}")))?;
+        let Some(list) = state_annotations.get(&page) else {
+            return Ok(HashMap::new());
+        };
+        let Some(erased) = erase_strokes(list, &center, radius) else {
+            return Ok(HashMap::new());
+        };
+
+        let previous = state_annotations.clone();
+        if erased.is_empty() {
+            state_annotations.remove(&page);
+        } else {
+            state_annotations.insert(page, erased);
+        }
+        state.record_annotation_edits(history::diff(&previous, &state_annotations));
+        persist_annotations(state, &state_annotations)?;
+        page_updates(&state_annotations, [page])
+    };
+
+    debug!(page, x = center.x, y = center.y, radius, "Erased strokes");
+    state.snapshot_annotations("erase");
+    publish_annotation_updates(state, app, &updates);
+    Ok(updates)
+}
+
+/// A page's serialized annotations with the eraser applied, or `None` if it
+/// hit nothing
+///
+/// The eraser reaches a stroke's outline, not just its center line. Locked
+/// strokes are left alone.
+pub fn erase_strokes(annotations: &[String], center: &Point, radius: f64) -> Option<Vec<String>> {
+    let mut result = Vec::with_capacity(annotations.len());
+    let mut hit = false;
+    for serialized in annotations {
+        let stroke = serde_json::from_str::<Annotation>(serialized)
+            .ok()
+            .filter(|a| a.annotation_type == AnnotationKind::Ink && !a.locked);
+        let Some(stroke) = stroke else {
+            result.push(serialized.clone());
+            continue;
+        };
+        let Some(points) = stroke.points.as_deref() else {
+            result.push(serialized.clone());
+            continue;
+        };
+        let reach = radius + stroke.stroke_width.unwrap_or_default() / 2.0;
+        let Some(pieces) = erase_polyline(points, center, reach) else {
+            result.push(serialized.clone());
+            continue;
+        };
+
+        hit = true;
+        let now = chrono::Utc::now().to_rfc3339();
+        for (i, piece) in pieces.into_iter().enumerate() {
+            let mut part = stroke.clone();
+            if i > 0 {
+                part.id = uuid::Uuid::new_v4().to_string();
+                part.created = now.clone();
+            }
+            part.modified = now.clone();
+            set_points(&mut part, piece);
+            if let Ok(json) = serde_json::to_string(&part) {
+                result.push(json);
+            }
+        }
+    }
+    hit.then_some(result)
+}
+
+/// The pieces of a polyline left outside a circle, or `None` if the circle
+/// doesn't touch it
+///
+/// Pieces shorter than two points are dropped.
+pub fn erase_polyline(points: &[Point], center: &Point, radius: f64) -> Option<Vec<Vec<Point>>> {
+    let inside = |p: &Point| (p.x - center.x).powi(2) + (p.y - center.y).powi(2) <= radius * radius;
+    let first = points.first()?;
+    if points.len() == 1 {
+        return inside(first).then(Vec::new);
+    }
+
+    let mut pieces = Vec::new();
+    let mut current = Vec::new();
+    let mut hit = false;
+    let mut flush = |current: &mut Vec<Point>| {
+        if current.len() >= 2 {
+            pieces.push(std::mem::take(current));
+        } else {
+            current.clear();
+        }
+    };
+
+    if !inside(first) {
+        current.push(first.clone());
+    }
+    for pair in points.windows(2) {
+        let (a, b) = (&pair[0], &pair[1]);
+        match segment_overlap(a, b, center, radius) {
+            None => {
+                if current.is_empty() {
+                    current.push(a.clone());
+                }
+                current.push(b.clone());
+            }
+            Some((t0, t1)) => {
+                hit = true;
+                if t0 > 0.0 {
+                    current.push(a.lerp(b, t0));
+                }
+                flush(&mut current);
+                if t1 < 1.0 {
+                    current.push(a.lerp(b, t1));
+                    current.push(b.clone());
+                }
+            }
+        }
+    }
+    flush(&mut current);
+    hit.then_some(pieces)
+}
+
+/// The part of segment `a`-`b` inside the circle, as a range of the
+/// segment's parameter (0 at `a`, 1 at `b`)
+fn segment_overlap(a: &Point, b: &Point, center: &Point, radius: f64) -> Option<(f64, f64)> {
+    let (dx, dy) = (b.x - a.x, b.y - a.y);
+    let (fx, fy) = (a.x - center.x, a.y - center.y);
+    let qa = dx * dx + dy * dy;
+    let qb = 2.0 * (fx * dx + fy * dy);
+    let qc = fx * fx + fy * fy - radius * radius;
+    if qa == 0.0 {
+        return (qc <= 0.0).then_some((0.0, 1.0));
+    }
+
+    let discriminant = qb * qb - 4.0 * qa * qc;
+    if discriminant <= 0.0 {
+        return None;
+    }
+    let root = discriminant.sqrt();
+    let t0 = (-qb - root) / (2.0 * qa);
+    let t1 = (-qb + root) / (2.0 * qa);
+    if t1 <= 0.0 || t0 >= 1.0 {
+        return None;
+    }
+    Some((t0.max(0.0), t1.min(1.0)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn line(coords: &[(f64, f64)]) -> Vec<Point> {
+        coords.iter().map(|&(x, y)| Point::new(x, y)).collect()
+    }
+
+    fn coords(points: &[Point]) -> Vec<(f64, f64)> {
+        points.iter().map(|p| (p.x, p.y)).collect()
+    }
+
+    #[test]
+    fn test_erase_polyline() {
+        let stroke = line(&[(0.0, 0.0), (10.0, 0.0), (20.0, 0.0)]);
+
+        // Through the middle: split in two
+        let pieces = erase_polyline(&stroke, &Point::new(10.0, 0.0), 2.0).unwrap();
+        let pieces: Vec<_> = pieces.iter().map(|p| coords(p)).collect();
+        assert_eq!(
+            pieces,
+            [vec![(0.0, 0.0), (8.0, 0.0)], vec![(12.0, 0.0), (20.0, 0.0)]]
+        );
+
+        // Over one end: trimmed
+        let pieces = erase_polyline(&stroke, &Point::new(0.0, 0.0), 5.0).unwrap();
+        assert_eq!(coords(&pieces[0]), [(5.0, 0.0), (10.0, 0.0), (20.0, 0.0)]);
+
+        // Everything: nothing left
+        let pieces = erase_polyline(&stroke, &Point::new(10.0, 0.0), 50.0).unwrap();
+        assert!(pieces.is_empty());
+
+        // Nearby but not touching
+        assert!(erase_polyline(&stroke, &Point::new(10.0, 5.0), 2.0).is_none());
+    }
+
+    #[test]
+    fn test_erase_strokes() {
+        let stroke = serde_json::json!({
+            "id": "s1", "type": "free_draw", "pageNumber": 1, "x": 0, "y": 0,
+            "width": 20, "height": 0, "content": "", "color": "#ff0000",
+            "opacity": 1, "strokeWidth": 2, "created": "", "modified": "",
+            "visible": true,
+            "points": [{ "x": 0, "y": 0 }, { "x": 10, "y": 0 }, { "x": 20, "y": 0 }],
+        })
+        .to_string();
+        let rect = serde_json::json!({
+            "id": "r1", "type": "rectangle", "pageNumber": 1, "x": 5, "y": -5,
+            "width": 10, "height": 10, "content": "", "color": "#000000",
+            "opacity": 1, "created": "", "modified": "", "visible": true,
+        })
+        .to_string();
+        let page = vec![rect.clone(), stroke];
+
+        // Reaches the stroke through its width, and leaves the rectangle alone
+        let erased = erase_strokes(&page, &Point::new(10.0, 1.5), 1.0).unwrap();
+        assert_eq!(erased.len(), 3);
+        assert_eq!(erased[0], rect);
+        let parts: Vec<Annotation> = erased[1..]
+            .iter()
+            .map(|s| serde_json::from_str(s).unwrap())
+            .collect();
+        assert_eq!(parts[0].id, "s1");
+        assert_ne!(parts[1].id, "s1");
+        let second = parts[1].points.as_deref().unwrap();
+        assert_eq!(parts[1].x, second[0].x);
+        assert_eq!(second.last().unwrap().x, 20.0);
+        assert!(parts[0].width < 10.0 && parts[1].width < 10.0);
+
+        assert!(erase_strokes(&page, &Point::new(10.0, 20.0), 1.0).is_none());
+    }
+}
@@ -0,0 +1,209 @@
+/*
+ * This file is part of StreamSlate.
+ * Copyright (C) 2025 StreamSlate Contributors
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+
+//! Gamepad navigation commands
+//!
+//! Mapped buttons run the same commands a Stream Deck would send over the
+//! WebSocket, so remote clients see their effect too.
+
+use crate::capture::laser::LaserPointer;
+use crate::error::{Result, StreamSlateError};
+use crate::gamepad::device::{self, GamepadDevice};
+use crate::gamepad::{self, ControllerEvent, GamepadAction, GamepadMapping, GamepadSession};
+use crate::settings::{load_settings, save_settings};
+use crate::state::{AppState, GamepadState};
+use crate::websocket::{handle_local_command, WebSocketCommand, WebSocketEvent};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use std::time::Instant;
+use tauri::{AppHandle, State};
+use tracing::{debug, info, instrument};
+
+/// Zoom factor of one zoom in/out press
+const ZOOM_STEP: f64 = 1.25;
+
+/// Gamepad state
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GamepadStatus {
+    pub active: bool,
+    pub device: Option<GamepadDevice>,
+    pub mapping: GamepadMapping,
+}
+
+/// Game controllers that can drive navigation
+#[tauri::command]
+#[instrument]
+pub async fn list_gamepads() -> Result<Vec<GamepadDevice>> {
+    device::list_devices()
+}
+
+/// Start navigating with a game controller
+///
+/// Uses the first connected controller when `device_id` is omitted.
+#[tauri::command]
+#[instrument(skip(app, state))]
+pub async fn start_gamepad(
+    device_id: Option<String>,
+    app: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<GamepadStatus> {
+    let device_id = match device_id {
+        Some(id) => id,
+        None => device::list_devices()?
+            .into_iter()
+            .next()
+            .map(|device| device.id)
+            .ok_or_else(|| StreamSlateError::Other("No game controller found".to_string()))?,
+    };
+
+    let mut gamepad = gamepad_state(&state)?;
+    // Release the current controller before opening another
+    gamepad.session.take();
+
+    let shared = Arc::new(state.inner().clone());
+    let session = GamepadSession::start(&device_id, gamepad.mapping.clone(), move |event| {
+        handle_event(&shared, &app, event)
+    })?;
+    info!(device = %session.device().name, "Gamepad navigation started");
+    gamepad.session = Some(session);
+    Ok(status(&gamepad))
+}
+
+/// Stop navigating with the game controller
+#[tauri::command]
+#[instrument(skip(state))]
+pub async fn stop_gamepad(state: State<'_, AppState>) -> Result<()> {
+    if gamepad_state(&state)?.session.take().is_none() {
+        return Ok(());
+    }
+    info!("Gamepad navigation stopped");
+
+    // Remove a laser dot the stick was steering, unless a pointing device owns it
+    let laser_device = state
+        .pointer_tap
+        .lock()
+        .map_err(|e| StreamSlateError::StateLock(format!("Pointer device: {e}")))?
+        .is_some();
+    if !laser_device {
+        state
+            .outputs
+            .lock()
+            .map_err(|e| StreamSlateError::StateLock(format!("Outputs: {e}")))?
+            .laser = None;
+    }
+    Ok(())
+}
+
+/// Whether a controller is in use, and its mapping
+#[tauri::command]
+#[instrument(skip(state))]
+pub async fn get_gamepad_status(state: State<'_, AppState>) -> Result<GamepadStatus> {
+    let gamepad = gamepad_state(&state)?;
+    Ok(status(&gamepad))
+}
+
+/// Replace the button mapping and persist it
+#[tauri::command]
+#[instrument(skip(app, state))]
+pub async fn set_gamepad_mapping(
+    mapping: GamepadMapping,
+    app: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<()> {
+    mapping.validate()?;
+    save_settings(&app, gamepad::MAPPING_FILE, &mapping)?;
+
+    let mut gamepad = gamepad_state(&state)?;
+    if let Some(session) = gamepad.session.as_ref() {
+        session.set_mapping(mapping.clone());
+    }
+    gamepad.mapping = mapping;
+    info!("Gamepad mapping updated");
+    Ok(())
+}
+
+/// Restore the stored button mapping
+pub fn load_gamepad_mapping(app: &AppHandle, state: &AppState) {
+    let mapping = load_settings(app, gamepad::MAPPING_FILE);
+    if let Ok(mut gamepad) = state.gamepad.lock() {
+        gamepad.mapping = mapping;
+    }
+}
+
+fn handle_event(state: &Arc<AppState>, app: &AppHandle, event: ControllerEvent) {
+    match event {
+        ControllerEvent::Action(action) => {
+            let Some(command) = command_for(state, action) else {
+                return;
+            };
+            if let WebSocketEvent::Error { message, .. } = handle_local_command(command, state, app)
+            {
+                debug!(?action, %message, "Gamepad action had no effect");
+            }
+        }
+        ControllerEvent::Pointer(event) => {
+            if let Ok(mut outputs) = state.outputs.lock() {
+                outputs
+                    .laser
+                    .get_or_insert_with(|| LaserPointer::new(1.0))
+                    .apply(event, Instant::now());
+            }
+        }
+    }
+}
+
+fn command_for(state: &AppState, action: GamepadAction) -> Option<WebSocketCommand> {
+    let zoom = |factor: f64| {
+        let current = state.get_pdf_state().ok()?.zoom_level;
+        Some(WebSocketCommand::SetZoom {
+            zoom: current * factor,
+        })
+    };
+    match action {
+        GamepadAction::NextPage => Some(WebSocketCommand::NextPage),
+        GamepadAction::PreviousPage => Some(WebSocketCommand::PreviousPage),
+        GamepadAction::NextSection => Some(WebSocketCommand::NextSection),
+        GamepadAction::PreviousSection => Some(WebSocketCommand::PreviousSection),
+        GamepadAction::TogglePresenter => Some(WebSocketCommand::TogglePresenter),
+        GamepadAction::ZoomIn => zoom(ZOOM_STEP),
+        GamepadAction::ZoomOut => zoom(1.0 / ZOOM_STEP),
+        GamepadAction::ResetZoom => Some(WebSocketCommand::SetZoom { zoom: 1.0 }),
+        // Handled by the controller as laser pointer input
+        GamepadAction::Laser => None,
+    }
+}
+
+fn status(gamepad: &GamepadState) -> GamepadStatus {
+    let device = gamepad
+        .session
+        .as_ref()
+        .map(|session| session.device().clone());
+    GamepadStatus {
+        active: device.is_some(),
+        device,
+        mapping: gamepad.mapping.clone(),
+    }
+}
+
+fn gamepad_state(state: &AppState) -> Result<std::sync::MutexGuard<'_, GamepadState>> {
+    state
+        .gamepad
+        .lock()
+        .map_err(|e| StreamSlateError::StateLock(format!("Gamepad: {e}")))
+}
@@ -0,0 +1,122 @@
+/*
+ * This file is part of StreamSlate.
+ * Copyright (C) 2025 StreamSlate Contributors
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+
+//! Document import commands
+//!
+//! Converts non-PDF sources (e.g. Markdown slides) to a PDF in the app cache
+//! directory and opens it, so the rest of the app sees an ordinary document.
+
+use super::pdf::{load_pdf, PdfInfo};
+use crate::error::{Result, StreamSlateError};
+use crate::importers;
+use crate::state::{AppState, DocumentKind};
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+use std::path::{Path, PathBuf};
+use tauri::{AppHandle, Manager, State};
+use tracing::{info, instrument, warn};
+
+/// Subdirectory of the app cache dir holding generated PDFs
+const IMPORT_CACHE_DIR: &str = "imports";
+
+/// Import a supported non-PDF file and open the generated document
+#[tauri::command]
+#[instrument(skip(app, state))]
+pub async fn import_document(
+    path: String,
+    app: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<PdfInfo> {
+    let source_path = PathBuf::from(&path);
+    if !source_path.exists() {
+        return Err(StreamSlateError::FileNotFound(path));
+    }
+
+    let importer = importers::importer_for(&source_path).ok_or_else(|| {
+        StreamSlateError::Other(format!(
+            "Unsupported import format (supported: {})",
+            importers::supported_extensions().join(", ")
+        ))
+    })?;
+
+    let source = std::fs::read_to_string(&source_path)?;
+    let mut document = importer.import(&source).map_err(|e| {
+        warn!(path = %path, importer = importer.name(), error = %e, "Import failed");
+        e
+    })?;
+
+    let cache_dir = app
+        .path()
+        .app_cache_dir()
+        .map_err(|e| StreamSlateError::Other(format!("No cache directory: {e}")))?
+        .join(IMPORT_CACHE_DIR);
+    std::fs::create_dir_all(&cache_dir)?;
+
+    let output_path = cache_dir.join(generated_file_name(&source_path));
+    document.save(&output_path)?;
+
+    info!(
+        source = %path,
+        output = %output_path.display(),
+        importer = importer.name(),
+        "Document imported"
+    );
+
+    load_pdf(
+        output_path.to_string_lossy().into_owned(),
+        &state,
+        DocumentKind::Imported,
+        false,
+    )
+    .map(|(info, _)| info)
+}
+
+/// List file extensions accepted by `import_document`
+#[tauri::command]
+pub fn get_supported_import_formats() -> Vec<String> {
+    importers::supported_extensions()
+        .into_iter()
+        .map(String::from)
+        .collect()
+}
+
+/// Cache file name that is stable per source path, so re-imports overwrite
+fn generated_file_name(source_path: &Path) -> String {
+    let mut hasher = DefaultHasher::new();
+    source_path.hash(&mut hasher);
+    let stem = source_path
+        .file_stem()
+        .and_then(|s| s.to_str())
+        .unwrap_or("import");
+    format!("{stem}-{:016x}.pdf", hasher.finish())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_generated_file_name() {
+        let a = generated_file_name(Path::new("/talks/a/slides.md"));
+        let b = generated_file_name(Path::new("/talks/b/slides.md"));
+
+        assert!(a.starts_with("slides-") && a.ends_with(".pdf"));
+        assert_ne!(a, b);
+        assert_eq!(a, generated_file_name(Path::new("/talks/a/slides.md")));
+    }
+}
@@ -0,0 +1,129 @@
+/*
+ * This file is part of StreamSlate.
+ * Copyright (C) 2025 StreamSlate Contributors
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+
+//! Ink cleanup commands
+//!
+//! Strokes are cleaned up once, when they are first stored: smoothing an
+//! already smoothed stroke again would keep adding points.
+
+use super::annotations::Annotation;
+use crate::error::{Result, StreamSlateError};
+use crate::settings::{load_settings, save_settings};
+use crate::state::ink::{self, InkConfig};
+use crate::state::{history, AppState};
+use std::collections::{HashMap, HashSet};
+use tauri::{AppHandle, State};
+use tracing::{info, instrument};
+
+/// How strokes are simplified and smoothed
+#[tauri::command]
+#[instrument(skip(state))]
+pub async fn get_ink_config(state: State<'_, AppState>) -> Result<InkConfig> {
+    ink_config(&state)
+}
+
+/// Set how strokes are simplified and smoothed and persist the choice
+///
+/// Applies to strokes drawn from now on.
+#[tauri::command]
+#[instrument(skip(app, state))]
+pub async fn set_ink_config(
+    config: InkConfig,
+    app: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<()> {
+    config.validate()?;
+    save_settings(&app, ink::CONFIG_FILE, &config)?;
+    *state
+        .ink_config
+        .write()
+        .map_err(|e| StreamSlateError::StateLock(format!("Ink config: {e}")))? = config;
+    info!(
+        tolerance = config.tolerance,
+        smooth = config.smooth,
+        "Ink settings updated"
+    );
+    Ok(())
+}
+
+/// Restore the stored ink settings
+pub fn load_ink_config(app: &AppHandle, state: &AppState) {
+    let config = load_settings(app, ink::CONFIG_FILE);
+    if let Ok(mut current) = state.ink_config.write() {
+        *current = config;
+    }
+}
+
+/// Clean up strokes not stored yet, returning the pages that changed
+pub(crate) fn clean_new_strokes(
+    state: &AppState,
+    annotations: &mut HashMap<u32, Vec<Annotation>>,
+) -> Result<Vec<u32>> {
+    let config = ink_config(state)?;
+    let stored: HashSet<String> = state
+        .annotations
+        .read()
+        .map_err(|e| StreamSlateError::StateLock(format!("Annotations: {e}")))?
+        .values()
+        .flatten()
+        .map(|a| history::annotation_id(a))
+        .collect();
+
+    let mut pages = Vec::new();
+    for (page, list) in annotations.iter_mut() {
+        let mut changed = false;
+        for annotation in list.iter_mut().filter(|a| !stored.contains(&a.id)) {
+            changed |= config.apply(annotation);
+        }
+        if changed {
+            pages.push(*page);
+        }
+    }
+    Ok(pages)
+}
+
+/// Clean up a stroke sent as JSON by a remote client
+pub(crate) fn clean_stroke_json(
+    state: &AppState,
+    annotation: &mut serde_json::Map<String, serde_json::Value>,
+) -> Result<()> {
+    let config = ink_config(state)?;
+    let Ok(mut parsed) =
+        serde_json::from_value::<Annotation>(serde_json::Value::Object(annotation.clone()))
+    else {
+        return Ok(());
+    };
+    if !config.apply(&mut parsed) {
+        return Ok(());
+    }
+    if let serde_json::Value::Object(cleaned) = serde_json::to_value(&parsed)? {
+        for field in ["points", "content", "x", "y", "width", "height"] {
+            if let Some(value) = cleaned.get(field) {
+                annotation.insert(field.to_string(), value.clone());
+            }
+        }
+    }
+    Ok(())
+}
+
+fn ink_config(state: &AppState) -> Result<InkConfig> {
+    Ok(*state
+        .ink_config
+        .read()
+        .map_err(|e| StreamSlateError::StateLock(format!("Ink config: {e}")))?)
+}
@@ -0,0 +1,158 @@
+/*
+ * This file is part of StreamSlate.
+ * Copyright (C) 2025 StreamSlate Contributors
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+
+//! Presentation clicker commands
+//!
+//! Mapped gestures run the same commands a Stream Deck would send over the
+//! WebSocket, so remote clients see their effect too.
+
+use crate::error::{Result, StreamSlateError};
+use crate::keymap::device::{self, ClickerDevice};
+use crate::keymap::{self, ClickerSession, Keymap, KeymapAction};
+use crate::settings::{load_settings, save_settings};
+use crate::state::{AppState, ClickerState};
+use crate::websocket::{handle_local_command, WebSocketCommand, WebSocketEvent};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use tauri::{AppHandle, State};
+use tracing::{debug, info, instrument};
+
+/// Clicker state
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ClickerStatus {
+    pub active: bool,
+    pub device: Option<ClickerDevice>,
+    pub keymap: Keymap,
+}
+
+/// Keyboard devices that can act as a clicker
+#[tauri::command]
+#[instrument]
+pub async fn list_clickers() -> Result<Vec<ClickerDevice>> {
+    device::list_devices()
+}
+
+/// Grab a clicker so its keys work system-wide
+///
+/// While grabbed its keys no longer reach the focused app.
+#[tauri::command]
+#[instrument(skip(app, state))]
+pub async fn start_clicker(
+    device_id: String,
+    app: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<ClickerStatus> {
+    let mut clicker = clicker_state(&state)?;
+    // Release the current clicker before grabbing another
+    clicker.session.take();
+
+    let shared = Arc::new(state.inner().clone());
+    let session = ClickerSession::start(&device_id, clicker.keymap.clone(), move |action| {
+        run_action(&shared, &app, action)
+    })?;
+    info!(device = %session.device().name, "Clicker started");
+    clicker.session = Some(session);
+    Ok(status(&clicker))
+}
+
+/// Release the clicker
+#[tauri::command]
+#[instrument(skip(state))]
+pub async fn stop_clicker(state: State<'_, AppState>) -> Result<()> {
+    if clicker_state(&state)?.session.take().is_some() {
+        info!("Clicker stopped");
+    }
+    Ok(())
+}
+
+/// Whether a clicker is grabbed, and its keymap
+#[tauri::command]
+#[instrument(skip(state))]
+pub async fn get_clicker_status(state: State<'_, AppState>) -> Result<ClickerStatus> {
+    let clicker = clicker_state(&state)?;
+    Ok(status(&clicker))
+}
+
+/// Replace the clicker keymap and persist it
+#[tauri::command]
+#[instrument(skip(app, state))]
+pub async fn set_clicker_keymap(
+    keymap: Keymap,
+    app: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<()> {
+    keymap.validate()?;
+    save_settings(&app, keymap::KEYMAP_FILE, &keymap)?;
+
+    let mut clicker = clicker_state(&state)?;
+    if let Some(session) = clicker.session.as_ref() {
+        session.set_keymap(keymap.clone());
+    }
+    clicker.keymap = keymap;
+    info!("Clicker keymap updated");
+    Ok(())
+}
+
+/// Restore the stored clicker keymap
+pub fn load_clicker_keymap(app: &AppHandle, state: &AppState) {
+    let keymap = load_settings(app, keymap::KEYMAP_FILE);
+    if let Ok(mut clicker) = state.clicker.lock() {
+        clicker.keymap = keymap;
+    }
+}
+
+fn run_action(state: &Arc<AppState>, app: &AppHandle, action: KeymapAction) {
+    let command = match action {
+        KeymapAction::NextPage => WebSocketCommand::NextPage,
+        KeymapAction::PreviousPage => WebSocketCommand::PreviousPage,
+        KeymapAction::NextSection => WebSocketCommand::NextSection,
+        KeymapAction::PreviousSection => WebSocketCommand::PreviousSection,
+        KeymapAction::TogglePresenter => WebSocketCommand::TogglePresenter,
+        KeymapAction::ToggleBlackout => {
+            let Ok(outputs) = state.outputs.lock() else {
+                return;
+            };
+            WebSocketCommand::SetBlackout {
+                enabled: !outputs.blackout,
+            }
+        }
+    };
+    if let WebSocketEvent::Error { message, .. } = handle_local_command(command, state, app) {
+        debug!(?action, %message, "Clicker action had no effect");
+    }
+}
+
+fn status(clicker: &ClickerState) -> ClickerStatus {
+    let device = clicker
+        .session
+        .as_ref()
+        .map(|session| session.device().clone());
+    ClickerStatus {
+        active: device.is_some(),
+        device,
+        keymap: clicker.keymap.clone(),
+    }
+}
+
+fn clicker_state(state: &AppState) -> Result<std::sync::MutexGuard<'_, ClickerState>> {
+    state
+        .clicker
+        .lock()
+        .map_err(|e| StreamSlateError::StateLock(format!("Clicker: {e}")))
+}
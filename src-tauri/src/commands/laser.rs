@@ -0,0 +1,141 @@
+/*
+ * This file is part of StreamSlate.
+ * Copyright (C) 2025 StreamSlate Contributors
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+
+//! Laser pointer commands
+//!
+//! A second pointing device (e.g. a co-host's mouse) is grabbed and its
+//! movement drives a laser dot drawn on the output frames, independently of
+//! the host cursor the presenter navigates with.
+
+use crate::capture::laser::LaserPointer;
+use crate::capture::pointer::{self, PointerDevice, PointerTap};
+use crate::error::{Result, StreamSlateError};
+use crate::state::AppState;
+use serde::{Deserialize, Serialize};
+use std::time::Instant;
+use tauri::State;
+use tracing::{info, instrument};
+
+/// Laser pointer state
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LaserPointerStatus {
+    pub active: bool,
+    pub device: Option<PointerDevice>,
+    pub pointer: Option<LaserPointer>,
+}
+
+/// Pointing devices that can drive the laser pointer
+#[tauri::command]
+#[instrument]
+pub async fn list_pointer_devices() -> Result<Vec<PointerDevice>> {
+    pointer::list_devices()
+}
+
+/// Grab a pointing device and draw its laser pointer on the outputs
+///
+/// While grabbed the device no longer moves the host cursor. `sensitivity`
+/// scales its movement (default 1.0).
+#[tauri::command]
+#[instrument(skip(state))]
+pub async fn start_laser_pointer(
+    device_id: String,
+    sensitivity: Option<f64>,
+    state: State<'_, AppState>,
+) -> Result<LaserPointerStatus> {
+    let sensitivity = sensitivity.unwrap_or(1.0);
+    if !sensitivity.is_finite() || sensitivity <= 0.0 || sensitivity > 10.0 {
+        return Err(StreamSlateError::Other(format!(
+            "Sensitivity must be between 0 and 10, got {sensitivity}"
+        )));
+    }
+
+    let mut tap = pointer_tap(&state)?;
+    // Release the current device before grabbing another
+    tap.take();
+
+    set_laser(&state, Some(LaserPointer::new(sensitivity)))?;
+    let outputs = state.outputs.clone();
+    let started = PointerTap::start(&device_id, move |event| {
+        if let Ok(mut outputs) = outputs.lock() {
+            if let Some(laser) = outputs.laser.as_mut() {
+                laser.apply(event, Instant::now());
+            }
+        }
+    });
+    let started = match started {
+        Ok(started) => started,
+        Err(e) => {
+            set_laser(&state, None)?;
+            return Err(e);
+        }
+    };
+
+    info!(device = %started.device().name, sensitivity, "Laser pointer started");
+    *tap = Some(started);
+    drop(tap);
+    laser_status(&state)
+}
+
+/// Release the pointing device and remove the laser pointer
+#[tauri::command]
+#[instrument(skip(state))]
+pub async fn stop_laser_pointer(state: State<'_, AppState>) -> Result<()> {
+    if pointer_tap(&state)?.take().is_some() {
+        info!("Laser pointer stopped");
+    }
+    set_laser(&state, None)
+}
+
+/// Whether a device drives the laser pointer, and where it points
+#[tauri::command]
+#[instrument(skip(state))]
+pub async fn get_laser_pointer_status(state: State<'_, AppState>) -> Result<LaserPointerStatus> {
+    laser_status(&state)
+}
+
+fn laser_status(state: &AppState) -> Result<LaserPointerStatus> {
+    let device = pointer_tap(state)?.as_ref().map(|tap| tap.device().clone());
+    let pointer = state
+        .outputs
+        .lock()
+        .map_err(|e| StreamSlateError::StateLock(format!("Outputs: {e}")))?
+        .laser
+        .clone();
+    Ok(LaserPointerStatus {
+        active: device.is_some(),
+        device,
+        pointer,
+    })
+}
+
+fn pointer_tap(state: &AppState) -> Result<std::sync::MutexGuard<'_, Option<PointerTap>>> {
+    state
+        .pointer_tap
+        .lock()
+        .map_err(|e| StreamSlateError::StateLock(format!("Pointer device: {e}")))
+}
+
+fn set_laser(state: &AppState, laser: Option<LaserPointer>) -> Result<()> {
+    state
+        .outputs
+        .lock()
+        .map_err(|e| StreamSlateError::StateLock(format!("Outputs: {e}")))?
+        .laser = laser;
+    Ok(())
+}
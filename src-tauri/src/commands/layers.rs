@@ -0,0 +1,271 @@
+/*
+ * This file is part of StreamSlate.
+ * Copyright (C) 2025 StreamSlate Contributors
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+
+//! Annotation layer commands
+//!
+//! Named layers stored in the annotations sidecar. An annotation names the
+//! layer it is on; annotations on a hidden layer are drawn neither in the
+//! viewer nor on the outputs, so rehearsal notes can stay with the slides
+//! while only the live markup reaches the program feed. Annotations without
+//! a layer, or on a layer that no longer exists, are always shown.
+
+use super::annotations::{
+    page_updates, persist_annotations, publish_annotation_updates, read_sidecar, write_sidecar,
+    Annotation,
+};
+use super::locking::keep_locked;
+use super::obs::fire_obs_trigger;
+use super::sections::open_document;
+use crate::error::{Result, StreamSlateError};
+use crate::obs::bindings::ObsTrigger;
+use crate::state::{history, AppState};
+use crate::websocket::WebSocketEvent;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Emitter, State};
+use tracing::{info, instrument, warn};
+
+/// A named group of annotations that is shown or hidden as one
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub struct Layer {
+    pub id: String,
+    pub name: String,
+    pub visible: bool,
+}
+
+/// Add a visible layer named `name`
+#[tauri::command]
+#[instrument(skip(state, app))]
+pub async fn create_layer(
+    name: String,
+    state: State<'_, AppState>,
+    app: AppHandle,
+) -> Result<Layer> {
+    let layer = Layer {
+        id: uuid::Uuid::new_v4().to_string(),
+        name: layer_name(&name)?,
+        visible: true,
+    };
+    let layers = update_layers(&state, &app, |layers| {
+        layers.push(layer.clone());
+        Ok(())
+    })?;
+    broadcast_layers(&state, layers);
+
+    info!(name = %layer.name, "Layer created");
+    Ok(layer)
+}
+
+/// List the open document's layers, in the order they were created
+#[tauri::command]
+#[instrument(skip(state))]
+pub async fn list_layers(state: State<'_, AppState>) -> Result<Vec<Layer>> {
+    layers_for_open_document(&state)
+}
+
+/// Rename a layer
+#[tauri::command]
+#[instrument(skip(state, app))]
+pub async fn rename_layer(
+    id: String,
+    name: String,
+    state: State<'_, AppState>,
+    app: AppHandle,
+) -> Result<Layer> {
+    let name = layer_name(&name)?;
+    let layers = update_layers(&state, &app, |layers| {
+        let index = layer_index(layers, &id)?;
+        layers[index].name = name;
+        Ok(())
+    })?;
+    let layer = layers[layer_index(&layers, &id)?].clone();
+    broadcast_layers(&state, layers);
+    Ok(layer)
+}
+
+/// Show or hide the annotations on a layer
+#[tauri::command]
+#[instrument(skip(state, app))]
+pub async fn set_layer_visible(
+    id: String,
+    visible: bool,
+    state: State<'_, AppState>,
+    app: AppHandle,
+) -> Result<Layer> {
+    let layers = set_layer_visibility(&state, &app, &id, visible)?;
+    let layer = layers[layer_index(&layers, &id)?].clone();
+    broadcast_layers(&state, layers);
+    Ok(layer)
+}
+
+/// Remove a layer and the annotations on it
+///
+/// Removing the annotations can be undone; the layer itself is gone.
+/// Locked annotations on it stay.
+#[tauri::command]
+#[instrument(skip(state, app))]
+pub async fn delete_layer(id: String, state: State<'_, AppState>, app: AppHandle) -> Result<()> {
+    let layers = update_layers(&state, &app, |layers| {
+        layers.remove(layer_index(layers, &id)?);
+        Ok(())
+    })?;
+    broadcast_layers(&state, layers);
+
+    let updates = {
+        let mut state_annotations = state
+            .annotations
+            .write()
+            .map_err(|e| StreamSlateError::StateLock(format!("Annotations: {e}")))?;
+        let previous = state_annotations.clone();
+        for list in state_annotations.values_mut() {
+            list.retain(|s| {
+                serde_json::from_str::<Annotation>(s)
+                    .map_or(true, |a| a.layer.as_deref() != Some(id.as_str()))
+            });
+        }
+        keep_locked(&previous, &mut state_annotations);
+        let edits = history::diff(&previous, &state_annotations);
+        let mut pages: Vec<u32> = edits.iter().map(|edit| edit.page).collect();
+        pages.dedup();
+        state.record_annotation_edits(edits);
+        persist_annotations(&state, &state_annotations)?;
+        page_updates(&state_annotations, pages)
+    };
+
+    info!(id = %id, pages = updates.len(), "Layer deleted");
+    if !updates.is_empty() {
+        state.snapshot_annotations("delete layer");
+        publish_annotation_updates(&state, &app, &updates);
+    }
+    Ok(())
+}
+
+/// Show or hide a layer, returning the new layer list
+///
+/// OBS bindings on the layer's name fire when its visibility changes.
+pub(crate) fn set_layer_visibility(
+    state: &AppState,
+    app: &AppHandle,
+    id: &str,
+    visible: bool,
+) -> Result<Vec<Layer>> {
+    let mut toggled = None;
+    let layers = update_layers(state, app, |layers| {
+        let index = layer_index(layers, id)?;
+        let layer = &mut layers[index];
+        if layer.visible != visible {
+            layer.visible = visible;
+            toggled = Some(layer.name.clone());
+        }
+        Ok(())
+    })?;
+    if let Some(layer) = toggled {
+        info!(id = %id, visible, "Layer visibility changed");
+        fire_obs_trigger(state, ObsTrigger::Layer { layer, visible });
+    }
+    Ok(layers)
+}
+
+/// Layers of the open document
+pub(crate) fn layers_for_open_document(state: &AppState) -> Result<Vec<Layer>> {
+    let (pdf_path, _) = open_document(state)?;
+    Ok(read_sidecar(&pdf_path)?.layers)
+}
+
+/// Change the open document's layers, save them and push the result to the
+/// webview
+fn update_layers(
+    state: &AppState,
+    app: &AppHandle,
+    change: impl FnOnce(&mut Vec<Layer>) -> Result<()>,
+) -> Result<Vec<Layer>> {
+    let (pdf_path, _) = open_document(state)?;
+    let mut file = read_sidecar(&pdf_path)?;
+    change(&mut file.layers)?;
+    write_sidecar(&mut file)?;
+
+    if let Err(e) = app.emit(
+        "layers-changed",
+        serde_json::json!({ "layers": file.layers }),
+    ) {
+        warn!("Failed to emit layers-changed event: {}", e);
+    }
+    Ok(file.layers)
+}
+
+fn broadcast_layers(state: &AppState, layers: Vec<Layer>) {
+    if let Err(e) = state.broadcast(WebSocketEvent::LayersChanged { layers }) {
+        warn!("Failed to broadcast layers change: {}", e);
+    }
+}
+
+fn layer_name(name: &str) -> Result<String> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err(StreamSlateError::Other("Layer name is empty".to_string()));
+    }
+    Ok(name.to_string())
+}
+
+fn layer_index(layers: &[Layer], id: &str) -> Result<usize> {
+    layers
+        .iter()
+        .position(|layer| layer.id == id)
+        .ok_or_else(|| StreamSlateError::Other(format!("No layer with id {id}")))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_layer_name() {
+        assert_eq!(layer_name("  Rehearsal notes ").unwrap(), "Rehearsal notes");
+        assert!(layer_name("   ").is_err());
+    }
+
+    #[test]
+    fn test_layer_index() {
+        let layers: Vec<Layer> = ["rehearsal", "live"]
+            .into_iter()
+            .map(|id| Layer {
+                id: id.to_string(),
+                name: id.to_string(),
+                visible: true,
+            })
+            .collect();
+        assert_eq!(layer_index(&layers, "live").unwrap(), 1);
+        assert!(layer_index(&layers, "missing").is_err());
+    }
+
+    #[test]
+    fn test_annotation_layer_field() {
+        let json = r##"{"id":"a1","type":"text","pageNumber":1,"x":0,"y":0,"width":10,
+            "height":10,"content":"Pause here","color":"#000000","opacity":1,
+            "created":"","modified":"","visible":true,"layer":"rehearsal"}"##;
+        let annotation: Annotation = serde_json::from_str(json).unwrap();
+        assert_eq!(annotation.layer.as_deref(), Some("rehearsal"));
+
+        let unlayered = json.replace(r#","layer":"rehearsal""#, "");
+        let annotation: Annotation = serde_json::from_str(&unlayered).unwrap();
+        assert_eq!(annotation.layer, None);
+        assert!(!serde_json::to_string(&annotation)
+            .unwrap()
+            .contains("layer"));
+    }
+}
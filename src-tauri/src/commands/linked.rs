@@ -0,0 +1,150 @@
+/*
+ * This file is part of StreamSlate.
+ * Copyright (C) 2025 StreamSlate Contributors
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+
+//! Linked two-document presentation
+//!
+//! Pairs the open document with a second one (e.g. an original and its
+//! translation) shown side by side. Paging the open document pages the
+//! linked one in step, and both pages are reported together in a
+//! `linked-pages-changed` event / `LINKED_PAGES_CHANGED` broadcast.
+
+use super::pdf::{listen_page_changes, load_pdf, read_pdf_file};
+use crate::error::{Result, StreamSlateError};
+use crate::state::{AppState, DocumentKind, LinkedDocument};
+use crate::websocket::WebSocketEvent;
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Emitter, State};
+use tracing::{info, instrument, warn};
+
+/// Pages shown for a linked pair
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LinkedPages {
+    pub path: String,
+    pub page: u32,
+    pub total_pages: u32,
+    pub linked_path: String,
+    pub linked_page: u32,
+    pub linked_total_pages: u32,
+    pub offset: i32,
+}
+
+/// Present `doc_a` with `doc_b` paged alongside it
+///
+/// `doc_a` becomes the open document (reopened unless it already is) and
+/// page `n` of it is shown with page `n + offset` of `doc_b`.
+#[tauri::command]
+#[instrument(skip(app, state))]
+pub async fn link_documents(
+    doc_a: String,
+    doc_b: String,
+    offset: Option<i32>,
+    app: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<LinkedPages> {
+    let linked_total = read_pdf_file(&doc_b)?.get_pages().len() as u32;
+    if linked_total == 0 {
+        return Err(StreamSlateError::InvalidPdf(format!(
+            "Linked document has no pages: {doc_b}"
+        )));
+    }
+
+    let pdf_state = state.get_pdf_state()?;
+    if !(pdf_state.is_loaded && pdf_state.current_file.as_deref() == Some(doc_a.as_str())) {
+        load_pdf(doc_a, &state, DocumentKind::Pdf, false)?;
+    }
+
+    let mut linked = LinkedDocument {
+        path: doc_b,
+        total_pages: linked_total,
+        offset: offset.unwrap_or(0),
+        current_page: 1,
+    };
+    state.update_pdf_state(|s| {
+        linked.current_page = linked.page_for(s.current_page);
+        s.linked = Some(linked.clone());
+    })?;
+    info!(linked = %linked.path, offset = linked.offset, "Documents linked");
+
+    let pages = linked_pages(&state)?
+        .ok_or_else(|| StreamSlateError::Other("Documents are not linked".to_string()))?;
+    emit_linked_pages(&app, &state, &pages);
+    Ok(pages)
+}
+
+/// Stop paging the linked document
+#[tauri::command]
+#[instrument(skip(state))]
+pub async fn unlink_documents(state: State<'_, AppState>) -> Result<()> {
+    state.update_pdf_state(|s| s.linked = None)?;
+    info!("Documents unlinked");
+    Ok(())
+}
+
+/// Pages currently shown for the linked pair, if documents are linked
+#[tauri::command]
+#[instrument(skip(state))]
+pub async fn get_linked_pages(state: State<'_, AppState>) -> Result<Option<LinkedPages>> {
+    linked_pages(&state)
+}
+
+/// Page the linked document in step with every page change
+pub fn spawn_linked_page_listener(app: &AppHandle, state: AppState) {
+    let handle = app.clone();
+    listen_page_changes(app, move |page| {
+        let updated = state.update_pdf_state(|s| {
+            if let Some(linked) = s.linked.as_mut() {
+                linked.current_page = linked.page_for(page);
+            }
+        });
+        match updated.and_then(|_| linked_pages(&state)) {
+            Ok(Some(pages)) => emit_linked_pages(&handle, &state, &pages),
+            Ok(None) => {}
+            Err(e) => warn!(error = %e, "Failed to page linked document"),
+        }
+    });
+}
+
+fn linked_pages(state: &AppState) -> Result<Option<LinkedPages>> {
+    let pdf_state = state.get_pdf_state()?;
+    let (Some(path), Some(linked)) = (pdf_state.current_file, pdf_state.linked) else {
+        return Ok(None);
+    };
+    Ok(Some(LinkedPages {
+        path,
+        page: pdf_state.current_page,
+        total_pages: pdf_state.total_pages,
+        linked_path: linked.path,
+        linked_page: linked.current_page,
+        linked_total_pages: linked.total_pages,
+        offset: linked.offset,
+    }))
+}
+
+fn emit_linked_pages(app: &AppHandle, state: &AppState, pages: &LinkedPages) {
+    if let Err(e) = app.emit("linked-pages-changed", pages) {
+        warn!(error = %e, "Failed to emit linked-pages-changed event");
+    }
+    let _ = state.broadcast(WebSocketEvent::LinkedPagesChanged {
+        page: pages.page,
+        total_pages: pages.total_pages,
+        linked_path: pages.linked_path.clone(),
+        linked_page: pages.linked_page,
+        linked_total_pages: pages.linked_total_pages,
+    });
+}
@@ -0,0 +1,128 @@
+/*
+ * This file is part of StreamSlate.
+ * Copyright (C) 2025 StreamSlate Contributors
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+
+//! LMS package export command
+//!
+//! Gathers the open document's annotations, its page timing session and
+//! cached summaries into a package for Canvas, Moodle and other LMSs.
+
+use super::annotations::{page_origins, read_sidecar};
+use super::pdf::validate_output_path;
+use super::shapes::AnnotationKind;
+use crate::error::{Result, StreamSlateError};
+use crate::lms::{self, DeckReference, SessionExport};
+use crate::pdf::xfdf;
+use crate::progress;
+use crate::state::AppState;
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::time::Instant;
+use tauri::State;
+use tracing::{info, instrument};
+
+/// Export the open document's session as a SCORM 1.2 zip package
+///
+/// The timeline is the running page timing session, or the last finished
+/// one for this document; it is left out when analytics are disabled.
+#[tauri::command]
+#[instrument(skip(state))]
+pub async fn export_lms_package(output_path: String, state: State<'_, AppState>) -> Result<()> {
+    let pdf_state = state.get_pdf_state()?;
+    let pdf_path = pdf_state.current_file.ok_or(StreamSlateError::NoDocument)?;
+    let output = validate_output_path(&output_path, Some(&pdf_path))?;
+
+    let timeline = {
+        let analytics = state
+            .analytics
+            .lock()
+            .map_err(|e| StreamSlateError::StateLock(format!("Analytics: {e}")))?;
+        match &analytics.session {
+            Some(timer) if timer.document() == pdf_path => {
+                Some(timer.clone().finish(Instant::now()))
+            }
+            _ => analytics
+                .last_report
+                .clone()
+                .filter(|report| report.document == pdf_path),
+        }
+    };
+
+    let file = read_sidecar(&pdf_path)?;
+    let mut notes: BTreeMap<u32, Vec<String>> = BTreeMap::new();
+    for (page, annotations) in &file.annotations {
+        for annotation in annotations {
+            if annotation.annotation_type == AnnotationKind::Text
+                && !annotation.content.trim().is_empty()
+            {
+                notes
+                    .entry(*page)
+                    .or_default()
+                    .push(annotation.content.trim().to_string());
+            }
+        }
+    }
+    if let Ok(summaries) = state.summaries.lock() {
+        if let Some(cache) = summaries.cache.as_ref().filter(|c| c.document == pdf_path) {
+            for summary in cache.pages.values() {
+                notes
+                    .entry(summary.page)
+                    .or_default()
+                    .push(summary.summary.clone());
+            }
+        }
+    }
+
+    let path = Path::new(&pdf_path);
+    let file_name = path
+        .file_name()
+        .map_or_else(|| pdf_path.clone(), |n| n.to_string_lossy().into_owned());
+    let stem = path
+        .file_stem()
+        .map_or_else(|| file_name.clone(), |s| s.to_string_lossy().into_owned());
+    let exported_at = chrono::Utc::now();
+    let date = timeline
+        .as_ref()
+        .and_then(|report| report.started_at.get(..10))
+        .map_or_else(
+            || exported_at.format("%Y-%m-%d").to_string(),
+            str::to_string,
+        );
+
+    let session = SessionExport {
+        title: format!("{stem} - {date}"),
+        exported_at,
+        deck: DeckReference {
+            file_name: file_name.clone(),
+            sha256: progress::content_hash(&std::fs::read(&pdf_path)?),
+            page_count: pdf_state.total_pages,
+        },
+        timeline,
+        annotations: file.annotations.clone().into_iter().collect(),
+        notes,
+    };
+
+    let xfdf = xfdf::write_xfdf(&file.annotations, &page_origins(&state)?, Some(&file_name));
+    std::fs::write(&output, lms::build_package(&session, &xfdf)?)?;
+
+    info!(
+        path = %output.display(),
+        timeline = session.timeline.is_some(),
+        "Exported LMS package"
+    );
+    Ok(())
+}
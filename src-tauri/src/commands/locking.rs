@@ -0,0 +1,209 @@
+/*
+ * This file is part of StreamSlate.
+ * Copyright (C) 2025 StreamSlate Contributors
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+
+//! Locked annotations
+//!
+//! Locking a page makes the annotations on it read-only, so prepared
+//! graphics survive a live show. Host changes (saves, the eraser, layers,
+//! imports, clears, restores) leave locked annotations as they were, remote
+//! clients' edits to them are refused, and an undo that would change one
+//! is refused until the page is unlocked. Annotations added to the page
+//! afterwards aren't locked. Locking isn't an undo step itself.
+
+use super::annotations::Annotation;
+use super::annotations::{page_updates, persist_annotations, publish_annotation_updates};
+use crate::error::{Result, StreamSlateError};
+use crate::state::history::{self, PageAnnotations};
+use crate::state::AppState;
+use std::collections::HashMap;
+use tauri::{AppHandle, State};
+use tracing::{info, instrument};
+
+/// Make the annotations on `page` read-only, returning how many were locked
+#[tauri::command]
+#[instrument(skip(state, app))]
+pub async fn lock_page_annotations(
+    page: u32,
+    state: State<'_, AppState>,
+    app: AppHandle,
+) -> Result<usize> {
+    set_page_locked(&state, &app, page, true)
+}
+
+/// Make the annotations on `page` editable again, returning how many were
+/// unlocked
+#[tauri::command]
+#[instrument(skip(state, app))]
+pub async fn unlock_page_annotations(
+    page: u32,
+    state: State<'_, AppState>,
+    app: AppHandle,
+) -> Result<usize> {
+    set_page_locked(&state, &app, page, false)
+}
+
+fn set_page_locked(state: &AppState, app: &AppHandle, page: u32, locked: bool) -> Result<usize> {
+    if state.get_pdf_state()?.current_file.is_none() {
+        return Err(StreamSlateError::NoDocument);
+    }
+
+    let (updates, changed) = {
+        let mut state_annotations = state
+            .annotations
+            .write()
+            .map_err(|e| StreamSlateError::StateLock(format!("Annotations: {e}")))?;
+        let Some(list) = state_annotations.get_mut(&page) else {
+            return Ok(0);
+        };
+        let mut changed = 0;
+        for serialized in list.iter_mut().filter(|a| is_locked(a) != locked) {
+            let Ok(serde_json::Value::Object(mut object)) = serde_json::from_str(serialized) else {
+                continue;
+            };
+            if locked {
+                object.insert("locked".to_string(), true.into());
+            } else {
+                object.remove("locked");
+            }
+            *serialized = serde_json::Value::Object(object).to_string();
+            changed += 1;
+        }
+        if changed == 0 {
+            return Ok(0);
+        }
+        persist_annotations(state, &state_annotations)?;
+        (page_updates(&state_annotations, [page]), changed)
+    };
+
+    info!(page, locked, count = changed, "Annotation lock changed");
+    state.snapshot_annotations(if locked { "lock" } else { "unlock" });
+    publish_annotation_updates(state, app, &updates);
+    Ok(changed)
+}
+
+/// Whether a serialized annotation is locked
+pub fn is_locked(annotation: &str) -> bool {
+    serde_json::from_str::<serde_json::Value>(annotation)
+        .ok()
+        .and_then(|value| value.get("locked")?.as_bool())
+        .unwrap_or(false)
+}
+
+/// Put back the locked annotations of `previous` that `next` changed,
+/// moved or removed, returning the pages of `next` that changed
+pub fn keep_locked(previous: &PageAnnotations, next: &mut PageAnnotations) -> Vec<u32> {
+    let locked = previous
+        .iter()
+        .flat_map(|(page, list)| {
+            list.iter()
+                .filter(|a| is_locked(a))
+                .map(move |a| (*page, a.clone()))
+        })
+        .collect();
+    restore(locked, next, |a| history::annotation_id(a), |a, b| a == b)
+}
+
+/// Put the stored locked annotations back into annotations sent by the
+/// host, returning the pages that changed
+pub(crate) fn restore_locked(
+    state: &AppState,
+    annotations: &mut HashMap<u32, Vec<Annotation>>,
+) -> Result<Vec<u32>> {
+    let locked = state
+        .annotations
+        .read()
+        .map_err(|e| StreamSlateError::StateLock(format!("Annotations: {e}")))?
+        .iter()
+        .flat_map(|(page, list)| {
+            list.iter()
+                .filter_map(|a| serde_json::from_str::<Annotation>(a).ok())
+                .filter(|a| a.locked)
+                .map(move |a| (*page, a))
+        })
+        .collect();
+    Ok(restore(
+        locked,
+        annotations,
+        |a| a.id.clone(),
+        |a, b| serde_json::to_value(a).ok() == serde_json::to_value(b).ok(),
+    ))
+}
+
+fn restore<T>(
+    locked: Vec<(u32, T)>,
+    next: &mut HashMap<u32, Vec<T>>,
+    id: impl Fn(&T) -> String,
+    same: impl Fn(&T, &T) -> bool,
+) -> Vec<u32> {
+    let mut pages = Vec::new();
+    for (page, annotation) in locked {
+        if next
+            .get(&page)
+            .is_some_and(|list| list.iter().any(|a| same(a, &annotation)))
+        {
+            continue;
+        }
+        let key = id(&annotation);
+        for (other, list) in next.iter_mut().filter(|(other, _)| **other != page) {
+            let len = list.len();
+            list.retain(|a| id(a) != key);
+            if list.len() != len {
+                pages.push(*other);
+            }
+        }
+        let list = next.entry(page).or_default();
+        match list.iter().position(|a| id(a) == key) {
+            Some(index) => list[index] = annotation,
+            None => list.push(annotation),
+        }
+        pages.push(page);
+    }
+    next.retain(|_, list| !list.is_empty());
+    pages.sort_unstable();
+    pages.dedup();
+    pages
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_keep_locked() {
+        let locked = r#"{"id":"a","x":1,"locked":true}"#.to_string();
+        let previous = PageAnnotations::from([
+            (1, vec![locked.clone(), r#"{"id":"b","x":1}"#.to_string()]),
+            (2, vec![r#"{"id":"c","locked":true}"#.to_string()]),
+        ]);
+
+        // Moving a locked annotation, changing one and erasing everything else
+        let mut next = PageAnnotations::from([
+            (1, vec![r#"{"id":"b","x":2}"#.to_string()]),
+            (3, vec![r#"{"id":"a","x":5}"#.to_string()]),
+        ]);
+        assert_eq!(keep_locked(&previous, &mut next), [1, 2, 3]);
+        assert_eq!(next[&1], [r#"{"id":"b","x":2}"#.to_string(), locked]);
+        assert_eq!(next[&2], previous[&2]);
+        assert!(!next.contains_key(&3));
+
+        // Unchanged locked annotations leave nothing to restore
+        let mut unchanged = previous.clone();
+        assert!(keep_locked(&previous, &mut unchanged).is_empty());
+        assert_eq!(unchanged, previous);
+    }
+}
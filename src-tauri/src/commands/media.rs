@@ -0,0 +1,154 @@
+/*
+ * This file is part of StreamSlate.
+ * Copyright (C) 2025 StreamSlate Contributors
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+
+//! Video playback control commands
+//!
+//! Clips come from the open PDF's media annotations and from companion
+//! media listed in its annotations sidecar. Play/pause/seek requests from
+//! the app or from remote controllers (`PLAY_MEDIA`, `PAUSE_MEDIA`,
+//! `SEEK_MEDIA` over WebSocket) update the shared state, which views follow
+//! via the `media-playback-changed` event / `MEDIA_PLAYBACK_CHANGED`
+//! broadcast.
+
+use super::annotations::read_sidecar;
+use crate::error::{Result, StreamSlateError};
+use crate::media::{MediaAction, MediaPlayback, MediaStatus};
+use crate::pdf::media::{self, MediaItem};
+use crate::state::AppState;
+use crate::websocket::WebSocketEvent;
+use std::time::Instant;
+use tauri::{AppHandle, Emitter, State};
+use tracing::{info, instrument, warn};
+
+/// Video clips of the open document with their playback state
+#[tauri::command]
+#[instrument(skip(state))]
+pub async fn list_media(state: State<'_, AppState>) -> Result<Vec<MediaStatus>> {
+    media_statuses(&state)
+}
+
+/// Start or resume playing a clip
+#[tauri::command]
+#[instrument(skip(app, state))]
+pub async fn play_media(
+    id: String,
+    app: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<MediaPlayback> {
+    control_and_broadcast(&state, &app, &id, MediaAction::Play)
+}
+
+/// Pause a clip
+#[tauri::command]
+#[instrument(skip(app, state))]
+pub async fn pause_media(
+    id: String,
+    app: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<MediaPlayback> {
+    control_and_broadcast(&state, &app, &id, MediaAction::Pause)
+}
+
+/// Move a clip to `position` seconds from its start
+#[tauri::command]
+#[instrument(skip(app, state))]
+pub async fn seek_media(
+    id: String,
+    position: f64,
+    app: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<MediaPlayback> {
+    control_and_broadcast(&state, &app, &id, MediaAction::Seek(position))
+}
+
+fn control_and_broadcast(
+    state: &AppState,
+    app: &AppHandle,
+    id: &str,
+    action: MediaAction,
+) -> Result<MediaPlayback> {
+    let playback = control_media(state, app, id, action)?;
+    state.broadcast(WebSocketEvent::MediaPlaybackChanged {
+        playback: playback.clone(),
+    })?;
+    Ok(playback)
+}
+
+/// Apply a playback request and notify the webview
+///
+/// Shared by the Tauri commands and the WebSocket handler.
+pub(crate) fn control_media(
+    state: &AppState,
+    app: &AppHandle,
+    id: &str,
+    action: MediaAction,
+) -> Result<MediaPlayback> {
+    if !media_for_open_document(state)?
+        .iter()
+        .any(|item| item.id == id)
+    {
+        return Err(StreamSlateError::Other(format!("No media with id {id}")));
+    }
+    let playback = state
+        .media
+        .lock()
+        .map_err(|e| StreamSlateError::StateLock(format!("Media: {e}")))?
+        .apply(id, action, Instant::now())?;
+    info!(id, ?action, "Media playback updated");
+
+    emit_media_playback(app, &playback);
+    Ok(playback)
+}
+
+/// Tell the webview a clip's playback state changed
+pub(crate) fn emit_media_playback(app: &AppHandle, playback: &MediaPlayback) {
+    if let Err(e) = app.emit("media-playback-changed", playback) {
+        warn!(error = %e, "Failed to emit media playback change");
+    }
+}
+
+/// Clips of the open document with their current playback state
+pub(crate) fn media_statuses(state: &AppState) -> Result<Vec<MediaStatus>> {
+    let items = media_for_open_document(state)?;
+    let media = state
+        .media
+        .lock()
+        .map_err(|e| StreamSlateError::StateLock(format!("Media: {e}")))?;
+    Ok(media.statuses(items, Instant::now()))
+}
+
+/// Media annotations of the open PDF followed by sidecar companion media,
+/// ordered by page
+fn media_for_open_document(state: &AppState) -> Result<Vec<MediaItem>> {
+    let pdf_state = state.get_pdf_state()?;
+    let path = match pdf_state.current_file {
+        Some(path) if pdf_state.is_loaded => path,
+        _ => return Err(StreamSlateError::NoDocument),
+    };
+
+    let mut items = state
+        .pdf_document
+        .read()
+        .map_err(|e| StreamSlateError::StateLock(format!("PDF document: {e}")))?
+        .as_ref()
+        .map(media::embedded_media)
+        .unwrap_or_default();
+    items.extend(read_sidecar(&path)?.media);
+    items.sort_by_key(|item| item.page);
+    Ok(items)
+}
@@ -21,17 +21,135 @@
 //! This module contains all the Tauri commands that can be invoked from the frontend.
 //! Commands are organized by functionality into separate modules.
 
+pub mod agenda;
+pub mod ai;
+pub mod analytics;
 pub mod annotations;
+pub mod assets;
+pub mod backups;
+pub mod checkpoint;
+pub mod deck;
+pub mod encryption;
+pub mod ephemeral;
+pub mod eraser;
+pub mod gamepad;
+pub mod import;
+pub mod ink;
+pub mod keymap;
+pub mod laser;
+pub mod layers;
+pub mod linked;
+pub mod lms;
+pub mod locking;
+pub mod media;
 pub mod ndi;
+pub mod notes;
+pub mod obs;
+pub mod ocr;
+pub mod osc;
 pub mod pdf;
+pub mod prerender;
 pub mod presenter;
+pub mod presets;
+pub mod print;
+pub mod progress;
+pub mod qr;
+pub mod remote;
+pub mod replay;
+pub mod report;
+pub mod search;
+pub mod sections;
+pub mod shapes;
+pub mod show;
+pub mod sidecar;
+pub mod stamps;
+pub mod tls;
+pub mod transfer;
+pub mod websocket;
 
 // Re-export all commands for easy access
+pub use agenda::{get_agenda, set_agenda_strip, spawn_agenda_listener};
+pub use ai::{
+    get_page_summaries, get_summarizer_config, is_summarizer_available, set_summarizer_config,
+    spawn_summary_listener, summarize_page,
+};
+pub use analytics::{
+    end_page_timing_session, get_analytics_config, set_analytics_config, spawn_page_timing_listener,
+};
 pub use annotations::*;
+pub use assets::{get_cached_asset, list_linked_assets, preload_assets};
+pub use backups::{
+    get_backup_config, list_annotation_backups, restore_annotation_backup, set_backup_config,
+    spawn_annotation_backups,
+};
+pub use checkpoint::{
+    close_checkpoint, get_checkpoint_session, list_checkpoint_sessions, start_checkpoint,
+};
+pub use deck::*;
+pub use ephemeral::{get_ephemeral_annotations, set_ephemeral_annotations};
+pub use eraser::erase_at;
+pub use gamepad::{
+    get_gamepad_status, list_gamepads, load_gamepad_mapping, set_gamepad_mapping, start_gamepad,
+    stop_gamepad,
+};
+pub use import::*;
+pub use ink::{get_ink_config, load_ink_config, set_ink_config};
+pub use keymap::{
+    get_clicker_status, list_clickers, load_clicker_keymap, set_clicker_keymap, start_clicker,
+    stop_clicker,
+};
+pub use laser::{
+    get_laser_pointer_status, list_pointer_devices, start_laser_pointer, stop_laser_pointer,
+};
+pub use layers::{create_layer, delete_layer, list_layers, rename_layer, set_layer_visible};
+pub use linked::{get_linked_pages, link_documents, spawn_linked_page_listener, unlink_documents};
+pub use lms::export_lms_package;
+pub use locking::{lock_page_annotations, unlock_page_annotations};
+pub use media::{list_media, pause_media, play_media, seek_media};
 pub use ndi::{
-    get_capture_status, get_output_capabilities, is_ndi_available, is_syphon_available,
-    list_capture_displays, list_capture_targets, send_video_frame, start_ndi_sender,
-    start_syphon_output, stop_ndi_sender, stop_syphon_output,
+    get_blackout, get_capture_status, get_output_capabilities, get_output_viewport,
+    get_power_status, is_ndi_available, is_syphon_available, list_capture_displays,
+    list_capture_targets, send_test_pattern, send_video_frame, set_auto_throttle, set_blackout,
+    set_capture_color_correction, set_output_viewport, spawn_power_monitor, start_capture_preview,
+    start_ndi_sender, start_remote_view, start_syphon_output, stop_capture_preview,
+    stop_ndi_sender, stop_remote_view, stop_syphon_output,
+};
+pub use notes::{get_all_notes, get_page_notes, set_page_notes};
+pub use obs::{
+    connect_obs, disconnect_obs, get_obs_bindings, get_obs_status, set_obs_bindings,
+    spawn_obs_page_listener,
 };
+pub use ocr::{is_ocr_available, ocr_page};
+pub use osc::{get_osc_config, set_osc_config};
 pub use pdf::*;
+pub use prerender::{get_prerender_config, set_prerender_config, spawn_prerender_listener};
 pub use presenter::*;
+pub use presets::{
+    create_style_preset, delete_style_preset, list_style_presets, load_style_presets,
+    set_active_style_preset, update_style_preset,
+};
+pub use print::{list_printers, print_pdf};
+pub use progress::{
+    get_reading_progress_enabled, load_reading_progress, save_reading_progress,
+    set_reading_progress_enabled,
+};
+pub use qr::{clear_qr, generate_qr};
+pub use remote::{connect_remote, disconnect_remote, get_remote_status, send_remote_command};
+pub use replay::{replay_annotations, stop_annotation_replay};
+pub use report::{export_annotation_report, get_annotation_report};
+pub use search::search_annotations;
+pub use sections::{create_section, delete_section, list_sections};
+pub use show::end_show;
+pub use sidecar::{
+    get_sidecar_config, init_sidecar_store, set_document_sidecar_location, set_sidecar_encryption,
+    set_sidecar_location,
+};
+pub use stamps::{add_stamp_image, get_stamp_image};
+pub use tls::{generate_tls_certificate, get_tls_certificate, get_tls_config, set_tls_config};
+pub use transfer::{copy_annotations, transfer_annotations};
+pub use websocket::{
+    disconnect_ws_client, get_command_history, get_websocket_config, get_websocket_status,
+    get_ws_access_config, list_ws_clients, publish_page_image, restart_websocket_server,
+    set_websocket_config, set_ws_access_config, set_ws_client_role, start_websocket_server,
+    stop_websocket_server,
+};
@@ -11,28 +11,65 @@
  */
 
 use crate::error::{Result, StreamSlateError};
-use crate::state::AppState;
+use crate::state::{AppState, OutputState};
 use serde::{Deserialize, Serialize};
-use tauri::State;
+use tauri::{AppHandle, Emitter, State};
 use tracing::{debug, info, warn};
 
+use crate::capture::agenda;
+use crate::capture::caption;
+#[cfg(target_os = "macos")]
+use crate::capture::color::{self, ColorCorrection};
+#[cfg(target_os = "macos")]
+use crate::capture::fullscreen::{FullscreenWarning, FULLSCREEN_WARNING_EVENT};
+use crate::capture::laser;
+use crate::capture::mock::{self, MockCapture, TestPattern};
+#[cfg(target_os = "macos")]
+use crate::capture::power::{PowerEvent, SleepWatcher};
+#[cfg(target_os = "macos")]
+use crate::capture::preview::frame_to_data_url;
+use crate::capture::preview::{PreviewConfig, PreviewOutput};
+use crate::capture::qr;
+use crate::capture::remote_view::{RemoteViewConfig, RemoteViewOutput};
+use crate::capture::thermal::{self, PowerStatus, PowerWarning, POWER_WARNING_EVENT};
+use crate::capture::viewport::{self, OutputViewport};
 #[cfg(target_os = "macos")]
 use crate::capture::{
-    create_display_filter, create_stream_config, create_window_filter, find_display_by_id,
-    find_streamslate_window, list_capturable_displays, list_capturable_windows, CaptureConfig,
-    FrameCallback, StreamHandler,
+    capture_still, create_display_filter, create_stream_config, create_window_filter,
+    find_display_by_id, find_streamslate_window, find_window_by_id, icons,
+    list_capturable_displays, list_capturable_windows, list_window_info, window_display,
+    window_fullscreen_display, FrameCallback, StreamHandler,
 };
+use crate::capture::{CaptureConfig, CapturedFrame};
 #[cfg(target_os = "macos")]
-use screencapturekit::prelude::{SCStream, SCStreamOutputType};
+use screencapturekit::prelude::{SCContentFilter, SCStream, SCStreamOutputType};
 #[cfg(target_os = "macos")]
 use std::sync::Arc;
 
+/// Window frame in global display coordinates
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct WindowBounds {
+    pub x: f64,
+    pub y: f64,
+    pub width: f64,
+    pub height: f64,
+}
+
 /// Information about a capturable window
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CaptureTarget {
     pub id: u32,
     pub app_name: String,
     pub title: String,
+    /// Bundle identifier of the owning application
+    pub bundle_id: Option<String>,
+    /// Whether the window is currently visible on screen
+    pub is_on_screen: bool,
+    pub bounds: Option<WindowBounds>,
+    /// Application icon as a PNG data URL
+    pub icon: Option<String>,
+    /// Window thumbnail as a PNG data URL (only when requested)
+    pub thumbnail: Option<String>,
 }
 
 /// Information about a capturable display/monitor
@@ -44,8 +81,13 @@ pub struct DisplayTarget {
     pub origin_x: f64,
     pub origin_y: f64,
     pub is_primary: bool,
+    /// Display thumbnail as a PNG data URL (only when requested)
+    pub thumbnail: Option<String>,
 }
 
+/// Maximum width of picker thumbnails in pixels
+const THUMBNAIL_MAX_WIDTH: u32 = 320;
+
 /// NDI/Capture feature status
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CaptureStatus {
@@ -54,6 +96,8 @@ pub struct CaptureStatus {
     pub ndi_running: bool,
     pub syphon_available: bool,
     pub syphon_running: bool,
+    /// JPEG frames are being published to remote viewers
+    pub remote_view_running: bool,
     pub frames_captured: u64,
     pub frames_sent: u64,
     pub target_fps: u8,
@@ -66,59 +110,131 @@ pub struct OutputCapabilities {
     pub platform: String,
     pub ndi_available: bool,
     pub syphon_available: bool,
+    /// Test-pattern frames replace screen capture (`STREAMSLATE_MOCK_CAPTURE`)
+    pub mock_capture: bool,
 }
 
 /// List available windows for capture
+///
+/// Thumbnails require a screenshot per window, so they are only taken when
+/// `include_thumbnails` is set.
 #[tauri::command]
 #[cfg(target_os = "macos")]
-pub async fn list_capture_targets() -> Result<Vec<CaptureTarget>> {
-    let windows = list_capturable_windows();
+pub async fn list_capture_targets(include_thumbnails: Option<bool>) -> Result<Vec<CaptureTarget>> {
+    let include_thumbnails = include_thumbnails.unwrap_or(false);
 
-    Ok(windows
-        .into_iter()
-        .map(|(id, app_name, title)| CaptureTarget {
-            id,
-            app_name,
-            title,
-        })
-        .collect())
+    tokio::task::spawn_blocking(move || {
+        list_window_info()
+            .into_iter()
+            .map(|info| {
+                let (x, y, width, height) = info.bounds;
+                let thumbnail = if include_thumbnails && info.is_on_screen {
+                    find_window_by_id(info.id).and_then(|window| {
+                        still_data_url(&create_window_filter(&window), width, height)
+                    })
+                } else {
+                    None
+                };
+
+                CaptureTarget {
+                    id: info.id,
+                    icon: info.bundle_id.as_deref().and_then(icons::app_icon_data_url),
+                    app_name: info.app_name,
+                    title: info.title,
+                    bundle_id: info.bundle_id,
+                    is_on_screen: info.is_on_screen,
+                    bounds: Some(WindowBounds {
+                        x,
+                        y,
+                        width,
+                        height,
+                    }),
+                    thumbnail,
+                }
+            })
+            .collect()
+    })
+    .await
+    .map_err(|e| StreamSlateError::Other(format!("Failed to list capture targets: {e}")))
 }
 
 /// List available windows for capture (non-macOS stub)
 #[tauri::command]
 #[cfg(not(target_os = "macos"))]
-pub async fn list_capture_targets() -> Result<Vec<CaptureTarget>> {
+pub async fn list_capture_targets(include_thumbnails: Option<bool>) -> Result<Vec<CaptureTarget>> {
     // Screen capture not supported on this platform
+    let _ = include_thumbnails;
     Ok(vec![])
 }
 
 /// List available displays/monitors for capture
 #[tauri::command]
 #[cfg(target_os = "macos")]
-pub async fn list_capture_displays() -> Result<Vec<DisplayTarget>> {
-    let displays = list_capturable_displays();
-    let primary_id = displays.first().map(|d| d.0);
-
-    Ok(displays
-        .into_iter()
-        .map(|(id, width, height, origin_x, origin_y)| DisplayTarget {
-            id,
-            width,
-            height,
-            origin_x,
-            origin_y,
-            is_primary: Some(id) == primary_id,
-        })
-        .collect())
+pub async fn list_capture_displays(include_thumbnails: Option<bool>) -> Result<Vec<DisplayTarget>> {
+    let include_thumbnails = include_thumbnails.unwrap_or(false);
+
+    tokio::task::spawn_blocking(move || {
+        let displays = list_capturable_displays();
+        let primary_id = displays.first().map(|d| d.0);
+
+        displays
+            .into_iter()
+            .map(|(id, width, height, origin_x, origin_y)| DisplayTarget {
+                id,
+                width,
+                height,
+                origin_x,
+                origin_y,
+                is_primary: Some(id) == primary_id,
+                thumbnail: if include_thumbnails {
+                    find_display_by_id(id).and_then(|display| {
+                        still_data_url(
+                            &create_display_filter(&display),
+                            f64::from(width),
+                            f64::from(height),
+                        )
+                    })
+                } else {
+                    None
+                },
+            })
+            .collect()
+    })
+    .await
+    .map_err(|e| StreamSlateError::Other(format!("Failed to list displays: {e}")))
 }
 
 /// List available displays for capture (non-macOS stub)
 #[tauri::command]
 #[cfg(not(target_os = "macos"))]
-pub async fn list_capture_displays() -> Result<Vec<DisplayTarget>> {
+pub async fn list_capture_displays(include_thumbnails: Option<bool>) -> Result<Vec<DisplayTarget>> {
+    let _ = include_thumbnails;
     Ok(vec![])
 }
 
+/// Capture a picker thumbnail of a content filter as a PNG data URL
+#[cfg(target_os = "macos")]
+fn still_data_url(filter: &SCContentFilter, width: f64, height: f64) -> Option<String> {
+    let (thumb_width, thumb_height) = thumbnail_size(width, height);
+    let frame = capture_still(filter, thumb_width, thumb_height)?;
+    frame_to_data_url(&frame, THUMBNAIL_MAX_WIDTH)
+        .map(|(_, _, data_url)| data_url)
+        .ok()
+}
+
+/// Scale a source size to fit the thumbnail width, keeping the aspect ratio
+#[cfg_attr(not(target_os = "macos"), allow(dead_code))]
+fn thumbnail_size(width: f64, height: f64) -> (u32, u32) {
+    if width <= 0.0 || height <= 0.0 {
+        return (THUMBNAIL_MAX_WIDTH, THUMBNAIL_MAX_WIDTH * 9 / 16);
+    }
+    let scale = (f64::from(THUMBNAIL_MAX_WIDTH) / width).min(1.0);
+    (
+        ((width * scale).round() as u32).max(1),
+        ((height * scale).round() as u32).max(1),
+    )
+}
+
 /// Check if NDI feature is available
 #[tauri::command]
 pub async fn is_ndi_available() -> Result<bool> {
@@ -138,12 +254,20 @@ pub async fn get_output_capabilities() -> Result<OutputCapabilities> {
         platform: std::env::consts::OS.to_string(),
         ndi_available: cfg!(feature = "ndi"),
         syphon_available: cfg!(all(feature = "syphon", target_os = "macos")),
+        mock_capture: mock::is_enabled(),
     })
 }
 
 /// Get current capture/NDI status
 #[tauri::command]
 pub async fn get_capture_status(state: State<'_, AppState>) -> Result<CaptureStatus> {
+    let (fps_limit, remote_view_running) = {
+        let outputs = state
+            .outputs
+            .lock()
+            .map_err(|e| StreamSlateError::StateLock(e.to_string()))?;
+        (outputs.fps_limit, outputs.remote_view.is_some())
+    };
     let integration = state
         .integration
         .lock()
@@ -156,26 +280,340 @@ pub async fn get_capture_status(state: State<'_, AppState>) -> Result<CaptureSta
         syphon_available: cfg!(all(feature = "syphon", target_os = "macos")),
         syphon_running: integration.syphon_active
             && cfg!(all(feature = "syphon", target_os = "macos")),
+        remote_view_running,
         frames_captured: integration.frames_captured,
         frames_sent: integration.frames_sent,
-        target_fps: 30,
+        target_fps: fps_limit.unwrap_or(CaptureConfig::default().fps),
         current_fps: 0.0,
     })
 }
 
-/// Start native capture (and optionally NDI output) - macOS implementation
+/// Get the current thermal and battery state
+#[tauri::command]
+pub async fn get_power_status() -> Result<PowerStatus> {
+    tokio::task::spawn_blocking(thermal::read_status)
+        .await
+        .map_err(|e| StreamSlateError::Other(format!("Power status task failed: {e}")))
+}
+
+/// Enable or disable automatic output frame-rate reduction
 ///
-/// If `display_id` is provided, captures that specific display.
-/// Otherwise, captures the StreamSlate main window.
+/// While capturing, critical thermal pressure or a low battery emits an
+/// `output-power-warning` event; with auto-throttle on, the output frame
+/// rate is also reduced until conditions recover.
 #[tauri::command]
-#[cfg(target_os = "macos")]
-pub async fn start_ndi_sender(state: State<'_, AppState>, display_id: Option<u32>) -> Result<()> {
+pub async fn set_auto_throttle(enabled: bool, state: State<'_, AppState>) -> Result<()> {
+    state
+        .integration
+        .lock()
+        .map_err(|e| StreamSlateError::StateLock(e.to_string()))?
+        .auto_throttle = enabled;
+    if !enabled {
+        state
+            .outputs
+            .lock()
+            .map_err(|e| StreamSlateError::StateLock(e.to_string()))?
+            .fps_limit = None;
+    }
+    info!(enabled, "Auto-throttle updated");
+    Ok(())
+}
+
+/// Convert captured frames from the display's color profile to sRGB
+///
+/// Keeps slides looking the same whether captured from a wide-gamut
+/// built-in panel or an sRGB external monitor. Applies to running captures
+/// immediately.
+#[tauri::command]
+pub async fn set_capture_color_correction(on: bool, state: State<'_, AppState>) -> Result<()> {
+    state
+        .integration
+        .lock()
+        .map_err(|e| StreamSlateError::StateLock(e.to_string()))?
+        .color_correction = on;
+    info!(on, "Capture color correction updated");
+    Ok(())
+}
+
+/// Default easing time for output pan/zoom moves
+const VIEWPORT_DEFAULT_MS: u32 = 500;
+
+/// Longest output pan/zoom move
+const VIEWPORT_MAX_MS: u32 = 10_000;
+
+/// Pan/zoom a virtual camera over the output frame
+///
+/// Crops into the captured frame and scales it back up on every output,
+/// easing over `duration_ms` (default 500 ms) so a producer can punch in on
+/// a chart without touching the presenter's zoom. Zoom 1.0 shows the whole
+/// frame. Returns the applied (clamped) viewport and broadcasts
+/// `OUTPUT_VIEWPORT_CHANGED`.
+#[tauri::command]
+pub async fn set_output_viewport(
+    viewport: OutputViewport,
+    duration_ms: Option<u32>,
+    app: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<OutputViewport> {
+    let viewport = set_output_viewport_state(&state, &app, viewport, duration_ms)?;
+    state.broadcast(crate::websocket::WebSocketEvent::OutputViewportChanged { viewport })?;
+    Ok(viewport)
+}
+
+/// Black out every output, e.g. to pause the slides for a discussion
+#[tauri::command]
+pub async fn set_blackout(enabled: bool, app: AppHandle, state: State<'_, AppState>) -> Result<()> {
+    set_blackout_state(&state, &app, enabled)?;
+    state.broadcast(crate::websocket::WebSocketEvent::BlackoutChanged { enabled })
+}
+
+/// Whether the outputs are blacked out
+#[tauri::command]
+pub async fn get_blackout(state: State<'_, AppState>) -> Result<bool> {
+    Ok(state
+        .outputs
+        .lock()
+        .map_err(|e| StreamSlateError::StateLock(e.to_string()))?
+        .blackout)
+}
+
+/// Store the blackout flag and notify the webview
+///
+/// Shared by the Tauri command, the WebSocket handler and the clicker.
+pub(crate) fn set_blackout_state(state: &AppState, app: &AppHandle, enabled: bool) -> Result<()> {
+    state
+        .outputs
+        .lock()
+        .map_err(|e| StreamSlateError::StateLock(e.to_string()))?
+        .blackout = enabled;
+    info!(enabled, "Output blackout updated");
+
+    #[derive(Serialize, Clone)]
+    struct BlackoutPayload {
+        enabled: bool,
+    }
+    if let Err(e) = app.emit("blackout-changed", BlackoutPayload { enabled }) {
+        warn!("Failed to emit blackout change: {}", e);
+    }
+    Ok(())
+}
+
+/// Current pan/zoom target of the outputs
+#[tauri::command]
+pub async fn get_output_viewport(state: State<'_, AppState>) -> Result<OutputViewport> {
+    Ok(state
+        .outputs
+        .lock()
+        .map_err(|e| StreamSlateError::StateLock(e.to_string()))?
+        .viewport
+        .target())
+}
+
+/// Start an output pan/zoom move and notify the webview
+///
+/// Shared by the Tauri command and the WebSocket handler.
+pub(crate) fn set_output_viewport_state(
+    state: &AppState,
+    app: &AppHandle,
+    viewport: OutputViewport,
+    duration_ms: Option<u32>,
+) -> Result<OutputViewport> {
+    let duration = duration_ms
+        .unwrap_or(VIEWPORT_DEFAULT_MS)
+        .min(VIEWPORT_MAX_MS);
+    let target = {
+        let mut outputs = state
+            .outputs
+            .lock()
+            .map_err(|e| StreamSlateError::StateLock(e.to_string()))?;
+        outputs.viewport.set_target(
+            viewport,
+            std::time::Duration::from_millis(u64::from(duration)),
+            std::time::Instant::now(),
+        );
+        outputs.viewport.target()
+    };
+    info!(
+        x = target.x,
+        y = target.y,
+        zoom = target.zoom,
+        duration,
+        "Output viewport updated"
+    );
+
+    if let Err(e) = app.emit("output-viewport-changed", target) {
+        warn!("Failed to emit output viewport change: {}", e);
+    }
+    Ok(target)
+}
+
+/// Longest test pattern `send_test_pattern` will play
+const MAX_TEST_PATTERN_SECONDS: u32 = 300;
+
+/// Frame rate of the test pattern
+const TEST_PATTERN_FPS: u8 = 30;
+
+/// Push a test pattern through every active output for `seconds`
+///
+/// Replaces the live capture on NDI, Syphon and the preview so routing can
+/// be checked without loading a deck. Defaults to SMPTE-style color bars.
+/// A reference tone will follow once outputs carry audio.
+#[tauri::command]
+pub async fn send_test_pattern(
+    seconds: u32,
+    pattern: Option<TestPattern>,
+    state: State<'_, AppState>,
+) -> Result<()> {
+    if !(1..=MAX_TEST_PATTERN_SECONDS).contains(&seconds) {
+        return Err(StreamSlateError::Other(format!(
+            "Test pattern length must be 1-{} seconds",
+            MAX_TEST_PATTERN_SECONDS
+        )));
+    }
+
+    let duration = std::time::Duration::from_secs(u64::from(seconds));
+    {
+        let mut outputs = state
+            .outputs
+            .lock()
+            .map_err(|e| StreamSlateError::StateLock(e.to_string()))?;
+        if outputs.iter().next().is_none() {
+            return Err(StreamSlateError::Other(
+                "No active outputs to send a test pattern to".to_string(),
+            ));
+        }
+        let now = std::time::Instant::now();
+        if outputs.test_pattern_active(now) {
+            return Err(StreamSlateError::Other(
+                "A test pattern is already playing".to_string(),
+            ));
+        }
+        outputs.test_pattern_until = Some(now + duration);
+    }
+
+    let pattern = pattern.unwrap_or_default();
+    let config = CaptureConfig {
+        fps: TEST_PATTERN_FPS,
+        ..CaptureConfig::default()
+    };
+    let state = state.inner().clone();
+    std::thread::spawn(move || {
+        let state_for_callback = state.clone();
+        let mut capture = MockCapture::start(
+            config,
+            pattern,
+            std::sync::Arc::new(move |frame| {
+                if let Ok(mut outputs) = state_for_callback.outputs.lock() {
+                    send_to_outputs(&state_for_callback, &mut outputs, &frame);
+                }
+            }),
+        );
+        std::thread::sleep(duration);
+        capture.stop();
+
+        if let Ok(mut outputs) = state.outputs.lock() {
+            outputs.test_pattern_until = None;
+        }
+        info!("Test pattern finished");
+    });
+
+    info!(seconds, ?pattern, "Test pattern started");
+    Ok(())
+}
+
+/// How often thermal and battery state is polled while capturing
+const POWER_POLL_INTERVAL: std::time::Duration = std::time::Duration::from_secs(10);
+
+/// Watch thermal and battery state while any output is active
+///
+/// Emits `output-power-warning` when conditions become (or stop being)
+/// critical, and applies the frame-rate cap if auto-throttle is on.
+pub fn spawn_power_monitor(app: AppHandle, state: AppState) {
+    tauri::async_runtime::spawn(async move {
+        let mut last: Option<(bool, Option<u8>)> = None;
+        loop {
+            tokio::time::sleep(POWER_POLL_INTERVAL).await;
+
+            let Ok((capturing, auto_throttle)) = state
+                .integration
+                .lock()
+                .map(|i| (i.ndi_active || i.syphon_active, i.auto_throttle))
+            else {
+                continue;
+            };
+            if !capturing {
+                last = None;
+                continue;
+            }
+
+            let status = tokio::task::spawn_blocking(thermal::read_status)
+                .await
+                .unwrap_or_default();
+            let critical = status.is_critical();
+            let fps_limit = auto_throttle
+                .then(|| status.throttled_fps(CaptureConfig::default().fps))
+                .flatten();
+
+            if let Ok(mut outputs) = state.outputs.lock() {
+                outputs.fps_limit = fps_limit;
+            }
+
+            // Quiet until the first problem, then report every change
+            let current = (critical, fps_limit);
+            if last.map_or(!critical, |previous| previous == current) {
+                last = Some(current);
+                continue;
+            }
+            last = Some(current);
+
+            if critical {
+                warn!(?status, ?fps_limit, "Thermal or battery state is critical");
+            } else {
+                info!(?status, "Thermal and battery state recovered");
+            }
+            let _ = app.emit(
+                POWER_WARNING_EVENT,
+                PowerWarning {
+                    status,
+                    critical,
+                    fps_limit,
+                },
+            );
+        }
+    });
+}
+
+/// Start frame capture (and optionally NDI output)
+///
+/// On macOS, if `display_id` is provided, captures that specific display;
+/// otherwise captures `window_id`, or the StreamSlate main window. When the
+/// mock backend is enabled (`STREAMSLATE_MOCK_CAPTURE`), test-pattern frames
+/// are generated instead, on any platform.
+///
+/// A captured window that enters native fullscreen on another Space emits a
+/// `capture-fullscreen-warning` event; with `fullscreen_fallback`, capture
+/// switches to that Space's display.
+#[tauri::command]
+pub async fn start_ndi_sender(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    display_id: Option<u32>,
+    window_id: Option<u32>,
+    fullscreen_fallback: Option<bool>,
+) -> Result<()> {
+    let use_mock = mock::is_enabled();
+
     // 1. Check/Set State
     {
         let mut integration = state
             .integration
             .lock()
             .map_err(|e| StreamSlateError::StateLock(e.to_string()))?;
+        if !use_mock && !cfg!(target_os = "macos") {
+            warn!("Native capture not supported on this platform");
+            integration.ndi_active = false;
+            return Ok(());
+        }
         if integration.ndi_active {
             warn!("Capture/NDI sender already running");
             return Ok(());
@@ -187,6 +625,7 @@ pub async fn start_ndi_sender(state: State<'_, AppState>, display_id: Option<u32
     #[cfg(feature = "ndi")]
     {
         use crate::ndi::NdiSender;
+        use std::sync::Arc;
 
         match NdiSender::new("StreamSlate") {
             Ok(sender) => {
@@ -207,29 +646,32 @@ pub async fn start_ndi_sender(state: State<'_, AppState>, display_id: Option<u32
         }
     }
 
-    info!("Starting native capture...");
-
     // 3. Spawn capture thread
     let state_arc = state.inner().clone();
-    std::thread::spawn(move || {
-        if let Err(e) = run_capture_loop(state_arc, display_id) {
-            warn!("Capture loop exited with error: {:?}", e);
-        }
-    });
+    if use_mock {
+        info!("Starting mock capture...");
+        std::thread::spawn(move || run_mock_capture_loop(state_arc));
+        return Ok(());
+    }
 
-    Ok(())
-}
+    #[cfg(target_os = "macos")]
+    {
+        info!("Starting native capture...");
+        let source = match (display_id, window_id) {
+            (Some(id), _) => CaptureSource::Display(id),
+            (None, Some(id)) => CaptureSource::Window(id),
+            (None, None) => CaptureSource::StreamSlate,
+        };
+        let fullscreen_fallback = fullscreen_fallback.unwrap_or(false);
+        std::thread::spawn(move || {
+            if let Err(e) = run_capture_loop(state_arc, app, source, fullscreen_fallback) {
+                warn!("Capture loop exited with error: {:?}", e);
+            }
+        });
+    }
+    #[cfg(not(target_os = "macos"))]
+    let _ = (app, state_arc, display_id, window_id, fullscreen_fallback);
 
-/// Start native capture - non-macOS stub
-#[tauri::command]
-#[cfg(not(target_os = "macos"))]
-pub async fn start_ndi_sender(state: State<'_, AppState>, _display_id: Option<u32>) -> Result<()> {
-    warn!("Native capture not supported on this platform");
-    let mut integration = state
-        .integration
-        .lock()
-        .map_err(|e| StreamSlateError::StateLock(e.to_string()))?;
-    integration.ndi_active = false;
     Ok(())
 }
 
@@ -250,7 +692,6 @@ pub async fn stop_ndi_sender(state: State<'_, AppState>) -> Result<()> {
     }
 
     // Stop and clear the NDI sender output
-    #[cfg(target_os = "macos")]
     {
         let mut outputs = state
             .outputs
@@ -266,6 +707,25 @@ pub async fn stop_ndi_sender(state: State<'_, AppState>) -> Result<()> {
     Ok(())
 }
 
+/// Stop capture and every output
+///
+/// The capture loop sees the cleared flag and exits on its own.
+pub(crate) fn stop_all_outputs(state: &AppState) -> Result<()> {
+    {
+        let mut integration = state
+            .integration
+            .lock()
+            .map_err(|e| StreamSlateError::StateLock(e.to_string()))?;
+        integration.ndi_active = false;
+        integration.syphon_active = false;
+        integration.frames_captured = 0;
+        integration.frames_sent = 0;
+    }
+    stop_outputs(state);
+    info!("All outputs stopped");
+    Ok(())
+}
+
 /// Start Syphon output - macOS + syphon feature
 #[tauri::command]
 #[cfg(all(target_os = "macos", feature = "syphon"))]
@@ -331,7 +791,6 @@ pub async fn stop_syphon_output(state: State<'_, AppState>) -> Result<()> {
         integration.syphon_active = false;
     }
 
-    #[cfg(target_os = "macos")]
     {
         let mut outputs = state
             .outputs
@@ -347,6 +806,106 @@ pub async fn stop_syphon_output(state: State<'_, AppState>) -> Result<()> {
     Ok(())
 }
 
+/// Start pushing a low-rate preview of captured frames to the webview
+///
+/// Frames arrive as `capture-preview` events carrying a PNG data URL. The
+/// preview stops with the capture, or via `stop_capture_preview`.
+#[tauri::command]
+pub async fn start_capture_preview(
+    config: Option<PreviewConfig>,
+    app: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<()> {
+    let config = config.unwrap_or_default();
+    let preview = PreviewOutput::new(
+        config,
+        Box::new(move |frame| {
+            if let Err(e) = app.emit("capture-preview", frame) {
+                debug!("Failed to emit capture preview: {}", e);
+            }
+        }),
+    );
+
+    let mut outputs = state
+        .outputs
+        .lock()
+        .map_err(|e| StreamSlateError::StateLock(e.to_string()))?;
+    if let Some(ref previous) = outputs.preview {
+        previous.stop();
+    }
+    outputs.preview = Some(std::sync::Arc::new(preview));
+
+    info!(
+        fps = config.fps,
+        max_width = config.max_width,
+        "Capture preview started"
+    );
+    Ok(())
+}
+
+/// Stop the webview capture preview
+#[tauri::command]
+pub async fn stop_capture_preview(state: State<'_, AppState>) -> Result<()> {
+    let mut outputs = state
+        .outputs
+        .lock()
+        .map_err(|e| StreamSlateError::StateLock(e.to_string()))?;
+    if let Some(ref preview) = outputs.preview {
+        preview.stop();
+    }
+    outputs.preview = None;
+
+    info!("Capture preview stopped");
+    Ok(())
+}
+
+/// Start publishing low-bandwidth JPEG frames of the output to remote viewers
+///
+/// WebSocket clients receive the frames as binary messages after sending
+/// `SET_REMOTE_VIEW`; plain HTTP clients poll `/remote-view.jpg`. The remote
+/// view stops with the capture, or via `stop_remote_view`.
+#[tauri::command]
+pub async fn start_remote_view(
+    config: Option<RemoteViewConfig>,
+    state: State<'_, AppState>,
+) -> Result<()> {
+    let config = config.unwrap_or_default();
+    let output = RemoteViewOutput::new(config, std::sync::Arc::clone(&state.remote_view));
+
+    let mut outputs = state
+        .outputs
+        .lock()
+        .map_err(|e| StreamSlateError::StateLock(e.to_string()))?;
+    if let Some(ref previous) = outputs.remote_view {
+        previous.stop();
+    }
+    outputs.remote_view = Some(std::sync::Arc::new(output));
+
+    info!(
+        quality = config.quality,
+        fps = config.fps,
+        max_width = config.max_width,
+        "Remote view started"
+    );
+    Ok(())
+}
+
+/// Stop publishing remote view frames
+#[tauri::command]
+pub async fn stop_remote_view(state: State<'_, AppState>) -> Result<()> {
+    let mut outputs = state
+        .outputs
+        .lock()
+        .map_err(|e| StreamSlateError::StateLock(e.to_string()))?;
+    if let Some(ref output) = outputs.remote_view {
+        output.stop();
+    }
+    outputs.remote_view = None;
+
+    info!("Remote view stopped");
+    Ok(())
+}
+
 /// Send a video frame from the frontend (legacy IPC path, for benchmarking)
 #[tauri::command]
 pub async fn send_video_frame(frame_data: Vec<u8>, width: u32, height: u32) -> Result<()> {
@@ -372,16 +931,39 @@ pub async fn send_video_frame(frame_data: Vec<u8>, width: u32, height: u32) -> R
     Ok(())
 }
 
+/// What the native capture loop records
+#[cfg(target_os = "macos")]
+#[derive(Clone, Copy)]
+enum CaptureSource {
+    Display(u32),
+    Window(u32),
+    StreamSlate,
+}
+
+/// How often a captured window is checked for native fullscreen
+#[cfg(target_os = "macos")]
+const FULLSCREEN_CHECK_INTERVAL: std::time::Duration = std::time::Duration::from_secs(1);
+
+/// Delay after wake before recreating the stream, so displays can reattach
+#[cfg(target_os = "macos")]
+const WAKE_SETTLE_DELAY: std::time::Duration = std::time::Duration::from_secs(2);
+
+/// Delay between attempts to restart capture after wake
+#[cfg(target_os = "macos")]
+const WAKE_RETRY_INTERVAL: std::time::Duration = std::time::Duration::from_secs(1);
+
 /// Main capture loop using ScreenCaptureKit (macOS only)
 ///
-/// If `display_id` is Some, captures the specified display.
-/// Otherwise, captures the StreamSlate main window.
 /// Each captured frame is fanned out to whichever outputs are active
 /// (NDI, Syphon) via the `FrameOutput` handles stored in `state.outputs`.
+/// While a window is captured it is watched for native fullscreen. The
+/// stream and outputs are stopped before system sleep and rebuilt after wake.
 #[cfg(target_os = "macos")]
 fn run_capture_loop(
     state: AppState,
-    display_id: Option<u32>,
+    app: AppHandle,
+    mut source: CaptureSource,
+    fullscreen_fallback: bool,
 ) -> std::result::Result<(), Box<dyn std::error::Error>> {
     info!("Native capture loop started");
 
@@ -389,119 +971,415 @@ fn run_capture_loop(
     let config = CaptureConfig::default();
     let stream_config = create_stream_config(&config);
 
-    // Create content filter based on capture target
-    let filter = if let Some(id) = display_id {
-        // Display capture mode
-        match find_display_by_id(id) {
-            Some(sc_display) => {
-                info!(
-                    "Capturing display {} ({}x{})",
-                    id,
-                    sc_display.width(),
-                    sc_display.height()
-                );
-                create_display_filter(&sc_display)
+    let Some((filter, mut watched_window)) = resolve_capture_filter(source) else {
+        if let Ok(mut integration) = state.integration.lock() {
+            integration.ndi_active = false;
+        }
+        return Ok(());
+    };
+
+    info!("Capture config: {:?}", config);
+
+    let mut stream = Some(start_stream(
+        &state,
+        &filter,
+        &stream_config,
+        display_color_correction(source),
+    )?);
+    info!("SCStream capture started");
+
+    // Poll for stop signal (frames arrive on SCK's dispatch queue), checking
+    // for sleep/wake and the captured window for native fullscreen
+    let mut power = SleepWatcher::new();
+    let mut resume_at: Option<std::time::Instant> = None;
+    let mut last_check = std::time::Instant::now();
+    let mut warned = false;
+    while capture_active(&state) {
+        std::thread::sleep(std::time::Duration::from_millis(100));
+
+        match power.poll() {
+            Some(PowerEvent::WillSleep) => {
+                info!("System going to sleep; suspending capture");
+                if let Some(stream) = stream.take() {
+                    if let Err(e) = stream.stop_capture() {
+                        warn!("Error stopping SCStream: {:?}", e);
+                    }
+                }
+                suspend_outputs(&state);
+                resume_at = None;
+                continue;
             }
-            None => {
-                warn!("Display {} not found — cannot start capture", id);
-                if let Ok(mut integration) = state.integration.lock() {
-                    integration.ndi_active = false;
+            Some(PowerEvent::DidWake) => {
+                info!("System woke; restarting capture shortly");
+                if let Some(stream) = stream.take() {
+                    let _ = stream.stop_capture();
                 }
-                return Ok(());
+                suspend_outputs(&state);
+                resume_at = Some(std::time::Instant::now() + WAKE_SETTLE_DELAY);
+                continue;
             }
+            None => {}
         }
-    } else {
-        // Window capture mode (legacy default)
-        match find_streamslate_window() {
-            Some(w) => {
-                info!(
-                    "Capturing StreamSlate window: {} (ID: {})",
-                    w.title().unwrap_or_default(),
-                    w.window_id()
-                );
-                create_window_filter(&w)
+
+        if let Some(at) = resume_at {
+            if std::time::Instant::now() < at {
+                continue;
             }
-            None => {
-                let windows = list_capturable_windows();
-                info!("Available windows ({}):", windows.len());
-                for (wid, app, title) in windows.iter().take(5) {
-                    debug!("  - [{}] {} : {}", wid, app, title);
+            let restarted = resolve_capture_filter(source).and_then(|(filter, window)| {
+                match start_stream(
+                    &state,
+                    &filter,
+                    &stream_config,
+                    display_color_correction(source),
+                ) {
+                    Ok(restarted) => Some((restarted, window)),
+                    Err(e) => {
+                        warn!("Failed to restart SCStream after wake: {:?}", e);
+                        None
+                    }
                 }
-                warn!("StreamSlate window not found — cannot start capture");
-                if let Ok(mut integration) = state.integration.lock() {
-                    integration.ndi_active = false;
+            });
+            match restarted {
+                Some((restarted, window)) => {
+                    stream = Some(restarted);
+                    watched_window = window;
+                    resume_at = None;
+                    resume_outputs(&state);
+                    info!("Capture restarted after wake");
+                }
+                None => {
+                    resume_at = Some(std::time::Instant::now() + WAKE_RETRY_INTERVAL);
                 }
-                return Ok(());
             }
+            continue;
         }
-    };
 
-    info!("Capture config: {:?}", config);
-
-    // Build the fan-out callback: each captured frame goes to all active outputs
-    let state_for_callback = state.clone();
-    let callback: FrameCallback = Arc::new(move |frame| {
-        // Skip empty frames (no pixel data)
-        if frame.data.is_empty() {
-            return;
+        let Some(window_id) = watched_window else {
+            continue;
+        };
+        if last_check.elapsed() < FULLSCREEN_CHECK_INTERVAL {
+            continue;
         }
+        last_check = std::time::Instant::now();
 
-        let _ = state_for_callback.increment_frames_captured();
-
-        // Fan out to all active outputs
-        let outputs = match state_for_callback.outputs.lock() {
-            Ok(o) => o,
-            Err(_) => return,
+        let Some(display_id) = window_fullscreen_display(window_id) else {
+            warned = false;
+            continue;
         };
+        if warned {
+            continue;
+        }
+        warned = true;
+        warn!(
+            window_id,
+            display_id, "Captured window entered fullscreen on another Space"
+        );
 
-        if let Some(ref ndi) = outputs.ndi_sender {
-            if ndi.is_running() {
-                if let Err(e) = ndi.send_frame(&frame) {
-                    debug!("NDI send_frame error: {}", e);
-                } else {
-                    let _ = state_for_callback.increment_frames_sent();
+        let mut fallback_active = false;
+        let mut failed = None;
+        if fullscreen_fallback {
+            if let Some(display) = find_display_by_id(display_id) {
+                if let Some(stream) = stream.take() {
+                    if let Err(e) = stream.stop_capture() {
+                        warn!("Error stopping SCStream: {:?}", e);
+                    }
+                }
+                source = CaptureSource::Display(display_id);
+                match start_stream(
+                    &state,
+                    &create_display_filter(&display),
+                    &stream_config,
+                    display_color_correction(source),
+                ) {
+                    Ok(started) => {
+                        stream = Some(started);
+                        watched_window = None;
+                        fallback_active = true;
+                        info!(
+                            display_id,
+                            "Switched to display capture of the fullscreen Space"
+                        );
+                    }
+                    Err(e) => {
+                        warn!(
+                            display_id,
+                            "Failed to capture the fullscreen Space: {:?}", e
+                        );
+                        failed = Some(e);
+                    }
                 }
             }
         }
 
-        if let Some(ref syphon) = outputs.syphon_server {
-            if syphon.is_running() {
-                if let Err(e) = syphon.send_frame(&frame) {
-                    debug!("Syphon send_frame error: {}", e);
-                } else {
-                    let _ = state_for_callback.increment_frames_sent();
-                }
+        let _ = app.emit(
+            FULLSCREEN_WARNING_EVENT,
+            FullscreenWarning {
+                window_id,
+                display_id,
+                fallback_active,
+            },
+        );
+
+        // The old stream is already stopped, so capture ends here
+        if let Some(e) = failed {
+            stop_outputs(&state);
+            let _ = state.reset_frame_counters();
+            if let Ok(mut integration) = state.integration.lock() {
+                integration.ndi_active = false;
             }
+            return Err(e);
+        }
+    }
+
+    // Stop stream
+    if let Some(stream) = stream {
+        if let Err(e) = stream.stop_capture() {
+            warn!("Error stopping SCStream: {:?}", e);
         }
+    }
+
+    stop_outputs(&state);
+    let _ = state.reset_frame_counters();
+    info!("Capture loop stopped");
+    Ok(())
+}
+
+/// Conversion from the source's display profile to sRGB, if one is needed
+#[cfg(target_os = "macos")]
+fn display_color_correction(source: CaptureSource) -> Option<Arc<ColorCorrection>> {
+    let display_id = match source {
+        CaptureSource::Display(id) => id,
+        CaptureSource::Window(id) => window_display(id)?,
+        CaptureSource::StreamSlate => window_display(find_streamslate_window()?.window_id())?,
+    };
+    let correction =
+        color::display_icc_profile(display_id).and_then(|icc| ColorCorrection::from_icc(&icc));
+    debug!(
+        display_id,
+        needed = correction.is_some(),
+        "Resolved display color correction"
+    );
+    correction.map(Arc::new)
+}
+
+/// Build the content filter for a capture source, plus the window to watch
+/// for fullscreen (if a window is captured)
+#[cfg(target_os = "macos")]
+fn resolve_capture_filter(source: CaptureSource) -> Option<(SCContentFilter, Option<u32>)> {
+    let window = match source {
+        CaptureSource::Display(id) => {
+            let Some(sc_display) = find_display_by_id(id) else {
+                warn!("Display {} not found — cannot start capture", id);
+                return None;
+            };
+            info!(
+                "Capturing display {} ({}x{})",
+                id,
+                sc_display.width(),
+                sc_display.height()
+            );
+            return Some((create_display_filter(&sc_display), None));
+        }
+        CaptureSource::Window(id) => find_window_by_id(id),
+        CaptureSource::StreamSlate => find_streamslate_window(),
+    };
+
+    match window {
+        Some(w) => {
+            info!(
+                "Capturing window: {} (ID: {})",
+                w.title().unwrap_or_default(),
+                w.window_id()
+            );
+            Some((create_window_filter(&w), Some(w.window_id())))
+        }
+        None => {
+            let windows = list_capturable_windows();
+            info!("Available windows ({}):", windows.len());
+            for (wid, app, title) in windows.iter().take(5) {
+                debug!("  - [{}] {} : {}", wid, app, title);
+            }
+            warn!("Capture window not found — cannot start capture");
+            None
+        }
+    }
+}
+
+/// Start an SCStream whose frames fan out to all active outputs
+///
+/// With `correction`, frames are converted from the display profile to sRGB
+/// while `set_capture_color_correction` is on.
+#[cfg(target_os = "macos")]
+fn start_stream(
+    state: &AppState,
+    filter: &SCContentFilter,
+    stream_config: &screencapturekit::prelude::SCStreamConfiguration,
+    correction: Option<Arc<ColorCorrection>>,
+) -> std::result::Result<SCStream, Box<dyn std::error::Error>> {
+    let state_for_callback = state.clone();
+    let callback: FrameCallback = Arc::new(move |mut frame| {
+        if let Some(ref correction) = correction {
+            if color_correction_enabled(&state_for_callback) {
+                correction.apply(&mut frame);
+            }
+        }
+        fan_out_frame(&state_for_callback, &frame)
     });
 
-    // Create stream with handler and start capture
     let handler = StreamHandler::with_callback(callback);
-    let mut stream = SCStream::new(&filter, &stream_config);
+    let mut stream = SCStream::new(filter, stream_config);
     stream.add_output_handler(handler, SCStreamOutputType::Screen);
     stream.start_capture()?;
+    Ok(stream)
+}
 
-    info!("SCStream capture started");
+/// Mock capture loop: fans synthesized test-pattern frames out to the outputs
+fn run_mock_capture_loop(state: AppState) {
+    let state_for_callback = state.clone();
+    let mut capture = MockCapture::start(
+        mock::config_from_env(),
+        TestPattern::default(),
+        std::sync::Arc::new(move |frame| fan_out_frame(&state_for_callback, &frame)),
+    );
 
-    // Poll for stop signal (frames arrive on SCK's dispatch queue)
-    loop {
-        let active = state
-            .integration
-            .lock()
-            .map(|i| i.ndi_active)
-            .unwrap_or(false);
-        if !active {
-            break;
+    wait_for_capture_stop(&state);
+    capture.stop();
+
+    stop_outputs(&state);
+    let _ = state.reset_frame_counters();
+    info!("Mock capture loop stopped");
+}
+
+/// Send a frame to every active output, updating the frame counters
+fn fan_out_frame(state: &AppState, frame: &CapturedFrame) {
+    // Skip empty frames (no pixel data)
+    if frame.data.is_empty() {
+        return;
+    }
+
+    let _ = state.increment_frames_captured();
+
+    let mut outputs = match state.outputs.lock() {
+        Ok(o) => o,
+        Err(_) => return,
+    };
+    if outputs.test_pattern_active(std::time::Instant::now()) {
+        return;
+    }
+    send_to_outputs(state, &mut outputs, frame);
+}
+
+/// Deliver a frame to the NDI, Syphon, preview and remote view outputs
+fn send_to_outputs(state: &AppState, outputs: &mut OutputState, frame: &CapturedFrame) {
+    let now = std::time::Instant::now();
+    if !outputs.take_frame_slot(now) {
+        return;
+    }
+
+    let view = outputs.viewport.current(now);
+    if let Some(laser) = outputs.laser.as_mut() {
+        laser.set_frame_size(frame.width, frame.height);
+    }
+    let laser = outputs.laser.as_ref().filter(|laser| laser.is_visible(now));
+    let mut composed;
+    let frame = if outputs.blackout {
+        // Keep sending frames so receivers show black rather than a frozen slide
+        composed = frame.clone();
+        for pixel in composed.data.chunks_exact_mut(4) {
+            pixel.copy_from_slice(&[0, 0, 0, 0xFF]);
+        }
+        &composed
+    } else if outputs.agenda_strip.is_none()
+        && outputs.checkpoint_code.is_none()
+        && outputs.qr_code.is_none()
+        && laser.is_none()
+        && view.is_identity()
+    {
+        frame
+    } else {
+        composed = if view.is_identity() {
+            frame.clone()
+        } else {
+            viewport::apply(frame, view)
+        };
+        if let Some(agenda) = &outputs.agenda_strip {
+            agenda::draw_strip(&mut composed, agenda);
+        }
+        if let Some(code) = &outputs.checkpoint_code {
+            caption::draw(&mut composed, code);
+        }
+        if let Some(overlay) = &outputs.qr_code {
+            qr::draw(&mut composed, &overlay.code);
+        }
+        if let Some(laser) = laser {
+            laser::draw(&mut composed, laser);
+        }
+        &composed
+    };
+
+    if let Some(ref ndi) = outputs.ndi_sender {
+        if ndi.is_running() {
+            if let Err(e) = ndi.send_frame(frame) {
+                debug!("NDI send_frame error: {}", e);
+            } else {
+                let _ = state.increment_frames_sent();
+            }
         }
-        std::thread::sleep(std::time::Duration::from_millis(100));
     }
 
-    // Stop stream
-    if let Err(e) = stream.stop_capture() {
-        warn!("Error stopping SCStream: {:?}", e);
+    if let Some(ref syphon) = outputs.syphon_server {
+        if syphon.is_running() {
+            if let Err(e) = syphon.send_frame(frame) {
+                debug!("Syphon send_frame error: {}", e);
+            } else {
+                let _ = state.increment_frames_sent();
+            }
+        }
+    }
+
+    // The preview is local-only, so it doesn't count towards frames sent
+    if let Some(ref preview) = outputs.preview {
+        if let Err(e) = preview.send_frame(frame) {
+            debug!("Preview send_frame error: {}", e);
+        }
+    }
+
+    // Nor does the remote view, which drops most frames by design
+    if let Some(ref remote_view) = outputs.remote_view {
+        if let Err(e) = remote_view.send_frame(frame) {
+            debug!("Remote view send_frame error: {}", e);
+        }
     }
+}
+
+/// Whether captured frames should be converted to sRGB
+#[cfg_attr(not(target_os = "macos"), allow(dead_code))]
+fn color_correction_enabled(state: &AppState) -> bool {
+    state
+        .integration
+        .lock()
+        .map(|i| i.color_correction)
+        .unwrap_or(false)
+}
 
-    // Stop all outputs
+/// Whether capture is still wanted (cleared by `stop_ndi_sender`)
+fn capture_active(state: &AppState) -> bool {
+    state
+        .integration
+        .lock()
+        .map(|i| i.ndi_active)
+        .unwrap_or(false)
+}
+
+/// Block until `stop_ndi_sender` clears the active flag
+fn wait_for_capture_stop(state: &AppState) {
+    while capture_active(state) {
+        std::thread::sleep(std::time::Duration::from_millis(100));
+    }
+}
+
+/// Stop and clear all outputs
+fn stop_outputs(state: &AppState) {
     if let Ok(mut outputs) = state.outputs.lock() {
         if let Some(ref sender) = outputs.ndi_sender {
             sender.stop();
@@ -511,13 +1389,39 @@ fn run_capture_loop(
             server.stop();
         }
         outputs.syphon_server = None;
+        if let Some(ref preview) = outputs.preview {
+            preview.stop();
+        }
+        outputs.preview = None;
+        if let Some(ref remote_view) = outputs.remote_view {
+            remote_view.stop();
+        }
+        outputs.remote_view = None;
     } else {
         warn!("Failed to lock outputs state during capture cleanup");
     }
+}
 
-    let _ = state.reset_frame_counters();
-    info!("Capture loop stopped");
-    Ok(())
+/// Stop every output without clearing it, so it can be resumed after wake
+#[cfg(target_os = "macos")]
+fn suspend_outputs(state: &AppState) {
+    if let Ok(outputs) = state.outputs.lock() {
+        for output in outputs.iter() {
+            output.stop();
+        }
+    }
+}
+
+/// Restart outputs stopped by `suspend_outputs`
+#[cfg(target_os = "macos")]
+fn resume_outputs(state: &AppState) {
+    if let Ok(outputs) = state.outputs.lock() {
+        for output in outputs.iter() {
+            if let Err(e) = output.resume() {
+                warn!("Failed to resume output after wake: {}", e);
+            }
+        }
+    }
 }
 
 #[cfg(test)]
@@ -532,4 +1436,11 @@ mod tests {
         // This will be true or false depending on how tests are run
         println!("NDI feature enabled: {}", available);
     }
+
+    #[test]
+    fn test_thumbnail_size() {
+        assert_eq!(thumbnail_size(1920.0, 1080.0), (320, 180));
+        assert_eq!(thumbnail_size(200.0, 100.0), (200, 100));
+        assert_eq!(thumbnail_size(0.0, 0.0), (320, 180));
+    }
 }
@@ -0,0 +1,144 @@
+/*
+ * This file is part of StreamSlate.
+ * Copyright (C) 2025 StreamSlate Contributors
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+
+//! Speaker notes commands
+//!
+//! Plain-text notes per page, stored in the annotations sidecar so they
+//! travel with the document. They are never drawn on the outputs; changes
+//! are emitted as `notes-updated` and broadcast as `NOTES_UPDATED` for
+//! confidence monitors, which fetch a page's notes with `GET_NOTES`.
+
+use super::annotations::{read_sidecar, write_sidecar};
+use super::sections::open_document;
+use crate::error::{Result, StreamSlateError};
+use crate::state::AppState;
+use crate::websocket::WebSocketEvent;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use tauri::{AppHandle, Emitter, State};
+use tracing::{info, instrument, warn};
+
+/// Longest note accepted for one page
+const MAX_NOTE_CHARS: usize = 20_000;
+
+/// Payload of the `notes-updated` event
+#[derive(Debug, Clone, Serialize)]
+struct NotesUpdated<'a> {
+    page: u32,
+    text: &'a str,
+}
+
+/// Notes of every page of the open document that has any
+#[tauri::command]
+#[instrument(skip(state))]
+pub async fn get_all_notes(state: State<'_, AppState>) -> Result<BTreeMap<u32, String>> {
+    let (pdf_path, _) = open_document(&state)?;
+    Ok(read_sidecar(&pdf_path)?.notes)
+}
+
+/// Notes of a page (1-indexed) of the open document, empty if it has none
+#[tauri::command]
+#[instrument(skip(state))]
+pub async fn get_page_notes(page: u32, state: State<'_, AppState>) -> Result<String> {
+    notes_for_page(&state, page)
+}
+
+/// Replace a page's notes; empty text removes them
+#[tauri::command]
+#[instrument(skip(state, app, text))]
+pub async fn set_page_notes(
+    page: u32,
+    text: String,
+    state: State<'_, AppState>,
+    app: AppHandle,
+) -> Result<()> {
+    let (pdf_path, total_pages) = open_document(&state)?;
+    check_page(page, total_pages)?;
+    if text.chars().count() > MAX_NOTE_CHARS {
+        return Err(StreamSlateError::Other(format!(
+            "Notes are limited to {MAX_NOTE_CHARS} characters per page"
+        )));
+    }
+
+    let mut file = read_sidecar(&pdf_path)?;
+    if !apply_note(&mut file.notes, page, &text) {
+        return Ok(());
+    }
+    write_sidecar(&mut file)?;
+    info!(page, chars = text.len(), "Speaker notes updated");
+
+    let text = file.notes.get(&page).cloned().unwrap_or_default();
+    if let Err(e) = app.emit("notes-updated", NotesUpdated { page, text: &text }) {
+        warn!("Failed to emit notes-updated event: {}", e);
+    }
+    if let Err(e) = state.broadcast(WebSocketEvent::NotesUpdated { page, text }) {
+        warn!("Failed to broadcast notes change: {}", e);
+    }
+    Ok(())
+}
+
+/// Notes of a page of the open document
+pub(crate) fn notes_for_page(state: &AppState, page: u32) -> Result<String> {
+    let (pdf_path, total_pages) = open_document(state)?;
+    check_page(page, total_pages)?;
+    Ok(read_sidecar(&pdf_path)?
+        .notes
+        .remove(&page)
+        .unwrap_or_default())
+}
+
+fn check_page(page: u32, total_pages: u32) -> Result<()> {
+    if page == 0 || page > total_pages {
+        return Err(StreamSlateError::PageOutOfRange { page, total_pages });
+    }
+    Ok(())
+}
+
+/// Store `text` as the page's notes, trimmed, or remove them when blank
+///
+/// Returns whether the notes changed.
+fn apply_note(notes: &mut BTreeMap<u32, String>, page: u32, text: &str) -> bool {
+    let text = text.trim();
+    if text.is_empty() {
+        return notes.remove(&page).is_some();
+    }
+    if notes.get(&page).map(String::as_str) == Some(text) {
+        return false;
+    }
+    notes.insert(page, text.to_string());
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_apply_note() {
+        let mut notes = BTreeMap::new();
+        assert!(apply_note(&mut notes, 3, "  Pause for questions\n"));
+        assert_eq!(notes[&3], "Pause for questions");
+        assert!(!apply_note(&mut notes, 3, "Pause for questions"));
+        assert!(apply_note(&mut notes, 3, "  "));
+        assert!(notes.is_empty());
+        assert!(!apply_note(&mut notes, 3, ""));
+        assert!(check_page(0, 10).is_err());
+        assert!(check_page(11, 10).is_err());
+        assert!(check_page(10, 10).is_ok());
+    }
+}
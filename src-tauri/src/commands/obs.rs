@@ -0,0 +1,289 @@
+/*
+ * This file is part of StreamSlate.
+ * Copyright (C) 2025 StreamSlate Contributors
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+
+//! OBS integration commands
+//!
+//! Connects to OBS over obs-websocket and manages bindings that show or hide
+//! OBS sources when the presentation reaches a page or an annotation layer
+//! is toggled (e.g. revealing an "answers" overlay with the answers layer).
+//! Recording starts are timed against the page timing session so its report
+//! can place each page on the recording.
+
+use super::pdf::listen_page_changes;
+use crate::error::{Result, StreamSlateError};
+use crate::obs::bindings::{self, ObsBinding, ObsTrigger};
+use crate::obs::{
+    ObsConnection, ObsEvent, RecordingSync, DEFAULT_PORT, RECORD_STARTED, RECORD_STOPPED,
+};
+use crate::settings::{load_settings, save_settings};
+use crate::state::AppState;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tauri::{AppHandle, Emitter, State};
+use tokio::sync::broadcast::{self, error::RecvError};
+use tracing::{debug, info, instrument, warn};
+
+/// Connection status of the OBS integration
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ObsStatus {
+    pub url: Option<String>,
+    pub connected: bool,
+    pub bindings: usize,
+    /// The recording in progress, if any
+    pub recording: Option<RecordingSync>,
+}
+
+/// Connect to OBS, replacing any existing connection
+///
+/// Defaults to `localhost:4455`. `password` is required when OBS has
+/// authentication enabled. A recording already in progress is synced before
+/// returning.
+#[tauri::command]
+#[instrument(skip(password, app, state))]
+pub async fn connect_obs(
+    host: Option<String>,
+    port: Option<u16>,
+    password: Option<String>,
+    app: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<ObsStatus> {
+    let host = host.unwrap_or_else(|| "localhost".to_string());
+    let connection =
+        ObsConnection::connect(&host, port.unwrap_or(DEFAULT_PORT), password.as_deref())
+            .await
+            .map_err(StreamSlateError::WebSocket)?;
+
+    let events = connection.subscribe();
+    let recording = match connection.recording_sync().await {
+        Ok(recording) => recording,
+        Err(e) => {
+            warn!(error = %e, "Failed to read OBS recording status");
+            None
+        }
+    };
+
+    let previous = obs_state(&state)?.connection.replace(connection);
+    if let Some(previous) = previous {
+        previous.close();
+    }
+    set_obs_connected(&state, true)?;
+    set_recording(&app, &state, recording);
+    spawn_recording_watch(app, state.inner().clone(), events);
+    obs_status(&state)
+}
+
+/// Disconnect from OBS
+#[tauri::command]
+#[instrument(skip(state))]
+pub async fn disconnect_obs(state: State<'_, AppState>) -> Result<()> {
+    let connection = {
+        let mut obs = obs_state(&state)?;
+        obs.recording = None;
+        obs.connection.take()
+    };
+    if let Some(connection) = connection {
+        connection.close();
+        info!(url = %connection.url(), "Disconnected from OBS");
+    }
+    set_obs_connected(&state, false)
+}
+
+/// Current OBS connection status
+#[tauri::command]
+#[instrument(skip(state))]
+pub async fn get_obs_status(state: State<'_, AppState>) -> Result<ObsStatus> {
+    obs_status(&state)
+}
+
+/// List the cue-to-source bindings
+#[tauri::command]
+#[instrument(skip(state))]
+pub async fn get_obs_bindings(state: State<'_, AppState>) -> Result<Vec<ObsBinding>> {
+    Ok(obs_state(&state)?.bindings.clone())
+}
+
+/// Replace the cue-to-source bindings and persist them
+///
+/// Each binding shows or hides an OBS source (in a given scene, or the
+/// current program scene) when its trigger fires: arriving at a page, or an
+/// annotation layer being shown or hidden.
+#[tauri::command]
+#[instrument(skip(app, state))]
+pub async fn set_obs_bindings(
+    bindings: Vec<ObsBinding>,
+    app: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<()> {
+    for binding in &bindings {
+        binding.validate()?;
+    }
+
+    save_settings(&app, bindings::BINDINGS_FILE, &bindings)?;
+    info!(count = bindings.len(), "OBS bindings updated");
+    obs_state(&state)?.bindings = bindings;
+    Ok(())
+}
+
+/// Apply every binding matching `trigger` in the background
+///
+/// Does nothing while OBS is disconnected. Failures (e.g. a renamed source)
+/// are logged rather than interrupting the presentation.
+pub(crate) fn fire_obs_trigger(state: &AppState, trigger: ObsTrigger) {
+    let (connection, matched) = {
+        let Ok(obs) = state.obs.lock() else {
+            return;
+        };
+        let Some(connection) = obs.connection.clone().filter(ObsConnection::is_connected) else {
+            return;
+        };
+        let matched: Vec<ObsBinding> = bindings::matching(&obs.bindings, &trigger)
+            .cloned()
+            .collect();
+        (connection, matched)
+    };
+    if matched.is_empty() {
+        return;
+    }
+
+    debug!(?trigger, count = matched.len(), "Firing OBS bindings");
+    tauri::async_runtime::spawn(async move {
+        for binding in matched {
+            if let Err(e) = connection
+                .set_source_visible(binding.scene.as_deref(), &binding.source, binding.visible)
+                .await
+            {
+                warn!(source = %binding.source, error = %e, "OBS binding failed");
+            }
+        }
+    });
+}
+
+/// Follow recording starts and stops until the connection is dropped
+fn spawn_recording_watch(
+    app: AppHandle,
+    state: AppState,
+    mut events: broadcast::Receiver<ObsEvent>,
+) {
+    tauri::async_runtime::spawn(async move {
+        loop {
+            let event = match events.recv().await {
+                Ok(event) => event,
+                Err(RecvError::Lagged(skipped)) => {
+                    debug!(skipped, "Missed OBS events");
+                    continue;
+                }
+                Err(RecvError::Closed) => break,
+            };
+            if event.event_type != "RecordStateChanged" {
+                continue;
+            }
+            match event.data.get("outputState").and_then(Value::as_str) {
+                Some(RECORD_STARTED) => {
+                    let Some(connection) = obs_state(&state)
+                        .ok()
+                        .and_then(|obs| obs.connection.clone())
+                    else {
+                        break;
+                    };
+                    match connection.recording_sync().await {
+                        Ok(recording) => set_recording(&app, &state, recording),
+                        Err(e) => warn!(error = %e, "Failed to sync OBS recording"),
+                    }
+                }
+                Some(RECORD_STOPPED) => set_recording(&app, &state, None),
+                _ => {}
+            }
+        }
+    });
+}
+
+/// Store the recording in progress and attach it to the timing session
+///
+/// A session keeps the last recording it saw after the recording stops, so
+/// its report still lines up with the video.
+fn set_recording(app: &AppHandle, state: &AppState, recording: Option<RecordingSync>) {
+    if let Ok(mut obs) = state.obs.lock() {
+        if obs.recording == recording {
+            return;
+        }
+        obs.recording = recording.clone();
+    }
+    if let Some(sync) = &recording {
+        info!(started_at = %sync.started_at, round_trip_ms = sync.round_trip_ms, "OBS recording synced");
+        if let Ok(mut analytics) = state.analytics.lock() {
+            if let Some(timer) = analytics.session.as_mut() {
+                timer.set_recording(recording.clone());
+            }
+        }
+    }
+    if let Err(e) = app.emit("obs-recording-changed", &recording) {
+        warn!(error = %e, "Failed to emit obs-recording-changed");
+    }
+}
+
+/// Timing of the recording in progress, to attach to a new timing session
+pub(crate) fn current_recording(state: &AppState) -> Option<RecordingSync> {
+    state.obs.lock().ok()?.recording.clone()
+}
+
+/// Load stored bindings and fire page bindings on every page change
+pub fn spawn_obs_page_listener(app: &AppHandle, state: AppState) {
+    let stored: Vec<ObsBinding> = load_settings(app, bindings::BINDINGS_FILE);
+    info!(count = stored.len(), "Loaded OBS bindings");
+    if let Ok(mut obs) = state.obs.lock() {
+        obs.bindings = stored;
+    }
+
+    listen_page_changes(app, move |page| {
+        let changed = state
+            .obs
+            .lock()
+            .map(|mut obs| obs.last_page.replace(page) != Some(page))
+            .unwrap_or(false);
+        if changed {
+            fire_obs_trigger(&state, ObsTrigger::Page { page });
+        }
+    });
+}
+
+fn obs_state(state: &AppState) -> Result<std::sync::MutexGuard<'_, crate::state::ObsState>> {
+    state
+        .obs
+        .lock()
+        .map_err(|e| StreamSlateError::StateLock(format!("OBS: {e}")))
+}
+
+fn set_obs_connected(state: &AppState, connected: bool) -> Result<()> {
+    state
+        .integration
+        .lock()
+        .map_err(|e| StreamSlateError::StateLock(e.to_string()))?
+        .obs_connected = connected;
+    Ok(())
+}
+
+fn obs_status(state: &AppState) -> Result<ObsStatus> {
+    let obs = obs_state(state)?;
+    let connection = obs.connection.as_ref();
+    Ok(ObsStatus {
+        url: connection.map(|c| c.url().to_string()),
+        connected: connection.is_some_and(ObsConnection::is_connected),
+        bindings: obs.bindings.len(),
+        recording: obs.recording.clone(),
+    })
+}
@@ -0,0 +1,124 @@
+/*
+ * This file is part of StreamSlate.
+ * Copyright (C) 2025 StreamSlate Contributors
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+
+//! OCR commands
+//!
+//! Recognises text on scanned PDF pages and image-deck slides. Results are
+//! cached per page and served by `get_page_words` (and the WebSocket
+//! `GET_PAGE_WORDS` command) wherever the page has no text layer.
+
+use crate::error::Result;
+#[cfg(feature = "ocr")]
+use crate::error::StreamSlateError;
+#[cfg(feature = "ocr")]
+use crate::pdf::ocr;
+#[cfg(not(feature = "ocr"))]
+use crate::pdf::text::PageWord;
+use crate::state::AppState;
+#[cfg(not(feature = "ocr"))]
+use serde::{Deserialize, Serialize};
+use tauri::State;
+#[cfg(feature = "ocr")]
+use tracing::info;
+use tracing::instrument;
+
+/// Recognised text of a page
+#[cfg(feature = "ocr")]
+pub use crate::pdf::ocr::OcrPage;
+
+/// Recognised text of a page (mirrors `pdf::ocr::OcrPage`)
+#[cfg(not(feature = "ocr"))]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OcrPage {
+    pub page: u32,
+    pub text: String,
+    pub words: Vec<PageWord>,
+    pub confidence: f64,
+}
+
+/// Check if OCR support is compiled in
+#[tauri::command]
+pub async fn is_ocr_available() -> Result<bool> {
+    Ok(cfg!(feature = "ocr"))
+}
+
+/// Run OCR on a page (1-indexed) of the open PDF or image deck
+///
+/// `language` is a tesseract language code such as `eng` or `deu+eng`.
+/// Recognition runs off the async runtime; the word boxes are cached for
+/// `get_page_words`.
+#[tauri::command]
+#[instrument(skip(state))]
+#[cfg(feature = "ocr")]
+pub async fn ocr_page(
+    page: u32,
+    language: Option<String>,
+    state: State<'_, AppState>,
+) -> Result<OcrPage> {
+    let language = language.unwrap_or_else(|| ocr::DEFAULT_LANGUAGE.to_string());
+    let deck = state.get_image_deck()?;
+    let document = state.get_pdf_document()?;
+
+    let result = tokio::task::spawn_blocking(move || {
+        if let Some(deck) = deck {
+            let slide = deck.page(page).ok_or_else(|| {
+                StreamSlateError::Other(format!(
+                    "Page {} not found (deck has {} pages)",
+                    page,
+                    deck.pages.len()
+                ))
+            })?;
+            return ocr::ocr_image_file(
+                std::path::Path::new(&slide.path),
+                page,
+                slide.width,
+                slide.height,
+                &language,
+            );
+        }
+
+        let document = document.ok_or(StreamSlateError::NoDocument)?;
+        ocr::ocr_pdf_page(&document, page, &language)
+    })
+    .await
+    .map_err(|e| StreamSlateError::Other(format!("OCR task failed: {e}")))??;
+
+    info!(
+        page,
+        words = result.words.len(),
+        confidence = result.confidence,
+        "OCR finished"
+    );
+    state.set_ocr_words(page, result.words.clone())?;
+    Ok(result)
+}
+
+/// Run OCR on a page (stub when built without the `ocr` feature)
+#[tauri::command]
+#[instrument(skip(state))]
+#[cfg(not(feature = "ocr"))]
+pub async fn ocr_page(
+    page: u32,
+    language: Option<String>,
+    state: State<'_, AppState>,
+) -> Result<OcrPage> {
+    let _ = (language, state);
+    Err(crate::error::StreamSlateError::Other(format!(
+        "Cannot OCR page {page}: StreamSlate was built without the 'ocr' feature"
+    )))
+}
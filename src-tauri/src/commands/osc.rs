@@ -0,0 +1,70 @@
+/*
+ * This file is part of StreamSlate.
+ * Copyright (C) 2025 StreamSlate Contributors
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+
+//! OSC endpoint commands
+
+use crate::error::{Result, StreamSlateError};
+use crate::osc::config::{self, OscConfig};
+use crate::osc::server;
+use crate::settings::{load_settings, save_settings};
+use crate::state::AppState;
+use std::sync::Arc;
+use tauri::{AppHandle, State};
+use tracing::{info, instrument};
+
+/// The stored OSC settings
+#[tauri::command]
+#[instrument(skip(app))]
+pub async fn get_osc_config(app: AppHandle) -> Result<OscConfig> {
+    Ok(load_settings(&app, config::CONFIG_FILE))
+}
+
+/// Turn the OSC endpoint on or off or move it, and persist the choice
+///
+/// The endpoint restarts right away; settings it can't listen with aren't
+/// saved.
+#[tauri::command]
+#[instrument(skip(app, state))]
+pub async fn set_osc_config(
+    config: OscConfig,
+    app: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<()> {
+    config.validate()?;
+
+    if !crate::is_remote_client_mode() {
+        let state = Arc::new(state.inner().clone());
+        server::start(&config, state, app.clone())
+            .await
+            .map_err(|e| {
+                StreamSlateError::Other(format!(
+                    "Can't listen for OSC on {}:{}: {e}",
+                    config.bind_addr, config.port
+                ))
+            })?;
+    }
+
+    save_settings(&app, config::CONFIG_FILE, &config)?;
+    info!(
+        enabled = config.enabled,
+        port = config.port,
+        targets = config.targets.len(),
+        "OSC settings updated"
+    );
+    Ok(())
+}
@@ -21,11 +21,25 @@
 //! This module provides commands for opening, closing, and querying PDF documents.
 //! PDF parsing is handled by the lopdf crate.
 
+use super::analytics::{finish_page_timing, start_page_timing};
+use super::progress::restore_reading_progress;
 use crate::error::{Result, StreamSlateError};
-use crate::state::AppState;
+use crate::pdf::attachments::{self, PdfAttachment};
+use crate::pdf::compare::{self, PageStatus, PdfComparison};
+use crate::pdf::optimize::{self, OptimizeOptions, OptimizeReport};
+use crate::pdf::sanitize::{self, SanitizeReport};
+use crate::pdf::signatures::{self, PdfSignature};
+use crate::pdf::text::{self, PageWord};
+use crate::pdf::validate;
+use crate::progress::ReadingProgress;
+use crate::state::{AppState, CropRect, DocumentKind};
 use serde::{Deserialize, Serialize};
-use std::path::PathBuf;
-use tauri::State;
+use std::collections::HashMap;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use tauri::{AppHandle, Emitter, Listener, State};
 use tracing::{debug, info, instrument, warn};
 
 /// Information about an opened PDF file
@@ -38,30 +52,211 @@ pub struct PdfInfo {
     pub file_size: u64,
     pub created: Option<String>,
     pub modified: Option<String>,
+    /// Stored reading progress the document was reopened at
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub restored: Option<ReadingProgress>,
 }
 
 /// Information about a specific page in the PDF
+///
+/// With a crop set, `width`/`height` are those of the visible region and
+/// `crop` locates it on the page.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PdfPage {
     pub page_number: u32,
     pub width: f64,
     pub height: f64,
     pub rotation: u32,
+    #[serde(default)]
+    pub crop: Option<CropRect>,
+}
+
+impl PdfPage {
+    /// Restrict the page to the document's crop region, if any
+    fn cropped(mut self, crop: Option<CropRect>) -> Self {
+        if let Some(rect) = crop.and_then(|c| c.clamp_to(self.width, self.height)) {
+            self.width = rect.width;
+            self.height = rect.height;
+            self.crop = Some(rect);
+        }
+        self
+    }
+}
+
+/// Stage of an in-progress `open_pdf`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PdfLoadStage {
+    Reading,
+    Validating,
+    Parsing,
+    Finishing,
 }
 
+/// Payload of the `pdf-load-progress` event
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PdfLoadProgress {
+    pub path: String,
+    pub stage: PdfLoadStage,
+    /// Overall progress, 0-100
+    pub percent: u8,
+}
+
+/// Bytes read between progress reports and cancellation checks
+const READ_CHUNK: usize = 4 * 1024 * 1024;
+
 /// Open a PDF file and return basic information about it
 ///
 /// This command loads the PDF using lopdf, extracts metadata,
 /// and stores the document in application state for subsequent operations.
+/// Loading runs off the async runtime, reporting `pdf-load-progress`
+/// events; `cancel_pdf_open` (or opening another file) abandons it and
+/// leaves the current document in place.
+/// With `sanitize`, JavaScript/launch actions and embedded executables are
+/// stripped first and a `pdf-sanitized` event reports what was removed.
+#[tauri::command]
+#[instrument(skip(app, state))]
+pub async fn open_pdf(
+    path: String,
+    sanitize: Option<bool>,
+    app: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<PdfInfo> {
+    let cancel = Arc::new(AtomicBool::new(false));
+    let previous = pdf_open_slot(&state)?.replace(Arc::clone(&cancel));
+    if let Some(previous) = previous {
+        previous.store(true, Ordering::Relaxed);
+    }
+
+    let task_state = state.inner().clone();
+    let task_cancel = Arc::clone(&cancel);
+    let progress_app = app.clone();
+    let result = tokio::task::spawn_blocking(move || {
+        let mut on_progress = |progress: PdfLoadProgress| {
+            if task_cancel.load(Ordering::Relaxed) {
+                return Err(StreamSlateError::Other("PDF open cancelled".to_string()));
+            }
+            if let Err(e) = progress_app.emit("pdf-load-progress", progress) {
+                warn!(error = %e, "Failed to emit load progress");
+            }
+            Ok(())
+        };
+        load_pdf_with_progress(
+            path,
+            &task_state,
+            DocumentKind::Pdf,
+            sanitize.unwrap_or(false),
+            &mut on_progress,
+        )
+    })
+    .await
+    .map_err(|e| StreamSlateError::Other(format!("PDF open task failed: {e}")));
+
+    // Release the slot unless a newer open has taken it
+    if let Ok(mut slot) = pdf_open_slot(&state) {
+        if slot
+            .as_ref()
+            .is_some_and(|current| Arc::ptr_eq(current, &cancel))
+        {
+            *slot = None;
+        }
+    }
+    let (mut info, report) = result??;
+    info.restored = restore_reading_progress(&state, &info.path);
+
+    if let Some(report) = report {
+        if let Err(e) = app.emit("pdf-sanitized", &report) {
+            warn!(error = %e, "Failed to emit sanitize report");
+        }
+    }
+    warn_on_invalid_signatures(&app, &state, &info.path);
+    Ok(info)
+}
+
+/// Abandon the `open_pdf` in progress, if any
+///
+/// The interrupted `open_pdf` fails with a cancellation error and the
+/// previously open document stays loaded.
 #[tauri::command]
 #[instrument(skip(state))]
-pub async fn open_pdf(path: String, state: State<'_, AppState>) -> Result<PdfInfo> {
-    let pdf_path = PathBuf::from(&path);
+pub async fn cancel_pdf_open(state: State<'_, AppState>) -> Result<bool> {
+    let Some(cancel) = pdf_open_slot(&state)?.take() else {
+        return Ok(false);
+    };
+    cancel.store(true, Ordering::Relaxed);
+    info!("PDF open cancelled");
+    Ok(true)
+}
+
+fn pdf_open_slot(state: &AppState) -> Result<std::sync::MutexGuard<'_, Option<Arc<AtomicBool>>>> {
+    state
+        .pdf_open
+        .lock()
+        .map_err(|e| StreamSlateError::StateLock(format!("PDF open: {e}")))
+}
+
+/// Payload of the `pdf-signature-warning` event
+#[derive(Debug, Clone, Serialize)]
+struct SignatureWarning<'a> {
+    path: &'a str,
+    signatures: &'a [PdfSignature],
+}
+
+/// Emit `pdf-signature-warning` if a signed document fails verification
+///
+/// Runs on open so a tampered contract is flagged before it goes on stream.
+fn warn_on_invalid_signatures(app: &AppHandle, state: &AppState, path: &str) {
+    let Ok(Some(document)) = state.get_pdf_document() else {
+        return;
+    };
+    if !signatures::has_signatures(&document) {
+        return;
+    }
+
+    let file = match std::fs::read(path) {
+        Ok(file) => file,
+        Err(e) => {
+            warn!(path = %path, error = %e, "Failed to re-read PDF for signature check");
+            return;
+        }
+    };
+
+    let results = signatures::verify_signatures(&document, &file);
+    if results.iter().any(PdfSignature::needs_warning) {
+        warn!(path = %path, "PDF signature verification failed");
+        let payload = SignatureWarning {
+            path,
+            signatures: &results,
+        };
+        if let Err(e) = app.emit("pdf-signature-warning", payload) {
+            warn!(error = %e, "Failed to emit signature warning");
+        }
+    }
+}
+
+/// Validate and parse a PDF file without touching application state
+pub(crate) fn read_pdf_file(path: &str) -> Result<lopdf::Document> {
+    read_pdf_file_with_progress(path, &mut |_| Ok(()))
+}
+
+/// `read_pdf_file`, reporting progress; an error from `on_progress` aborts
+fn read_pdf_file_with_progress(
+    path: &str,
+    on_progress: &mut dyn FnMut(PdfLoadProgress) -> Result<()>,
+) -> Result<lopdf::Document> {
+    let mut report = |stage, percent| {
+        on_progress(PdfLoadProgress {
+            path: path.to_string(),
+            stage,
+            percent,
+        })
+    };
+    let pdf_path = Path::new(path);
 
     // Validate file exists
     if !pdf_path.exists() {
         warn!(path = %path, "PDF file not found");
-        return Err(StreamSlateError::FileNotFound(path));
+        return Err(StreamSlateError::FileNotFound(path.to_string()));
     }
 
     // Validate file extension
@@ -75,16 +270,76 @@ pub async fn open_pdf(path: String, state: State<'_, AppState>) -> Result<PdfInf
         ));
     }
 
-    // Get file metadata
-    let metadata = std::fs::metadata(&pdf_path)?;
+    info!(path = %path, "Loading PDF document");
 
-    info!(path = %path, size = metadata.len(), "Loading PDF document");
+    // Read in chunks so large files report progress and can be cancelled;
+    // reading is the first 40% of the load
+    let mut file = std::fs::File::open(pdf_path)?;
+    let size = file.metadata()?.len() as usize;
+    let mut data = Vec::with_capacity(size);
+    report(PdfLoadStage::Reading, 0)?;
+    loop {
+        let read = (&mut file).take(READ_CHUNK as u64).read_to_end(&mut data)?;
+        if read == 0 {
+            break;
+        }
+        report(
+            PdfLoadStage::Reading,
+            (data.len().min(size) * 40 / size.max(1)) as u8,
+        )?;
+    }
+
+    // Reject disguised or truncated files before the parser sees them
+    report(PdfLoadStage::Validating, 40)?;
+    validate::validate_structure(&data).map_err(|e| {
+        warn!(path = %path, error = %e, "PDF failed structural validation");
+        e
+    })?;
 
     // Load the PDF document with lopdf
-    let document = lopdf::Document::load(&pdf_path).map_err(|e| {
+    report(PdfLoadStage::Parsing, 45)?;
+    let document = lopdf::Document::load_mem(&data).map_err(|e| {
         warn!(path = %path, error = %e, "Failed to parse PDF");
         StreamSlateError::InvalidPdf(format!("Failed to parse PDF: {e}"))
     })?;
+    report(PdfLoadStage::Finishing, 90)?;
+    Ok(document)
+}
+
+/// Load a PDF from disk into application state
+///
+/// Shared by `open_pdf` and importers, which write a generated PDF first.
+/// Returns the sanitize report when `sanitize` is set.
+pub(crate) fn load_pdf(
+    path: String,
+    state: &AppState,
+    kind: DocumentKind,
+    sanitize: bool,
+) -> Result<(PdfInfo, Option<SanitizeReport>)> {
+    load_pdf_with_progress(path, state, kind, sanitize, &mut |_| Ok(()))
+}
+
+/// `load_pdf`, reporting progress; an error from `on_progress` aborts the
+/// load before application state is touched
+fn load_pdf_with_progress(
+    path: String,
+    state: &AppState,
+    kind: DocumentKind,
+    sanitize: bool,
+    on_progress: &mut dyn FnMut(PdfLoadProgress) -> Result<()>,
+) -> Result<(PdfInfo, Option<SanitizeReport>)> {
+    let pdf_path = PathBuf::from(&path);
+    let mut document = read_pdf_file_with_progress(&path, on_progress)?;
+    let metadata = std::fs::metadata(&pdf_path)?;
+
+    // Strip active content before the document is cached in state
+    let sanitize_report = sanitize.then(|| {
+        let report = sanitize::sanitize(&mut document);
+        if !report.is_clean() {
+            warn!(path = %path, removed = report.removed.len(), "Removed active content from PDF");
+        }
+        report
+    });
 
     // Get page count
     let page_count = document.get_pages().len() as u32;
@@ -93,8 +348,16 @@ pub async fn open_pdf(path: String, state: State<'_, AppState>) -> Result<PdfInf
     // Extract metadata from PDF info dictionary
     let (title, author) = extract_pdf_metadata(&document);
 
+    // Last chance to cancel before the open document is replaced
+    on_progress(PdfLoadProgress {
+        path: path.clone(),
+        stage: PdfLoadStage::Finishing,
+        percent: 100,
+    })?;
+
     // Store the document in application state
     state.set_pdf_document(Some(document))?;
+    state.set_image_deck(None)?;
 
     // Update PDF state
     state.update_pdf_state(|pdf_state| {
@@ -102,7 +365,14 @@ pub async fn open_pdf(path: String, state: State<'_, AppState>) -> Result<PdfInf
         pdf_state.total_pages = page_count;
         pdf_state.current_page = 1;
         pdf_state.is_loaded = true;
+        pdf_state.document_kind = kind;
+        pdf_state.crop = None;
+        pdf_state.smart_invert = false;
+        pdf_state.linked = None;
     })?;
+    state.clear_reactions();
+    state.clear_media();
+    state.clear_annotation_history();
 
     info!(
         path = %path,
@@ -110,8 +380,9 @@ pub async fn open_pdf(path: String, state: State<'_, AppState>) -> Result<PdfInf
         title = ?title,
         "PDF opened successfully"
     );
+    start_page_timing(state, &path);
 
-    Ok(PdfInfo {
+    let info = PdfInfo {
         path,
         title: title.or_else(|| {
             pdf_path
@@ -128,7 +399,10 @@ pub async fn open_pdf(path: String, state: State<'_, AppState>) -> Result<PdfInf
                 .ok()
                 .map(|d| d.as_secs().to_string())
         }),
-    })
+        restored: None,
+    };
+
+    Ok((info, sanitize_report))
 }
 
 /// Extract title and author from PDF metadata
@@ -175,9 +449,11 @@ fn extract_string_from_object(obj: &lopdf::Object) -> Option<String> {
 #[instrument(skip(state))]
 pub async fn close_pdf(state: State<'_, AppState>) -> Result<()> {
     info!("Closing PDF document");
+    finish_page_timing(&state);
 
     // Clear the document from state
     state.set_pdf_document(None)?;
+    state.set_image_deck(None)?;
 
     // Reset PDF state
     state.update_pdf_state(|pdf_state| {
@@ -185,14 +461,22 @@ pub async fn close_pdf(state: State<'_, AppState>) -> Result<()> {
         pdf_state.total_pages = 0;
         pdf_state.current_page = 1;
         pdf_state.is_loaded = false;
+        pdf_state.document_kind = DocumentKind::Pdf;
+        pdf_state.crop = None;
+        pdf_state.smart_invert = false;
+        pdf_state.linked = None;
     })?;
+    state.clear_reactions();
+    state.clear_media();
+    state.clear_annotation_history();
 
     Ok(())
 }
 
 /// Get information about a specific page in the PDF
 ///
-/// Returns page dimensions and rotation. Page numbers are 1-indexed.
+/// Returns page dimensions and rotation, restricted to the crop region set
+/// with `set_page_crop`. Page numbers are 1-indexed.
 #[tauri::command]
 #[instrument(skip(state))]
 pub async fn get_pdf_page_info(page_number: u32, state: State<'_, AppState>) -> Result<PdfPage> {
@@ -202,11 +486,30 @@ pub async fn get_pdf_page_info(page_number: u32, state: State<'_, AppState>) ->
         ));
     }
 
+    let crop = state.get_pdf_state()?.crop;
+
+    // Image decks report the pixel size of each page image
+    if let Some(deck) = state.get_image_deck()? {
+        let page = deck.page(page_number).ok_or_else(|| {
+            StreamSlateError::InvalidPdf(format!(
+                "Page {} not found (deck has {} pages)",
+                page_number,
+                deck.pages.len()
+            ))
+        })?;
+        return Ok(PdfPage {
+            page_number,
+            width: page.width as f64,
+            height: page.height as f64,
+            rotation: 0,
+            crop: None,
+        }
+        .cropped(crop));
+    }
+
     // Get the document from state
     let document = state.get_pdf_document()?;
-    let document = document.ok_or_else(|| {
-        StreamSlateError::InvalidPdf("No PDF document is currently open".to_string())
-    })?;
+    let document = document.ok_or(StreamSlateError::NoDocument)?;
 
     // Get the page
     let pages = document.get_pages();
@@ -218,9 +521,96 @@ pub async fn get_pdf_page_info(page_number: u32, state: State<'_, AppState>) ->
         ))
     })?;
 
-    // Get page dictionary
+    let page = read_page_info(&document, *page_id, page_number)?;
+
+    debug!(
+        page = page_number,
+        width = page.width,
+        height = page.height,
+        rotation = page.rotation,
+        "Page info retrieved"
+    );
+
+    Ok(page.cropped(crop))
+}
+
+/// Get dimensions and rotation of every page in one call
+///
+/// Same values as `get_pdf_page_info` (including the crop region), in page
+/// order, so views like the thumbnail grid don't need one IPC call per page.
+#[tauri::command]
+#[instrument(skip(state))]
+pub async fn get_all_page_info(state: State<'_, AppState>) -> Result<Vec<PdfPage>> {
+    let crop = state.get_pdf_state()?.crop;
+
+    if let Some(deck) = state.get_image_deck()? {
+        return Ok(deck
+            .pages
+            .iter()
+            .map(|page| {
+                PdfPage {
+                    page_number: page.page_number,
+                    width: page.width as f64,
+                    height: page.height as f64,
+                    rotation: 0,
+                    crop: None,
+                }
+                .cropped(crop)
+            })
+            .collect());
+    }
+
+    let document = state
+        .get_pdf_document()?
+        .ok_or(StreamSlateError::NoDocument)?;
+
+    let pages = document
+        .get_pages()
+        .into_iter()
+        .map(|(page_number, page_id)| {
+            read_page_info(&document, page_id, page_number).map(|page| page.cropped(crop))
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    debug!(count = pages.len(), "All page info retrieved");
+    Ok(pages)
+}
+
+/// Uncropped size of each page of the open document or image deck
+pub(crate) fn open_page_sizes(state: &AppState) -> Result<HashMap<u32, (f64, f64)>> {
+    if let Some(deck) = state.get_image_deck()? {
+        return Ok(deck
+            .pages
+            .iter()
+            .map(|page| (page.page_number, (page.width as f64, page.height as f64)))
+            .collect());
+    }
+    Ok(state
+        .get_pdf_document()?
+        .map(|document| page_sizes(&document))
+        .unwrap_or_default())
+}
+
+/// Size of each page of `document`
+pub(crate) fn page_sizes(document: &lopdf::Document) -> HashMap<u32, (f64, f64)> {
+    document
+        .get_pages()
+        .into_iter()
+        .filter_map(|(page_number, page_id)| {
+            let page = read_page_info(document, page_id, page_number).ok()?;
+            Some((page_number, (page.width, page.height)))
+        })
+        .collect()
+}
+
+/// Read size and rotation from a page dictionary
+fn read_page_info(
+    document: &lopdf::Document,
+    page_id: lopdf::ObjectId,
+    page_number: u32,
+) -> Result<PdfPage> {
     let page_dict = document
-        .get_dictionary(*page_id)
+        .get_dictionary(page_id)
         .map_err(|e| StreamSlateError::InvalidPdf(format!("Failed to get page dictionary: {e}")))?;
 
     // Extract MediaBox for dimensions (default to US Letter if not found)
@@ -234,22 +624,44 @@ pub async fn get_pdf_page_info(page_number: u32, state: State<'_, AppState>) ->
         .map(|r| (r % 360) as u32)
         .unwrap_or(0);
 
-    debug!(
-        page = page_number,
-        width = width,
-        height = height,
-        rotation = rotation,
-        "Page info retrieved"
-    );
-
     Ok(PdfPage {
         page_number,
         width,
         height,
         rotation,
+        crop: None,
     })
 }
 
+/// Set (or with `None`, clear) the crop region for the open document
+///
+/// The region applies to every page and is what the audience sees, e.g. to
+/// trim wide margins or hide speaker notes in the page footer. It is reset
+/// when another document is opened. Emits `page-crop-changed`.
+#[tauri::command]
+#[instrument(skip(app, state))]
+pub async fn set_page_crop(
+    rect: Option<CropRect>,
+    app: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<()> {
+    if !state.get_pdf_state()?.is_loaded {
+        return Err(StreamSlateError::NoDocument);
+    }
+    if rect.is_some_and(|r| !r.is_valid()) {
+        return Err(StreamSlateError::Other(
+            "Crop rectangle must have a non-negative origin and a positive size".to_string(),
+        ));
+    }
+
+    state.update_pdf_state(|pdf_state| pdf_state.crop = rect)?;
+    info!(crop = ?rect, "Page crop updated");
+    if let Err(e) = app.emit("page-crop-changed", rect) {
+        warn!(error = %e, "Failed to emit crop change");
+    }
+    Ok(())
+}
+
 /// Extract page dimensions from MediaBox or CropBox
 fn extract_page_dimensions(page_dict: &lopdf::Dictionary) -> Option<(f64, f64)> {
     // Try MediaBox first, then CropBox
@@ -287,9 +699,7 @@ pub async fn get_pdf_page_count(state: State<'_, AppState>) -> Result<u32> {
     let pdf_state = state.get_pdf_state()?;
 
     if !pdf_state.is_loaded {
-        return Err(StreamSlateError::InvalidPdf(
-            "No PDF document is currently open".to_string(),
-        ));
+        return Err(StreamSlateError::NoDocument);
     }
 
     Ok(pdf_state.total_pages)
@@ -303,6 +713,275 @@ pub async fn is_pdf_open(state: State<'_, AppState>) -> Result<bool> {
     Ok(pdf_state.is_loaded)
 }
 
+/// List files embedded in the currently open PDF
+#[tauri::command]
+#[instrument(skip(state))]
+pub async fn list_pdf_attachments(state: State<'_, AppState>) -> Result<Vec<PdfAttachment>> {
+    let document = state
+        .get_pdf_document()?
+        .ok_or(StreamSlateError::NoDocument)?;
+
+    let attachments = attachments::list_attachments(&document);
+    debug!(count = attachments.len(), "Listed PDF attachments");
+    Ok(attachments)
+}
+
+/// Extract an embedded file to `output_path`
+///
+/// `name` is the attachment key returned by `list_pdf_attachments`.
+/// Returns the number of bytes written.
+#[tauri::command]
+#[instrument(skip(state))]
+pub async fn extract_pdf_attachment(
+    name: String,
+    output_path: String,
+    state: State<'_, AppState>,
+) -> Result<u64> {
+    let pdf_state = state.get_pdf_state()?;
+    let document = state
+        .get_pdf_document()?
+        .ok_or(StreamSlateError::NoDocument)?;
+
+    let output = validate_output_path(&output_path, pdf_state.current_file.as_deref())?;
+    let data = attachments::extract_attachment(&document, &name)?;
+    std::fs::write(&output, &data)?;
+
+    info!(name = %name, path = %output.display(), bytes = data.len(), "Extracted PDF attachment");
+    Ok(data.len() as u64)
+}
+
+/// Write a smaller copy of the open PDF to `output_path`
+///
+/// Downsamples oversized images and re-saves with compressed object streams
+/// (see `OptimizeOptions` for the defaults), for decks too heavy for
+/// lower-end machines. The open document is not changed.
+#[tauri::command]
+#[instrument(skip(state))]
+pub async fn optimize_pdf(
+    output_path: String,
+    options: Option<OptimizeOptions>,
+    state: State<'_, AppState>,
+) -> Result<OptimizeReport> {
+    let pdf_state = state.get_pdf_state()?;
+    let document = state
+        .get_pdf_document()?
+        .ok_or(StreamSlateError::NoDocument)?;
+    let output = validate_output_path(&output_path, pdf_state.current_file.as_deref())?;
+
+    let options = options.unwrap_or_default();
+    let (bytes, mut report) =
+        tokio::task::spawn_blocking(move || optimize::optimize(document, &options))
+            .await
+            .map_err(|e| StreamSlateError::Other(format!("Optimization task failed: {e}")))??;
+    std::fs::write(&output, &bytes)?;
+
+    report.original_bytes = pdf_state
+        .current_file
+        .as_deref()
+        .and_then(|path| std::fs::metadata(path).ok())
+        .map_or(0, |metadata| metadata.len());
+    info!(
+        path = %output.display(),
+        original = report.original_bytes,
+        optimized = report.optimized_bytes,
+        images = report.images_downsampled,
+        "Optimized PDF written"
+    );
+    Ok(report)
+}
+
+/// Verify digital signatures in the currently open PDF
+///
+/// Reports the signer and validity of each signed signature field. Unsigned
+/// documents return an empty list.
+#[tauri::command]
+#[instrument(skip(state))]
+pub async fn verify_pdf_signatures(state: State<'_, AppState>) -> Result<Vec<PdfSignature>> {
+    let document = state
+        .get_pdf_document()?
+        .ok_or(StreamSlateError::NoDocument)?;
+    let path = state
+        .get_pdf_state()?
+        .current_file
+        .ok_or_else(|| StreamSlateError::InvalidPdf("Open PDF has no file path".to_string()))?;
+
+    let file = std::fs::read(&path)?;
+    let results = signatures::verify_signatures(&document, &file);
+    debug!(count = results.len(), "Verified PDF signatures");
+    Ok(results)
+}
+
+/// Get the words on a page with their bounding boxes
+///
+/// Rectangles are in PDF points from the top-left of the unrotated page, the
+/// coordinate space annotations use, so highlights can snap to text. Pages
+/// without a text layer (scans, image decks) return OCR results from
+/// `ocr_page` if available, otherwise an empty list.
+#[tauri::command]
+#[instrument(skip(state))]
+pub async fn get_page_words(page: u32, state: State<'_, AppState>) -> Result<Vec<PageWord>> {
+    let words = words_for_page(&state, page)?;
+    debug!(page, count = words.len(), "Extracted page words");
+    Ok(words)
+}
+
+/// Word boxes for a page: the text layer, falling back to cached OCR words
+pub(crate) fn words_for_page(state: &AppState, page: u32) -> Result<Vec<PageWord>> {
+    if page == 0 {
+        return Err(StreamSlateError::InvalidPdf(
+            "Page numbers start from 1".to_string(),
+        ));
+    }
+
+    let words = if state.get_image_deck()?.is_some() {
+        Vec::new()
+    } else {
+        let document = state
+            .get_pdf_document()?
+            .ok_or(StreamSlateError::NoDocument)?;
+        text::page_words(&document, page)?
+    };
+
+    if words.is_empty() {
+        if let Some(ocr_words) = state.get_ocr_words(page)? {
+            return Ok(ocr_words);
+        }
+    }
+    Ok(words)
+}
+
+/// Toggle smart dark mode for the open document
+///
+/// Pages are rendered with luminance inverted and hue preserved, so white
+/// slides don't flash bright on stream overlays while colors keep their
+/// meaning. Reset when another document is opened. Emits
+/// `smart-invert-changed` and broadcasts `SMART_INVERT_CHANGED`.
+#[tauri::command]
+#[instrument(skip(app, state))]
+pub async fn set_smart_invert(
+    enabled: bool,
+    app: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<()> {
+    set_smart_invert_state(&state, &app, enabled)?;
+    state.broadcast(crate::websocket::WebSocketEvent::SmartInvertChanged { enabled })
+}
+
+/// Store the smart dark mode flag and notify the webview
+///
+/// Shared by the Tauri command and the WebSocket handler.
+pub(crate) fn set_smart_invert_state(
+    state: &AppState,
+    app: &AppHandle,
+    enabled: bool,
+) -> Result<()> {
+    if !state.get_pdf_state()?.is_loaded {
+        return Err(StreamSlateError::NoDocument);
+    }
+    state.update_pdf_state(|pdf_state| pdf_state.smart_invert = enabled)?;
+    info!(enabled, "Smart invert updated");
+
+    #[derive(Serialize, Clone)]
+    struct SmartInvertPayload {
+        enabled: bool,
+    }
+    if let Err(e) = app.emit("smart-invert-changed", SmartInvertPayload { enabled }) {
+        warn!(error = %e, "Failed to emit smart invert change");
+    }
+    Ok(())
+}
+
+/// Compare two PDFs page by page
+///
+/// Reports per-page text insertions/deletions (with word boxes) and whether
+/// the page size or drawn content changed, e.g. to check the final deck
+/// against the rehearsed one. Neither file is opened in the app.
+#[tauri::command]
+#[instrument]
+pub async fn compare_pdfs(path_a: String, path_b: String) -> Result<PdfComparison> {
+    let comparison = tokio::task::spawn_blocking(move || {
+        let a = read_pdf_file(&path_a)?;
+        let b = read_pdf_file(&path_b)?;
+        compare::compare_documents(&a, &b)
+    })
+    .await
+    .map_err(|e| StreamSlateError::Other(format!("Comparison task failed: {e}")))??;
+
+    info!(
+        changed = comparison
+            .pages
+            .iter()
+            .filter(|page| page.status != PageStatus::Unchanged)
+            .count(),
+        "PDF comparison finished"
+    );
+    Ok(comparison)
+}
+
+/// Validate a user-chosen destination for writing extracted data
+///
+/// The path must be absolute, its parent directory must exist, and it must
+/// not point at a directory or at the currently open document.
+pub(crate) fn validate_output_path(
+    output_path: &str,
+    open_document: Option<&str>,
+) -> Result<PathBuf> {
+    let output = PathBuf::from(output_path);
+
+    if !output.is_absolute() {
+        return Err(StreamSlateError::Other(format!(
+            "Output path must be absolute: {output_path}"
+        )));
+    }
+
+    if output.file_name().is_none() || output.is_dir() {
+        return Err(StreamSlateError::Other(format!(
+            "Output path must name a file: {output_path}"
+        )));
+    }
+
+    let parent = output.parent().filter(|p| p.is_dir()).ok_or_else(|| {
+        StreamSlateError::FileNotFound(format!("Output directory does not exist: {output_path}"))
+    })?;
+
+    if let Some(open) = open_document {
+        let same_file = match (
+            std::fs::canonicalize(open),
+            std::fs::canonicalize(parent).map(|p| p.join(output.file_name().unwrap_or_default())),
+        ) {
+            (Ok(a), Ok(b)) => a == b,
+            _ => Path::new(open) == output,
+        };
+        if same_file {
+            return Err(StreamSlateError::Other(
+                "Refusing to overwrite the open document".to_string(),
+            ));
+        }
+    }
+
+    Ok(output)
+}
+
+/// Call `on_page` with the new page (1-indexed) on every page change
+///
+/// Page changes from the webview and from remote clients both surface as
+/// `page-changed` events.
+pub fn listen_page_changes<F>(app: &AppHandle, on_page: F)
+where
+    F: Fn(u32) + Send + 'static,
+{
+    #[derive(Deserialize)]
+    struct PagePayload {
+        page: u32,
+    }
+
+    app.listen_any("page-changed", move |event| {
+        if let Ok(PagePayload { page }) = serde_json::from_str(event.payload()) {
+            on_page(page);
+        }
+    });
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -317,6 +996,7 @@ mod tests {
             file_size: 1024,
             created: None,
             modified: Some("1234567890".to_string()),
+            restored: None,
         };
 
         let json = serde_json::to_string(&info).unwrap();
@@ -331,10 +1011,105 @@ mod tests {
             width: 612.0,
             height: 792.0,
             rotation: 0,
+            crop: None,
         };
 
         let json = serde_json::to_string(&page).unwrap();
         assert!(json.contains("612"));
         assert!(json.contains("792"));
     }
+
+    #[test]
+    fn test_read_page_info() {
+        use lopdf::{dictionary, Document, Object};
+
+        let mut doc = Document::with_version("1.7");
+        let page_id = doc.add_object(dictionary! {
+            "Type" => "Page",
+            "MediaBox" => vec![0.into(), 0.into(), 960.into(), Object::Real(540.0)],
+            "Rotate" => 450,
+        });
+
+        let page = read_page_info(&doc, page_id, 3).unwrap();
+        assert_eq!(page.page_number, 3);
+        assert_eq!((page.width, page.height, page.rotation), (960.0, 540.0, 90));
+        assert!(read_page_info(&doc, (99, 0), 1).is_err());
+    }
+
+    #[test]
+    fn test_page_crop() {
+        let page = PdfPage {
+            page_number: 1,
+            width: 720.0,
+            height: 540.0,
+            rotation: 0,
+            crop: None,
+        };
+
+        // Hide a 90pt notes footer; the rect may overhang the page
+        let footer = CropRect {
+            x: 0.0,
+            y: 0.0,
+            width: 1000.0,
+            height: 450.0,
+        };
+        let cropped = page.clone().cropped(Some(footer));
+        assert_eq!((cropped.width, cropped.height), (720.0, 450.0));
+        assert_eq!(cropped.crop.unwrap().width, 720.0);
+
+        // A crop outside the page leaves it untouched
+        let outside = CropRect {
+            x: 800.0,
+            y: 0.0,
+            width: 100.0,
+            height: 100.0,
+        };
+        assert!(page.clone().cropped(Some(outside)).crop.is_none());
+        assert!(!CropRect {
+            width: 0.0,
+            ..footer
+        }
+        .is_valid());
+    }
+
+    #[test]
+    fn test_validate_output_path() {
+        let dir = std::env::temp_dir();
+        let target = dir.join("streamslate-attachment.csv");
+        let target_str = target.to_str().unwrap();
+
+        assert!(validate_output_path(target_str, None).is_ok());
+        assert!(validate_output_path("relative/file.csv", None).is_err());
+        assert!(validate_output_path(dir.to_str().unwrap(), None).is_err());
+        assert!(validate_output_path(target_str, Some(target_str)).is_err());
+
+        let missing = dir.join("streamslate-missing-dir").join("file.csv");
+        assert!(validate_output_path(missing.to_str().unwrap(), None).is_err());
+    }
+
+    #[test]
+    fn test_read_progress_and_cancel() {
+        let mut doc = crate::pdf::tests::document_with_pages(&[""]);
+        let path = std::env::temp_dir().join("streamslate-load-progress.pdf");
+        doc.save(&path).unwrap();
+        let path = path.to_str().unwrap();
+
+        let mut stages = Vec::new();
+        let document = read_pdf_file_with_progress(path, &mut |p| {
+            stages.push((p.stage, p.percent));
+            Ok(())
+        })
+        .unwrap();
+        assert_eq!(document.get_pages().len(), 1);
+        assert_eq!(stages.first(), Some(&(PdfLoadStage::Reading, 0)));
+        assert!(stages.contains(&(PdfLoadStage::Parsing, 45)));
+        assert!(stages.windows(2).all(|w| w[0].1 <= w[1].1));
+
+        let cancelled = read_pdf_file_with_progress(path, &mut |p| match p.stage {
+            PdfLoadStage::Parsing => Err(StreamSlateError::Other("cancelled".to_string())),
+            _ => Ok(()),
+        });
+        assert!(cancelled.is_err());
+        let _ = std::fs::remove_file(path);
+    }
 }
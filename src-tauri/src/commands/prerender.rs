@@ -0,0 +1,120 @@
+/*
+ * This file is part of StreamSlate.
+ * Copyright (C) 2025 StreamSlate Contributors
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+
+//! Page pre-render commands
+//!
+//! On every page change the neighbouring pages are queued for rendering at
+//! output resolution: sent to the webviews as a `prerender-pages` event and
+//! to browser-source views as a `PRERENDER_PAGES` broadcast.
+
+use super::pdf::listen_page_changes;
+use crate::capture::prerender::PrerenderConfig;
+use crate::error::{Result, StreamSlateError};
+use crate::state::AppState;
+use crate::websocket::WebSocketEvent;
+use serde::Serialize;
+use tauri::{AppHandle, Emitter, State};
+use tracing::{debug, info, instrument, warn};
+
+/// Payload of the `prerender-pages` event
+#[derive(Debug, Clone, Serialize)]
+struct PrerenderPayload {
+    page: u32,
+    pages: Vec<u32>,
+}
+
+/// How many pages around the current one are pre-rendered
+#[tauri::command]
+#[instrument(skip(state))]
+pub async fn get_prerender_config(state: State<'_, AppState>) -> Result<PrerenderConfig> {
+    outputs_prerender(&state)
+}
+
+/// Set how many pages around the current one are pre-rendered
+///
+/// The new queue for the current page is sent out right away.
+#[tauri::command]
+#[instrument(skip(app, state))]
+pub async fn set_prerender_config(
+    config: PrerenderConfig,
+    app: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<()> {
+    config.validate()?;
+    state
+        .outputs
+        .lock()
+        .map_err(|e| StreamSlateError::StateLock(format!("Outputs: {e}")))?
+        .prerender = config;
+    info!(
+        enabled = config.enabled,
+        ahead = config.ahead,
+        behind = config.behind,
+        "Pre-render settings updated"
+    );
+
+    let pdf_state = state.get_pdf_state()?;
+    if pdf_state.is_loaded {
+        announce(&app, &state, pdf_state.current_page);
+    }
+    Ok(())
+}
+
+fn outputs_prerender(state: &AppState) -> Result<PrerenderConfig> {
+    Ok(state
+        .outputs
+        .lock()
+        .map_err(|e| StreamSlateError::StateLock(format!("Outputs: {e}")))?
+        .prerender)
+}
+
+/// Queue the pages around `page` for pre-rendering
+fn announce(app: &AppHandle, state: &AppState, page: u32) {
+    let total_pages = match state.get_pdf_state() {
+        Ok(pdf_state) => pdf_state.total_pages,
+        Err(e) => {
+            warn!(error = %e, "Failed to read PDF state for pre-rendering");
+            return;
+        }
+    };
+    let pages = match outputs_prerender(state) {
+        Ok(config) => config.queue(page, total_pages),
+        Err(e) => {
+            warn!(error = %e, "Failed to read pre-render settings");
+            return;
+        }
+    };
+    debug!(page, ?pages, "Queueing pages for pre-rendering");
+
+    if let Err(e) = app.emit(
+        "prerender-pages",
+        PrerenderPayload {
+            page,
+            pages: pages.clone(),
+        },
+    ) {
+        warn!(error = %e, "Failed to emit pre-render queue");
+    }
+    let _ = state.broadcast(WebSocketEvent::PrerenderPages { page, pages });
+}
+
+/// Queue neighbouring pages for pre-rendering on every page change
+pub fn spawn_prerender_listener(app: &AppHandle, state: AppState) {
+    let handle = app.clone();
+    listen_page_changes(app, move |page| announce(&handle, &state, page));
+}
@@ -21,7 +21,7 @@
 use crate::error::Result;
 use crate::state::AppState;
 use serde::{Deserialize, Serialize};
-use tauri::{Emitter, Manager, State, WebviewUrl, WebviewWindow, WebviewWindowBuilder};
+use tauri::{AppHandle, Emitter, Manager, State, WebviewUrl, WebviewWindow, WebviewWindowBuilder};
 use tracing::{debug, info, instrument};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -175,8 +175,11 @@ fn emit_current_state_to_presenter(
 #[tauri::command]
 #[instrument(skip(window, state))]
 pub async fn close_presenter_mode(window: WebviewWindow, state: State<'_, AppState>) -> Result<()> {
-    let app_handle = window.app_handle();
+    close_presenter_window(window.app_handle(), &state)
+}
 
+/// Close the presenter window, if open, and mark presenter mode inactive
+pub(crate) fn close_presenter_window(app_handle: &AppHandle, state: &AppState) -> Result<()> {
     info!("Closing presenter mode");
 
     if let Some(presenter_window) = app_handle.get_webview_window("presenter") {
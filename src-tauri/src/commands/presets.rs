@@ -0,0 +1,179 @@
+/*
+ * This file is part of StreamSlate.
+ * Copyright (C) 2025 StreamSlate Contributors
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+
+//! Annotation style preset commands
+//!
+//! Every change is saved, sent to the webview as `style-presets-changed` so
+//! the drawing tools pick up the active pen, and broadcast to clients as
+//! `STYLE_PRESETS_CHANGED`.
+
+use crate::error::{Result, StreamSlateError};
+use crate::settings::{load_settings, save_settings};
+use crate::state::presets::{self, PresetStore, StylePreset};
+use crate::state::AppState;
+use crate::websocket::WebSocketEvent;
+use tauri::{AppHandle, Emitter, State};
+use tracing::{info, instrument, warn};
+
+/// The presets and which one is active
+#[tauri::command]
+#[instrument(skip(state))]
+pub async fn list_style_presets(state: State<'_, AppState>) -> Result<PresetStore> {
+    style_presets(&state)
+}
+
+/// Add a preset
+#[tauri::command]
+#[instrument(skip(state, app))]
+pub async fn create_style_preset(
+    mut preset: StylePreset,
+    state: State<'_, AppState>,
+    app: AppHandle,
+) -> Result<StylePreset> {
+    preset.normalize()?;
+    preset.id = uuid::Uuid::new_v4().to_string();
+    let store = update_presets(&state, &app, |store| {
+        store.presets.push(preset.clone());
+        Ok(())
+    })?;
+    broadcast_presets(&state, store);
+
+    info!(name = %preset.name, "Style preset created");
+    Ok(preset)
+}
+
+/// Replace the style of the preset with the same `id`
+#[tauri::command]
+#[instrument(skip(state, app))]
+pub async fn update_style_preset(
+    mut preset: StylePreset,
+    state: State<'_, AppState>,
+    app: AppHandle,
+) -> Result<StylePreset> {
+    preset.normalize()?;
+    let store = update_presets(&state, &app, |store| {
+        let index = store.index(&preset.id)?;
+        store.presets[index] = preset.clone();
+        Ok(())
+    })?;
+    broadcast_presets(&state, store);
+    Ok(preset)
+}
+
+/// Remove a preset, deactivating it if it was active
+#[tauri::command]
+#[instrument(skip(state, app))]
+pub async fn delete_style_preset(
+    id: String,
+    state: State<'_, AppState>,
+    app: AppHandle,
+) -> Result<()> {
+    let store = update_presets(&state, &app, |store| {
+        store.presets.remove(store.index(&id)?);
+        if store.active.as_deref() == Some(id.as_str()) {
+            store.active = None;
+        }
+        Ok(())
+    })?;
+    broadcast_presets(&state, store);
+    Ok(())
+}
+
+/// Apply preset `id` to annotations added from now on, or no preset
+#[tauri::command]
+#[instrument(skip(state, app))]
+pub async fn set_active_style_preset(
+    id: Option<String>,
+    state: State<'_, AppState>,
+    app: AppHandle,
+) -> Result<PresetStore> {
+    let store = set_active_preset_state(&state, &app, id)?;
+    broadcast_presets(&state, store.clone());
+    Ok(store)
+}
+
+/// Shared logic for the Tauri command and WebSocket handler
+pub(crate) fn set_active_preset_state(
+    state: &AppState,
+    app: &AppHandle,
+    id: Option<String>,
+) -> Result<PresetStore> {
+    let store = update_presets(state, app, |store| {
+        if let Some(id) = &id {
+            store.index(id)?;
+        }
+        store.active = id.clone();
+        Ok(())
+    })?;
+    info!(
+        preset = store.active_preset().map(|p| p.name.as_str()),
+        "Active style preset changed"
+    );
+    Ok(store)
+}
+
+/// Current presets
+pub(crate) fn style_presets(state: &AppState) -> Result<PresetStore> {
+    Ok(state
+        .style_presets
+        .read()
+        .map_err(|e| StreamSlateError::StateLock(format!("Style presets: {e}")))?
+        .clone())
+}
+
+/// Restore the stored presets
+pub fn load_style_presets(app: &AppHandle, state: &AppState) {
+    let store = load_settings(app, presets::STORE_FILE);
+    if let Ok(mut presets) = state.style_presets.write() {
+        *presets = store;
+    }
+}
+
+/// Change the presets, save them and push the result to the webview
+fn update_presets(
+    state: &AppState,
+    app: &AppHandle,
+    change: impl FnOnce(&mut PresetStore) -> Result<()>,
+) -> Result<PresetStore> {
+    let store = {
+        let mut presets = state
+            .style_presets
+            .write()
+            .map_err(|e| StreamSlateError::StateLock(format!("Style presets: {e}")))?;
+        let mut store = presets.clone();
+        change(&mut store)?;
+        save_settings(app, presets::STORE_FILE, &store)?;
+        *presets = store.clone();
+        store
+    };
+
+    if let Err(e) = app.emit("style-presets-changed", &store) {
+        warn!("Failed to emit style-presets-changed event: {}", e);
+    }
+    Ok(store)
+}
+
+fn broadcast_presets(state: &AppState, store: PresetStore) {
+    let event = WebSocketEvent::StylePresetsChanged {
+        presets: store.presets,
+        active: store.active,
+    };
+    if let Err(e) = state.broadcast(event) {
+        warn!("Failed to broadcast style presets change: {}", e);
+    }
+}
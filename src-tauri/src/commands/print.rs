@@ -0,0 +1,256 @@
+/*
+ * This file is part of StreamSlate.
+ * Copyright (C) 2025 StreamSlate Contributors
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+
+//! Printing through the OS print pipeline
+//!
+//! Hands the document to CUPS (`lp`) on macOS and Linux, or to the shell's
+//! `PrintTo` verb on Windows, so a moderator can print the rundown after the
+//! show without opening another app.
+
+use crate::error::{Result, StreamSlateError};
+use crate::state::AppState;
+use std::path::Path;
+use std::process::Command;
+use tauri::State;
+use tracing::{info, instrument};
+
+/// Print the open document, or `path` (e.g. an exported marked-up copy)
+///
+/// `range` selects pages like `"1-3,7"`; all pages are printed without it.
+/// `printer` defaults to the system default printer. Returns the print
+/// system's job description.
+#[tauri::command]
+#[instrument(skip(state))]
+pub async fn print_pdf(
+    range: Option<String>,
+    printer: Option<String>,
+    path: Option<String>,
+    state: State<'_, AppState>,
+) -> Result<String> {
+    let pdf_state = state.get_pdf_state()?;
+    let path = match path {
+        Some(path) => path,
+        None => pdf_state
+            .current_file
+            .filter(|_| pdf_state.is_loaded)
+            .ok_or(StreamSlateError::NoDocument)?,
+    };
+    if !Path::new(&path).is_file() {
+        return Err(StreamSlateError::FileNotFound(path));
+    }
+
+    let total_pages = super::pdf::read_pdf_file(&path)?.get_pages().len() as u32;
+    let ranges = range
+        .as_deref()
+        .map(|range| parse_page_ranges(range, total_pages))
+        .transpose()?;
+    if let Some(printer) = &printer {
+        validate_printer(printer)?;
+    }
+
+    let job = tokio::task::spawn_blocking(move || send_to_printer(&path, ranges, printer))
+        .await
+        .map_err(|e| StreamSlateError::Other(format!("Print task failed: {e}")))??;
+    info!(job = %job, "Print job submitted");
+    Ok(job)
+}
+
+/// Names of the printers the OS knows about
+#[tauri::command]
+#[instrument]
+pub async fn list_printers() -> Result<Vec<String>> {
+    tokio::task::spawn_blocking(query_printers)
+        .await
+        .map_err(|e| StreamSlateError::Other(format!("Printer query failed: {e}")))?
+}
+
+/// Parse `"1-3, 5"` into inclusive page ranges within `1..=total_pages`
+pub fn parse_page_ranges(range: &str, total_pages: u32) -> Result<Vec<(u32, u32)>> {
+    let invalid = || StreamSlateError::Other(format!("Invalid page range: {range}"));
+    let mut ranges = Vec::new();
+    for part in range.split(',').map(str::trim) {
+        let (start, end) = match part.split_once('-') {
+            Some((start, end)) => (start.trim(), end.trim()),
+            None => (part, part),
+        };
+        let start: u32 = start.parse().map_err(|_| invalid())?;
+        let end: u32 = end.parse().map_err(|_| invalid())?;
+        if start < 1 || start > end {
+            return Err(invalid());
+        }
+        if end > total_pages {
+            return Err(StreamSlateError::PageOutOfRange {
+                page: end,
+                total_pages,
+            });
+        }
+        ranges.push((start, end));
+    }
+    Ok(ranges)
+}
+
+/// Reject names that could be read as command-line options
+fn validate_printer(printer: &str) -> Result<()> {
+    if printer.trim().is_empty() || printer.starts_with('-') || printer.contains('\0') {
+        return Err(StreamSlateError::Other(format!(
+            "Invalid printer name: {printer}"
+        )));
+    }
+    Ok(())
+}
+
+/// `lp` arguments for printing `path`
+#[cfg_attr(windows, allow(dead_code))]
+fn lp_args(path: &str, ranges: Option<&[(u32, u32)]>, printer: Option<&str>) -> Vec<String> {
+    let mut args = Vec::new();
+    if let Some(printer) = printer {
+        args.extend(["-d".to_string(), printer.to_string()]);
+    }
+    if let Some(ranges) = ranges {
+        let pages: Vec<String> = ranges
+            .iter()
+            .map(|&(start, end)| {
+                if start == end {
+                    start.to_string()
+                } else {
+                    format!("{start}-{end}")
+                }
+            })
+            .collect();
+        args.extend(["-o".to_string(), format!("page-ranges={}", pages.join(","))]);
+    }
+    // End of options, so a path starting with '-' isn't read as one
+    args.extend(["--".to_string(), path.to_string()]);
+    args
+}
+
+#[cfg(not(windows))]
+fn send_to_printer(
+    path: &str,
+    ranges: Option<Vec<(u32, u32)>>,
+    printer: Option<String>,
+) -> Result<String> {
+    let output = Command::new("lp")
+        .args(lp_args(path, ranges.as_deref(), printer.as_deref()))
+        .output()
+        .map_err(|e| StreamSlateError::Other(format!("Failed to run lp ({e})")))?;
+    if !output.status.success() {
+        return Err(StreamSlateError::Other(format!(
+            "lp failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        )));
+    }
+    // e.g. "request id is Office_Printer-42 (1 file(s))"
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+#[cfg(windows)]
+fn send_to_printer(
+    path: &str,
+    ranges: Option<Vec<(u32, u32)>>,
+    printer: Option<String>,
+) -> Result<String> {
+    if ranges.is_some() {
+        return Err(StreamSlateError::Other(
+            "Printing a page range is not supported on Windows".to_string(),
+        ));
+    }
+    // The shell's PDF handler does the printing; arguments are passed
+    // through environment variables so they are never parsed as script
+    let script = match printer {
+        Some(_) => "Start-Process -FilePath $env:SS_PRINT_PATH -Verb PrintTo -ArgumentList ('\"' + $env:SS_PRINTER + '\"')",
+        None => "Start-Process -FilePath $env:SS_PRINT_PATH -Verb Print",
+    };
+    let output = Command::new("powershell")
+        .args(["-NoProfile", "-NonInteractive", "-Command", script])
+        .env("SS_PRINT_PATH", path)
+        .env("SS_PRINTER", printer.as_deref().unwrap_or_default())
+        .output()
+        .map_err(|e| StreamSlateError::Other(format!("Failed to run powershell ({e})")))?;
+    if !output.status.success() {
+        return Err(StreamSlateError::Other(format!(
+            "Printing failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        )));
+    }
+    Ok(format!("Sent {path} to the printer"))
+}
+
+#[cfg(not(windows))]
+fn query_printers() -> Result<Vec<String>> {
+    let output = Command::new("lpstat")
+        .arg("-e")
+        .output()
+        .map_err(|e| StreamSlateError::Other(format!("Failed to run lpstat ({e})")))?;
+    Ok(String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .map(String::from)
+        .collect())
+}
+
+#[cfg(windows)]
+fn query_printers() -> Result<Vec<String>> {
+    let output = Command::new("powershell")
+        .args([
+            "-NoProfile",
+            "-NonInteractive",
+            "-Command",
+            "Get-Printer | Select-Object -ExpandProperty Name",
+        ])
+        .output()
+        .map_err(|e| StreamSlateError::Other(format!("Failed to run powershell ({e})")))?;
+    Ok(String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .map(String::from)
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_page_ranges() {
+        assert_eq!(parse_page_ranges("1-3, 7", 10).unwrap(), [(1, 3), (7, 7)]);
+        assert!(parse_page_ranges("0-2", 10).is_err());
+        assert!(parse_page_ranges("4-2", 10).is_err());
+        assert!(parse_page_ranges("9-11", 10).is_err());
+        assert!(parse_page_ranges("1,,2", 10).is_err());
+    }
+
+    #[test]
+    fn test_lp_args() {
+        let args = lp_args("/tmp/rundown.pdf", Some(&[(1, 3), (7, 7)]), Some("Office"));
+        assert_eq!(
+            args,
+            [
+                "-d",
+                "Office",
+                "-o",
+                "page-ranges=1-3,7",
+                "--",
+                "/tmp/rundown.pdf"
+            ]
+        );
+        assert!(validate_printer("-o evil").is_err());
+    }
+}
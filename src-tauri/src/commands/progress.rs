@@ -0,0 +1,146 @@
+/*
+ * This file is part of StreamSlate.
+ * Copyright (C) 2025 StreamSlate Contributors
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+
+//! Reading progress commands
+//!
+//! `open_pdf` restores the stored page and zoom of a document it has seen
+//! before; the webview saves progress (including the scroll offset, which
+//! only it knows) as the reader moves through the document.
+
+use crate::error::{Result, StreamSlateError};
+use crate::progress::{self, ProgressStore, ReadingProgress};
+use crate::settings::{load_settings, save_settings};
+use crate::state::AppState;
+use tauri::{AppHandle, State};
+use tracing::{debug, info, instrument, warn};
+
+/// Remember where the open document is being read
+#[tauri::command]
+#[instrument(skip(app, state))]
+pub async fn save_reading_progress(
+    page: u32,
+    zoom: f64,
+    scroll_x: Option<f64>,
+    scroll_y: Option<f64>,
+    app: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<()> {
+    let current_file = state.get_pdf_state()?.current_file;
+    let mut reading = progress_state(&state)?;
+    if !reading.store.enabled {
+        return Ok(());
+    }
+    let Some((path, hash)) = reading.current.clone() else {
+        return Ok(());
+    };
+    // Progress arriving after another document was opened is stale
+    if current_file.as_deref() != Some(path.as_str()) {
+        return Ok(());
+    }
+
+    reading.store.record(
+        &hash,
+        ReadingProgress {
+            page: page.max(1),
+            zoom: if zoom.is_finite() && zoom > 0.0 {
+                zoom
+            } else {
+                1.0
+            },
+            scroll_x: scroll_x.filter(|v| v.is_finite()).unwrap_or(0.0),
+            scroll_y: scroll_y.filter(|v| v.is_finite()).unwrap_or(0.0),
+            updated_at: chrono::Utc::now().to_rfc3339(),
+        },
+    );
+    save_settings(&app, progress::STORE_FILE, &reading.store)
+}
+
+/// Whether documents reopen where they were left
+#[tauri::command]
+#[instrument(skip(state))]
+pub async fn get_reading_progress_enabled(state: State<'_, AppState>) -> Result<bool> {
+    Ok(progress_state(&state)?.store.enabled)
+}
+
+/// Turn restoring reading progress on or off
+#[tauri::command]
+#[instrument(skip(app, state))]
+pub async fn set_reading_progress_enabled(
+    enabled: bool,
+    app: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<()> {
+    let mut reading = progress_state(&state)?;
+    reading.store.enabled = enabled;
+    save_settings(&app, progress::STORE_FILE, &reading.store)?;
+    info!(enabled, "Reading progress restore updated");
+    Ok(())
+}
+
+/// Load the progress store from the app data directory
+pub fn load_reading_progress(app: &AppHandle, state: &AppState) {
+    let store: ProgressStore = load_settings(app, progress::STORE_FILE);
+    debug!(documents = store.documents.len(), "Loaded reading progress");
+    if let Ok(mut reading) = state.reading_progress.lock() {
+        reading.store = store;
+    }
+}
+
+/// Identify the just-opened document and restore its stored progress
+///
+/// Moves the open document to the stored page and zoom and returns the
+/// progress (with the scroll offset for the webview) when restoring is
+/// enabled and the document has been read before.
+pub(crate) fn restore_reading_progress(state: &AppState, path: &str) -> Option<ReadingProgress> {
+    let hash = match std::fs::read(path) {
+        Ok(data) => progress::content_hash(&data),
+        Err(e) => {
+            warn!(path = %path, error = %e, "Failed to hash document for reading progress");
+            return None;
+        }
+    };
+
+    let stored = {
+        let mut reading = state.reading_progress.lock().ok()?;
+        reading.current = Some((path.to_string(), hash.clone()));
+        if !reading.store.enabled {
+            return None;
+        }
+        reading.store.documents.get(&hash).cloned()?
+    };
+
+    let mut restored = stored;
+    state
+        .update_pdf_state(|pdf_state| {
+            restored.page = restored.page.clamp(1, pdf_state.total_pages.max(1));
+            pdf_state.current_page = restored.page;
+            pdf_state.zoom_level = restored.zoom;
+        })
+        .ok()?;
+    info!(path = %path, page = restored.page, "Restored reading progress");
+    Some(restored)
+}
+
+fn progress_state(
+    state: &AppState,
+) -> Result<std::sync::MutexGuard<'_, crate::state::ProgressState>> {
+    state
+        .reading_progress
+        .lock()
+        .map_err(|e| StreamSlateError::StateLock(format!("Reading progress: {e}")))
+}
@@ -0,0 +1,140 @@
+/*
+ * This file is part of StreamSlate.
+ * Copyright (C) 2025 StreamSlate Contributors
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+
+//! QR code overlay commands
+//!
+//! `generate_qr` flashes a QR code (feedback form, repository link) in the
+//! corner of every output, for a set time or until `clear_qr`.
+
+use crate::capture::qr::{self, QrCode, QrOverlay};
+use crate::error::{Result, StreamSlateError};
+use crate::state::AppState;
+use crate::websocket::WebSocketEvent;
+use serde::Serialize;
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter, State};
+use tracing::{info, instrument, warn};
+
+/// Longest a QR code may stay up on a timer
+const MAX_QR_SECONDS: u32 = 3600;
+
+/// Show `data` as a QR code on every output
+///
+/// Replaces any QR code already shown. It stays up for `duration_seconds`
+/// if given, otherwise until `clear_qr`. Returns the encoded symbol so the
+/// UI can preview it.
+#[tauri::command]
+#[instrument(skip(app, state))]
+pub async fn generate_qr(
+    data: String,
+    duration_seconds: Option<u32>,
+    app: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<QrCode> {
+    let code = show_qr_state(&state, &app, data.clone(), duration_seconds)?;
+    state.broadcast(WebSocketEvent::QrCodeChanged { data: Some(data) })?;
+    Ok(code)
+}
+
+/// Remove the QR code from the outputs
+#[tauri::command]
+#[instrument(skip(app, state))]
+pub async fn clear_qr(app: AppHandle, state: State<'_, AppState>) -> Result<()> {
+    clear_qr_state(&state, &app)?;
+    state.broadcast(WebSocketEvent::QrCodeChanged { data: None })
+}
+
+/// Encode and show a QR code, clearing it after `duration_seconds`
+///
+/// Shared by the Tauri command and the WebSocket handler.
+pub(crate) fn show_qr_state(
+    state: &AppState,
+    app: &AppHandle,
+    data: String,
+    duration_seconds: Option<u32>,
+) -> Result<QrCode> {
+    if duration_seconds.is_some_and(|s| !(1..=MAX_QR_SECONDS).contains(&s)) {
+        return Err(StreamSlateError::Other(format!(
+            "QR code duration must be 1-{MAX_QR_SECONDS} seconds"
+        )));
+    }
+    if data.is_empty() {
+        return Err(StreamSlateError::Other("QR code data is empty".to_string()));
+    }
+
+    let code = qr::generate_qr(&data)?;
+    let shown_at = Instant::now();
+    state
+        .outputs
+        .lock()
+        .map_err(|e| StreamSlateError::StateLock(e.to_string()))?
+        .qr_code = Some(QrOverlay {
+        data: data.clone(),
+        code: code.clone(),
+        shown_at,
+    });
+    info!(version = code.version, "QR code shown on outputs");
+    emit_qr_changed(app, Some(&data));
+
+    if let Some(seconds) = duration_seconds {
+        let (state, app) = (state.clone(), app.clone());
+        tauri::async_runtime::spawn(async move {
+            tokio::time::sleep(Duration::from_secs(u64::from(seconds))).await;
+            let still_shown = state.outputs.lock().is_ok_and(|outputs| {
+                outputs
+                    .qr_code
+                    .as_ref()
+                    .is_some_and(|overlay| overlay.shown_at == shown_at)
+            });
+            if still_shown {
+                let cleared = clear_qr_state(&state, &app)
+                    .and_then(|()| state.broadcast(WebSocketEvent::QrCodeChanged { data: None }));
+                if let Err(e) = cleared {
+                    warn!(error = %e, "Failed to clear QR code");
+                }
+            }
+        });
+    }
+    Ok(code)
+}
+
+/// Remove the QR code, if any
+pub(crate) fn clear_qr_state(state: &AppState, app: &AppHandle) -> Result<()> {
+    let previous = state
+        .outputs
+        .lock()
+        .map_err(|e| StreamSlateError::StateLock(e.to_string()))?
+        .qr_code
+        .take();
+    if previous.is_some() {
+        info!("QR code cleared from outputs");
+        emit_qr_changed(app, None);
+    }
+    Ok(())
+}
+
+/// Tell the webview the QR code was shown or cleared
+pub(crate) fn emit_qr_changed(app: &AppHandle, data: Option<&str>) {
+    #[derive(Serialize, Clone)]
+    struct QrPayload<'a> {
+        data: Option<&'a str>,
+    }
+    if let Err(e) = app.emit("qr-code-changed", QrPayload { data }) {
+        warn!("Failed to emit QR code change: {}", e);
+    }
+}
@@ -0,0 +1,244 @@
+/*
+ * This file is part of StreamSlate.
+ * Copyright (C) 2025 StreamSlate Contributors
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+
+//! Remote client commands
+//!
+//! Lets this instance control another StreamSlate over WebSocket. Mobile
+//! builds use this exclusively instead of running their own server; desktop
+//! builds need the `client-sdk` feature.
+
+#[cfg(any(mobile, feature = "client-sdk"))]
+use crate::client::{self, ClientConfig, ClientNotification};
+use crate::error::{Result, StreamSlateError};
+use crate::protocol::WebSocketCommand;
+use crate::state::AppState;
+#[cfg(any(mobile, feature = "client-sdk"))]
+use crate::state::RemoteConnection;
+use serde::{Deserialize, Serialize};
+#[cfg(any(mobile, feature = "client-sdk"))]
+use tauri::Emitter;
+use tauri::{AppHandle, State};
+use tracing::instrument;
+#[cfg(any(mobile, feature = "client-sdk"))]
+use tracing::{info, warn};
+
+/// Connection status of the remote client
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RemoteStatus {
+    pub url: Option<String>,
+    pub connected: bool,
+}
+
+/// Payload for `remote-connection` events
+#[cfg(any(mobile, feature = "client-sdk"))]
+#[derive(Debug, Clone, Serialize)]
+struct RemoteConnectionPayload {
+    connected: bool,
+    reason: Option<String>,
+    retry_in_ms: Option<u64>,
+}
+
+/// Connect to a desktop StreamSlate instance, replacing any existing connection
+///
+/// Server events are re-emitted to the webview as `remote-event`, and
+/// connection changes as `remote-connection`.
+#[tauri::command]
+#[instrument(skip(app, state))]
+#[cfg(any(mobile, feature = "client-sdk"))]
+pub async fn connect_remote(
+    url: String,
+    client_id: Option<String>,
+    app: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<()> {
+    if !(url.starts_with("ws://") || url.starts_with("wss://")) {
+        return Err(StreamSlateError::WebSocket(format!(
+            "Remote URL must start with ws:// or wss://: {url}"
+        )));
+    }
+
+    if let Some(id) = &client_id {
+        crate::websocket::preferences::validate_client_id(id)?;
+    }
+
+    start_remote_client(
+        &app,
+        &state,
+        ClientConfig {
+            client_id,
+            ..ClientConfig::new(url)
+        },
+    )
+}
+
+/// Start the remote client and store its handle in state
+#[cfg(any(mobile, feature = "client-sdk"))]
+pub fn start_remote_client(app: &AppHandle, state: &AppState, config: ClientConfig) -> Result<()> {
+    let url = config.url.clone();
+    let app_handle = app.clone();
+
+    let handle = client::spawn(config, move |notification| {
+        let result = match notification {
+            ClientNotification::Connected => app_handle.emit(
+                "remote-connection",
+                RemoteConnectionPayload {
+                    connected: true,
+                    reason: None,
+                    retry_in_ms: None,
+                },
+            ),
+            ClientNotification::Disconnected { reason, retry_in } => app_handle.emit(
+                "remote-connection",
+                RemoteConnectionPayload {
+                    connected: false,
+                    reason: Some(reason),
+                    retry_in_ms: Some(retry_in.as_millis() as u64),
+                },
+            ),
+            ClientNotification::Event(event) => app_handle.emit("remote-event", event),
+        };
+        if let Err(e) = result {
+            warn!(error = %e, "Failed to emit remote client event");
+        }
+    });
+
+    let previous = state
+        .remote_client
+        .lock()
+        .map_err(|e| StreamSlateError::StateLock(format!("Remote client: {e}")))?
+        .replace(RemoteConnection {
+            url: url.clone(),
+            handle,
+        });
+
+    if let Some(previous) = previous {
+        previous.handle.shutdown();
+    }
+
+    info!(url = %url, "Remote client started");
+    Ok(())
+}
+
+/// Disconnect from the remote instance
+#[tauri::command]
+#[instrument(skip(state))]
+#[cfg(any(mobile, feature = "client-sdk"))]
+pub async fn disconnect_remote(state: State<'_, AppState>) -> Result<()> {
+    let previous = state
+        .remote_client
+        .lock()
+        .map_err(|e| StreamSlateError::StateLock(format!("Remote client: {e}")))?
+        .take();
+
+    if let Some(connection) = previous {
+        connection.handle.shutdown();
+        info!(url = %connection.url, "Remote client disconnected");
+    }
+
+    Ok(())
+}
+
+/// Send a protocol command to the connected remote instance
+#[tauri::command]
+#[instrument(skip(state))]
+#[cfg(any(mobile, feature = "client-sdk"))]
+pub async fn send_remote_command(
+    command: WebSocketCommand,
+    state: State<'_, AppState>,
+) -> Result<()> {
+    let remote = state
+        .remote_client
+        .lock()
+        .map_err(|e| StreamSlateError::StateLock(format!("Remote client: {e}")))?;
+
+    let connection = remote
+        .as_ref()
+        .ok_or_else(|| StreamSlateError::WebSocket("No remote connection".to_string()))?;
+
+    connection
+        .handle
+        .send(command)
+        .map_err(StreamSlateError::WebSocket)
+}
+
+/// Get the remote client's connection status
+#[tauri::command]
+#[instrument(skip(state))]
+#[cfg(any(mobile, feature = "client-sdk"))]
+pub async fn get_remote_status(state: State<'_, AppState>) -> Result<RemoteStatus> {
+    let remote = state
+        .remote_client
+        .lock()
+        .map_err(|e| StreamSlateError::StateLock(format!("Remote client: {e}")))?;
+
+    Ok(RemoteStatus {
+        url: remote.as_ref().map(|c| c.url.clone()),
+        connected: remote.as_ref().is_some_and(|c| c.handle.is_connected()),
+    })
+}
+
+/// Connect to a remote instance (stub when built without the `client-sdk` feature)
+#[tauri::command]
+#[instrument(skip(app, state))]
+#[cfg(not(any(mobile, feature = "client-sdk")))]
+pub async fn connect_remote(
+    url: String,
+    client_id: Option<String>,
+    app: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<()> {
+    let _ = (client_id, app, state);
+    Err(StreamSlateError::WebSocket(format!(
+        "Cannot connect to {url}: StreamSlate was built without the 'client-sdk' feature"
+    )))
+}
+
+/// Disconnect from the remote instance (stub when built without the `client-sdk` feature)
+#[tauri::command]
+#[instrument(skip(state))]
+#[cfg(not(any(mobile, feature = "client-sdk")))]
+pub async fn disconnect_remote(state: State<'_, AppState>) -> Result<()> {
+    let _ = state;
+    Ok(())
+}
+
+/// Send a command to the remote instance (stub when built without the `client-sdk` feature)
+#[tauri::command]
+#[instrument(skip(state))]
+#[cfg(not(any(mobile, feature = "client-sdk")))]
+pub async fn send_remote_command(
+    command: WebSocketCommand,
+    state: State<'_, AppState>,
+) -> Result<()> {
+    let _ = (command, state);
+    Err(StreamSlateError::WebSocket(
+        "No remote connection".to_string(),
+    ))
+}
+
+/// Get the remote client's connection status (stub when built without the `client-sdk` feature)
+#[tauri::command]
+#[instrument(skip(state))]
+#[cfg(not(any(mobile, feature = "client-sdk")))]
+pub async fn get_remote_status(state: State<'_, AppState>) -> Result<RemoteStatus> {
+    let _ = state;
+    Ok(RemoteStatus {
+        url: None,
+        connected: false,
+    })
+}
@@ -0,0 +1,204 @@
+/*
+ * This file is part of StreamSlate.
+ * Copyright (C) 2025 StreamSlate Contributors
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+
+//! Annotation replay
+//!
+//! `replay_annotations` redraws the open document's markup on the remote
+//! clients in the order it was made, for recap videos. Clients are first
+//! sent the pages as they were before the first change, then each change as
+//! an `ANNOTATION_ADDED`/`UPDATED`/`DELETED` event, spaced as they were
+//! made, and finally the current annotations. `ANNOTATION_REPLAY` events
+//! bracket the replay. The host's own annotations are never touched.
+//!
+//! Changes made since the document was opened are replayed exactly, undos
+//! included; for markup from earlier sessions only the order it was created
+//! in is known.
+
+use super::annotations::{edit_event, page_updates};
+use crate::error::{Result, StreamSlateError};
+use crate::state::history::{self, TimelineEntry};
+use crate::state::AppState;
+use crate::websocket::WebSocketEvent;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use tauri::State;
+use tracing::{info, instrument, warn};
+
+/// Slowest and fastest accepted replay speeds
+pub const SPEED_RANGE: std::ops::RangeInclusive<f64> = 0.1..=100.0;
+
+/// Longest pause between two replayed changes, after scaling
+const MAX_GAP: Duration = Duration::from_secs(2);
+
+/// Replay the open document's annotation changes on the remote clients,
+/// `speed` times as fast as they were made
+///
+/// Pauses longer than two seconds are shortened. Replaces any replay in
+/// progress and returns the number of changes replayed.
+#[tauri::command]
+#[instrument(skip(state))]
+pub async fn replay_annotations(speed: f64, state: State<'_, AppState>) -> Result<usize> {
+    if !SPEED_RANGE.contains(&speed) {
+        return Err(StreamSlateError::Other(format!(
+            "Replay speed must be between {} and {}",
+            SPEED_RANGE.start(),
+            SPEED_RANGE.end()
+        )));
+    }
+    if state.get_pdf_state()?.current_file.is_none() {
+        return Err(StreamSlateError::NoDocument);
+    }
+
+    let (start, entries) = {
+        let annotations = state
+            .annotations
+            .read()
+            .map_err(|e| StreamSlateError::StateLock(format!("Annotations: {e}")))?;
+        let timeline = state
+            .annotation_timeline
+            .lock()
+            .map_err(|e| StreamSlateError::StateLock(format!("Annotation timeline: {e}")))?;
+        if timeline.is_empty() {
+            history::timeline_from_created(&annotations)
+        } else {
+            timeline.replay(&annotations)
+        }
+    };
+    let mut pages: Vec<u32> = start
+        .keys()
+        .copied()
+        .chain(entries.iter().map(|entry| entry.edit.page))
+        .collect();
+    pages.sort_unstable();
+    pages.dedup();
+
+    let cancel = Arc::new(AtomicBool::new(false));
+    if let Some(previous) = replay_slot(&state)?.replace(Arc::clone(&cancel)) {
+        previous.store(true, Ordering::Relaxed);
+    }
+
+    let count = entries.len();
+    let delays = replay_delays(&entries, speed);
+    let state = state.inner().clone();
+    tauri::async_runtime::spawn(async move {
+        let clock = state.annotation_clock();
+        broadcast(&state, WebSocketEvent::AnnotationReplay { playing: true });
+        broadcast(
+            &state,
+            WebSocketEvent::AnnotationsUpdated {
+                annotations: page_updates(&start, pages.iter().copied()),
+            },
+        );
+        for (entry, delay) in entries.iter().zip(delays) {
+            tokio::time::sleep(delay).await;
+            if cancel.load(Ordering::Relaxed) {
+                break;
+            }
+            broadcast(&state, edit_event(&entry.edit, clock));
+        }
+
+        // Back to the live annotations, including changes made meanwhile
+        let current = state
+            .annotations
+            .read()
+            .map(|annotations| page_updates(&annotations, pages));
+        if let Ok(annotations) = current {
+            broadcast(&state, WebSocketEvent::AnnotationsUpdated { annotations });
+        }
+        broadcast(&state, WebSocketEvent::AnnotationReplay { playing: false });
+        if let Ok(mut slot) = replay_slot(&state) {
+            if slot
+                .as_ref()
+                .is_some_and(|current| Arc::ptr_eq(current, &cancel))
+            {
+                *slot = None;
+            }
+        }
+    });
+
+    info!(speed, changes = count, "Replaying annotations");
+    Ok(count)
+}
+
+/// Stop the annotation replay in progress, if any
+#[tauri::command]
+#[instrument(skip(state))]
+pub async fn stop_annotation_replay(state: State<'_, AppState>) -> Result<bool> {
+    let Some(cancel) = replay_slot(&state)?.take() else {
+        return Ok(false);
+    };
+    cancel.store(true, Ordering::Relaxed);
+    info!("Annotation replay stopped");
+    Ok(true)
+}
+
+/// Wait before each change of a replay at `speed`
+pub fn replay_delays(entries: &[TimelineEntry], speed: f64) -> Vec<Duration> {
+    let mut previous = entries.first().map(|entry| entry.at);
+    entries
+        .iter()
+        .map(|entry| {
+            let gap = previous
+                .replace(entry.at)
+                .and_then(|at| (entry.at - at).to_std().ok())
+                .unwrap_or_default();
+            gap.div_f64(speed).min(MAX_GAP)
+        })
+        .collect()
+}
+
+fn broadcast(state: &AppState, event: WebSocketEvent) {
+    if let Err(e) = state.broadcast(event) {
+        warn!(error = %e, "Failed to broadcast annotation replay");
+    }
+}
+
+fn replay_slot(state: &AppState) -> Result<std::sync::MutexGuard<'_, Option<Arc<AtomicBool>>>> {
+    state
+        .annotation_replay
+        .lock()
+        .map_err(|e| StreamSlateError::StateLock(format!("Annotation replay: {e}")))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::DateTime;
+
+    #[test]
+    fn test_replay_delays() {
+        let entry = |secs| TimelineEntry {
+            at: DateTime::from_timestamp(secs, 0).unwrap(),
+            edit: history::added(1, r#"{"id":"a"}"#.to_string()),
+        };
+        let entries = [entry(100), entry(101), entry(101), entry(160)];
+
+        let delays = replay_delays(&entries, 2.0);
+        assert_eq!(
+            delays,
+            [
+                Duration::ZERO,
+                Duration::from_millis(500),
+                Duration::ZERO,
+                MAX_GAP
+            ]
+        );
+        assert!(replay_delays(&[], 1.0).is_empty());
+    }
+}
@@ -0,0 +1,227 @@
+/*
+ * This file is part of StreamSlate.
+ * Copyright (C) 2025 StreamSlate Contributors
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+
+//! Annotation statistics
+//!
+//! Counts the open document's annotations per page, type and author, e.g.
+//! for a teacher summarizing the markup on a stack of graded papers.
+//! Annotations made on the host have no author and are counted under an
+//! empty name.
+
+use super::annotations::Annotation;
+use super::pdf::validate_output_path;
+use crate::analytics::csv_field;
+use crate::error::{Result, StreamSlateError};
+use crate::state::AppState;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use tauri::State;
+use tracing::{info, instrument};
+
+/// Header row of the CSV export
+const CSV_HEADER: &str = "page,type,author,count";
+
+/// Annotation counts for a document
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AnnotationReport {
+    pub document: String,
+    pub total: usize,
+    pub by_type: BTreeMap<String, usize>,
+    pub by_author: BTreeMap<String, usize>,
+    /// Pages with annotations, in order
+    pub pages: Vec<PageReport>,
+}
+
+/// Annotation counts for one page
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PageReport {
+    pub page: u32,
+    pub total: usize,
+    pub by_type: BTreeMap<String, usize>,
+    pub by_author: BTreeMap<String, usize>,
+    /// Count per type and author, as in the CSV export
+    #[serde(skip)]
+    rows: BTreeMap<(String, String), usize>,
+}
+
+/// Format of an exported report
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ReportFormat {
+    /// One `page,type,author,count` row per combination
+    Csv,
+    /// The report as returned by `get_annotation_report`
+    Json,
+}
+
+/// Count the open document's annotations per page, type and author
+#[tauri::command]
+#[instrument(skip(state))]
+pub async fn get_annotation_report(state: State<'_, AppState>) -> Result<AnnotationReport> {
+    open_report(&state)
+}
+
+/// Write the annotation report to `output_path` as CSV or JSON, returning
+/// the number of annotations counted
+#[tauri::command]
+#[instrument(skip(state))]
+pub async fn export_annotation_report(
+    output_path: String,
+    format: ReportFormat,
+    state: State<'_, AppState>,
+) -> Result<usize> {
+    let report = open_report(&state)?;
+    let output = validate_output_path(&output_path, Some(&report.document))?;
+    let content = match format {
+        ReportFormat::Csv => report_csv(&report),
+        ReportFormat::Json => serde_json::to_string_pretty(&report)?,
+    };
+    std::fs::write(&output, content)?;
+    info!(path = %output.display(), ?format, total = report.total, "Exported annotation report");
+    Ok(report.total)
+}
+
+fn open_report(state: &AppState) -> Result<AnnotationReport> {
+    let pdf_path = state
+        .get_pdf_state()?
+        .current_file
+        .ok_or(StreamSlateError::NoDocument)?;
+    let annotations: HashMap<u32, Vec<Annotation>> = state
+        .annotations
+        .read()
+        .map_err(|e| StreamSlateError::StateLock(format!("Annotations: {e}")))?
+        .iter()
+        .map(|(page, list)| {
+            let parsed = list
+                .iter()
+                .filter_map(|s| serde_json::from_str(s).ok())
+                .collect();
+            (*page, parsed)
+        })
+        .collect();
+    Ok(build_report(&pdf_path, &annotations))
+}
+
+/// Count `annotations` of `document`
+pub fn build_report(
+    document: &str,
+    annotations: &HashMap<u32, Vec<Annotation>>,
+) -> AnnotationReport {
+    let mut report = AnnotationReport {
+        document: document.to_string(),
+        total: 0,
+        by_type: BTreeMap::new(),
+        by_author: BTreeMap::new(),
+        pages: Vec::new(),
+    };
+    let mut pages: Vec<_> = annotations
+        .iter()
+        .filter(|(_, list)| !list.is_empty())
+        .collect();
+    pages.sort_by_key(|(page, _)| **page);
+
+    for (page, list) in pages {
+        let mut page_report = PageReport {
+            page: *page,
+            total: list.len(),
+            by_type: BTreeMap::new(),
+            by_author: BTreeMap::new(),
+            rows: BTreeMap::new(),
+        };
+        for annotation in list {
+            let kind = serde_json::to_value(annotation.annotation_type)
+                .ok()
+                .and_then(|value| value.as_str().map(str::to_string))
+                .unwrap_or_default();
+            let author = annotation.author.clone().unwrap_or_default();
+            *page_report.by_type.entry(kind.clone()).or_default() += 1;
+            *page_report.by_author.entry(author.clone()).or_default() += 1;
+            *report.by_type.entry(kind.clone()).or_default() += 1;
+            *report.by_author.entry(author.clone()).or_default() += 1;
+            *page_report.rows.entry((kind, author)).or_default() += 1;
+        }
+        report.total += page_report.total;
+        report.pages.push(page_report);
+    }
+    report
+}
+
+/// The report as CSV with a header row
+pub fn report_csv(report: &AnnotationReport) -> String {
+    let mut csv = format!("{CSV_HEADER}\n");
+    for page in &report.pages {
+        for ((kind, author), count) in &page.rows {
+            csv.push_str(&format!(
+                "{},{},{},{}\n",
+                page.page,
+                kind,
+                csv_field(author),
+                count
+            ));
+        }
+    }
+    csv
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn annotation(kind: &str, author: Option<&str>) -> Annotation {
+        serde_json::from_value(serde_json::json!({
+            "id": uuid::Uuid::new_v4().to_string(), "type": kind, "pageNumber": 1,
+            "x": 0, "y": 0, "width": 10, "height": 10, "content": "",
+            "color": "#000000", "opacity": 1, "created": "", "modified": "",
+            "visible": true, "author": author,
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_build_report() {
+        let mut annotations = HashMap::new();
+        annotations.insert(
+            3,
+            vec![
+                annotation("highlight", Some("Lee, TA")),
+                annotation("highlight", None),
+                annotation("text", None),
+            ],
+        );
+        annotations.insert(1, vec![annotation("highlight", None)]);
+        annotations.insert(2, Vec::new());
+
+        let report = build_report("/exams/paper.pdf", &annotations);
+        assert_eq!(report.total, 4);
+        assert_eq!(report.by_type["highlight"], 3);
+        assert_eq!(report.by_author[""], 3);
+        let pages: Vec<_> = report.pages.iter().map(|p| (p.page, p.total)).collect();
+        assert_eq!(pages, [(1, 1), (3, 3)]);
+
+        assert_eq!(
+            report_csv(&report),
+            "page,type,author,count\n\
+             1,highlight,,1\n\
+             3,highlight,,1\n\
+             3,highlight,\"Lee, TA\",1\n\
+             3,text,,1\n"
+        );
+    }
+}
@@ -0,0 +1,168 @@
+/*
+ * This file is part of StreamSlate.
+ * Copyright (C) 2025 StreamSlate Contributors
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+
+//! Annotation search
+//!
+//! Finds annotations of the open document by their text, so a note made an
+//! hour into a session ("pricing correction") can be found without paging
+//! back through the deck. Matching ignores case, and every word of the query
+//! must appear in the annotation's content or author.
+
+use super::annotations::Annotation;
+use super::shapes::AnnotationKind;
+use crate::error::{Result, StreamSlateError};
+use crate::state::AppState;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use tauri::State;
+use tracing::{debug, instrument};
+
+/// An annotation found by [`search_annotations`]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct AnnotationMatch {
+    pub page: u32,
+    pub annotation: serde_json::Value,
+}
+
+/// Find annotations whose text contains every word of `query`, in page order
+#[tauri::command]
+#[instrument(skip(state))]
+pub async fn search_annotations(
+    query: String,
+    state: State<'_, AppState>,
+) -> Result<Vec<AnnotationMatch>> {
+    search_annotations_state(&state, &query)
+}
+
+/// Shared search for the Tauri command and WebSocket handler
+pub(crate) fn search_annotations_state(
+    state: &AppState,
+    query: &str,
+) -> Result<Vec<AnnotationMatch>> {
+    let annotations = state
+        .annotations
+        .read()
+        .map_err(|e| StreamSlateError::StateLock(format!("Annotations: {e}")))?;
+    let matches = find_annotations(&annotations, query)?;
+    debug!(query, count = matches.len(), "Searched annotations");
+    Ok(matches)
+}
+
+/// Search serialized annotations keyed by page
+pub fn find_annotations(
+    annotations: &HashMap<u32, Vec<String>>,
+    query: &str,
+) -> Result<Vec<AnnotationMatch>> {
+    let terms: Vec<String> = query.split_whitespace().map(str::to_lowercase).collect();
+    if terms.is_empty() {
+        return Err(StreamSlateError::Other("Search query is empty".to_string()));
+    }
+
+    let mut pages: Vec<u32> = annotations.keys().copied().collect();
+    pages.sort_unstable();
+    let mut matches = Vec::new();
+    for page in pages {
+        for serialized in &annotations[&page] {
+            let Ok(annotation) = serde_json::from_str::<Annotation>(serialized) else {
+                continue;
+            };
+            if !matches_terms(&annotation, &terms) {
+                continue;
+            }
+            if let Ok(annotation) = serde_json::from_str(serialized) {
+                matches.push(AnnotationMatch { page, annotation });
+            }
+        }
+    }
+    Ok(matches)
+}
+
+/// Whether every lowercase term is in the annotation's text
+fn matches_terms(annotation: &Annotation, terms: &[String]) -> bool {
+    // Ink keeps its points in `content` in older sidecars
+    let content = match annotation.annotation_type {
+        AnnotationKind::Ink => "",
+        _ => annotation.content.as_str(),
+    };
+    let text = format!(
+        "{} {}",
+        content,
+        annotation.author.as_deref().unwrap_or_default()
+    )
+    .to_lowercase();
+    terms.iter().all(|term| text.contains(term.as_str()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn stored(id: &str, kind: &str, content: &str, author: Option<&str>) -> String {
+        serde_json::json!({
+            "id": id, "type": kind, "pageNumber": 1, "x": 0, "y": 0,
+            "width": 10, "height": 10, "content": content, "color": "#000000",
+            "opacity": 1, "created": "", "modified": "", "visible": true,
+            "author": author,
+        })
+        .to_string()
+    }
+
+    fn ids(matches: &[AnnotationMatch]) -> Vec<(u32, &str)> {
+        matches
+            .iter()
+            .map(|m| (m.page, m.annotation["id"].as_str().unwrap()))
+            .collect()
+    }
+
+    #[test]
+    fn test_find_annotations() {
+        let annotations = HashMap::from([
+            (
+                12,
+                vec![
+                    stored("late", "text", "Pricing correction: $49 not $59", None),
+                    stored("ink", "free_draw", "[{\"x\":1,\"y\":2}]", None),
+                ],
+            ),
+            (
+                3,
+                vec![
+                    stored("early", "text", "Check PRICING slide", Some("Ana")),
+                    stored("box", "rectangle", "", Some("Ana")),
+                ],
+            ),
+        ]);
+
+        let found = find_annotations(&annotations, "pricing").unwrap();
+        assert_eq!(ids(&found), [(3, "early"), (12, "late")]);
+        assert_eq!(
+            found[1].annotation["content"],
+            "Pricing correction: $49 not $59"
+        );
+
+        let found = find_annotations(&annotations, "  Pricing   CORRECTION ").unwrap();
+        assert_eq!(ids(&found), [(12, "late")]);
+
+        let found = find_annotations(&annotations, "ana").unwrap();
+        assert_eq!(ids(&found), [(3, "early"), (3, "box")]);
+
+        assert!(find_annotations(&annotations, "x").unwrap().is_empty());
+        assert!(find_annotations(&annotations, "   ").is_err());
+    }
+}
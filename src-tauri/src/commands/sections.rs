@@ -0,0 +1,152 @@
+/*
+ * This file is part of StreamSlate.
+ * Copyright (C) 2025 StreamSlate Contributors
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+
+//! Section marker commands
+//!
+//! Named markers at page numbers, stored in the annotations sidecar and
+//! independent of the PDF's own bookmarks, so a host can skip between
+//! rundown segments (`NEXT_SECTION` / `PREVIOUS_SECTION` over WebSocket).
+
+use super::annotations::{read_sidecar, write_sidecar};
+use crate::error::{Result, StreamSlateError};
+use crate::state::AppState;
+use serde::{Deserialize, Serialize};
+use tauri::State;
+use tracing::{info, instrument};
+
+/// A named marker at the page a section starts on
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Section {
+    pub id: String,
+    pub name: String,
+    pub page: u32,
+}
+
+/// Add a section marker starting at `page`
+#[tauri::command]
+#[instrument(skip(state))]
+pub async fn create_section(
+    name: String,
+    page: u32,
+    state: State<'_, AppState>,
+) -> Result<Section> {
+    let (pdf_path, total_pages) = open_document(&state)?;
+    let name = name.trim().to_string();
+    if name.is_empty() {
+        return Err(StreamSlateError::Other("Section name is empty".to_string()));
+    }
+    if page < 1 || page > total_pages {
+        return Err(StreamSlateError::PageOutOfRange { page, total_pages });
+    }
+
+    let section = Section {
+        id: uuid::Uuid::new_v4().to_string(),
+        name,
+        page,
+    };
+    let mut file = read_sidecar(&pdf_path)?;
+    file.sections.push(section.clone());
+    file.sections.sort_by_key(|s| s.page);
+    write_sidecar(&mut file)?;
+
+    info!(name = %section.name, page, "Section created");
+    Ok(section)
+}
+
+/// List section markers of the open document, ordered by page
+#[tauri::command]
+#[instrument(skip(state))]
+pub async fn list_sections(state: State<'_, AppState>) -> Result<Vec<Section>> {
+    sections_for_open_document(&state)
+}
+
+/// Remove a section marker
+#[tauri::command]
+#[instrument(skip(state))]
+pub async fn delete_section(id: String, state: State<'_, AppState>) -> Result<()> {
+    let (pdf_path, _) = open_document(&state)?;
+    let mut file = read_sidecar(&pdf_path)?;
+    let before = file.sections.len();
+    file.sections.retain(|s| s.id != id);
+    if file.sections.len() == before {
+        return Err(StreamSlateError::Other(format!("No section with id {id}")));
+    }
+    write_sidecar(&mut file)?;
+
+    info!(id = %id, "Section deleted");
+    Ok(())
+}
+
+/// Section markers of the open document, ordered by page
+pub(crate) fn sections_for_open_document(state: &AppState) -> Result<Vec<Section>> {
+    let (pdf_path, _) = open_document(state)?;
+    let mut sections = read_sidecar(&pdf_path)?.sections;
+    sections.sort_by_key(|s| s.page);
+    Ok(sections)
+}
+
+/// First page of the next section after `current`
+pub fn next_section_page(sections: &[Section], current: u32) -> Option<u32> {
+    sections
+        .iter()
+        .map(|s| s.page)
+        .filter(|&p| p > current)
+        .min()
+}
+
+/// Start of the section before `current`; from mid-section, its own start
+pub fn previous_section_page(sections: &[Section], current: u32) -> Option<u32> {
+    sections
+        .iter()
+        .map(|s| s.page)
+        .filter(|&p| p < current)
+        .max()
+}
+
+pub(super) fn open_document(state: &AppState) -> Result<(String, u32)> {
+    let pdf_state = state.get_pdf_state()?;
+    match pdf_state.current_file {
+        Some(path) if pdf_state.is_loaded => Ok((path, pdf_state.total_pages)),
+        _ => Err(StreamSlateError::NoDocument),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_section_navigation() {
+        let sections: Vec<Section> = [(1, "Intro"), (4, "Demo"), (9, "Q&A")]
+            .into_iter()
+            .map(|(page, name)| Section {
+                id: name.to_string(),
+                name: name.to_string(),
+                page,
+            })
+            .collect();
+
+        assert_eq!(next_section_page(&sections, 1), Some(4));
+        assert_eq!(next_section_page(&sections, 6), Some(9));
+        assert_eq!(next_section_page(&sections, 9), None);
+
+        assert_eq!(previous_section_page(&sections, 6), Some(4));
+        assert_eq!(previous_section_page(&sections, 4), Some(1));
+        assert_eq!(previous_section_page(&sections, 1), None);
+    }
+}
@@ -0,0 +1,354 @@
+/*
+ * This file is part of StreamSlate.
+ * Copyright (C) 2025 StreamSlate Contributors
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+
+//! Annotation kinds and shape validation
+//!
+//! Annotations from remote clients are checked before they reach the shared
+//! state: coordinates must be finite, boxes non-empty, colors hex and
+//! opacities between 0 and 1. What can be repaired without guessing is
+//! normalized instead — boxes drawn right-to-left get a positive size and
+//! colors become lowercase `#rrggbb` — so every view sees the same shape.
+
+use super::annotations::Annotation;
+use crate::pdf::xfdf::parse_color;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+/// What an annotation draws
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum AnnotationKind {
+    /// Translucent box over text
+    Highlight,
+    /// Freehand strokes through `points`
+    #[serde(rename = "free_draw", alias = "ink")]
+    Ink,
+    #[serde(rename = "rectangle", alias = "rect")]
+    Rect,
+    /// Ellipse inscribed in the box
+    #[serde(rename = "circle", alias = "ellipse")]
+    Ellipse,
+    /// Line from (`x`, `y`) to (`x + width`, `y + height`) with a head at
+    /// the end
+    Arrow,
+    /// Line from (`x`, `y`) to (`x + width`, `y + height`)
+    Line,
+    /// Closed outline through `points`
+    Polygon,
+    /// `content` with its baseline at `y`
+    Text,
+    /// Stored stamp named by `image`
+    Image,
+}
+
+impl AnnotationKind {
+    /// Whether the annotation is a box that can't have a negative size
+    fn is_box(self) -> bool {
+        matches!(
+            self,
+            Self::Highlight | Self::Rect | Self::Ellipse | Self::Text | Self::Image
+        )
+    }
+
+    /// Fewest `points` the kind can be drawn from, if it is drawn from points
+    fn min_points(self) -> Option<usize> {
+        match self {
+            Self::Ink => Some(2),
+            Self::Polygon => Some(3),
+            _ => None,
+        }
+    }
+}
+
+/// A field of an annotation that was rejected, and why
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub struct FieldError {
+    /// Field name as sent, e.g. `width` or `points`
+    pub field: String,
+    pub message: String,
+}
+
+impl FieldError {
+    fn new(field: &str, message: impl Into<String>) -> Self {
+        Self {
+            field: field.to_string(),
+            message: message.into(),
+        }
+    }
+}
+
+/// Fields every annotation needs once defaults are filled in
+const REQUIRED_FIELDS: [&str; 6] = ["type", "x", "y", "width", "height", "color"];
+
+/// Check an annotation's geometry and colors, normalizing them in place
+///
+/// Every invalid field is reported, not just the first.
+pub fn normalize_annotation(a: &mut Annotation) -> Result<(), Vec<FieldError>> {
+    let mut errors = Vec::new();
+    let kind = a.annotation_type;
+
+    if a.page_number == 0 {
+        errors.push(FieldError::new("pageNumber", "pages are numbered from 1"));
+    }
+    for (field, value) in [
+        ("x", a.x),
+        ("y", a.y),
+        ("width", a.width),
+        ("height", a.height),
+    ] {
+        if !value.is_finite() {
+            errors.push(FieldError::new(field, "must be a finite number"));
+        }
+    }
+
+    if kind.is_box() {
+        if a.width < 0.0 {
+            a.x += a.width;
+            a.width = -a.width;
+        }
+        if a.height < 0.0 {
+            a.y += a.height;
+            a.height = -a.height;
+        }
+        // Text grows to fit its content
+        if kind != AnnotationKind::Text {
+            for (field, value) in [("width", a.width), ("height", a.height)] {
+                if value == 0.0 {
+                    errors.push(FieldError::new(field, "box is empty"));
+                }
+            }
+        }
+    }
+    if matches!(kind, AnnotationKind::Arrow | AnnotationKind::Line)
+        && a.width == 0.0
+        && a.height == 0.0
+    {
+        errors.push(FieldError::new("width", "line has no length"));
+    }
+
+    if let Some(min) = kind.min_points() {
+        match &a.points {
+            Some(points) if points.len() < min => errors.push(FieldError::new(
+                "points",
+                format!("needs at least {min} points"),
+            )),
+            Some(points) if points.iter().any(|p| !p.is_valid()) => errors.push(FieldError::new(
+                "points",
+                "must be finite, with pressure from 0 to 1 and tilt from -90 to 90",
+            )),
+            Some(_) => {}
+            None => errors.push(FieldError::new("points", "is required")),
+        }
+    }
+    if kind == AnnotationKind::Image && a.image.is_none() {
+        errors.push(FieldError::new("image", "is required"));
+    }
+
+    match normalize_color(&a.color) {
+        Some(color) => a.color = color,
+        None => errors.push(FieldError::new("color", "must be #rgb or #rrggbb")),
+    }
+    if let Some(background) = &a.background_color {
+        match normalize_color(background) {
+            Some(color) => a.background_color = Some(color),
+            None => errors.push(FieldError::new(
+                "backgroundColor",
+                "must be #rgb or #rrggbb",
+            )),
+        }
+    }
+
+    let unit = |value: f64| (0.0..=1.0).contains(&value);
+    if !unit(a.opacity) {
+        errors.push(FieldError::new("opacity", "must be between 0 and 1"));
+    }
+    if a.background_opacity.is_some_and(|o| !unit(o)) {
+        errors.push(FieldError::new(
+            "backgroundOpacity",
+            "must be between 0 and 1",
+        ));
+    }
+    let positive = |value: f64| value.is_finite() && value > 0.0;
+    if a.stroke_width.is_some_and(|w| !positive(w)) {
+        errors.push(FieldError::new("strokeWidth", "must be positive"));
+    }
+    if a.font_size.is_some_and(|s| !positive(s)) {
+        errors.push(FieldError::new("fontSize", "must be positive"));
+    }
+
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(errors)
+    }
+}
+
+/// Validate an annotation sent as JSON for `page`, writing normalized and
+/// defaulted values back into it
+///
+/// `current` is the stored annotation an update applies to; an update only
+/// carries the fields it changes. Fields a client may leave out of a new
+/// annotation (`id`, timestamps, `content`, `opacity`, `visible`) get
+/// defaults.
+pub fn normalize_annotation_json(
+    annotation: &mut Map<String, Value>,
+    current: Option<&Map<String, Value>>,
+    page: u32,
+) -> Result<(), Vec<FieldError>> {
+    let now = chrono::Utc::now().to_rfc3339();
+    let mut merged = current.cloned().unwrap_or_default();
+    merged.extend(annotation.clone());
+    let sent = merged.clone();
+    for (field, value) in [
+        ("id", Value::from(uuid::Uuid::new_v4().to_string())),
+        ("content", Value::from("")),
+        ("opacity", Value::from(1.0)),
+        ("visible", Value::from(true)),
+        ("created", Value::from(now.clone())),
+        ("modified", Value::from(now)),
+    ] {
+        merged.entry(field).or_insert(value);
+    }
+    merged.insert("pageNumber".to_string(), page.into());
+
+    let mut errors: Vec<FieldError> = REQUIRED_FIELDS
+        .iter()
+        .filter(|field| !merged.contains_key(**field))
+        .map(|field| FieldError::new(field, "is required"))
+        .collect();
+    if let Some(kind) = merged.get("type") {
+        if AnnotationKind::deserialize(kind).is_err() {
+            errors.push(FieldError::new(
+                "type",
+                format!("unknown annotation type {kind}"),
+            ));
+        }
+    }
+    if !errors.is_empty() {
+        return Err(errors);
+    }
+
+    let mut parsed: Annotation = serde_json::from_value(Value::Object(merged.clone()))
+        .map_err(|e| vec![FieldError::new("annotation", e.to_string())])?;
+    normalize_annotation(&mut parsed)?;
+
+    let Ok(Value::Object(normalized)) = serde_json::to_value(&parsed) else {
+        return Err(vec![FieldError::new("annotation", "cannot be serialized")]);
+    };
+    for (field, value) in normalized {
+        if !sent.get(&field).is_some_and(|old| same_value(old, &value)) {
+            annotation.insert(field, value);
+        }
+    }
+    Ok(())
+}
+
+/// `#rgb` or `#rrggbb` as lowercase `#rrggbb`
+pub(crate) fn normalize_color(color: &str) -> Option<String> {
+    let (r, g, b) = parse_color(color)?;
+    Some(format!("#{r:02x}{g:02x}{b:02x}"))
+}
+
+/// Equal JSON, counting `2` and `2.0` as the same number
+fn same_value(a: &Value, b: &Value) -> bool {
+    match (a.as_f64(), b.as_f64()) {
+        (Some(x), Some(y)) => x == y,
+        _ => a == b,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn object(value: Value) -> Map<String, Value> {
+        match value {
+            Value::Object(map) => map,
+            _ => unreachable!(),
+        }
+    }
+
+    #[test]
+    fn test_kind_names() {
+        for (name, kind) in [
+            ("rectangle", AnnotationKind::Rect),
+            ("rect", AnnotationKind::Rect),
+            ("circle", AnnotationKind::Ellipse),
+            ("ellipse", AnnotationKind::Ellipse),
+            ("free_draw", AnnotationKind::Ink),
+            ("ink", AnnotationKind::Ink),
+            ("polygon", AnnotationKind::Polygon),
+        ] {
+            assert_eq!(AnnotationKind::deserialize(&json!(name)).unwrap(), kind);
+        }
+        // Written with the names the frontend has always used
+        assert_eq!(json!(AnnotationKind::Rect), json!("rectangle"));
+        assert_eq!(json!(AnnotationKind::Ink), json!("free_draw"));
+    }
+
+    #[test]
+    fn test_new_annotation_is_normalized() {
+        let mut annotation = object(json!({
+            "id": "a1", "type": "rect", "x": 100, "y": 80, "width": -40, "height": 20,
+            "color": "#F00"
+        }));
+        normalize_annotation_json(&mut annotation, None, 3).unwrap();
+        assert_eq!(annotation["type"], "rectangle");
+        assert_eq!(annotation["x"], 60.0);
+        assert_eq!(annotation["width"], 40.0);
+        assert_eq!(annotation["color"], "#ff0000");
+        assert_eq!(annotation["pageNumber"], 3);
+        assert_eq!(annotation["visible"], true);
+        // Untouched fields aren't rewritten
+        assert_eq!(annotation["y"], 80);
+    }
+
+    #[test]
+    fn test_invalid_annotation_lists_every_field() {
+        let mut annotation = object(json!({
+            "type": "polygon", "x": 0, "y": 0, "width": 10, "height": 10,
+            "color": "red", "opacity": 1.5, "points": [{ "x": 0, "y": 0 }]
+        }));
+        let errors = normalize_annotation_json(&mut annotation, None, 1).unwrap_err();
+        let fields: Vec<_> = errors.iter().map(|e| e.field.as_str()).collect();
+        assert_eq!(fields, ["points", "color", "opacity"]);
+
+        let mut annotation = object(json!({ "type": "sticker", "x": 0 }));
+        let errors = normalize_annotation_json(&mut annotation, None, 1).unwrap_err();
+        let fields: Vec<_> = errors.iter().map(|e| e.field.as_str()).collect();
+        assert_eq!(fields, ["y", "width", "height", "color", "type"]);
+    }
+
+    #[test]
+    fn test_update_is_checked_against_current() {
+        let current = object(json!({
+            "id": "a1", "type": "line", "pageNumber": 1, "x": 10, "y": 10, "width": 5,
+            "height": 0, "content": "", "color": "#000000", "opacity": 1,
+            "created": "", "modified": "", "visible": true
+        }));
+        let mut update = object(json!({ "id": "a1", "color": "#ABC" }));
+        normalize_annotation_json(&mut update, Some(&current), 1).unwrap();
+        assert_eq!(update, object(json!({ "id": "a1", "color": "#aabbcc" })));
+
+        let mut update = object(json!({ "id": "a1", "width": 0 }));
+        let errors = normalize_annotation_json(&mut update, Some(&current), 1).unwrap_err();
+        assert_eq!(errors, [FieldError::new("width", "line has no length")]);
+    }
+}
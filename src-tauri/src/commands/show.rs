@@ -0,0 +1,234 @@
+/*
+ * This file is part of StreamSlate.
+ * Copyright (C) 2025 StreamSlate Contributors
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+
+//! End-of-show teardown
+//!
+//! `end_show` runs everything an operator would otherwise do by hand once
+//! the stream is over, so it fits on a single Stream Deck button. Each step
+//! runs even if an earlier one failed; failures are listed in the summary.
+//!
+//! When page timing was running, the session timeline is written to
+//! `timelines/document-<session>.timeline.json` in the app data directory,
+//! with video chapters in `document-<session>.chapters.txt` beside it.
+
+use super::analytics::finish_page_timing;
+use super::annotations::persist_annotations;
+use super::ndi::stop_all_outputs;
+use super::presenter::close_presenter_window;
+use crate::analytics::{self, SessionReport};
+use crate::error::{Result, StreamSlateError};
+use crate::state::AppState;
+use crate::websocket::WebSocketEvent;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use tauri::{AppHandle, Emitter, Manager, State};
+use tracing::{debug, info, instrument, warn};
+
+/// Directory in the app data directory that session timelines go to
+const TIMELINE_DIR: &str = "timelines";
+
+/// Optional end-of-show steps
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub struct EndShowOptions {
+    /// Close the presenter window
+    #[serde(default)]
+    pub close_presenter: bool,
+    /// URL that receives the summary as a JSON `POST`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub webhook_url: Option<String>,
+}
+
+impl EndShowOptions {
+    /// Reject URLs that can't be posted to
+    pub fn validate(&self) -> Result<()> {
+        match &self.webhook_url {
+            Some(url) if !(url.starts_with("http://") || url.starts_with("https://")) => Err(
+                StreamSlateError::Other(format!("Show webhook must be an http(s) URL: {url}")),
+            ),
+            _ => Ok(()),
+        }
+    }
+}
+
+/// What `end_show` did
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub struct ShowSummary {
+    pub outputs_stopped: bool,
+    pub annotations_saved: bool,
+    /// Session timeline JSON, if page timing was running
+    pub timeline_path: Option<String>,
+    /// Video chapter list, if page timing was running
+    pub chapters_path: Option<String>,
+    pub presenter_closed: bool,
+    /// Steps that failed
+    pub errors: Vec<String>,
+}
+
+/// Stop every output, save annotations, export the session timeline and
+/// chapters, and optionally close the presenter window and post a
+/// completion webhook
+#[tauri::command]
+#[instrument(skip(state, app))]
+pub async fn end_show(
+    options: Option<EndShowOptions>,
+    state: State<'_, AppState>,
+    app: AppHandle,
+) -> Result<ShowSummary> {
+    let summary = end_show_state(&state, &app, options.unwrap_or_default())?;
+    state.broadcast(WebSocketEvent::ShowEnded {
+        summary: summary.clone(),
+    })?;
+    Ok(summary)
+}
+
+/// Shared end-of-show logic for the Tauri command and WebSocket handler
+pub(crate) fn end_show_state(
+    state: &AppState,
+    app: &AppHandle,
+    options: EndShowOptions,
+) -> Result<ShowSummary> {
+    options.validate()?;
+    let mut summary = ShowSummary::default();
+    let errors = &mut summary.errors;
+
+    summary.outputs_stopped = step(errors, "Stop outputs", stop_all_outputs(state)).is_some();
+
+    let saved = state
+        .annotations
+        .read()
+        .map_err(|e| StreamSlateError::StateLock(format!("Annotations: {e}")))
+        .and_then(|annotations| persist_annotations(state, &annotations));
+    summary.annotations_saved = step(errors, "Save annotations", saved).is_some();
+
+    if let Some(report) = finish_page_timing(state) {
+        let exported = timeline_dir(app).and_then(|dir| export_timeline(&report, &dir));
+        if let Some((timeline, chapters)) = step(errors, "Export timeline", exported) {
+            summary.timeline_path = Some(timeline);
+            summary.chapters_path = Some(chapters);
+        }
+    }
+
+    if options.close_presenter {
+        summary.presenter_closed = step(
+            errors,
+            "Close presenter",
+            close_presenter_window(app, state),
+        )
+        .is_some();
+    }
+
+    info!(failed = summary.errors.len(), "Show ended");
+    if let Err(e) = app.emit("show-ended", &summary) {
+        warn!("Failed to emit show-ended event: {}", e);
+    }
+
+    if let Some(url) = options.webhook_url {
+        let body = summary.clone();
+        tauri::async_runtime::spawn(async move {
+            match analytics::post_webhook(&url, &body).await {
+                Ok(()) => debug!(url = %url, "Posted show summary"),
+                Err(e) => warn!(url = %url, error = %e, "Failed to post show summary"),
+            }
+        });
+    }
+    Ok(summary)
+}
+
+fn timeline_dir(app: &AppHandle) -> Result<PathBuf> {
+    app.path()
+        .app_data_dir()
+        .map(|dir| dir.join(TIMELINE_DIR))
+        .map_err(|e| StreamSlateError::Other(format!("No app data directory: {e}")))
+}
+
+/// Write the timeline and chapters to `dir`, returning their paths
+fn export_timeline(report: &SessionReport, dir: &Path) -> Result<(String, String)> {
+    let stem = Path::new(&report.document).file_stem().map_or_else(
+        || "session".to_string(),
+        |s| s.to_string_lossy().to_string(),
+    );
+    let name = format!("{stem}-{}", report.session_id);
+    let timeline = dir.join(format!("{name}.timeline.json"));
+    let chapters = dir.join(format!("{name}.chapters.txt"));
+    std::fs::create_dir_all(dir)?;
+    std::fs::write(&timeline, serde_json::to_string_pretty(report)?)?;
+    std::fs::write(&chapters, analytics::chapters(report))?;
+    Ok((
+        timeline.to_string_lossy().to_string(),
+        chapters.to_string_lossy().to_string(),
+    ))
+}
+
+/// Keep the result of a teardown step, noting its failure
+fn step<T>(errors: &mut Vec<String>, name: &str, result: Result<T>) -> Option<T> {
+    result
+        .map_err(|e| {
+            warn!(step = name, error = %e, "End-of-show step failed");
+            errors.push(format!("{name}: {e}"));
+        })
+        .ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_end_show_options() {
+        let options: EndShowOptions = serde_json::from_str("{}").unwrap();
+        assert_eq!(options, EndShowOptions::default());
+        assert!(options.validate().is_ok());
+
+        let options = EndShowOptions {
+            close_presenter: true,
+            webhook_url: Some("ftp://example.com".to_string()),
+        };
+        assert!(options.validate().is_err());
+    }
+
+    #[test]
+    fn test_export_timeline() {
+        let dir = std::env::temp_dir().join(format!("streamslate-show-{}", uuid::Uuid::new_v4()));
+        let report = SessionReport {
+            session_id: "abc".to_string(),
+            document: "/shows/keynote.pdf".to_string(),
+            started_at: "2025-01-01T00:00:00Z".to_string(),
+            ended_at: "2025-01-01T00:01:00Z".to_string(),
+            duration_ms: 60_000,
+            pages: Vec::new(),
+            recording: None,
+        };
+
+        let (timeline, chapters) = export_timeline(&report, &dir).unwrap();
+        assert_eq!(Path::new(&timeline), dir.join("keynote-abc.timeline.json"));
+        assert_eq!(Path::new(&chapters), dir.join("keynote-abc.chapters.txt"));
+        assert!(Path::new(&chapters).exists());
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_step_collects_failures() {
+        let mut errors = Vec::new();
+        assert_eq!(step(&mut errors, "Save annotations", Ok(3)), Some(3));
+        let failed: Result<()> = Err(StreamSlateError::Other("disk full".to_string()));
+        assert_eq!(step(&mut errors, "Export timeline", failed), None);
+        assert_eq!(errors, ["Export timeline: disk full"]);
+    }
+}
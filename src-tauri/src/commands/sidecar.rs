@@ -0,0 +1,648 @@
+/*
+ * This file is part of StreamSlate.
+ * Copyright (C) 2025 StreamSlate Contributors
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+
+//! Annotation sidecar location
+//!
+//! By default sidecars are kept in the `annotations` folder of the app data
+//! directory, named by the SHA-256 of the document, so they follow a PDF that
+//! is renamed or moved and documents on read-only volumes can be annotated. A
+//! document whose content changed is found by the path it was last saved from
+//! instead, through an index of those paths kept in the store. Sidecars can
+//! also be kept next to the document (`document.pdf.annotations.json`), for
+//! every document or just some; a sidecar found in the other place is moved
+//! over, so switching loses nothing. Documents that can't be hashed, such as
+//! image folders, always keep theirs next to them.
+//!
+//! Sidecars are replaced through a synced temporary file, and the previous
+//! version is kept as `<sidecar>.bak` to recover from if the file is damaged.
+//! They can be encrypted; see [`super::encryption`].
+
+use super::encryption;
+use crate::error::{Result, StreamSlateError};
+use crate::settings::{load_settings, save_settings, Settings};
+use crate::state::AppState;
+use ring::digest::{Context, SHA256};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, OnceLock, RwLock};
+use std::time::SystemTime;
+use tauri::{AppHandle, Manager, State};
+use tracing::{debug, info, instrument, warn};
+
+/// Folder of the sidecar store inside the app data directory
+const STORE_FOLDER: &str = "annotations";
+
+/// File in the sidecar store indexing sidecars by document path
+const INDEX_FILE: &str = "paths.index";
+
+/// File name of the settings inside the app data directory
+pub const CONFIG_FILE: &str = "sidecars.json";
+
+/// Sidecar store directory, set once during app setup
+static STORE_DIR: OnceLock<PathBuf> = OnceLock::new();
+
+/// Where sidecars are kept
+static CONFIG: OnceLock<RwLock<SidecarConfig>> = OnceLock::new();
+
+/// A content hash with the modification time and size it was computed for
+type CachedHash = (SystemTime, u64, String);
+
+/// Content hashes keyed by path
+static HASH_CACHE: OnceLock<Mutex<HashMap<PathBuf, CachedHash>>> = OnceLock::new();
+
+/// File names of stored sidecars keyed by the document path last saved to them
+type PathIndex = HashMap<String, String>;
+
+/// Path indexes keyed by store directory, loaded on first use
+static PATH_INDEXES: OnceLock<Mutex<HashMap<PathBuf, PathIndex>>> = OnceLock::new();
+
+/// Where a document's sidecar is kept
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum SidecarLocation {
+    /// The app data directory, keyed by the document's content
+    #[default]
+    AppData,
+    /// Next to the document
+    Document,
+}
+
+/// Sidecar location settings
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SidecarConfig {
+    #[serde(default)]
+    pub location: SidecarLocation,
+    /// Locations chosen for single documents, keyed by path
+    #[serde(default)]
+    pub documents: HashMap<String, SidecarLocation>,
+    /// Encrypt sidecars with the key in the OS keychain
+    #[serde(default)]
+    pub encrypt: bool,
+}
+
+impl SidecarConfig {
+    /// Where the sidecar of `pdf_path` goes
+    pub fn location_for(&self, pdf_path: &str) -> SidecarLocation {
+        self.documents
+            .get(pdf_path)
+            .copied()
+            .unwrap_or(self.location)
+    }
+}
+
+impl Settings for SidecarConfig {}
+
+/// Where sidecars are kept, for every document and for single ones
+#[tauri::command]
+#[instrument]
+pub async fn get_sidecar_config() -> Result<SidecarConfig> {
+    Ok(config()
+        .read()
+        .map_err(|e| StreamSlateError::StateLock(format!("Sidecar config: {e}")))?
+        .clone())
+}
+
+/// Keep sidecars in the app data directory or next to their documents
+///
+/// Documents with a location of their own keep it. The open document's
+/// sidecar is moved right away, others the next time they are opened.
+#[tauri::command]
+#[instrument(skip(app, state))]
+pub async fn set_sidecar_location(
+    location: SidecarLocation,
+    app: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<()> {
+    update_config(&app, &state, |config| config.location = location)?;
+    info!(?location, "Sidecar location updated");
+    Ok(())
+}
+
+/// Choose where the open document's sidecar is kept, or with `None` follow
+/// the general setting again
+#[tauri::command]
+#[instrument(skip(app, state))]
+pub async fn set_document_sidecar_location(
+    location: Option<SidecarLocation>,
+    app: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<()> {
+    let pdf_path = state
+        .get_pdf_state()?
+        .current_file
+        .ok_or(StreamSlateError::NoDocument)?;
+    update_config(&app, &state, |config| match location {
+        Some(location) => {
+            config.documents.insert(pdf_path.clone(), location);
+        }
+        None => {
+            config.documents.remove(&pdf_path);
+        }
+    })?;
+    info!(pdf_path, ?location, "Document sidecar location updated");
+    Ok(())
+}
+
+/// Encrypt sidecars with a key kept in the OS keychain, or stop
+///
+/// The open document's sidecar and its backup are converted right away,
+/// others when they are next saved; both kinds can always be read.
+#[tauri::command]
+#[instrument(skip(app, state))]
+pub async fn set_sidecar_encryption(
+    enabled: bool,
+    app: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<()> {
+    // Fail before changing anything if the keychain can't be used
+    if enabled {
+        encryption::key()?;
+    }
+    update_config(&app, &state, |config| config.encrypt = enabled)?;
+
+    if let Some(pdf_path) = state.get_pdf_state()?.current_file {
+        let path = sidecar_path(&pdf_path);
+        reseal(&path)?;
+        reseal(&backup_path(&path))?;
+    }
+    info!(enabled, "Sidecar encryption updated");
+    Ok(())
+}
+
+/// Change the settings, save them and move the open document's sidecar
+fn update_config(
+    app: &AppHandle,
+    state: &AppState,
+    change: impl FnOnce(&mut SidecarConfig),
+) -> Result<()> {
+    {
+        let mut config = config()
+            .write()
+            .map_err(|e| StreamSlateError::StateLock(format!("Sidecar config: {e}")))?;
+        let mut updated = config.clone();
+        change(&mut updated);
+        save_settings(app, CONFIG_FILE, &updated)?;
+        *config = updated;
+    }
+
+    if let Some(pdf_path) = state.get_pdf_state()?.current_file {
+        sidecar_path(&pdf_path);
+    }
+    Ok(())
+}
+
+/// Use the app data directory for sidecars and restore the stored settings
+pub fn init_sidecar_store(app_handle: &AppHandle) {
+    match app_handle.path().app_data_dir() {
+        Ok(dir) => {
+            let _ = STORE_DIR.set(dir.join(STORE_FOLDER));
+        }
+        Err(e) => warn!(error = %e, "No app data directory; keeping sidecars next to documents"),
+    }
+    if let Ok(mut config) = config().write() {
+        *config = load_settings(app_handle, CONFIG_FILE);
+    }
+}
+
+/// Path of the sidecar for `pdf_path`, moving it to where it belongs
+///
+/// The file may not exist yet.
+pub(crate) fn sidecar_path(pdf_path: &str) -> PathBuf {
+    let location = config()
+        .read()
+        .map(|config| config.location_for(pdf_path))
+        .unwrap_or_default();
+    locate(store_dir(), pdf_path, location)
+}
+
+/// The sidecar store directory, if there is an app data directory
+pub(crate) fn store_dir() -> Option<&'static Path> {
+    STORE_DIR.get().map(PathBuf::as_path)
+}
+
+/// Sidecar next to the document
+pub fn document_sidecar_path(pdf_path: &str) -> PathBuf {
+    PathBuf::from(format!("{pdf_path}.annotations.json"))
+}
+
+fn config() -> &'static RwLock<SidecarConfig> {
+    CONFIG.get_or_init(|| RwLock::new(SidecarConfig::default()))
+}
+
+fn locate(store: Option<&Path>, pdf_path: &str, location: SidecarLocation) -> PathBuf {
+    let beside = document_sidecar_path(pdf_path);
+    if location == SidecarLocation::Document && beside.exists() {
+        return beside;
+    }
+    let Some(keyed) = store.and_then(|store| stored_sidecar(store, pdf_path)) else {
+        return beside;
+    };
+
+    let (preferred, other) = match location {
+        SidecarLocation::AppData => (keyed, beside),
+        SidecarLocation::Document => (beside, keyed),
+    };
+    if preferred.exists() || !other.exists() {
+        return preferred;
+    }
+    match relocate(&other, &preferred) {
+        Ok(()) => {
+            info!(from = %other.display(), to = %preferred.display(), "Moved annotations");
+            preferred
+        }
+        Err(e) => {
+            warn!(path = %other.display(), error = %e, "Failed to move annotations");
+            other
+        }
+    }
+}
+
+/// Path of the stored sidecar of `pdf_path`, or `None` if the document
+/// can't be hashed
+///
+/// A sidecar saved before the document was edited is re-keyed to its new
+/// content.
+fn stored_sidecar(store: &Path, pdf_path: &str) -> Option<PathBuf> {
+    let keyed = store.join(format!("{}.json", content_hash(Path::new(pdf_path))?));
+    if keyed.exists() {
+        return Some(keyed);
+    }
+    let Some(previous) = find_by_path(store, pdf_path) else {
+        return Some(keyed);
+    };
+    match std::fs::rename(&previous, &keyed) {
+        Ok(()) => {
+            info!(
+                pdf_path,
+                "Document changed; annotations re-keyed to its new content"
+            );
+            index_sidecar(store, pdf_path, &keyed);
+            Some(keyed)
+        }
+        Err(e) => {
+            warn!(error = %e, "Failed to re-key annotations");
+            Some(previous)
+        }
+    }
+}
+
+/// Save the sidecar of `pdf_path`, noting a stored one in the path index
+pub(crate) fn save_sidecar(pdf_path: &str, content: &[u8]) -> Result<()> {
+    let path = sidecar_path(pdf_path);
+    write_atomic(&path, content)?;
+    if let Some(store) = store_dir().filter(|store| path.parent() == Some(*store)) {
+        index_sidecar(store, pdf_path, &path);
+    }
+    Ok(())
+}
+
+/// Backup of a sidecar, holding the version before the last save
+pub(crate) fn backup_path(path: &Path) -> PathBuf {
+    let mut backup = path.as_os_str().to_owned();
+    backup.push(".bak");
+    PathBuf::from(backup)
+}
+
+/// Replace a sidecar so a crash leaves either the old or the new version,
+/// keeping the old one as its backup
+pub(crate) fn write_atomic(path: &Path, content: &[u8]) -> Result<()> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    if path.exists() {
+        std::fs::copy(path, backup_path(path))?;
+    }
+    replace_file(path, content)
+}
+
+/// Sidecar content as stored, encrypted if that is enabled
+pub(crate) fn encode(json: String) -> Result<Vec<u8>> {
+    let encrypt = config()
+        .read()
+        .map_err(|e| StreamSlateError::StateLock(format!("Sidecar config: {e}")))?
+        .encrypt;
+    if encrypt {
+        encryption::seal(json.as_bytes())
+    } else {
+        Ok(json.into_bytes())
+    }
+}
+
+/// Read a sidecar, falling back to its backup if it is damaged
+///
+/// `None` if there is no sidecar.
+pub(crate) fn read_recovering<T: DeserializeOwned>(path: &Path) -> Result<Option<T>> {
+    let error = match std::fs::read_to_string(path) {
+        Ok(content) => match decode(content) {
+            Ok(value) => return Ok(Some(value)),
+            Err(e) => e,
+        },
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+        Err(e) => StreamSlateError::Io(e),
+    };
+
+    let backup = backup_path(path);
+    let recovered = std::fs::read_to_string(&backup)
+        .ok()
+        .and_then(|content| decode(content).ok());
+    match recovered {
+        Some(value) => {
+            warn!(path = %path.display(), error = %error, "Sidecar damaged; recovered the backup");
+            Ok(Some(value))
+        }
+        None => Err(error),
+    }
+}
+
+fn decode<T: DeserializeOwned>(content: String) -> Result<T> {
+    Ok(serde_json::from_str(&encryption::open(content)?)?)
+}
+
+/// Store an existing sidecar again, encrypted or not as now configured
+fn reseal(path: &Path) -> Result<()> {
+    let content = match std::fs::read_to_string(path) {
+        Ok(content) => content,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(()),
+        Err(e) => return Err(e.into()),
+    };
+    replace_file(path, &encode(encryption::open(content)?)?)
+}
+
+/// Write through a synced temporary file renamed over `path`
+fn replace_file(path: &Path, content: &[u8]) -> Result<()> {
+    let mut temp = path.as_os_str().to_owned();
+    temp.push(".tmp");
+    let temp = PathBuf::from(temp);
+
+    let result = (|| -> Result<()> {
+        let mut file = std::fs::File::create(&temp)?;
+        file.write_all(content)?;
+        file.sync_all()?;
+        std::fs::rename(&temp, path)?;
+        Ok(())
+    })();
+    if result.is_err() {
+        let _ = std::fs::remove_file(&temp);
+        return result;
+    }
+
+    // Make the rename itself durable; not every platform can open a folder
+    if let Some(dir) = path.parent().and_then(|p| std::fs::File::open(p).ok()) {
+        if let Err(e) = dir.sync_all() {
+            debug!(path = %path.display(), error = %e, "Could not sync sidecar folder");
+        }
+    }
+    Ok(())
+}
+
+/// Copy a sidecar to its new place, then remove the original
+fn relocate(from: &Path, to: &Path) -> std::io::Result<()> {
+    if let Some(parent) = to.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    std::fs::copy(from, to)?;
+    // A read-only volume keeps its copy; the new one is used from now on
+    if let Err(e) = std::fs::remove_file(from) {
+        debug!(path = %from.display(), error = %e, "Left moved sidecar in place");
+    }
+    Ok(())
+}
+
+/// The stored sidecar last saved for `pdf_path`
+fn find_by_path(store: &Path, pdf_path: &str) -> Option<PathBuf> {
+    let name = with_index(store, |index| index.get(pdf_path).cloned())??;
+    Some(store.join(name)).filter(|path| path.exists())
+}
+
+/// Record that `sidecar` in `store` was saved for `pdf_path`
+fn index_sidecar(store: &Path, pdf_path: &str, sidecar: &Path) {
+    let Some(name) = sidecar.file_name().and_then(|name| name.to_str()) else {
+        return;
+    };
+    let updated = with_index(store, |index| {
+        if index.get(pdf_path).is_some_and(|indexed| indexed == name) {
+            return None;
+        }
+        index.insert(pdf_path.to_string(), name.to_string());
+        serde_json::to_vec(index).ok()
+    });
+    if let Some(Some(content)) = updated {
+        if let Err(e) = replace_file(&store.join(INDEX_FILE), &content) {
+            warn!(error = %e, "Failed to save the sidecar index");
+        }
+    }
+}
+
+fn with_index<T>(store: &Path, f: impl FnOnce(&mut PathIndex) -> T) -> Option<T> {
+    let mut indexes = PATH_INDEXES
+        .get_or_init(|| Mutex::new(HashMap::new()))
+        .lock()
+        .ok()?;
+    let index = indexes
+        .entry(store.to_path_buf())
+        .or_insert_with(|| load_index(store));
+    Some(f(index))
+}
+
+fn load_index(store: &Path) -> PathIndex {
+    match std::fs::read_to_string(store.join(INDEX_FILE)) {
+        Ok(content) => serde_json::from_str(&content).unwrap_or_else(|e| {
+            warn!(error = %e, "Sidecar index damaged; rebuilding it");
+            build_index(store)
+        }),
+        Err(_) => build_index(store),
+    }
+}
+
+/// Index the unencrypted sidecars in a store, for stores from before the
+/// index or with a damaged one
+fn build_index(store: &Path) -> PathIndex {
+    #[derive(Deserialize)]
+    #[serde(rename_all = "camelCase")]
+    struct Header {
+        pdf_path: String,
+    }
+
+    let Ok(entries) = std::fs::read_dir(store) else {
+        return PathIndex::new();
+    };
+    let mut newest: HashMap<String, (Option<SystemTime>, String)> = HashMap::new();
+    let paths = entries
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "json"));
+    for path in paths {
+        let (Some(name), Some(header)) = (
+            path.file_name().and_then(|name| name.to_str()),
+            std::fs::read_to_string(&path)
+                .ok()
+                .and_then(|content| serde_json::from_str::<Header>(&content).ok()),
+        ) else {
+            continue;
+        };
+        let modified = std::fs::metadata(&path)
+            .and_then(|meta| meta.modified())
+            .ok();
+        match newest.get(&header.pdf_path) {
+            Some((time, _)) if *time >= modified => {}
+            _ => {
+                newest.insert(header.pdf_path, (modified, name.to_string()));
+            }
+        }
+    }
+    debug!(store = %store.display(), sidecars = newest.len(), "Indexed sidecar store");
+    newest
+        .into_iter()
+        .map(|(pdf_path, (_, name))| (pdf_path, name))
+        .collect()
+}
+
+/// Hex SHA-256 of a file's content, or `None` if it isn't a readable file
+pub fn content_hash(path: &Path) -> Option<String> {
+    let meta = std::fs::metadata(path).ok().filter(|meta| meta.is_file())?;
+    let (modified, len) = (meta.modified().ok()?, meta.len());
+    let cache = HASH_CACHE.get_or_init(|| Mutex::new(HashMap::new()));
+    if let Some((m, l, hash)) = cache.lock().ok()?.get(path) {
+        if (*m, *l) == (modified, len) {
+            return Some(hash.clone());
+        }
+    }
+
+    let mut file = std::fs::File::open(path).ok()?;
+    let mut context = Context::new(&SHA256);
+    let mut buffer = vec![0; 64 * 1024];
+    loop {
+        let read = file.read(&mut buffer).ok()?;
+        if read == 0 {
+            break;
+        }
+        context.update(&buffer[..read]);
+    }
+    let hash: String = context
+        .finish()
+        .as_ref()
+        .iter()
+        .map(|b| format!("{b:02x}"))
+        .collect();
+
+    if let Ok(mut cache) = cache.lock() {
+        cache.insert(path.to_path_buf(), (modified, len, hash.clone()));
+    }
+    Some(hash)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sidecar(pdf_path: &str) -> String {
+        serde_json::json!({ "version": 1, "pdfPath": pdf_path, "annotations": {} }).to_string()
+    }
+
+    #[test]
+    fn test_locate_sidecar() {
+        use SidecarLocation::{AppData, Document};
+
+        let dir =
+            std::env::temp_dir().join(format!("streamslate-sidecar-{}", uuid::Uuid::new_v4()));
+        let store = dir.join("store");
+        std::fs::create_dir_all(&dir).unwrap();
+        let pdf = dir.join("deck.pdf").to_string_lossy().into_owned();
+        std::fs::write(&pdf, b"%PDF-1.7 first").unwrap();
+
+        // Without a store, or for something that isn't a file, it's next to the document
+        assert_eq!(locate(None, &pdf, AppData), document_sidecar_path(&pdf));
+        let folder = dir.to_string_lossy().into_owned();
+        assert_eq!(
+            locate(Some(&store), &folder, AppData),
+            document_sidecar_path(&folder)
+        );
+
+        // An older sidecar moves into the store
+        std::fs::write(document_sidecar_path(&pdf), sidecar(&pdf)).unwrap();
+        let keyed = locate(Some(&store), &pdf, AppData);
+        assert_eq!(keyed.parent().unwrap(), store);
+        assert!(keyed.exists());
+        assert!(!document_sidecar_path(&pdf).exists());
+
+        // A renamed document keeps it
+        let renamed = dir.join("renamed.pdf").to_string_lossy().into_owned();
+        std::fs::rename(&pdf, &renamed).unwrap();
+        assert_eq!(locate(Some(&store), &renamed, AppData), keyed);
+
+        // An edited document finds it by the path it was saved from
+        index_sidecar(&store, &renamed, &keyed);
+        std::fs::write(&renamed, b"%PDF-1.7 second version").unwrap();
+        let rekeyed = locate(Some(&store), &renamed, AppData);
+        assert_ne!(rekeyed, keyed);
+        assert!(rekeyed.exists() && !keyed.exists());
+
+        // Choosing the document's folder moves it back out, and back in again
+        let beside = locate(Some(&store), &renamed, Document);
+        assert_eq!(beside, document_sidecar_path(&renamed));
+        assert!(beside.exists() && !rekeyed.exists());
+        assert_eq!(locate(Some(&store), &renamed, AppData), rekeyed);
+        assert!(rekeyed.exists() && !beside.exists());
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_build_index() {
+        let store =
+            std::env::temp_dir().join(format!("streamslate-sidecar-{}", uuid::Uuid::new_v4()));
+        assert!(build_index(&store).is_empty());
+
+        std::fs::create_dir_all(&store).unwrap();
+        std::fs::write(store.join("aaa.json"), sidecar("/shows/deck.pdf")).unwrap();
+        std::fs::write(store.join("bbb.json"), "sealed").unwrap();
+        std::fs::write(store.join("aaa.json.bak"), sidecar("/shows/old.pdf")).unwrap();
+        let index = build_index(&store);
+        assert_eq!(index.len(), 1);
+        assert_eq!(index["/shows/deck.pdf"], "aaa.json");
+
+        std::fs::remove_dir_all(store).unwrap();
+    }
+
+    #[test]
+    fn test_write_atomic() {
+        let dir =
+            std::env::temp_dir().join(format!("streamslate-sidecar-{}", uuid::Uuid::new_v4()));
+        let path = dir.join("deck.pdf.annotations.json");
+
+        write_atomic(&path, b"{\"version\": 1}").unwrap();
+        assert!(!backup_path(&path).exists());
+        write_atomic(&path, b"{\"version\": 2}").unwrap();
+        let read = |path: &Path| read_recovering::<serde_json::Value>(path).unwrap();
+        assert_eq!(read(&path).unwrap()["version"], 2);
+        assert_eq!(read(&backup_path(&path)).unwrap()["version"], 1);
+
+        // A truncated file falls back to the backup
+        std::fs::write(&path, b"{\"vers").unwrap();
+        assert_eq!(read(&path).unwrap()["version"], 1);
+        std::fs::write(backup_path(&path), b"").unwrap();
+        assert!(read_recovering::<serde_json::Value>(&path).is_err());
+        assert!(read(&dir.join("missing.json")).is_none());
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
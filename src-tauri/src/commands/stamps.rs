@@ -0,0 +1,223 @@
+/*
+ * This file is part of StreamSlate.
+ * Copyright (C) 2025 StreamSlate Contributors
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+
+//! Image stamp commands
+//!
+//! An `image` annotation draws a PNG (a logo, an "APPROVED" stamp, an emoji
+//! reaction) into its box. The PNG is stored once per document in a
+//! `document.pdf.stamps` directory next to the sidecar, named by its
+//! SHA-256, and annotations refer to it by that file name in `image`.
+
+use super::sections::open_document;
+use super::shapes::AnnotationKind;
+use crate::error::{Result, StreamSlateError};
+use crate::state::AppState;
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use ring::digest::{digest, SHA256};
+use serde::Deserialize;
+use std::path::PathBuf;
+use tauri::State;
+use tracing::{info, instrument};
+
+/// Refuse stamp files larger than this
+pub const MAX_STAMP_BYTES: usize = 1024 * 1024;
+
+/// Refuse stamps wider or taller than this many pixels
+pub const MAX_STAMP_SIDE: u32 = 2048;
+
+/// Store a PNG for image annotations on the open document
+///
+/// `data` is base64, optionally as a `data:image/png;base64,` URL. Returns
+/// the name to put in an annotation's `image`; storing the same image twice
+/// returns the same name.
+#[tauri::command]
+#[instrument(skip(data, state))]
+pub async fn add_stamp_image(data: String, state: State<'_, AppState>) -> Result<String> {
+    let (pdf_path, _) = open_document(&state)?;
+    let encoded = data.strip_prefix("data:image/png;base64,").unwrap_or(&data);
+    let png = BASE64
+        .decode(encoded.trim())
+        .map_err(|e| StreamSlateError::Other(format!("Stamp is not base64: {e}")))?;
+    let (width, height) = validate_png(&png)?;
+
+    let name = stamp_name(&png);
+    let path = stamps_dir(&pdf_path).join(&name);
+    if !path.exists() {
+        std::fs::create_dir_all(stamps_dir(&pdf_path))?;
+        std::fs::write(&path, &png)?;
+        info!(name = %name, width, height, "Stamp image stored");
+    }
+    Ok(name)
+}
+
+/// A stored stamp of the open document as a `data:image/png` URL
+#[tauri::command]
+#[instrument(skip(state))]
+pub async fn get_stamp_image(name: String, state: State<'_, AppState>) -> Result<String> {
+    let (pdf_path, _) = open_document(&state)?;
+    let png = std::fs::read(stamp_path(&pdf_path, &name)?)?;
+    Ok(format!("data:image/png;base64,{}", BASE64.encode(png)))
+}
+
+/// Check that `image` names a stored stamp, and that image annotations have
+/// one
+pub(crate) fn check_stamp_reference(
+    pdf_path: &str,
+    annotation_type: Option<AnnotationKind>,
+    image: Option<&str>,
+) -> Result<()> {
+    match (annotation_type, image) {
+        (_, Some(name)) => {
+            if stamp_path(pdf_path, name)?.is_file() {
+                Ok(())
+            } else {
+                Err(StreamSlateError::FileNotFound(format!("Stamp {name}")))
+            }
+        }
+        (Some(AnnotationKind::Image), None) => Err(StreamSlateError::Other(
+            "Image annotation has no image".to_string(),
+        )),
+        _ => Ok(()),
+    }
+}
+
+/// [`check_stamp_reference`] for an annotation sent as JSON by a remote
+/// client; an update without `type` only has its `image` checked
+pub(crate) fn check_stamp_json(
+    state: &AppState,
+    annotation: &serde_json::Map<String, serde_json::Value>,
+) -> Result<()> {
+    let annotation_type = annotation
+        .get("type")
+        .and_then(|t| AnnotationKind::deserialize(t).ok());
+    let image = annotation.get("image").and_then(|i| i.as_str());
+    if annotation_type != Some(AnnotationKind::Image) && image.is_none() {
+        return Ok(());
+    }
+    let (pdf_path, _) = open_document(state)?;
+    check_stamp_reference(&pdf_path, annotation_type, image)
+}
+
+/// Directory holding the stamps of `pdf_path`
+fn stamps_dir(pdf_path: &str) -> PathBuf {
+    PathBuf::from(format!("{pdf_path}.stamps"))
+}
+
+/// Copy stamp `name` of one document to another, if it isn't there yet
+pub(crate) fn copy_stamp(from_pdf: &str, to_pdf: &str, name: &str) -> Result<()> {
+    let target = stamp_path(to_pdf, name)?;
+    if !target.is_file() {
+        std::fs::create_dir_all(stamps_dir(to_pdf))?;
+        std::fs::copy(stamp_path(from_pdf, name)?, target)?;
+    }
+    Ok(())
+}
+
+/// Path of stamp `name`, which must be a name from [`stamp_name`]
+fn stamp_path(pdf_path: &str, name: &str) -> Result<PathBuf> {
+    let hash = name.strip_suffix(".png").unwrap_or_default();
+    if hash.len() != 64 || !hash.bytes().all(|b| matches!(b, b'0'..=b'9' | b'a'..=b'f')) {
+        return Err(StreamSlateError::Other(format!(
+            "Invalid stamp name: {name}"
+        )));
+    }
+    Ok(stamps_dir(pdf_path).join(name))
+}
+
+/// File name of a stamp: the hex SHA-256 of its bytes
+pub fn stamp_name(png: &[u8]) -> String {
+    let hash: String = digest(&SHA256, png)
+        .as_ref()
+        .iter()
+        .map(|b| format!("{b:02x}"))
+        .collect();
+    format!("{hash}.png")
+}
+
+/// Check that `png` is a PNG within the stamp limits, returning its size
+pub fn validate_png(png: &[u8]) -> Result<(u32, u32)> {
+    if png.len() > MAX_STAMP_BYTES {
+        return Err(StreamSlateError::Other(format!(
+            "Stamp is {} KiB; the limit is {} KiB",
+            png.len() / 1024,
+            MAX_STAMP_BYTES / 1024
+        )));
+    }
+    let reader = png::Decoder::new(png)
+        .read_info()
+        .map_err(|e| StreamSlateError::Other(format!("Stamp is not a valid PNG: {e}")))?;
+    let info = reader.info();
+    if info.width > MAX_STAMP_SIDE || info.height > MAX_STAMP_SIDE {
+        return Err(StreamSlateError::Other(format!(
+            "Stamp is {}x{} pixels; the limit is {MAX_STAMP_SIDE}x{MAX_STAMP_SIDE}",
+            info.width, info.height
+        )));
+    }
+    Ok((info.width, info.height))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn png(width: u32, height: u32) -> Vec<u8> {
+        let mut out = Vec::new();
+        let mut encoder = png::Encoder::new(&mut out, width, height);
+        encoder.set_color(png::ColorType::Rgba);
+        encoder.set_depth(png::BitDepth::Eight);
+        let mut writer = encoder.write_header().unwrap();
+        writer
+            .write_image_data(&vec![0; (width * height * 4) as usize])
+            .unwrap();
+        drop(writer);
+        out
+    }
+
+    #[test]
+    fn test_validate_png() {
+        assert_eq!(validate_png(&png(120, 40)).unwrap(), (120, 40));
+        assert!(validate_png(&png(MAX_STAMP_SIDE + 1, 1)).is_err());
+        assert!(validate_png(b"GIF89a").is_err());
+        assert!(validate_png(&vec![0; MAX_STAMP_BYTES + 1]).is_err());
+    }
+
+    #[test]
+    fn test_stamp_references() {
+        let dir = std::env::temp_dir().join(format!("streamslate-stamps-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let pdf_path = dir.join("deck.pdf").to_string_lossy().into_owned();
+
+        let image = png(8, 8);
+        let name = stamp_name(&image);
+        assert_eq!(name.len(), 68);
+        std::fs::create_dir_all(stamps_dir(&pdf_path)).unwrap();
+        std::fs::write(stamps_dir(&pdf_path).join(&name), &image).unwrap();
+
+        assert!(check_stamp_reference(&pdf_path, Some(AnnotationKind::Image), Some(&name)).is_ok());
+        assert!(check_stamp_reference(&pdf_path, Some(AnnotationKind::Rect), None).is_ok());
+        assert!(check_stamp_reference(&pdf_path, Some(AnnotationKind::Image), None).is_err());
+        let missing = stamp_name(b"other");
+        assert!(
+            check_stamp_reference(&pdf_path, Some(AnnotationKind::Image), Some(&missing)).is_err()
+        );
+        assert!(check_stamp_reference(&pdf_path, None, Some("../../secrets.png")).is_err());
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
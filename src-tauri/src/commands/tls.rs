@@ -0,0 +1,112 @@
+/*
+ * This file is part of StreamSlate.
+ * Copyright (C) 2025 StreamSlate Contributors
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+
+//! Control server TLS commands
+//!
+//! Settings take effect the next time the WebSocket server starts.
+
+use crate::error::{Result, StreamSlateError};
+use crate::settings::{load_settings, save_settings};
+use crate::websocket::tls::{self, TlsConfig};
+use serde::Serialize;
+use tauri::AppHandle;
+use tracing::{info, instrument};
+
+/// A certificate served by the control server
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TlsCertificate {
+    pub cert_path: String,
+    /// SHA-256 fingerprint, colon-separated hex
+    pub fingerprint: String,
+}
+
+/// The stored TLS settings
+#[tauri::command]
+#[instrument(skip(app))]
+pub async fn get_tls_config(app: AppHandle) -> Result<TlsConfig> {
+    Ok(load_settings(&app, tls::CONFIG_FILE))
+}
+
+/// Change the TLS settings
+///
+/// Turning TLS on without a certificate of the user's generates a
+/// self-signed one if there isn't one yet. The certificate is checked before
+/// saving, so a bad one can't keep the server from starting.
+#[tauri::command]
+#[instrument(skip(app))]
+pub async fn set_tls_config(config: TlsConfig, app: AppHandle) -> Result<()> {
+    config.validate()?;
+    if config.enabled {
+        let dir = cert_dir(&app)?;
+        let (cert, key) = config.files(&dir);
+        if config.cert_path.is_none() && !(cert.exists() && key.exists()) {
+            tls::generate_certificate(&dir, &[])?;
+        }
+        tls::acceptor(&cert, &key)?;
+    }
+    save_settings(&app, tls::CONFIG_FILE, &config)?;
+    info!(
+        enabled = config.enabled,
+        user_certificate = config.cert_path.is_some(),
+        "TLS settings updated"
+    );
+    Ok(())
+}
+
+/// Generate a new self-signed certificate, replacing the previous one
+///
+/// `names` are extra host names or LAN addresses clients connect to;
+/// localhost is always included.
+#[tauri::command]
+#[instrument(skip(app))]
+pub async fn generate_tls_certificate(
+    names: Vec<String>,
+    app: AppHandle,
+) -> Result<TlsCertificate> {
+    let dir = cert_dir(&app)?;
+    let fingerprint = tls::generate_certificate(&dir, &names)?;
+    let (cert, _) = TlsConfig::default().files(&dir);
+    info!(fingerprint = %fingerprint, "Generated TLS certificate");
+    Ok(TlsCertificate {
+        cert_path: cert.to_string_lossy().into_owned(),
+        fingerprint,
+    })
+}
+
+/// The certificate the server uses with the current settings, if there is one
+///
+/// Show its fingerprint so users can check it when a client warns about the
+/// self-signed certificate.
+#[tauri::command]
+#[instrument(skip(app))]
+pub async fn get_tls_certificate(app: AppHandle) -> Result<Option<TlsCertificate>> {
+    let config = get_tls_config(app.clone()).await?;
+    let (cert, _) = config.files(&cert_dir(&app)?);
+    if !cert.exists() {
+        return Ok(None);
+    }
+    Ok(Some(TlsCertificate {
+        fingerprint: tls::certificate_fingerprint(&cert)?,
+        cert_path: cert.to_string_lossy().into_owned(),
+    }))
+}
+
+fn cert_dir(app: &AppHandle) -> Result<std::path::PathBuf> {
+    tls::cert_dir(app).ok_or_else(|| StreamSlateError::Other("No app data directory".to_string()))
+}
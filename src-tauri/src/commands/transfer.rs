@@ -0,0 +1,268 @@
+/*
+ * This file is part of StreamSlate.
+ * Copyright (C) 2025 StreamSlate Contributors
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+
+//! Copying annotations between pages and documents
+//!
+//! Annotations are in page points, so when the source and target pages
+//! differ in size their geometry is scaled to the target page: a note in
+//! the corner of a letter page stays in the corner of an A4 or 16:9 one.
+
+use super::annotations::{page_updates, persist_annotations, publish_annotation_updates};
+use super::annotations::{read_sidecar, write_sidecar, Annotation};
+use super::pdf::{open_page_sizes, page_sizes, read_pdf_file};
+use super::stamps::copy_stamp;
+use crate::error::{Result, StreamSlateError};
+use crate::state::ink::set_points;
+use crate::state::{history, AppState};
+use serde::Serialize;
+use std::collections::HashMap;
+use tauri::{AppHandle, State};
+use tracing::{info, instrument, warn};
+
+/// Outcome of an annotation transfer
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AnnotationTransfer {
+    /// Annotations added to or replaced in the target document
+    pub transferred: usize,
+    /// Annotations whose page isn't in the target document
+    pub skipped: usize,
+}
+
+/// Copy the annotations on `from_page` to `to_page` of the open document
+///
+/// Copies get new IDs and are added to what `to_page` already has. Returns
+/// the target page's full annotation list. A single undo step.
+#[tauri::command]
+#[instrument(skip(state, app))]
+pub async fn copy_annotations(
+    from_page: u32,
+    to_page: u32,
+    state: State<'_, AppState>,
+    app: AppHandle,
+) -> Result<HashMap<u32, Vec<serde_json::Value>>> {
+    if state.get_pdf_state()?.current_file.is_none() {
+        return Err(StreamSlateError::NoDocument);
+    }
+    let sizes = open_page_sizes(&state)?;
+    let (from_size, to_size) = (page_size(&sizes, from_page)?, page_size(&sizes, to_page)?);
+
+    let (updates, copied) = {
+        let mut state_annotations = state
+            .annotations
+            .write()
+            .map_err(|e| StreamSlateError::StateLock(format!("Annotations: {e}")))?;
+        let now = chrono::Utc::now().to_rfc3339();
+        let copies: Vec<String> = state_annotations
+            .get(&from_page)
+            .into_iter()
+            .flatten()
+            .filter_map(|s| serde_json::from_str::<Annotation>(s).ok())
+            .filter_map(|mut annotation| {
+                if let (Some(from), Some(to)) = (from_size, to_size) {
+                    remap(&mut annotation, from, to);
+                }
+                annotation.id = uuid::Uuid::new_v4().to_string();
+                annotation.page_number = to_page;
+                annotation.created = now.clone();
+                annotation.modified = now.clone();
+                serde_json::to_string(&annotation).ok()
+            })
+            .collect();
+        if copies.is_empty() {
+            return Ok(HashMap::new());
+        }
+
+        let previous = state_annotations.clone();
+        let copied = copies.len();
+        state_annotations.entry(to_page).or_default().extend(copies);
+        state.record_annotation_edits(history::diff(&previous, &state_annotations));
+        persist_annotations(&state, &state_annotations)?;
+        (page_updates(&state_annotations, [to_page]), copied)
+    };
+
+    info!(from_page, to_page, copied, "Copied annotations");
+    state.snapshot_annotations("copy");
+    publish_annotation_updates(&state, &app, &updates);
+    Ok(updates)
+}
+
+/// Copy the open document's annotations into the sidecar of `target_pdf`,
+/// moving each `page_offset` pages (e.g. 1 when a slide was inserted
+/// before them)
+///
+/// Annotations already in the target with the same ID are replaced, and the
+/// layers and stamps they use come along. The open document is unchanged.
+#[tauri::command]
+#[instrument(skip(state))]
+pub async fn transfer_annotations(
+    target_pdf: String,
+    page_offset: Option<i32>,
+    state: State<'_, AppState>,
+) -> Result<AnnotationTransfer> {
+    let source_pdf = state
+        .get_pdf_state()?
+        .current_file
+        .ok_or(StreamSlateError::NoDocument)?;
+    if target_pdf == source_pdf {
+        return Err(StreamSlateError::Other(
+            "Annotations can't be transferred to the open document itself".to_string(),
+        ));
+    }
+    let target_sizes = page_sizes(&read_pdf_file(&target_pdf)?);
+    let source_sizes = open_page_sizes(&state)?;
+    let source = read_sidecar(&source_pdf)?;
+    let annotations: Vec<Annotation> = state
+        .annotations
+        .read()
+        .map_err(|e| StreamSlateError::StateLock(format!("Annotations: {e}")))?
+        .values()
+        .flatten()
+        .filter_map(|s| serde_json::from_str(s).ok())
+        .collect();
+
+    let mut target = read_sidecar(&target_pdf)?;
+    let mut result = AnnotationTransfer {
+        transferred: 0,
+        skipped: 0,
+    };
+    for mut annotation in annotations {
+        let page = i64::from(annotation.page_number) + i64::from(page_offset.unwrap_or(0));
+        let Some((page, to)) = u32::try_from(page)
+            .ok()
+            .and_then(|page| Some((page, *target_sizes.get(&page)?)))
+        else {
+            result.skipped += 1;
+            continue;
+        };
+        if let Some(from) = source_sizes.get(&annotation.page_number) {
+            remap(&mut annotation, *from, to);
+        }
+        if let Some(name) = annotation.image.as_deref() {
+            if let Err(e) = copy_stamp(&source_pdf, &target_pdf, name) {
+                warn!(name, error = %e, "Failed to copy stamp");
+                result.skipped += 1;
+                continue;
+            }
+        }
+        if let Some(layer) = annotation.layer.as_deref() {
+            if !target.layers.iter().any(|l| l.id == layer) {
+                if let Some(found) = source.layers.iter().find(|l| l.id == layer) {
+                    target.layers.push(found.clone());
+                }
+            }
+        }
+
+        // An ID moved to another page is dropped from its old one
+        for list in target.annotations.values_mut() {
+            list.retain(|a| a.id != annotation.id);
+        }
+        annotation.page_number = page;
+        target.annotations.entry(page).or_default().push(annotation);
+        result.transferred += 1;
+    }
+    target.annotations.retain(|_, list| !list.is_empty());
+    write_sidecar(&mut target)?;
+
+    info!(
+        target = %target_pdf,
+        transferred = result.transferred,
+        skipped = result.skipped,
+        "Transferred annotations"
+    );
+    Ok(result)
+}
+
+/// Size of `page`, or `None` if sizes aren't known
+fn page_size(sizes: &HashMap<u32, (f64, f64)>, page: u32) -> Result<Option<(f64, f64)>> {
+    if sizes.is_empty() {
+        return Ok(None);
+    }
+    match sizes.get(&page) {
+        Some(size) => Ok(Some(*size)),
+        None => Err(StreamSlateError::Other(format!(
+            "Page {page} is not in the document"
+        ))),
+    }
+}
+
+/// Scale an annotation drawn on a page of size `from` onto one of size `to`
+///
+/// Stroke widths and font sizes follow the smaller of the two scales, so
+/// text still fits its box.
+pub fn remap(annotation: &mut Annotation, from: (f64, f64), to: (f64, f64)) {
+    if from == to || from.0 <= 0.0 || from.1 <= 0.0 {
+        return;
+    }
+    let (sx, sy) = (to.0 / from.0, to.1 / from.1);
+    annotation.x *= sx;
+    annotation.y *= sy;
+    annotation.width *= sx;
+    annotation.height *= sy;
+    let scale = sx.min(sy);
+    if let Some(width) = annotation.stroke_width.as_mut() {
+        *width *= scale;
+    }
+    if let Some(size) = annotation.font_size.as_mut() {
+        *size *= scale;
+    }
+    if let Some(points) = annotation.points.take() {
+        let points = points
+            .into_iter()
+            .map(|mut p| {
+                p.x *= sx;
+                p.y *= sy;
+                p
+            })
+            .collect();
+        set_points(annotation, points);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_remap() {
+        let mut stroke: Annotation = serde_json::from_value(serde_json::json!({
+            "id": "s1", "type": "free_draw", "pageNumber": 1, "x": 10, "y": 20,
+            "width": 10, "height": 20, "content": "", "color": "#000000",
+            "opacity": 1, "strokeWidth": 4, "created": "", "modified": "",
+            "visible": true,
+            "points": [{ "x": 10, "y": 20, "pressure": 0.5 }, { "x": 20, "y": 40 }],
+        }))
+        .unwrap();
+
+        // Letter portrait onto a page twice as wide and the same height
+        remap(&mut stroke, (612.0, 792.0), (1224.0, 792.0));
+        assert_eq!(
+            (stroke.x, stroke.y, stroke.width, stroke.height),
+            (20.0, 20.0, 20.0, 20.0)
+        );
+        assert_eq!(stroke.stroke_width, Some(4.0));
+        let points = stroke.points.as_deref().unwrap();
+        assert_eq!((points[1].x, points[1].y), (40.0, 40.0));
+        assert_eq!(points[0].pressure, Some(0.5));
+
+        // Halving both sides halves the stroke too
+        remap(&mut stroke, (1224.0, 792.0), (612.0, 396.0));
+        assert_eq!(stroke.stroke_width, Some(2.0));
+        assert_eq!((stroke.x, stroke.y), (10.0, 10.0));
+    }
+}
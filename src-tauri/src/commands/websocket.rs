@@ -0,0 +1,265 @@
+/*
+ * This file is part of StreamSlate.
+ * Copyright (C) 2025 StreamSlate Contributors
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+
+//! Control server commands
+
+use crate::error::{Result, StreamSlateError};
+use crate::settings::{load_settings, save_settings};
+use crate::state::{AppState, WebSocketState};
+use crate::websocket::access::{self, AccessConfig, Role};
+use crate::websocket::audit::{self, AuditEntry};
+use crate::websocket::clients::ConnectedClient;
+use crate::websocket::page_images::{ImageSize, PageImage};
+use crate::websocket::{self, config, WebSocketConfig};
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use std::sync::Arc;
+use tauri::{AppHandle, State};
+use tracing::{info, instrument, warn};
+
+/// The stored address and port of the control server
+#[tauri::command]
+#[instrument(skip(app))]
+pub async fn get_websocket_config(app: AppHandle) -> Result<WebSocketConfig> {
+    Ok(stored_config(&app))
+}
+
+/// The port the control server listens on, which is a fallback port when
+/// the configured one was taken
+#[tauri::command]
+#[instrument(skip(state))]
+pub async fn get_websocket_status(state: State<'_, AppState>) -> Result<WebSocketState> {
+    state.get_websocket_state()
+}
+
+/// Move the control server to another port or address and persist the choice
+///
+/// The listener restarts right away; connected clients stay connected. If
+/// none of the new ports can be bound, the server goes back to the previous
+/// settings and the new ones aren't saved.
+#[tauri::command]
+#[instrument(skip(app, state))]
+pub async fn set_websocket_config(
+    config: WebSocketConfig,
+    app: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<()> {
+    config.validate()?;
+    let previous = stored_config(&app);
+
+    if !crate::is_remote_client_mode() {
+        let state = Arc::new(state.inner().clone());
+        if let Err(e) = websocket::start_server(&config, state.clone(), app.clone()).await {
+            if let Err(e) = websocket::start_server(&previous, state, app.clone()).await {
+                warn!(error = %e, "Failed to restart WebSocket server");
+            }
+            return Err(StreamSlateError::WebSocket(format!(
+                "Can't listen on {}:{}: {e}",
+                config.bind_addr, config.port
+            )));
+        }
+    }
+
+    save_settings(&app, config::CONFIG_FILE, &config)?;
+    info!(
+        port = config.port,
+        bind_addr = %config.bind_addr,
+        "WebSocket settings updated"
+    );
+    Ok(())
+}
+
+/// Start the control server with the stored settings, returning the port it
+/// listens on
+///
+/// Restarts the listener if it's already running; connected clients stay
+/// connected.
+#[tauri::command]
+#[instrument(skip(app, state))]
+pub async fn start_websocket_server(app: AppHandle, state: State<'_, AppState>) -> Result<u16> {
+    ensure_server_mode()?;
+    let config = stored_config(&app);
+    let state = Arc::new(state.inner().clone());
+    let addr = websocket::start_server(&config, state, app)
+        .await
+        .map_err(|e| StreamSlateError::WebSocket(format!("Can't start the server: {e}")))?;
+    Ok(addr.port())
+}
+
+/// Stop the control server, closing every client's connection
+#[tauri::command]
+#[instrument(skip(app, state))]
+pub async fn stop_websocket_server(app: AppHandle, state: State<'_, AppState>) -> Result<()> {
+    ensure_server_mode()?;
+    let state = Arc::new(state.inner().clone());
+    if !websocket::stop_server(&state, &app).await {
+        return Err(StreamSlateError::WebSocket(
+            "The server isn't running".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+/// Stop the control server and start it again with the stored settings,
+/// returning the port it listens on
+///
+/// Unlike `set_websocket_config`, clients are disconnected and have to
+/// reconnect.
+#[tauri::command]
+#[instrument(skip(app, state))]
+pub async fn restart_websocket_server(app: AppHandle, state: State<'_, AppState>) -> Result<u16> {
+    ensure_server_mode()?;
+    let config = stored_config(&app);
+    let state = Arc::new(state.inner().clone());
+    websocket::stop_server(&state, &app).await;
+    let addr = websocket::start_server(&config, state, app)
+        .await
+        .map_err(|e| StreamSlateError::WebSocket(format!("Can't start the server: {e}")))?;
+    Ok(addr.port())
+}
+
+/// The clients connected to the control server, oldest first
+#[tauri::command]
+#[instrument(skip(state))]
+pub async fn list_ws_clients(state: State<'_, AppState>) -> Result<Vec<ConnectedClient>> {
+    Ok(state
+        .ws_clients
+        .lock()
+        .map_err(|e| StreamSlateError::StateLock(format!("WebSocket clients: {e}")))?
+        .list())
+}
+
+/// Most entries returned by `get_command_history` unless asked for more
+const DEFAULT_HISTORY_LIMIT: usize = 500;
+
+/// Control commands clients sent, with who sent them and whether they were
+/// applied; the last `limit` (500 by default), oldest first
+#[tauri::command]
+#[instrument]
+pub async fn get_command_history(limit: Option<usize>) -> Result<Vec<AuditEntry>> {
+    Ok(audit::history(limit.unwrap_or(DEFAULT_HISTORY_LIMIT)))
+}
+
+/// Close a client's connection, e.g. a stale or unknown controller
+///
+/// The client can connect again; this doesn't block it.
+#[tauri::command]
+#[instrument(skip(state))]
+pub async fn disconnect_ws_client(id: u64, state: State<'_, AppState>) -> Result<()> {
+    let open = state
+        .ws_clients
+        .lock()
+        .map_err(|e| StreamSlateError::StateLock(format!("WebSocket clients: {e}")))?
+        .disconnect(id);
+    if !open {
+        return Err(StreamSlateError::WebSocket(format!(
+            "No client is connected with ID {id}"
+        )));
+    }
+    info!(id, "Disconnected WebSocket client");
+    Ok(())
+}
+
+/// Change the role of a connected client, e.g. to let a phone remote turn
+/// pages without giving it a token
+#[tauri::command]
+#[instrument(skip(state))]
+pub async fn set_ws_client_role(id: u64, role: Role, state: State<'_, AppState>) -> Result<()> {
+    let open = state
+        .ws_clients
+        .lock()
+        .map_err(|e| StreamSlateError::StateLock(format!("WebSocket clients: {e}")))?
+        .set_role(id, role);
+    if !open {
+        return Err(StreamSlateError::WebSocket(format!(
+            "No client is connected with ID {id}"
+        )));
+    }
+    info!(id, role = role.as_str(), "Changed WebSocket client role");
+    Ok(())
+}
+
+/// The default role of new clients and the access tokens
+#[tauri::command]
+#[instrument]
+pub async fn get_ws_access_config() -> Result<AccessConfig> {
+    Ok(access::access()
+        .read()
+        .map_err(|e| StreamSlateError::StateLock(format!("WebSocket access: {e}")))?
+        .clone())
+}
+
+/// Change the default role and access tokens and persist them
+///
+/// Applies to clients connecting or sending `HELLO` from now on; connected
+/// clients keep their role.
+#[tauri::command]
+#[instrument(skip(config, app))]
+pub async fn set_ws_access_config(config: AccessConfig, app: AppHandle) -> Result<()> {
+    config.validate()?;
+    save_settings(&app, access::CONFIG_FILE, &config)?;
+    info!(
+        default_role = config.default_role.as_str(),
+        tokens = config.tokens.len(),
+        "WebSocket access settings updated"
+    );
+    *access::access()
+        .write()
+        .map_err(|e| StreamSlateError::StateLock(format!("WebSocket access: {e}")))? = config;
+    Ok(())
+}
+
+/// Hand a page image rendered by the webview to the control server, in
+/// answer to `page-image-requested`
+///
+/// `data` is a base64 JPEG or PNG file, optionally as a data URL. Clients
+/// waiting for the page get it right away.
+#[tauri::command]
+#[instrument(skip(data, state))]
+pub async fn publish_page_image(
+    page: u32,
+    size: ImageSize,
+    width: u32,
+    height: u32,
+    data: String,
+    state: State<'_, AppState>,
+) -> Result<()> {
+    let encoded = data
+        .split_once(";base64,")
+        .map_or(data.as_str(), |(_, d)| d);
+    let bytes = BASE64
+        .decode(encoded.trim())
+        .map_err(|e| StreamSlateError::Other(format!("Page image is not base64: {e}")))?;
+    let image = PageImage::new(page, size, width, height, bytes)?;
+    let document = state.get_pdf_state()?.current_file;
+    state.page_images.publish(document.as_deref(), image)
+}
+
+/// Fail in remote client mode, where this instance runs no server
+fn ensure_server_mode() -> Result<()> {
+    if crate::is_remote_client_mode() {
+        return Err(StreamSlateError::WebSocket(
+            "Running as a remote client; the WebSocket server is disabled".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+fn stored_config(app: &AppHandle) -> WebSocketConfig {
+    load_settings(app, config::CONFIG_FILE)
+}
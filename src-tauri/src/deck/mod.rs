@@ -0,0 +1,291 @@
+/*
+ * This file is part of StreamSlate.
+ * Copyright (C) 2025 StreamSlate Contributors
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+
+//! Image folder decks
+//!
+//! A directory of PNG/JPEG files presented as a paged document. Pages are
+//! ordered by natural file name order (`slide2.png` before `slide10.png`),
+//! and only image headers are read up front to get dimensions.
+
+use crate::error::{Result, StreamSlateError};
+use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use tracing::{debug, warn};
+
+/// Upper bound on header bytes read when probing image dimensions
+const MAX_HEADER_PROBE: u64 = 256 * 1024;
+
+/// A single page of an image deck
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DeckPage {
+    pub page_number: u32,
+    pub path: String,
+    pub width: u32,
+    pub height: u32,
+}
+
+/// A folder of images treated as a paged document
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ImageDeck {
+    pub folder: String,
+    pub pages: Vec<DeckPage>,
+    /// Combined size of all page images in bytes
+    pub total_size: u64,
+}
+
+impl ImageDeck {
+    /// Scan `folder` for PNG/JPEG files and build a deck from them
+    pub fn load(folder: &Path) -> Result<Self> {
+        if !folder.is_dir() {
+            return Err(StreamSlateError::FileNotFound(format!(
+                "Not a directory: {}",
+                folder.display()
+            )));
+        }
+
+        let mut files: Vec<PathBuf> = std::fs::read_dir(folder)?
+            .filter_map(|entry| entry.ok().map(|e| e.path()))
+            .filter(|path| path.is_file() && is_supported_image(path))
+            .collect();
+
+        files.sort_by(|a, b| {
+            natural_cmp(
+                &a.file_name().unwrap_or_default().to_string_lossy(),
+                &b.file_name().unwrap_or_default().to_string_lossy(),
+            )
+        });
+
+        let mut pages = Vec::with_capacity(files.len());
+        let mut total_size = 0;
+
+        for path in files {
+            match probe_dimensions(&path) {
+                Ok((width, height)) => {
+                    total_size += std::fs::metadata(&path).map(|m| m.len()).unwrap_or(0);
+                    pages.push(DeckPage {
+                        page_number: pages.len() as u32 + 1,
+                        path: path.to_string_lossy().into_owned(),
+                        width,
+                        height,
+                    });
+                }
+                Err(e) => warn!(path = %path.display(), error = %e, "Skipping unreadable image"),
+            }
+        }
+
+        if pages.is_empty() {
+            return Err(StreamSlateError::Other(format!(
+                "No PNG or JPEG images found in {}",
+                folder.display()
+            )));
+        }
+
+        debug!(folder = %folder.display(), pages = pages.len(), "Image deck loaded");
+
+        Ok(Self {
+            folder: folder.to_string_lossy().into_owned(),
+            pages,
+            total_size,
+        })
+    }
+
+    /// Get a page by 1-indexed page number
+    pub fn page(&self, page_number: u32) -> Option<&DeckPage> {
+        page_number
+            .checked_sub(1)
+            .and_then(|index| self.pages.get(index as usize))
+    }
+}
+
+fn is_supported_image(path: &Path) -> bool {
+    path.extension()
+        .and_then(|ext| ext.to_str())
+        .is_some_and(|ext| {
+            ["png", "jpg", "jpeg"]
+                .iter()
+                .any(|supported| ext.eq_ignore_ascii_case(supported))
+        })
+}
+
+/// Compare file names so that embedded numbers sort numerically
+pub fn natural_cmp(a: &str, b: &str) -> Ordering {
+    let mut a_chars = a.chars().peekable();
+    let mut b_chars = b.chars().peekable();
+
+    loop {
+        match (a_chars.peek().copied(), b_chars.peek().copied()) {
+            (None, None) => return Ordering::Equal,
+            (None, Some(_)) => return Ordering::Less,
+            (Some(_), None) => return Ordering::Greater,
+            (Some(x), Some(y)) if x.is_ascii_digit() && y.is_ascii_digit() => {
+                let a_num = take_number(&mut a_chars);
+                let b_num = take_number(&mut b_chars);
+                // Compare by magnitude first, then by length to keep "01" != "1" stable
+                let ordering = a_num
+                    .trim_start_matches('0')
+                    .len()
+                    .cmp(&b_num.trim_start_matches('0').len())
+                    .then_with(|| {
+                        a_num
+                            .trim_start_matches('0')
+                            .cmp(b_num.trim_start_matches('0'))
+                    })
+                    .then_with(|| a_num.len().cmp(&b_num.len()));
+                if ordering != Ordering::Equal {
+                    return ordering;
+                }
+            }
+            (Some(x), Some(y)) => {
+                let ordering = x.to_ascii_lowercase().cmp(&y.to_ascii_lowercase());
+                if ordering != Ordering::Equal {
+                    return ordering;
+                }
+                a_chars.next();
+                b_chars.next();
+            }
+        }
+    }
+}
+
+fn take_number(chars: &mut std::iter::Peekable<std::str::Chars<'_>>) -> String {
+    let mut digits = String::new();
+    while let Some(c) = chars.peek().copied().filter(char::is_ascii_digit) {
+        digits.push(c);
+        chars.next();
+    }
+    digits
+}
+
+/// Read image dimensions from the file header without decoding pixels
+pub fn probe_dimensions(path: &Path) -> Result<(u32, u32)> {
+    let mut header = Vec::new();
+    std::fs::File::open(path)?
+        .take(MAX_HEADER_PROBE)
+        .read_to_end(&mut header)?;
+
+    png_dimensions(&header)
+        .or_else(|| jpeg_dimensions(&header))
+        .ok_or_else(|| StreamSlateError::Other(format!("Unrecognized image: {}", path.display())))
+}
+
+/// Parse width/height from a PNG IHDR chunk
+fn png_dimensions(data: &[u8]) -> Option<(u32, u32)> {
+    const SIGNATURE: &[u8] = b"\x89PNG\r\n\x1a\n";
+    if data.len() < 24 || !data.starts_with(SIGNATURE) || &data[12..16] != b"IHDR" {
+        return None;
+    }
+    let width = u32::from_be_bytes(data[16..20].try_into().ok()?);
+    let height = u32::from_be_bytes(data[20..24].try_into().ok()?);
+    Some((width, height))
+}
+
+/// Parse width/height from the first JPEG start-of-frame marker
+fn jpeg_dimensions(data: &[u8]) -> Option<(u32, u32)> {
+    if data.len() < 4 || data[0] != 0xFF || data[1] != 0xD8 {
+        return None;
+    }
+
+    let mut pos = 2;
+    while pos + 4 <= data.len() {
+        if data[pos] != 0xFF {
+            return None;
+        }
+        let marker = data[pos + 1];
+        // Fill bytes and standalone markers carry no length
+        if marker == 0xFF {
+            pos += 1;
+            continue;
+        }
+        if marker == 0x01 || (0xD0..=0xD7).contains(&marker) {
+            pos += 2;
+            continue;
+        }
+
+        let length = u16::from_be_bytes([data[pos + 2], data[pos + 3]]) as usize;
+        let is_sof = matches!(marker, 0xC0..=0xCF) && !matches!(marker, 0xC4 | 0xC8 | 0xCC);
+        if is_sof && pos + 9 <= data.len() {
+            let height = u16::from_be_bytes([data[pos + 5], data[pos + 6]]) as u32;
+            let width = u16::from_be_bytes([data[pos + 7], data[pos + 8]]) as u32;
+            return Some((width, height));
+        }
+        pos += 2 + length;
+    }
+
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn png_header(width: u32, height: u32) -> Vec<u8> {
+        let mut data = b"\x89PNG\r\n\x1a\n\x00\x00\x00\x0dIHDR".to_vec();
+        data.extend_from_slice(&width.to_be_bytes());
+        data.extend_from_slice(&height.to_be_bytes());
+        data.extend_from_slice(&[8, 6, 0, 0, 0]);
+        data
+    }
+
+    fn jpeg_header(width: u16, height: u16) -> Vec<u8> {
+        let mut data = vec![0xFF, 0xD8];
+        // APP0 segment to skip over
+        data.extend_from_slice(&[0xFF, 0xE0, 0x00, 0x04, 0x00, 0x00]);
+        data.extend_from_slice(&[0xFF, 0xC0, 0x00, 0x11, 0x08]);
+        data.extend_from_slice(&height.to_be_bytes());
+        data.extend_from_slice(&width.to_be_bytes());
+        data.extend_from_slice(&[0x03; 12]);
+        data
+    }
+
+    #[test]
+    fn test_natural_ordering() {
+        let mut names = vec!["slide10.png", "Slide2.png", "slide1.png", "slide02.png"];
+        names.sort_by(|a, b| natural_cmp(a, b));
+        assert_eq!(
+            names,
+            vec!["slide1.png", "Slide2.png", "slide02.png", "slide10.png"]
+        );
+    }
+
+    #[test]
+    fn test_image_dimensions() {
+        assert_eq!(png_dimensions(&png_header(1920, 1080)), Some((1920, 1080)));
+        assert_eq!(jpeg_dimensions(&jpeg_header(1280, 720)), Some((1280, 720)));
+        assert_eq!(png_dimensions(b"not an image"), None);
+        assert_eq!(jpeg_dimensions(b"not an image"), None);
+    }
+
+    #[test]
+    fn test_load_deck() {
+        let dir = std::env::temp_dir().join(format!("streamslate-deck-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("slide10.png"), png_header(800, 600)).unwrap();
+        std::fs::write(dir.join("slide2.jpg"), jpeg_header(1280, 720)).unwrap();
+        std::fs::write(dir.join("notes.txt"), b"ignored").unwrap();
+
+        let deck = ImageDeck::load(&dir).unwrap();
+        assert_eq!(deck.pages.len(), 2);
+        assert!(deck.page(1).unwrap().path.ends_with("slide2.jpg"));
+        assert_eq!(deck.page(2).unwrap().width, 800);
+        assert!(deck.page(3).is_none());
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -21,6 +21,8 @@
 //! This module provides a unified error type that can be returned from
 //! Tauri commands and serialized to the frontend.
 
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
 use thiserror::Error;
 
 /// Main error type for StreamSlate operations
@@ -38,6 +40,10 @@ pub enum StreamSlateError {
     #[error("Invalid PDF: {0}")]
     InvalidPdf(String),
 
+    /// File is not structurally a PDF (wrong magic bytes, truncated, ...)
+    #[error("Invalid PDF format: {0}")]
+    PdfInvalidFormat(String),
+
     /// Failed to acquire state lock
     #[error("State lock error: {0}")]
     StateLock(String),
@@ -58,6 +64,14 @@ pub enum StreamSlateError {
     #[error("Window error: {0}")]
     Window(String),
 
+    /// The operation needs an open document and none is open
+    #[error("No PDF is currently open")]
+    NoDocument,
+
+    /// Page number outside the open document
+    #[error("Page {page} is out of range (1-{total_pages})")]
+    PageOutOfRange { page: u32, total_pages: u32 },
+
     /// Generic error for other cases
     #[error("{0}")]
     Other(String),
@@ -76,6 +90,58 @@ impl serde::Serialize for StreamSlateError {
     }
 }
 
+impl StreamSlateError {
+    /// The code clients see for this error
+    pub fn code(&self) -> ErrorCode {
+        match self {
+            Self::Pdf(_) | Self::InvalidPdf(_) | Self::PdfInvalidFormat(_) => ErrorCode::InvalidPdf,
+            Self::FileNotFound(_) => ErrorCode::NotFound,
+            Self::Io(_) => ErrorCode::Io,
+            Self::StateLock(_) | Self::WebSocket(_) | Self::Json(_) | Self::Window(_) => {
+                ErrorCode::Internal
+            }
+            Self::NoDocument => ErrorCode::NoDocument,
+            Self::PageOutOfRange { .. } => ErrorCode::PageOutOfRange,
+            Self::Other(_) => ErrorCode::Other,
+        }
+    }
+}
+
+/// Kind of failure, for clients that react to errors or show their own
+/// (e.g. translated) messages instead of the English `message`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum ErrorCode {
+    /// No document is open
+    NoDocument,
+    /// The page is outside the open document
+    PageOutOfRange,
+    /// Already on the first or last page or section
+    EndOfDocument,
+    /// The annotation, clip, layer or file doesn't exist
+    NotFound,
+    /// The annotation is locked
+    Locked,
+    /// The command is malformed or not allowed in this context
+    InvalidCommand,
+    /// A value in the command is not accepted
+    InvalidArgument,
+    /// The connection's role doesn't allow the command, or the access token
+    /// is unknown
+    Forbidden,
+    /// Sent too often
+    RateLimited,
+    /// The document can't be read as a PDF
+    InvalidPdf,
+    /// Reading or writing a file failed
+    Io,
+    /// Something went wrong inside StreamSlate
+    Internal,
+    /// Any other failure
+    #[default]
+    Other,
+}
+
 /// Result type alias for StreamSlate operations
 pub type Result<T> = std::result::Result<T, StreamSlateError>;
 
@@ -96,6 +162,21 @@ mod tests {
         assert_eq!(json, "\"Invalid PDF: Corrupted header\"");
     }
 
+    #[test]
+    fn test_error_code() {
+        let err = StreamSlateError::PageOutOfRange {
+            page: 12,
+            total_pages: 10,
+        };
+        assert_eq!(err.to_string(), "Page 12 is out of range (1-10)");
+        assert_eq!(err.code(), ErrorCode::PageOutOfRange);
+        assert_eq!(StreamSlateError::NoDocument.code(), ErrorCode::NoDocument);
+        assert_eq!(
+            serde_json::to_string(&ErrorCode::NoDocument).unwrap(),
+            "\"NO_DOCUMENT\""
+        );
+    }
+
     #[test]
     fn test_io_error_conversion() {
         let io_err = std::io::Error::new(std::io::ErrorKind::NotFound, "file missing");
@@ -0,0 +1,227 @@
+/*
+ * This file is part of StreamSlate.
+ * Copyright (C) 2025 StreamSlate Contributors
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+
+//! Controller input
+//!
+//! Reads game controllers through gilrs, which maps each controller's
+//! buttons and axes to a standard gamepad layout.
+
+use super::{GamepadAxis, GamepadButton, GamepadInput};
+use crate::error::{Result, StreamSlateError};
+use gilrs::{Axis, Button, EventType, GamepadId, Gilrs};
+use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{mpsc, Arc};
+use std::time::Duration;
+use tracing::debug;
+
+/// How often the tap thread checks whether it should stop
+const POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// A connected game controller
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GamepadDevice {
+    /// Index of the controller among those gilrs has seen
+    pub id: String,
+    pub name: String,
+}
+
+fn open() -> Result<Gilrs> {
+    Gilrs::new().map_err(|e| StreamSlateError::Other(format!("Cannot read game controllers: {e}")))
+}
+
+fn connected(gilrs: &Gilrs) -> Vec<(GamepadId, GamepadDevice)> {
+    gilrs
+        .gamepads()
+        .map(|(id, gamepad)| {
+            let device = GamepadDevice {
+                id: usize::from(id).to_string(),
+                name: gamepad.name().to_string(),
+            };
+            (id, device)
+        })
+        .collect()
+}
+
+/// Game controllers connected to this machine
+pub fn list_devices() -> Result<Vec<GamepadDevice>> {
+    Ok(connected(&open()?)
+        .into_iter()
+        .map(|(_, device)| device)
+        .collect())
+}
+
+/// An open controller delivering its input until dropped
+pub struct GamepadTap {
+    device: GamepadDevice,
+    stop: Arc<AtomicBool>,
+}
+
+impl GamepadTap {
+    /// Open the controller with `id` and call `on_input` for each of its inputs
+    pub fn start(
+        id: &str,
+        mut on_input: impl FnMut(GamepadInput) + Send + 'static,
+    ) -> Result<Self> {
+        let stop = Arc::new(AtomicBool::new(false));
+        let stopped = stop.clone();
+        let wanted = id.to_string();
+        let (tx, rx) = mpsc::channel();
+        // Gilrs isn't `Send` on every platform, so it lives on the tap thread
+        std::thread::Builder::new()
+            .name("gamepad-tap".to_string())
+            .spawn(move || {
+                let opened = open().and_then(|gilrs| {
+                    connected(&gilrs)
+                        .into_iter()
+                        .find(|(_, device)| device.id == wanted)
+                        .map(|(gamepad, device)| (gilrs, gamepad, device))
+                        .ok_or_else(|| {
+                            StreamSlateError::Other(format!("No game controller {wanted}"))
+                        })
+                });
+                let (mut gilrs, gamepad, device) = match opened {
+                    Ok(opened) => opened,
+                    Err(e) => {
+                        let _ = tx.send(Err(e));
+                        return;
+                    }
+                };
+                let _ = tx.send(Ok(device.clone()));
+
+                while !stopped.load(Ordering::Relaxed) {
+                    let Some(event) = gilrs.next_event_blocking(Some(POLL_INTERVAL)) else {
+                        continue;
+                    };
+                    if event.id != gamepad {
+                        continue;
+                    }
+                    if let EventType::Disconnected = event.event {
+                        debug!(device = %device.name, "Game controller disconnected");
+                        break;
+                    }
+                    if let Some(input) = decode(event.event) {
+                        on_input(input);
+                    }
+                }
+                debug!(device = %device.name, "Gamepad tap stopped");
+            })?;
+
+        let device = rx
+            .recv()
+            .map_err(|_| StreamSlateError::Other("Game controller thread exited".to_string()))??;
+        Ok(Self { device, stop })
+    }
+
+    pub fn device(&self) -> &GamepadDevice {
+        &self.device
+    }
+}
+
+impl Drop for GamepadTap {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::Relaxed);
+    }
+}
+
+/// The input a gilrs event reports, if any
+///
+/// gilrs reports analogue triggers as presses and releases past its
+/// threshold, and hats as D-pad buttons, so neither needs handling here.
+fn decode(event: EventType) -> Option<GamepadInput> {
+    match event {
+        EventType::ButtonPressed(button, _) => Some(GamepadInput::Button {
+            button: map_button(button)?,
+            pressed: true,
+        }),
+        EventType::ButtonReleased(button, _) => Some(GamepadInput::Button {
+            button: map_button(button)?,
+            pressed: false,
+        }),
+        EventType::AxisChanged(axis, value, _) => {
+            let (axis, value) = map_axis(axis, value)?;
+            Some(GamepadInput::Axis { axis, value })
+        }
+        _ => None,
+    }
+}
+
+fn map_button(button: Button) -> Option<GamepadButton> {
+    use GamepadButton::*;
+    Some(match button {
+        Button::South => South,
+        Button::East => East,
+        Button::North => North,
+        Button::West => West,
+        // gilrs calls the bumpers triggers and the triggers second triggers
+        Button::LeftTrigger => LeftBumper,
+        Button::RightTrigger => RightBumper,
+        Button::LeftTrigger2 => LeftTrigger,
+        Button::RightTrigger2 => RightTrigger,
+        Button::Select => Select,
+        Button::Start => Start,
+        Button::LeftThumb => LeftStick,
+        Button::RightThumb => RightStick,
+        Button::DPadUp => DpadUp,
+        Button::DPadDown => DpadDown,
+        Button::DPadLeft => DpadLeft,
+        Button::DPadRight => DpadRight,
+        _ => return None,
+    })
+}
+
+/// Map a gilrs axis and value; gilrs reports up as positive, we report down
+fn map_axis(axis: Axis, value: f32) -> Option<(GamepadAxis, f64)> {
+    let value = f64::from(value);
+    Some(match axis {
+        Axis::LeftStickX => (GamepadAxis::LeftX, value),
+        Axis::LeftStickY => (GamepadAxis::LeftY, -value),
+        Axis::RightStickX => (GamepadAxis::RightX, value),
+        Axis::RightStickY => (GamepadAxis::RightY, -value),
+        _ => return None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_mapping() {
+        assert_eq!(
+            map_button(Button::LeftTrigger2),
+            Some(GamepadButton::LeftTrigger)
+        );
+        assert_eq!(
+            map_button(Button::RightTrigger),
+            Some(GamepadButton::RightBumper)
+        );
+        assert_eq!(map_button(Button::DPadUp), Some(GamepadButton::DpadUp));
+        assert_eq!(map_button(Button::Mode), None);
+
+        assert_eq!(
+            map_axis(Axis::LeftStickY, 0.5),
+            Some((GamepadAxis::LeftY, -0.5))
+        );
+        assert_eq!(
+            map_axis(Axis::RightStickX, -1.0),
+            Some((GamepadAxis::RightX, -1.0))
+        );
+        assert_eq!(map_axis(Axis::LeftZ, 1.0), None);
+    }
+}
@@ -0,0 +1,411 @@
+/*
+ * This file is part of StreamSlate.
+ * Copyright (C) 2025 StreamSlate Contributors
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+
+//! Gamepad navigation
+//!
+//! Game controllers make cheap wireless clickers. Buttons are mapped to
+//! navigation actions, and a stick can steer the output laser pointer.
+
+pub mod device;
+
+use crate::capture::laser::COUNTS_PER_WIDTH;
+use crate::capture::pointer::PointerEvent;
+use crate::error::{Result, StreamSlateError};
+use crate::settings::Settings;
+use device::{GamepadDevice, GamepadTap};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// Mapping file in the app data directory
+pub const MAPPING_FILE: &str = "gamepad.json";
+
+/// How often stick deflection is turned into laser pointer motion
+const POINTER_TICK: Duration = Duration::from_millis(16);
+
+/// A controller button, named by position (Xbox A / PlayStation ✕ is `South`)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum GamepadButton {
+    South,
+    East,
+    North,
+    West,
+    LeftBumper,
+    RightBumper,
+    LeftTrigger,
+    RightTrigger,
+    Select,
+    Start,
+    LeftStick,
+    RightStick,
+    DpadUp,
+    DpadDown,
+    DpadLeft,
+    DpadRight,
+}
+
+/// A stick axis; positive values point right and down
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GamepadAxis {
+    LeftX,
+    LeftY,
+    RightX,
+    RightY,
+}
+
+/// Input reported by a controller
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum GamepadInput {
+    Button {
+        button: GamepadButton,
+        pressed: bool,
+    },
+    /// Stick position from -1.0 to 1.0
+    Axis { axis: GamepadAxis, value: f64 },
+}
+
+/// What a button does
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum GamepadAction {
+    NextPage,
+    PreviousPage,
+    NextSection,
+    PreviousSection,
+    ZoomIn,
+    ZoomOut,
+    ResetZoom,
+    TogglePresenter,
+    /// Enlarge the laser dot while held
+    Laser,
+}
+
+/// A thumbstick
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Stick {
+    Left,
+    Right,
+}
+
+/// Button assignments and stick behaviour
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct GamepadMapping {
+    pub buttons: BTreeMap<GamepadButton, GamepadAction>,
+    /// Stick that moves the laser pointer, if any
+    pub pointer_stick: Option<Stick>,
+    /// Stick deflection ignored as drift, from 0 to 0.9
+    pub deadzone: f64,
+    /// Laser pointer speed at full deflection, in frame widths per second
+    pub pointer_speed: f64,
+}
+
+impl Default for GamepadMapping {
+    fn default() -> Self {
+        use GamepadAction::*;
+        use GamepadButton::*;
+        Self {
+            buttons: BTreeMap::from([
+                (DpadRight, NextPage),
+                (RightBumper, NextPage),
+                (South, NextPage),
+                (DpadLeft, PreviousPage),
+                (LeftBumper, PreviousPage),
+                (East, PreviousPage),
+                (West, NextSection),
+                (Select, PreviousSection),
+                (DpadUp, ZoomIn),
+                (DpadDown, ZoomOut),
+                (North, ResetZoom),
+                (Start, TogglePresenter),
+                (RightTrigger, Laser),
+            ]),
+            pointer_stick: Some(Stick::Left),
+            deadzone: 0.15,
+            pointer_speed: 0.8,
+        }
+    }
+}
+
+impl GamepadMapping {
+    pub fn validate(&self) -> Result<()> {
+        if !(0.0..=0.9).contains(&self.deadzone) {
+            return Err(StreamSlateError::Other(format!(
+                "Deadzone must be between 0 and 0.9, got {}",
+                self.deadzone
+            )));
+        }
+        if !self.pointer_speed.is_finite() || self.pointer_speed <= 0.0 || self.pointer_speed > 10.0
+        {
+            return Err(StreamSlateError::Other(format!(
+                "Pointer speed must be between 0 and 10, got {}",
+                self.pointer_speed
+            )));
+        }
+        Ok(())
+    }
+}
+
+impl Settings for GamepadMapping {
+    fn check(&self) -> Result<()> {
+        self.validate()
+    }
+}
+
+/// Output of the controller state machine
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ControllerEvent {
+    Action(GamepadAction),
+    Pointer(PointerEvent),
+}
+
+/// Applies the mapping to controller input
+#[derive(Debug)]
+pub struct GamepadController {
+    mapping: GamepadMapping,
+    /// Latest left X, left Y, right X, right Y
+    sticks: [f64; 4],
+    /// Sub-count pointer motion carried to the next tick
+    remainder: (f64, f64),
+}
+
+impl GamepadController {
+    pub fn new(mapping: GamepadMapping) -> Self {
+        Self {
+            mapping,
+            sticks: [0.0; 4],
+            remainder: (0.0, 0.0),
+        }
+    }
+
+    pub fn set_mapping(&mut self, mapping: GamepadMapping) {
+        self.mapping = mapping;
+        self.remainder = (0.0, 0.0);
+    }
+
+    /// Map one input; actions fire on press, the laser button on both edges
+    pub fn handle(&mut self, input: GamepadInput) -> Option<ControllerEvent> {
+        match input {
+            GamepadInput::Button { button, pressed } => {
+                match *self.mapping.buttons.get(&button)? {
+                    GamepadAction::Laser => {
+                        Some(ControllerEvent::Pointer(PointerEvent::Button { pressed }))
+                    }
+                    action if pressed => Some(ControllerEvent::Action(action)),
+                    _ => None,
+                }
+            }
+            GamepadInput::Axis { axis, value } => {
+                self.sticks[axis as usize] = value.clamp(-1.0, 1.0);
+                None
+            }
+        }
+    }
+
+    /// Laser pointer motion for `elapsed` at the current stick deflection
+    pub fn pointer_motion(&mut self, elapsed: Duration) -> Option<PointerEvent> {
+        let (x, y) = match self.mapping.pointer_stick? {
+            Stick::Left => (self.sticks[0], self.sticks[1]),
+            Stick::Right => (self.sticks[2], self.sticks[3]),
+        };
+
+        // Radial deadzone, rescaled so motion starts smoothly at its edge
+        let magnitude = x.hypot(y);
+        let deadzone = self.mapping.deadzone;
+        if magnitude <= deadzone {
+            self.remainder = (0.0, 0.0);
+            return None;
+        }
+        let scale = ((magnitude - deadzone) / (1.0 - deadzone)).min(1.0) / magnitude;
+
+        let counts = self.mapping.pointer_speed * COUNTS_PER_WIDTH * elapsed.as_secs_f64() * scale;
+        let dx = x * counts + self.remainder.0;
+        let dy = y * counts + self.remainder.1;
+        self.remainder = (dx.fract(), dy.fract());
+        let (dx, dy) = (dx.trunc() as i32, dy.trunc() as i32);
+        (dx != 0 || dy != 0).then_some(PointerEvent::Move { dx, dy })
+    }
+}
+
+/// A connected controller driving navigation until stopped or dropped
+pub struct GamepadSession {
+    device: GamepadDevice,
+    controller: Arc<Mutex<GamepadController>>,
+    stop: Arc<AtomicBool>,
+    _tap: GamepadTap,
+}
+
+impl GamepadSession {
+    /// Listen to the controller with `id`, calling `on_event` for each mapped input
+    pub fn start(
+        id: &str,
+        mapping: GamepadMapping,
+        on_event: impl Fn(ControllerEvent) + Send + Sync + 'static,
+    ) -> Result<Self> {
+        let on_event = Arc::new(on_event);
+        let controller = Arc::new(Mutex::new(GamepadController::new(mapping)));
+
+        let inputs = controller.clone();
+        let on_input = on_event.clone();
+        let tap = GamepadTap::start(id, move |input| {
+            let event = match inputs.lock() {
+                Ok(mut controller) => controller.handle(input),
+                Err(_) => return,
+            };
+            if let Some(event) = event {
+                on_input(event);
+            }
+        })?;
+
+        let stop = Arc::new(AtomicBool::new(false));
+        let stopped = stop.clone();
+        let sticks = controller.clone();
+        std::thread::Builder::new()
+            .name("gamepad-pointer".to_string())
+            .spawn(move || {
+                let mut last = Instant::now();
+                while !stopped.load(Ordering::Relaxed) {
+                    std::thread::sleep(POINTER_TICK);
+                    let now = Instant::now();
+                    let motion = match sticks.lock() {
+                        Ok(mut controller) => controller.pointer_motion(now - last),
+                        Err(_) => break,
+                    };
+                    last = now;
+                    if let Some(motion) = motion {
+                        on_event(ControllerEvent::Pointer(motion));
+                    }
+                }
+            })?;
+
+        Ok(Self {
+            device: tap.device().clone(),
+            controller,
+            stop,
+            _tap: tap,
+        })
+    }
+
+    pub fn device(&self) -> &GamepadDevice {
+        &self.device
+    }
+
+    /// Apply a new mapping without reconnecting
+    pub fn set_mapping(&self, mapping: GamepadMapping) {
+        if let Ok(mut controller) = self.controller.lock() {
+            controller.set_mapping(mapping);
+        }
+    }
+}
+
+impl Drop for GamepadSession {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::Relaxed);
+    }
+}
+
+impl std::fmt::Debug for GamepadSession {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("GamepadSession")
+            .field("device", &self.device)
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn press(button: GamepadButton, pressed: bool) -> GamepadInput {
+        GamepadInput::Button { button, pressed }
+    }
+
+    #[test]
+    fn test_buttons_map_to_actions() {
+        let mut controller = GamepadController::new(GamepadMapping::default());
+
+        assert_eq!(
+            controller.handle(press(GamepadButton::DpadRight, true)),
+            Some(ControllerEvent::Action(GamepadAction::NextPage))
+        );
+        assert_eq!(
+            controller.handle(press(GamepadButton::DpadRight, false)),
+            None
+        );
+        assert_eq!(
+            controller.handle(press(GamepadButton::RightStick, true)),
+            None
+        );
+        assert_eq!(
+            controller.handle(press(GamepadButton::RightTrigger, false)),
+            Some(ControllerEvent::Pointer(PointerEvent::Button {
+                pressed: false
+            }))
+        );
+
+        let json = serde_json::to_string(&GamepadMapping::default()).unwrap();
+        assert!(json.contains(r#""dpad_right":"next_page""#));
+        let parsed: GamepadMapping = serde_json::from_str(r#"{"deadzone":0.3}"#).unwrap();
+        assert_eq!(parsed.deadzone, 0.3);
+        assert_eq!(parsed.pointer_stick, Some(Stick::Left));
+    }
+
+    #[test]
+    fn test_stick_moves_pointer_outside_deadzone() {
+        let mut controller = GamepadController::new(GamepadMapping {
+            pointer_speed: 1.0,
+            deadzone: 0.2,
+            ..GamepadMapping::default()
+        });
+        let second = Duration::from_secs(1);
+
+        controller.handle(GamepadInput::Axis {
+            axis: GamepadAxis::LeftX,
+            value: 0.1,
+        });
+        assert_eq!(controller.pointer_motion(second), None);
+
+        // Full deflection crosses the frame once per second at speed 1.0
+        controller.handle(GamepadInput::Axis {
+            axis: GamepadAxis::LeftX,
+            value: -1.0,
+        });
+        assert_eq!(
+            controller.pointer_motion(second),
+            Some(PointerEvent::Move {
+                dx: -(COUNTS_PER_WIDTH as i32),
+                dy: 0
+            })
+        );
+
+        // Fractions of a count carry over between ticks
+        controller.handle(GamepadInput::Axis {
+            axis: GamepadAxis::LeftX,
+            value: 0.2 + 0.8 * 0.6 / COUNTS_PER_WIDTH,
+        });
+        assert_eq!(controller.pointer_motion(second), None);
+        assert_eq!(
+            controller.pointer_motion(second),
+            Some(PointerEvent::Move { dx: 1, dy: 0 })
+        );
+    }
+}
@@ -0,0 +1,240 @@
+/*
+ * This file is part of StreamSlate.
+ * Copyright (C) 2025 StreamSlate Contributors
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+
+//! REST API mirroring the WebSocket commands
+//!
+//! Automation tools such as Bitfocus Companion's generic HTTP module, shell
+//! scripts and cron jobs find a request easier to make than a WebSocket
+//! connection. Each route maps to a command, which runs as if a WebSocket
+//! client sent it, with the role of the request's bearer token; the reply
+//! is the event a client would get.
+//!
+//! Responses carry no CORS headers, so pages on other sites can't read
+//! them. A request without a token runs with the default role, but never
+//! as an admin. Unless it's a `GET`, which only reads state, it must be
+//! sent as `application/json`, which a browser only sends across sites
+//! after a CORS preflight, so such pages can't send commands either.
+
+use super::Request;
+use crate::state::AppState;
+use crate::websocket::access::{self, Role};
+use crate::websocket::{handle_command_as, WebSocketCommand, WebSocketEvent};
+use serde_json::{json, Map, Value};
+use std::net::SocketAddr;
+use std::sync::Arc;
+use tauri::AppHandle;
+
+/// Path prefix of the API routes
+pub const PREFIX: &str = "/api/";
+
+/// The command a request maps to
+///
+/// Fails with the status and message to answer with when the route is
+/// unknown or the body doesn't fit the command.
+pub fn command_for(
+    method: &str,
+    path: &str,
+    body: &[u8],
+) -> Result<WebSocketCommand, (u16, String)> {
+    let route = path
+        .strip_prefix(PREFIX)
+        .unwrap_or(path)
+        .trim_end_matches('/');
+    let segments: Vec<&str> = route.split('/').collect();
+    let command = match (method, segments.as_slice()) {
+        ("GET", ["state"]) => WebSocketCommand::GetState,
+        ("GET", ["agenda"]) => WebSocketCommand::GetAgenda,
+        ("GET", ["media"]) => WebSocketCommand::GetMedia,
+        ("GET", ["layers"]) => WebSocketCommand::GetLayers,
+        ("GET", ["notes", page]) => WebSocketCommand::GetNotes {
+            page: page
+                .parse()
+                .map_err(|_| (404, format!("No such page: {page}")))?,
+        },
+        ("GET", ["style-presets"]) => WebSocketCommand::GetStylePresets,
+        ("POST", ["page", "next"]) => WebSocketCommand::NextPage,
+        ("POST", ["page", "previous"]) => WebSocketCommand::PreviousPage,
+        ("POST", ["page", page]) => WebSocketCommand::GoToPage {
+            page: page
+                .parse()
+                .map_err(|_| (404, format!("No such page: {page}")))?,
+        },
+        ("POST", ["section", "next"]) => WebSocketCommand::NextSection,
+        ("POST", ["section", "previous"]) => WebSocketCommand::PreviousSection,
+        ("POST", ["presenter", "toggle"]) => WebSocketCommand::TogglePresenter,
+        ("POST", ["zoom"]) => with_body("SET_ZOOM", body)?,
+        ("POST", ["blackout"]) => with_body("SET_BLACKOUT", body)?,
+        ("POST", ["smart-invert"]) => with_body("SET_SMART_INVERT", body)?,
+        ("POST", ["annotations"]) => with_body("ADD_ANNOTATION", body)?,
+        ("DELETE", ["annotations"]) => WebSocketCommand::ClearAnnotations,
+        ("POST", ["annotations", "undo"]) => WebSocketCommand::UndoAnnotation,
+        ("POST", ["annotations", "redo"]) => WebSocketCommand::RedoAnnotation,
+        ("POST", ["command"]) => {
+            serde_json::from_slice(body).map_err(|e| (400, format!("Invalid command: {e}")))?
+        }
+        (
+            _,
+            ["state" | "agenda" | "media" | "layers" | "style-presets" | "page" | "section"
+            | "presenter" | "zoom" | "blackout" | "smart-invert" | "annotations" | "command", ..],
+        ) => return Err((405, "Method not allowed".to_string())),
+        _ => return Err((404, "Not found".to_string())),
+    };
+    Ok(command)
+}
+
+/// A command of type `kind` with the fields of a JSON object body
+fn with_body(kind: &str, body: &[u8]) -> Result<WebSocketCommand, (u16, String)> {
+    let mut fields: Map<String, Value> = if body.is_empty() {
+        Map::new()
+    } else {
+        serde_json::from_slice(body).map_err(|e| (400, format!("Invalid body: {e}")))?
+    };
+    fields.insert("type".to_string(), Value::String(kind.to_string()));
+    serde_json::from_value(Value::Object(fields)).map_err(|e| (400, format!("Invalid body: {e}")))
+}
+
+/// The role a request's bearer token grants, or the default role up to
+/// controller for a `GET` or JSON request without one
+fn role_for(request: &Request) -> Result<Role, (u16, String)> {
+    if request.token.is_none() {
+        if request.method != "GET" && !request.json {
+            return Err((
+                401,
+                "Send an access token or an application/json request".to_string(),
+            ));
+        }
+        return Ok(access::default_client_role().min(Role::Controller));
+    }
+    token_role(request)
+}
+
+/// The role a request's bearer token grants, failing without a known token
+pub fn token_role(request: &Request) -> Result<Role, (u16, String)> {
+    let Some(token) = &request.token else {
+        return Err((401, "Send an access token".to_string()));
+    };
+    access::access()
+        .read()
+        .ok()
+        .and_then(|config| config.role_for(token))
+        .ok_or_else(|| (401, "Unknown access token".to_string()))
+}
+
+/// Run the command a request maps to, answering with a status code and the
+/// reply event
+pub fn handle(
+    request: &Request,
+    peer_addr: SocketAddr,
+    state: &Arc<AppState>,
+    app_handle: &AppHandle,
+) -> (u16, Value) {
+    let run = || -> Result<WebSocketEvent, (u16, String)> {
+        let role = role_for(request)?;
+        let command = command_for(&request.method, &request.path, &request.body)?;
+        Ok(handle_command_as(
+            command, role, peer_addr, state, app_handle,
+        ))
+    };
+    match run() {
+        Ok(event) => {
+            let status = if event.is_failure() { 400 } else { 200 };
+            let body =
+                serde_json::to_value(&event).unwrap_or_else(|e| json!({ "error": e.to_string() }));
+            (status, body)
+        }
+        Err((status, message)) => (status, json!({ "error": message })),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_command_for() {
+        let command = |method, path, body: &str| command_for(method, path, body.as_bytes());
+
+        assert!(matches!(
+            command("POST", "/api/page/next", ""),
+            Ok(WebSocketCommand::NextPage)
+        ));
+        assert!(matches!(
+            command("POST", "/api/page/7/", ""),
+            Ok(WebSocketCommand::GoToPage { page: 7 })
+        ));
+        assert!(matches!(
+            command("GET", "/api/state", ""),
+            Ok(WebSocketCommand::GetState)
+        ));
+        assert!(matches!(
+            command("POST", "/api/zoom", r#"{"zoom":1.5}"#),
+            Ok(WebSocketCommand::SetZoom { zoom }) if zoom == 1.5
+        ));
+        assert!(matches!(
+            command(
+                "POST",
+                "/api/annotations",
+                r#"{"page":2,"annotation":{"id":"a1","type":"ink"}}"#
+            ),
+            Ok(WebSocketCommand::AddAnnotation { page: 2, .. })
+        ));
+        assert!(matches!(
+            command("POST", "/api/command", r#"{"type":"HIDE_QR"}"#),
+            Ok(WebSocketCommand::HideQr)
+        ));
+
+        assert!(matches!(command("POST", "/api/zoom", "{}"), Err((400, _))));
+        assert!(matches!(
+            command("GET", "/api/page/next", ""),
+            Err((405, _))
+        ));
+        assert!(matches!(
+            command("POST", "/api/page/last", ""),
+            Err((404, _))
+        ));
+        assert!(matches!(command("GET", "/api/teleport", ""), Err((404, _))));
+    }
+
+    #[test]
+    fn test_role_for() {
+        let request = |token: Option<&str>, json| Request {
+            method: "POST".to_string(),
+            path: "/api/page/next".to_string(),
+            token: token.map(str::to_string),
+            json,
+            body: Default::default(),
+        };
+
+        // A form or `fetch` from another site can't set a JSON content type
+        assert!(matches!(role_for(&request(None, false)), Err((401, _))));
+        // but reading state needs no body to declare
+        let get = Request {
+            method: "GET".to_string(),
+            path: "/api/state".to_string(),
+            ..request(None, false)
+        };
+        assert!(role_for(&get).is_ok_and(|role| role <= Role::Controller));
+        assert!(matches!(token_role(&get), Err((401, _))));
+        assert!(role_for(&request(None, true)).is_ok_and(|role| role <= Role::Controller));
+        assert!(matches!(
+            role_for(&request(Some("not-a-token"), true)),
+            Err((401, _))
+        ));
+        assert!(matches!(token_role(&request(None, true)), Err((401, _))));
+    }
+}
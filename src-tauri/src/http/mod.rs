@@ -0,0 +1,357 @@
+/*
+ * This file is part of StreamSlate.
+ * Copyright (C) 2025 StreamSlate Contributors
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+
+//! Local HTTP endpoint
+//!
+//! An HTTP/1.1 server (hyper) next to the WebSocket server for clients that
+//! can only make plain requests, such as a web form viewers fill in to
+//! answer attendance checkpoints, a monitor page polling the remote view, or
+//! automation tools using the REST API under `/api/`. It also serves the
+//! browser remote-control page at `/`.
+
+pub mod api;
+pub mod remote;
+
+use crate::commands::checkpoint::{current_checkpoint, submit_checkpoint_response};
+use crate::settings::load_settings;
+use crate::state::AppState;
+use crate::websocket::tls::{self, TlsConfig};
+use http_body_util::{BodyExt, Full, Limited};
+use hyper::body::{Bytes, Incoming};
+use hyper::header::{self, HeaderMap, HeaderValue};
+use hyper::server::conn::http1;
+use hyper::service::service_fn;
+use hyper::{Response, StatusCode};
+use hyper_util::rt::{TokioIo, TokioTimer};
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::convert::Infallible;
+use std::io::ErrorKind;
+use std::net::{IpAddr, SocketAddr};
+use std::sync::Arc;
+use std::time::Duration;
+use tauri::AppHandle;
+use tokio::io::{AsyncRead, AsyncWrite};
+use tokio::net::TcpListener;
+use tracing::{debug, error, info};
+
+/// Default port for the HTTP endpoint, next to the WebSocket server's
+pub const DEFAULT_PORT: u16 = 11452;
+
+/// Largest request body read from a client, enough for an annotation with
+/// many ink points
+const MAX_BODY_BYTES: usize = 1024 * 1024;
+
+/// Drop clients that don't finish sending their request in this time
+const READ_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// A request with its body read
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Request {
+    pub method: String,
+    /// Path without the query string
+    pub path: String,
+    /// Bearer token from the `Authorization` header
+    pub token: Option<String>,
+    /// Whether the body is declared as `application/json`
+    pub json: bool,
+    pub body: Bytes,
+}
+
+/// Start the HTTP endpoint
+///
+/// Binds to the same address as the WebSocket server, loopback unless it
+/// was set to accept connections from the network; expose it through a
+/// tunnel or reverse proxy for remote viewers. With TLS on for the
+/// WebSocket server, requests are served over HTTPS with the same
+/// certificate, so access tokens aren't sent in the clear.
+pub async fn start_server(
+    ip: IpAddr,
+    port: u16,
+    state: Arc<AppState>,
+    app_handle: AppHandle,
+) -> Result<(), std::io::Error> {
+    let tls_config: TlsConfig = load_settings(&app_handle, tls::CONFIG_FILE);
+    let acceptor = tls::acceptor_for(&app_handle, &tls_config)
+        .map_err(|e| std::io::Error::new(ErrorKind::InvalidInput, e.to_string()))?;
+    let addr = SocketAddr::new(ip, port);
+    let listener = TcpListener::bind(addr).await?;
+
+    let scheme = if acceptor.is_some() { "https" } else { "http" };
+    info!(
+        port = port,
+        "HTTP endpoint started on {}://{}", scheme, addr
+    );
+
+    tokio::spawn(async move {
+        loop {
+            match listener.accept().await {
+                Ok((stream, peer_addr)) => {
+                    let state = Arc::clone(&state);
+                    let app_handle = app_handle.clone();
+                    let acceptor = acceptor.clone();
+                    tokio::spawn(async move {
+                        let Some(acceptor) = acceptor else {
+                            return serve(stream, peer_addr, state, app_handle).await;
+                        };
+                        let handshake = tokio::time::timeout(READ_TIMEOUT, acceptor.accept(stream));
+                        match handshake.await {
+                            Ok(Ok(stream)) => serve(stream, peer_addr, state, app_handle).await,
+                            Ok(Err(e)) => {
+                                debug!(peer = %peer_addr, error = %e, "TLS handshake failed");
+                            }
+                            Err(_) => debug!(peer = %peer_addr, "TLS handshake timed out"),
+                        }
+                    });
+                }
+                Err(e) => {
+                    error!(error = %e, "Failed to accept HTTP connection");
+                }
+            }
+        }
+    });
+
+    Ok(())
+}
+
+/// Serve the requests of one connection, plain or over TLS
+async fn serve<S: AsyncRead + AsyncWrite + Unpin + Send + 'static>(
+    stream: S,
+    peer_addr: SocketAddr,
+    state: Arc<AppState>,
+    app_handle: AppHandle,
+) {
+    let service = service_fn(move |request| {
+        let state = Arc::clone(&state);
+        let app_handle = app_handle.clone();
+        async move { Ok::<_, Infallible>(respond(request, peer_addr, &state, &app_handle).await) }
+    });
+    let served = http1::Builder::new()
+        .timer(TokioTimer::new())
+        .header_read_timeout(READ_TIMEOUT)
+        .serve_connection(TokioIo::new(stream), service)
+        .await;
+    if let Err(e) = served {
+        debug!(peer = %peer_addr, error = %e, "HTTP connection error");
+    }
+}
+
+/// Answer one request
+async fn respond(
+    request: hyper::Request<Incoming>,
+    peer_addr: SocketAddr,
+    state: &Arc<AppState>,
+    app_handle: &AppHandle,
+) -> Response<Full<Bytes>> {
+    let request = match read_request(request).await {
+        Ok(request) => request,
+        Err((status, reason)) => return json_response(status, &json!({ "error": reason })),
+    };
+    debug!(peer = %peer_addr, method = %request.method, path = %request.path, "HTTP request");
+
+    match (request.method.as_str(), request.path.as_str()) {
+        // A live capture of the screen, for token holders only
+        ("GET", "/remote-view.jpg") => match api::token_role(&request) {
+            Ok(_) => remote_view_response(state),
+            Err((status, reason)) => json_response(status, &json!({ "error": reason })),
+        },
+        ("GET", "/" | "/remote") => remote_page_response(state, app_handle),
+        // Only tools, not pages on other sites, may call the API
+        (_, path) if path.starts_with(api::PREFIX) => {
+            let (status, body) = api::handle(&request, peer_addr, state, app_handle);
+            json_response(status, &body)
+        }
+        _ => {
+            let (status, body) = route(&request, state, app_handle);
+            allow_any_origin(json_response(status, &body))
+        }
+    }
+}
+
+/// Read a request's head and body, or the status and reason to refuse it
+async fn read_request(request: hyper::Request<Incoming>) -> Result<Request, (u16, &'static str)> {
+    let (parts, body) = request.into_parts();
+    let body = tokio::time::timeout(READ_TIMEOUT, Limited::new(body, MAX_BODY_BYTES).collect())
+        .await
+        .map_err(|_| (408, "Request timed out"))?
+        .map_err(|_| (413, "Request too large"))?
+        .to_bytes();
+    Ok(Request {
+        method: parts.method.to_string(),
+        path: parts.uri.path().to_string(),
+        token: bearer_token(&parts.headers),
+        json: is_json(&parts.headers),
+        body,
+    })
+}
+
+fn bearer_token(headers: &HeaderMap) -> Option<String> {
+    let value = headers.get(header::AUTHORIZATION)?.to_str().ok()?;
+    value
+        .trim()
+        .strip_prefix("Bearer ")
+        .map(|token| token.trim().to_string())
+}
+
+fn is_json(headers: &HeaderMap) -> bool {
+    headers
+        .get(header::CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.split(';').next())
+        .is_some_and(|mime| mime.trim().eq_ignore_ascii_case("application/json"))
+}
+
+/// The latest remote view frame as a JPEG, never cached
+fn remote_view_response(state: &AppState) -> Response<Full<Bytes>> {
+    let Some(frame) = state.remote_view.borrow().clone() else {
+        return json_response(404, &json!({ "error": "Remote view is off" }));
+    };
+    let mut response = response(200, "image/jpeg", Bytes::copy_from_slice(&frame.jpeg));
+    response
+        .headers_mut()
+        .insert(header::CACHE_CONTROL, HeaderValue::from_static("no-store"));
+    response
+}
+
+/// The remote-control page, pointed at the running WebSocket server
+fn remote_page_response(state: &AppState, app_handle: &AppHandle) -> Response<Full<Bytes>> {
+    let port = state
+        .websocket
+        .read()
+        .map(|websocket| websocket.port)
+        .unwrap_or(crate::websocket::DEFAULT_PORT);
+    let tls = load_settings::<TlsConfig>(app_handle, tls::CONFIG_FILE).enabled;
+    let mut response = response(200, "text/html; charset=utf-8", remote::page(port, tls));
+    response
+        .headers_mut()
+        .insert(header::CACHE_CONTROL, HeaderValue::from_static("no-store"));
+    response
+}
+
+/// Answer a request with a status code and JSON body
+fn route(request: &Request, state: &AppState, app_handle: &AppHandle) -> (u16, Value) {
+    match (request.method.as_str(), request.path.as_str()) {
+        // CORS preflight from browser forms
+        ("OPTIONS", _) => (204, Value::Null),
+        ("GET", "/checkpoint") => match current_checkpoint(state) {
+            Ok(Some(open)) => (200, json!({ "id": open.id, "prompt": open.prompt })),
+            Ok(None) => (404, json!({ "error": "No checkpoint is open" })),
+            Err(e) => (500, json!({ "error": e.to_string() })),
+        },
+        ("POST", "/checkpoint") => {
+            #[derive(Deserialize)]
+            struct Answer {
+                name: String,
+                code: String,
+            }
+
+            let answer: Answer = match serde_json::from_slice(&request.body) {
+                Ok(answer) => answer,
+                Err(e) => return (400, json!({ "error": format!("Invalid answer: {e}") })),
+            };
+            match submit_checkpoint_response(state, app_handle, &answer.name, &answer.code) {
+                Ok(recorded) => (200, json!({ "recorded": recorded })),
+                Err(e) => (400, json!({ "error": e.to_string() })),
+            }
+        }
+        (_, "/checkpoint") => (405, json!({ "error": "Method not allowed" })),
+        _ => (404, json!({ "error": "Not found" })),
+    }
+}
+
+fn response(
+    status: u16,
+    content_type: &'static str,
+    body: impl Into<Bytes>,
+) -> Response<Full<Bytes>> {
+    let mut response = Response::new(Full::new(body.into()));
+    *response.status_mut() =
+        StatusCode::from_u16(status).unwrap_or(StatusCode::INTERNAL_SERVER_ERROR);
+    response
+        .headers_mut()
+        .insert(header::CONTENT_TYPE, HeaderValue::from_static(content_type));
+    response
+}
+
+/// A JSON response; a null body is sent empty
+fn json_response(status: u16, body: &Value) -> Response<Full<Bytes>> {
+    let body = if body.is_null() {
+        String::new()
+    } else {
+        body.to_string()
+    };
+    response(status, "application/json", body)
+}
+
+/// Let pages on any site call a public route, such as the checkpoint form
+fn allow_any_origin(mut response: Response<Full<Bytes>>) -> Response<Full<Bytes>> {
+    let headers = response.headers_mut();
+    headers.insert(
+        header::ACCESS_CONTROL_ALLOW_ORIGIN,
+        HeaderValue::from_static("*"),
+    );
+    headers.insert(
+        header::ACCESS_CONTROL_ALLOW_METHODS,
+        HeaderValue::from_static("GET, POST, OPTIONS"),
+    );
+    headers.insert(
+        header::ACCESS_CONTROL_ALLOW_HEADERS,
+        HeaderValue::from_static("Content-Type"),
+    );
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_request_headers() {
+        let mut headers = HeaderMap::new();
+        assert_eq!(bearer_token(&headers), None);
+        assert!(!is_json(&headers));
+
+        headers.insert(
+            header::AUTHORIZATION,
+            HeaderValue::from_static("Bearer deck-secret"),
+        );
+        headers.insert(
+            header::CONTENT_TYPE,
+            HeaderValue::from_static("application/JSON; charset=utf-8"),
+        );
+        assert_eq!(bearer_token(&headers).as_deref(), Some("deck-secret"));
+        assert!(is_json(&headers));
+
+        headers.insert(header::AUTHORIZATION, HeaderValue::from_static("Basic abc"));
+        headers.insert(header::CONTENT_TYPE, HeaderValue::from_static("text/plain"));
+        assert_eq!(bearer_token(&headers), None);
+        assert!(!is_json(&headers));
+    }
+
+    #[test]
+    fn test_cors_headers() {
+        let api = json_response(404, &json!({ "error": "Not found" }));
+        assert_eq!(api.status(), StatusCode::NOT_FOUND);
+        assert!(!api
+            .headers()
+            .contains_key(header::ACCESS_CONTROL_ALLOW_ORIGIN));
+
+        let public = allow_any_origin(json_response(204, &Value::Null));
+        assert_eq!(public.headers()[header::ACCESS_CONTROL_ALLOW_ORIGIN], "*");
+    }
+}
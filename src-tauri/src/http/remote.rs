@@ -0,0 +1,44 @@
+/*
+ * This file is part of StreamSlate.
+ * Copyright (C) 2025 StreamSlate Contributors
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+
+//! Browser remote-control page
+//!
+//! A phone on the same network opens the endpoint in its browser and gets
+//! next, previous, go-to-page and zoom buttons that talk to the WebSocket
+//! server, so it can be used as a clicker without installing anything. A
+//! `?token=` query parameter is sent in `HELLO` for another role.
+
+const PAGE: &str = include_str!("remote.html");
+
+/// The page, pointed at the WebSocket server on `port`
+pub fn page(port: u16, tls: bool) -> String {
+    PAGE.replace("__WS_SCHEME__", if tls { "wss" } else { "ws" })
+        .replace("__WS_PORT__", &port.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_page() {
+        let html = page(11455, true);
+        assert!(html.contains(r#""wss://" + location.hostname + ":11455""#));
+        assert!(!html.contains("__WS_"));
+    }
+}
@@ -0,0 +1,472 @@
+/*
+ * This file is part of StreamSlate.
+ * Copyright (C) 2025 StreamSlate Contributors
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+
+//! Markdown slide decks
+//!
+//! One slide per `---` separator. Supports headings, paragraphs, bullet and
+//! numbered lists, and fenced code blocks; inline formatting is reduced to
+//! plain text. Slides are laid out with the PDF base-14 fonts, so no font
+//! files need to be bundled.
+
+use super::Importer;
+use crate::error::{Result, StreamSlateError};
+use lopdf::content::{Content, Operation};
+use lopdf::{dictionary, Dictionary, Document, Object, Stream, StringFormat};
+
+/// 16:9 slide size in points
+const PAGE_WIDTH: f64 = 960.0;
+const PAGE_HEIGHT: f64 = 540.0;
+const MARGIN: f64 = 48.0;
+
+const BODY_SIZE: f64 = 22.0;
+const CODE_SIZE: f64 = 18.0;
+const LINE_SPACING: f64 = 1.3;
+
+/// A parsed block of slide content
+#[derive(Debug, Clone, PartialEq)]
+pub enum Block {
+    Heading {
+        level: u8,
+        text: String,
+    },
+    Paragraph(String),
+    ListItem {
+        depth: u8,
+        marker: String,
+        text: String,
+    },
+    Code(Vec<String>),
+}
+
+/// Markdown importer (`.md`, `.markdown`)
+pub struct MarkdownImporter;
+
+impl Importer for MarkdownImporter {
+    fn name(&self) -> &'static str {
+        "Markdown"
+    }
+
+    fn extensions(&self) -> &'static [&'static str] {
+        &["md", "markdown"]
+    }
+
+    fn import(&self, source: &str) -> Result<Document> {
+        let slides = parse_slides(source);
+        if slides.is_empty() {
+            return Err(StreamSlateError::Other(
+                "Markdown file contains no slides".to_string(),
+            ));
+        }
+        render_slides(&slides)
+    }
+}
+
+/// Split Markdown into slides of parsed blocks
+///
+/// Empty slides (e.g. from front matter delimiters) are dropped.
+pub fn parse_slides(source: &str) -> Vec<Vec<Block>> {
+    let mut slides = Vec::new();
+    let mut blocks = Vec::new();
+    let mut paragraph: Vec<&str> = Vec::new();
+    let mut code: Option<Vec<String>> = None;
+
+    let flush_paragraph = |paragraph: &mut Vec<&str>, blocks: &mut Vec<Block>| {
+        if !paragraph.is_empty() {
+            blocks.push(Block::Paragraph(inline_text(&paragraph.join(" "))));
+            paragraph.clear();
+        }
+    };
+
+    for line in source.lines() {
+        let trimmed = line.trim();
+
+        if let Some(lines) = code.as_mut() {
+            if trimmed.starts_with("```") {
+                blocks.push(Block::Code(code.take().unwrap_or_default()));
+            } else {
+                lines.push(line.trim_end().to_string());
+            }
+            continue;
+        }
+
+        if trimmed.starts_with("```") {
+            flush_paragraph(&mut paragraph, &mut blocks);
+            code = Some(Vec::new());
+        } else if trimmed == "---" {
+            flush_paragraph(&mut paragraph, &mut blocks);
+            if !blocks.is_empty() {
+                slides.push(std::mem::take(&mut blocks));
+            }
+        } else if trimmed.is_empty() {
+            flush_paragraph(&mut paragraph, &mut blocks);
+        } else if let Some(block) = parse_heading(trimmed).or_else(|| parse_list_item(line)) {
+            flush_paragraph(&mut paragraph, &mut blocks);
+            blocks.push(block);
+        } else {
+            paragraph.push(trimmed);
+        }
+    }
+
+    // An unterminated fence runs to the end of the file
+    if let Some(lines) = code {
+        blocks.push(Block::Code(lines));
+    }
+    flush_paragraph(&mut paragraph, &mut blocks);
+    if !blocks.is_empty() {
+        slides.push(blocks);
+    }
+
+    slides
+}
+
+fn parse_heading(line: &str) -> Option<Block> {
+    let level = line.chars().take_while(|&c| c == '#').count();
+    if level == 0 || level > 6 || !line[level..].starts_with(' ') {
+        return None;
+    }
+    Some(Block::Heading {
+        level: level as u8,
+        text: inline_text(line[level..].trim()),
+    })
+}
+
+fn parse_list_item(line: &str) -> Option<Block> {
+    let indent = line.len() - line.trim_start().len();
+    let trimmed = line.trim_start();
+    let depth = (indent / 2).min(4) as u8;
+
+    for bullet in ["- ", "* ", "+ "] {
+        if let Some(text) = trimmed.strip_prefix(bullet) {
+            return Some(Block::ListItem {
+                depth,
+                marker: "\u{2022}".to_string(),
+                text: inline_text(text.trim()),
+            });
+        }
+    }
+
+    let digits = trimmed.chars().take_while(char::is_ascii_digit).count();
+    if digits > 0 && trimmed[digits..].starts_with(". ") {
+        return Some(Block::ListItem {
+            depth,
+            marker: trimmed[..=digits].to_string(),
+            text: inline_text(trimmed[digits + 2..].trim()),
+        });
+    }
+
+    None
+}
+
+/// Reduce inline Markdown (emphasis, code spans, links, images) to plain text
+pub fn inline_text(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut rest = text;
+
+    while let Some(c) = rest.chars().next() {
+        // [label](url) and ![alt](src) keep only the label
+        if c == '[' || (c == '!' && rest[1..].starts_with('[')) {
+            let open = rest.find('[').unwrap_or(0);
+            let close = closing_bracket(&rest[open..]).map(|i| open + i);
+            if let Some(close) = close.filter(|&close| rest[close + 1..].starts_with('(')) {
+                if let Some(end) = rest[close..].find(')') {
+                    out.push_str(&inline_text(&rest[open + 1..close]));
+                    rest = &rest[close + end + 1..];
+                    continue;
+                }
+            }
+        }
+        let double_underscore = c == '_' && rest[1..].starts_with('_');
+        if double_underscore {
+            // Skip both underscores of `__bold__`, but keep snake_case intact
+            rest = &rest[1..];
+        } else if !matches!(c, '*' | '`') {
+            out.push(c);
+        }
+        rest = &rest[c.len_utf8()..];
+    }
+
+    out
+}
+
+/// Byte offset of the `]` matching the `[` that `text` starts with
+fn closing_bracket(text: &str) -> Option<usize> {
+    let mut depth = 0;
+    for (i, c) in text.char_indices() {
+        match c {
+            '[' => depth += 1,
+            ']' => {
+                depth -= 1;
+                if depth == 0 {
+                    return Some(i);
+                }
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+/// Render parsed slides into a PDF document, one page per slide
+fn render_slides(slides: &[Vec<Block>]) -> Result<Document> {
+    let mut doc = Document::with_version("1.7");
+    let pages_id = doc.new_object_id();
+
+    let font = |base: &str| -> Object {
+        dictionary! {
+            "Type" => "Font",
+            "Subtype" => "Type1",
+            "BaseFont" => base,
+            "Encoding" => "WinAnsiEncoding",
+        }
+        .into()
+    };
+    let resources_id = doc.add_object(dictionary! {
+        "Font" => dictionary! {
+            "F1" => font("Helvetica"),
+            "F2" => font("Helvetica-Bold"),
+            "F3" => font("Courier"),
+        },
+    });
+
+    let mut kids = Vec::with_capacity(slides.len());
+    for blocks in slides {
+        let content = Content {
+            operations: layout_slide(blocks),
+        };
+        let encoded = content.encode()?;
+        let content_id = doc.add_object(Stream::new(Dictionary::new(), encoded));
+        let page_id = doc.add_object(dictionary! {
+            "Type" => "Page",
+            "Parent" => pages_id,
+            "Contents" => content_id,
+        });
+        kids.push(page_id.into());
+    }
+
+    let count = kids.len() as i64;
+    doc.objects.insert(
+        pages_id,
+        Object::Dictionary(dictionary! {
+            "Type" => "Pages",
+            "Kids" => kids,
+            "Count" => count,
+            "Resources" => resources_id,
+            "MediaBox" => vec![0.into(), 0.into(), PAGE_WIDTH.into(), PAGE_HEIGHT.into()],
+        }),
+    );
+
+    let mut info = Dictionary::new();
+    if let Some(title) = slides.iter().flatten().find_map(|block| match block {
+        Block::Heading { text, .. } => Some(text),
+        _ => None,
+    }) {
+        info.set("Title", Object::string_literal(encode_win_ansi(title)));
+    }
+    info.set("Producer", Object::string_literal("StreamSlate"));
+    let info_id = doc.add_object(info);
+
+    let catalog_id = doc.add_object(dictionary! {
+        "Type" => "Catalog",
+        "Pages" => pages_id,
+    });
+    doc.trailer.set("Root", catalog_id);
+    doc.trailer.set("Info", info_id);
+    doc.compress();
+
+    Ok(doc)
+}
+
+/// Lay out one slide top to bottom, clipping content that overflows
+fn layout_slide(blocks: &[Block]) -> Vec<Operation> {
+    let mut ops = Vec::new();
+    let mut y = PAGE_HEIGHT - MARGIN;
+    let max_width = PAGE_WIDTH - 2.0 * MARGIN;
+
+    for (index, block) in blocks.iter().enumerate() {
+        if index > 0 {
+            y -= BODY_SIZE * 0.5;
+        }
+        match block {
+            Block::Heading { level, text } => {
+                let size = match level {
+                    1 => 40.0,
+                    2 => 32.0,
+                    _ => 26.0,
+                };
+                for wrapped in wrap(text, max_width, size * 0.55) {
+                    push_line(&mut ops, &mut y, "F2", size, MARGIN, &wrapped);
+                }
+            }
+            Block::Paragraph(text) => {
+                for wrapped in wrap(text, max_width, BODY_SIZE * 0.5) {
+                    push_line(&mut ops, &mut y, "F1", BODY_SIZE, MARGIN, &wrapped);
+                }
+            }
+            Block::ListItem {
+                depth,
+                marker,
+                text,
+            } => {
+                let indent = MARGIN + f64::from(*depth) * 32.0;
+                let text_x = indent + 28.0;
+                for (i, wrapped) in wrap(text, PAGE_WIDTH - MARGIN - text_x, BODY_SIZE * 0.5)
+                    .iter()
+                    .enumerate()
+                {
+                    if i == 0 {
+                        push_line(&mut ops, &mut y, "F1", BODY_SIZE, indent, marker);
+                        y += BODY_SIZE * LINE_SPACING;
+                    }
+                    push_line(&mut ops, &mut y, "F1", BODY_SIZE, text_x, wrapped);
+                }
+            }
+            Block::Code(lines) => {
+                // Courier is monospaced at 0.6em, so truncation is exact
+                let max_chars = (max_width / (CODE_SIZE * 0.6)) as usize;
+                for code_line in lines {
+                    let clipped: String = code_line.chars().take(max_chars).collect();
+                    push_line(&mut ops, &mut y, "F3", CODE_SIZE, MARGIN, &clipped);
+                }
+            }
+        }
+    }
+
+    ops
+}
+
+/// Advance one line and draw `text` at `x`, unless it falls below the margin
+fn push_line(ops: &mut Vec<Operation>, y: &mut f64, font: &str, size: f64, x: f64, text: &str) {
+    *y -= size * LINE_SPACING;
+    if *y < MARGIN {
+        return;
+    }
+    ops.push(Operation::new("BT", vec![]));
+    ops.push(Operation::new("Tf", vec![font.into(), size.into()]));
+    ops.push(Operation::new("Td", vec![x.into(), (*y).into()]));
+    ops.push(Operation::new(
+        "Tj",
+        vec![Object::String(encode_win_ansi(text), StringFormat::Literal)],
+    ));
+    ops.push(Operation::new("ET", vec![]));
+}
+
+/// Greedy word wrap using an average glyph width estimate
+fn wrap(text: &str, max_width: f64, avg_char_width: f64) -> Vec<String> {
+    let max_chars = ((max_width / avg_char_width) as usize).max(1);
+    let mut lines = Vec::new();
+    let mut current = String::new();
+
+    for word in text.split_whitespace() {
+        if !current.is_empty() && current.chars().count() + 1 + word.chars().count() > max_chars {
+            lines.push(std::mem::take(&mut current));
+        }
+        if !current.is_empty() {
+            current.push(' ');
+        }
+        current.push_str(word);
+    }
+    if !current.is_empty() {
+        lines.push(current);
+    }
+
+    lines
+}
+
+/// Encode text for a WinAnsiEncoding font, replacing unsupported characters
+fn encode_win_ansi(text: &str) -> Vec<u8> {
+    text.chars()
+        .map(|c| match c {
+            '\u{2022}' => 0x95,
+            '\u{2013}' => 0x96,
+            '\u{2014}' => 0x97,
+            '\u{2018}' => 0x91,
+            '\u{2019}' => 0x92,
+            '\u{201C}' => 0x93,
+            '\u{201D}' => 0x94,
+            '\u{2026}' => 0x85,
+            '\u{20AC}' => 0x80,
+            c if (c as u32) < 0x80 || (0xA0..=0xFF).contains(&(c as u32)) => c as u8,
+            _ => b'?',
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_slides() {
+        let source = "---\ntitle: front matter\n---\n# Intro\n\nHello **world**\nagain\n\n---\n\n## Agenda\n- one\n  - nested\n1. first\n\n```\nlet x = 1;\n---\n```\n";
+        let slides = parse_slides(source);
+
+        assert_eq!(slides.len(), 3);
+        assert_eq!(
+            slides[1],
+            vec![
+                Block::Heading {
+                    level: 1,
+                    text: "Intro".to_string()
+                },
+                Block::Paragraph("Hello world again".to_string()),
+            ]
+        );
+        assert!(
+            matches!(&slides[2][2], Block::ListItem { depth: 1, text, .. } if text == "nested")
+        );
+        assert!(matches!(&slides[2][3], Block::ListItem { marker, .. } if marker == "1."));
+        // A separator inside a code fence doesn't start a new slide
+        assert_eq!(
+            slides[2][4],
+            Block::Code(vec!["let x = 1;".to_string(), "---".to_string()])
+        );
+    }
+
+    #[test]
+    fn test_inline_text() {
+        assert_eq!(
+            inline_text("See [the docs](https://x.y) and `code`"),
+            "See the docs and code"
+        );
+        assert_eq!(
+            inline_text("![logo](logo.png) __bold__ *em*"),
+            "logo bold em"
+        );
+        assert_eq!(inline_text("snake_case_name"), "snake_case_name");
+        assert_eq!(
+            inline_text("Step [1] of [the guide](https://x.y)"),
+            "Step [1] of the guide"
+        );
+        assert_eq!(inline_text("[![logo](logo.png)](https://x.y)"), "logo");
+    }
+
+    #[test]
+    fn test_import_renders_one_page_per_slide() {
+        let doc = MarkdownImporter
+            .import("# Welcome\n\nFirst slide\n---\n- a\n- b\n---\n```\ncode\n```")
+            .unwrap();
+        assert_eq!(doc.get_pages().len(), 3);
+
+        let mut bytes = Vec::new();
+        let mut doc = doc;
+        doc.save_to(&mut bytes).unwrap();
+        assert!(bytes.starts_with(b"%PDF-1.7"));
+
+        assert!(MarkdownImporter.import("\n---\n\n").is_err());
+    }
+}
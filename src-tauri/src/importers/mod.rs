@@ -0,0 +1,76 @@
+/*
+ * This file is part of StreamSlate.
+ * Copyright (C) 2025 StreamSlate Contributors
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+
+//! Document importers
+//!
+//! Importers convert non-PDF sources into an in-memory `lopdf::Document`.
+//! The generated PDF is opened like any other, so paging, annotations,
+//! presenter output, and WebSocket control all work unchanged.
+
+pub mod markdown;
+
+use crate::error::Result;
+use lopdf::Document;
+use std::path::Path;
+
+/// Converts a source file into a paged PDF document
+pub trait Importer: Send + Sync {
+    /// Human-readable name used in logs and errors
+    fn name(&self) -> &'static str;
+
+    /// Lowercase file extensions this importer handles
+    fn extensions(&self) -> &'static [&'static str];
+
+    /// Build a document from the source text
+    fn import(&self, source: &str) -> Result<Document>;
+}
+
+static IMPORTERS: &[&dyn Importer] = &[&markdown::MarkdownImporter];
+
+/// Find the importer that handles the given file, by extension
+pub fn importer_for(path: &Path) -> Option<&'static dyn Importer> {
+    let ext = path.extension()?.to_str()?.to_ascii_lowercase();
+    IMPORTERS
+        .iter()
+        .copied()
+        .find(|importer| importer.extensions().contains(&ext.as_str()))
+}
+
+/// All file extensions that can be imported
+pub fn supported_extensions() -> Vec<&'static str> {
+    IMPORTERS
+        .iter()
+        .flat_map(|importer| importer.extensions().iter().copied())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_importer_lookup() {
+        assert_eq!(
+            importer_for(Path::new("talk.MD")).map(|i| i.name()),
+            Some("Markdown")
+        );
+        assert!(importer_for(Path::new("talk.pdf")).is_none());
+        assert!(importer_for(Path::new("README")).is_none());
+        assert!(supported_extensions().contains(&"markdown"));
+    }
+}
@@ -0,0 +1,466 @@
+/*
+ * This file is part of StreamSlate.
+ * Copyright (C) 2025 StreamSlate Contributors
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+
+//! Exclusive input from a dedicated device
+//!
+//! The device is grabbed (evdev EVIOCGRAB on Linux, an IOHIDManager seize
+//! on macOS, which needs Input Monitoring permission) so its input no longer
+//! reaches the host cursor or the focused app, and its raw values are
+//! delivered on a background thread. The laser pointer and the clicker
+//! keymap decode them.
+
+use crate::error::{Result, StreamSlateError};
+use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+/// Which devices to list and grab
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DeviceKind {
+    /// Mice and other devices with relative X and Y axes
+    Pointer,
+    /// Keyboards that can send Page Down, which covers clickers
+    Keyboard,
+}
+
+impl DeviceKind {
+    fn noun(self) -> &'static str {
+        match self {
+            DeviceKind::Pointer => "pointing device",
+            DeviceKind::Keyboard => "keyboard device",
+        }
+    }
+}
+
+/// A device that can be grabbed
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct InputDevice {
+    /// Platform device ID (`event5` on Linux, the HID location ID on macOS)
+    pub id: String,
+    pub name: String,
+}
+
+/// One input value as the platform reports it
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RawInput {
+    /// evdev event type on Linux, HID usage page on macOS
+    pub page: u32,
+    /// evdev event code on Linux, HID usage on macOS
+    pub code: u32,
+    pub value: i32,
+}
+
+/// Devices of `kind` connected to this machine
+pub fn list_devices(kind: DeviceKind) -> Result<Vec<InputDevice>> {
+    platform::list_devices(kind)
+}
+
+/// A grabbed device delivering its input until dropped
+pub struct InputTap {
+    device: InputDevice,
+    stop: Arc<AtomicBool>,
+}
+
+impl InputTap {
+    /// Grab the device of `kind` with `id` and call `on_input` for each of its inputs
+    pub fn start(
+        kind: DeviceKind,
+        id: &str,
+        on_input: impl FnMut(RawInput) + Send + 'static,
+    ) -> Result<Self> {
+        let device = list_devices(kind)?
+            .into_iter()
+            .find(|device| device.id == id)
+            .ok_or_else(|| StreamSlateError::Other(format!("No {} {id}", kind.noun())))?;
+        let stop = Arc::new(AtomicBool::new(false));
+        platform::start(kind, &device.id, stop.clone(), Box::new(on_input))?;
+        Ok(Self { device, stop })
+    }
+
+    pub fn device(&self) -> &InputDevice {
+        &self.device
+    }
+}
+
+impl Drop for InputTap {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::Relaxed);
+    }
+}
+
+impl std::fmt::Debug for InputTap {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("InputTap")
+            .field("device", &self.device)
+            .finish()
+    }
+}
+
+type InputSink = Box<dyn FnMut(RawInput) + Send>;
+
+/// Linux input event types and codes
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+pub(crate) mod evdev {
+    use super::RawInput;
+
+    pub const EV_SYN: u16 = 0x00;
+    pub const EV_KEY: u16 = 0x01;
+    pub const EV_REL: u16 = 0x02;
+    pub const REL_X: u16 = 0x00;
+    pub const REL_Y: u16 = 0x01;
+    pub const BTN_LEFT: u16 = 0x110;
+    pub const KEY_PAGEDOWN: u16 = 109;
+
+    /// `struct input_event`: a `timeval`, then type, code and value
+    pub const EVENT_SIZE: usize = 2 * std::mem::size_of::<std::os::raw::c_long>() + 8;
+
+    /// Whether a sysfs capability bitmap (`capabilities/key`) has `code` set
+    ///
+    /// Bitmaps are printed as `long` words in hex, most significant first.
+    pub fn has_capability(bitmap: &str, code: u16) -> bool {
+        let word_bits = 8 * std::mem::size_of::<std::os::raw::c_long>();
+        let bit = code as usize;
+        bitmap
+            .split_whitespace()
+            .rev()
+            .nth(bit / word_bits)
+            .and_then(|word| u64::from_str_radix(word, 16).ok())
+            .is_some_and(|word| word & (1 << (bit % word_bits)) != 0)
+    }
+
+    /// Each whole `input_event` record in `data`
+    pub fn records(data: &[u8]) -> impl Iterator<Item = RawInput> + '_ {
+        data.chunks_exact(EVENT_SIZE).map(|record| {
+            let header = &record[EVENT_SIZE - 8..];
+            RawInput {
+                page: u16::from_ne_bytes([header[0], header[1]]).into(),
+                code: u16::from_ne_bytes([header[2], header[3]]).into(),
+                value: i32::from_ne_bytes([header[4], header[5], header[6], header[7]]),
+            }
+        })
+    }
+}
+
+/// HID usage pages and usages
+#[cfg_attr(not(target_os = "macos"), allow(dead_code))]
+pub(crate) mod hid {
+    pub const PAGE_GENERIC_DESKTOP: u32 = 0x01;
+    pub const PAGE_KEYBOARD: u32 = 0x07;
+    pub const PAGE_BUTTON: u32 = 0x09;
+    pub const USAGE_MOUSE: u32 = 0x02;
+    pub const USAGE_KEYBOARD: u32 = 0x06;
+    pub const USAGE_X: u32 = 0x30;
+    pub const USAGE_Y: u32 = 0x31;
+}
+
+#[cfg(target_os = "linux")]
+mod platform {
+    use super::evdev::{has_capability, records, EVENT_SIZE, KEY_PAGEDOWN, REL_X, REL_Y};
+    use super::{DeviceKind, InputDevice, InputSink};
+    use crate::error::{Result, StreamSlateError};
+    use std::io::Read;
+    use std::os::raw::{c_int, c_ulong};
+    use std::os::unix::fs::OpenOptionsExt;
+    use std::os::unix::io::AsRawFd;
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use std::sync::Arc;
+    use std::time::Duration;
+    use tracing::{debug, warn};
+
+    const O_NONBLOCK: i32 = 0o4000;
+    /// `_IOW('E', 0x90, int)`
+    const EVIOCGRAB: c_ulong = 0x4004_4590;
+    const POLL_INTERVAL: Duration = Duration::from_millis(4);
+
+    extern "C" {
+        fn ioctl(fd: c_int, request: c_ulong, ...) -> c_int;
+    }
+
+    pub fn list_devices(kind: DeviceKind) -> Result<Vec<InputDevice>> {
+        let mut devices = Vec::new();
+        for entry in std::fs::read_dir("/sys/class/input")?.flatten() {
+            let id = entry.file_name().to_string_lossy().to_string();
+            if !id.starts_with("event") {
+                continue;
+            }
+            let read = |name: &str| std::fs::read_to_string(entry.path().join("device").join(name));
+            let matches = match kind {
+                // Relative X and Y axes rule out keyboards and touchpads
+                DeviceKind::Pointer => read("capabilities/rel")
+                    .is_ok_and(|caps| has_capability(&caps, REL_X) && has_capability(&caps, REL_Y)),
+                DeviceKind::Keyboard => {
+                    read("capabilities/key").is_ok_and(|caps| has_capability(&caps, KEY_PAGEDOWN))
+                }
+            };
+            if matches {
+                let name = read("name")
+                    .map(|n| n.trim().to_string())
+                    .unwrap_or_default();
+                devices.push(InputDevice { id, name });
+            }
+        }
+        devices.sort_by(|a, b| a.id.cmp(&b.id));
+        Ok(devices)
+    }
+
+    pub fn start(
+        _kind: DeviceKind,
+        id: &str,
+        stop: Arc<AtomicBool>,
+        mut on_input: InputSink,
+    ) -> Result<()> {
+        let path = format!("/dev/input/{id}");
+        let mut file = std::fs::OpenOptions::new()
+            .read(true)
+            .custom_flags(O_NONBLOCK)
+            .open(&path)
+            .map_err(|e| {
+                StreamSlateError::Other(format!(
+                    "Cannot open {path} ({e}); the user needs read access to input devices"
+                ))
+            })?;
+        // SAFETY: the fd is valid for the lifetime of `file`
+        if unsafe { ioctl(file.as_raw_fd(), EVIOCGRAB, 1 as c_int) } != 0 {
+            return Err(StreamSlateError::Other(format!(
+                "Cannot grab {path}: {}",
+                std::io::Error::last_os_error()
+            )));
+        }
+
+        std::thread::Builder::new()
+            .name("input-tap".to_string())
+            .spawn(move || {
+                let mut buffer = vec![0u8; EVENT_SIZE * 64];
+                while !stop.load(Ordering::Relaxed) {
+                    match file.read(&mut buffer) {
+                        Ok(0) => break,
+                        Ok(read) => records(&buffer[..read]).for_each(&mut on_input),
+                        Err(e) if e.kind() == std::io::ErrorKind::WouldBlock => {
+                            std::thread::sleep(POLL_INTERVAL);
+                        }
+                        Err(e) => {
+                            warn!(device = %path, error = %e, "Input device read failed");
+                            break;
+                        }
+                    }
+                }
+                // Closing the file releases the grab
+                debug!(device = %path, "Input tap stopped");
+            })?;
+        Ok(())
+    }
+}
+
+#[cfg(target_os = "macos")]
+mod platform {
+    use super::hid::{USAGE_KEYBOARD, USAGE_MOUSE};
+    use super::{DeviceKind, InputDevice, InputSink, RawInput};
+    use crate::error::{Result, StreamSlateError};
+    use std::ffi::CStr;
+    use std::os::raw::{c_char, c_int, c_long, c_void};
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use std::sync::{mpsc, Arc};
+    use std::time::Duration;
+
+    const START_TIMEOUT: Duration = Duration::from_secs(5);
+
+    extern "C" {
+        fn input_list_devices(
+            usage: u32,
+            callback: extern "C" fn(*mut c_void, u32, *const c_char),
+            context: *mut c_void,
+        );
+        fn input_tap_run(
+            usage: u32,
+            location_id: u32,
+            callback: extern "C" fn(*mut c_void, u32, u32, c_long),
+            context: *mut c_void,
+            stop: *const AtomicBool,
+        ) -> c_int;
+    }
+
+    fn usage(kind: DeviceKind) -> u32 {
+        match kind {
+            DeviceKind::Pointer => USAGE_MOUSE,
+            DeviceKind::Keyboard => USAGE_KEYBOARD,
+        }
+    }
+
+    extern "C" fn on_device(context: *mut c_void, location_id: u32, name: *const c_char) {
+        // SAFETY: `context` is the Vec passed by `list_devices`, and `name`
+        // is a NUL-terminated string valid for the duration of the call
+        let devices = unsafe { &mut *(context as *mut Vec<InputDevice>) };
+        let name = unsafe { CStr::from_ptr(name) }
+            .to_string_lossy()
+            .to_string();
+        devices.push(InputDevice {
+            id: format!("{location_id:08x}"),
+            name,
+        });
+    }
+
+    pub fn list_devices(kind: DeviceKind) -> Result<Vec<InputDevice>> {
+        let mut devices: Vec<InputDevice> = Vec::new();
+        // SAFETY: the callback only runs synchronously inside this call
+        unsafe {
+            input_list_devices(
+                usage(kind),
+                on_device,
+                &mut devices as *mut _ as *mut c_void,
+            );
+        }
+        Ok(devices)
+    }
+
+    struct Sink {
+        on_input: InputSink,
+        started: Option<mpsc::Sender<std::result::Result<(), c_int>>>,
+    }
+
+    extern "C" fn on_value(context: *mut c_void, page: u32, code: u32, value: c_long) {
+        // SAFETY: `context` is the Sink owned by the tap thread, which only
+        // reclaims it after `input_tap_run` has returned
+        let sink = unsafe { &mut *(context as *mut Sink) };
+        // Page 0 signals that the device is seized
+        if page == 0 {
+            if let Some(started) = sink.started.take() {
+                let _ = started.send(Ok(()));
+            }
+            return;
+        }
+        (sink.on_input)(RawInput {
+            page,
+            code,
+            value: value as i32,
+        });
+    }
+
+    pub fn start(
+        kind: DeviceKind,
+        id: &str,
+        stop: Arc<AtomicBool>,
+        on_input: InputSink,
+    ) -> Result<()> {
+        let location_id = u32::from_str_radix(id, 16)
+            .map_err(|_| StreamSlateError::Other(format!("Invalid device ID {id}")))?;
+        let usage = usage(kind);
+        let stopped = stop.clone();
+        let (tx, rx) = mpsc::channel();
+        std::thread::Builder::new()
+            .name("input-tap".to_string())
+            .spawn(move || {
+                let sink = Box::into_raw(Box::new(Sink {
+                    on_input,
+                    started: Some(tx),
+                }));
+                // SAFETY: `sink` stays valid until reclaimed below, and the
+                // bridge reads `stopped` (an `atomic_bool` there) while the
+                // Arc keeps it alive
+                let code = unsafe {
+                    input_tap_run(
+                        usage,
+                        location_id,
+                        on_value,
+                        sink as *mut c_void,
+                        Arc::as_ptr(&stopped),
+                    )
+                };
+                let sink = unsafe { Box::from_raw(sink) };
+                if let Some(started) = sink.started {
+                    let _ = started.send(Err(code));
+                }
+            })?;
+
+        match rx.recv_timeout(START_TIMEOUT) {
+            Ok(Ok(())) => Ok(()),
+            Ok(Err(code)) => Err(StreamSlateError::Other(format!(
+                "Cannot grab {} {id} (error {code:#x}); \
+                 StreamSlate needs Input Monitoring permission",
+                kind.noun()
+            ))),
+            Err(_) => {
+                stop.store(true, Ordering::Relaxed);
+                Err(StreamSlateError::Other(format!(
+                    "Timed out grabbing the {}",
+                    kind.noun()
+                )))
+            }
+        }
+    }
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos")))]
+mod platform {
+    use super::{DeviceKind, InputDevice, InputSink};
+    use crate::error::{Result, StreamSlateError};
+    use std::sync::atomic::AtomicBool;
+    use std::sync::Arc;
+
+    pub fn list_devices(_kind: DeviceKind) -> Result<Vec<InputDevice>> {
+        Ok(Vec::new())
+    }
+
+    pub fn start(
+        kind: DeviceKind,
+        _id: &str,
+        _stop: Arc<AtomicBool>,
+        _on_input: InputSink,
+    ) -> Result<()> {
+        Err(StreamSlateError::Other(format!(
+            "Grabbing a {} is not supported on this platform",
+            kind.noun()
+        )))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::evdev::*;
+    use super::RawInput;
+
+    #[test]
+    fn test_has_capability() {
+        // BTN_LEFT (0x110) sits in the fifth 64-bit word
+        let mouse = "70000 0 0 0 0";
+        assert!(has_capability(mouse, BTN_LEFT));
+        assert!(!has_capability(mouse, 0x111 + 64));
+        assert!(!has_capability("", BTN_LEFT));
+        // Relative X and Y
+        assert!(has_capability("103", REL_X) && has_capability("103", REL_Y));
+    }
+
+    #[test]
+    fn test_records() {
+        let mut data = vec![0u8; EVENT_SIZE - 8];
+        data.extend_from_slice(&EV_REL.to_ne_bytes());
+        data.extend_from_slice(&REL_Y.to_ne_bytes());
+        data.extend_from_slice(&(-2i32).to_ne_bytes());
+        // A partial record is left for the next read
+        data.extend_from_slice(&[0; 4]);
+
+        let inputs: Vec<_> = records(&data).collect();
+        assert_eq!(
+            inputs,
+            [RawInput {
+                page: EV_REL.into(),
+                code: REL_Y.into(),
+                value: -2
+            }]
+        );
+    }
+}
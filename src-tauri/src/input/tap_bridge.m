@@ -0,0 +1,107 @@
+/*
+ * This file is part of StreamSlate.
+ * Copyright (C) 2025 StreamSlate Contributors
+ *
+ * IOKit bridge seizing a dedicated HID device (the laser pointer's mouse or
+ * a presentation clicker), so its input is delivered to Rust instead of
+ * reaching the host cursor or whichever app is focused.
+ */
+
+#import <Foundation/Foundation.h>
+#import <IOKit/hid/IOHIDManager.h>
+#include <stdatomic.h>
+#include <stdbool.h>
+
+/// Receives each matching device's HID location ID and product name.
+typedef void (*input_device_callback)(void *context, uint32_t location_id, const char *name);
+
+/// Receives each input value as usage page, usage and value; page 0 means started.
+typedef void (*input_value_callback)(void *context, uint32_t page, uint32_t usage, long value);
+
+/// Devices with the Generic Desktop `usage` (mouse, keyboard, ...).
+static IOHIDManagerRef createManager(uint32_t usage) {
+    IOHIDManagerRef manager = IOHIDManagerCreate(kCFAllocatorDefault, kIOHIDOptionsTypeNone);
+    NSDictionary *matching = @{
+        @kIOHIDDeviceUsagePageKey: @(kHIDPage_GenericDesktop),
+        @kIOHIDDeviceUsageKey: @(usage),
+    };
+    IOHIDManagerSetDeviceMatching(manager, (__bridge CFDictionaryRef)matching);
+    return manager;
+}
+
+static NSNumber *locationOf(IOHIDDeviceRef device) {
+    id location = (__bridge id)IOHIDDeviceGetProperty(device, CFSTR(kIOHIDLocationIDKey));
+    return [location isKindOfClass:[NSNumber class]] ? location : nil;
+}
+
+void input_list_devices(uint32_t usage, input_device_callback callback, void *context) {
+    @autoreleasepool {
+        IOHIDManagerRef manager = createManager(usage);
+        NSSet *devices = CFBridgingRelease(IOHIDManagerCopyDevices(manager));
+        for (id object in devices) {
+            IOHIDDeviceRef device = (__bridge IOHIDDeviceRef)object;
+            NSNumber *location = locationOf(device);
+            if (!location) continue;
+            id name = (__bridge id)IOHIDDeviceGetProperty(device, CFSTR(kIOHIDProductKey));
+            if (![name isKindOfClass:[NSString class]]) name = @"";
+            callback(context, location.unsignedIntValue, [name UTF8String]);
+        }
+        CFRelease(manager);
+    }
+}
+
+typedef struct {
+    input_value_callback callback;
+    void *context;
+} TapTarget;
+
+static void onInputValue(void *context, IOReturn result, void *sender, IOHIDValueRef value) {
+    TapTarget *target = context;
+    IOHIDElementRef element = IOHIDValueGetElement(value);
+    target->callback(target->context, IOHIDElementGetUsagePage(element),
+                     IOHIDElementGetUsage(element), (long)IOHIDValueGetIntegerValue(value));
+}
+
+/// Seize the device with `usage` at `location_id` and deliver its input on
+/// the calling thread until `*stop` is set. Returns 0 once stopped, or an
+/// error code without reporting "started" if the device can't be seized.
+int input_tap_run(uint32_t usage, uint32_t location_id, input_value_callback callback,
+                  void *context, const atomic_bool *stop) {
+    IOHIDDeviceRef device = NULL;
+
+    @autoreleasepool {
+        IOHIDManagerRef manager = createManager(usage);
+        NSSet *devices = CFBridgingRelease(IOHIDManagerCopyDevices(manager));
+        for (id object in devices) {
+            IOHIDDeviceRef candidate = (__bridge IOHIDDeviceRef)object;
+            if (locationOf(candidate).unsignedIntValue == location_id) {
+                device = (IOHIDDeviceRef)CFRetain(candidate);
+                break;
+            }
+        }
+        CFRelease(manager);
+    }
+    if (!device) return kIOReturnNotFound;
+
+    IOReturn status = IOHIDDeviceOpen(device, kIOHIDOptionsTypeSeizeDevice);
+    if (status != kIOReturnSuccess) {
+        CFRelease(device);
+        return status;
+    }
+
+    TapTarget target = { callback, context };
+    IOHIDDeviceRegisterInputValueCallback(device, onInputValue, &target);
+    IOHIDDeviceScheduleWithRunLoop(device, CFRunLoopGetCurrent(), kCFRunLoopDefaultMode);
+    callback(context, 0, 0, 0);
+
+    // Stopping takes effect within 250 ms
+    while (!atomic_load(stop)) {
+        CFRunLoopRunInMode(kCFRunLoopDefaultMode, 0.25, false);
+    }
+
+    IOHIDDeviceUnscheduleFromRunLoop(device, CFRunLoopGetCurrent(), kCFRunLoopDefaultMode);
+    IOHIDDeviceRegisterInputValueCallback(device, NULL, NULL);
+    IOHIDDeviceClose(device, kIOHIDOptionsTypeSeizeDevice);
+    CFRelease(device);
+    return 0;
+}
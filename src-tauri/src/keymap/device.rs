@@ -0,0 +1,117 @@
+/*
+ * This file is part of StreamSlate.
+ * Copyright (C) 2025 StreamSlate Contributors
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+
+//! Clicker input
+//!
+//! Clickers show up as keyboards. The chosen one is grabbed through the
+//! shared input tap so its keys work while the webview isn't focused and
+//! don't also reach whichever app is.
+
+use super::ClickerKey;
+use crate::error::Result;
+use crate::input::evdev::EV_KEY;
+use crate::input::hid::PAGE_KEYBOARD;
+use crate::input::{self, DeviceKind, InputDevice, InputTap, RawInput};
+
+/// A keyboard device that can act as a clicker
+pub type ClickerDevice = InputDevice;
+
+/// Keyboard devices connected to this machine
+pub fn list_devices() -> Result<Vec<ClickerDevice>> {
+    input::list_devices(DeviceKind::Keyboard)
+}
+
+/// A grabbed clicker delivering its keys until dropped
+pub struct ClickerTap(InputTap);
+
+impl ClickerTap {
+    /// Grab the device with `id` and call `on_key` for each clicker key press or release
+    pub fn start(
+        id: &str,
+        mut on_key: impl FnMut(ClickerKey, bool) + Send + 'static,
+    ) -> Result<Self> {
+        InputTap::start(DeviceKind::Keyboard, id, move |input| {
+            if let Some((key, pressed)) = decode(input) {
+                on_key(key, pressed);
+            }
+        })
+        .map(Self)
+    }
+
+    pub fn device(&self) -> &ClickerDevice {
+        self.0.device()
+    }
+}
+
+/// A clicker key and whether it was pressed or released
+fn decode(input: RawInput) -> Option<(ClickerKey, bool)> {
+    let RawInput { page, code, value } = input;
+    if cfg!(target_os = "macos") {
+        return match page {
+            PAGE_KEYBOARD => Some((hid_key(code)?, value != 0)),
+            _ => None,
+        };
+    }
+    // Value 2 is auto-repeat
+    if page != u32::from(EV_KEY) || value == 2 {
+        return None;
+    }
+    Some((evdev_key(u16::try_from(code).ok()?)?, value == 1))
+}
+
+/// Linux key codes
+fn evdev_key(code: u16) -> Option<ClickerKey> {
+    use ClickerKey::*;
+    Some(match code {
+        1 => Escape,
+        15 => Tab,
+        28 => Enter,
+        48 => B,
+        52 => Period,
+        57 => Space,
+        63 => F5,
+        103 => Up,
+        104 => PageUp,
+        105 => Left,
+        106 => Right,
+        108 => Down,
+        109 => PageDown,
+        _ => return None,
+    })
+}
+
+/// HID keyboard page usages
+fn hid_key(usage: u32) -> Option<ClickerKey> {
+    use ClickerKey::*;
+    Some(match usage {
+        0x05 => B,
+        0x28 => Enter,
+        0x29 => Escape,
+        0x2B => Tab,
+        0x2C => Space,
+        0x37 => Period,
+        0x3E => F5,
+        0x4B => PageUp,
+        0x4E => PageDown,
+        0x4F => Right,
+        0x50 => Left,
+        0x51 => Down,
+        0x52 => Up,
+        _ => return None,
+    })
+}
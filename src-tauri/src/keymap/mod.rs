@@ -0,0 +1,413 @@
+/*
+ * This file is part of StreamSlate.
+ * Copyright (C) 2025 StreamSlate Contributors
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+
+//! Presentation clicker key mapping
+//!
+//! Clickers send a handful of keyboard keys. Besides plain presses, holding a
+//! key or pressing it twice can be mapped to its own action, so a two-button
+//! clicker can also black out the outputs or toggle presenter mode.
+
+pub mod device;
+
+use crate::error::{Result, StreamSlateError};
+use crate::settings::Settings;
+use device::{ClickerDevice, ClickerTap};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// Keymap file in the app data directory
+pub const KEYMAP_FILE: &str = "keymap.json";
+
+/// How often held keys and pending presses are checked
+const GESTURE_TICK: Duration = Duration::from_millis(20);
+
+/// A key sent by presentation clickers
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ClickerKey {
+    PageUp,
+    PageDown,
+    Left,
+    Right,
+    Up,
+    Down,
+    Space,
+    Enter,
+    Tab,
+    Escape,
+    F5,
+    /// "Black screen" key on many clickers
+    B,
+    Period,
+}
+
+/// How a key was pressed
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Gesture {
+    Press,
+    /// Held past the long-press threshold; fires while still held
+    LongPress,
+    /// Pressed twice within the double-press window
+    DoublePress,
+}
+
+/// What a gesture does
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum KeymapAction {
+    NextPage,
+    PreviousPage,
+    NextSection,
+    PreviousSection,
+    ToggleBlackout,
+    TogglePresenter,
+}
+
+/// One gesture of one key
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct KeyBinding {
+    pub key: ClickerKey,
+    pub gesture: Gesture,
+    pub action: KeymapAction,
+}
+
+/// Clicker bindings and gesture timing
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct Keymap {
+    pub bindings: Vec<KeyBinding>,
+    /// Hold time before a press counts as a long press
+    pub long_press_ms: u32,
+    /// Window for the second press of a double press
+    pub double_press_ms: u32,
+}
+
+impl Default for Keymap {
+    fn default() -> Self {
+        use ClickerKey::*;
+        use Gesture::*;
+        use KeymapAction::*;
+        let bind = |key, gesture, action| KeyBinding {
+            key,
+            gesture,
+            action,
+        };
+        Self {
+            bindings: vec![
+                bind(PageDown, Press, NextPage),
+                bind(PageUp, Press, PreviousPage),
+                bind(Right, Press, NextPage),
+                bind(Left, Press, PreviousPage),
+                bind(PageDown, LongPress, ToggleBlackout),
+                bind(PageUp, LongPress, TogglePresenter),
+                bind(B, Press, ToggleBlackout),
+                bind(Period, Press, ToggleBlackout),
+                bind(F5, Press, TogglePresenter),
+                bind(Escape, Press, TogglePresenter),
+            ],
+            long_press_ms: 600,
+            double_press_ms: 300,
+        }
+    }
+}
+
+impl Keymap {
+    pub fn validate(&self) -> Result<()> {
+        if !(200..=3000).contains(&self.long_press_ms) {
+            return Err(StreamSlateError::Other(format!(
+                "Long press must be 200-3000 ms, got {}",
+                self.long_press_ms
+            )));
+        }
+        if !(100..=1000).contains(&self.double_press_ms) {
+            return Err(StreamSlateError::Other(format!(
+                "Double press window must be 100-1000 ms, got {}",
+                self.double_press_ms
+            )));
+        }
+        for (i, binding) in self.bindings.iter().enumerate() {
+            if self.bindings[..i]
+                .iter()
+                .any(|b| b.key == binding.key && b.gesture == binding.gesture)
+            {
+                return Err(StreamSlateError::Other(format!(
+                    "{:?} {:?} is bound more than once",
+                    binding.key, binding.gesture
+                )));
+            }
+        }
+        Ok(())
+    }
+
+    fn action(&self, key: ClickerKey, gesture: Gesture) -> Option<KeymapAction> {
+        self.bindings
+            .iter()
+            .find(|b| b.key == key && b.gesture == gesture)
+            .map(|b| b.action)
+    }
+
+    fn is_bound(&self, key: ClickerKey, gesture: Gesture) -> bool {
+        self.action(key, gesture).is_some()
+    }
+}
+
+impl Settings for Keymap {
+    fn check(&self) -> Result<()> {
+        self.validate()
+    }
+}
+
+/// Per-key gesture progress
+#[derive(Debug, Default)]
+struct KeyState {
+    /// When the key went down, while it is held
+    down_at: Option<Instant>,
+    /// The long press already fired for the current hold
+    long_fired: bool,
+    /// A press waiting to see whether a second one follows
+    pending_since: Option<Instant>,
+}
+
+/// Turns key presses and releases into mapped actions
+///
+/// A plain press fires on key down unless the key also has a long or double
+/// press binding; it then waits until those can be ruled out.
+#[derive(Debug)]
+pub struct GestureDetector {
+    keymap: Keymap,
+    keys: HashMap<ClickerKey, KeyState>,
+}
+
+impl GestureDetector {
+    pub fn new(keymap: Keymap) -> Self {
+        Self {
+            keymap,
+            keys: HashMap::new(),
+        }
+    }
+
+    pub fn set_keymap(&mut self, keymap: Keymap) {
+        self.keymap = keymap;
+        self.keys.clear();
+    }
+
+    /// Feed a key press or release
+    pub fn key(&mut self, key: ClickerKey, pressed: bool, now: Instant) -> Option<KeymapAction> {
+        let keymap = &self.keymap;
+        let state = self.keys.entry(key).or_default();
+        let delayed =
+            keymap.is_bound(key, Gesture::LongPress) || keymap.is_bound(key, Gesture::DoublePress);
+
+        if pressed {
+            if state.down_at.is_some() {
+                // Auto-repeat
+                return None;
+            }
+            state.down_at = Some(now);
+            state.long_fired = false;
+            return if delayed {
+                None
+            } else {
+                keymap.action(key, Gesture::Press)
+            };
+        }
+
+        let was_down = state.down_at.take().is_some();
+        if !was_down || !delayed || state.long_fired {
+            return None;
+        }
+        if !keymap.is_bound(key, Gesture::DoublePress) {
+            return keymap.action(key, Gesture::Press);
+        }
+        if state.pending_since.take().is_some() {
+            return keymap.action(key, Gesture::DoublePress);
+        }
+        state.pending_since = Some(now);
+        None
+    }
+
+    /// Fire long presses and single presses whose timers have run out
+    pub fn poll(&mut self, now: Instant) -> Vec<KeymapAction> {
+        let long = Duration::from_millis(u64::from(self.keymap.long_press_ms));
+        let double = Duration::from_millis(u64::from(self.keymap.double_press_ms));
+        let mut actions = Vec::new();
+        for (&key, state) in &mut self.keys {
+            if let Some(down_at) = state.down_at {
+                if !state.long_fired && now.saturating_duration_since(down_at) >= long {
+                    if let Some(action) = self.keymap.action(key, Gesture::LongPress) {
+                        state.long_fired = true;
+                        state.pending_since = None;
+                        actions.push(action);
+                    }
+                }
+            }
+            if let Some(since) = state.pending_since {
+                if state.down_at.is_none() && now.saturating_duration_since(since) >= double {
+                    state.pending_since = None;
+                    actions.extend(self.keymap.action(key, Gesture::Press));
+                }
+            }
+        }
+        actions
+    }
+}
+
+/// A clicker mapped to actions until stopped or dropped
+pub struct ClickerSession {
+    device: ClickerDevice,
+    detector: Arc<Mutex<GestureDetector>>,
+    stop: Arc<AtomicBool>,
+    _tap: ClickerTap,
+}
+
+impl ClickerSession {
+    /// Grab the clicker with `id`, calling `on_action` for each mapped gesture
+    pub fn start(
+        id: &str,
+        keymap: Keymap,
+        on_action: impl Fn(KeymapAction) + Send + Sync + 'static,
+    ) -> Result<Self> {
+        let on_action = Arc::new(on_action);
+        let detector = Arc::new(Mutex::new(GestureDetector::new(keymap)));
+
+        let keys = detector.clone();
+        let on_key = on_action.clone();
+        let tap = ClickerTap::start(id, move |key, pressed| {
+            let action = match keys.lock() {
+                Ok(mut detector) => detector.key(key, pressed, Instant::now()),
+                Err(_) => return,
+            };
+            if let Some(action) = action {
+                on_key(action);
+            }
+        })?;
+
+        let stop = Arc::new(AtomicBool::new(false));
+        let stopped = stop.clone();
+        let timers = detector.clone();
+        std::thread::Builder::new()
+            .name("clicker-gestures".to_string())
+            .spawn(move || {
+                while !stopped.load(Ordering::Relaxed) {
+                    std::thread::sleep(GESTURE_TICK);
+                    let actions = match timers.lock() {
+                        Ok(mut detector) => detector.poll(Instant::now()),
+                        Err(_) => break,
+                    };
+                    for action in actions {
+                        on_action(action);
+                    }
+                }
+            })?;
+
+        Ok(Self {
+            device: tap.device().clone(),
+            detector,
+            stop,
+            _tap: tap,
+        })
+    }
+
+    pub fn device(&self) -> &ClickerDevice {
+        &self.device
+    }
+
+    /// Apply a new keymap without releasing the clicker
+    pub fn set_keymap(&self, keymap: Keymap) {
+        if let Ok(mut detector) = self.detector.lock() {
+            detector.set_keymap(keymap);
+        }
+    }
+}
+
+impl Drop for ClickerSession {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::Relaxed);
+    }
+}
+
+impl std::fmt::Debug for ClickerSession {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ClickerSession")
+            .field("device", &self.device)
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ClickerKey::*;
+    use KeymapAction::*;
+
+    #[test]
+    fn test_long_press_replaces_press() {
+        let start = Instant::now();
+        let at = |ms| start + Duration::from_millis(ms);
+        let mut detector = GestureDetector::new(Keymap::default());
+
+        // Right has only a plain press, which fires immediately
+        assert_eq!(detector.key(Right, true, at(0)), Some(NextPage));
+        assert_eq!(detector.key(Right, true, at(30)), None);
+        assert_eq!(detector.key(Right, false, at(50)), None);
+
+        // A short tap on PageDown fires on release
+        assert_eq!(detector.key(PageDown, true, at(100)), None);
+        assert_eq!(detector.key(PageDown, false, at(200)), Some(NextPage));
+
+        // Holding it blacks out while held, and the release does nothing
+        assert_eq!(detector.key(PageDown, true, at(1000)), None);
+        assert!(detector.poll(at(1500)).is_empty());
+        assert_eq!(detector.poll(at(1600)), vec![ToggleBlackout]);
+        assert!(detector.poll(at(2000)).is_empty());
+        assert_eq!(detector.key(PageDown, false, at(2100)), None);
+    }
+
+    #[test]
+    fn test_double_press() {
+        let start = Instant::now();
+        let at = |ms| start + Duration::from_millis(ms);
+        let mut keymap = Keymap::default();
+        keymap.bindings.push(KeyBinding {
+            key: PageUp,
+            gesture: Gesture::DoublePress,
+            action: ToggleBlackout,
+        });
+        assert!(keymap.validate().is_ok());
+        let mut detector = GestureDetector::new(keymap.clone());
+
+        detector.key(PageUp, true, at(0));
+        assert_eq!(detector.key(PageUp, false, at(80)), None);
+        detector.key(PageUp, true, at(150));
+        assert_eq!(detector.key(PageUp, false, at(220)), Some(ToggleBlackout));
+        assert!(detector.poll(at(1000)).is_empty());
+
+        // A single tap waits out the window
+        detector.key(PageUp, true, at(2000));
+        detector.key(PageUp, false, at(2050));
+        assert!(detector.poll(at(2200)).is_empty());
+        assert_eq!(detector.poll(at(2400)), vec![PreviousPage]);
+
+        keymap.bindings.push(keymap.bindings[0]);
+        assert!(keymap.validate().is_err());
+    }
+}
@@ -16,13 +16,38 @@
  * along with this program.  If not, see <https://www.gnu.org/licenses/>.
  */
 
+pub mod ai;
+pub mod analytics;
+pub mod assets;
+pub mod checkpoint;
 mod commands;
+pub mod deck;
 pub mod error;
+pub mod gamepad;
+pub mod http;
+pub mod importers;
+pub mod input;
+pub mod keymap;
+pub mod lms;
+pub mod media;
+pub mod obs;
+pub mod osc;
+pub mod pdf;
+pub mod progress;
+pub mod protocol;
+pub mod settings;
 pub mod state;
 pub mod websocket;
 
-// Native screen capture (macOS ScreenCaptureKit)
-#[cfg(target_os = "macos")]
+// Outgoing WebSocket client for remote client mode, which mobile builds always use
+#[cfg(any(mobile, feature = "client-sdk"))]
+pub mod client;
+
+// Typed client API for third-party Rust tools
+#[cfg(feature = "client-sdk")]
+pub mod sdk;
+
+// Frame capture (macOS ScreenCaptureKit, plus a mock backend for development)
 pub mod capture;
 
 // NDI output support (optional, requires NDI SDK)
@@ -35,10 +60,22 @@ pub mod syphon;
 
 use commands::*;
 use state::AppState;
+use std::net::{IpAddr, Ipv4Addr};
 use std::sync::Arc;
 use tauri::Manager;
 use tracing::{info, warn};
 
+/// Environment variable naming a desktop instance to control (remote client mode)
+const REMOTE_URL_ENV: &str = "STREAMSLATE_REMOTE_URL";
+
+/// Whether this instance acts as a pure remote client instead of a server
+///
+/// Mobile builds always do; desktop builds with the `client-sdk` feature can
+/// opt in for testing by setting `STREAMSLATE_REMOTE_URL`.
+pub(crate) fn is_remote_client_mode() -> bool {
+    cfg!(mobile) || (cfg!(feature = "client-sdk") && std::env::var_os(REMOTE_URL_ENV).is_some())
+}
+
 // Learn more about Tauri commands at https://tauri.app/develop/calling-rust/
 #[tauri::command]
 fn greet(name: &str) -> String {
@@ -59,10 +96,29 @@ pub fn run() {
             greet,
             // PDF commands
             open_pdf,
+            cancel_pdf_open,
             close_pdf,
             get_pdf_page_info,
+            get_all_page_info,
             get_pdf_page_count,
             is_pdf_open,
+            list_pdf_attachments,
+            extract_pdf_attachment,
+            verify_pdf_signatures,
+            get_page_words,
+            compare_pdfs,
+            optimize_pdf,
+            set_page_crop,
+            set_smart_invert,
+            // OCR commands
+            is_ocr_available,
+            ocr_page,
+            // Image deck commands
+            open_image_deck,
+            get_image_deck_page,
+            // Import commands
+            import_document,
+            get_supported_import_formats,
             // Presenter commands
             open_presenter_mode,
             close_presenter_mode,
@@ -75,7 +131,34 @@ pub fn run() {
             load_annotations,
             get_page_annotations,
             clear_annotations,
+            undo_annotation,
+            redo_annotation,
+            erase_at,
+            get_ink_config,
+            set_ink_config,
+            get_ephemeral_annotations,
+            set_ephemeral_annotations,
+            lock_page_annotations,
+            unlock_page_annotations,
+            get_sidecar_config,
+            set_sidecar_location,
+            set_document_sidecar_location,
+            set_sidecar_encryption,
+            list_annotation_versions,
+            restore_annotation_version,
+            list_annotation_backups,
+            restore_annotation_backup,
+            get_backup_config,
+            set_backup_config,
+            replay_annotations,
+            stop_annotation_replay,
             has_annotations,
+            export_annotations_xfdf,
+            get_annotation_report,
+            export_annotation_report,
+            import_annotations,
+            copy_annotations,
+            transfer_annotations,
             // Capture & NDI commands
             start_ndi_sender,
             stop_ndi_sender,
@@ -86,8 +169,122 @@ pub fn run() {
             is_syphon_available,
             get_output_capabilities,
             get_capture_status,
+            get_power_status,
+            set_auto_throttle,
+            set_capture_color_correction,
+            set_output_viewport,
+            get_output_viewport,
+            set_blackout,
+            get_blackout,
+            send_test_pattern,
             start_syphon_output,
-            stop_syphon_output
+            stop_syphon_output,
+            start_capture_preview,
+            stop_capture_preview,
+            start_remote_view,
+            stop_remote_view,
+            // Remote client commands
+            connect_remote,
+            disconnect_remote,
+            connect_obs,
+            disconnect_obs,
+            get_obs_status,
+            get_obs_bindings,
+            set_obs_bindings,
+            get_osc_config,
+            set_osc_config,
+            create_section,
+            list_sections,
+            delete_section,
+            create_layer,
+            list_layers,
+            rename_layer,
+            set_layer_visible,
+            delete_layer,
+            get_all_notes,
+            get_page_notes,
+            set_page_notes,
+            add_stamp_image,
+            get_stamp_image,
+            search_annotations,
+            list_style_presets,
+            create_style_preset,
+            update_style_preset,
+            delete_style_preset,
+            set_active_style_preset,
+            get_analytics_config,
+            set_analytics_config,
+            end_page_timing_session,
+            end_show,
+            is_summarizer_available,
+            get_summarizer_config,
+            set_summarizer_config,
+            summarize_page,
+            get_page_summaries,
+            export_lms_package,
+            link_documents,
+            unlink_documents,
+            get_linked_pages,
+            save_reading_progress,
+            get_reading_progress_enabled,
+            set_reading_progress_enabled,
+            get_agenda,
+            set_agenda_strip,
+            print_pdf,
+            list_printers,
+            list_linked_assets,
+            preload_assets,
+            get_cached_asset,
+            list_media,
+            play_media,
+            pause_media,
+            seek_media,
+            get_prerender_config,
+            set_prerender_config,
+            list_pointer_devices,
+            start_laser_pointer,
+            stop_laser_pointer,
+            get_laser_pointer_status,
+            // Gamepad commands
+            list_gamepads,
+            start_gamepad,
+            stop_gamepad,
+            get_gamepad_status,
+            set_gamepad_mapping,
+            // Clicker commands
+            list_clickers,
+            start_clicker,
+            stop_clicker,
+            get_clicker_status,
+            set_clicker_keymap,
+            // Checkpoint commands
+            start_checkpoint,
+            close_checkpoint,
+            get_checkpoint_session,
+            list_checkpoint_sessions,
+            // QR code overlay commands
+            generate_qr,
+            clear_qr,
+            send_remote_command,
+            get_remote_status,
+            // Control server commands
+            get_websocket_config,
+            get_websocket_status,
+            set_websocket_config,
+            start_websocket_server,
+            stop_websocket_server,
+            restart_websocket_server,
+            list_ws_clients,
+            disconnect_ws_client,
+            set_ws_client_role,
+            get_command_history,
+            get_ws_access_config,
+            set_ws_access_config,
+            publish_page_image,
+            get_tls_config,
+            set_tls_config,
+            generate_tls_certificate,
+            get_tls_certificate
         ])
         .setup(|app| {
             // Initialize structured logging with tracing
@@ -100,6 +297,10 @@ pub fn run() {
 
             info!("StreamSlate starting...");
 
+            // Watch for sleep/wake so capture can be rebuilt after wake
+            #[cfg(target_os = "macos")]
+            capture::power::start_monitor();
+
             // Get the managed state and clone it for the WebSocket server
             // Clone is cheap - only clones Arc pointers, not underlying data
             let state: tauri::State<'_, AppState> = app.state::<AppState>();
@@ -108,25 +309,94 @@ pub fn run() {
             // Get app handle for emitting events from WebSocket handlers
             let app_handle = app.handle().clone();
 
-            // Start WebSocket server on port 11451 using Tauri's runtime.
-            // Using raw tokio::spawn here can panic during startup if no Tokio
-            // reactor is active yet in the setup context.
+            // Keep annotation sidecars in the app data directory, backed up
+            // on a schedule
+            init_sidecar_store(&app_handle);
+            spawn_annotation_backups(&app_handle);
+
+            // Warn about (and optionally throttle for) heat and low battery
+            spawn_power_monitor(app_handle.clone(), state.inner().clone());
+
+            // Drive OBS source bindings from page changes
+            load_reading_progress(&app_handle, &state);
+            spawn_obs_page_listener(&app_handle, state.inner().clone());
+            spawn_page_timing_listener(&app_handle, state.inner().clone());
+            spawn_summary_listener(&app_handle, state.inner().clone());
+            spawn_linked_page_listener(&app_handle, state.inner().clone());
+            spawn_agenda_listener(&app_handle, state.inner().clone());
+            spawn_prerender_listener(&app_handle, state.inner().clone());
+            load_gamepad_mapping(&app_handle, &state);
+            load_clicker_keymap(&app_handle, &state);
+            load_style_presets(&app_handle, &state);
+            load_ink_config(&app_handle, &state);
+
+            // Give WebSocket clients their configured roles, and record
+            // what they do
+            websocket::access::load_access_config(&app_handle);
+            websocket::audit::init(&app_handle);
+
+            // Restore remote client preferences so reconnecting clients resume
+            let stored: websocket::preferences::ClientPreferencesMap =
+                settings::load_settings(&app_handle, websocket::preferences::PREFERENCES_FILE);
+            info!(count = stored.len(), "Loaded client preferences");
+            if let Ok(mut prefs) = state_arc.client_preferences.write() {
+                *prefs = stored;
+            }
+
+            if is_remote_client_mode() {
+                info!("Running as remote client; WebSocket server disabled");
+                #[cfg(any(mobile, feature = "client-sdk"))]
+                if let Ok(url) = std::env::var(REMOTE_URL_ENV) {
+                    let config = client::ClientConfig::new(url);
+                    if let Err(e) = remote::start_remote_client(&app_handle, &state_arc, config) {
+                        warn!(error = %e, "Failed to start remote client");
+                    }
+                }
+                return Ok(());
+            }
+
+            let config: websocket::config::WebSocketConfig =
+                settings::load_settings(&app_handle, websocket::config::CONFIG_FILE);
+
+            // Serve checkpoint answers, the remote-control page and other
+            // plain HTTP clients on the WebSocket server's address
+            let http_ip = config
+                .addr()
+                .map(|addr| addr.ip())
+                .unwrap_or(IpAddr::V4(Ipv4Addr::LOCALHOST));
+            let http_state = state_arc.clone();
+            let http_handle = app_handle.clone();
             tauri::async_runtime::spawn(async move {
-                // Clone state for the server, keep one for setting sender
-                let server_state = state_arc.clone();
-                match websocket::start_server(websocket::DEFAULT_PORT, server_state, app_handle)
-                    .await
+                if let Err(e) =
+                    http::start_server(http_ip, http::DEFAULT_PORT, http_state, http_handle).await
                 {
-                    Ok(tx) => {
-                        info!("WebSocket server started, broadcast channel ready");
-                        // Store the broadcast sender for future use
-                        if let Err(e) = state_arc.set_broadcast_sender(tx) {
-                            warn!("Failed to set broadcast sender: {}", e);
-                        }
-                    }
-                    Err(e) => {
-                        warn!(error = %e, "Failed to start WebSocket server");
-                    }
+                    warn!(error = %e, "Failed to start HTTP endpoint");
+                }
+            });
+
+            // Start WebSocket server on the configured port (11451 by default,
+            // or a free port after it) using Tauri's runtime. Using raw
+            // tokio::spawn here can panic during startup if no Tokio reactor
+            // is active yet in the setup context.
+            tauri::async_runtime::spawn(async move {
+                // Failures are logged and reported to the webview
+                let _ =
+                    websocket::start_server(&config, state_arc.clone(), app_handle.clone()).await;
+
+                // Same protocol for local scripts, over a Unix socket or
+                // named pipe
+                if let Err(e) =
+                    websocket::local::start_local_server(state_arc.clone(), app_handle.clone())
+                        .await
+                {
+                    warn!(error = %e, "Failed to start local control socket");
+                }
+
+                // OSC for lighting desks and show control, when turned on
+                let osc_config: osc::config::OscConfig =
+                    settings::load_settings(&app_handle, osc::config::CONFIG_FILE);
+                if let Err(e) = osc::server::start(&osc_config, state_arc, app_handle).await {
+                    warn!(error = %e, "Failed to start OSC endpoint");
                 }
             });
 
@@ -0,0 +1,304 @@
+/*
+ * This file is part of StreamSlate.
+ * Copyright (C) 2025 StreamSlate Contributors
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+
+//! Session export for learning management systems
+//!
+//! A presented session is packaged as a zip with an `imsmanifest.xml` at its
+//! root, which Canvas, Moodle and other LMSs import as a SCORM 1.2 content
+//! package. The package holds a readable summary page, the annotations as
+//! XFDF and the raw session data as JSON. The deck itself is referenced by
+//! name and content hash rather than copied.
+
+mod zip;
+
+use crate::analytics::{format_duration, SessionReport};
+use crate::commands::annotations::Annotation;
+use crate::error::Result;
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::fmt::Write;
+
+/// The presented document
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DeckReference {
+    pub file_name: String,
+    /// Hex SHA-256 of the file, to match the deck on the learner's side
+    pub sha256: String,
+    pub page_count: u32,
+}
+
+/// Everything recorded about one presented session
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionExport {
+    pub title: String,
+    pub exported_at: DateTime<Utc>,
+    pub deck: DeckReference,
+    /// Page timing of the session, when analytics were enabled
+    pub timeline: Option<SessionReport>,
+    /// Sidecar annotations by page
+    pub annotations: BTreeMap<u32, Vec<Annotation>>,
+    /// Text notes by page: text annotations and cached page summaries
+    pub notes: BTreeMap<u32, Vec<String>>,
+}
+
+/// Build the zip package for `session`, with `xfdf` as its annotation file
+pub fn build_package(session: &SessionExport, xfdf: &str) -> Result<Vec<u8>> {
+    let mut zip = zip::ZipWriter::new(session.exported_at);
+    zip.add("imsmanifest.xml", manifest(session).as_bytes())?;
+    zip.add("index.html", summary_html(session).as_bytes())?;
+    zip.add(
+        "session.json",
+        serde_json::to_string_pretty(session)?.as_bytes(),
+    )?;
+    zip.add("annotations.xfdf", xfdf.as_bytes())?;
+    zip.finish()
+}
+
+/// SCORM 1.2 manifest with the summary page as the single launchable item
+fn manifest(session: &SessionExport) -> String {
+    let title = escape(&session.title);
+    let identifier = match &session.timeline {
+        Some(report) => format!("streamslate-{}", report.session_id),
+        None => format!("streamslate-{}", session.exported_at.format("%Y%m%d%H%M%S")),
+    };
+    format!(
+        r#"<?xml version="1.0" encoding="UTF-8"?>
+<manifest identifier="{identifier}" version="1.0"
+  xmlns="http://www.imsproject.org/xsd/imscp_rootv1p1p2"
+  xmlns:adlcp="http://www.adlnet.org/xsd/adlcp_rootv1p2"
+  xmlns:xsi="http://www.w3.org/2001/XMLSchema-instance"
+  xsi:schemaLocation="http://www.imsproject.org/xsd/imscp_rootv1p1p2 imscp_rootv1p1p2.xsd http://www.adlnet.org/xsd/adlcp_rootv1p2 adlcp_rootv1p2.xsd">
+  <metadata>
+    <schema>ADL SCORM</schema>
+    <schemaversion>1.2</schemaversion>
+  </metadata>
+  <organizations default="session">
+    <organization identifier="session">
+      <title>{title}</title>
+      <item identifier="summary" identifierref="summary-resource">
+        <title>{title}</title>
+      </item>
+    </organization>
+  </organizations>
+  <resources>
+    <resource identifier="summary-resource" type="webcontent" adlcp:scormtype="asset" href="index.html">
+      <file href="index.html"/>
+      <file href="session.json"/>
+      <file href="annotations.xfdf"/>
+    </resource>
+  </resources>
+</manifest>
+"#
+    )
+}
+
+/// Standalone page describing the session
+fn summary_html(session: &SessionExport) -> String {
+    let title = escape(&session.title);
+    let mut html = format!(
+        "<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n\
+         <title>{title}</title>\n<style>\n\
+         body {{ font-family: system-ui, sans-serif; max-width: 48rem; margin: 2rem auto; padding: 0 1rem; }}\n\
+         table {{ border-collapse: collapse; }}\n\
+         th, td {{ padding: 0.25rem 0.75rem; border-bottom: 1px solid #ddd; text-align: left; }}\n\
+         </style>\n</head>\n<body>\n<h1>{title}</h1>\n"
+    );
+    let deck = &session.deck;
+    let _ = writeln!(
+        html,
+        "<p>Deck: {} ({} pages)</p>",
+        escape(&deck.file_name),
+        deck.page_count
+    );
+
+    if let Some(report) = &session.timeline {
+        let recorded = report.recording.is_some();
+        let _ = writeln!(
+            html,
+            "<p>Presented {} for {}</p>\n<h2>Timeline</h2>\n<table>\n\
+             <tr><th>Page</th><th>First shown</th>{}<th>Time on page</th><th>Visits</th></tr>",
+            escape(&report.started_at),
+            format_duration(report.duration_ms),
+            if recorded {
+                "<th>In recording</th>"
+            } else {
+                ""
+            }
+        );
+        let mut pages: Vec<_> = report.pages.iter().collect();
+        pages.sort_by(|a, b| a.first_viewed_at.cmp(&b.first_viewed_at));
+        for page in pages {
+            let in_recording = match page.recording_offset_ms {
+                _ if !recorded => String::new(),
+                Some(offset) if offset >= 0 => {
+                    format!("<td>{}</td>", format_duration(offset as u64))
+                }
+                _ => "<td>before recording</td>".to_string(),
+            };
+            let _ = writeln!(
+                html,
+                "<tr><td>{}</td><td>{}</td>{in_recording}<td>{}</td><td>{}</td></tr>",
+                page.page,
+                escape(&page.first_viewed_at),
+                format_duration(page.dwell_ms),
+                page.visits
+            );
+        }
+        html.push_str("</table>\n");
+    }
+
+    if !session.notes.is_empty() {
+        html.push_str("<h2>Notes</h2>\n");
+        for (page, notes) in &session.notes {
+            let _ = writeln!(html, "<h3>Page {page}</h3>\n<ul>");
+            for note in notes {
+                let _ = writeln!(html, "<li>{}</li>", escape(note));
+            }
+            html.push_str("</ul>\n");
+        }
+    }
+
+    let count: usize = session.annotations.values().map(Vec::len).sum();
+    let _ = writeln!(
+        html,
+        "<h2>Annotations</h2>\n<p>{count} annotations on {} pages. \
+         Open <a href=\"annotations.xfdf\">annotations.xfdf</a> with the deck in a PDF \
+         viewer to see them; <a href=\"session.json\">session.json</a> has the full \
+         session data.</p>\n</body>\n</html>",
+        session
+            .annotations
+            .values()
+            .filter(|a| !a.is_empty())
+            .count()
+    );
+    html
+}
+
+/// Escape text for HTML and XML
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analytics::PageDwell;
+    use crate::obs::RecordingSync;
+    use chrono::TimeZone;
+
+    fn session() -> SessionExport {
+        SessionExport {
+            title: "Week 3 <Sorting>".to_string(),
+            exported_at: Utc.with_ymd_and_hms(2025, 3, 14, 10, 0, 0).unwrap(),
+            deck: DeckReference {
+                file_name: "sorting.pdf".to_string(),
+                sha256: "ab".repeat(32),
+                page_count: 12,
+            },
+            timeline: Some(SessionReport {
+                session_id: "s1".to_string(),
+                document: "/lectures/sorting.pdf".to_string(),
+                started_at: "2025-03-14T09:00:00.000Z".to_string(),
+                ended_at: "2025-03-14T09:50:00.000Z".to_string(),
+                duration_ms: 3_000_000,
+                pages: vec![
+                    PageDwell {
+                        page: 1,
+                        visits: 2,
+                        dwell_ms: 95_000,
+                        first_viewed_at: "2025-03-14T09:00:00.000Z".to_string(),
+                        recording_offset_ms: None,
+                    },
+                    PageDwell {
+                        page: 4,
+                        visits: 1,
+                        dwell_ms: 3_725_000,
+                        first_viewed_at: "2025-03-14T09:01:00.000Z".to_string(),
+                        recording_offset_ms: None,
+                    },
+                ],
+                recording: None,
+            }),
+            annotations: BTreeMap::new(),
+            notes: BTreeMap::from([(4, vec!["Quicksort & pivots".to_string()])]),
+        }
+    }
+
+    #[test]
+    fn test_manifest_lists_package_files() {
+        let manifest = manifest(&session());
+        assert!(manifest.contains("identifier=\"streamslate-s1\""));
+        assert!(manifest.contains("<title>Week 3 &lt;Sorting&gt;</title>"));
+        assert!(manifest.contains("<schemaversion>1.2</schemaversion>"));
+        for file in ["index.html", "session.json", "annotations.xfdf"] {
+            assert!(manifest.contains(&format!("<file href=\"{file}\"/>")));
+        }
+    }
+
+    #[test]
+    fn test_summary_html() {
+        let html = summary_html(&session());
+        assert!(html.contains("<h1>Week 3 &lt;Sorting&gt;</h1>"));
+        assert!(html.contains("for 50:00</p>"));
+        assert!(html.contains("<tr><td>4</td><td>2025-03-14T09:01:00.000Z</td><td>1:02:05</td>"));
+        assert!(html.contains("<li>Quicksort &amp; pivots</li>"));
+        assert!(html.contains("0 annotations on 0 pages"));
+        assert!(!html.contains("In recording"));
+    }
+
+    #[test]
+    fn test_summary_html_recording_offsets() {
+        let mut session = session();
+        let report = session.timeline.as_mut().unwrap();
+        report.recording = Some(RecordingSync {
+            started_at: Utc.with_ymd_and_hms(2025, 3, 14, 9, 0, 30).unwrap(),
+            round_trip_ms: 8,
+        });
+        report.pages[0].recording_offset_ms = Some(-30_000);
+        report.pages[1].recording_offset_ms = Some(30_000);
+
+        let html = summary_html(&session);
+        assert!(
+            html.contains("<td>1</td><td>2025-03-14T09:00:00.000Z</td><td>before recording</td>")
+        );
+        assert!(html
+            .contains("<td>4</td><td>2025-03-14T09:01:00.000Z</td><td>0:30</td><td>1:02:05</td>"));
+    }
+
+    #[test]
+    fn test_build_package() {
+        let package = build_package(&session(), "<xfdf/>").unwrap();
+        assert!(package.starts_with(b"PK\x03\x04"));
+        // Manifest first, as some importers expect
+        assert_eq!(&package[30..45], b"imsmanifest.xml");
+    }
+}
@@ -0,0 +1,218 @@
+/*
+ * This file is part of StreamSlate.
+ * Copyright (C) 2025 StreamSlate Contributors
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+
+//! Minimal zip writer
+//!
+//! Deflated entries in memory, without zip64, encryption or extra fields;
+//! enough for the small text files of an export package.
+
+use crate::error::{Result, StreamSlateError};
+use chrono::{DateTime, Datelike, Timelike, Utc};
+use flate2::write::DeflateEncoder;
+use flate2::{Compression, Crc};
+use std::io::Write;
+
+/// Compression method 8 (deflate)
+const METHOD_DEFLATE: u16 = 8;
+
+/// Version 2.0, the minimum for deflate
+const VERSION: u16 = 20;
+
+/// General purpose flag bit 11: names are UTF-8
+const FLAG_UTF8: u16 = 1 << 11;
+
+struct Entry {
+    name: String,
+    crc: u32,
+    compressed_size: u32,
+    size: u32,
+    offset: u32,
+}
+
+/// Builds a zip archive in memory
+pub struct ZipWriter {
+    out: Vec<u8>,
+    entries: Vec<Entry>,
+    /// MS-DOS time and date stamped on every entry
+    dos_time: (u16, u16),
+}
+
+impl ZipWriter {
+    /// Archive whose entries are stamped with `modified`
+    pub fn new(modified: DateTime<Utc>) -> Self {
+        Self {
+            out: Vec::new(),
+            entries: Vec::new(),
+            dos_time: dos_time(modified),
+        }
+    }
+
+    /// Add a file at `name`, a `/`-separated path inside the archive
+    pub fn add(&mut self, name: &str, data: &[u8]) -> Result<()> {
+        if name.is_empty() || name.starts_with('/') || name.contains('\\') {
+            return Err(StreamSlateError::Other(format!(
+                "Invalid zip entry name: {name}"
+            )));
+        }
+
+        let mut crc = Crc::new();
+        crc.update(data);
+        let mut encoder = DeflateEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(data)?;
+        let compressed = encoder.finish()?;
+
+        let entry = Entry {
+            name: name.to_string(),
+            crc: crc.sum(),
+            compressed_size: to_u32(compressed.len())?,
+            size: to_u32(data.len())?,
+            offset: to_u32(self.out.len())?,
+        };
+
+        self.out.extend_from_slice(&0x0403_4b50u32.to_le_bytes());
+        self.out.extend_from_slice(&VERSION.to_le_bytes());
+        self.write_entry_fields(&entry);
+        self.out.extend_from_slice(&0u16.to_le_bytes()); // extra field length
+        self.out.extend_from_slice(entry.name.as_bytes());
+        self.out.extend_from_slice(&compressed);
+        self.entries.push(entry);
+        Ok(())
+    }
+
+    /// Write the central directory and return the archive
+    pub fn finish(mut self) -> Result<Vec<u8>> {
+        let directory_offset = to_u32(self.out.len())?;
+        let entries = std::mem::take(&mut self.entries);
+        for entry in &entries {
+            self.out.extend_from_slice(&0x0201_4b50u32.to_le_bytes());
+            self.out.extend_from_slice(&VERSION.to_le_bytes()); // made by
+            self.out.extend_from_slice(&VERSION.to_le_bytes()); // needed to extract
+            self.write_entry_fields(entry);
+            self.out.extend_from_slice(&[0; 12]); // extra, comment, disk, attributes
+            self.out.extend_from_slice(&entry.offset.to_le_bytes());
+            self.out.extend_from_slice(entry.name.as_bytes());
+        }
+        let directory_size = to_u32(self.out.len())? - directory_offset;
+        let count = u16::try_from(entries.len())
+            .map_err(|_| StreamSlateError::Other("Too many zip entries".to_string()))?;
+
+        self.out.extend_from_slice(&0x0605_4b50u32.to_le_bytes());
+        self.out.extend_from_slice(&[0; 4]); // disk numbers
+        self.out.extend_from_slice(&count.to_le_bytes());
+        self.out.extend_from_slice(&count.to_le_bytes());
+        self.out.extend_from_slice(&directory_size.to_le_bytes());
+        self.out.extend_from_slice(&directory_offset.to_le_bytes());
+        self.out.extend_from_slice(&0u16.to_le_bytes()); // comment length
+        Ok(self.out)
+    }
+
+    /// Fields shared by local headers and central directory records, from
+    /// the flags up to the file name length
+    fn write_entry_fields(&mut self, entry: &Entry) {
+        let (time, date) = self.dos_time;
+        self.out.extend_from_slice(&FLAG_UTF8.to_le_bytes());
+        self.out.extend_from_slice(&METHOD_DEFLATE.to_le_bytes());
+        self.out.extend_from_slice(&time.to_le_bytes());
+        self.out.extend_from_slice(&date.to_le_bytes());
+        self.out.extend_from_slice(&entry.crc.to_le_bytes());
+        self.out
+            .extend_from_slice(&entry.compressed_size.to_le_bytes());
+        self.out.extend_from_slice(&entry.size.to_le_bytes());
+        // Names come from `add`, which only takes short fixed paths
+        self.out
+            .extend_from_slice(&(entry.name.len() as u16).to_le_bytes());
+    }
+}
+
+/// MS-DOS `(time, date)`, clamped to the format's 1980 epoch
+fn dos_time(at: DateTime<Utc>) -> (u16, u16) {
+    if at.year() < 1980 {
+        return (0, (1 << 5) | 1);
+    }
+    let time = (at.hour() << 11) | (at.minute() << 5) | (at.second() / 2);
+    let date = ((at.year() as u32 - 1980).min(127) << 9) | (at.month() << 5) | at.day();
+    (time as u16, date as u16)
+}
+
+fn to_u32(value: usize) -> Result<u32> {
+    u32::try_from(value)
+        .map_err(|_| StreamSlateError::Other("Zip archive larger than 4 GiB".to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+    use flate2::read::DeflateDecoder;
+    use std::io::Read;
+
+    fn u16_at(data: &[u8], at: usize) -> u16 {
+        u16::from_le_bytes([data[at], data[at + 1]])
+    }
+
+    fn u32_at(data: &[u8], at: usize) -> u32 {
+        u32::from_le_bytes([data[at], data[at + 1], data[at + 2], data[at + 3]])
+    }
+
+    #[test]
+    fn test_archive_layout() {
+        let modified = Utc.with_ymd_and_hms(2025, 3, 14, 15, 9, 26).unwrap();
+        let mut zip = ZipWriter::new(modified);
+        zip.add("index.html", b"<h1>Hello</h1>").unwrap();
+        zip.add("data/notes.txt", "café ".repeat(50).as_bytes())
+            .unwrap();
+        let archive = zip.finish().unwrap();
+
+        // End of central directory record
+        let eocd = archive.len() - 22;
+        assert_eq!(u32_at(&archive, eocd), 0x0605_4b50);
+        assert_eq!(u16_at(&archive, eocd + 10), 2);
+        let directory = u32_at(&archive, eocd + 16) as usize;
+
+        // Second central directory record points at its local header
+        let first_name_len = u16_at(&archive, directory + 28) as usize;
+        let second = directory + 46 + first_name_len;
+        assert_eq!(u32_at(&archive, second), 0x0201_4b50);
+        assert_eq!(&archive[second + 46..second + 60], b"data/notes.txt");
+        let local = u32_at(&archive, second + 42) as usize;
+        assert_eq!(u32_at(&archive, local), 0x0403_4b50);
+        assert_eq!(
+            u16_at(&archive, local + 12),
+            (2025 - 1980) << 9 | 3 << 5 | 14
+        );
+
+        let compressed_size = u32_at(&archive, local + 18) as usize;
+        let data_start = local + 30 + 14;
+        let mut content = String::new();
+        DeflateDecoder::new(&archive[data_start..data_start + compressed_size])
+            .read_to_string(&mut content)
+            .unwrap();
+        assert_eq!(content, "café ".repeat(50));
+        let mut crc = Crc::new();
+        crc.update(content.as_bytes());
+        assert_eq!(u32_at(&archive, local + 14), crc.sum());
+    }
+
+    #[test]
+    fn test_rejects_bad_names() {
+        let mut zip = ZipWriter::new(Utc::now());
+        assert!(zip.add("/etc/passwd", b"").is_err());
+        assert!(zip.add("a\\b", b"").is_err());
+        assert!(zip.add("", b"").is_err());
+    }
+}
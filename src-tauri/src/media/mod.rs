@@ -0,0 +1,163 @@
+/*
+ * This file is part of StreamSlate.
+ * Copyright (C) 2025 StreamSlate Contributors
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+
+//! Media playback state
+//!
+//! StreamSlate doesn't decode video itself; the views play the clips. This
+//! keeps the authoritative play/pause/seek state so every view and remote
+//! controller agrees on where each clip is, and late joiners can catch up.
+
+use crate::error::{Result, StreamSlateError};
+use crate::pdf::media::MediaItem;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::time::Instant;
+
+/// Playback state of one clip
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct MediaPlayback {
+    pub id: String,
+    pub playing: bool,
+    /// Seconds from the start of the clip
+    pub position: f64,
+}
+
+/// A clip together with its playback state
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct MediaStatus {
+    #[serde(flatten)]
+    pub item: MediaItem,
+    pub playing: bool,
+    pub position: f64,
+}
+
+/// A playback control request
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum MediaAction {
+    Play,
+    Pause,
+    /// Jump to a position in seconds, keeping the play/pause state
+    Seek(f64),
+}
+
+/// Playback state of all clips of the open document
+#[derive(Debug, Clone, Default)]
+pub struct MediaPlayer {
+    /// Each clip's state and when its position was last set
+    clips: HashMap<String, (MediaPlayback, Instant)>,
+}
+
+impl MediaPlayer {
+    /// Apply `action` to clip `id` and return its new state
+    pub fn apply(&mut self, id: &str, action: MediaAction, now: Instant) -> Result<MediaPlayback> {
+        let mut playback = self.status(id, now);
+        match action {
+            MediaAction::Play => playback.playing = true,
+            MediaAction::Pause => playback.playing = false,
+            MediaAction::Seek(position) => {
+                if !position.is_finite() || position < 0.0 {
+                    return Err(StreamSlateError::Other(format!(
+                        "Invalid media position {position}"
+                    )));
+                }
+                playback.position = position;
+            }
+        }
+        self.clips.insert(id.to_string(), (playback.clone(), now));
+        Ok(playback)
+    }
+
+    /// Current state of clip `id`; clips never touched are paused at the start
+    ///
+    /// The position of a playing clip advances with wall-clock time.
+    pub fn status(&self, id: &str, now: Instant) -> MediaPlayback {
+        match self.clips.get(id) {
+            Some((playback, since)) if playback.playing => MediaPlayback {
+                position: playback.position + now.saturating_duration_since(*since).as_secs_f64(),
+                ..playback.clone()
+            },
+            Some((playback, _)) => playback.clone(),
+            None => MediaPlayback {
+                id: id.to_string(),
+                playing: false,
+                position: 0.0,
+            },
+        }
+    }
+
+    /// Status of each of `items`
+    pub fn statuses(&self, items: Vec<MediaItem>, now: Instant) -> Vec<MediaStatus> {
+        items
+            .into_iter()
+            .map(|item| {
+                let playback = self.status(&item.id, now);
+                MediaStatus {
+                    item,
+                    playing: playback.playing,
+                    position: playback.position,
+                }
+            })
+            .collect()
+    }
+
+    /// Forget all clips, which belong to the previous document
+    pub fn clear(&mut self) {
+        self.clips.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn test_media_player() {
+        let mut player = MediaPlayer::default();
+        let start = Instant::now();
+        assert!(!player.status("clip", start).playing);
+
+        player
+            .apply("clip", MediaAction::Seek(10.0), start)
+            .unwrap();
+        player.apply("clip", MediaAction::Play, start).unwrap();
+        let later = start + Duration::from_secs(5);
+        assert_eq!(player.status("clip", later).position, 15.0);
+
+        let paused = player.apply("clip", MediaAction::Pause, later).unwrap();
+        assert!(!paused.playing);
+        assert_eq!(paused.position, 15.0);
+        assert_eq!(
+            player
+                .status("clip", later + Duration::from_secs(5))
+                .position,
+            15.0
+        );
+
+        assert!(player
+            .apply("clip", MediaAction::Seek(-1.0), later)
+            .is_err());
+        assert!(player
+            .apply("clip", MediaAction::Seek(f64::NAN), later)
+            .is_err());
+
+        player.clear();
+        assert_eq!(player.status("clip", later).position, 0.0);
+    }
+}
@@ -170,6 +170,12 @@ impl crate::state::FrameOutput for NdiSender {
     fn is_running(&self) -> bool {
         self.is_running()
     }
+
+    fn resume(&self) -> Result<(), String> {
+        // The NDI instance and its sockets don't survive sleep; recreate them
+        self.stop();
+        self.start().map_err(|e| e.to_string())
+    }
 }
 
 impl Drop for NdiSender {
@@ -0,0 +1,131 @@
+/*
+ * This file is part of StreamSlate.
+ * Copyright (C) 2025 StreamSlate Contributors
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+
+//! Presentation cues mapped to OBS source visibility
+//!
+//! Bindings are stored in the app data directory so a show's setup survives
+//! restarts.
+
+use crate::error::{Result, StreamSlateError};
+use crate::settings::Settings;
+use serde::{Deserialize, Serialize};
+
+/// File name of the bindings store inside the app data directory
+pub const BINDINGS_FILE: &str = "obs_bindings.json";
+
+/// Something that happens in the presentation
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum ObsTrigger {
+    /// The presentation arrives at a page
+    Page { page: u32 },
+    /// An annotation layer is shown or hidden
+    Layer { layer: String, visible: bool },
+}
+
+/// Show or hide an OBS source when a trigger fires
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ObsBinding {
+    pub trigger: ObsTrigger,
+    /// Scene containing the source; the current program scene if omitted
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub scene: Option<String>,
+    pub source: String,
+    pub visible: bool,
+}
+
+impl ObsBinding {
+    /// Reject bindings that can never fire or target nothing
+    pub fn validate(&self) -> Result<()> {
+        if self.source.trim().is_empty() {
+            return Err(StreamSlateError::Other(
+                "OBS binding needs a source name".to_string(),
+            ));
+        }
+        match &self.trigger {
+            ObsTrigger::Page { page: 0 } => Err(StreamSlateError::Other(
+                "OBS binding pages start at 1".to_string(),
+            )),
+            ObsTrigger::Layer { layer, .. } if layer.trim().is_empty() => Err(
+                StreamSlateError::Other("OBS binding needs a layer name".to_string()),
+            ),
+            _ => Ok(()),
+        }
+    }
+}
+
+impl Settings for Vec<ObsBinding> {}
+
+/// Bindings that fire for `trigger`
+pub fn matching<'a>(
+    bindings: &'a [ObsBinding],
+    trigger: &'a ObsTrigger,
+) -> impl Iterator<Item = &'a ObsBinding> + 'a {
+    bindings
+        .iter()
+        .filter(move |binding| binding.trigger == *trigger)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn binding(trigger: ObsTrigger, source: &str) -> ObsBinding {
+        ObsBinding {
+            trigger,
+            scene: None,
+            source: source.to_string(),
+            visible: true,
+        }
+    }
+
+    #[test]
+    fn test_matching_bindings() {
+        let answers = ObsTrigger::Layer {
+            layer: "answers".to_string(),
+            visible: true,
+        };
+        let bindings = vec![
+            binding(ObsTrigger::Page { page: 3 }, "Lower third"),
+            binding(answers.clone(), "Answers overlay"),
+        ];
+
+        let fired: Vec<_> = matching(&bindings, &answers).map(|b| &b.source).collect();
+        assert_eq!(fired, ["Answers overlay"]);
+        let hidden = ObsTrigger::Layer {
+            layer: "answers".to_string(),
+            visible: false,
+        };
+        assert_eq!(matching(&bindings, &hidden).count(), 0);
+    }
+
+    #[test]
+    fn test_binding_json_and_validation() {
+        let json = r#"{"trigger":{"kind":"page","page":2},"source":"Webcam","visible":false}"#;
+        let parsed: ObsBinding = serde_json::from_str(json).unwrap();
+        assert_eq!(parsed.trigger, ObsTrigger::Page { page: 2 });
+        assert!(parsed.validate().is_ok());
+
+        assert!(binding(ObsTrigger::Page { page: 0 }, "Webcam")
+            .validate()
+            .is_err());
+        assert!(binding(ObsTrigger::Page { page: 1 }, " ")
+            .validate()
+            .is_err());
+    }
+}
@@ -0,0 +1,475 @@
+/*
+ * This file is part of StreamSlate.
+ * Copyright (C) 2025 StreamSlate Contributors
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+
+//! OBS Studio integration over obs-websocket (protocol v5)
+//!
+//! A minimal request client: connect, authenticate, and send requests such
+//! as toggling a source's visibility. Bindings in [`bindings`] map
+//! presentation cues onto those requests. Output events are forwarded so
+//! recordings can be lined up with the presentation timeline.
+
+pub mod bindings;
+
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use chrono::{DateTime, Utc};
+use futures_util::{SinkExt, StreamExt};
+use ring::digest::{digest, SHA256};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::{broadcast, mpsc, oneshot, watch};
+use tokio_tungstenite::{connect_async, tungstenite::Message};
+use tracing::{debug, info, warn};
+
+/// Default obs-websocket port
+pub const DEFAULT_PORT: u16 = 4455;
+
+/// How long to wait for the handshake or a request response
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// obs-websocket RPC version spoken by this client
+const RPC_VERSION: u64 = 1;
+
+// Message opcodes
+const OP_HELLO: u64 = 0;
+const OP_IDENTIFY: u64 = 1;
+const OP_IDENTIFIED: u64 = 2;
+const OP_EVENT: u64 = 5;
+const OP_REQUEST: u64 = 6;
+const OP_REQUEST_RESPONSE: u64 = 7;
+
+/// Event subscription bit for output (stream, record, replay buffer) events
+const EVENT_SUBSCRIPTION_OUTPUTS: u64 = 1 << 6;
+
+/// `outputState` of a `RecordStateChanged` event when recording has begun
+pub const RECORD_STARTED: &str = "OBS_WEBSOCKET_OUTPUT_STARTED";
+
+/// `outputState` of a `RecordStateChanged` event when recording has ended
+pub const RECORD_STOPPED: &str = "OBS_WEBSOCKET_OUTPUT_STOPPED";
+
+type Reply = oneshot::Sender<Result<Value, String>>;
+
+/// Request ID, message payload and where to deliver the response
+type QueuedRequest = (String, Value, Reply);
+
+type PendingRequests = HashMap<String, Reply>;
+
+/// An event pushed by OBS
+#[derive(Debug, Clone)]
+pub struct ObsEvent {
+    pub event_type: String,
+    pub data: Value,
+}
+
+/// When an OBS recording's first frame was captured, by the local clock
+///
+/// Derived from the recording duration OBS reports, taken at the midpoint of
+/// the request's round trip, so it is accurate to about half of
+/// `round_trip_ms`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RecordingSync {
+    pub started_at: DateTime<Utc>,
+    pub round_trip_ms: u64,
+}
+
+impl RecordingSync {
+    /// Milliseconds into the recording at `at`, negative before it started
+    pub fn offset_ms(&self, at: DateTime<Utc>) -> i64 {
+        (at - self.started_at).num_milliseconds()
+    }
+}
+
+/// Handle to a live obs-websocket connection
+///
+/// Cheap to clone; the connection closes when `close` is called or OBS
+/// goes away.
+#[derive(Debug, Clone)]
+pub struct ObsConnection {
+    url: String,
+    request_tx: mpsc::Sender<QueuedRequest>,
+    shutdown_tx: watch::Sender<bool>,
+    connected: Arc<AtomicBool>,
+    next_id: Arc<AtomicU64>,
+    events_tx: broadcast::Sender<ObsEvent>,
+}
+
+impl ObsConnection {
+    /// Connect to OBS at `host:port` and authenticate with `password` if required
+    pub async fn connect(host: &str, port: u16, password: Option<&str>) -> Result<Self, String> {
+        let url = format!("ws://{host}:{port}");
+        let (mut ws, _) = tokio::time::timeout(REQUEST_TIMEOUT, connect_async(url.as_str()))
+            .await
+            .map_err(|_| format!("Timed out connecting to OBS at {url}"))?
+            .map_err(|e| format!("Failed to connect to OBS at {url}: {e}"))?;
+
+        let hello = read_op(&mut ws, OP_HELLO).await?;
+        let mut identify = json!({
+            "rpcVersion": RPC_VERSION,
+            "eventSubscriptions": EVENT_SUBSCRIPTION_OUTPUTS,
+        });
+        if let Some(auth) = hello.get("authentication") {
+            let password = password.ok_or("OBS requires a password")?;
+            let field = |key: &str| {
+                auth.get(key)
+                    .and_then(Value::as_str)
+                    .ok_or_else(|| format!("OBS hello is missing {key}"))
+            };
+            identify["authentication"] =
+                json!(auth_response(password, field("salt")?, field("challenge")?));
+        }
+        send_op(&mut ws, OP_IDENTIFY, identify).await?;
+        read_op(&mut ws, OP_IDENTIFIED)
+            .await
+            .map_err(|e| format!("OBS rejected the connection (wrong password?): {e}"))?;
+        info!(url = %url, "Connected to OBS");
+
+        let (request_tx, request_rx) = mpsc::channel(32);
+        let (shutdown_tx, shutdown_rx) = watch::channel(false);
+        let connected = Arc::new(AtomicBool::new(true));
+        let (events_tx, _) = broadcast::channel(32);
+        tauri::async_runtime::spawn(run_connection(
+            ws,
+            request_rx,
+            shutdown_rx,
+            connected.clone(),
+            events_tx.clone(),
+        ));
+
+        Ok(Self {
+            url,
+            request_tx,
+            shutdown_tx,
+            connected,
+            next_id: Arc::new(AtomicU64::new(1)),
+            events_tx,
+        })
+    }
+
+    pub fn url(&self) -> &str {
+        &self.url
+    }
+
+    /// Whether the connection is still open
+    pub fn is_connected(&self) -> bool {
+        self.connected.load(Ordering::SeqCst)
+    }
+
+    /// Close the connection
+    pub fn close(&self) {
+        let _ = self.shutdown_tx.send(true);
+    }
+
+    /// Receive the events OBS pushes from now on
+    pub fn subscribe(&self) -> broadcast::Receiver<ObsEvent> {
+        self.events_tx.subscribe()
+    }
+
+    /// Send a request and wait for its `responseData`
+    pub async fn request(&self, request_type: &str, data: Value) -> Result<Value, String> {
+        if !self.is_connected() {
+            return Err("Not connected to OBS".to_string());
+        }
+        let id = self.next_id.fetch_add(1, Ordering::SeqCst).to_string();
+        let (tx, rx) = oneshot::channel();
+        let payload = json!({
+            "requestType": request_type,
+            "requestId": id,
+            "requestData": data,
+        });
+        self.request_tx
+            .send((id, payload, tx))
+            .await
+            .map_err(|_| "OBS connection closed".to_string())?;
+        tokio::time::timeout(REQUEST_TIMEOUT, rx)
+            .await
+            .map_err(|_| format!("OBS did not answer {request_type}"))?
+            .map_err(|_| "OBS connection closed".to_string())?
+    }
+
+    /// Show or hide `source` in `scene` (the current program scene if `None`)
+    pub async fn set_source_visible(
+        &self,
+        scene: Option<&str>,
+        source: &str,
+        visible: bool,
+    ) -> Result<(), String> {
+        let scene = match scene {
+            Some(scene) => scene.to_string(),
+            None => self
+                .request("GetCurrentProgramScene", json!({}))
+                .await?
+                .get("currentProgramSceneName")
+                .and_then(Value::as_str)
+                .ok_or("OBS did not report a program scene")?
+                .to_string(),
+        };
+        let item_id = self
+            .request(
+                "GetSceneItemId",
+                json!({ "sceneName": scene, "sourceName": source }),
+            )
+            .await?
+            .get("sceneItemId")
+            .and_then(Value::as_i64)
+            .ok_or_else(|| format!("Source '{source}' not found in scene '{scene}'"))?;
+        self.request(
+            "SetSceneItemEnabled",
+            json!({ "sceneName": scene, "sceneItemId": item_id, "sceneItemEnabled": visible }),
+        )
+        .await?;
+        debug!(scene = %scene, source = %source, visible, "OBS source visibility set");
+        Ok(())
+    }
+
+    /// Correlate the running recording with the local clock
+    ///
+    /// Returns `None` when OBS is not recording.
+    pub async fn recording_sync(&self) -> Result<Option<RecordingSync>, String> {
+        let sent_at = Utc::now();
+        let sent = Instant::now();
+        let status = self.request("GetRecordStatus", json!({})).await?;
+        Ok(recording_sync_from_status(&status, sent_at, sent.elapsed()))
+    }
+}
+
+/// Recording start from a `GetRecordStatus` response sent at `sent_at`
+fn recording_sync_from_status(
+    status: &Value,
+    sent_at: DateTime<Utc>,
+    round_trip: Duration,
+) -> Option<RecordingSync> {
+    if status.get("outputActive").and_then(Value::as_bool) != Some(true) {
+        return None;
+    }
+    let duration_ms = status.get("outputDuration").and_then(Value::as_i64)?;
+    let answered_at = sent_at + chrono::Duration::from_std(round_trip / 2).ok()?;
+    Some(RecordingSync {
+        started_at: answered_at - chrono::Duration::milliseconds(duration_ms),
+        round_trip_ms: round_trip.as_millis() as u64,
+    })
+}
+
+/// obs-websocket authentication string for a password, salt and challenge
+pub fn auth_response(password: &str, salt: &str, challenge: &str) -> String {
+    let secret = BASE64.encode(digest(&SHA256, format!("{password}{salt}").as_bytes()));
+    BASE64.encode(digest(&SHA256, format!("{secret}{challenge}").as_bytes()))
+}
+
+type WsStream =
+    tokio_tungstenite::WebSocketStream<tokio_tungstenite::MaybeTlsStream<tokio::net::TcpStream>>;
+
+async fn send_op(ws: &mut WsStream, op: u64, data: Value) -> Result<(), String> {
+    let message = json!({ "op": op, "d": data }).to_string();
+    ws.send(Message::Text(message))
+        .await
+        .map_err(|e| format!("Failed to send to OBS: {e}"))
+}
+
+/// Read messages until one with opcode `op` arrives, returning its data
+async fn read_op(ws: &mut WsStream, op: u64) -> Result<Value, String> {
+    let read = async {
+        while let Some(message) = ws.next().await {
+            let message = message.map_err(|e| e.to_string())?;
+            if let Message::Text(text) = message {
+                let mut value: Value = serde_json::from_str(&text).map_err(|e| e.to_string())?;
+                if value.get("op").and_then(Value::as_u64) == Some(op) {
+                    return Ok(value["d"].take());
+                }
+            }
+        }
+        Err("connection closed".to_string())
+    };
+    tokio::time::timeout(REQUEST_TIMEOUT, read)
+        .await
+        .map_err(|_| "timed out".to_string())?
+}
+
+/// Pump requests out and route responses back until closed
+async fn run_connection(
+    ws: WsStream,
+    mut request_rx: mpsc::Receiver<QueuedRequest>,
+    mut shutdown_rx: watch::Receiver<bool>,
+    connected: Arc<AtomicBool>,
+    events_tx: broadcast::Sender<ObsEvent>,
+) {
+    let (mut sink, mut stream) = ws.split();
+    let mut pending = PendingRequests::new();
+
+    let reason = loop {
+        tokio::select! {
+            request = request_rx.recv() => {
+                let Some((id, payload, reply)) = request else {
+                    break "handle dropped".to_string();
+                };
+                let message = json!({ "op": OP_REQUEST, "d": payload }).to_string();
+                if let Err(e) = sink.send(Message::Text(message)).await {
+                    let _ = reply.send(Err(format!("Failed to send to OBS: {e}")));
+                    break e.to_string();
+                }
+                pending.insert(id, reply);
+            }
+            message = stream.next() => {
+                match message {
+                    Some(Ok(Message::Text(text))) => route_message(&text, &mut pending, &events_tx),
+                    Some(Ok(Message::Close(_))) | None => break "closed by OBS".to_string(),
+                    Some(Err(e)) => break e.to_string(),
+                    Some(Ok(_)) => {}
+                }
+            }
+            _ = shutdown_rx.changed() => {
+                let _ = sink.send(Message::Close(None)).await;
+                break "closed".to_string();
+            }
+        }
+    };
+
+    connected.store(false, Ordering::SeqCst);
+    for (_, reply) in pending.drain() {
+        let _ = reply.send(Err("OBS connection closed".to_string()));
+    }
+    warn!(reason = %reason, "OBS connection ended");
+}
+
+/// Complete the pending request a response answers, or forward an event
+fn route_message(
+    text: &str,
+    pending: &mut PendingRequests,
+    events_tx: &broadcast::Sender<ObsEvent>,
+) {
+    let Ok(mut value) = serde_json::from_str::<Value>(text) else {
+        return;
+    };
+    let mut data = value["d"].take();
+    match value.get("op").and_then(Value::as_u64) {
+        Some(OP_REQUEST_RESPONSE) => {}
+        Some(OP_EVENT) => {
+            if let Some(event_type) = data.get("eventType").and_then(Value::as_str) {
+                // Sending fails only when nothing is listening
+                let _ = events_tx.send(ObsEvent {
+                    event_type: event_type.to_string(),
+                    data: data["eventData"].take(),
+                });
+            }
+            return;
+        }
+        _ => return,
+    }
+    let Some(reply) = data
+        .get("requestId")
+        .and_then(Value::as_str)
+        .and_then(|id| pending.remove(id))
+    else {
+        return;
+    };
+
+    let status = &data["requestStatus"];
+    let result = if status.get("result").and_then(Value::as_bool) == Some(true) {
+        Ok(data.get("responseData").cloned().unwrap_or(Value::Null))
+    } else {
+        Err(format!(
+            "OBS request failed ({}): {}",
+            status.get("code").and_then(Value::as_i64).unwrap_or(0),
+            status
+                .get("comment")
+                .and_then(Value::as_str)
+                .unwrap_or("no details")
+        ))
+    };
+    let _ = reply.send(result);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_auth_response() {
+        // Example from the obs-websocket protocol documentation
+        assert_eq!(
+            auth_response(
+                "supersecretpassword",
+                "lM1GncleQOaCu9lT1yeUZhFYnqhsLLP1G5lAGo3ixaI=",
+                "+IxH4CnCiqpX1rM9scsNynZzbOe4KhDeYcTNS3PDaeY="
+            ),
+            "1Ct943GAT+6YQUUX47Ia/ncufilbe6+oD6lY+5kaCu4="
+        );
+    }
+
+    #[test]
+    fn test_route_message() {
+        let mut pending = PendingRequests::new();
+        let (events_tx, mut events) = broadcast::channel(4);
+        let (tx, mut rx) = oneshot::channel();
+        pending.insert("7".to_string(), tx);
+        route_message(
+            r#"{"op":7,"d":{"requestType":"GetSceneItemId","requestId":"7","requestStatus":{"result":true,"code":100},"responseData":{"sceneItemId":3}}}"#,
+            &mut pending,
+            &events_tx,
+        );
+        assert!(pending.is_empty());
+        assert_eq!(rx.try_recv().unwrap().unwrap()["sceneItemId"], 3);
+
+        let (tx, mut rx) = oneshot::channel();
+        pending.insert("8".to_string(), tx);
+        route_message(
+            r#"{"op":7,"d":{"requestId":"8","requestStatus":{"result":false,"code":600,"comment":"No source"}}}"#,
+            &mut pending,
+            &events_tx,
+        );
+        assert!(rx.try_recv().unwrap().unwrap_err().contains("No source"));
+
+        route_message(
+            r#"{"op":5,"d":{"eventType":"RecordStateChanged","eventIntent":64,"eventData":{"outputActive":true,"outputState":"OBS_WEBSOCKET_OUTPUT_STARTED"}}}"#,
+            &mut pending,
+            &events_tx,
+        );
+        let event = events.try_recv().unwrap();
+        assert_eq!(event.event_type, "RecordStateChanged");
+        assert_eq!(event.data["outputState"], RECORD_STARTED);
+    }
+
+    #[test]
+    fn test_recording_sync_from_status() {
+        let sent_at = "2025-03-14T10:00:00Z".parse::<DateTime<Utc>>().unwrap();
+        let sync = recording_sync_from_status(
+            &json!({ "outputActive": true, "outputDuration": 90_000 }),
+            sent_at,
+            Duration::from_millis(40),
+        )
+        .unwrap();
+        // Answered 20ms after sending, 90s into the recording
+        assert_eq!(
+            sync.started_at.to_rfc3339(),
+            "2025-03-14T09:58:30.020+00:00"
+        );
+        assert_eq!(sync.round_trip_ms, 40);
+        assert_eq!(sync.offset_ms(sent_at), 89_980);
+
+        assert!(recording_sync_from_status(
+            &json!({ "outputActive": false, "outputDuration": 0 }),
+            sent_at,
+            Duration::from_millis(40),
+        )
+        .is_none());
+    }
+}
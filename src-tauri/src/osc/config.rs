@@ -0,0 +1,111 @@
+/*
+ * This file is part of StreamSlate.
+ * Copyright (C) 2025 StreamSlate Contributors
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+
+//! OSC settings
+//!
+//! The endpoint is off until turned on, since it accepts commands from
+//! anything that can send it a UDP packet.
+
+use crate::error::{Result, StreamSlateError};
+use crate::settings::Settings;
+use serde::{Deserialize, Serialize};
+use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+
+/// File name of the settings inside the app data directory
+pub const CONFIG_FILE: &str = "osc.json";
+
+/// Default UDP port to listen on
+pub const DEFAULT_PORT: u16 = 9000;
+
+/// Where OSC is received and sent
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OscConfig {
+    #[serde(default)]
+    pub enabled: bool,
+    /// IP address to listen on, `127.0.0.1` for this machine only or
+    /// `0.0.0.0` for desks on the network
+    #[serde(default = "default_bind_addr")]
+    pub bind_addr: String,
+    #[serde(default = "default_port")]
+    pub port: u16,
+    /// `ip:port` addresses state changes are sent to
+    #[serde(default)]
+    pub targets: Vec<String>,
+}
+
+impl Default for OscConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            bind_addr: default_bind_addr(),
+            port: default_port(),
+            targets: Vec::new(),
+        }
+    }
+}
+
+impl OscConfig {
+    /// The socket address to listen on
+    pub fn addr(&self) -> Result<SocketAddr> {
+        if self.port == 0 {
+            return Err(StreamSlateError::Other(
+                "OSC port must be between 1 and 65535".to_string(),
+            ));
+        }
+        let ip: IpAddr = self.bind_addr.trim().parse().map_err(|_| {
+            StreamSlateError::Other(format!("Invalid OSC bind address: {}", self.bind_addr))
+        })?;
+        Ok(SocketAddr::new(ip, self.port))
+    }
+
+    /// The addresses state changes are sent to
+    pub fn target_addrs(&self) -> Result<Vec<SocketAddr>> {
+        self.targets
+            .iter()
+            .map(|target| {
+                target.trim().parse().map_err(|_| {
+                    StreamSlateError::Other(format!(
+                        "Invalid OSC target: {target} (expected ip:port)"
+                    ))
+                })
+            })
+            .collect()
+    }
+
+    /// Check the address, port and targets are usable
+    pub fn validate(&self) -> Result<()> {
+        self.addr()?;
+        self.target_addrs()?;
+        Ok(())
+    }
+}
+
+impl Settings for OscConfig {
+    fn check(&self) -> Result<()> {
+        self.validate()
+    }
+}
+
+fn default_port() -> u16 {
+    DEFAULT_PORT
+}
+
+fn default_bind_addr() -> String {
+    Ipv4Addr::LOCALHOST.to_string()
+}
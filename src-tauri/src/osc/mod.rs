@@ -0,0 +1,345 @@
+/*
+ * This file is part of StreamSlate.
+ * Copyright (C) 2025 StreamSlate Contributors
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+
+//! Open Sound Control (OSC) endpoint
+//!
+//! Lighting desks, QLab and TouchOSC layouts speak OSC over UDP rather than
+//! WebSocket. Messages under `/streamslate/` are mapped onto control
+//! commands, and state changes are sent back out as OSC messages to the
+//! configured targets so their faders and labels follow the show. Only the
+//! parts of OSC 1.0 these tools use are supported: messages and bundles with
+//! `i`, `f`, `s`, `T` and `F` arguments.
+
+pub mod config;
+pub mod server;
+
+use crate::websocket::{WebSocketCommand, WebSocketEvent};
+
+/// Prefix of every address StreamSlate handles and sends
+pub const ADDRESS_PREFIX: &str = "/streamslate/";
+
+/// One OSC argument
+#[derive(Debug, Clone, PartialEq)]
+pub enum OscArg {
+    Int(i32),
+    Float(f32),
+    Str(String),
+    Bool(bool),
+}
+
+impl OscArg {
+    fn as_f64(&self) -> Option<f64> {
+        match self {
+            Self::Int(value) => Some(f64::from(*value)),
+            Self::Float(value) => Some(f64::from(*value)),
+            Self::Str(value) => value.trim().parse().ok(),
+            Self::Bool(value) => Some(if *value { 1.0 } else { 0.0 }),
+        }
+    }
+}
+
+/// An OSC message: an address and its arguments
+#[derive(Debug, Clone, PartialEq)]
+pub struct OscMessage {
+    pub address: String,
+    pub args: Vec<OscArg>,
+}
+
+impl OscMessage {
+    pub fn new(address: impl Into<String>, args: Vec<OscArg>) -> Self {
+        Self {
+            address: address.into(),
+            args,
+        }
+    }
+
+    /// Serialize the message as an OSC packet
+    pub fn encode(&self) -> Vec<u8> {
+        let mut out = Vec::new();
+        write_string(&mut out, &self.address);
+        let tags: String = std::iter::once(',')
+            .chain(self.args.iter().map(|arg| match arg {
+                OscArg::Int(_) => 'i',
+                OscArg::Float(_) => 'f',
+                OscArg::Str(_) => 's',
+                OscArg::Bool(true) => 'T',
+                OscArg::Bool(false) => 'F',
+            }))
+            .collect();
+        write_string(&mut out, &tags);
+        for arg in &self.args {
+            match arg {
+                OscArg::Int(value) => out.extend_from_slice(&value.to_be_bytes()),
+                OscArg::Float(value) => out.extend_from_slice(&value.to_be_bytes()),
+                OscArg::Str(value) => write_string(&mut out, value),
+                OscArg::Bool(_) => {}
+            }
+        }
+        out
+    }
+}
+
+/// Write a string with its NUL terminator, padded to four bytes
+fn write_string(out: &mut Vec<u8>, value: &str) {
+    out.extend_from_slice(value.as_bytes());
+    let padding = 4 - value.len() % 4;
+    out.resize(out.len() + padding, 0);
+}
+
+/// Reads the fields of a packet in order
+struct Reader<'a> {
+    data: &'a [u8],
+}
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, len: usize) -> Result<&'a [u8], String> {
+        if self.data.len() < len {
+            return Err("Packet ends early".to_string());
+        }
+        let (field, rest) = self.data.split_at(len);
+        self.data = rest;
+        Ok(field)
+    }
+
+    fn string(&mut self) -> Result<String, String> {
+        let end = self
+            .data
+            .iter()
+            .position(|&byte| byte == 0)
+            .ok_or("String isn't terminated")?;
+        let value = std::str::from_utf8(&self.data[..end])
+            .map_err(|_| "String isn't UTF-8".to_string())?
+            .to_string();
+        self.take((end / 4 + 1) * 4)?;
+        Ok(value)
+    }
+
+    fn word(&mut self) -> Result<[u8; 4], String> {
+        let mut word = [0; 4];
+        word.copy_from_slice(self.take(4)?);
+        Ok(word)
+    }
+}
+
+/// Decode an OSC packet into its messages, flattening bundles
+///
+/// Bundle time tags are ignored; their messages apply at once.
+pub fn decode(packet: &[u8]) -> Result<Vec<OscMessage>, String> {
+    let mut messages = Vec::new();
+    decode_into(packet, &mut messages, 0)?;
+    Ok(messages)
+}
+
+fn decode_into(packet: &[u8], messages: &mut Vec<OscMessage>, depth: usize) -> Result<(), String> {
+    if depth > 8 {
+        return Err("Bundles are nested too deeply".to_string());
+    }
+    let mut reader = Reader { data: packet };
+    if packet.starts_with(b"#bundle\0") {
+        reader.take(16)?;
+        while !reader.data.is_empty() {
+            let len = u32::from_be_bytes(reader.word()?) as usize;
+            decode_into(reader.take(len)?, messages, depth + 1)?;
+        }
+        return Ok(());
+    }
+
+    let address = reader.string()?;
+    if !address.starts_with('/') {
+        return Err(format!("Invalid address: {address}"));
+    }
+    // Very old senders omit the type tags
+    let tags = if reader.data.is_empty() {
+        ",".to_string()
+    } else {
+        reader.string()?
+    };
+    let Some(tags) = tags.strip_prefix(',') else {
+        return Err("Missing type tags".to_string());
+    };
+    let mut args = Vec::new();
+    for tag in tags.chars() {
+        let arg = match tag {
+            'i' => OscArg::Int(i32::from_be_bytes(reader.word()?)),
+            'f' => OscArg::Float(f32::from_be_bytes(reader.word()?)),
+            's' | 'S' => OscArg::Str(reader.string()?),
+            'T' => OscArg::Bool(true),
+            'F' => OscArg::Bool(false),
+            'h' | 'd' | 't' => {
+                reader.take(8)?;
+                continue;
+            }
+            'N' | 'I' => continue,
+            other => return Err(format!("Unsupported argument type '{other}'")),
+        };
+        args.push(arg);
+    }
+    messages.push(OscMessage { address, args });
+    Ok(())
+}
+
+/// The command an incoming message maps to, if any
+///
+/// Buttons on TouchOSC and similar surfaces send `1` when pressed and `0`
+/// when released; a trigger whose first argument is zero is the release
+/// and does nothing, so one press moves one page.
+pub fn command_for(message: &OscMessage) -> Option<WebSocketCommand> {
+    let path = message.address.strip_prefix(ADDRESS_PREFIX)?;
+    let first = message.args.first().and_then(OscArg::as_f64);
+    let released = first == Some(0.0);
+
+    let command = match path {
+        "page/next" | "next" if !released => WebSocketCommand::NextPage,
+        "page/previous" | "previous" | "prev" if !released => WebSocketCommand::PreviousPage,
+        "goto" | "page" => WebSocketCommand::GoToPage {
+            page: first.filter(|page| *page >= 1.0 && page.fract() == 0.0)? as u32,
+        },
+        "section/next" if !released => WebSocketCommand::NextSection,
+        "section/previous" if !released => WebSocketCommand::PreviousSection,
+        "presenter/toggle" if !released => WebSocketCommand::TogglePresenter,
+        "zoom" => WebSocketCommand::SetZoom {
+            zoom: first.filter(|zoom| zoom.is_finite() && *zoom > 0.0)?,
+        },
+        "blackout" => WebSocketCommand::SetBlackout {
+            enabled: first? != 0.0,
+        },
+        "smart-invert" => WebSocketCommand::SetSmartInvert {
+            enabled: first? != 0.0,
+        },
+        "annotations/clear" if !released => WebSocketCommand::ClearAnnotations,
+        "annotations/undo" if !released => WebSocketCommand::UndoAnnotation,
+        "annotations/redo" if !released => WebSocketCommand::RedoAnnotation,
+        _ => return None,
+    };
+    Some(command)
+}
+
+/// The messages sent to the targets for a state change
+pub fn messages_for(event: &WebSocketEvent) -> Vec<OscMessage> {
+    let address = |path: &str| format!("{ADDRESS_PREFIX}{path}");
+    let flag = |enabled: bool| vec![OscArg::Int(i32::from(enabled))];
+    match event {
+        WebSocketEvent::PageChanged {
+            page, total_pages, ..
+        } => vec![
+            OscMessage::new(address("page"), vec![OscArg::Int(*page as i32)]),
+            OscMessage::new(
+                address("total_pages"),
+                vec![OscArg::Int(*total_pages as i32)],
+            ),
+        ],
+        WebSocketEvent::ZoomChanged { zoom } => vec![OscMessage::new(
+            address("zoom"),
+            vec![OscArg::Float(*zoom as f32)],
+        )],
+        WebSocketEvent::PresenterChanged { active } => {
+            vec![OscMessage::new(address("presenter"), flag(*active))]
+        }
+        WebSocketEvent::BlackoutChanged { enabled } => {
+            vec![OscMessage::new(address("blackout"), flag(*enabled))]
+        }
+        WebSocketEvent::SmartInvertChanged { enabled } => {
+            vec![OscMessage::new(address("smart-invert"), flag(*enabled))]
+        }
+        WebSocketEvent::PdfOpened {
+            title, page_count, ..
+        } => vec![
+            OscMessage::new(
+                address("document"),
+                vec![OscArg::Str(title.clone().unwrap_or_default())],
+            ),
+            OscMessage::new(
+                address("total_pages"),
+                vec![OscArg::Int(*page_count as i32)],
+            ),
+        ],
+        WebSocketEvent::PdfClosed => vec![OscMessage::new(
+            address("document"),
+            vec![OscArg::Str(String::new())],
+        )],
+        _ => Vec::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_encode_decode() {
+        let message = OscMessage::new(
+            "/streamslate/goto",
+            vec![
+                OscArg::Int(12),
+                OscArg::Float(1.5),
+                OscArg::Str("deck".to_string()),
+                OscArg::Bool(true),
+            ],
+        );
+        let packet = message.encode();
+        assert_eq!(packet.len() % 4, 0);
+        assert_eq!(&packet[..20], b"/streamslate/goto\0\0\0");
+        assert_eq!(decode(&packet).unwrap(), std::slice::from_ref(&message));
+
+        // A bundle holding the message twice
+        let mut bundle = b"#bundle\0\0\0\0\0\0\0\0\x01".to_vec();
+        for _ in 0..2 {
+            bundle.extend_from_slice(&(packet.len() as u32).to_be_bytes());
+            bundle.extend_from_slice(&packet);
+        }
+        assert_eq!(decode(&bundle).unwrap(), [message.clone(), message]);
+
+        assert!(decode(b"/streamslate/goto\0\0\0,i\0\0").is_err());
+        assert!(decode(b"streamslate\0").is_err());
+    }
+
+    #[test]
+    fn test_command_for() {
+        let command =
+            |address: &str, args: Vec<OscArg>| command_for(&OscMessage::new(address, args));
+
+        assert!(matches!(
+            command("/streamslate/page/next", vec![]),
+            Some(WebSocketCommand::NextPage)
+        ));
+        assert!(matches!(
+            command("/streamslate/next", vec![OscArg::Float(1.0)]),
+            Some(WebSocketCommand::NextPage)
+        ));
+        // Button release
+        assert!(command("/streamslate/next", vec![OscArg::Float(0.0)]).is_none());
+        assert!(matches!(
+            command("/streamslate/goto", vec![OscArg::Int(12)]),
+            Some(WebSocketCommand::GoToPage { page: 12 })
+        ));
+        assert!(matches!(
+            command("/streamslate/goto", vec![OscArg::Str("7".to_string())]),
+            Some(WebSocketCommand::GoToPage { page: 7 })
+        ));
+        assert!(command("/streamslate/goto", vec![OscArg::Float(2.5)]).is_none());
+        assert!(matches!(
+            command("/streamslate/zoom", vec![OscArg::Float(1.5)]),
+            Some(WebSocketCommand::SetZoom { zoom }) if zoom == 1.5
+        ));
+        assert!(matches!(
+            command("/streamslate/blackout", vec![OscArg::Bool(true)]),
+            Some(WebSocketCommand::SetBlackout { enabled: true })
+        ));
+        assert!(command("/qlab/go", vec![]).is_none());
+    }
+}
@@ -0,0 +1,130 @@
+/*
+ * This file is part of StreamSlate.
+ * Copyright (C) 2025 StreamSlate Contributors
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+
+//! OSC endpoint task
+//!
+//! One UDP socket both receives commands and sends state changes to the
+//! targets, so desks that reply to the sender's port see them too.
+
+use super::config::OscConfig;
+use super::{command_for, decode, messages_for};
+use crate::state::AppState;
+use crate::websocket::{self, access};
+use std::io::ErrorKind;
+use std::net::SocketAddr;
+use std::sync::{Arc, Mutex};
+use tauri::AppHandle;
+use tokio::net::UdpSocket;
+use tokio::sync::broadcast::error::RecvError;
+use tokio::task::JoinHandle;
+use tracing::{debug, info};
+
+/// Largest UDP packet read
+const MAX_PACKET_BYTES: usize = 64 * 1024;
+
+/// The running endpoint
+static TASK: Mutex<Option<JoinHandle<()>>> = Mutex::new(None);
+
+/// Start the endpoint, replacing the running one
+///
+/// With OSC turned off this only stops the running endpoint. Commands run
+/// with the default client role, since OSC senders can't present a token.
+pub async fn start(
+    config: &OscConfig,
+    state: Arc<AppState>,
+    app_handle: AppHandle,
+) -> std::io::Result<()> {
+    stop().await;
+    if !config.enabled {
+        return Ok(());
+    }
+    let invalid = |e: crate::error::StreamSlateError| {
+        std::io::Error::new(ErrorKind::InvalidInput, e.to_string())
+    };
+    let addr = config.addr().map_err(invalid)?;
+    let targets = config.target_addrs().map_err(invalid)?;
+    let socket = UdpSocket::bind(addr).await?;
+    let (_, client_events) = websocket::channels(&state);
+    let mut events = client_events.subscribe();
+    info!(%addr, targets = targets.len(), "OSC endpoint started");
+
+    let task = tokio::spawn(async move {
+        let mut buf = vec![0u8; MAX_PACKET_BYTES];
+        loop {
+            tokio::select! {
+                received = socket.recv_from(&mut buf) => match received {
+                    Ok((len, peer)) => handle_packet(&buf[..len], peer, &state, &app_handle),
+                    // Includes unreachable targets reported back on some platforms
+                    Err(e) => debug!(error = %e, "OSC receive error"),
+                },
+                event = events.recv() => match event {
+                    Ok(sequenced) => {
+                        for message in messages_for(&sequenced.event) {
+                            let packet = message.encode();
+                            for target in &targets {
+                                if let Err(e) = socket.send_to(&packet, target).await {
+                                    debug!(%target, error = %e, "Failed to send OSC message");
+                                }
+                            }
+                        }
+                    }
+                    Err(RecvError::Lagged(skipped)) => {
+                        debug!(skipped, "OSC endpoint lagged behind on state changes");
+                    }
+                    Err(RecvError::Closed) => break,
+                },
+            }
+        }
+    });
+    if let Ok(mut running) = TASK.lock() {
+        *running = Some(task);
+    }
+    Ok(())
+}
+
+/// Stop the endpoint, waiting until its port is released
+pub async fn stop() {
+    let task = TASK.lock().ok().and_then(|mut running| running.take());
+    if let Some(task) = task {
+        task.abort();
+        let _ = task.await;
+        info!("OSC endpoint stopped");
+    }
+}
+
+/// Run the commands of a received packet
+fn handle_packet(packet: &[u8], peer: SocketAddr, state: &Arc<AppState>, app_handle: &AppHandle) {
+    let messages = match decode(packet) {
+        Ok(messages) => messages,
+        Err(e) => {
+            debug!(%peer, error = %e, "Ignoring invalid OSC packet");
+            return;
+        }
+    };
+    for message in messages {
+        let Some(command) = command_for(&message) else {
+            debug!(%peer, address = %message.address, "Unhandled OSC message");
+            continue;
+        };
+        let role = access::default_client_role();
+        let reply = websocket::handle_command_as(command, role, peer, state, app_handle);
+        if reply.is_failure() {
+            debug!(%peer, address = %message.address, ?reply, "OSC command failed");
+        }
+    }
+}
@@ -0,0 +1,282 @@
+/*
+ * This file is part of StreamSlate.
+ * Copyright (C) 2025 StreamSlate Contributors
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+
+//! Embedded file attachments
+//!
+//! Reads the `/EmbeddedFiles` name tree from the document catalog. Show
+//! rundowns frequently ship CSVs or notes attached to the PDF itself.
+
+use super::{decode_text_string, resolve};
+use crate::error::{Result, StreamSlateError};
+use flate2::read::ZlibDecoder;
+use lopdf::{Dictionary, Document, Object, Stream};
+use serde::{Deserialize, Serialize};
+use std::io::Read;
+
+/// Guard against malicious, cyclic name trees
+const MAX_NAME_TREE_DEPTH: usize = 32;
+
+/// Metadata about a file embedded in the PDF
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PdfAttachment {
+    /// Key in the `/EmbeddedFiles` name tree (used to extract the file)
+    pub name: String,
+    /// File name suggested by the file specification
+    pub file_name: String,
+    pub description: Option<String>,
+    pub mime_type: Option<String>,
+    /// Uncompressed size in bytes, if declared
+    pub size: Option<u64>,
+}
+
+/// List all files in the document's `/EmbeddedFiles` name tree
+pub fn list_attachments(document: &Document) -> Vec<PdfAttachment> {
+    embedded_file_specs(document)
+        .into_iter()
+        .map(|(name, spec)| describe_attachment(document, name, spec))
+        .collect()
+}
+
+/// Return the decoded contents of the attachment with the given name-tree key
+pub fn extract_attachment(document: &Document, name: &str) -> Result<Vec<u8>> {
+    let (_, spec) = embedded_file_specs(document)
+        .into_iter()
+        .find(|(key, _)| key == name)
+        .ok_or_else(|| StreamSlateError::Other(format!("Attachment '{name}' not found")))?;
+
+    let stream = embedded_stream(document, spec)
+        .ok_or_else(|| StreamSlateError::InvalidPdf(format!("Attachment '{name}' has no data")))?;
+
+    decode_stream(stream).map_err(|e| {
+        StreamSlateError::InvalidPdf(format!("Attachment '{name}' can't be decompressed: {e}"))
+    })
+}
+
+/// The decoded contents of a stream
+///
+/// lopdf passes over errors in Flate data and returns what it decoded up to
+/// them, so plain Flate streams, what embedded files almost always use, are
+/// inflated here instead.
+fn decode_stream(stream: &Stream) -> std::result::Result<Vec<u8>, String> {
+    let filters = match stream.dict.get(b"Filter") {
+        Ok(_) => stream.filters().map_err(|e| e.to_string())?,
+        Err(_) => return Ok(stream.content.clone()),
+    };
+    if filters == ["FlateDecode"] && !stream.dict.has(b"DecodeParms") {
+        let mut data = Vec::new();
+        ZlibDecoder::new(stream.content.as_slice())
+            .read_to_end(&mut data)
+            .map_err(|e| e.to_string())?;
+        return Ok(data);
+    }
+    stream.decompressed_content().map_err(|e| e.to_string())
+}
+
+/// The embedded file stream of a file specification, `/UF` before `/F`
+fn embedded_stream<'a>(document: &'a Document, spec: &'a Dictionary) -> Option<&'a Stream> {
+    let ef = spec
+        .get(b"EF")
+        .map(|obj| resolve(document, obj))
+        .and_then(Object::as_dict)
+        .ok()?;
+    ef.get(b"UF")
+        .or_else(|_| ef.get(b"F"))
+        .map(|obj| resolve(document, obj))
+        .and_then(Object::as_stream)
+        .ok()
+}
+
+/// Collect (name, file specification) pairs from the EmbeddedFiles name tree
+fn embedded_file_specs(document: &Document) -> Vec<(String, &Dictionary)> {
+    let mut specs = Vec::new();
+
+    let root = document
+        .catalog()
+        .and_then(|catalog| catalog.get(b"Names"))
+        .map(|obj| resolve(document, obj))
+        .and_then(Object::as_dict)
+        .and_then(|names| names.get(b"EmbeddedFiles"))
+        .map(|obj| resolve(document, obj))
+        .and_then(Object::as_dict);
+
+    if let Ok(root) = root {
+        collect_name_tree(document, root, 0, &mut specs);
+    }
+
+    specs
+}
+
+fn collect_name_tree<'a>(
+    document: &'a Document,
+    node: &'a Dictionary,
+    depth: usize,
+    out: &mut Vec<(String, &'a Dictionary)>,
+) {
+    if depth > MAX_NAME_TREE_DEPTH {
+        return;
+    }
+
+    if let Ok(names) = node.get(b"Names").and_then(Object::as_array) {
+        for pair in names.chunks_exact(2) {
+            let key = match resolve(document, &pair[0]) {
+                Object::String(bytes, _) => decode_text_string(bytes),
+                _ => continue,
+            };
+            if let Ok(spec) = resolve(document, &pair[1]).as_dict() {
+                out.push((key, spec));
+            }
+        }
+    }
+
+    if let Ok(kids) = node.get(b"Kids").and_then(Object::as_array) {
+        for kid in kids {
+            if let Ok(kid) = resolve(document, kid).as_dict() {
+                collect_name_tree(document, kid, depth + 1, out);
+            }
+        }
+    }
+}
+
+fn describe_attachment(document: &Document, name: String, spec: &Dictionary) -> PdfAttachment {
+    let text = |key: &[u8]| match spec.get(key).map(|obj| resolve(document, obj)) {
+        Ok(Object::String(bytes, _)) => Some(decode_text_string(bytes)),
+        _ => None,
+    };
+
+    let file_name = text(b"UF")
+        .or_else(|| text(b"F"))
+        .unwrap_or_else(|| name.clone());
+    let description = text(b"Desc");
+
+    let stream = embedded_stream(document, spec);
+
+    let mime_type = stream
+        .and_then(|s| s.dict.get(b"Subtype").ok())
+        .and_then(|obj| obj.as_name_str().ok())
+        .map(String::from);
+
+    let size = stream
+        .and_then(|s| s.dict.get(b"Params").ok())
+        .map(|obj| resolve(document, obj))
+        .and_then(|params| params.as_dict().ok())
+        .and_then(|params| params.get(b"Size").ok())
+        .and_then(|size| size.as_i64().ok())
+        .and_then(|size| u64::try_from(size).ok());
+
+    PdfAttachment {
+        name,
+        file_name,
+        description,
+        mime_type,
+        size,
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use lopdf::{dictionary, Stream};
+
+    /// Build a minimal document with a single embedded file
+    pub(crate) fn document_with_attachment(file_name: &str, data: &[u8]) -> Document {
+        let mut doc = Document::with_version("1.7");
+
+        let file_stream = Stream::new(
+            dictionary! {
+                "Type" => "EmbeddedFile",
+                "Subtype" => Object::Name(b"text/csv".to_vec()),
+                "Params" => dictionary! { "Size" => data.len() as i64 },
+            },
+            data.to_vec(),
+        );
+        let stream_id = doc.add_object(file_stream);
+
+        let spec_id = doc.add_object(dictionary! {
+            "Type" => "Filespec",
+            "F" => Object::string_literal(file_name),
+            "Desc" => Object::string_literal("Show rundown"),
+            "EF" => dictionary! { "F" => stream_id },
+        });
+
+        let names_id = doc.add_object(dictionary! {
+            "EmbeddedFiles" => dictionary! {
+                "Names" => vec![Object::string_literal(file_name), spec_id.into()],
+            },
+        });
+
+        let catalog_id = doc.add_object(dictionary! {
+            "Type" => "Catalog",
+            "Names" => names_id,
+        });
+        doc.trailer.set("Root", catalog_id);
+        doc
+    }
+
+    #[test]
+    fn test_list_attachments() {
+        let doc = document_with_attachment("rundown.csv", b"segment,start\nintro,0\n");
+        let attachments = list_attachments(&doc);
+
+        assert_eq!(attachments.len(), 1);
+        assert_eq!(attachments[0].file_name, "rundown.csv");
+        assert_eq!(attachments[0].description.as_deref(), Some("Show rundown"));
+        assert_eq!(attachments[0].mime_type.as_deref(), Some("text/csv"));
+        assert_eq!(attachments[0].size, Some(22));
+    }
+
+    #[test]
+    fn test_extract_attachment() {
+        let doc = document_with_attachment("notes.txt", b"cue lights");
+        assert_eq!(
+            extract_attachment(&doc, "notes.txt").unwrap(),
+            b"cue lights"
+        );
+        assert!(extract_attachment(&doc, "missing.txt").is_err());
+
+        let text = "cue lights\n".repeat(20);
+        let mut compressed = document_with_attachment("notes.txt", b"");
+        for object in compressed.objects.values_mut() {
+            if let Object::Stream(stream) = object {
+                stream.set_plain_content(text.as_bytes().to_vec());
+                stream.compress().unwrap();
+                assert!(stream.dict.has(b"Filter"));
+            }
+        }
+        assert_eq!(
+            extract_attachment(&compressed, "notes.txt").unwrap(),
+            text.as_bytes()
+        );
+
+        // A stream that claims compression but isn't compressed
+        let mut doc = document_with_attachment("notes.txt", b"not deflate data");
+        for object in doc.objects.values_mut() {
+            if let Object::Stream(stream) = object {
+                stream
+                    .dict
+                    .set("Filter", Object::Name(b"FlateDecode".to_vec()));
+            }
+        }
+        assert!(extract_attachment(&doc, "notes.txt").is_err());
+    }
+
+    #[test]
+    fn test_no_attachments() {
+        let doc = Document::with_version("1.7");
+        assert!(list_attachments(&doc).is_empty());
+    }
+}
@@ -0,0 +1,462 @@
+/*
+ * This file is part of StreamSlate.
+ * Copyright (C) 2025 StreamSlate Contributors
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+
+//! Page-by-page comparison of two documents
+//!
+//! Pages are paired by number. Text is compared word by word; the visual
+//! side compares page size, rotation and a digest of everything the page
+//! draws (its content streams plus the images and forms they reference), so
+//! a swapped chart or re-exported slide shows up even when the text matches.
+//! The digest covers decoded content: content streams are re-serialised
+//! operator by operator and Flate images are inflated, so recompressing or
+//! reformatting a file doesn't count as a change. JPEG and JPEG 2000 images
+//! are compared as encoded.
+
+use super::image::inflate;
+use super::resolve;
+use super::text::{page_resources, page_words, PageWord};
+use crate::error::Result;
+use lopdf::content::Content;
+use lopdf::{Document, Object, ObjectId, Stream};
+use ring::digest::{Context, SHA256};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
+use std::collections::HashSet;
+
+/// Maximum nesting of form XObjects included in the visual digest
+const MAX_FORM_DEPTH: usize = 8;
+
+/// Pages with more words than this are compared as a whole rather than diffed
+const MAX_DIFF_WORDS: usize = 5000;
+
+/// How a page differs between the two documents
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum PageStatus {
+    Unchanged,
+    Changed,
+    /// Only in the second document
+    Added,
+    /// Only in the first document
+    Removed,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum TextChangeKind {
+    /// In the second document only
+    Inserted,
+    /// In the first document only
+    Deleted,
+}
+
+/// A run of consecutive inserted or deleted words
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct TextChange {
+    pub kind: TextChangeKind,
+    pub text: String,
+    /// Word boxes on the page the words appear on (second document for
+    /// insertions, first for deletions), for highlighting the change
+    pub words: Vec<PageWord>,
+}
+
+/// Differences on one page
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct PageDiff {
+    pub page: u32,
+    pub status: PageStatus,
+    pub text_changes: Vec<TextChange>,
+    /// Page size or rotation differs
+    pub size_changed: bool,
+    /// Decoded drawing operators or image samples differ
+    pub visual_changed: bool,
+}
+
+/// Result of comparing two documents
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct PdfComparison {
+    pub page_count_a: u32,
+    pub page_count_b: u32,
+    pub pages: Vec<PageDiff>,
+}
+
+impl PdfComparison {
+    /// Whether no page differs
+    pub fn is_identical(&self) -> bool {
+        self.pages
+            .iter()
+            .all(|page| page.status == PageStatus::Unchanged)
+    }
+}
+
+/// Compare two documents page by page
+pub fn compare_documents(a: &Document, b: &Document) -> Result<PdfComparison> {
+    let pages_a = a.get_pages();
+    let pages_b = b.get_pages();
+    let count = pages_a.len().max(pages_b.len()) as u32;
+
+    let mut pages = Vec::with_capacity(count as usize);
+    for page in 1..=count {
+        let diff = match (pages_a.get(&page), pages_b.get(&page)) {
+            (Some(&id_a), Some(&id_b)) => compare_page(a, id_a, b, id_b, page)?,
+            (Some(_), None) => PageDiff {
+                page,
+                status: PageStatus::Removed,
+                text_changes: whole_page_change(TextChangeKind::Deleted, page_words(a, page)?),
+                size_changed: false,
+                visual_changed: true,
+            },
+            (None, _) => PageDiff {
+                page,
+                status: PageStatus::Added,
+                text_changes: whole_page_change(TextChangeKind::Inserted, page_words(b, page)?),
+                size_changed: false,
+                visual_changed: true,
+            },
+        };
+        pages.push(diff);
+    }
+
+    Ok(PdfComparison {
+        page_count_a: pages_a.len() as u32,
+        page_count_b: pages_b.len() as u32,
+        pages,
+    })
+}
+
+fn compare_page(
+    a: &Document,
+    id_a: ObjectId,
+    b: &Document,
+    id_b: ObjectId,
+    page: u32,
+) -> Result<PageDiff> {
+    let text_changes = diff_words(page_words(a, page)?, page_words(b, page)?);
+    let size_changed = page_geometry(a, id_a) != page_geometry(b, id_b);
+    let visual_changed = page_digest(a, id_a) != page_digest(b, id_b);
+
+    let status = if text_changes.is_empty() && !size_changed && !visual_changed {
+        PageStatus::Unchanged
+    } else {
+        PageStatus::Changed
+    };
+    Ok(PageDiff {
+        page,
+        status,
+        text_changes,
+        size_changed,
+        visual_changed,
+    })
+}
+
+fn whole_page_change(kind: TextChangeKind, words: Vec<PageWord>) -> Vec<TextChange> {
+    if words.is_empty() {
+        return Vec::new();
+    }
+    vec![text_change(kind, words)]
+}
+
+fn text_change(kind: TextChangeKind, words: Vec<PageWord>) -> TextChange {
+    let text = words
+        .iter()
+        .map(|word| word.text.as_str())
+        .collect::<Vec<_>>()
+        .join(" ");
+    TextChange { kind, text, words }
+}
+
+/// Word-level diff (longest common subsequence), grouped into runs
+fn diff_words(a: Vec<PageWord>, b: Vec<PageWord>) -> Vec<TextChange> {
+    if a.len() > MAX_DIFF_WORDS || b.len() > MAX_DIFF_WORDS {
+        if a.iter().map(|w| &w.text).eq(b.iter().map(|w| &w.text)) {
+            return Vec::new();
+        }
+        let mut changes = whole_page_change(TextChangeKind::Deleted, a);
+        changes.extend(whole_page_change(TextChangeKind::Inserted, b));
+        return changes;
+    }
+
+    let text_a: Vec<&str> = a.iter().map(|w| w.text.as_str()).collect();
+    let text_b: Vec<&str> = b.iter().map(|w| w.text.as_str()).collect();
+    let mut matched = Vec::new();
+    align(&text_a, &text_b, (0, 0), &mut matched);
+
+    let mut changes: Vec<TextChange> = Vec::new();
+    let mut push = |kind: TextChangeKind, word: &PageWord| match changes.last_mut() {
+        Some(last) if last.kind == kind => {
+            last.text.push(' ');
+            last.text.push_str(&word.text);
+            last.words.push(word.clone());
+        }
+        _ => changes.push(text_change(kind, vec![word.clone()])),
+    };
+
+    // Between matched words, insertions come before deletions
+    let (mut i, mut j) = (0, 0);
+    for (next_i, next_j) in matched.into_iter().chain([(a.len(), b.len())]) {
+        for word in &b[j..next_j] {
+            push(TextChangeKind::Inserted, word);
+        }
+        for word in &a[i..next_i] {
+            push(TextChangeKind::Deleted, word);
+        }
+        (i, j) = (next_i + 1, next_j + 1);
+    }
+    changes
+}
+
+/// Positions of the words of a longest common subsequence of `a` and `b`,
+/// offset by `origin`, in order
+///
+/// Hirschberg's divide and conquer, so memory stays linear in the number
+/// of words rather than growing with the product of both pages.
+fn align(a: &[&str], b: &[&str], origin: (usize, usize), matched: &mut Vec<(usize, usize)>) {
+    if a.is_empty() || b.is_empty() {
+        return;
+    }
+    if let [word] = a {
+        if let Some(j) = b.iter().position(|w| w == word) {
+            matched.push((origin.0, origin.1 + j));
+        }
+        return;
+    }
+
+    // Split b where the halves of a line up best
+    let mid = a.len() / 2;
+    let forward = lcs_lengths(a[..mid].iter(), b.iter());
+    let backward = lcs_lengths(a[mid..].iter().rev(), b.iter().rev());
+    let m = b.len();
+    let split = (0..=m)
+        .max_by_key(|&k| (forward[k] + backward[m - k], std::cmp::Reverse(k)))
+        .unwrap_or(0);
+
+    align(&a[..mid], &b[..split], origin, matched);
+    align(
+        &a[mid..],
+        &b[split..],
+        (origin.0 + mid, origin.1 + split),
+        matched,
+    );
+}
+
+/// LCS length of all of `a` and each prefix of `b`, one row at a time
+fn lcs_lengths<'a>(
+    a: impl Iterator<Item = &'a &'a str>,
+    b: impl Iterator<Item = &'a &'a str> + Clone,
+) -> Vec<u32> {
+    let len = b.clone().count();
+    let mut row = vec![0u32; len + 1];
+    for word in a {
+        let mut diagonal = 0;
+        for (j, other) in b.clone().enumerate() {
+            let above = row[j + 1];
+            row[j + 1] = if word == other {
+                diagonal + 1
+            } else {
+                above.max(row[j])
+            };
+            diagonal = above;
+        }
+    }
+    row
+}
+
+/// Page box and rotation, inherited from parent nodes where absent
+fn page_geometry(document: &Document, page_id: ObjectId) -> (Vec<f64>, i64) {
+    let inherited = |key: &[u8]| {
+        let mut node = document.get_dictionary(page_id).ok();
+        while let Some(dict) = node {
+            if let Ok(value) = dict.get(key) {
+                return Some(resolve(document, value));
+            }
+            node = dict
+                .get(b"Parent")
+                .and_then(Object::as_reference)
+                .and_then(|id| document.get_dictionary(id))
+                .ok();
+        }
+        None
+    };
+
+    let media_box = match inherited(b"MediaBox") {
+        Some(Object::Array(values)) => values
+            .iter()
+            .filter_map(|v| v.as_float().ok().map(f64::from))
+            .collect(),
+        _ => Vec::new(),
+    };
+    let rotation = inherited(b"Rotate")
+        .and_then(|r| r.as_i64().ok())
+        .unwrap_or(0)
+        .rem_euclid(360);
+    (media_box, rotation)
+}
+
+/// Digest of a page's decoded content streams and the XObjects they draw
+fn page_digest(document: &Document, page_id: ObjectId) -> Vec<u8> {
+    let mut context = Context::new(&SHA256);
+    let content = document.get_page_content(page_id).unwrap_or_default();
+    context.update(&normalized_content(content));
+
+    let mut xobjects: Vec<_> = page_resources(document, page_id)
+        .xobjects
+        .into_iter()
+        .collect();
+    xobjects.sort();
+    let mut visited = HashSet::new();
+    for (name, id) in xobjects {
+        context.update(&name);
+        digest_xobject(document, id, &mut context, &mut visited, 0);
+    }
+    context.finish().as_ref().to_vec()
+}
+
+fn digest_xobject(
+    document: &Document,
+    id: ObjectId,
+    context: &mut Context,
+    visited: &mut HashSet<ObjectId>,
+    depth: usize,
+) {
+    if depth > MAX_FORM_DEPTH || !visited.insert(id) {
+        return;
+    }
+    let Ok(stream) = document.get_object(id).and_then(Object::as_stream) else {
+        return;
+    };
+    let is_image = matches!(
+        stream.dict.get(b"Subtype").and_then(Object::as_name),
+        Ok(b"Image")
+    );
+    if is_image {
+        context.update(&image_samples(stream));
+    } else {
+        match stream.decompressed_content() {
+            Ok(content) => context.update(&normalized_content(content)),
+            Err(_) => context.update(&stream.content),
+        }
+    }
+
+    let nested = stream
+        .dict
+        .get(b"Resources")
+        .map(|r| resolve(document, r))
+        .and_then(Object::as_dict)
+        .and_then(|r| r.get(b"XObject"))
+        .map(|x| resolve(document, x))
+        .and_then(Object::as_dict);
+    if let Ok(nested) = nested {
+        let mut children: Vec<_> = nested
+            .iter()
+            .filter_map(|(name, value)| Some((name.clone(), value.as_reference().ok()?)))
+            .collect();
+        children.sort();
+        for (name, child) in children {
+            context.update(&name);
+            digest_xobject(document, child, context, visited, depth + 1);
+        }
+    }
+}
+
+/// Content stream operators re-serialised, so spacing and line breaks
+/// don't matter; unparseable content is kept as is
+fn normalized_content(content: Vec<u8>) -> Vec<u8> {
+    Content::decode(&content)
+        .and_then(|operations| operations.encode())
+        .unwrap_or(content)
+}
+
+/// Decoded samples of an image, or its encoded bytes when they can't be
+/// decoded here
+fn image_samples(stream: &Stream) -> Cow<'_, [u8]> {
+    match stream.filters().unwrap_or_default().as_slice() {
+        [f] if f == "FlateDecode" => match inflate(stream) {
+            Ok(samples) => Cow::Owned(samples),
+            Err(_) => Cow::Borrowed(&stream.content),
+        },
+        _ => Cow::Borrowed(&stream.content),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pdf::tests::document_with_pages;
+
+    fn text(s: &str) -> String {
+        format!("BT /F1 10 Tf 72 700 Td ({s}) Tj ET")
+    }
+
+    #[test]
+    fn test_identical_documents() {
+        let a = document_with_pages(&[&text("Q3 revenue up")]);
+        let b = document_with_pages(&[&text("Q3 revenue up")]);
+        let comparison = compare_documents(&a, &b).unwrap();
+        assert!(comparison.is_identical());
+        assert_eq!(comparison.pages[0].status, PageStatus::Unchanged);
+    }
+
+    #[test]
+    fn test_text_changes_and_added_page() {
+        let a = document_with_pages(&[&text("Q3 revenue up 12 percent")]);
+        let b = document_with_pages(&[&text("Q3 revenue up 15 percent"), &text("Questions")]);
+        let comparison = compare_documents(&a, &b).unwrap();
+
+        assert!(!comparison.is_identical());
+        assert_eq!((comparison.page_count_a, comparison.page_count_b), (1, 2));
+
+        let page = &comparison.pages[0];
+        assert_eq!(page.status, PageStatus::Changed);
+        assert!(!page.size_changed);
+        let changes: Vec<_> = page
+            .text_changes
+            .iter()
+            .map(|c| (c.kind, c.text.as_str()))
+            .collect();
+        assert_eq!(
+            changes,
+            [
+                (TextChangeKind::Inserted, "15"),
+                (TextChangeKind::Deleted, "12")
+            ]
+        );
+        assert_eq!(page.text_changes[0].words[0].x, 142.0);
+
+        assert_eq!(comparison.pages[1].status, PageStatus::Added);
+        assert_eq!(comparison.pages[1].text_changes[0].text, "Questions");
+    }
+
+    #[test]
+    fn test_visual_only_change() {
+        let a = document_with_pages(&["0 0 1 rg 10 10 100 100 re f"]);
+        let b = document_with_pages(&["1 0 0 rg 10 10 100 100 re f"]);
+        let page = &compare_documents(&a, &b).unwrap().pages[0];
+        assert_eq!(page.status, PageStatus::Changed);
+        assert!(page.visual_changed);
+        assert!(page.text_changes.is_empty());
+    }
+
+    #[test]
+    fn test_reformatted_content_is_unchanged() {
+        let a = document_with_pages(&["0 0 1 rg 10 10 100 100 re f"]);
+        let mut b = document_with_pages(&["0 0 1 rg\n10  10 100 100 re\nf\n"]);
+        b.compress();
+        assert!(compare_documents(&a, &b).unwrap().is_identical());
+    }
+}
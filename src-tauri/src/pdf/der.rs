@@ -0,0 +1,168 @@
+/*
+ * This file is part of StreamSlate.
+ * Copyright (C) 2025 StreamSlate Contributors
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+
+//! Minimal DER/BER reader
+//!
+//! Just enough ASN.1 to walk CMS signatures embedded in PDFs: single-byte
+//! tags, definite lengths, and the indefinite lengths some signers emit.
+
+/// Universal and context-specific tags used by CMS and X.509
+pub const INTEGER: u8 = 0x02;
+pub const BIT_STRING: u8 = 0x03;
+pub const OCTET_STRING: u8 = 0x04;
+pub const OID: u8 = 0x06;
+pub const SEQUENCE: u8 = 0x30;
+pub const SET: u8 = 0x31;
+pub const CONTEXT_0: u8 = 0xA0;
+
+/// Maximum nesting accepted when scanning indefinite-length values
+const MAX_DEPTH: usize = 64;
+
+/// A decoded tag-length-value element
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Tlv<'a> {
+    pub tag: u8,
+    /// Value bytes (excluding tag, length, and end-of-contents octets)
+    pub contents: &'a [u8],
+    /// The complete encoding including the header
+    pub raw: &'a [u8],
+}
+
+impl<'a> Tlv<'a> {
+    /// Decode the child elements of a constructed value
+    pub fn children(&self) -> Option<Vec<Tlv<'a>>> {
+        let mut rest = self.contents;
+        let mut children = Vec::new();
+        while !rest.is_empty() {
+            let (child, next) = read(rest)?;
+            children.push(child);
+            rest = next;
+        }
+        Some(children)
+    }
+
+    /// Return `Some(self)` if the tag matches
+    pub fn expect(self, tag: u8) -> Option<Self> {
+        (self.tag == tag).then_some(self)
+    }
+}
+
+/// Read one element, returning it and the remaining input
+pub fn read(input: &[u8]) -> Option<(Tlv<'_>, &[u8])> {
+    read_at_depth(input, 0)
+}
+
+fn read_at_depth(input: &[u8], depth: usize) -> Option<(Tlv<'_>, &[u8])> {
+    if depth > MAX_DEPTH {
+        return None;
+    }
+
+    let tag = *input.first()?;
+    // Multi-byte tag numbers never appear in the structures we read
+    if tag & 0x1F == 0x1F {
+        return None;
+    }
+    let first_len = *input.get(1)?;
+
+    if first_len == 0x80 {
+        // Indefinite length: constructed only, terminated by 00 00
+        if tag & 0x20 == 0 {
+            return None;
+        }
+        let body = &input[2..];
+        let mut rest = body;
+        loop {
+            if rest.starts_with(&[0, 0]) {
+                let consumed = body.len() - rest.len();
+                let total = 2 + consumed + 2;
+                return Some((
+                    Tlv {
+                        tag,
+                        contents: &body[..consumed],
+                        raw: &input[..total],
+                    },
+                    &input[total..],
+                ));
+            }
+            let (_, next) = read_at_depth(rest, depth + 1)?;
+            rest = next;
+        }
+    }
+
+    let (len, header) = if first_len < 0x80 {
+        (first_len as usize, 2)
+    } else {
+        let count = (first_len & 0x7F) as usize;
+        if count > 4 {
+            return None;
+        }
+        let bytes = input.get(2..2 + count)?;
+        let len = bytes.iter().fold(0usize, |acc, &b| (acc << 8) | b as usize);
+        (len, 2 + count)
+    };
+
+    let end = header.checked_add(len)?;
+    let contents = input.get(header..end)?;
+    Some((
+        Tlv {
+            tag,
+            contents,
+            raw: &input[..end],
+        },
+        &input[end..],
+    ))
+}
+
+/// Strip the unused-bits octet from a BIT STRING value
+pub fn bit_string_bytes(tlv: Tlv<'_>) -> Option<&[u8]> {
+    let tlv = tlv.expect(BIT_STRING)?;
+    match tlv.contents.split_first() {
+        Some((0, bytes)) => Some(bytes),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_read_definite_and_long_form() {
+        let mut long = vec![OCTET_STRING, 0x81, 200];
+        long.extend(std::iter::repeat(7).take(200));
+        let input = [&[SEQUENCE, 0x03, INTEGER, 0x01, 0x05][..], &long].concat();
+
+        let (seq, rest) = read(&input).unwrap();
+        assert_eq!(seq.children().unwrap()[0].contents, &[5]);
+        let (octets, rest) = read(rest).unwrap();
+        assert_eq!(octets.contents.len(), 200);
+        assert!(rest.is_empty());
+    }
+
+    #[test]
+    fn test_read_indefinite_length() {
+        let input = [SEQUENCE, 0x80, INTEGER, 0x01, 0x2A, 0x00, 0x00, 0xFF];
+        let (seq, rest) = read(&input).unwrap();
+        assert_eq!(seq.children().unwrap().len(), 1);
+        assert_eq!(seq.raw.len(), 7);
+        assert_eq!(rest, &[0xFF]);
+
+        // Truncated input is rejected rather than panicking
+        assert!(read(&[SEQUENCE, 0x05, INTEGER]).is_none());
+    }
+}
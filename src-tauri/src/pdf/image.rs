@@ -0,0 +1,202 @@
+/*
+ * This file is part of StreamSlate.
+ * Copyright (C) 2025 StreamSlate Contributors
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+
+//! Image XObject sample decoding and resampling
+//!
+//! Shared by OCR (which hands page images to tesseract) and optimization
+//! (which downsamples oversized images).
+
+use super::resolve;
+use crate::error::{Result, StreamSlateError};
+use lopdf::{Document, Object, Stream};
+use std::io::Read;
+
+/// Inflate a `FlateDecode` image stream and undo any PNG predictor
+pub(crate) fn inflate(stream: &Stream) -> Result<Vec<u8>> {
+    let mut data = Vec::new();
+    flate2::read::ZlibDecoder::new(stream.content.as_slice())
+        .read_to_end(&mut data)
+        .map_err(|e| StreamSlateError::InvalidPdf(format!("Corrupt image data: {e}")))?;
+    undo_png_predictor(stream, data)
+}
+
+pub(crate) fn int_param(stream: &Stream, key: &[u8]) -> Option<i64> {
+    stream
+        .dict
+        .get(b"DecodeParms")
+        .and_then(Object::as_dict)
+        .and_then(|params| params.get(key))
+        .and_then(Object::as_i64)
+        .ok()
+}
+
+/// Reverse PNG row predictors (`Predictor` >= 10) applied before Flate
+pub(crate) fn undo_png_predictor(stream: &Stream, data: Vec<u8>) -> Result<Vec<u8>> {
+    if int_param(stream, b"Predictor").unwrap_or(1) < 10 {
+        return Ok(data);
+    }
+    let colors = int_param(stream, b"Colors").unwrap_or(1) as usize;
+    let bpc = int_param(stream, b"BitsPerComponent").unwrap_or(8) as usize;
+    let columns = int_param(stream, b"Columns").unwrap_or(1) as usize;
+    let bpp = ((colors * bpc + 7) / 8).max(1);
+    let row_len = (colors * bpc * columns + 7) / 8;
+
+    let mut out = Vec::with_capacity(data.len());
+    let mut prev = vec![0u8; row_len];
+    for chunk in data.chunks(row_len + 1) {
+        if chunk.len() < row_len + 1 {
+            break;
+        }
+        let (kind, row) = (chunk[0], &chunk[1..]);
+        let mut cur = row.to_vec();
+        for i in 0..row_len {
+            let a = if i >= bpp { cur[i - bpp] } else { 0 };
+            let b = prev[i];
+            let c = if i >= bpp { prev[i - bpp] } else { 0 };
+            let pred = match kind {
+                0 => 0,
+                1 => a,
+                2 => b,
+                3 => ((u16::from(a) + u16::from(b)) / 2) as u8,
+                4 => paeth(a, b, c),
+                _ => {
+                    return Err(StreamSlateError::InvalidPdf(format!(
+                        "Unknown PNG predictor {kind}"
+                    )))
+                }
+            };
+            cur[i] = cur[i].wrapping_add(pred);
+        }
+        out.extend_from_slice(&cur);
+        prev = cur;
+    }
+    Ok(out)
+}
+
+fn paeth(a: u8, b: u8, c: u8) -> u8 {
+    let p = i16::from(a) + i16::from(b) - i16::from(c);
+    let (pa, pb, pc) = (
+        (p - i16::from(a)).abs(),
+        (p - i16::from(b)).abs(),
+        (p - i16::from(c)).abs(),
+    );
+    if pa <= pb && pa <= pc {
+        a
+    } else if pb <= pc {
+        b
+    } else {
+        c
+    }
+}
+
+/// Component count, plus (base components, lookup table) for Indexed spaces
+pub(crate) type ColorSpaceInfo = (usize, Option<(usize, Vec<u8>)>);
+
+pub(crate) fn color_space_info(
+    document: &Document,
+    space: Option<&Object>,
+) -> Option<ColorSpaceInfo> {
+    match space? {
+        Object::Name(name) => match name.as_slice() {
+            b"DeviceGray" | b"CalGray" | b"G" => Some((1, None)),
+            b"DeviceRGB" | b"CalRGB" | b"RGB" => Some((3, None)),
+            b"DeviceCMYK" | b"CMYK" => Some((4, None)),
+            _ => None,
+        },
+        Object::Array(array) => {
+            let family = array.first()?.as_name().ok()?;
+            match family {
+                b"ICCBased" => {
+                    let profile = resolve(document, array.get(1)?).as_stream().ok()?;
+                    let n = profile.dict.get(b"N").and_then(Object::as_i64).ok()?;
+                    Some((n as usize, None))
+                }
+                b"Indexed" | b"I" => {
+                    let (base, _) =
+                        color_space_info(document, array.get(1).map(|o| resolve(document, o)))?;
+                    let lookup = match resolve(document, array.get(3)?) {
+                        Object::String(bytes, _) => bytes.clone(),
+                        Object::Stream(stream) => stream
+                            .decompressed_content()
+                            .unwrap_or_else(|_| stream.content.clone()),
+                        _ => return None,
+                    };
+                    (base == 1 || base == 3).then_some((1, Some((base, lookup))))
+                }
+                b"CalGray" | b"CalRGB" => {
+                    color_space_info(document, Some(&Object::Name(family.to_vec())))
+                }
+                _ => None,
+            }
+        }
+        _ => None,
+    }
+}
+
+/// Shrink 8-bit interleaved samples to `to_width` x `to_height` by averaging
+///
+/// Each destination pixel is the mean of the source pixels it covers, which
+/// avoids the aliasing nearest-neighbour scaling gives text in screenshots.
+pub(crate) fn downsample(
+    data: &[u8],
+    (width, height): (usize, usize),
+    components: usize,
+    (to_width, to_height): (usize, usize),
+) -> Vec<u8> {
+    let mut out = Vec::with_capacity(to_width * to_height * components);
+    for y in 0..to_height {
+        let (y0, y1) = (
+            y * height / to_height,
+            ((y + 1) * height / to_height).max(y * height / to_height + 1),
+        );
+        for x in 0..to_width {
+            let (x0, x1) = (
+                x * width / to_width,
+                ((x + 1) * width / to_width).max(x * width / to_width + 1),
+            );
+            let count = ((y1 - y0) * (x1 - x0)) as u32;
+            for c in 0..components {
+                let mut sum = 0u32;
+                for sy in y0..y1 {
+                    let row = sy * width * components;
+                    for sx in x0..x1 {
+                        sum += u32::from(data[row + sx * components + c]);
+                    }
+                }
+                out.push(((sum + count / 2) / count) as u8);
+            }
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_downsample_averages_blocks() {
+        // 4x2 gray image halved in both directions
+        let data = [0, 10, 100, 100, 20, 30, 200, 200];
+        assert_eq!(downsample(&data, (4, 2), 1, (2, 1)), vec![15, 150]);
+
+        // RGB channels are averaged independently
+        let rgb = [255, 0, 0, 0, 0, 255];
+        assert_eq!(downsample(&rgb, (2, 1), 3, (1, 1)), vec![128, 0, 128]);
+    }
+}
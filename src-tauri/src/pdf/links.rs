@@ -0,0 +1,163 @@
+/*
+ * This file is part of StreamSlate.
+ * Copyright (C) 2025 StreamSlate Contributors
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+
+//! Linked media
+//!
+//! Finds the external video and image URLs a deck links to from its pages
+//! (`/Link` annotations with a `/URI` action) so they can be fetched ahead
+//! of the show.
+
+use super::resolve;
+use lopdf::{Document, Object};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+
+const VIDEO_EXTENSIONS: &[&str] = &["mp4", "m4v", "mov", "webm", "mkv", "ogv"];
+const IMAGE_EXTENSIONS: &[&str] = &["png", "jpg", "jpeg", "gif", "webp", "svg", "avif"];
+
+/// Kind of media a link points at
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AssetKind {
+    Video,
+    Image,
+}
+
+/// An external media URL and the first page linking to it
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LinkedAsset {
+    pub url: String,
+    pub page: u32,
+    pub kind: AssetKind,
+}
+
+/// Media linked from the document's pages, in page order
+///
+/// Each URL is reported once, at the first page that links to it. Links to
+/// anything other than http(s) video or image files are ignored.
+pub fn linked_assets(document: &Document) -> Vec<LinkedAsset> {
+    let mut assets = Vec::new();
+    let mut seen = HashSet::new();
+    for (page, page_id) in document.get_pages() {
+        let Ok(annots) = document
+            .get_dictionary(page_id)
+            .and_then(|dict| dict.get(b"Annots"))
+            .map(|obj| resolve(document, obj))
+            .and_then(Object::as_array)
+        else {
+            continue;
+        };
+        for annot in annots {
+            let Some(url) = link_uri(document, resolve(document, annot)) else {
+                continue;
+            };
+            let Some(kind) = asset_kind(&url) else {
+                continue;
+            };
+            if seen.insert(url.clone()) {
+                assets.push(LinkedAsset { url, page, kind });
+            }
+        }
+    }
+    assets
+}
+
+/// Classify a URL by the extension of its path
+pub fn asset_kind(url: &str) -> Option<AssetKind> {
+    let lower = url.to_ascii_lowercase();
+    let rest = lower
+        .strip_prefix("https://")
+        .or_else(|| lower.strip_prefix("http://"))?;
+    let path = rest.split(['?', '#']).next().unwrap_or_default();
+    let (_, file) = path.split_once('/')?;
+    let extension = file.rsplit_once('.')?.1;
+    if VIDEO_EXTENSIONS.contains(&extension) {
+        Some(AssetKind::Video)
+    } else if IMAGE_EXTENSIONS.contains(&extension) {
+        Some(AssetKind::Image)
+    } else {
+        None
+    }
+}
+
+fn link_uri(document: &Document, annot: &Object) -> Option<String> {
+    let annot = annot.as_dict().ok()?;
+    if annot.get(b"Subtype").and_then(Object::as_name).ok()? != b"Link" {
+        return None;
+    }
+    let action = resolve(document, annot.get(b"A").ok()?).as_dict().ok()?;
+    if action.get(b"S").and_then(Object::as_name).ok()? != b"URI" {
+        return None;
+    }
+    match resolve(document, action.get(b"URI").ok()?) {
+        Object::String(bytes, _) => Some(String::from_utf8_lossy(bytes).trim().to_string()),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pdf::tests::TestDocument;
+    use lopdf::dictionary;
+
+    fn link(uri: &str) -> Object {
+        Object::Dictionary(dictionary! {
+            "Type" => "Annot",
+            "Subtype" => "Link",
+            "A" => dictionary! { "S" => "URI", "URI" => Object::string_literal(uri) },
+        })
+    }
+
+    #[test]
+    fn test_linked_assets() {
+        let mut builder = TestDocument::new();
+        let annots = [
+            vec![
+                link("https://cdn.example.com/intro.MP4?token=1"),
+                link("https://example.com/about"),
+            ],
+            vec![
+                link("https://cdn.example.com/intro.MP4?token=1"),
+                link("http://example.com/img/chart.png"),
+                link("file:///etc/clip.mp4"),
+            ],
+        ];
+        for annots in annots {
+            builder.page_with("", dictionary! { "Annots" => annots });
+        }
+        let doc = builder.finish();
+
+        assert_eq!(
+            linked_assets(&doc),
+            vec![
+                LinkedAsset {
+                    url: "https://cdn.example.com/intro.MP4?token=1".to_string(),
+                    page: 1,
+                    kind: AssetKind::Video,
+                },
+                LinkedAsset {
+                    url: "http://example.com/img/chart.png".to_string(),
+                    page: 2,
+                    kind: AssetKind::Image,
+                },
+            ]
+        );
+        assert_eq!(asset_kind("https://example.com.mp4"), None);
+    }
+}
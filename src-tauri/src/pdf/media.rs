@@ -0,0 +1,176 @@
+/*
+ * This file is part of StreamSlate.
+ * Copyright (C) 2025 StreamSlate Contributors
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+
+//! Embedded media
+//!
+//! Lists the video clips placed on pages as `/Screen` (rendition),
+//! `/RichMedia` or `/Movie` annotations, so they can be played back and
+//! remote controlled alongside the slides.
+
+use super::{decode_text_string, resolve};
+use lopdf::{Dictionary, Document, Object};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// A video clip shown on a page
+///
+/// Clips found in the PDF get IDs of the form `pdf-<page>-<n>`; companion
+/// clips listed in the annotations sidecar carry their own IDs.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub struct MediaItem {
+    pub id: String,
+    pub page: u32,
+    #[serde(default)]
+    pub name: String,
+    /// URL or path of the clip; `None` when it is embedded in the PDF
+    #[serde(default)]
+    pub source: Option<String>,
+}
+
+/// Media annotations in page order
+pub fn embedded_media(document: &Document) -> Vec<MediaItem> {
+    let mut items = Vec::new();
+    for (page, page_id) in document.get_pages() {
+        let Ok(annots) = document
+            .get_dictionary(page_id)
+            .and_then(|dict| dict.get(b"Annots"))
+            .map(|obj| resolve(document, obj))
+            .and_then(Object::as_array)
+        else {
+            continue;
+        };
+        let mut index = 0;
+        for annot in annots {
+            let Ok(annot) = resolve(document, annot).as_dict() else {
+                continue;
+            };
+            let source = match annot.get(b"Subtype").and_then(Object::as_name) {
+                Ok(b"Screen") => match screen_clip(document, annot) {
+                    Some(clip) => clip_source(document, clip),
+                    None => continue,
+                },
+                Ok(b"RichMedia") => None,
+                Ok(b"Movie") => annot
+                    .get(b"Movie")
+                    .map(|obj| resolve(document, obj))
+                    .and_then(Object::as_dict)
+                    .ok()
+                    .and_then(|movie| movie.get(b"F").ok())
+                    .and_then(|spec| file_spec_source(document, spec)),
+                _ => continue,
+            };
+            index += 1;
+            items.push(MediaItem {
+                id: format!("pdf-{page}-{index}"),
+                page,
+                name: annotation_name(document, annot)
+                    .unwrap_or_else(|| format!("Video {index} on page {page}")),
+                source,
+            });
+        }
+    }
+    items
+}
+
+/// Media clip dictionary of a screen annotation's rendition action
+fn screen_clip<'a>(document: &'a Document, annot: &'a Dictionary) -> Option<&'a Dictionary> {
+    let action = resolve(document, annot.get(b"A").ok()?).as_dict().ok()?;
+    if action.get(b"S").and_then(Object::as_name).ok()? != b"Rendition" {
+        return None;
+    }
+    let rendition = resolve(document, action.get(b"R").ok()?).as_dict().ok()?;
+    resolve(document, rendition.get(b"C").ok()?).as_dict().ok()
+}
+
+fn clip_source(document: &Document, clip: &Dictionary) -> Option<String> {
+    file_spec_source(document, clip.get(b"D").ok()?)
+}
+
+/// Location named by a file specification, unless the file is embedded
+fn file_spec_source(document: &Document, spec: &Object) -> Option<String> {
+    match resolve(document, spec) {
+        Object::String(bytes, _) => Some(decode_text_string(bytes)),
+        Object::Dictionary(dict) => {
+            if dict.has(b"EF") {
+                return None;
+            }
+            [b"UF".as_slice(), b"F"].iter().find_map(|key| {
+                match dict.get(key).map(|obj| resolve(document, obj)) {
+                    Ok(Object::String(bytes, _)) => Some(decode_text_string(bytes)),
+                    _ => None,
+                }
+            })
+        }
+        _ => None,
+    }
+}
+
+fn annotation_name(document: &Document, annot: &Dictionary) -> Option<String> {
+    [b"T".as_slice(), b"Contents", b"NM"]
+        .iter()
+        .find_map(
+            |key| match annot.get(key).map(|obj| resolve(document, obj)) {
+                Ok(Object::String(bytes, _)) => {
+                    Some(decode_text_string(bytes).trim().to_string()).filter(|s| !s.is_empty())
+                }
+                _ => None,
+            },
+        )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pdf::tests::TestDocument;
+    use lopdf::dictionary;
+
+    #[test]
+    fn test_embedded_media() {
+        let screen = dictionary! {
+            "Subtype" => "Screen",
+            "T" => Object::string_literal("Demo reel"),
+            "A" => dictionary! {
+                "S" => "Rendition",
+                "R" => dictionary! {
+                    "S" => "MR",
+                    "C" => dictionary! {
+                        "S" => "MCD",
+                        "D" => dictionary! { "Type" => "Filespec", "F" => Object::string_literal("demo.mp4") },
+                    },
+                },
+            },
+        };
+        let rich_media = dictionary! { "Subtype" => "RichMedia" };
+        let link = dictionary! { "Subtype" => "Link" };
+        let mut builder = TestDocument::new();
+        builder.page_with(
+            "",
+            dictionary! { "Annots" => vec![screen.into(), link.into(), rich_media.into()] },
+        );
+        let doc = builder.finish();
+
+        let media = embedded_media(&doc);
+        assert_eq!(media.len(), 2);
+        assert_eq!(media[0].id, "pdf-1-1");
+        assert_eq!(media[0].name, "Demo reel");
+        assert_eq!(media[0].source.as_deref(), Some("demo.mp4"));
+        assert_eq!(media[1].id, "pdf-1-2");
+        assert_eq!(media[1].name, "Video 2 on page 1");
+        assert_eq!(media[1].source, None);
+    }
+}
@@ -0,0 +1,170 @@
+/*
+ * This file is part of StreamSlate.
+ * Copyright (C) 2025 StreamSlate Contributors
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+
+//! PDF document inspection helpers
+//!
+//! Structural operations on `lopdf::Document` that are independent of Tauri.
+//! The command layer in `commands::pdf` wraps these for the frontend.
+
+pub mod attachments;
+pub mod compare;
+mod der;
+mod image;
+pub mod links;
+pub mod media;
+#[cfg(feature = "ocr")]
+pub mod ocr;
+pub mod optimize;
+pub mod outline;
+pub mod sanitize;
+pub mod signatures;
+pub mod text;
+pub mod validate;
+pub mod xfdf;
+mod xml;
+
+use lopdf::{Document, Object};
+
+/// Decode a PDF text string (PDFDocEncoding/UTF-8 or UTF-16BE with BOM)
+pub fn decode_text_string(bytes: &[u8]) -> String {
+    if bytes.len() >= 2 && bytes[0] == 0xFE && bytes[1] == 0xFF {
+        let units: Vec<u16> = bytes[2..]
+            .chunks_exact(2)
+            .map(|pair| u16::from_be_bytes([pair[0], pair[1]]))
+            .collect();
+        return String::from_utf16_lossy(&units);
+    }
+
+    match std::str::from_utf8(bytes) {
+        Ok(s) => s.to_string(),
+        // PDFDocEncoding is a superset of Latin-1 for printable characters
+        Err(_) => bytes.iter().map(|&b| b as char).collect(),
+    }
+}
+
+/// Follow a reference to the object it points at, returning the object itself otherwise
+pub fn resolve<'a>(document: &'a Document, object: &'a Object) -> &'a Object {
+    document
+        .dereference(object)
+        .map(|(_, obj)| obj)
+        .unwrap_or(object)
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use lopdf::{dictionary, Dictionary, ObjectId, Stream};
+
+    /// Builds small documents for tests
+    ///
+    /// Pages share a Letter media box and a Helvetica font named `F1`; each
+    /// page draws its own content stream.
+    pub(crate) struct TestDocument {
+        pub(crate) document: Document,
+        pages_id: ObjectId,
+        kids: Vec<Object>,
+    }
+
+    impl TestDocument {
+        pub(crate) fn new() -> Self {
+            let mut document = Document::with_version("1.7");
+            let pages_id = document.new_object_id();
+            Self {
+                document,
+                pages_id,
+                kids: Vec::new(),
+            }
+        }
+
+        /// Add a page drawing `content`
+        pub(crate) fn page(&mut self, content: &str) -> ObjectId {
+            self.page_with(content, dictionary! {})
+        }
+
+        /// Add a page drawing `content`, with extra entries such as `Annots`
+        pub(crate) fn page_with(&mut self, content: &str, entries: Dictionary) -> ObjectId {
+            let content_id = self
+                .document
+                .add_object(Stream::new(dictionary! {}, content.as_bytes().to_vec()));
+            let mut page = dictionary! {
+                "Type" => "Page",
+                "Parent" => self.pages_id,
+                "Contents" => content_id,
+            };
+            for (key, value) in &entries {
+                page.set(key.clone(), value.clone());
+            }
+            let page_id = self.document.add_object(page);
+            self.kids.push(page_id.into());
+            page_id
+        }
+
+        pub(crate) fn finish(self) -> Document {
+            self.finish_with(dictionary! {})
+        }
+
+        /// Finish with extra catalog entries such as `Outlines`
+        pub(crate) fn finish_with(mut self, catalog: Dictionary) -> Document {
+            let font_id = self.document.add_object(dictionary! {
+                "Type" => "Font",
+                "Subtype" => "Type1",
+                "BaseFont" => "Helvetica",
+                "FirstChar" => 32,
+                "Widths" => vec![Object::Integer(500); 95],
+            });
+            self.document.objects.insert(
+                self.pages_id,
+                Object::Dictionary(dictionary! {
+                    "Type" => "Pages",
+                    "Count" => self.kids.len() as i64,
+                    "Kids" => self.kids,
+                    "MediaBox" => vec![0.into(), 0.into(), 612.into(), 792.into()],
+                    "Resources" => dictionary! { "Font" => dictionary! { "F1" => font_id } },
+                }),
+            );
+            let mut root = dictionary! { "Type" => "Catalog", "Pages" => self.pages_id };
+            for (key, value) in &catalog {
+                root.set(key.clone(), value.clone());
+            }
+            let catalog_id = self.document.add_object(root);
+            self.document.trailer.set("Root", catalog_id);
+            self.document
+        }
+    }
+
+    /// Build a document with one page per content stream
+    pub(crate) fn document_with_pages(contents: &[&str]) -> Document {
+        let mut builder = TestDocument::new();
+        for content in contents {
+            builder.page(content);
+        }
+        builder.finish()
+    }
+
+    #[test]
+    fn test_decode_utf16_text_string() {
+        let bytes = [0xFE, 0xFF, 0x00, 0x4E, 0x00, 0x6F, 0x00, 0x74, 0x00, 0x65];
+        assert_eq!(decode_text_string(&bytes), "Note");
+    }
+
+    #[test]
+    fn test_decode_latin1_text_string() {
+        assert_eq!(decode_text_string(b"caf\xe9"), "café");
+        assert_eq!(decode_text_string(b"rundown.csv"), "rundown.csv");
+    }
+}
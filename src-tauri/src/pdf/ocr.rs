@@ -0,0 +1,537 @@
+/*
+ * This file is part of StreamSlate.
+ * Copyright (C) 2025 StreamSlate Contributors
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+
+//! OCR for image-only pages
+//!
+//! The page's scanned image is handed to the `tesseract` executable and the
+//! recognised word boxes are mapped into annotation coordinates, so scanned
+//! pages work with the same word-box and search APIs as text PDFs.
+
+use super::image::{color_space_info, inflate, int_param};
+use super::resolve;
+use super::text::{
+    apply, matrix_from, multiply, page_origin, page_resources, Matrix, PageWord, Resources,
+    IDENTITY,
+};
+use crate::error::{Result, StreamSlateError};
+use lopdf::content::Content;
+use lopdf::{Document, Object, ObjectId, Stream};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use std::process::Command;
+use tracing::debug;
+
+/// Language used when the caller doesn't pick one
+pub const DEFAULT_LANGUAGE: &str = "eng";
+
+/// Maximum nesting of form XObjects searched for the page image
+const MAX_FORM_DEPTH: usize = 8;
+
+/// Recognised text of a page
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OcrPage {
+    pub page: u32,
+    /// Plain text, one line per recognised text line
+    pub text: String,
+    pub words: Vec<PageWord>,
+    /// Mean word confidence (0.0-1.0)
+    pub confidence: f64,
+}
+
+/// Run OCR on the largest image drawn on a PDF page
+pub fn ocr_pdf_page(document: &Document, page_number: u32, language: &str) -> Result<OcrPage> {
+    let pages = document.get_pages();
+    let page_id = *pages.get(&page_number).ok_or_else(|| {
+        StreamSlateError::InvalidPdf(format!(
+            "Page {} not found (document has {} pages)",
+            page_number,
+            pages.len()
+        ))
+    })?;
+
+    let (image_id, placement) = largest_image(document, page_id)
+        .ok_or_else(|| StreamSlateError::InvalidPdf("Page has no image to OCR".to_string()))?;
+    let stream = document.get_object(image_id)?.as_stream()?;
+    let (data, extension) = encode_image(document, stream)?;
+    let width = stream.dict.get(b"Width")?.as_i64()? as u32;
+    let height = stream.dict.get(b"Height")?.as_i64()? as u32;
+
+    // Image space is the unit square; flip into top-left page coordinates
+    let (left, top) = page_origin(document, page_id);
+    let to_page = multiply(&placement, &[1.0, 0.0, 0.0, -1.0, -left, top]);
+
+    let path = std::env::temp_dir().join(format!(
+        "streamslate-ocr-{}.{}",
+        uuid::Uuid::new_v4(),
+        extension
+    ));
+    std::fs::write(&path, data)?;
+    let tsv = run_tesseract(&path, language);
+    let _ = std::fs::remove_file(&path);
+
+    Ok(build_page(
+        page_number,
+        &parse_tsv(&tsv?),
+        width,
+        height,
+        &to_page,
+    ))
+}
+
+/// Run OCR on an image file that fills a page measured in its own pixels
+pub fn ocr_image_file(
+    path: &Path,
+    page_number: u32,
+    width: u32,
+    height: u32,
+    language: &str,
+) -> Result<OcrPage> {
+    let tsv = run_tesseract(path, language)?;
+    let (w, h) = (f64::from(width), f64::from(height));
+    Ok(build_page(
+        page_number,
+        &parse_tsv(&tsv),
+        width,
+        height,
+        &[w, 0.0, 0.0, -h, 0.0, h],
+    ))
+}
+
+/// Invoke tesseract and return its TSV output
+fn run_tesseract(image: &Path, language: &str) -> Result<String> {
+    if language.is_empty()
+        || !language
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '+'))
+    {
+        return Err(StreamSlateError::Other(format!(
+            "Invalid OCR language: {language}"
+        )));
+    }
+
+    let output = Command::new("tesseract")
+        .arg(image)
+        .arg("stdout")
+        .args(["-l", language, "tsv"])
+        .output()
+        .map_err(|e| {
+            StreamSlateError::Other(format!(
+                "Failed to run tesseract ({e}); install it to enable OCR"
+            ))
+        })?;
+    if !output.status.success() {
+        return Err(StreamSlateError::Other(format!(
+            "tesseract failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        )));
+    }
+
+    debug!(image = %image.display(), language, "tesseract finished");
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+/// A word row from tesseract's TSV output
+#[derive(Debug, PartialEq)]
+struct TsvWord {
+    line: (u32, u32, u32),
+    left: f64,
+    top: f64,
+    width: f64,
+    height: f64,
+    confidence: f64,
+    text: String,
+}
+
+fn parse_tsv(tsv: &str) -> Vec<TsvWord> {
+    tsv.lines()
+        .skip(1)
+        .filter_map(|row| {
+            let cols: Vec<&str> = row.splitn(12, '\t').collect();
+            if cols.len() < 12 || cols[0] != "5" {
+                return None;
+            }
+            let num = |i: usize| cols[i].trim().parse::<f64>().ok();
+            let text = cols[11].trim();
+            let confidence = num(10)?;
+            if text.is_empty() || confidence < 0.0 {
+                return None;
+            }
+            Some(TsvWord {
+                line: (num(2)? as u32, num(3)? as u32, num(4)? as u32),
+                left: num(6)?,
+                top: num(7)?,
+                width: num(8)?,
+                height: num(9)?,
+                confidence,
+                text: text.to_string(),
+            })
+        })
+        .collect()
+}
+
+/// Map pixel boxes through `to_page` (unit image square to page coordinates)
+fn build_page(page: u32, rows: &[TsvWord], width: u32, height: u32, to_page: &Matrix) -> OcrPage {
+    let (w, h) = (f64::from(width.max(1)), f64::from(height.max(1)));
+    let mut text = String::new();
+    let mut last_line = None;
+    let mut words = Vec::with_capacity(rows.len());
+
+    for row in rows {
+        match last_line {
+            Some(line) if line == row.line => text.push(' '),
+            Some(_) => text.push('\n'),
+            None => {}
+        }
+        last_line = Some(row.line);
+        text.push_str(&row.text);
+
+        let corners = [
+            (row.left, row.top),
+            (row.left + row.width, row.top),
+            (row.left, row.top + row.height),
+            (row.left + row.width, row.top + row.height),
+        ]
+        .map(|(x, y)| apply(to_page, x / w, 1.0 - y / h));
+        let min_x = corners.iter().map(|c| c.0).fold(f64::INFINITY, f64::min);
+        let max_x = corners
+            .iter()
+            .map(|c| c.0)
+            .fold(f64::NEG_INFINITY, f64::max);
+        let min_y = corners.iter().map(|c| c.1).fold(f64::INFINITY, f64::min);
+        let max_y = corners
+            .iter()
+            .map(|c| c.1)
+            .fold(f64::NEG_INFINITY, f64::max);
+
+        words.push(PageWord {
+            text: row.text.clone(),
+            x: min_x,
+            y: min_y,
+            width: max_x - min_x,
+            height: max_y - min_y,
+        });
+    }
+
+    let confidence = if rows.is_empty() {
+        0.0
+    } else {
+        rows.iter().map(|r| r.confidence).sum::<f64>() / rows.len() as f64 / 100.0
+    };
+
+    OcrPage {
+        page,
+        text,
+        words,
+        confidence,
+    }
+}
+
+/// The image XObject covering the largest area on a page, with its placement
+fn largest_image(document: &Document, page_id: ObjectId) -> Option<(ObjectId, Matrix)> {
+    let content = document.get_page_content(page_id).ok()?;
+    let resources = page_resources(document, page_id);
+    let mut images = Vec::new();
+    collect_images(document, &content, &resources, IDENTITY, 0, &mut images);
+
+    images.into_iter().max_by(|(_, a), (_, b)| {
+        let area = |m: &Matrix| (m[0] * m[3] - m[1] * m[2]).abs();
+        area(a).total_cmp(&area(b))
+    })
+}
+
+fn collect_images(
+    document: &Document,
+    content: &[u8],
+    resources: &Resources,
+    ctm: Matrix,
+    depth: usize,
+    images: &mut Vec<(ObjectId, Matrix)>,
+) {
+    let Ok(content) = Content::decode(content) else {
+        return;
+    };
+    let mut ctm = ctm;
+    let mut saved = Vec::new();
+
+    for op in &content.operations {
+        match op.operator.as_str() {
+            "q" => saved.push(ctm),
+            "Q" => ctm = saved.pop().unwrap_or(ctm),
+            "cm" => {
+                if let Some(m) = matrix_from(&op.operands) {
+                    ctm = multiply(&m, &ctm);
+                }
+            }
+            "Do" => {
+                let Some(id) = op
+                    .operands
+                    .first()
+                    .and_then(|o| o.as_name().ok())
+                    .and_then(|name| resources.xobjects.get(name))
+                else {
+                    continue;
+                };
+                let Ok(stream) = document.get_object(*id).and_then(Object::as_stream) else {
+                    continue;
+                };
+                match stream.dict.get(b"Subtype").and_then(Object::as_name_str) {
+                    Ok("Image") => images.push((*id, ctm)),
+                    Ok("Form") if depth < MAX_FORM_DEPTH => {
+                        let matrix = stream
+                            .dict
+                            .get(b"Matrix")
+                            .and_then(Object::as_array)
+                            .ok()
+                            .and_then(|m| matrix_from(m))
+                            .unwrap_or(IDENTITY);
+                        let own = stream
+                            .dict
+                            .get(b"Resources")
+                            .map(|o| resolve(document, o))
+                            .and_then(Object::as_dict)
+                            .ok()
+                            .map(|dict| Resources::from_dicts(document, &[dict]));
+                        let content = stream
+                            .decompressed_content()
+                            .unwrap_or_else(|_| stream.content.clone());
+                        collect_images(
+                            document,
+                            &content,
+                            own.as_ref().unwrap_or(resources),
+                            multiply(&matrix, &ctm),
+                            depth + 1,
+                            images,
+                        );
+                    }
+                    _ => {}
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Convert an image XObject into a file tesseract can read, with its extension
+fn encode_image(document: &Document, stream: &Stream) -> Result<(Vec<u8>, &'static str)> {
+    let filters = stream.filters().unwrap_or_default();
+    let unsupported = |what: &str| {
+        StreamSlateError::InvalidPdf(format!("Unsupported image encoding for OCR: {what}"))
+    };
+
+    match filters.as_slice() {
+        [f] if f == "DCTDecode" => Ok((stream.content.clone(), "jpg")),
+        [f] if f == "JPXDecode" => Ok((stream.content.clone(), "jp2")),
+        [f] if f == "CCITTFaxDecode" => Ok((ccitt_to_tiff(stream)?, "tif")),
+        [] => Ok((raw_to_png(document, stream, stream.content.clone())?, "png")),
+        [f] if f == "FlateDecode" => Ok((raw_to_png(document, stream, inflate(stream)?)?, "png")),
+        other => Err(unsupported(&other.join(", "))),
+    }
+}
+
+/// Encode decoded image samples as PNG (Gray, RGB, CMYK, ICCBased, Indexed)
+fn raw_to_png(document: &Document, stream: &Stream, data: Vec<u8>) -> Result<Vec<u8>> {
+    let dict = &stream.dict;
+    let width = dict.get(b"Width")?.as_i64()? as u32;
+    let height = dict.get(b"Height")?.as_i64()? as u32;
+    let image_mask = matches!(dict.get(b"ImageMask"), Ok(Object::Boolean(true)));
+    let bpc = if image_mask {
+        1
+    } else {
+        dict.get(b"BitsPerComponent")
+            .and_then(Object::as_i64)
+            .unwrap_or(8)
+    };
+    let unsupported = |what: String| {
+        StreamSlateError::InvalidPdf(format!("Unsupported image format for OCR: {what}"))
+    };
+
+    let color_space = dict.get(b"ColorSpace").map(|o| resolve(document, o));
+    let (components, palette) = if image_mask {
+        (1, None)
+    } else {
+        color_space_info(document, color_space.ok())
+            .ok_or_else(|| unsupported("color space".to_string()))?
+    };
+
+    let (color, depth, pixels) = match (bpc, components, palette) {
+        (1, 1, None) => (png::ColorType::Grayscale, png::BitDepth::One, data),
+        (8, 1, None) => (png::ColorType::Grayscale, png::BitDepth::Eight, data),
+        (8, 3, None) => (png::ColorType::Rgb, png::BitDepth::Eight, data),
+        (8, 4, None) => (
+            png::ColorType::Rgb,
+            png::BitDepth::Eight,
+            cmyk_to_rgb(&data),
+        ),
+        (8, 1, Some((base, lookup))) => {
+            let rgb = data
+                .iter()
+                .flat_map(|&i| {
+                    let at = |c: usize| lookup.get(i as usize * base + c).copied().unwrap_or(0);
+                    match base {
+                        1 => [at(0), at(0), at(0)],
+                        _ => [at(0), at(1), at(2)],
+                    }
+                })
+                .collect();
+            (png::ColorType::Rgb, png::BitDepth::Eight, rgb)
+        }
+        (bpc, components, _) => {
+            return Err(unsupported(format!(
+                "{bpc} bits per component, {components} components"
+            )))
+        }
+    };
+
+    let mut out = Vec::new();
+    let mut encoder = png::Encoder::new(&mut out, width, height);
+    encoder.set_color(color);
+    encoder.set_depth(depth);
+    let mut writer = encoder
+        .write_header()
+        .map_err(|e| StreamSlateError::Other(e.to_string()))?;
+    writer
+        .write_image_data(&pixels)
+        .map_err(|e| StreamSlateError::InvalidPdf(format!("Image data size mismatch: {e}")))?;
+    writer
+        .finish()
+        .map_err(|e| StreamSlateError::Other(e.to_string()))?;
+    Ok(out)
+}
+
+fn cmyk_to_rgb(data: &[u8]) -> Vec<u8> {
+    data.chunks_exact(4)
+        .flat_map(|px| {
+            let k = 255 - u16::from(px[3]);
+            [0, 1, 2].map(|i| ((255 - u16::from(px[i])) * k / 255) as u8)
+        })
+        .collect()
+}
+
+/// Wrap CCITT fax data in a single-strip TIFF
+fn ccitt_to_tiff(stream: &Stream) -> Result<Vec<u8>> {
+    let width = stream.dict.get(b"Width")?.as_i64()? as u32;
+    let height = stream.dict.get(b"Height")?.as_i64()? as u32;
+    let k = int_param(stream, b"K").unwrap_or(0);
+    // Group 4 for K < 0, otherwise Group 3 (1D or mixed 2D)
+    let (compression, t4_options) = match k {
+        k if k < 0 => (4u32, None),
+        0 => (3, Some(0u32)),
+        _ => (3, Some(1)),
+    };
+
+    let mut entries: Vec<(u16, u16, u32)> = vec![
+        (256, 4, width),
+        (257, 4, height),
+        (258, 3, 1),
+        (259, 3, compression),
+        (262, 3, 0),
+        (273, 4, 0), // strip offset, patched below
+        (277, 3, 1),
+        (278, 4, height),
+        (279, 4, stream.content.len() as u32),
+    ];
+    if let Some(options) = t4_options {
+        entries.push((292, 4, options));
+    }
+
+    let ifd_len = 2 + entries.len() * 12 + 4;
+    let data_offset = (8 + ifd_len) as u32;
+    for entry in &mut entries {
+        if entry.0 == 273 {
+            entry.2 = data_offset;
+        }
+    }
+
+    let mut tiff = Vec::with_capacity(data_offset as usize + stream.content.len());
+    tiff.extend_from_slice(b"II*\0");
+    tiff.extend_from_slice(&8u32.to_le_bytes());
+    tiff.extend_from_slice(&(entries.len() as u16).to_le_bytes());
+    for (tag, kind, value) in entries {
+        tiff.extend_from_slice(&tag.to_le_bytes());
+        tiff.extend_from_slice(&kind.to_le_bytes());
+        tiff.extend_from_slice(&1u32.to_le_bytes());
+        if kind == 3 {
+            tiff.extend_from_slice(&(value as u16).to_le_bytes());
+            tiff.extend_from_slice(&[0, 0]);
+        } else {
+            tiff.extend_from_slice(&value.to_le_bytes());
+        }
+    }
+    tiff.extend_from_slice(&0u32.to_le_bytes());
+    tiff.extend_from_slice(&stream.content);
+    Ok(tiff)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pdf::image::undo_png_predictor;
+    use lopdf::dictionary;
+
+    const TSV: &str = "level\tpage_num\tblock_num\tpar_num\tline_num\tword_num\tleft\ttop\twidth\theight\tconf\ttext\n\
+        1\t1\t0\t0\t0\t0\t0\t0\t1000\t500\t-1\t\n\
+        5\t1\t1\t1\t1\t1\t100\t50\t200\t40\t96.5\tQuarterly\n\
+        5\t1\t1\t1\t1\t2\t320\t50\t150\t40\t91.5\tresults\n\
+        5\t1\t1\t1\t2\t1\t100\t120\t80\t40\t90\tQ3\n";
+
+    #[test]
+    fn test_tsv_words_map_to_page() {
+        let rows = parse_tsv(TSV);
+        assert_eq!(rows.len(), 3);
+
+        // A 1000x500 px scan drawn over a 500x250 pt page: 2 px per point
+        let placement = [500.0, 0.0, 0.0, 250.0, 0.0, 0.0];
+        let to_page = multiply(&placement, &[1.0, 0.0, 0.0, -1.0, 0.0, 250.0]);
+        let page = build_page(1, &rows, 1000, 500, &to_page);
+
+        assert_eq!(page.text, "Quarterly results\nQ3");
+        let word = &page.words[0];
+        assert_eq!(word.text, "Quarterly");
+        for (actual, expected) in [
+            (word.x, 50.0),
+            (word.y, 25.0),
+            (word.width, 100.0),
+            (word.height, 20.0),
+        ] {
+            assert!((actual - expected).abs() < 1e-6, "{actual} != {expected}");
+        }
+        assert!((page.confidence - 0.926666).abs() < 1e-4);
+    }
+
+    #[test]
+    fn test_png_predictor_and_encoding() {
+        // Two 2x1 gray rows with "Up" and "Sub" predictors
+        let stream = Stream::new(
+            dictionary! {
+                "Width" => 2, "Height" => 2, "BitsPerComponent" => 8, "ColorSpace" => "DeviceGray",
+                "DecodeParms" => dictionary! { "Predictor" => 12, "Columns" => 2 },
+            },
+            Vec::new(),
+        );
+        let decoded = undo_png_predictor(&stream, vec![2, 10, 20, 1, 5, 5]).unwrap();
+        assert_eq!(decoded, vec![10, 20, 5, 10]);
+
+        let png = raw_to_png(&Document::with_version("1.7"), &stream, decoded).unwrap();
+        assert!(png.starts_with(b"\x89PNG"));
+    }
+
+    #[test]
+    fn test_rejects_unsafe_language() {
+        assert!(run_tesseract(Path::new("page.png"), "eng; rm -rf /").is_err());
+    }
+}
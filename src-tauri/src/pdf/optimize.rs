@@ -0,0 +1,448 @@
+/*
+ * This file is part of StreamSlate.
+ * Copyright (C) 2025 StreamSlate Contributors
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+
+//! Size reduction for exported decks
+//!
+//! Oversized images are downsampled, unused objects dropped, uncompressed
+//! streams deflated, and the remaining dictionaries packed into compressed
+//! object streams with a cross-reference stream (PDF 1.5). The open document
+//! is left untouched; the result is written to a new file.
+//!
+//! lopdf only writes classic cross-reference tables, so the object stream
+//! layout is serialized here.
+
+use super::image::{color_space_info, downsample, inflate};
+use super::resolve;
+use crate::error::{Result, StreamSlateError};
+use flate2::write::ZlibEncoder;
+use flate2::Compression;
+use lopdf::{Dictionary, Document, Object, ObjectId, Stream, StringFormat};
+use serde::{Deserialize, Serialize};
+use std::io::Write;
+
+/// Objects packed into each object stream
+const OBJECTS_PER_STREAM: usize = 100;
+
+/// What `optimize` is allowed to change
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct OptimizeOptions {
+    /// Pack dictionaries into compressed object streams
+    pub object_streams: bool,
+    /// Downsample images whose longer side exceeds this many pixels
+    pub max_image_size: Option<u32>,
+}
+
+impl Default for OptimizeOptions {
+    fn default() -> Self {
+        Self {
+            object_streams: true,
+            max_image_size: Some(2048),
+        }
+    }
+}
+
+/// Outcome of an optimization run
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OptimizeReport {
+    pub original_bytes: u64,
+    pub optimized_bytes: u64,
+    pub images_downsampled: u32,
+    /// Oversized images in encodings that can't be resampled (e.g. JPEG)
+    pub images_skipped: u32,
+    pub objects_removed: u32,
+}
+
+/// Produce an optimized copy of `document`, returning the PDF bytes
+pub fn optimize(
+    mut document: Document,
+    options: &OptimizeOptions,
+) -> Result<(Vec<u8>, OptimizeReport)> {
+    if document.trailer.has(b"Encrypt") {
+        return Err(StreamSlateError::InvalidPdf(
+            "Encrypted documents can't be optimized".to_string(),
+        ));
+    }
+
+    let mut report = OptimizeReport::default();
+
+    if let Some(max_size) = options.max_image_size {
+        downsample_images(&mut document, max_size.max(1) as usize, &mut report);
+    }
+
+    let removed = document.prune_objects().len() + document.delete_zero_length_streams().len();
+    report.objects_removed = removed as u32;
+    document.compress();
+
+    let mut output = Vec::new();
+    if options.object_streams {
+        document.renumber_objects();
+        write_with_object_streams(&document, &mut output)?;
+    } else {
+        document.save_to(&mut output)?;
+    }
+    report.optimized_bytes = output.len() as u64;
+    Ok((output, report))
+}
+
+/// Downsample every 8-bit image XObject larger than `max_size` on its longer side
+fn downsample_images(document: &mut Document, max_size: usize, report: &mut OptimizeReport) {
+    let ids: Vec<ObjectId> = document
+        .objects
+        .iter()
+        .filter(|(_, object)| {
+            object.as_stream().is_ok_and(|stream| {
+                stream.dict.get(b"Subtype").and_then(Object::as_name).ok() == Some(b"Image")
+            })
+        })
+        .map(|(&id, _)| id)
+        .collect();
+
+    for id in ids {
+        let Ok(stream) = document.get_object(id).and_then(Object::as_stream) else {
+            continue;
+        };
+        let dimension = |key: &[u8]| {
+            stream
+                .dict
+                .get(key)
+                .and_then(Object::as_i64)
+                .map_or(0, |v| v.max(0) as usize)
+        };
+        let (width, height) = (dimension(b"Width"), dimension(b"Height"));
+        if width.max(height) <= max_size {
+            continue;
+        }
+
+        match resample(document, stream, (width, height), max_size) {
+            Some(replacement) => {
+                if let Ok(Object::Stream(stream)) = document.get_object_mut(id) {
+                    *stream = replacement;
+                    report.images_downsampled += 1;
+                }
+            }
+            None => report.images_skipped += 1,
+        }
+    }
+}
+
+/// Resampled copy of an 8-bit raw or Flate image, or `None` if unsupported
+fn resample(
+    document: &Document,
+    stream: &Stream,
+    (width, height): (usize, usize),
+    max_size: usize,
+) -> Option<Stream> {
+    let dict = &stream.dict;
+    if matches!(dict.get(b"ImageMask"), Ok(Object::Boolean(true)))
+        || dict.get(b"BitsPerComponent").and_then(Object::as_i64).ok() != Some(8)
+    {
+        return None;
+    }
+    // Indexed samples are palette entries, which can't be averaged
+    let space = dict.get(b"ColorSpace").map(|o| resolve(document, o)).ok();
+    let components = match color_space_info(document, space)? {
+        (components, None) => components,
+        (_, Some(_)) => return None,
+    };
+
+    let data = match stream.filters().unwrap_or_default().as_slice() {
+        [] => stream.content.clone(),
+        [f] if f == "FlateDecode" => inflate(stream).ok()?,
+        _ => return None,
+    };
+    if data.len() < width * height * components {
+        return None;
+    }
+
+    let scale = max_size as f64 / width.max(height) as f64;
+    let to_width = ((width as f64 * scale).round() as usize).max(1);
+    let to_height = ((height as f64 * scale).round() as usize).max(1);
+    let pixels = downsample(&data, (width, height), components, (to_width, to_height));
+
+    let mut dict = dict.clone();
+    dict.set("Width", to_width as i64);
+    dict.set("Height", to_height as i64);
+    dict.remove(b"DecodeParms");
+    dict.remove(b"Filter");
+    let mut resampled = Stream::new(dict, pixels);
+    resampled.compress().ok()?;
+    Some(resampled)
+}
+
+/// Write `document` with its generation-0 dictionaries packed into object
+/// streams and a cross-reference stream in place of the xref table
+fn write_with_object_streams(document: &Document, out: &mut Vec<u8>) -> Result<()> {
+    let version = if document.version.as_str() < "1.5" {
+        "1.5"
+    } else {
+        document.version.as_str()
+    };
+    out.extend_from_slice(format!("%PDF-{version}\n").as_bytes());
+    out.extend_from_slice(b"%\xE2\xE3\xCF\xD3\n");
+
+    let mut packable = Vec::new();
+    let mut offsets = Vec::new();
+    for (&(id, generation), object) in &document.objects {
+        match object {
+            Object::Stream(stream) => {
+                let skip = stream
+                    .dict
+                    .get(b"Type")
+                    .and_then(Object::as_name)
+                    .is_ok_and(|t| t == b"ObjStm" || t == b"XRef");
+                if !skip {
+                    offsets.push((id, XrefEntry::Offset(out.len(), generation)));
+                    write_indirect(out, (id, generation), object);
+                }
+            }
+            _ if generation == 0 => packable.push((id, object)),
+            _ => {
+                offsets.push((id, XrefEntry::Offset(out.len(), generation)));
+                write_indirect(out, (id, generation), object);
+            }
+        }
+    }
+
+    let mut next_id = document.max_id + 1;
+    for chunk in packable.chunks(OBJECTS_PER_STREAM) {
+        let stream_id = next_id;
+        next_id += 1;
+
+        let mut header = Vec::new();
+        let mut body = Vec::new();
+        for (index, (id, object)) in chunk.iter().enumerate() {
+            header.extend_from_slice(format!("{} {} ", id, body.len()).as_bytes());
+            write_object(&mut body, object);
+            body.push(b'\n');
+            offsets.push((*id, XrefEntry::Packed(stream_id, index)));
+        }
+        let first = header.len();
+        header.extend_from_slice(&body);
+
+        let mut dict = Dictionary::new();
+        dict.set("Type", "ObjStm");
+        dict.set("N", chunk.len() as i64);
+        dict.set("First", first as i64);
+        dict.set("Filter", "FlateDecode");
+        let object_stream = Object::Stream(Stream::new(dict, deflate(&header)?));
+        offsets.push((stream_id, XrefEntry::Offset(out.len(), 0)));
+        write_indirect(out, (stream_id, 0), &object_stream);
+    }
+
+    let xref_id = next_id;
+    let xref_offset = out.len();
+    offsets.push((xref_id, XrefEntry::Offset(xref_offset, 0)));
+    offsets.sort_by_key(|(id, _)| *id);
+
+    let size = xref_id + 1;
+    let mut entries = vec![0u8; size as usize * 7];
+    entries[..7].copy_from_slice(&[0, 0, 0, 0, 0, 0xFF, 0xFF]);
+    for (id, entry) in &offsets {
+        let (kind, field, extra) = match *entry {
+            XrefEntry::Offset(offset, generation) => (1u8, offset as u32, generation),
+            XrefEntry::Packed(stream_id, index) => (2u8, stream_id, index as u16),
+        };
+        let row = &mut entries[*id as usize * 7..][..7];
+        row[0] = kind;
+        row[1..5].copy_from_slice(&field.to_be_bytes());
+        row[5..7].copy_from_slice(&extra.to_be_bytes());
+    }
+
+    let mut dict = Dictionary::new();
+    dict.set("Type", "XRef");
+    dict.set("Size", i64::from(size));
+    dict.set("W", Object::Array(vec![1.into(), 4.into(), 2.into()]));
+    for key in [&b"Root"[..], b"Info", b"ID"] {
+        if let Ok(value) = document.trailer.get(key) {
+            dict.set(key.to_vec(), value.clone());
+        }
+    }
+    dict.set("Filter", "FlateDecode");
+    let xref = Stream::new(dict, deflate(&entries)?);
+    write_indirect(out, (xref_id, 0), &Object::Stream(xref));
+
+    out.extend_from_slice(format!("startxref\n{xref_offset}\n%%EOF\n").as_bytes());
+    Ok(())
+}
+
+/// Where an object lives in the written file
+enum XrefEntry {
+    /// Byte offset and generation of a top-level object
+    Offset(usize, u16),
+    /// Object stream id and index within it
+    Packed(u32, usize),
+}
+
+fn deflate(data: &[u8]) -> Result<Vec<u8>> {
+    let mut encoder = ZlibEncoder::new(Vec::new(), Compression::best());
+    encoder.write_all(data)?;
+    Ok(encoder.finish()?)
+}
+
+fn write_indirect(out: &mut Vec<u8>, (id, generation): ObjectId, object: &Object) {
+    out.extend_from_slice(format!("{id} {generation} obj\n").as_bytes());
+    write_object(out, object);
+    out.extend_from_slice(b"\nendobj\n");
+}
+
+/// Serialize an object in PDF syntax
+fn write_object(out: &mut Vec<u8>, object: &Object) {
+    match object {
+        Object::Null => out.extend_from_slice(b"null"),
+        Object::Boolean(value) => out.extend_from_slice(if *value { b"true" } else { b"false" }),
+        Object::Integer(value) => out.extend_from_slice(value.to_string().as_bytes()),
+        Object::Real(value) => out.extend_from_slice(value.to_string().as_bytes()),
+        Object::Name(name) => write_name(out, name),
+        Object::String(text, StringFormat::Literal) => {
+            out.push(b'(');
+            for &byte in text {
+                match byte {
+                    b'(' | b')' | b'\\' => out.extend_from_slice(&[b'\\', byte]),
+                    b'\r' => out.extend_from_slice(b"\\r"),
+                    _ => out.push(byte),
+                }
+            }
+            out.push(b')');
+        }
+        Object::String(text, StringFormat::Hexadecimal) => {
+            out.push(b'<');
+            for byte in text {
+                out.extend_from_slice(format!("{byte:02X}").as_bytes());
+            }
+            out.push(b'>');
+        }
+        Object::Array(items) => {
+            out.push(b'[');
+            for (i, item) in items.iter().enumerate() {
+                if i > 0 {
+                    out.push(b' ');
+                }
+                write_object(out, item);
+            }
+            out.push(b']');
+        }
+        Object::Dictionary(dict) => write_dictionary(out, dict),
+        Object::Stream(stream) => {
+            let mut dict = stream.dict.clone();
+            dict.set("Length", stream.content.len() as i64);
+            write_dictionary(out, &dict);
+            out.extend_from_slice(b"stream\n");
+            out.extend_from_slice(&stream.content);
+            out.extend_from_slice(b"\nendstream");
+        }
+        Object::Reference((id, generation)) => {
+            out.extend_from_slice(format!("{id} {generation} R").as_bytes())
+        }
+    }
+}
+
+fn write_dictionary(out: &mut Vec<u8>, dict: &Dictionary) {
+    out.extend_from_slice(b"<<");
+    for (key, value) in dict.iter() {
+        write_name(out, key);
+        out.push(b' ');
+        write_object(out, value);
+    }
+    out.extend_from_slice(b">>");
+}
+
+fn write_name(out: &mut Vec<u8>, name: &[u8]) {
+    out.push(b'/');
+    for &byte in name {
+        if b" \t\n\r\x0C()<>[]{}/%#".contains(&byte) || !(33..=126).contains(&byte) {
+            out.extend_from_slice(format!("#{byte:02X}").as_bytes());
+        } else {
+            out.push(byte);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pdf::tests::TestDocument;
+    use lopdf::dictionary;
+
+    fn document_with_image(width: i64, height: i64) -> Document {
+        let mut builder = TestDocument::new();
+        let image_id = builder.document.add_object(Stream::new(
+            dictionary! {
+                "Type" => "XObject", "Subtype" => "Image",
+                "Width" => width, "Height" => height,
+                "ColorSpace" => "DeviceGray", "BitsPerComponent" => 8,
+            },
+            vec![128; (width * height) as usize],
+        ));
+        builder.page_with(
+            "q 100 0 0 100 0 0 cm /Im0 Do Q",
+            dictionary! {
+                "Resources" => dictionary! { "XObject" => dictionary! { "Im0" => image_id } },
+            },
+        );
+        builder.finish()
+    }
+
+    fn image_width(doc: &Document) -> i64 {
+        doc.objects
+            .values()
+            .filter_map(|o| o.as_stream().ok())
+            .find(|s| s.dict.get(b"Subtype").and_then(Object::as_name).ok() == Some(b"Image"))
+            .and_then(|s| s.dict.get(b"Width").and_then(Object::as_i64).ok())
+            .unwrap()
+    }
+
+    #[test]
+    fn test_object_streams_round_trip() {
+        let doc = document_with_image(300, 10);
+        let (bytes, report) = optimize(doc, &OptimizeOptions::default()).unwrap();
+        assert_eq!(report.images_downsampled, 0);
+        assert_eq!(report.optimized_bytes, bytes.len() as u64);
+
+        let reloaded = Document::load_mem(&bytes).unwrap();
+        assert_eq!(reloaded.get_pages().len(), 1);
+        assert_eq!(image_width(&reloaded), 300);
+        let page_id = reloaded.page_iter().next().unwrap();
+        assert_eq!(
+            reloaded.get_page_content(page_id).unwrap(),
+            b"q 100 0 0 100 0 0 cm /Im0 Do Q"
+        );
+    }
+
+    #[test]
+    fn test_downsamples_large_images() {
+        let doc = document_with_image(4000, 20);
+        let options = OptimizeOptions {
+            object_streams: false,
+            max_image_size: Some(1000),
+        };
+        let (bytes, report) = optimize(doc, &options).unwrap();
+        assert_eq!(report.images_downsampled, 1);
+        assert_eq!(image_width(&Document::load_mem(&bytes).unwrap()), 1000);
+    }
+
+    #[test]
+    fn test_write_escaped_string() {
+        let mut out = Vec::new();
+        write_object(
+            &mut out,
+            &Object::String(b"a(b)\\".to_vec(), StringFormat::Literal),
+        );
+        assert_eq!(out, b"(a\\(b\\)\\\\)");
+    }
+}
@@ -0,0 +1,232 @@
+/*
+ * This file is part of StreamSlate.
+ * Copyright (C) 2025 StreamSlate Contributors
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+
+//! Document outline (bookmarks)
+//!
+//! Reads the top-level entries of the `/Outlines` tree, which presentation
+//! tools export as the deck's sections, together with the page each one
+//! points at.
+
+use super::{decode_text_string, resolve};
+use lopdf::{Dictionary, Document, Object, ObjectId};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashSet};
+
+/// Guard against malicious, cyclic outline and name trees
+const MAX_OUTLINE_ENTRIES: usize = 1000;
+const MAX_NAME_TREE_DEPTH: usize = 32;
+
+/// A top-level outline entry and the page it opens
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OutlineEntry {
+    pub title: String,
+    pub page: u32,
+}
+
+/// Top-level outline entries in document order
+///
+/// Entries whose destination can't be resolved to a page are skipped.
+pub fn top_level_entries(document: &Document) -> Vec<OutlineEntry> {
+    let first = document
+        .catalog()
+        .and_then(|catalog| catalog.get(b"Outlines"))
+        .map(|obj| resolve(document, obj))
+        .and_then(Object::as_dict)
+        .and_then(|outlines| outlines.get(b"First"))
+        .and_then(Object::as_reference);
+    let Ok(first) = first else {
+        return Vec::new();
+    };
+
+    let pages: BTreeMap<ObjectId, u32> = document
+        .get_pages()
+        .into_iter()
+        .map(|(number, id)| (id, number))
+        .collect();
+
+    let mut entries = Vec::new();
+    let mut seen = HashSet::new();
+    let mut next = Some(first);
+    while let Some(id) = next.filter(|id| seen.insert(*id) && seen.len() <= MAX_OUTLINE_ENTRIES) {
+        let Ok(item) = document.get_dictionary(id) else {
+            break;
+        };
+        let title = match item.get(b"Title").map(|obj| resolve(document, obj)) {
+            Ok(Object::String(bytes, _)) => decode_text_string(bytes),
+            _ => String::new(),
+        };
+        if let Some(page) = item_page(document, item).and_then(|id| pages.get(&id)) {
+            entries.push(OutlineEntry {
+                title: title.trim().to_string(),
+                page: *page,
+            });
+        }
+        next = item.get(b"Next").and_then(Object::as_reference).ok();
+    }
+    entries
+}
+
+/// Page object an outline item opens, via `/Dest` or a GoTo action
+fn item_page(document: &Document, item: &Dictionary) -> Option<ObjectId> {
+    let dest = match item.get(b"Dest") {
+        Ok(dest) => dest,
+        Err(_) => {
+            let action = resolve(document, item.get(b"A").ok()?).as_dict().ok()?;
+            if action.get(b"S").and_then(Object::as_name).ok()? != b"GoTo" {
+                return None;
+            }
+            action.get(b"D").ok()?
+        }
+    };
+    destination_page(document, resolve(document, dest), 0)
+}
+
+fn destination_page(document: &Document, dest: &Object, depth: usize) -> Option<ObjectId> {
+    if depth > 2 {
+        return None;
+    }
+    match dest {
+        // Explicit destination: [page /XYZ left top zoom] and friends
+        Object::Array(array) => array.first()?.as_reference().ok(),
+        // Named destination entries may wrap the array in a dictionary
+        Object::Dictionary(dict) => {
+            destination_page(document, resolve(document, dict.get(b"D").ok()?), depth + 1)
+        }
+        Object::Name(name) => named_destination(document, name)
+            .and_then(|dest| destination_page(document, dest, depth + 1)),
+        Object::String(name, _) => named_destination(document, name)
+            .and_then(|dest| destination_page(document, dest, depth + 1)),
+        _ => None,
+    }
+}
+
+/// Look a name up in the catalog's `/Dests` dictionary or `/Names /Dests` tree
+fn named_destination<'a>(document: &'a Document, name: &[u8]) -> Option<&'a Object> {
+    let catalog = document.catalog().ok()?;
+    if let Ok(dests) = catalog
+        .get(b"Dests")
+        .map(|obj| resolve(document, obj))
+        .and_then(Object::as_dict)
+    {
+        if let Ok(dest) = dests.get(name) {
+            return Some(resolve(document, dest));
+        }
+    }
+
+    let tree = catalog
+        .get(b"Names")
+        .map(|obj| resolve(document, obj))
+        .and_then(Object::as_dict)
+        .and_then(|names| names.get(b"Dests"))
+        .map(|obj| resolve(document, obj))
+        .and_then(Object::as_dict)
+        .ok()?;
+    find_in_name_tree(document, tree, name, 0)
+}
+
+fn find_in_name_tree<'a>(
+    document: &'a Document,
+    node: &'a Dictionary,
+    name: &[u8],
+    depth: usize,
+) -> Option<&'a Object> {
+    if depth > MAX_NAME_TREE_DEPTH {
+        return None;
+    }
+    if let Ok(names) = node.get(b"Names").and_then(Object::as_array) {
+        for pair in names.chunks_exact(2) {
+            if matches!(resolve(document, &pair[0]), Object::String(key, _) if key == name) {
+                return Some(resolve(document, &pair[1]));
+            }
+        }
+    }
+    node.get(b"Kids")
+        .and_then(Object::as_array)
+        .ok()?
+        .iter()
+        .filter_map(|kid| resolve(document, kid).as_dict().ok())
+        .find_map(|kid| find_in_name_tree(document, kid, name, depth + 1))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pdf::tests::TestDocument;
+    use lopdf::dictionary;
+
+    #[test]
+    fn test_top_level_entries() {
+        let mut builder = TestDocument::new();
+        let page_ids: Vec<ObjectId> = (0..3).map(|_| builder.page("")).collect();
+        let doc = &mut builder.document;
+
+        let outlines_id = doc.new_object_id();
+        let (intro, results, broken) = (
+            doc.new_object_id(),
+            doc.new_object_id(),
+            doc.new_object_id(),
+        );
+        doc.objects.insert(
+            intro,
+            Object::Dictionary(dictionary! {
+                "Title" => Object::string_literal("Intro"),
+                "Parent" => outlines_id,
+                "Dest" => vec![page_ids[0].into(), "Fit".into()],
+                "Next" => results,
+            }),
+        );
+        doc.objects.insert(
+            results,
+            Object::Dictionary(dictionary! {
+                "Title" => Object::string_literal("Results"),
+                "Parent" => outlines_id,
+                "A" => dictionary! { "S" => "GoTo", "D" => Object::string_literal("results") },
+                "Next" => broken,
+            }),
+        );
+        doc.objects.insert(
+            broken,
+            Object::Dictionary(dictionary! {
+                "Title" => Object::string_literal("Dangling"),
+                "Parent" => outlines_id,
+                "Dest" => Object::Name(b"missing".to_vec()),
+            }),
+        );
+        doc.objects.insert(
+            outlines_id,
+            Object::Dictionary(
+                dictionary! { "Type" => "Outlines", "First" => intro, "Last" => broken },
+            ),
+        );
+        let doc = builder.finish_with(dictionary! {
+            "Outlines" => outlines_id,
+            "Names" => dictionary! {
+                "Dests" => dictionary! {
+                    "Names" => vec![
+                        Object::string_literal("results"),
+                        vec![page_ids[2].into(), "Fit".into()].into(),
+                    ],
+                },
+            },
+        });
+
+        let entries = top_level_entries(&doc);
+        let pages: Vec<_> = entries.iter().map(|e| (e.title.as_str(), e.page)).collect();
+        assert_eq!(pages, [("Intro", 1), ("Results", 3)]);
+    }
+}
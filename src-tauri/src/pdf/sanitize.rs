@@ -0,0 +1,325 @@
+/*
+ * This file is part of StreamSlate.
+ * Copyright (C) 2025 StreamSlate Contributors
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+
+//! Opt-in sanitization of untrusted documents
+//!
+//! Strips JavaScript and launch actions and embedded executables before a
+//! document is cached in state. Dangerous objects are deleted outright, and
+//! every reference to them (dictionary entries, array items, name-tree
+//! pairs) is dropped so nothing dangles.
+
+use super::decode_text_string;
+use lopdf::{Dictionary, Document, Object, ObjectId};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+
+/// Attachment extensions treated as executable
+const EXECUTABLE_EXTENSIONS: &[&str] = &[
+    "exe", "com", "bat", "cmd", "scr", "msi", "dll", "ps1", "vbs", "js", "jar", "sh", "app", "dmg",
+    "pkg", "apk", "lnk",
+];
+
+/// Category of removed content
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RemovedKind {
+    JavascriptAction,
+    LaunchAction,
+    EmbeddedExecutable,
+}
+
+/// One piece of content removed by the sanitizer
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RemovedItem {
+    pub kind: RemovedKind,
+    /// Object number, if the content was an indirect object
+    pub object: Option<u32>,
+    /// File name or launch target, when known
+    pub detail: Option<String>,
+}
+
+/// Everything removed from a document
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SanitizeReport {
+    pub removed: Vec<RemovedItem>,
+}
+
+impl SanitizeReport {
+    /// Whether the document contained nothing to remove
+    pub fn is_clean(&self) -> bool {
+        self.removed.is_empty()
+    }
+
+    pub fn count(&self, kind: RemovedKind) -> usize {
+        self.removed.iter().filter(|item| item.kind == kind).count()
+    }
+}
+
+/// Remove dangerous actions and executables from `document` in place
+pub fn sanitize(document: &mut Document) -> SanitizeReport {
+    let mut report = SanitizeReport::default();
+
+    // 1. Find dangerous indirect objects, plus the data streams of executables
+    let mut dangerous = HashSet::new();
+    let mut payload_streams = HashSet::new();
+    for (&id, object) in &document.objects {
+        let Some(dict) = as_dict(object) else {
+            continue;
+        };
+        if let Some(item) = classify(dict, Some(id)) {
+            if item.kind == RemovedKind::EmbeddedExecutable {
+                payload_streams.extend(embedded_stream_ids(dict));
+            }
+            dangerous.insert(id);
+            report.removed.push(item);
+        }
+    }
+
+    for id in dangerous.iter().chain(&payload_streams) {
+        document.objects.remove(id);
+    }
+    dangerous.extend(payload_streams);
+
+    // 2. Drop every reference to them, and any inline dangerous values
+    for object in document.objects.values_mut() {
+        scrub(object, &dangerous, &mut report);
+    }
+    for object in document.trailer.iter_mut().map(|(_, value)| value) {
+        scrub(object, &dangerous, &mut report);
+    }
+
+    report
+}
+
+fn as_dict(object: &Object) -> Option<&Dictionary> {
+    match object {
+        Object::Dictionary(dict) => Some(dict),
+        Object::Stream(stream) => Some(&stream.dict),
+        _ => None,
+    }
+}
+
+/// Classify a dictionary as a dangerous action or executable file spec
+fn classify(dict: &Dictionary, id: Option<ObjectId>) -> Option<RemovedItem> {
+    let object = id.map(|(number, _)| number);
+    let action = dict.get(b"S").and_then(Object::as_name).ok();
+
+    if action == Some(b"JavaScript") || dict.has(b"JS") {
+        return Some(RemovedItem {
+            kind: RemovedKind::JavascriptAction,
+            object,
+            detail: None,
+        });
+    }
+
+    if action == Some(b"Launch") {
+        return Some(RemovedItem {
+            kind: RemovedKind::LaunchAction,
+            object,
+            detail: launch_target(dict),
+        });
+    }
+
+    let is_file_spec = dict.has(b"EF")
+        || dict
+            .get(b"Type")
+            .and_then(Object::as_name)
+            .is_ok_and(|t| t == b"Filespec");
+    if is_file_spec {
+        let name = file_name(dict)?;
+        if is_executable_name(&name) {
+            return Some(RemovedItem {
+                kind: RemovedKind::EmbeddedExecutable,
+                object,
+                detail: Some(name),
+            });
+        }
+    }
+
+    None
+}
+
+fn file_name(dict: &Dictionary) -> Option<String> {
+    [b"UF".as_slice(), b"F"]
+        .iter()
+        .find_map(|key| match dict.get(key) {
+            Ok(Object::String(bytes, _)) => Some(decode_text_string(bytes)),
+            _ => None,
+        })
+}
+
+fn launch_target(dict: &Dictionary) -> Option<String> {
+    match dict.get(b"F") {
+        Ok(Object::String(bytes, _)) => Some(decode_text_string(bytes)),
+        Ok(Object::Dictionary(spec)) => file_name(spec),
+        // Windows-specific launch parameters
+        _ => match dict.get(b"Win") {
+            Ok(Object::Dictionary(win)) => file_name(win),
+            _ => None,
+        },
+    }
+}
+
+fn is_executable_name(name: &str) -> bool {
+    name.rsplit_once('.').is_some_and(|(_, ext)| {
+        EXECUTABLE_EXTENSIONS
+            .iter()
+            .any(|candidate| ext.eq_ignore_ascii_case(candidate))
+    })
+}
+
+/// Embedded file stream references from a file spec's `/EF` dictionary
+fn embedded_stream_ids(spec: &Dictionary) -> Vec<ObjectId> {
+    match spec.get(b"EF") {
+        Ok(Object::Dictionary(ef)) => ef
+            .iter()
+            .filter_map(|(_, value)| value.as_reference().ok())
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+/// Whether a value refers to removed content (recording inline removals)
+fn is_dangerous(
+    value: &Object,
+    dangerous: &HashSet<ObjectId>,
+    report: &mut SanitizeReport,
+) -> bool {
+    match value {
+        Object::Reference(id) => dangerous.contains(id),
+        Object::Dictionary(dict) => match classify(dict, None) {
+            Some(item) => {
+                report.removed.push(item);
+                true
+            }
+            None => false,
+        },
+        _ => false,
+    }
+}
+
+fn scrub(object: &mut Object, dangerous: &HashSet<ObjectId>, report: &mut SanitizeReport) {
+    match object {
+        Object::Dictionary(dict) => scrub_dict(dict, dangerous, report),
+        Object::Stream(stream) => scrub_dict(&mut stream.dict, dangerous, report),
+        Object::Array(items) => {
+            items.retain(|item| !is_dangerous(item, dangerous, report));
+            for item in items {
+                scrub(item, dangerous, report);
+            }
+        }
+        _ => {}
+    }
+}
+
+fn scrub_dict(dict: &mut Dictionary, dangerous: &HashSet<ObjectId>, report: &mut SanitizeReport) {
+    let doomed: Vec<Vec<u8>> = dict
+        .iter()
+        .filter(|(_, value)| is_dangerous(value, dangerous, report))
+        .map(|(key, _)| key.clone())
+        .collect();
+    for key in doomed {
+        dict.remove(&key);
+    }
+
+    for (key, value) in dict.iter_mut() {
+        // Name trees store [key value key value ...]; drop whole pairs
+        if key.as_slice() == b"Names" {
+            if let Object::Array(pairs) = value {
+                let mut kept = Vec::with_capacity(pairs.len());
+                for pair in pairs.chunks(2) {
+                    if !pair
+                        .iter()
+                        .any(|item| is_dangerous(item, dangerous, report))
+                    {
+                        kept.extend_from_slice(pair);
+                    }
+                }
+                *pairs = kept;
+            }
+        }
+        scrub(value, dangerous, report);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pdf::attachments::{list_attachments, tests::document_with_attachment};
+    use lopdf::dictionary;
+
+    #[test]
+    fn test_sanitize_removes_actions_and_executables() {
+        let mut doc = document_with_attachment("payload.exe", b"MZ");
+
+        let launch_id = doc.add_object(dictionary! {
+            "S" => "Launch",
+            "F" => Object::string_literal("calc.exe"),
+        });
+        let js_id = doc.add_object(dictionary! {
+            "S" => "JavaScript",
+            "JS" => Object::string_literal("app.alert(1)"),
+        });
+        let page_id = doc.add_object(dictionary! {
+            "Type" => "Page",
+            "AA" => dictionary! { "O" => launch_id },
+        });
+
+        let catalog_id = doc.trailer.get(b"Root").unwrap().as_reference().unwrap();
+        let catalog = doc.get_dictionary_mut(catalog_id).unwrap();
+        catalog.set(
+            "OpenAction",
+            dictionary! { "S" => "JavaScript", "JS" => Object::string_literal("this.print()") },
+        );
+        catalog.set(
+            "Pages",
+            dictionary! { "Kids" => vec![page_id.into()], "Count" => 1 },
+        );
+        let names_id = catalog.get(b"Names").unwrap().as_reference().unwrap();
+        doc.get_dictionary_mut(names_id).unwrap().set(
+            "JavaScript",
+            dictionary! { "Names" => vec![Object::string_literal("init"), js_id.into()] },
+        );
+
+        let report = sanitize(&mut doc);
+
+        assert_eq!(report.count(RemovedKind::JavascriptAction), 2);
+        assert_eq!(report.count(RemovedKind::LaunchAction), 1);
+        assert_eq!(report.count(RemovedKind::EmbeddedExecutable), 1);
+        assert!(report
+            .removed
+            .iter()
+            .any(|item| item.detail.as_deref() == Some("calc.exe")));
+
+        assert!(list_attachments(&doc).is_empty());
+        assert!(doc.get_object(js_id).is_err());
+        let catalog = doc.get_dictionary(catalog_id).unwrap();
+        assert!(!catalog.has(b"OpenAction"));
+        let page = doc.get_dictionary(page_id).unwrap();
+        assert!(page.get(b"AA").unwrap().as_dict().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_sanitize_keeps_safe_content() {
+        let mut doc = document_with_attachment("rundown.csv", b"a,b");
+        let report = sanitize(&mut doc);
+
+        assert!(report.is_clean());
+        assert_eq!(list_attachments(&doc).len(), 1);
+    }
+}
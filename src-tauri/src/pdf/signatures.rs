@@ -0,0 +1,731 @@
+/*
+ * This file is part of StreamSlate.
+ * Copyright (C) 2025 StreamSlate Contributors
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+
+//! Digital signature verification
+//!
+//! Checks CMS signatures (`adbe.pkcs7.detached` and `ETSI.CAdES.detached`)
+//! in the document's signature fields: the signed byte ranges must hash to
+//! the signed `messageDigest`, and the signer certificate's key must verify
+//! the signature. Certificate chains are not validated against a trust
+//! store, so `Valid` means "intact and signed by this certificate", not
+//! "signed by someone trusted".
+
+use super::der::{self, Tlv};
+use super::{decode_text_string, resolve};
+use lopdf::{Dictionary, Document, Object};
+use ring::digest;
+use ring::signature::{self, UnparsedPublicKey, VerificationAlgorithm};
+use serde::{Deserialize, Serialize};
+
+/// Guard against cyclic or absurdly deep form field trees
+const MAX_FIELD_DEPTH: usize = 32;
+
+mod oid {
+    pub const SIGNED_DATA: &[u8] = &[0x2A, 0x86, 0x48, 0x86, 0xF7, 0x0D, 0x01, 0x07, 0x02];
+    pub const MESSAGE_DIGEST: &[u8] = &[0x2A, 0x86, 0x48, 0x86, 0xF7, 0x0D, 0x01, 0x09, 0x04];
+    pub const RSA_ENCRYPTION: &[u8] = &[0x2A, 0x86, 0x48, 0x86, 0xF7, 0x0D, 0x01, 0x01, 0x01];
+    pub const SHA1_WITH_RSA: &[u8] = &[0x2A, 0x86, 0x48, 0x86, 0xF7, 0x0D, 0x01, 0x01, 0x05];
+    pub const SHA256_WITH_RSA: &[u8] = &[0x2A, 0x86, 0x48, 0x86, 0xF7, 0x0D, 0x01, 0x01, 0x0B];
+    pub const SHA384_WITH_RSA: &[u8] = &[0x2A, 0x86, 0x48, 0x86, 0xF7, 0x0D, 0x01, 0x01, 0x0C];
+    pub const SHA512_WITH_RSA: &[u8] = &[0x2A, 0x86, 0x48, 0x86, 0xF7, 0x0D, 0x01, 0x01, 0x0D];
+    pub const EC_PUBLIC_KEY: &[u8] = &[0x2A, 0x86, 0x48, 0xCE, 0x3D, 0x02, 0x01];
+    pub const ECDSA_SHA256: &[u8] = &[0x2A, 0x86, 0x48, 0xCE, 0x3D, 0x04, 0x03, 0x02];
+    pub const ECDSA_SHA384: &[u8] = &[0x2A, 0x86, 0x48, 0xCE, 0x3D, 0x04, 0x03, 0x03];
+    pub const P256: &[u8] = &[0x2A, 0x86, 0x48, 0xCE, 0x3D, 0x03, 0x01, 0x07];
+    pub const P384: &[u8] = &[0x2B, 0x81, 0x04, 0x00, 0x22];
+    pub const SHA1: &[u8] = &[0x2B, 0x0E, 0x03, 0x02, 0x1A];
+    pub const SHA256: &[u8] = &[0x60, 0x86, 0x48, 0x01, 0x65, 0x03, 0x04, 0x02, 0x01];
+    pub const SHA384: &[u8] = &[0x60, 0x86, 0x48, 0x01, 0x65, 0x03, 0x04, 0x02, 0x02];
+    pub const SHA512: &[u8] = &[0x60, 0x86, 0x48, 0x01, 0x65, 0x03, 0x04, 0x02, 0x03];
+    pub const COMMON_NAME: &[u8] = &[0x55, 0x04, 0x03];
+}
+
+/// Outcome of checking a single signature
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SignatureStatus {
+    /// Signed bytes are intact and the signature verifies
+    Valid,
+    /// Tampered, malformed, or the signature does not verify
+    Invalid,
+    /// Signature type or algorithm we can't check
+    Unsupported,
+}
+
+/// A signature field and its verification result
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PdfSignature {
+    /// Fully qualified form field name
+    pub field_name: Option<String>,
+    /// Common name from the signer certificate, or the `/Name` entry
+    pub signer: Option<String>,
+    /// Signing time as recorded in the signature dictionary (`/M`)
+    pub signing_time: Option<String>,
+    pub reason: Option<String>,
+    pub location: Option<String>,
+    pub sub_filter: Option<String>,
+    pub status: SignatureStatus,
+    /// Why the signature is not valid, or what couldn't be checked
+    pub message: Option<String>,
+    /// False when the file was changed (incrementally) after signing
+    pub covers_whole_document: bool,
+}
+
+impl PdfSignature {
+    /// Whether the document should be flagged before it is shown on stream
+    pub fn needs_warning(&self) -> bool {
+        match self.status {
+            SignatureStatus::Invalid => true,
+            SignatureStatus::Valid => !self.covers_whole_document,
+            SignatureStatus::Unsupported => false,
+        }
+    }
+}
+
+/// Whether the document has any filled signature fields
+pub fn has_signatures(document: &Document) -> bool {
+    !signature_fields(document).is_empty()
+}
+
+/// Verify every signature in `document`, whose on-disk bytes are `file`
+pub fn verify_signatures(document: &Document, file: &[u8]) -> Vec<PdfSignature> {
+    signature_fields(document)
+        .into_iter()
+        .map(|(field_name, sig)| describe_signature(document, field_name, sig, file))
+        .collect()
+}
+
+/// Collect (field name, signature dictionary) for signed `/Sig` fields
+fn signature_fields(document: &Document) -> Vec<(Option<String>, &Dictionary)> {
+    let mut out = Vec::new();
+    let fields = document
+        .catalog()
+        .and_then(|catalog| catalog.get(b"AcroForm"))
+        .map(|obj| resolve(document, obj))
+        .and_then(Object::as_dict)
+        .and_then(|form| form.get(b"Fields"))
+        .map(|obj| resolve(document, obj))
+        .and_then(Object::as_array);
+
+    if let Ok(fields) = fields {
+        for field in fields {
+            collect_fields(document, field, None, false, 0, &mut out);
+        }
+    }
+    out
+}
+
+fn collect_fields<'a>(
+    document: &'a Document,
+    field: &'a Object,
+    parent_name: Option<&str>,
+    inherited_sig: bool,
+    depth: usize,
+    out: &mut Vec<(Option<String>, &'a Dictionary)>,
+) {
+    if depth > MAX_FIELD_DEPTH {
+        return;
+    }
+    let Ok(dict) = resolve(document, field).as_dict() else {
+        return;
+    };
+
+    let partial = match dict.get(b"T").map(|obj| resolve(document, obj)) {
+        Ok(Object::String(bytes, _)) => Some(decode_text_string(bytes)),
+        _ => None,
+    };
+    let name = match (parent_name, partial) {
+        (Some(parent), Some(partial)) => Some(format!("{parent}.{partial}")),
+        (parent, partial) => partial.or_else(|| parent.map(String::from)),
+    };
+    let is_sig = dict
+        .get(b"FT")
+        .and_then(Object::as_name)
+        .map(|ft| ft == b"Sig")
+        .unwrap_or(inherited_sig);
+
+    if is_sig {
+        if let Ok(value) = dict
+            .get(b"V")
+            .map(|obj| resolve(document, obj))
+            .and_then(Object::as_dict)
+        {
+            out.push((name.clone(), value));
+        }
+    }
+
+    if let Ok(kids) = dict.get(b"Kids").and_then(Object::as_array) {
+        for kid in kids {
+            collect_fields(document, kid, name.as_deref(), is_sig, depth + 1, out);
+        }
+    }
+}
+
+fn describe_signature(
+    document: &Document,
+    field_name: Option<String>,
+    sig: &Dictionary,
+    file: &[u8],
+) -> PdfSignature {
+    let text = |key: &[u8]| match sig.get(key).map(|obj| resolve(document, obj)) {
+        Ok(Object::String(bytes, _)) => Some(decode_text_string(bytes)),
+        _ => None,
+    };
+    let sub_filter = sig
+        .get(b"SubFilter")
+        .and_then(Object::as_name_str)
+        .ok()
+        .map(String::from);
+    let byte_range: Vec<i64> = sig
+        .get(b"ByteRange")
+        .map(|obj| resolve(document, obj))
+        .and_then(Object::as_array)
+        .map(|range| range.iter().filter_map(|n| n.as_i64().ok()).collect())
+        .unwrap_or_default();
+    let contents = match sig.get(b"Contents").map(|obj| resolve(document, obj)) {
+        Ok(Object::String(bytes, _)) => bytes.as_slice(),
+        _ => &[],
+    };
+
+    let verification = match sub_filter.as_deref() {
+        Some("adbe.pkcs7.detached" | "ETSI.CAdES.detached") => {
+            verify_detached(file, &byte_range, contents)
+        }
+        other => Verification {
+            status: SignatureStatus::Unsupported,
+            message: Some(format!(
+                "Unsupported signature type: {}",
+                other.unwrap_or("none")
+            )),
+            signer: None,
+            covers_whole_document: covers_whole_document(file, &byte_range),
+        },
+    };
+
+    PdfSignature {
+        field_name,
+        signer: verification.signer.or_else(|| text(b"Name")),
+        signing_time: text(b"M"),
+        reason: text(b"Reason"),
+        location: text(b"Location"),
+        sub_filter,
+        status: verification.status,
+        message: verification.message,
+        covers_whole_document: verification.covers_whole_document,
+    }
+}
+
+/// Result of checking the bytes of one signature
+#[derive(Debug)]
+struct Verification {
+    status: SignatureStatus,
+    message: Option<String>,
+    signer: Option<String>,
+    covers_whole_document: bool,
+}
+
+/// Verify a detached CMS signature over the given byte ranges of `file`
+fn verify_detached(file: &[u8], byte_range: &[i64], contents: &[u8]) -> Verification {
+    let covers_whole_document = covers_whole_document(file, byte_range);
+    let mut signer = None;
+
+    let result = (|| {
+        let signed = signed_bytes(file, byte_range)?;
+        let cms = parse_cms(contents).ok_or("Malformed signature data")?;
+        signer = cms.certificate.as_ref().and_then(|c| c.common_name.clone());
+        check_cms(&cms, &signed)
+    })();
+
+    let (status, message) = match result {
+        Ok(()) if covers_whole_document => (SignatureStatus::Valid, None),
+        Ok(()) => (
+            SignatureStatus::Valid,
+            Some("Document was modified after signing".to_string()),
+        ),
+        Err(Failure::Invalid(message)) => (SignatureStatus::Invalid, Some(message)),
+        Err(Failure::Unsupported(message)) => (SignatureStatus::Unsupported, Some(message)),
+    };
+
+    Verification {
+        status,
+        message,
+        signer,
+        covers_whole_document,
+    }
+}
+
+#[derive(Debug)]
+enum Failure {
+    Invalid(String),
+    Unsupported(String),
+}
+
+impl From<&str> for Failure {
+    fn from(message: &str) -> Self {
+        Self::Invalid(message.to_string())
+    }
+}
+
+fn covers_whole_document(file: &[u8], byte_range: &[i64]) -> bool {
+    matches!(byte_range, [0, _, start, len] if start + len == file.len() as i64)
+}
+
+/// Concatenate the two signed byte ranges `[a, b, c, d]`
+fn signed_bytes(file: &[u8], byte_range: &[i64]) -> Result<Vec<u8>, Failure> {
+    let [a, b, c, d] = byte_range else {
+        return Err("Signature has no valid /ByteRange".into());
+    };
+    let to_usize = |n: &i64| usize::try_from(*n).ok();
+    let (Some(a), Some(b), Some(c), Some(d)) = (to_usize(a), to_usize(b), to_usize(c), to_usize(d))
+    else {
+        return Err("Signature has a negative /ByteRange".into());
+    };
+
+    let first = a.checked_add(b).filter(|&end| end <= c);
+    let second = c.checked_add(d).filter(|&end| end <= file.len());
+    match (first, second) {
+        (Some(first_end), Some(second_end)) => {
+            Ok([&file[a..first_end], &file[c..second_end]].concat())
+        }
+        _ => Err("Signature /ByteRange lies outside the file".into()),
+    }
+}
+
+/// Fields of a CMS SignedData needed for verification
+struct Cms<'a> {
+    digest_algorithm: &'a [u8],
+    signed_attributes: Option<Tlv<'a>>,
+    signature_algorithm: &'a [u8],
+    signature: &'a [u8],
+    certificate: Option<Certificate<'a>>,
+}
+
+struct Certificate<'a> {
+    common_name: Option<String>,
+    key_algorithm: &'a [u8],
+    curve: Option<&'a [u8]>,
+    public_key: &'a [u8],
+}
+
+/// First child OID of an AlgorithmIdentifier
+fn algorithm_oid(tlv: Tlv<'_>) -> Option<&[u8]> {
+    Some(
+        tlv.expect(der::SEQUENCE)?
+            .children()?
+            .first()?
+            .expect(der::OID)?
+            .contents,
+    )
+}
+
+fn parse_cms(contents: &[u8]) -> Option<Cms<'_>> {
+    let (content_info, _) = der::read(contents)?;
+    let content_info = content_info.expect(der::SEQUENCE)?.children()?;
+    if content_info.first()?.expect(der::OID)?.contents != oid::SIGNED_DATA {
+        return None;
+    }
+    let explicit = content_info.get(1)?.expect(der::CONTEXT_0)?.children()?;
+    let signed_data = explicit.first()?.expect(der::SEQUENCE)?.children()?;
+
+    let certificates: Vec<Tlv<'_>> = signed_data
+        .iter()
+        .find(|tlv| tlv.tag == der::CONTEXT_0)
+        .and_then(|certs| certs.children())
+        .unwrap_or_default();
+    let signer_infos = signed_data.last()?.expect(der::SET)?.children()?;
+    let signer_info = signer_infos.first()?.expect(der::SEQUENCE)?.children()?;
+
+    let sid = *signer_info.get(1)?;
+    let digest_algorithm = algorithm_oid(*signer_info.get(2)?)?;
+    let (signed_attributes, rest) = match signer_info.get(3)? {
+        attrs if attrs.tag == der::CONTEXT_0 => (Some(*attrs), &signer_info[4..]),
+        _ => (None, &signer_info[3..]),
+    };
+    let signature_algorithm = algorithm_oid(*rest.first()?)?;
+    let signature = rest.get(1)?.expect(der::OCTET_STRING)?.contents;
+
+    let certificate = find_signer_certificate(&certificates, sid);
+
+    Some(Cms {
+        digest_algorithm,
+        signed_attributes,
+        signature_algorithm,
+        signature,
+        certificate,
+    })
+}
+
+/// Pick the certificate named by the signer identifier
+fn find_signer_certificate<'a>(certificates: &[Tlv<'a>], sid: Tlv<'a>) -> Option<Certificate<'a>> {
+    let issuer_and_serial = sid
+        .expect(der::SEQUENCE)
+        .and_then(|sid| sid.children())
+        .filter(|parts| parts.len() == 2);
+
+    let tbs_fields = |cert: &Tlv<'a>| -> Option<Vec<Tlv<'a>>> {
+        let tbs = cert
+            .expect(der::SEQUENCE)?
+            .children()?
+            .first()?
+            .expect(der::SEQUENCE)?;
+        let fields = tbs.children()?;
+        // Skip the optional explicit [0] version
+        let skip = usize::from(fields.first()?.tag == der::CONTEXT_0);
+        Some(fields[skip..].to_vec())
+    };
+
+    let matching = certificates.iter().find_map(|cert| {
+        let fields = tbs_fields(cert)?;
+        match &issuer_and_serial {
+            Some(parts) => (fields.first()?.expect(der::INTEGER)?.raw == parts[1].raw
+                && fields.get(2)?.raw == parts[0].raw)
+                .then_some(fields),
+            None => None,
+        }
+    });
+    // Subject key identifier signers: fall back to a lone certificate
+    let fields = matching.or_else(|| match certificates {
+        [only] => tbs_fields(only),
+        _ => None,
+    })?;
+
+    // serial, signature, issuer, validity, subject, subjectPublicKeyInfo
+    let subject = *fields.get(4)?;
+    let spki = fields.get(5)?.expect(der::SEQUENCE)?.children()?;
+    let algorithm = spki.first()?.expect(der::SEQUENCE)?.children()?;
+
+    Some(Certificate {
+        common_name: common_name(subject),
+        key_algorithm: algorithm.first()?.expect(der::OID)?.contents,
+        curve: algorithm
+            .get(1)
+            .and_then(|params| params.expect(der::OID))
+            .map(|curve| curve.contents),
+        public_key: der::bit_string_bytes(*spki.get(1)?)?,
+    })
+}
+
+/// Extract the CN attribute from an X.501 Name
+fn common_name(name: Tlv<'_>) -> Option<String> {
+    for rdn in name.expect(der::SEQUENCE)?.children()? {
+        for attribute in rdn.children()? {
+            let parts = attribute.children()?;
+            if parts.first()?.contents == oid::COMMON_NAME {
+                let value = parts.get(1)?;
+                return Some(match value.tag {
+                    // BMPString
+                    0x1E => {
+                        let units: Vec<u16> = value
+                            .contents
+                            .chunks_exact(2)
+                            .map(|pair| u16::from_be_bytes([pair[0], pair[1]]))
+                            .collect();
+                        String::from_utf16_lossy(&units)
+                    }
+                    _ => String::from_utf8_lossy(value.contents).into_owned(),
+                });
+            }
+        }
+    }
+    None
+}
+
+fn digest_for(oid: &[u8]) -> Option<&'static digest::Algorithm> {
+    match oid {
+        oid::SHA1 => Some(&digest::SHA1_FOR_LEGACY_USE_ONLY),
+        oid::SHA256 => Some(&digest::SHA256),
+        oid::SHA384 => Some(&digest::SHA384),
+        oid::SHA512 => Some(&digest::SHA512),
+        _ => None,
+    }
+}
+
+/// Check the message digest and the signature itself
+fn check_cms(cms: &Cms<'_>, signed: &[u8]) -> Result<(), Failure> {
+    let digest_algorithm = digest_for(cms.digest_algorithm)
+        .ok_or_else(|| Failure::Unsupported("Unsupported digest algorithm".to_string()))?;
+    let document_digest = digest::digest(digest_algorithm, signed);
+
+    // With signed attributes, the signature covers their DER encoding (as a
+    // SET) and the document hash is carried in the messageDigest attribute
+    let retagged;
+    let message: &[u8] = match cms.signed_attributes {
+        Some(attributes) => {
+            let expected = message_digest(attributes).ok_or("Signature has no message digest")?;
+            if expected != document_digest.as_ref() {
+                return Err("Signed content does not match the document".into());
+            }
+            retagged = [&[der::SET][..], &attributes.raw[1..]].concat();
+            &retagged
+        }
+        None => signed,
+    };
+
+    let certificate = cms
+        .certificate
+        .as_ref()
+        .ok_or("Signer certificate is missing")?;
+    let algorithm = verification_algorithm(cms, certificate)
+        .ok_or_else(|| Failure::Unsupported("Unsupported signature algorithm".to_string()))?;
+
+    UnparsedPublicKey::new(algorithm, certificate.public_key)
+        .verify(message, cms.signature)
+        .map_err(|_| "Signature does not verify against the signer certificate".into())
+}
+
+fn message_digest<'a>(attributes: Tlv<'a>) -> Option<&'a [u8]> {
+    attributes.children()?.into_iter().find_map(|attribute| {
+        let parts = attribute.children()?;
+        if parts.first()?.contents != oid::MESSAGE_DIGEST {
+            return None;
+        }
+        let values = parts.get(1)?.expect(der::SET)?.children()?;
+        Some(values.first()?.expect(der::OCTET_STRING)?.contents)
+    })
+}
+
+fn verification_algorithm(
+    cms: &Cms<'_>,
+    certificate: &Certificate<'_>,
+) -> Option<&'static dyn VerificationAlgorithm> {
+    match certificate.key_algorithm {
+        oid::RSA_ENCRYPTION => {
+            // The hash is named either by the signature OID or the digest OID
+            let hash = match cms.signature_algorithm {
+                oid::SHA1_WITH_RSA => oid::SHA1,
+                oid::SHA256_WITH_RSA => oid::SHA256,
+                oid::SHA384_WITH_RSA => oid::SHA384,
+                oid::SHA512_WITH_RSA => oid::SHA512,
+                _ => cms.digest_algorithm,
+            };
+            match hash {
+                oid::SHA1 => Some(&signature::RSA_PKCS1_2048_8192_SHA1_FOR_LEGACY_USE_ONLY),
+                oid::SHA256 => Some(&signature::RSA_PKCS1_2048_8192_SHA256),
+                oid::SHA384 => Some(&signature::RSA_PKCS1_2048_8192_SHA384),
+                oid::SHA512 => Some(&signature::RSA_PKCS1_2048_8192_SHA512),
+                _ => None,
+            }
+        }
+        oid::EC_PUBLIC_KEY => {
+            let hash = match cms.signature_algorithm {
+                oid::ECDSA_SHA256 => oid::SHA256,
+                oid::ECDSA_SHA384 => oid::SHA384,
+                _ => cms.digest_algorithm,
+            };
+            match (certificate.curve?, hash) {
+                (oid::P256, oid::SHA256) => Some(&signature::ECDSA_P256_SHA256_ASN1),
+                (oid::P256, oid::SHA384) => Some(&signature::ECDSA_P256_SHA384_ASN1),
+                (oid::P384, oid::SHA256) => Some(&signature::ECDSA_P384_SHA256_ASN1),
+                (oid::P384, oid::SHA384) => Some(&signature::ECDSA_P384_SHA384_ASN1),
+                _ => None,
+            }
+        }
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use lopdf::{dictionary, StringFormat};
+    use ring::rand::SystemRandom;
+    use ring::signature::{EcdsaKeyPair, KeyPair, ECDSA_P256_SHA256_ASN1_SIGNING};
+
+    fn tlv(tag: u8, parts: &[&[u8]]) -> Vec<u8> {
+        let contents = parts.concat();
+        let mut out = vec![tag];
+        match contents.len() {
+            len @ 0..=0x7F => out.push(len as u8),
+            len @ 0x80..=0xFF => out.extend_from_slice(&[0x81, len as u8]),
+            len => out.extend_from_slice(&[0x82, (len >> 8) as u8, len as u8]),
+        }
+        out.extend_from_slice(&contents);
+        out
+    }
+
+    /// Build a detached CMS signature over `signed` with a fresh P-256 key
+    fn sign_detached(signed: &[u8], signer: &str) -> Vec<u8> {
+        let rng = SystemRandom::new();
+        let alg = &ECDSA_P256_SHA256_ASN1_SIGNING;
+        let pkcs8 = EcdsaKeyPair::generate_pkcs8(alg, &rng).unwrap();
+        let key = EcdsaKeyPair::from_pkcs8(alg, pkcs8.as_ref(), &rng).unwrap();
+
+        let oid = |bytes: &[u8]| tlv(der::OID, &[bytes]);
+        let name = tlv(
+            der::SEQUENCE,
+            &[&tlv(
+                der::SET,
+                &[&tlv(
+                    der::SEQUENCE,
+                    &[&oid(oid::COMMON_NAME), &tlv(0x0C, &[signer.as_bytes()])],
+                )],
+            )],
+        );
+        let serial = tlv(der::INTEGER, &[&[0x01]]);
+        let ecdsa_alg = tlv(der::SEQUENCE, &[&oid(oid::ECDSA_SHA256)]);
+        let sha256_alg = tlv(der::SEQUENCE, &[&oid(oid::SHA256)]);
+
+        let spki = tlv(
+            der::SEQUENCE,
+            &[
+                &tlv(der::SEQUENCE, &[&oid(oid::EC_PUBLIC_KEY), &oid(oid::P256)]),
+                &tlv(der::BIT_STRING, &[&[0], key.public_key().as_ref()]),
+            ],
+        );
+        let tbs = tlv(
+            der::SEQUENCE,
+            &[
+                &tlv(der::CONTEXT_0, &[&tlv(der::INTEGER, &[&[0x02]])]),
+                &serial,
+                &ecdsa_alg,
+                &name,
+                &tlv(der::SEQUENCE, &[]),
+                &name,
+                &spki,
+            ],
+        );
+        let certificate = tlv(
+            der::SEQUENCE,
+            &[&tbs, &ecdsa_alg, &tlv(der::BIT_STRING, &[&[0]])],
+        );
+
+        let document_digest = digest::digest(&digest::SHA256, signed);
+        let attribute = tlv(
+            der::SEQUENCE,
+            &[
+                &oid(oid::MESSAGE_DIGEST),
+                &tlv(
+                    der::SET,
+                    &[&tlv(der::OCTET_STRING, &[document_digest.as_ref()])],
+                ),
+            ],
+        );
+        let attributes_set = tlv(der::SET, &[&attribute]);
+        let signature = key.sign(&rng, &attributes_set).unwrap();
+
+        let signer_info = tlv(
+            der::SEQUENCE,
+            &[
+                &tlv(der::INTEGER, &[&[0x01]]),
+                &tlv(der::SEQUENCE, &[&name, &serial]),
+                &sha256_alg,
+                &tlv(der::CONTEXT_0, &[&attribute]),
+                &ecdsa_alg,
+                &tlv(der::OCTET_STRING, &[signature.as_ref()]),
+            ],
+        );
+        let signed_data = tlv(
+            der::SEQUENCE,
+            &[
+                &tlv(der::INTEGER, &[&[0x01]]),
+                &tlv(der::SET, &[&sha256_alg]),
+                &tlv(
+                    der::SEQUENCE,
+                    &[&oid(&[
+                        0x2A, 0x86, 0x48, 0x86, 0xF7, 0x0D, 0x01, 0x07, 0x01,
+                    ])],
+                ),
+                &tlv(der::CONTEXT_0, &[&certificate]),
+                &tlv(der::SET, &[&signer_info]),
+            ],
+        );
+        tlv(
+            der::SEQUENCE,
+            &[
+                &oid(oid::SIGNED_DATA),
+                &tlv(der::CONTEXT_0, &[&signed_data]),
+            ],
+        )
+    }
+
+    /// A fake file with a signature hole and its byte range
+    fn signed_file() -> (Vec<u8>, Vec<i64>, Vec<u8>) {
+        let head = b"%PDF-1.7 signed contract <";
+        let tail = b"> %%EOF";
+        let file = [&head[..], &[b'0'; 64], &tail[..]].concat();
+        let range = vec![
+            0,
+            head.len() as i64,
+            (head.len() + 64) as i64,
+            tail.len() as i64,
+        ];
+        let contents = sign_detached(&[&head[..], &tail[..]].concat(), "Jane Notary");
+        (file, range, contents)
+    }
+
+    #[test]
+    fn test_valid_signature() {
+        let (file, range, contents) = signed_file();
+        let result = verify_detached(&file, &range, &contents);
+
+        assert_eq!(result.status, SignatureStatus::Valid);
+        assert_eq!(result.signer.as_deref(), Some("Jane Notary"));
+        assert!(result.covers_whole_document);
+    }
+
+    #[test]
+    fn test_tampered_and_appended_documents() {
+        let (mut file, range, contents) = signed_file();
+
+        // Appending an incremental update keeps the signature valid but partial
+        let mut appended = file.clone();
+        appended.extend_from_slice(b"\n1 0 obj (edited) endobj");
+        let result = verify_detached(&appended, &range, &contents);
+        assert_eq!(result.status, SignatureStatus::Valid);
+        assert!(!result.covers_whole_document);
+
+        file[3] = b'X';
+        let result = verify_detached(&file, &range, &contents);
+        assert_eq!(result.status, SignatureStatus::Invalid);
+
+        let result = verify_detached(&file, &range, b"garbage");
+        assert_eq!(result.status, SignatureStatus::Invalid);
+    }
+
+    #[test]
+    fn test_verify_signatures_in_document() {
+        let (file, range, contents) = signed_file();
+        let mut doc = Document::with_version("1.7");
+        let sig_id = doc.add_object(dictionary! {
+            "Type" => "Sig",
+            "Filter" => "Adobe.PPKLite",
+            "SubFilter" => "adbe.pkcs7.detached",
+            "ByteRange" => range.into_iter().map(Object::Integer).collect::<Vec<_>>(),
+            "Contents" => Object::String(contents, StringFormat::Hexadecimal),
+            "Reason" => Object::string_literal("Approved"),
+        });
+        let field_id = doc.add_object(dictionary! {
+            "FT" => "Sig",
+            "T" => Object::string_literal("Signature1"),
+            "V" => sig_id,
+        });
+        let catalog_id = doc.add_object(dictionary! {
+            "Type" => "Catalog",
+            "AcroForm" => dictionary! { "Fields" => vec![field_id.into()] },
+        });
+        doc.trailer.set("Root", catalog_id);
+
+        assert!(has_signatures(&doc));
+        let signatures = verify_signatures(&doc, &file);
+        assert_eq!(signatures.len(), 1);
+        assert_eq!(signatures[0].field_name.as_deref(), Some("Signature1"));
+        assert_eq!(signatures[0].reason.as_deref(), Some("Approved"));
+        assert_eq!(signatures[0].status, SignatureStatus::Valid);
+        assert!(!signatures[0].needs_warning());
+    }
+}
@@ -0,0 +1,856 @@
+/*
+ * This file is part of StreamSlate.
+ * Copyright (C) 2025 StreamSlate Contributors
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+
+//! Word-level text layout
+//!
+//! Interprets page content streams far enough to place every glyph, then
+//! groups glyphs into words. Rectangles are in PDF points measured from the
+//! top-left corner of the unrotated MediaBox, the same space annotations use.
+
+use super::resolve;
+use crate::error::{Result, StreamSlateError};
+use lopdf::content::Content;
+use lopdf::{Dictionary, Document, Object, ObjectId};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::rc::Rc;
+
+/// Maximum nesting of form XObjects followed while collecting text
+const MAX_FORM_DEPTH: usize = 8;
+
+/// Distance from the expected pen position, in ems, that starts a new word
+const WORD_GAP: f64 = 0.15;
+
+/// A word on a page and its bounding box
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct PageWord {
+    pub text: String,
+    pub x: f64,
+    pub y: f64,
+    pub width: f64,
+    pub height: f64,
+}
+
+/// Extract the words on a page (1-indexed) in content-stream order
+pub fn page_words(document: &Document, page_number: u32) -> Result<Vec<PageWord>> {
+    let pages = document.get_pages();
+    let page_id = *pages.get(&page_number).ok_or_else(|| {
+        StreamSlateError::InvalidPdf(format!(
+            "Page {} not found (document has {} pages)",
+            page_number,
+            pages.len()
+        ))
+    })?;
+
+    let content = document.get_page_content(page_id)?;
+    let resources = page_resources(document, page_id);
+    let mut collector = WordCollector::default();
+    run_content(document, &content, &resources, IDENTITY, 0, &mut collector);
+    collector.flush();
+
+    let (left, top) = page_origin(document, page_id);
+    Ok(collector
+        .words
+        .into_iter()
+        .map(|word| PageWord {
+            text: word.text,
+            x: word.min_x - left,
+            y: top - word.max_y,
+            width: word.max_x - word.min_x,
+            height: word.max_y - word.min_y,
+        })
+        .collect())
+}
+
+pub(super) type Matrix = [f64; 6];
+
+pub(super) const IDENTITY: Matrix = [1.0, 0.0, 0.0, 1.0, 0.0, 0.0];
+
+/// `m` followed by `n`
+pub(super) fn multiply(m: &Matrix, n: &Matrix) -> Matrix {
+    [
+        m[0] * n[0] + m[1] * n[2],
+        m[0] * n[1] + m[1] * n[3],
+        m[2] * n[0] + m[3] * n[2],
+        m[2] * n[1] + m[3] * n[3],
+        m[4] * n[0] + m[5] * n[2] + n[4],
+        m[4] * n[1] + m[5] * n[3] + n[5],
+    ]
+}
+
+fn translate(tx: f64, ty: f64) -> Matrix {
+    [1.0, 0.0, 0.0, 1.0, tx, ty]
+}
+
+pub(super) fn apply(m: &Matrix, x: f64, y: f64) -> (f64, f64) {
+    (m[0] * x + m[2] * y + m[4], m[1] * x + m[3] * y + m[5])
+}
+
+pub(super) fn number(object: &Object) -> Option<f64> {
+    object.as_float().ok().map(f64::from)
+}
+
+pub(super) fn matrix_from(array: &[Object]) -> Option<Matrix> {
+    if array.len() != 6 {
+        return None;
+    }
+    let mut m = IDENTITY;
+    for (slot, object) in m.iter_mut().zip(array) {
+        *slot = number(object)?;
+    }
+    Some(m)
+}
+
+/// Left and top edges of the page's MediaBox, following inheritance
+pub(crate) fn page_origin(document: &Document, page_id: ObjectId) -> (f64, f64) {
+    let mut node = document.get_dictionary(page_id).ok();
+    while let Some(dict) = node {
+        if let Ok(Object::Array(media_box)) = dict.get(b"MediaBox").map(|o| resolve(document, o)) {
+            let values: Vec<f64> = media_box.iter().filter_map(number).collect();
+            if values.len() == 4 {
+                return (values[0].min(values[2]), values[1].max(values[3]));
+            }
+        }
+        node = dict
+            .get(b"Parent")
+            .and_then(Object::as_reference)
+            .and_then(|id| document.get_dictionary(id))
+            .ok();
+    }
+    (0.0, 792.0)
+}
+
+/// Fonts and XObjects reachable from a content stream
+pub(super) struct Resources {
+    fonts: HashMap<Vec<u8>, Rc<Font>>,
+    pub(super) xobjects: HashMap<Vec<u8>, ObjectId>,
+}
+
+impl Resources {
+    /// Merge resource dictionaries; earlier dictionaries take precedence
+    pub(super) fn from_dicts(document: &Document, dicts: &[&Dictionary]) -> Self {
+        let mut resources = Self {
+            fonts: HashMap::new(),
+            xobjects: HashMap::new(),
+        };
+
+        for dict in dicts {
+            if let Ok(fonts) = dict
+                .get(b"Font")
+                .map(|o| resolve(document, o))
+                .and_then(Object::as_dict)
+            {
+                for (name, font) in fonts.iter() {
+                    if let Ok(font) = resolve(document, font).as_dict() {
+                        resources
+                            .fonts
+                            .entry(name.clone())
+                            .or_insert_with(|| Rc::new(Font::load(document, font)));
+                    }
+                }
+            }
+            if let Ok(xobjects) = dict
+                .get(b"XObject")
+                .map(|o| resolve(document, o))
+                .and_then(Object::as_dict)
+            {
+                for (name, xobject) in xobjects.iter() {
+                    if let Ok(id) = xobject.as_reference() {
+                        resources.xobjects.entry(name.clone()).or_insert(id);
+                    }
+                }
+            }
+        }
+
+        resources
+    }
+}
+
+/// Resources of a page and its ancestors, nearest first
+pub(super) fn page_resources(document: &Document, page_id: ObjectId) -> Resources {
+    let mut dicts = Vec::new();
+    let mut node = document.get_dictionary(page_id).ok();
+    while let Some(dict) = node {
+        if let Ok(resources) = dict
+            .get(b"Resources")
+            .map(|o| resolve(document, o))
+            .and_then(Object::as_dict)
+        {
+            dicts.push(resources);
+        }
+        node = dict
+            .get(b"Parent")
+            .and_then(Object::as_reference)
+            .and_then(|id| document.get_dictionary(id))
+            .ok();
+    }
+    Resources::from_dicts(document, &dicts)
+}
+
+/// The metrics and character mapping needed to place and decode glyphs
+struct Font {
+    two_byte: bool,
+    /// Glyph widths in thousandths of an em
+    widths: HashMap<u32, f64>,
+    default_width: f64,
+    to_unicode: HashMap<u32, String>,
+    ascent: f64,
+    descent: f64,
+}
+
+impl Font {
+    fn load(document: &Document, dict: &Dictionary) -> Self {
+        let two_byte = dict.get(b"Subtype").and_then(Object::as_name_str).ok() == Some("Type0");
+        let mut font = Self {
+            two_byte,
+            widths: HashMap::new(),
+            default_width: if two_byte { 1000.0 } else { 500.0 },
+            to_unicode: HashMap::new(),
+            ascent: 800.0,
+            descent: -200.0,
+        };
+
+        let metrics = if two_byte {
+            let descendant = dict
+                .get(b"DescendantFonts")
+                .map(|o| resolve(document, o))
+                .and_then(Object::as_array)
+                .ok()
+                .and_then(|fonts| fonts.first())
+                .and_then(|o| resolve(document, o).as_dict().ok());
+            if let Some(cid_font) = descendant {
+                if let Some(dw) = cid_font.get(b"DW").ok().and_then(number) {
+                    font.default_width = dw;
+                }
+                if let Ok(w) = cid_font
+                    .get(b"W")
+                    .map(|o| resolve(document, o))
+                    .and_then(Object::as_array)
+                {
+                    font.widths = cid_widths(document, w);
+                }
+            }
+            descendant
+        } else {
+            let first_char = dict.get(b"FirstChar").ok().and_then(number).unwrap_or(0.0) as u32;
+            if let Ok(widths) = dict
+                .get(b"Widths")
+                .map(|o| resolve(document, o))
+                .and_then(Object::as_array)
+            {
+                for (offset, width) in widths.iter().enumerate() {
+                    if let Some(width) = number(resolve(document, width)) {
+                        font.widths.insert(first_char + offset as u32, width);
+                    }
+                }
+            }
+            let base_font = dict
+                .get(b"BaseFont")
+                .and_then(Object::as_name_str)
+                .unwrap_or("");
+            if base_font.contains("Courier") {
+                font.default_width = 600.0;
+            }
+            Some(dict)
+        };
+
+        let descriptor = metrics
+            .and_then(|d| d.get(b"FontDescriptor").ok())
+            .and_then(|o| resolve(document, o).as_dict().ok());
+        if let Some(descriptor) = descriptor {
+            let value = |key: &[u8]| {
+                descriptor
+                    .get(key)
+                    .ok()
+                    .and_then(number)
+                    .filter(|v| *v != 0.0)
+            };
+            if let Some(ascent) = value(b"Ascent") {
+                font.ascent = ascent;
+            }
+            if let Some(descent) = value(b"Descent") {
+                font.descent = descent;
+            }
+            if let (false, Some(missing)) = (two_byte, value(b"MissingWidth")) {
+                font.default_width = missing;
+            }
+        }
+
+        if let Ok(stream) = dict
+            .get(b"ToUnicode")
+            .map(|o| resolve(document, o))
+            .and_then(Object::as_stream)
+        {
+            let data = stream
+                .decompressed_content()
+                .unwrap_or_else(|_| stream.content.clone());
+            font.to_unicode = parse_to_unicode(&data);
+        }
+
+        font
+    }
+
+    fn codes(&self, bytes: &[u8]) -> Vec<u32> {
+        if self.two_byte {
+            bytes
+                .chunks(2)
+                .map(|pair| pair.iter().fold(0, |code, &b| (code << 8) | u32::from(b)))
+                .collect()
+        } else {
+            bytes.iter().map(|&b| u32::from(b)).collect()
+        }
+    }
+
+    fn width(&self, code: u32) -> f64 {
+        self.widths
+            .get(&code)
+            .copied()
+            .unwrap_or(self.default_width)
+    }
+
+    fn text(&self, code: u32) -> String {
+        if let Some(text) = self.to_unicode.get(&code) {
+            return text.clone();
+        }
+        if self.two_byte {
+            return char::from_u32(code).map(String::from).unwrap_or_default();
+        }
+        win_ansi_char(code as u8).to_string()
+    }
+}
+
+/// Decode a WinAnsiEncoding byte, covering the common typographic characters
+fn win_ansi_char(byte: u8) -> char {
+    match byte {
+        0x85 => '\u{2026}',
+        0x91 => '\u{2018}',
+        0x92 => '\u{2019}',
+        0x93 => '\u{201C}',
+        0x94 => '\u{201D}',
+        0x95 => '\u{2022}',
+        0x96 => '\u{2013}',
+        0x97 => '\u{2014}',
+        other => other as char,
+    }
+}
+
+/// Parse a CID font `W` array (`c [w1 w2 ...]` and `c_first c_last w` forms)
+fn cid_widths(document: &Document, array: &[Object]) -> HashMap<u32, f64> {
+    let mut widths = HashMap::new();
+    let mut i = 0;
+    while i + 1 < array.len() {
+        let Some(first) = number(resolve(document, &array[i])) else {
+            break;
+        };
+        let first = first as u32;
+        if let Object::Array(list) = resolve(document, &array[i + 1]) {
+            for (offset, width) in list.iter().enumerate() {
+                if let Some(width) = number(resolve(document, width)) {
+                    widths.insert(first + offset as u32, width);
+                }
+            }
+            i += 2;
+        } else {
+            let (Some(last), Some(width)) = (
+                number(resolve(document, &array[i + 1])),
+                array.get(i + 2).and_then(|o| number(resolve(document, o))),
+            ) else {
+                break;
+            };
+            for code in first..=(last as u32).min(first + 0xFFFF) {
+                widths.insert(code, width);
+            }
+            i += 3;
+        }
+    }
+    widths
+}
+
+#[derive(Debug, PartialEq)]
+enum CMapToken {
+    Hex(Vec<u8>),
+    Word(String),
+    Open,
+    Close,
+}
+
+fn tokenize_cmap(data: &[u8]) -> Vec<CMapToken> {
+    let mut tokens = Vec::new();
+    let mut i = 0;
+    while i < data.len() {
+        match data[i] {
+            b'%' => {
+                while i < data.len() && data[i] != b'\n' && data[i] != b'\r' {
+                    i += 1;
+                }
+            }
+            b'<' if data.get(i + 1) == Some(&b'<') => {
+                tokens.push(CMapToken::Word("<<".to_string()));
+                i += 2;
+            }
+            b'<' => {
+                let end = data[i..]
+                    .iter()
+                    .position(|&b| b == b'>')
+                    .map_or(data.len(), |p| i + p);
+                let digits: Vec<u8> = data[i + 1..end]
+                    .iter()
+                    .filter_map(|&b| (b as char).to_digit(16).map(|d| d as u8))
+                    .collect();
+                tokens.push(CMapToken::Hex(
+                    digits
+                        .chunks(2)
+                        .map(|pair| (pair[0] << 4) | pair.get(1).copied().unwrap_or(0))
+                        .collect(),
+                ));
+                i = end + 1;
+            }
+            b'[' => {
+                tokens.push(CMapToken::Open);
+                i += 1;
+            }
+            b']' => {
+                tokens.push(CMapToken::Close);
+                i += 1;
+            }
+            b if b.is_ascii_whitespace() || b == b'>' => i += 1,
+            _ => {
+                let start = i;
+                i += 1;
+                while i < data.len()
+                    && !data[i].is_ascii_whitespace()
+                    && !b"<>[]%/(".contains(&data[i])
+                {
+                    i += 1;
+                }
+                tokens.push(CMapToken::Word(
+                    String::from_utf8_lossy(&data[start..i]).into_owned(),
+                ));
+            }
+        }
+    }
+    tokens
+}
+
+fn code_of(bytes: &[u8]) -> u32 {
+    bytes.iter().fold(0, |code, &b| (code << 8) | u32::from(b))
+}
+
+fn utf16_text(bytes: &[u8]) -> String {
+    let units: Vec<u16> = bytes
+        .chunks(2)
+        .map(|pair| u16::from_be_bytes([pair[0], pair.get(1).copied().unwrap_or(0)]))
+        .collect();
+    String::from_utf16_lossy(&units)
+}
+
+/// Parse the `bfchar` and `bfrange` sections of a ToUnicode CMap
+fn parse_to_unicode(data: &[u8]) -> HashMap<u32, String> {
+    let tokens = tokenize_cmap(data);
+    let mut map = HashMap::new();
+    let mut i = 0;
+    while i < tokens.len() {
+        match &tokens[i] {
+            CMapToken::Word(word) if word == "beginbfchar" => {
+                i += 1;
+                while let (Some(CMapToken::Hex(src)), Some(CMapToken::Hex(dst))) =
+                    (tokens.get(i), tokens.get(i + 1))
+                {
+                    map.insert(code_of(src), utf16_text(dst));
+                    i += 2;
+                }
+            }
+            CMapToken::Word(word) if word == "beginbfrange" => {
+                i += 1;
+                while let (Some(CMapToken::Hex(lo)), Some(CMapToken::Hex(hi))) =
+                    (tokens.get(i), tokens.get(i + 1))
+                {
+                    let (lo, hi) = (code_of(lo), code_of(hi).min(code_of(lo) + 0xFFFF));
+                    match tokens.get(i + 2) {
+                        Some(CMapToken::Hex(dst)) => {
+                            let mut units: Vec<u16> = dst
+                                .chunks(2)
+                                .map(|pair| {
+                                    u16::from_be_bytes([pair[0], pair.get(1).copied().unwrap_or(0)])
+                                })
+                                .collect();
+                            for code in lo..=hi {
+                                map.insert(code, String::from_utf16_lossy(&units));
+                                if let Some(last) = units.last_mut() {
+                                    *last = last.wrapping_add(1);
+                                }
+                            }
+                            i += 3;
+                        }
+                        Some(CMapToken::Open) => {
+                            i += 3;
+                            let mut code = lo;
+                            while let Some(CMapToken::Hex(dst)) = tokens.get(i) {
+                                if code <= hi {
+                                    map.insert(code, utf16_text(dst));
+                                }
+                                code += 1;
+                                i += 1;
+                            }
+                            if tokens.get(i) == Some(&CMapToken::Close) {
+                                i += 1;
+                            }
+                        }
+                        _ => break,
+                    }
+                }
+            }
+            _ => i += 1,
+        }
+    }
+    map
+}
+
+/// Text-related graphics state, saved and restored by `q`/`Q`
+#[derive(Clone)]
+struct GraphicsState {
+    ctm: Matrix,
+    font: Option<Rc<Font>>,
+    font_size: f64,
+    char_spacing: f64,
+    word_spacing: f64,
+    horizontal_scaling: f64,
+    leading: f64,
+    rise: f64,
+}
+
+/// A positioned glyph in page space
+struct Glyph {
+    text: String,
+    corners: [(f64, f64); 4],
+    origin: (f64, f64),
+    end: (f64, f64),
+    em: f64,
+}
+
+struct Word {
+    text: String,
+    min_x: f64,
+    min_y: f64,
+    max_x: f64,
+    max_y: f64,
+}
+
+/// Groups glyphs into words by whitespace and pen discontinuities
+#[derive(Default)]
+struct WordCollector {
+    words: Vec<Word>,
+    current: Option<Word>,
+    pen: Option<(f64, f64)>,
+}
+
+impl WordCollector {
+    fn push(&mut self, glyph: Glyph) {
+        if glyph.text.is_empty() {
+            self.pen = self.current.as_ref().map(|_| glyph.end);
+            return;
+        }
+        if glyph.text.chars().all(char::is_whitespace) {
+            self.flush();
+            return;
+        }
+        if let Some(pen) = self.pen {
+            let gap = (glyph.origin.0 - pen.0).hypot(glyph.origin.1 - pen.1);
+            if gap > WORD_GAP * glyph.em {
+                self.flush();
+            }
+        }
+
+        let word = self.current.get_or_insert_with(|| Word {
+            text: String::new(),
+            min_x: f64::INFINITY,
+            min_y: f64::INFINITY,
+            max_x: f64::NEG_INFINITY,
+            max_y: f64::NEG_INFINITY,
+        });
+        word.text.push_str(&glyph.text);
+        for (x, y) in glyph.corners {
+            word.min_x = word.min_x.min(x);
+            word.min_y = word.min_y.min(y);
+            word.max_x = word.max_x.max(x);
+            word.max_y = word.max_y.max(y);
+        }
+        self.pen = Some(glyph.end);
+    }
+
+    fn flush(&mut self) {
+        if let Some(word) = self.current.take() {
+            self.words.push(word);
+        }
+        self.pen = None;
+    }
+}
+
+fn run_content(
+    document: &Document,
+    content: &[u8],
+    resources: &Resources,
+    ctm: Matrix,
+    depth: usize,
+    collector: &mut WordCollector,
+) {
+    let Ok(content) = Content::decode(content) else {
+        return;
+    };
+
+    let mut gs = GraphicsState {
+        ctm,
+        font: None,
+        font_size: 0.0,
+        char_spacing: 0.0,
+        word_spacing: 0.0,
+        horizontal_scaling: 1.0,
+        leading: 0.0,
+        rise: 0.0,
+    };
+    let mut saved = Vec::new();
+    let mut tm = IDENTITY;
+    let mut tlm = IDENTITY;
+
+    for op in &content.operations {
+        let operands = &op.operands;
+        let n = |i: usize| operands.get(i).and_then(number).unwrap_or(0.0);
+        match op.operator.as_str() {
+            "q" => saved.push(gs.clone()),
+            "Q" => {
+                if let Some(previous) = saved.pop() {
+                    gs = previous;
+                }
+            }
+            "cm" => {
+                if let Some(m) = matrix_from(operands) {
+                    gs.ctm = multiply(&m, &gs.ctm);
+                }
+            }
+            "BT" => {
+                tm = IDENTITY;
+                tlm = IDENTITY;
+            }
+            "Tf" => {
+                gs.font = operands
+                    .first()
+                    .and_then(|o| o.as_name().ok())
+                    .and_then(|name| resources.fonts.get(name))
+                    .cloned();
+                gs.font_size = n(1);
+            }
+            "Tc" => gs.char_spacing = n(0),
+            "Tw" => gs.word_spacing = n(0),
+            "Tz" => gs.horizontal_scaling = n(0) / 100.0,
+            "TL" => gs.leading = n(0),
+            "Ts" => gs.rise = n(0),
+            "Td" | "TD" => {
+                if op.operator == "TD" {
+                    gs.leading = -n(1);
+                }
+                tlm = multiply(&translate(n(0), n(1)), &tlm);
+                tm = tlm;
+            }
+            "Tm" => {
+                if let Some(m) = matrix_from(operands) {
+                    tlm = m;
+                    tm = m;
+                }
+            }
+            "T*" => {
+                tlm = multiply(&translate(0.0, -gs.leading), &tlm);
+                tm = tlm;
+            }
+            "Tj" | "'" | "\"" => {
+                if op.operator == "\"" {
+                    gs.word_spacing = n(0);
+                    gs.char_spacing = n(1);
+                }
+                if op.operator != "Tj" {
+                    tlm = multiply(&translate(0.0, -gs.leading), &tlm);
+                    tm = tlm;
+                }
+                if let Some(Object::String(bytes, _)) = operands.last() {
+                    show_text(&gs, &mut tm, bytes, collector);
+                }
+            }
+            "TJ" => {
+                let Some(Object::Array(items)) = operands.first() else {
+                    continue;
+                };
+                for item in items {
+                    match item {
+                        Object::String(bytes, _) => show_text(&gs, &mut tm, bytes, collector),
+                        other => {
+                            if let Some(adjust) = number(other) {
+                                let tx = -adjust / 1000.0 * gs.font_size * gs.horizontal_scaling;
+                                tm = multiply(&translate(tx, 0.0), &tm);
+                            }
+                        }
+                    }
+                }
+            }
+            "Do" if depth < MAX_FORM_DEPTH => {
+                if let Some(id) = operands
+                    .first()
+                    .and_then(|o| o.as_name().ok())
+                    .and_then(|name| resources.xobjects.get(name))
+                {
+                    run_form(document, *id, resources, gs.ctm, depth, collector);
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Collect text from a form XObject drawn with `Do`
+fn run_form(
+    document: &Document,
+    id: ObjectId,
+    resources: &Resources,
+    ctm: Matrix,
+    depth: usize,
+    collector: &mut WordCollector,
+) {
+    let Ok(stream) = document.get_object(id).and_then(Object::as_stream) else {
+        return;
+    };
+    if stream
+        .dict
+        .get(b"Subtype")
+        .and_then(Object::as_name_str)
+        .ok()
+        != Some("Form")
+    {
+        return;
+    }
+
+    let matrix = stream
+        .dict
+        .get(b"Matrix")
+        .and_then(Object::as_array)
+        .ok()
+        .and_then(|m| matrix_from(m))
+        .unwrap_or(IDENTITY);
+    let own_resources = stream
+        .dict
+        .get(b"Resources")
+        .map(|o| resolve(document, o))
+        .and_then(Object::as_dict)
+        .ok()
+        .map(|dict| Resources::from_dicts(document, &[dict]));
+    let content = stream
+        .decompressed_content()
+        .unwrap_or_else(|_| stream.content.clone());
+
+    run_content(
+        document,
+        &content,
+        own_resources.as_ref().unwrap_or(resources),
+        multiply(&matrix, &ctm),
+        depth + 1,
+        collector,
+    );
+}
+
+fn show_text(gs: &GraphicsState, tm: &mut Matrix, bytes: &[u8], collector: &mut WordCollector) {
+    let Some(font) = &gs.font else {
+        return;
+    };
+    let size = gs.font_size;
+    let bottom = font.descent / 1000.0 * size + gs.rise;
+    let top = font.ascent / 1000.0 * size + gs.rise;
+
+    for code in font.codes(bytes) {
+        let glyph_width = font.width(code) / 1000.0 * size;
+        let word_spacing = if !font.two_byte && code == 32 {
+            gs.word_spacing
+        } else {
+            0.0
+        };
+        let advance = (glyph_width + gs.char_spacing + word_spacing) * gs.horizontal_scaling;
+        let right = glyph_width * gs.horizontal_scaling;
+
+        let trm = multiply(tm, &gs.ctm);
+        collector.push(Glyph {
+            text: font.text(code),
+            corners: [
+                apply(&trm, 0.0, bottom),
+                apply(&trm, right, bottom),
+                apply(&trm, 0.0, top),
+                apply(&trm, right, top),
+            ],
+            origin: apply(&trm, 0.0, gs.rise),
+            end: apply(&trm, advance, gs.rise),
+            em: (trm[2] * size).hypot(trm[3] * size),
+        });
+
+        *tm = multiply(&translate(advance, 0.0), tm);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pdf::tests::document_with_pages;
+
+    #[test]
+    fn test_words_split_on_spaces() {
+        let doc = document_with_pages(&["BT /F1 10 Tf 72 700 Td (Hello world) Tj ET"]);
+        let words = page_words(&doc, 1).unwrap();
+
+        assert_eq!(words.len(), 2);
+        assert_eq!(
+            words[0],
+            PageWord {
+                text: "Hello".to_string(),
+                x: 72.0,
+                y: 84.0,
+                width: 25.0,
+                height: 10.0,
+            }
+        );
+        assert_eq!(words[1].text, "world");
+        assert_eq!(words[1].x, 102.0);
+    }
+
+    #[test]
+    fn test_kerning_gaps_split_words() {
+        let doc = document_with_pages(&[
+            "BT /F1 20 Tf 1 0 0 1 100 500 Tm [(Sn) 20 (ap) -400 (To)] TJ ET",
+        ]);
+        let words = page_words(&doc, 1).unwrap();
+        let texts: Vec<&str> = words.iter().map(|w| w.text.as_str()).collect();
+        assert_eq!(texts, ["Snap", "To"]);
+        assert!(page_words(&doc, 2).is_err());
+    }
+
+    #[test]
+    fn test_parse_to_unicode() {
+        let cmap = b"2 beginbfchar <0003> <0020> <0011> <00660069> endbfchar\n\
+            1 beginbfrange <0024> <0026> <0041> endbfrange\n\
+            1 beginbfrange <0030> <0031> [<03B1> <03B2>] endbfrange";
+        let map = parse_to_unicode(cmap);
+        assert_eq!(map[&0x03], " ");
+        assert_eq!(map[&0x11], "fi");
+        assert_eq!(map[&0x26], "C");
+        assert_eq!(map[&0x31], "\u{3B2}");
+    }
+}
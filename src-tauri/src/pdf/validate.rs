@@ -0,0 +1,176 @@
+/*
+ * This file is part of StreamSlate.
+ * Copyright (C) 2025 StreamSlate Contributors
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+
+//! Structural checks run before a file is handed to lopdf
+//!
+//! A `.pdf` extension proves nothing: renamed images, archives or truncated
+//! downloads are rejected here with a specific reason rather than surfacing
+//! as an opaque parser error (or a parser walking arbitrary bytes).
+
+use crate::error::{Result, StreamSlateError};
+
+/// Bytes at the start of the file searched for the `%PDF-` header
+const HEADER_WINDOW: usize = 1024;
+
+/// Bytes at the end of the file searched for `startxref` and `%%EOF`
+const TRAILER_WINDOW: usize = 2048;
+
+/// Leading bytes of formats commonly renamed to `.pdf`
+const DISGUISED_FORMATS: &[(&[u8], &str)] = &[
+    (b"PK\x03\x04", "a ZIP archive (or Office document)"),
+    (b"\x89PNG", "a PNG image"),
+    (b"\xFF\xD8\xFF", "a JPEG image"),
+    (b"GIF8", "a GIF image"),
+    (b"\xD0\xCF\x11\xE0", "a legacy Office document"),
+    (b"{\\rtf", "an RTF document"),
+    (b"%!PS", "a PostScript file"),
+    (b"MZ", "a Windows executable"),
+    (b"\x7FELF", "an ELF executable"),
+];
+
+fn invalid(reason: impl Into<String>) -> StreamSlateError {
+    StreamSlateError::PdfInvalidFormat(reason.into())
+}
+
+/// Check the header, trailer and cross-reference offset of a PDF file
+pub fn validate_structure(data: &[u8]) -> Result<()> {
+    if data.is_empty() {
+        return Err(invalid("file is empty"));
+    }
+
+    for (magic, name) in DISGUISED_FORMATS {
+        if data.starts_with(magic) {
+            return Err(invalid(format!("file is {name}, not a PDF")));
+        }
+    }
+    let head = &data[..data.len().min(HEADER_WINDOW)];
+    if head.starts_with(b"<") || head.to_ascii_lowercase().starts_with(b"<!doctype") {
+        return Err(invalid("file is an HTML/XML document, not a PDF"));
+    }
+
+    // The spec allows junk before the header; readers accept it in the
+    // first kilobyte
+    let header = find(head, b"%PDF-").ok_or_else(|| invalid("missing %PDF- header"))?;
+    match head.get(header + 5..header + 8) {
+        Some([b'1' | b'2', b'.', minor]) if minor.is_ascii_digit() => {}
+        _ => return Err(invalid("unrecognised PDF version in header")),
+    }
+
+    let tail_start = data.len().saturating_sub(TRAILER_WINDOW);
+    let tail = &data[tail_start..];
+    if rfind(tail, b"%%EOF").is_none() {
+        return Err(invalid("missing %%EOF marker (file may be truncated)"));
+    }
+
+    let startxref = rfind(tail, b"startxref").ok_or_else(|| invalid("missing startxref"))?;
+    let offset = parse_offset(&tail[startxref + b"startxref".len()..])
+        .ok_or_else(|| invalid("unreadable startxref offset"))?;
+    // Offsets are relative to the header when there is leading junk
+    if ![offset, offset + header]
+        .into_iter()
+        .any(|at| at < data.len() && starts_xref_section(&data[at..]))
+    {
+        return Err(invalid(
+            "startxref does not point at a cross-reference section",
+        ));
+    }
+
+    Ok(())
+}
+
+/// Whether `data` begins with an `xref` table or an `N G obj` xref stream
+fn starts_xref_section(data: &[u8]) -> bool {
+    let data = trim_start(data);
+    if data.starts_with(b"xref") {
+        return true;
+    }
+    let window = &data[..data.len().min(32)];
+    let mut tokens = window
+        .split(|b| b.is_ascii_whitespace())
+        .filter(|t| !t.is_empty());
+    matches!(
+        (tokens.next(), tokens.next(), tokens.next()),
+        (Some(num), Some(gen), Some(obj))
+            if num.iter().all(u8::is_ascii_digit)
+                && gen.iter().all(u8::is_ascii_digit)
+                && obj.starts_with(b"obj")
+    )
+}
+
+fn parse_offset(data: &[u8]) -> Option<usize> {
+    let data = trim_start(data);
+    let digits = data.iter().take_while(|b| b.is_ascii_digit()).count();
+    if digits == 0 || digits > 20 {
+        return None;
+    }
+    std::str::from_utf8(&data[..digits]).ok()?.parse().ok()
+}
+
+fn trim_start(data: &[u8]) -> &[u8] {
+    let skip = data.iter().take_while(|b| b.is_ascii_whitespace()).count();
+    &data[skip..]
+}
+
+fn find(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+    haystack.windows(needle.len()).position(|w| w == needle)
+}
+
+fn rfind(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+    haystack.windows(needle.len()).rposition(|w| w == needle)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pdf::tests::document_with_pages;
+
+    fn minimal_pdf() -> Vec<u8> {
+        let mut data = Vec::new();
+        document_with_pages(&[""]).save_to(&mut data).unwrap();
+        data
+    }
+
+    fn reason(data: &[u8]) -> String {
+        validate_structure(data).unwrap_err().to_string()
+    }
+
+    #[test]
+    fn test_accepts_valid_pdf() {
+        assert!(validate_structure(&minimal_pdf()).is_ok());
+
+        // Leading junk before the header is tolerated
+        let mut prefixed = b"junk\n".to_vec();
+        prefixed.extend(minimal_pdf());
+        assert!(validate_structure(&prefixed).is_ok());
+    }
+
+    #[test]
+    fn test_rejects_disguised_and_broken_files() {
+        assert!(reason(b"\x89PNG\r\n\x1a\n....").contains("PNG image"));
+        assert!(reason(b"<!DOCTYPE html><html>").contains("HTML"));
+        assert!(reason(b"hello world").contains("%PDF- header"));
+
+        let pdf = minimal_pdf();
+        assert!(reason(&pdf[..pdf.len() / 2]).contains("%%EOF"));
+
+        let mut wrong_offset = pdf.clone();
+        let at = rfind(&wrong_offset, b"startxref").unwrap() + b"startxref\n".len();
+        wrong_offset[at] = b'9';
+        assert!(reason(&wrong_offset).contains("cross-reference"));
+    }
+}
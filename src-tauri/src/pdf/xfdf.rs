@@ -0,0 +1,1121 @@
+/*
+ * This file is part of StreamSlate.
+ * Copyright (C) 2025 StreamSlate Contributors
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+
+//! XFDF export and XFDF/FDF import of sidecar annotations
+//!
+//! Sidecar annotations are in PDF points from the top-left corner of the
+//! page, while XFDF uses PDF user space (origin at the bottom-left of the
+//! MediaBox, y up). Each annotation type maps onto the closest standard
+//! markup annotation so Acrobat and other viewers can import the file, and
+//! importing maps those markup annotations back.
+
+use super::text::number;
+use super::{decode_text_string, resolve, xml};
+use crate::commands::annotations::{Annotation, Point};
+use crate::commands::shapes::AnnotationKind;
+use crate::error::{Result, StreamSlateError};
+use lopdf::{Dictionary, Document, Object};
+use std::collections::HashMap;
+use std::fmt::Write;
+
+/// Stroke width used when an annotation doesn't specify one (frontend default)
+const DEFAULT_STROKE_WIDTH: f64 = 2.0;
+
+/// Font size used when a text annotation doesn't specify one (frontend default)
+const DEFAULT_FONT_SIZE: f64 = 14.0;
+
+/// Converts sidecar coordinates into PDF user space for one page
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PageOrigin {
+    /// Left edge of the MediaBox
+    pub left: f64,
+    /// Top edge of the MediaBox
+    pub top: f64,
+}
+
+impl PageOrigin {
+    fn point(&self, x: f64, y: f64) -> (f64, f64) {
+        (self.left + x, self.top - y)
+    }
+
+    /// Inverse of `point`: PDF user space back to sidecar coordinates
+    fn local(&self, x: f64, y: f64) -> (f64, f64) {
+        (x - self.left, self.top - y)
+    }
+
+    /// `x1,y1,x2,y2` rectangle for a top-left box
+    fn rect(&self, x: f64, y: f64, width: f64, height: f64) -> String {
+        let (x1, y1) = self.point(x.min(x + width), y.max(y + height));
+        let (x2, y2) = self.point(x.max(x + width), y.min(y + height));
+        format!("{},{},{},{}", num(x1), num(y1), num(x2), num(y2))
+    }
+}
+
+impl Default for PageOrigin {
+    /// US Letter with the MediaBox at the origin
+    fn default() -> Self {
+        Self {
+            left: 0.0,
+            top: 792.0,
+        }
+    }
+}
+
+/// Write an XFDF document for `annotations`, keyed by 1-based page number
+///
+/// `href` names the PDF the annotations belong to. Pages missing from
+/// `origins` use the default origin. Annotation types XFDF has no
+/// equivalent for are skipped.
+pub fn write_xfdf(
+    annotations: &HashMap<u32, Vec<Annotation>>,
+    origins: &HashMap<u32, PageOrigin>,
+    href: Option<&str>,
+) -> String {
+    let mut out = String::from(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n\
+         <xfdf xmlns=\"http://ns.adobe.com/xfdf/\" xml:space=\"preserve\">\n",
+    );
+    if let Some(href) = href {
+        let _ = writeln!(out, "  <f href=\"{}\"/>", escape(href));
+    }
+    out.push_str("  <annots>\n");
+
+    let mut pages: Vec<&u32> = annotations.keys().collect();
+    pages.sort();
+    for page in pages {
+        let origin = origins.get(page).copied().unwrap_or_default();
+        for annotation in &annotations[page] {
+            if let Some(element) = write_annotation(annotation, page.saturating_sub(1), origin) {
+                out.push_str(&element);
+            }
+        }
+    }
+
+    out.push_str("  </annots>\n</xfdf>\n");
+    out
+}
+
+/// One annotation element, or `None` for unsupported types
+fn write_annotation(
+    annotation: &Annotation,
+    page_index: u32,
+    origin: PageOrigin,
+) -> Option<String> {
+    let a = annotation;
+    let stroke = a.stroke_width.unwrap_or(DEFAULT_STROKE_WIDTH);
+    let mut attrs = format!(
+        "page=\"{page_index}\" name=\"{}\" opacity=\"{}\"",
+        escape(&a.id),
+        num(a.opacity.clamp(0.0, 1.0))
+    );
+    if let Some(author) = &a.author {
+        let _ = write!(attrs, " title=\"{}\"", escape(author));
+    }
+    if let Some(date) = pdf_date(&a.modified) {
+        let _ = write!(attrs, " date=\"{date}\"");
+    }
+    if let Some(date) = pdf_date(&a.created) {
+        let _ = write!(attrs, " creationdate=\"{date}\"");
+    }
+    if !a.visible {
+        attrs.push_str(" flags=\"hidden\"");
+    }
+
+    let (tag, extra, children) = match a.annotation_type {
+        AnnotationKind::Highlight => {
+            let (x1, y1) = origin.point(a.x.min(a.x + a.width), a.y.min(a.y + a.height));
+            let (x2, y2) = origin.point(a.x.max(a.x + a.width), a.y.max(a.y + a.height));
+            // Quadrilateral corners: upper-left, upper-right, lower-left, lower-right
+            let coords = [x1, y1, x2, y1, x1, y2, x2, y2].map(num).join(",");
+            (
+                "highlight",
+                format!(
+                    " rect=\"{}\"{} coords=\"{coords}\"",
+                    origin.rect(a.x, a.y, a.width, a.height),
+                    color_attr("color", &a.color)
+                ),
+                String::new(),
+            )
+        }
+        kind @ (AnnotationKind::Rect | AnnotationKind::Ellipse) => (
+            if kind == AnnotationKind::Rect {
+                "square"
+            } else {
+                "circle"
+            },
+            format!(
+                " rect=\"{}\"{} width=\"{}\"",
+                origin.rect(a.x, a.y, a.width, a.height),
+                color_attr("color", &a.color),
+                num(stroke)
+            ),
+            String::new(),
+        ),
+        kind @ (AnnotationKind::Arrow | AnnotationKind::Line) => {
+            // Padded by the stroke so the arrowhead isn't clipped
+            let pad = stroke * 3.0;
+            let (sx, sy) = origin.point(a.x, a.y);
+            let (ex, ey) = origin.point(a.x + a.width, a.y + a.height);
+            let tail = if kind == AnnotationKind::Arrow {
+                "OpenArrow"
+            } else {
+                "None"
+            };
+            (
+                "line",
+                format!(
+                    " rect=\"{},{},{},{}\"{} width=\"{}\" start=\"{},{}\" end=\"{},{}\" head=\"None\" tail=\"{tail}\"",
+                    num(sx.min(ex) - pad),
+                    num(sy.min(ey) - pad),
+                    num(sx.max(ex) + pad),
+                    num(sy.max(ey) + pad),
+                    color_attr("color", &a.color),
+                    num(stroke),
+                    num(sx),
+                    num(sy),
+                    num(ex),
+                    num(ey)
+                ),
+                String::new(),
+            )
+        }
+        AnnotationKind::Ink => {
+            let points = a.points.as_deref().filter(|p| !p.is_empty())?;
+            let converted: Vec<(f64, f64)> =
+                points.iter().map(|p| origin.point(p.x, p.y)).collect();
+            let (mut x1, mut y1, mut x2, mut y2) = (f64::MAX, f64::MAX, f64::MIN, f64::MIN);
+            for &(x, y) in &converted {
+                (x1, y1, x2, y2) = (x1.min(x), y1.min(y), x2.max(x), y2.max(y));
+            }
+            let pad = stroke / 2.0;
+            let gesture = converted
+                .iter()
+                .map(|&(x, y)| format!("{},{}", num(x), num(y)))
+                .collect::<Vec<_>>()
+                .join(";");
+            (
+                "ink",
+                format!(
+                    " rect=\"{},{},{},{}\"{} width=\"{}\"",
+                    num(x1 - pad),
+                    num(y1 - pad),
+                    num(x2 + pad),
+                    num(y2 + pad),
+                    color_attr("color", &a.color),
+                    num(stroke)
+                ),
+                format!("      <inklist><gesture>{gesture}</gesture></inklist>\n"),
+            )
+        }
+        AnnotationKind::Text => {
+            // Same box the frontend draws around the baseline at (x, y)
+            let font_size = a.font_size.unwrap_or(DEFAULT_FONT_SIZE);
+            let height = a.height.max(font_size + 8.0);
+            let (r, g, b) = parse_color(&a.color).unwrap_or((0, 0, 0));
+            let appearance = format!(
+                "/Helv {} Tf {} {} {} rg",
+                num(font_size),
+                num(f64::from(r) / 255.0),
+                num(f64::from(g) / 255.0),
+                num(f64::from(b) / 255.0)
+            );
+            (
+                "freetext",
+                format!(
+                    " rect=\"{}\"{}",
+                    origin.rect(a.x - 2.0, a.y - font_size, a.width + 4.0, height),
+                    a.background_color
+                        .as_deref()
+                        .map(|c| color_attr("color", c))
+                        .unwrap_or_default()
+                ),
+                format!(
+                    "      <defaultappearance>{}</defaultappearance>\n",
+                    escape(&appearance)
+                ),
+            )
+        }
+        _ => return None,
+    };
+
+    let mut element = format!("    <{tag} {attrs}{extra}>\n");
+    if !a.content.is_empty() {
+        let _ = writeln!(element, "      <contents>{}</contents>", escape(&a.content));
+    }
+    element.push_str(&children);
+    let _ = writeln!(element, "    </{tag}>");
+    Some(element)
+}
+
+/// ` name="#RRGGBB"` for a valid hex colour, empty otherwise
+fn color_attr(name: &str, color: &str) -> String {
+    parse_color(color)
+        .map(|(r, g, b)| format!(" {name}=\"#{r:02X}{g:02X}{b:02X}\""))
+        .unwrap_or_default()
+}
+
+/// Parse `#rgb` or `#rrggbb`
+pub(crate) fn parse_color(color: &str) -> Option<(u8, u8, u8)> {
+    let hex = color.strip_prefix('#')?;
+    if !hex.is_ascii() {
+        return None;
+    }
+    let channel = |s: &str| u8::from_str_radix(s, 16).ok();
+    match hex.len() {
+        3 => {
+            let expand = |i: usize| channel(&hex[i..=i]).map(|v| v * 17);
+            Some((expand(0)?, expand(1)?, expand(2)?))
+        }
+        6 => Some((
+            channel(&hex[0..2])?,
+            channel(&hex[2..4])?,
+            channel(&hex[4..6])?,
+        )),
+        _ => None,
+    }
+}
+
+/// RFC 3339 timestamp as a PDF date string in UTC
+fn pdf_date(rfc3339: &str) -> Option<String> {
+    let date = chrono::DateTime::parse_from_rfc3339(rfc3339).ok()?;
+    Some(
+        date.with_timezone(&chrono::Utc)
+            .format("D:%Y%m%d%H%M%S+00'00'")
+            .to_string(),
+    )
+}
+
+/// Coordinate with at most two decimals and no trailing zeros
+fn num(value: f64) -> String {
+    let rounded = format!("{value:.2}");
+    let trimmed = rounded.trim_end_matches('0').trim_end_matches('.');
+    if trimmed == "-0" {
+        "0".to_string()
+    } else {
+        trimmed.to_string()
+    }
+}
+
+/// Escape text for XML attributes and content
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            '\n' => escaped.push_str("&#10;"),
+            '\r' => escaped.push_str("&#13;"),
+            // Control characters are not allowed in XML 1.0
+            c if c.is_control() && c != '\t' => {}
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// Annotations read from an XFDF or FDF file
+#[derive(Debug, Clone, Default)]
+pub struct ImportedAnnotations {
+    pub annotations: Vec<Annotation>,
+    /// Markup annotations with no sidecar equivalent
+    pub skipped: usize,
+}
+
+/// A markup annotation as stored in XFDF or FDF, in PDF user space
+#[derive(Debug, Clone, Default)]
+struct Markup {
+    /// Lowercase subtype, as in the XFDF element name
+    subtype: String,
+    /// 0-based page index
+    page: u32,
+    rect: Option<[f64; 4]>,
+    color: Option<String>,
+    opacity: Option<f64>,
+    name: Option<String>,
+    /// Author (`title` in XFDF, `/T` in FDF)
+    author: Option<String>,
+    contents: String,
+    modified: Option<String>,
+    created: Option<String>,
+    width: Option<f64>,
+    quad_points: Vec<f64>,
+    line: Option<[f64; 4]>,
+    ink: Vec<Vec<(f64, f64)>>,
+    appearance: Option<String>,
+    hidden: bool,
+}
+
+/// Read the annotations of an XFDF document
+///
+/// Pages missing from `origins` use the default origin.
+pub fn read_xfdf(xfdf: &str, origins: &HashMap<u32, PageOrigin>) -> Result<ImportedAnnotations> {
+    let root = xml::parse(xfdf)?;
+    if root.name != "xfdf" {
+        return Err(invalid_import("root element is not <xfdf>"));
+    }
+
+    let markups = root
+        .child("annots")
+        .into_iter()
+        .flat_map(|annots| annots.elements())
+        .map(markup_from_element)
+        .collect::<Vec<_>>();
+    Ok(import_markups(markups, origins))
+}
+
+/// Read the annotations of an FDF file
+///
+/// FDF is PDF syntax without a cross-reference table, so one is rebuilt
+/// from the object headers before handing the file to lopdf.
+pub fn read_fdf(fdf: &[u8], origins: &HashMap<u32, PageOrigin>) -> Result<ImportedAnnotations> {
+    let document = Document::load_mem(&fdf_as_pdf(fdf)?)
+        .map_err(|e| invalid_import(&format!("unreadable FDF: {e}")))?;
+
+    let fdf_dict = document
+        .trailer
+        .get(b"Root")
+        .and_then(|root| document.dereference(root))
+        .and_then(|(_, root)| root.as_dict())
+        .and_then(|root| root.get(b"FDF"))
+        .map(|fdf| resolve(&document, fdf))
+        .and_then(Object::as_dict)
+        .map_err(|_| invalid_import("missing /FDF dictionary"))?;
+
+    let markups = match fdf_dict.get(b"Annots").map(|a| resolve(&document, a)) {
+        Ok(Object::Array(annots)) => annots
+            .iter()
+            .filter_map(|annot| resolve(&document, annot).as_dict().ok())
+            .filter_map(|dict| markup_from_dict(&document, dict))
+            .collect(),
+        _ => Vec::new(),
+    };
+    Ok(import_markups(markups, origins))
+}
+
+fn markup_from_element(element: &xml::Element) -> Markup {
+    let numbers = |name: &str| element.attribute(name).map(parse_numbers);
+    let point = |name: &str| {
+        numbers(name)
+            .filter(|values| values.len() == 2)
+            .map(|values| (values[0], values[1]))
+    };
+
+    let line = match (point("start"), point("end")) {
+        (Some((sx, sy)), Some((ex, ey))) => Some([sx, sy, ex, ey]),
+        _ => None,
+    };
+    let ink = element
+        .child("inklist")
+        .into_iter()
+        .flat_map(|list| list.elements())
+        .filter(|gesture| gesture.name == "gesture")
+        .map(|gesture| {
+            gesture
+                .text()
+                .split(';')
+                .filter_map(|pair| {
+                    let values = parse_numbers(pair);
+                    (values.len() == 2).then(|| (values[0], values[1]))
+                })
+                .collect()
+        })
+        .collect();
+    let contents = element
+        .child("contents")
+        .or_else(|| element.child("contents-richtext"))
+        .map(|c| c.text().trim().to_string())
+        .unwrap_or_default();
+
+    Markup {
+        subtype: element.name.to_ascii_lowercase(),
+        page: element
+            .attribute("page")
+            .and_then(|p| p.trim().parse().ok())
+            .unwrap_or(0),
+        rect: numbers("rect").and_then(|values| values.try_into().ok()),
+        color: element
+            .attribute("color")
+            .and_then(parse_color)
+            .map(hex_color),
+        opacity: element
+            .attribute("opacity")
+            .and_then(|o| o.trim().parse().ok()),
+        name: element.attribute("name").map(str::to_string),
+        author: element.attribute("title").map(str::to_string),
+        contents,
+        modified: element.attribute("date").and_then(parse_pdf_date),
+        created: element.attribute("creationdate").and_then(parse_pdf_date),
+        width: element
+            .attribute("width")
+            .and_then(|w| w.trim().parse().ok()),
+        quad_points: numbers("coords").unwrap_or_default(),
+        line,
+        ink,
+        appearance: element
+            .child("defaultappearance")
+            .map(xml::Element::text)
+            .or_else(|| element.attribute("defaultappearance").map(str::to_string)),
+        hidden: element
+            .attribute("flags")
+            .is_some_and(|flags| flags.split(',').any(|f| f.trim() == "hidden")),
+    }
+}
+
+fn markup_from_dict(document: &Document, dict: &Dictionary) -> Option<Markup> {
+    let get = |key: &[u8]| dict.get(key).ok().map(|o| resolve(document, o));
+    let numbers = |key: &[u8]| match get(key) {
+        Some(Object::Array(values)) => values
+            .iter()
+            .map(|v| number(resolve(document, v)))
+            .collect::<Option<Vec<f64>>>(),
+        _ => None,
+    };
+    let text = |key: &[u8]| match get(key) {
+        Some(Object::String(bytes, _)) => Some(decode_text_string(bytes)),
+        _ => None,
+    };
+
+    let subtype = get(b"Subtype")?.as_name_str().ok()?.to_ascii_lowercase();
+    let width = match get(b"BS") {
+        Some(Object::Dictionary(bs)) => bs.get(b"W").ok().and_then(number),
+        _ => None,
+    }
+    .or_else(|| numbers(b"Border").and_then(|border| border.get(2).copied()));
+    let ink = match get(b"InkList") {
+        Some(Object::Array(strokes)) => strokes
+            .iter()
+            .filter_map(|stroke| match resolve(document, stroke) {
+                Object::Array(values) => Some(
+                    values
+                        .chunks_exact(2)
+                        .filter_map(|pair| Some((number(&pair[0])?, number(&pair[1])?)))
+                        .collect(),
+                ),
+                _ => None,
+            })
+            .collect(),
+        _ => Vec::new(),
+    };
+
+    Some(Markup {
+        subtype,
+        page: get(b"Page")
+            .and_then(|p| p.as_i64().ok())
+            .and_then(|p| u32::try_from(p).ok())
+            .unwrap_or(0),
+        rect: numbers(b"Rect").and_then(|values| values.try_into().ok()),
+        color: numbers(b"C").and_then(|c| color_from_components(&c)),
+        opacity: get(b"CA").and_then(number),
+        name: text(b"NM"),
+        author: text(b"T"),
+        contents: text(b"Contents").unwrap_or_default(),
+        modified: text(b"M").as_deref().and_then(parse_pdf_date),
+        created: text(b"CreationDate").as_deref().and_then(parse_pdf_date),
+        width,
+        quad_points: numbers(b"QuadPoints").unwrap_or_default(),
+        line: numbers(b"L").and_then(|values| values.try_into().ok()),
+        ink,
+        appearance: text(b"DA"),
+        // Annotation flag bit 2 is Hidden
+        hidden: get(b"F")
+            .and_then(|f| f.as_i64().ok())
+            .is_some_and(|f| f & 2 != 0),
+    })
+}
+
+/// Convert markup into sidecar annotations, counting what can't be mapped
+fn import_markups(markups: Vec<Markup>, origins: &HashMap<u32, PageOrigin>) -> ImportedAnnotations {
+    let mut imported = ImportedAnnotations::default();
+    for markup in markups {
+        let origin = origins.get(&(markup.page + 1)).copied().unwrap_or_default();
+        let annotations = annotations_from_markup(&markup, origin);
+        if annotations.is_empty() {
+            imported.skipped += 1;
+        }
+        imported.annotations.extend(annotations);
+    }
+    imported
+}
+
+/// Sidecar annotations for one markup annotation
+///
+/// Highlights spanning several lines and ink with several strokes become
+/// one annotation each, the first keeping the markup's name as its id.
+fn annotations_from_markup(m: &Markup, origin: PageOrigin) -> Vec<Annotation> {
+    let now = chrono::Utc::now().to_rfc3339();
+    let base = Annotation {
+        id: m
+            .name
+            .clone()
+            .filter(|name| !name.is_empty())
+            .unwrap_or_else(|| uuid::Uuid::new_v4().to_string()),
+        // Set for each markup kind below
+        annotation_type: AnnotationKind::Highlight,
+        page_number: m.page + 1,
+        x: 0.0,
+        y: 0.0,
+        width: 0.0,
+        height: 0.0,
+        content: m.contents.clone(),
+        color: m.color.clone().unwrap_or_else(|| "#000000".to_string()),
+        opacity: m.opacity.unwrap_or(1.0).clamp(0.0, 1.0),
+        stroke_width: None,
+        font_size: None,
+        background_color: None,
+        background_opacity: None,
+        created: m.created.clone().unwrap_or_else(|| now.clone()),
+        modified: m.modified.clone().unwrap_or(now),
+        visible: !m.hidden,
+        points: None,
+        author: m.author.clone(),
+        layer: None,
+        image: None,
+        ephemeral: false,
+        locked: false,
+    };
+    // Top-left box in sidecar coordinates from PDF corner points
+    let local_box = |x1: f64, y1: f64, x2: f64, y2: f64| {
+        let (x, y) = origin.local(x1.min(x2), y1.max(y2));
+        (x, y, (x2 - x1).abs(), (y2 - y1).abs())
+    };
+
+    let mut annotations = Vec::new();
+    match m.subtype.as_str() {
+        "highlight" => {
+            let mut boxes: Vec<[f64; 4]> = m
+                .quad_points
+                .chunks_exact(8)
+                .map(|quad| {
+                    let xs = [quad[0], quad[2], quad[4], quad[6]];
+                    let ys = [quad[1], quad[3], quad[5], quad[7]];
+                    [
+                        xs.iter().copied().fold(f64::MAX, f64::min),
+                        ys.iter().copied().fold(f64::MAX, f64::min),
+                        xs.iter().copied().fold(f64::MIN, f64::max),
+                        ys.iter().copied().fold(f64::MIN, f64::max),
+                    ]
+                })
+                .collect();
+            if boxes.is_empty() {
+                boxes.extend(m.rect);
+            }
+            for [x1, y1, x2, y2] in boxes {
+                let (x, y, width, height) = local_box(x1, y1, x2, y2);
+                annotations.push(Annotation {
+                    annotation_type: AnnotationKind::Highlight,
+                    x,
+                    y,
+                    width,
+                    height,
+                    ..base.clone()
+                });
+            }
+        }
+        kind @ ("square" | "circle") => {
+            if let Some([x1, y1, x2, y2]) = m.rect {
+                let (x, y, width, height) = local_box(x1, y1, x2, y2);
+                annotations.push(Annotation {
+                    annotation_type: if kind == "square" {
+                        AnnotationKind::Rect
+                    } else {
+                        AnnotationKind::Ellipse
+                    },
+                    x,
+                    y,
+                    width,
+                    height,
+                    stroke_width: m.width,
+                    ..base
+                });
+            }
+        }
+        "line" => {
+            if let Some([sx, sy, ex, ey]) = m.line {
+                let (x, y) = origin.local(sx, sy);
+                let (end_x, end_y) = origin.local(ex, ey);
+                annotations.push(Annotation {
+                    annotation_type: AnnotationKind::Arrow,
+                    x,
+                    y,
+                    width: end_x - x,
+                    height: end_y - y,
+                    stroke_width: m.width,
+                    ..base
+                });
+            }
+        }
+        "ink" => {
+            for stroke in m.ink.iter().filter(|stroke| !stroke.is_empty()) {
+                let points: Vec<Point> = stroke
+                    .iter()
+                    .map(|&(px, py)| {
+                        let (x, y) = origin.local(px, py);
+                        Point::new(x, y)
+                    })
+                    .collect();
+                let (mut x1, mut y1, mut x2, mut y2) = (f64::MAX, f64::MAX, f64::MIN, f64::MIN);
+                for p in &points {
+                    (x1, y1, x2, y2) = (x1.min(p.x), y1.min(p.y), x2.max(p.x), y2.max(p.y));
+                }
+                annotations.push(Annotation {
+                    annotation_type: AnnotationKind::Ink,
+                    x: x1,
+                    y: y1,
+                    width: x2 - x1,
+                    height: y2 - y1,
+                    stroke_width: m.width,
+                    points: Some(points),
+                    ..base.clone()
+                });
+            }
+        }
+        "freetext" | "text" => {
+            if let Some([x1, y1, x2, y2]) = m.rect {
+                let (font_size, text_color) = m
+                    .appearance
+                    .as_deref()
+                    .map(parse_appearance)
+                    .unwrap_or((None, None));
+                let font_size = font_size.unwrap_or(DEFAULT_FONT_SIZE);
+                // Undo the padding `write_annotation` puts around the baseline
+                let (x, y, width, height) = local_box(x1, y1, x2, y2);
+                annotations.push(Annotation {
+                    annotation_type: AnnotationKind::Text,
+                    x: x + 2.0,
+                    y: y + font_size,
+                    width: (width - 4.0).max(0.0),
+                    height,
+                    color: text_color.unwrap_or_else(|| "#000000".to_string()),
+                    font_size: Some(font_size),
+                    background_color: if m.subtype == "freetext" {
+                        m.color.clone()
+                    } else {
+                        None
+                    },
+                    ..base
+                });
+            }
+        }
+        _ => {}
+    }
+
+    for (i, annotation) in annotations.iter_mut().enumerate().skip(1) {
+        annotation.id = format!("{}-{}", annotation.id, i + 1);
+    }
+    annotations
+}
+
+/// Make an FDF file loadable as a PDF by appending a cross-reference table
+fn fdf_as_pdf(fdf: &[u8]) -> Result<Vec<u8>> {
+    if !fdf.starts_with(b"%FDF-") {
+        return Err(invalid_import("missing %FDF header"));
+    }
+    // Same length, so object offsets stay valid
+    let mut pdf = fdf.to_vec();
+    pdf[..5].copy_from_slice(b"%PDF-");
+
+    // Later definitions of an object replace earlier ones
+    let mut offsets: std::collections::BTreeMap<u32, (u16, usize)> = Default::default();
+    for (start, _) in pdf
+        .iter()
+        .enumerate()
+        .filter(|&(i, _)| i == 0 || matches!(pdf[i - 1], b'\n' | b'\r'))
+    {
+        if let Some((id, generation)) = object_header(&pdf[start..]) {
+            offsets.insert(id, (generation, start));
+        }
+    }
+    let root = trailer_root(&pdf).ok_or_else(|| invalid_import("missing /Root in trailer"))?;
+    let size = offsets.keys().next_back().map_or(1, |max| max + 1);
+
+    let xref_start = pdf.len() + 1;
+    let mut xref = String::from("\nxref\n0 1\n0000000000 65535 f\r\n");
+    for (id, (generation, offset)) in &offsets {
+        let _ = write!(xref, "{id} 1\n{offset:010} {generation:05} n\r\n");
+    }
+    let _ = write!(
+        xref,
+        "trailer\n<< /Size {size} /Root {} {} R >>\nstartxref\n{xref_start}\n%%EOF\n",
+        root.0, root.1
+    );
+    pdf.extend_from_slice(xref.as_bytes());
+    Ok(pdf)
+}
+
+/// `(id, generation)` if `line` starts with an `N G obj` header
+fn object_header(line: &[u8]) -> Option<(u32, u16)> {
+    let mut parts = line
+        .split(|b| b.is_ascii_whitespace())
+        .filter(|part| !part.is_empty());
+    let id = std::str::from_utf8(parts.next()?).ok()?.parse().ok()?;
+    let generation = std::str::from_utf8(parts.next()?).ok()?.parse().ok()?;
+    parts
+        .next()?
+        .starts_with(b"obj")
+        .then_some((id, generation))
+}
+
+/// Catalog reference from the last trailer
+fn trailer_root(pdf: &[u8]) -> Option<(u32, u16)> {
+    let trailer = pdf.windows(7).rposition(|w| w == b"trailer")?;
+    let after = &pdf[trailer..];
+    let root = after.windows(5).position(|w| w == b"/Root")?;
+    let mut parts = after[root + 5..]
+        .split(|b| b.is_ascii_whitespace())
+        .filter(|part| !part.is_empty());
+    let id = std::str::from_utf8(parts.next()?).ok()?.parse().ok()?;
+    let generation = std::str::from_utf8(parts.next()?).ok()?.parse().ok()?;
+    parts.next()?.starts_with(b"R").then_some((id, generation))
+}
+
+/// Font size and fill colour from a default appearance string
+fn parse_appearance(appearance: &str) -> (Option<f64>, Option<String>) {
+    let tokens: Vec<&str> = appearance.split_whitespace().collect();
+    let mut font_size = None;
+    let mut color = None;
+    for (i, token) in tokens.iter().enumerate() {
+        let operands = |count: usize| -> Option<Vec<f64>> {
+            let start = i.checked_sub(count)?;
+            tokens[start..i].iter().map(|t| t.parse().ok()).collect()
+        };
+        match *token {
+            "Tf" => font_size = operands(1).map(|v| v[0]).filter(|size| *size > 0.0),
+            "rg" => color = operands(3).and_then(|c| color_from_components(&c)),
+            "g" => color = operands(1).and_then(|c| color_from_components(&c)),
+            _ => {}
+        }
+    }
+    (font_size, color)
+}
+
+/// `#rrggbb` for gray or RGB components in 0..=1
+fn color_from_components(components: &[f64]) -> Option<String> {
+    let channel = |v: f64| (v.clamp(0.0, 1.0) * 255.0).round() as u8;
+    match *components {
+        [gray] => Some(hex_color((channel(gray), channel(gray), channel(gray)))),
+        [r, g, b] => Some(hex_color((channel(r), channel(g), channel(b)))),
+        _ => None,
+    }
+}
+
+fn hex_color((r, g, b): (u8, u8, u8)) -> String {
+    format!("#{r:02x}{g:02x}{b:02x}")
+}
+
+/// Numbers separated by commas or whitespace
+fn parse_numbers(text: &str) -> Vec<f64> {
+    text.split(|c: char| c == ',' || c.is_whitespace())
+        .filter(|part| !part.is_empty())
+        .filter_map(|part| part.parse().ok())
+        .collect()
+}
+
+/// PDF date string (`D:YYYYMMDDHHmmSSOHH'mm'`) as RFC 3339
+fn parse_pdf_date(date: &str) -> Option<String> {
+    let date = date.trim();
+    let date = date.strip_prefix("D:").unwrap_or(date);
+    let digits = date.bytes().take_while(u8::is_ascii_digit).count();
+    if digits < 4 {
+        return None;
+    }
+    let field = |start: usize, len: usize, default: u32| -> u32 {
+        date.get(start..start + len)
+            .filter(|_| start + len <= digits)
+            .and_then(|s| s.parse().ok())
+            .unwrap_or(default)
+    };
+    let naive = chrono::NaiveDate::from_ymd_opt(
+        i32::try_from(field(0, 4, 0)).ok()?,
+        field(4, 2, 1),
+        field(6, 2, 1),
+    )?
+    .and_hms_opt(field(8, 2, 0), field(10, 2, 0), field(12, 2, 0))?;
+
+    let zone = &date[digits..];
+    let offset_seconds = match zone.chars().next() {
+        Some(sign @ ('+' | '-')) => {
+            let parts = parse_numbers(&zone[1..].replace('\'', " "));
+            let seconds = parts.first().copied().unwrap_or(0.0) * 3600.0
+                + parts.get(1).copied().unwrap_or(0.0) * 60.0;
+            let seconds = seconds as i32;
+            if sign == '-' {
+                -seconds
+            } else {
+                seconds
+            }
+        }
+        _ => 0,
+    };
+    let offset = chrono::FixedOffset::east_opt(offset_seconds)?;
+    Some(
+        naive
+            .and_local_timezone(offset)
+            .single()?
+            .with_timezone(&chrono::Utc)
+            .to_rfc3339(),
+    )
+}
+
+fn invalid_import(reason: &str) -> StreamSlateError {
+    StreamSlateError::Other(format!("Cannot import annotations: {reason}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::annotations::Point;
+
+    fn annotation(kind: &str) -> Annotation {
+        Annotation {
+            id: "a1".to_string(),
+            annotation_type: serde_json::from_value(kind.into()).unwrap(),
+            page_number: 2,
+            x: 100.0,
+            y: 50.0,
+            width: 200.0,
+            height: 20.0,
+            content: "Note <1> & \"2\"".to_string(),
+            color: "#ff0".to_string(),
+            opacity: 0.5,
+            stroke_width: None,
+            font_size: None,
+            background_color: None,
+            background_opacity: None,
+            created: "2025-01-01T10:00:00+02:00".to_string(),
+            modified: "2025-01-02T00:00:00Z".to_string(),
+            visible: true,
+            points: None,
+            author: Some("Ana".to_string()),
+            layer: None,
+            image: None,
+            ephemeral: false,
+            locked: false,
+        }
+    }
+
+    #[test]
+    fn test_highlight_element() {
+        let origin = PageOrigin::default();
+        let element = write_annotation(&annotation("highlight"), 1, origin).unwrap();
+        assert!(element.starts_with("    <highlight page=\"1\" name=\"a1\" opacity=\"0.5\""));
+        assert!(element.contains("title=\"Ana\""));
+        assert!(element.contains("date=\"D:20250102000000+00'00'\""));
+        assert!(element.contains("creationdate=\"D:20250101080000+00'00'\""));
+        assert!(element.contains("rect=\"100,722,300,742\""));
+        assert!(element.contains("color=\"#FFFF00\""));
+        assert!(element.contains("coords=\"100,742,300,742,100,722,300,722\""));
+        assert!(element.contains("<contents>Note &lt;1&gt; &amp; &quot;2&quot;</contents>"));
+    }
+
+    #[test]
+    fn test_ink_and_arrow_elements() {
+        let origin = PageOrigin {
+            left: 10.0,
+            top: 600.0,
+        };
+        let mut ink = annotation("free_draw");
+        ink.points = Some(vec![Point::new(0.0, 0.0), Point::new(5.5, 10.0)]);
+        let element = write_annotation(&ink, 0, origin).unwrap();
+        assert!(element.contains("<gesture>10,600;15.5,590</gesture>"));
+        assert!(element.contains("rect=\"9,589,16.5,601\""));
+
+        let element = write_annotation(&annotation("arrow"), 0, origin).unwrap();
+        assert!(element.contains("start=\"110,550\" end=\"310,530\""));
+        assert!(element.contains("tail=\"OpenArrow\""));
+        let element = write_annotation(&annotation("line"), 0, origin).unwrap();
+        assert!(element.contains("tail=\"None\""));
+
+        ink.points = None;
+        assert!(write_annotation(&ink, 0, origin).is_none());
+        assert!(write_annotation(&annotation("image"), 0, origin).is_none());
+    }
+
+    #[test]
+    fn test_write_xfdf_document() {
+        let mut annotations = HashMap::new();
+        annotations.insert(3, vec![annotation("circle")]);
+        annotations.insert(1, vec![annotation("text")]);
+        let xfdf = write_xfdf(&annotations, &HashMap::new(), Some("deck & notes.pdf"));
+
+        assert!(xfdf.starts_with("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<xfdf "));
+        assert!(xfdf.contains("<f href=\"deck &amp; notes.pdf\"/>"));
+        let text = xfdf.find("<freetext page=\"0\"").unwrap();
+        let circle = xfdf.find("<circle page=\"2\"").unwrap();
+        assert!(text < circle);
+        assert!(xfdf.contains("<defaultappearance>/Helv 14 Tf 1 1 0 rg</defaultappearance>"));
+        assert!(xfdf.trim_end().ends_with("</annots>\n</xfdf>"));
+    }
+
+    #[test]
+    fn test_parse_color_and_num() {
+        assert_eq!(parse_color("#1a2B3c"), Some((0x1A, 0x2B, 0x3C)));
+        assert_eq!(parse_color("red"), None);
+        assert_eq!(parse_color("#12345"), None);
+        assert_eq!(num(1.005e2), "100.5");
+        assert_eq!(num(-0.001), "0");
+        assert_eq!(num(12.0), "12");
+    }
+
+    #[test]
+    fn test_xfdf_round_trip() {
+        let origin = PageOrigin {
+            left: 0.0,
+            top: 600.0,
+        };
+        let origins = HashMap::from([(2, origin)]);
+        let mut ink = annotation("free_draw");
+        ink.id = "ink".to_string();
+        ink.stroke_width = Some(3.0);
+        ink.points = Some(vec![Point::new(10.0, 20.0), Point::new(30.0, 5.0)]);
+        let mut text = annotation("text");
+        text.id = "note".to_string();
+        text.font_size = Some(18.0);
+        text.height = 40.0;
+        text.background_color = Some("#336699".to_string());
+        let mut annotations = HashMap::new();
+        annotations.insert(
+            2,
+            vec![annotation("highlight"), annotation("arrow"), ink, text],
+        );
+
+        let xfdf = write_xfdf(&annotations, &origins, None);
+        let imported = read_xfdf(&xfdf, &origins).unwrap();
+        assert_eq!(imported.skipped, 0);
+        let by_id = |id: &str| imported.annotations.iter().find(|a| a.id == id).unwrap();
+
+        let highlight = &imported.annotations[0];
+        assert_eq!(highlight.annotation_type, AnnotationKind::Highlight);
+        assert_eq!(highlight.page_number, 2);
+        assert_eq!(
+            (highlight.x, highlight.y, highlight.width, highlight.height),
+            (100.0, 50.0, 200.0, 20.0)
+        );
+        assert_eq!(highlight.color, "#ffff00");
+        assert_eq!(highlight.opacity, 0.5);
+        assert_eq!(highlight.content, "Note <1> & \"2\"");
+        assert_eq!(highlight.created, "2025-01-01T08:00:00+00:00");
+        assert_eq!(highlight.author.as_deref(), Some("Ana"));
+
+        let arrow = &imported.annotations[1];
+        assert_eq!(arrow.annotation_type, AnnotationKind::Arrow);
+        assert_eq!(
+            (arrow.x, arrow.y, arrow.width, arrow.height),
+            (100.0, 50.0, 200.0, 20.0)
+        );
+
+        let ink = by_id("ink");
+        assert_eq!(ink.stroke_width, Some(3.0));
+        let points: Vec<(f64, f64)> = ink
+            .points
+            .as_ref()
+            .unwrap()
+            .iter()
+            .map(|p| (p.x, p.y))
+            .collect();
+        assert_eq!(points, [(10.0, 20.0), (30.0, 5.0)]);
+
+        let text = by_id("note");
+        assert_eq!(
+            (text.x, text.y, text.width, text.height),
+            (100.0, 50.0, 200.0, 40.0)
+        );
+        assert_eq!(text.font_size, Some(18.0));
+        assert_eq!(text.color, "#ffff00");
+        assert_eq!(text.background_color.as_deref(), Some("#336699"));
+    }
+
+    #[test]
+    fn test_read_xfdf_from_acrobat() {
+        let xfdf = r##"<?xml version="1.0" encoding="UTF-8"?>
+<xfdf xmlns="http://ns.adobe.com/xfdf/" xml:space="preserve">
+  <annots>
+    <highlight color="#FFFF00" page="0" flags="print,hidden" name="h1"
+      rect="10,700,90,730" coords="10,730,90,730,10,715,90,715,10,715,50,715,10,700,50,700">
+      <contents-richtext><body><p>Check </p><p>this</p></body></contents-richtext>
+    </highlight>
+    <stamp page="0" rect="0,0,10,10" name="s1"/>
+    <polygon page="0" rect="0,0,10,10" vertices="0,0;5,5;10,0"/>
+  </annots>
+</xfdf>"##;
+        let imported = read_xfdf(xfdf, &HashMap::new()).unwrap();
+
+        assert_eq!(imported.skipped, 2);
+        let ids: Vec<&str> = imported.annotations.iter().map(|a| a.id.as_str()).collect();
+        assert_eq!(ids, ["h1", "h1-2"]);
+        let second = &imported.annotations[1];
+        assert_eq!(
+            (second.x, second.y, second.width, second.height),
+            (10.0, 77.0, 40.0, 15.0)
+        );
+        assert_eq!(second.page_number, 1);
+        assert_eq!(second.content, "Check this");
+        assert!(!second.visible);
+        assert_eq!(second.opacity, 1.0);
+
+        assert!(read_xfdf("<fdf/>", &HashMap::new()).is_err());
+    }
+
+    #[test]
+    fn test_read_fdf() {
+        let fdf = b"%FDF-1.2\n%\xe2\xe3\xcf\xd3\n\
+            1 0 obj\n<< /FDF << /Annots [2 0 R 3 0 R] /F (deck.pdf) >> >>\nendobj\n\
+            2 0 obj\n<< /Type /Annot /Subtype /Square /Page 1 /Rect [72 600 172 700] \
+            /C [1 0 0] /CA 0.75 /BS << /W 4 >> /NM (box) /T (Bob) /F 2 \
+            /M (D:20250301120000-05'00') /Contents (\xfe\xff\x00H\x00i) >>\nendobj\n\
+            3 0 obj\n<< /Type /Annot /Subtype /FreeText /Page 0 /Rect [98 750 302 780] \
+            /DA (/Helv 12 Tf 0 0 1 rg) /Contents (Hello) >>\nendobj\n\
+            trailer\n<< /Root 1 0 R >>\n%%EOF\n";
+        let origins = HashMap::from([(
+            2,
+            PageOrigin {
+                left: 0.0,
+                top: 800.0,
+            },
+        )]);
+        let imported = read_fdf(fdf, &origins).unwrap();
+
+        assert_eq!(imported.skipped, 0);
+        let square = &imported.annotations[0];
+        assert_eq!(square.id, "box");
+        assert_eq!(square.annotation_type, AnnotationKind::Rect);
+        assert_eq!(square.page_number, 2);
+        assert_eq!(
+            (square.x, square.y, square.width, square.height),
+            (72.0, 100.0, 100.0, 100.0)
+        );
+        assert_eq!(square.color, "#ff0000");
+        assert_eq!((square.opacity, square.stroke_width), (0.75, Some(4.0)));
+        assert_eq!(square.content, "Hi");
+        assert_eq!(square.author.as_deref(), Some("Bob"));
+        assert_eq!(square.modified, "2025-03-01T17:00:00+00:00");
+        assert!(!square.visible);
+
+        let text = &imported.annotations[1];
+        assert_eq!(text.annotation_type, AnnotationKind::Text);
+        assert_eq!((text.x, text.y, text.width), (100.0, 24.0, 200.0));
+        assert_eq!(
+            (text.font_size, text.color.as_str()),
+            (Some(12.0), "#0000ff")
+        );
+
+        assert!(read_fdf(b"%PDF-1.7\n", &origins).is_err());
+    }
+}
@@ -0,0 +1,288 @@
+/*
+ * This file is part of StreamSlate.
+ * Copyright (C) 2025 StreamSlate Contributors
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+
+//! Minimal XML reader
+//!
+//! Just enough XML to read XFDF: elements, attributes, text, CDATA and the
+//! predefined and numeric entities. Declarations, comments, processing
+//! instructions and DOCTYPEs are skipped; namespace prefixes are dropped
+//! from names.
+
+use crate::error::{Result, StreamSlateError};
+
+/// Maximum element nesting accepted
+const MAX_DEPTH: usize = 64;
+
+/// An element with its attributes and children
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Element {
+    /// Local name, without any namespace prefix
+    pub name: String,
+    pub attributes: Vec<(String, String)>,
+    pub children: Vec<Node>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Node {
+    Element(Element),
+    Text(String),
+}
+
+impl Element {
+    fn new(name: &str) -> Self {
+        Self {
+            name: local_name(name).to_string(),
+            attributes: Vec::new(),
+            children: Vec::new(),
+        }
+    }
+
+    /// Value of the attribute with local name `name`
+    pub fn attribute(&self, name: &str) -> Option<&str> {
+        self.attributes
+            .iter()
+            .find(|(key, _)| key == name)
+            .map(|(_, value)| value.as_str())
+    }
+
+    /// Child elements, in document order
+    pub fn elements(&self) -> impl Iterator<Item = &Element> {
+        self.children.iter().filter_map(|node| match node {
+            Node::Element(element) => Some(element),
+            Node::Text(_) => None,
+        })
+    }
+
+    /// First child element named `name`
+    pub fn child(&self, name: &str) -> Option<&Element> {
+        self.elements().find(|element| element.name == name)
+    }
+
+    /// All text inside the element, including nested elements
+    pub fn text(&self) -> String {
+        let mut text = String::new();
+        for node in &self.children {
+            match node {
+                Node::Text(t) => text.push_str(t),
+                Node::Element(element) => text.push_str(&element.text()),
+            }
+        }
+        text
+    }
+}
+
+/// Parse a document and return its root element
+pub fn parse(input: &str) -> Result<Element> {
+    let mut stack: Vec<Element> = Vec::new();
+    let mut root = None;
+    let mut rest = input.strip_prefix('\u{FEFF}').unwrap_or(input);
+
+    while !rest.is_empty() {
+        if let Some(after) = rest.strip_prefix("<!--") {
+            rest = skip_past(after, "-->")?;
+        } else if let Some(after) = rest.strip_prefix("<![CDATA[") {
+            let end = after
+                .find("]]>")
+                .ok_or_else(|| invalid("Unterminated CDATA"))?;
+            if let Some(parent) = stack.last_mut() {
+                parent.children.push(Node::Text(after[..end].to_string()));
+            }
+            rest = &after[end + 3..];
+        } else if let Some(after) = rest.strip_prefix("<?") {
+            rest = skip_past(after, "?>")?;
+        } else if let Some(after) = rest.strip_prefix("<!") {
+            rest = skip_past(after, ">")?;
+        } else if let Some(after) = rest.strip_prefix("</") {
+            let end = after
+                .find('>')
+                .ok_or_else(|| invalid("Unterminated end tag"))?;
+            let name = local_name(after[..end].trim());
+            let element = stack.pop().ok_or_else(|| invalid("Unexpected end tag"))?;
+            if element.name != name {
+                return Err(invalid("Mismatched end tag"));
+            }
+            close(&mut stack, &mut root, element)?;
+            rest = &after[end + 1..];
+        } else if let Some(after) = rest.strip_prefix('<') {
+            let (element, self_closing, remaining) = start_tag(after)?;
+            if self_closing {
+                close(&mut stack, &mut root, element)?;
+            } else {
+                if stack.len() >= MAX_DEPTH {
+                    return Err(invalid("Elements nested too deeply"));
+                }
+                stack.push(element);
+            }
+            rest = remaining;
+        } else {
+            let end = rest.find('<').unwrap_or(rest.len());
+            let text = unescape(&rest[..end])?;
+            match stack.last_mut() {
+                Some(parent) => parent.children.push(Node::Text(text)),
+                None if text.trim().is_empty() => {}
+                None => return Err(invalid("Text outside the root element")),
+            }
+            rest = &rest[end..];
+        }
+    }
+
+    if !stack.is_empty() {
+        return Err(invalid("Unclosed element"));
+    }
+    root.ok_or_else(|| invalid("No root element"))
+}
+
+/// Attach a finished element to its parent, or make it the root
+fn close(stack: &mut [Element], root: &mut Option<Element>, element: Element) -> Result<()> {
+    match stack.last_mut() {
+        Some(parent) => parent.children.push(Node::Element(element)),
+        None if root.is_none() => *root = Some(element),
+        None => return Err(invalid("More than one root element")),
+    }
+    Ok(())
+}
+
+/// Parse a start tag after its `<`, returning the rest of the input
+fn start_tag(input: &str) -> Result<(Element, bool, &str)> {
+    let name_end = input
+        .find(|c: char| c.is_whitespace() || c == '>' || c == '/')
+        .ok_or_else(|| invalid("Unterminated start tag"))?;
+    if name_end == 0 {
+        return Err(invalid("Missing element name"));
+    }
+    let mut element = Element::new(&input[..name_end]);
+    let mut rest = &input[name_end..];
+
+    loop {
+        rest = rest.trim_start();
+        if let Some(after) = rest.strip_prefix("/>") {
+            return Ok((element, true, after));
+        }
+        if let Some(after) = rest.strip_prefix('>') {
+            return Ok((element, false, after));
+        }
+
+        let eq = rest
+            .find('=')
+            .ok_or_else(|| invalid("Malformed attribute"))?;
+        let name = rest[..eq].trim();
+        if name.is_empty() || name.contains(|c: char| c.is_whitespace() || c == '>') {
+            return Err(invalid("Malformed attribute"));
+        }
+        let value_start = rest[eq + 1..].trim_start();
+        let quote = value_start
+            .chars()
+            .next()
+            .filter(|&c| c == '"' || c == '\'')
+            .ok_or_else(|| invalid("Unquoted attribute value"))?;
+        let value_end = value_start[1..]
+            .find(quote)
+            .ok_or_else(|| invalid("Unterminated attribute value"))?;
+        element.attributes.push((
+            local_name(name).to_string(),
+            unescape(&value_start[1..1 + value_end])?,
+        ));
+        rest = &value_start[value_end + 2..];
+    }
+}
+
+/// Replace entity and character references
+fn unescape(text: &str) -> Result<String> {
+    let mut result = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(amp) = rest.find('&') {
+        result.push_str(&rest[..amp]);
+        let end = rest[amp..]
+            .find(';')
+            .ok_or_else(|| invalid("Unterminated entity"))?;
+        let entity = &rest[amp + 1..amp + end];
+        let c = match entity {
+            "amp" => '&',
+            "lt" => '<',
+            "gt" => '>',
+            "quot" => '"',
+            "apos" => '\'',
+            _ => {
+                let code = if let Some(hex) = entity.strip_prefix("#x") {
+                    u32::from_str_radix(hex, 16).ok()
+                } else if let Some(decimal) = entity.strip_prefix('#') {
+                    decimal.parse().ok()
+                } else {
+                    None
+                };
+                code.and_then(char::from_u32)
+                    .ok_or_else(|| invalid("Unknown entity"))?
+            }
+        };
+        result.push(c);
+        rest = &rest[amp + end + 1..];
+    }
+    result.push_str(rest);
+    Ok(result)
+}
+
+fn skip_past<'a>(input: &'a str, terminator: &str) -> Result<&'a str> {
+    input
+        .find(terminator)
+        .map(|end| &input[end + terminator.len()..])
+        .ok_or_else(|| invalid("Unterminated markup"))
+}
+
+fn local_name(name: &str) -> &str {
+    name.rsplit(':').next().unwrap_or(name)
+}
+
+fn invalid(reason: &str) -> StreamSlateError {
+    StreamSlateError::Other(format!("Invalid XML: {reason}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_document() {
+        let root = parse(
+            "<?xml version=\"1.0\"?>\n<!-- exported -->\n\
+             <x:xfdf xmlns:x=\"ns\"><annots>\
+             <square page='2' title=\"A &amp; B\"/>\
+             <text><contents>Line 1&#10;&lt;2&gt;<![CDATA[ & <raw>]]></contents></text>\
+             </annots></x:xfdf>",
+        )
+        .unwrap();
+
+        assert_eq!(root.name, "xfdf");
+        let annots = root.child("annots").unwrap();
+        let names: Vec<&str> = annots.elements().map(|e| e.name.as_str()).collect();
+        assert_eq!(names, ["square", "text"]);
+        let square = annots.child("square").unwrap();
+        assert_eq!(square.attribute("page"), Some("2"));
+        assert_eq!(square.attribute("title"), Some("A & B"));
+        assert_eq!(annots.child("text").unwrap().text(), "Line 1\n<2> & <raw>");
+    }
+
+    #[test]
+    fn test_parse_rejects_malformed() {
+        assert!(parse("<a><b></a>").is_err());
+        assert!(parse("<a>").is_err());
+        assert!(parse("<a/><b/>").is_err());
+        assert!(parse("<a x=1/>").is_err());
+        assert!(parse("<a>&bogus;</a>").is_err());
+        assert!(parse("").is_err());
+    }
+}
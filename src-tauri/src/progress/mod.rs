@@ -0,0 +1,144 @@
+/*
+ * This file is part of StreamSlate.
+ * Copyright (C) 2025 StreamSlate Contributors
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+
+//! Per-document reading progress
+//!
+//! Remembers the last page, zoom and scroll offset of each document so it
+//! reopens where it was left. Documents are keyed by a hash of their content,
+//! so progress follows a file that is moved or renamed.
+
+use crate::settings::Settings;
+use ring::digest::{digest, SHA256};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// File name of the progress store inside the app data directory
+pub const STORE_FILE: &str = "reading_progress.json";
+
+/// Documents remembered before the least recently read are forgotten
+pub const MAX_DOCUMENTS: usize = 500;
+
+/// Where a document was left
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ReadingProgress {
+    pub page: u32,
+    pub zoom: f64,
+    #[serde(default)]
+    pub scroll_x: f64,
+    #[serde(default)]
+    pub scroll_y: f64,
+    /// When the progress was saved (RFC 3339)
+    pub updated_at: String,
+}
+
+/// Stored progress of every document, keyed by content hash
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProgressStore {
+    /// Restore progress when documents are opened
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+    #[serde(default)]
+    pub documents: HashMap<String, ReadingProgress>,
+}
+
+fn default_enabled() -> bool {
+    true
+}
+
+impl Default for ProgressStore {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            documents: HashMap::new(),
+        }
+    }
+}
+
+impl ProgressStore {
+    /// Record progress for `hash`, forgetting the oldest entries past
+    /// `MAX_DOCUMENTS`
+    pub fn record(&mut self, hash: &str, progress: ReadingProgress) {
+        self.documents.insert(hash.to_string(), progress);
+        while self.documents.len() > MAX_DOCUMENTS {
+            // RFC 3339 timestamps in UTC sort chronologically
+            let oldest = self
+                .documents
+                .iter()
+                .min_by(|a, b| a.1.updated_at.cmp(&b.1.updated_at))
+                .map(|(hash, _)| hash.clone());
+            match oldest {
+                Some(oldest) => self.documents.remove(&oldest),
+                None => break,
+            };
+        }
+    }
+}
+
+impl Settings for ProgressStore {}
+
+/// Hex SHA-256 of a document's bytes
+pub fn content_hash(data: &[u8]) -> String {
+    digest(&SHA256, data)
+        .as_ref()
+        .iter()
+        .map(|b| format!("{b:02x}"))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn progress(page: u32, updated_at: &str) -> ReadingProgress {
+        ReadingProgress {
+            page,
+            zoom: 1.0,
+            scroll_x: 0.0,
+            scroll_y: 0.0,
+            updated_at: updated_at.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_content_hash() {
+        assert_eq!(
+            content_hash(b"abc"),
+            "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+        );
+    }
+
+    #[test]
+    fn test_store_forgets_oldest() {
+        let mut store = ProgressStore::default();
+        for i in 0..MAX_DOCUMENTS {
+            store.record(
+                &format!("doc{i}"),
+                progress(1, &format!("2025-01-01T00:{:02}:{:02}Z", i / 60, i % 60)),
+            );
+        }
+        store.record("new", progress(7, "2025-02-01T00:00:00Z"));
+
+        assert_eq!(store.documents.len(), MAX_DOCUMENTS);
+        assert!(!store.documents.contains_key("doc0"));
+        assert_eq!(store.documents["new"].page, 7);
+
+        // Older stores without the flag default to restoring
+        let parsed: ProgressStore = serde_json::from_str(r#"{"documents":{}}"#).unwrap();
+        assert!(parsed.enabled);
+    }
+}
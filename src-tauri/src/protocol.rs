@@ -0,0 +1,931 @@
+/*
+ * This file is part of StreamSlate.
+ * Copyright (C) 2025 StreamSlate Contributors
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+
+//! WebSocket message protocol types
+//!
+//! Defines the JSON message format for client-server communication. Shared
+//! by the server (`websocket`), the outgoing client (`client`) and the SDK.
+//!
+//! Any command may carry a `request_id`, which the reply to it echoes along
+//! with `ok`, so clients sending commands in quick succession can tell which
+//! reply (or error) belongs to which command.
+
+use crate::capture::agenda::{Agenda, SectionProgress};
+use crate::capture::viewport::OutputViewport;
+use crate::commands::layers::Layer;
+use crate::commands::search::AnnotationMatch;
+use crate::commands::shapes::FieldError;
+use crate::commands::show::ShowSummary;
+use crate::error::{ErrorCode, StreamSlateError};
+use crate::media::{MediaPlayback, MediaStatus};
+use crate::pdf::text::PageWord;
+use crate::state::presets::StylePreset;
+use crate::websocket::access::Role;
+use crate::websocket::page_images::{ImageFormat, ImageSize};
+use crate::websocket::preferences::ClientPreferences;
+use crate::websocket::rate::CommandClass;
+use crate::websocket::reactions::ReactionCount;
+use crate::websocket::topics::Topic;
+use schemars::gen::SchemaGenerator;
+use schemars::schema::{InstanceType, Metadata, Schema, SchemaObject, SubschemaValidation};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// Client-chosen ID of a command, echoed in the reply to it
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(untagged)]
+pub enum RequestId {
+    Number(u64),
+    Text(String),
+}
+
+/// Reply to a command that carried a `request_id`
+#[derive(Debug, Serialize)]
+struct Reply<'a> {
+    #[serde(flatten)]
+    event: &'a WebSocketEvent,
+    request_id: &'a RequestId,
+    /// Whether the command was applied
+    ok: bool,
+}
+
+/// A broadcast event with its sequence number, as sent to clients and
+/// replayed by `RESUME`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SequencedEvent {
+    /// Increases by one with every broadcast
+    pub seq: u64,
+    #[serde(flatten)]
+    pub event: WebSocketEvent,
+}
+
+/// Described as the event type plus `seq`, rather than the derived copy of
+/// every event variant with `seq` added
+impl JsonSchema for SequencedEvent {
+    fn schema_name() -> String {
+        "SequencedEvent".to_string()
+    }
+
+    fn json_schema(gen: &mut SchemaGenerator) -> Schema {
+        let mut seq = SchemaObject {
+            instance_type: Some(InstanceType::Object.into()),
+            ..Default::default()
+        };
+        let object = seq.object();
+        object
+            .properties
+            .insert("seq".to_string(), gen.subschema_for::<u64>());
+        object.required.insert("seq".to_string());
+
+        SchemaObject {
+            metadata: Some(Box::new(Metadata {
+                description: Some(
+                    "A broadcast event with its sequence number, as sent to clients and replayed by `RESUME`"
+                        .to_string(),
+                ),
+                ..Default::default()
+            })),
+            subschemas: Some(Box::new(SubschemaValidation {
+                all_of: Some(vec![gen.subschema_for::<WebSocketEvent>(), seq.into()]),
+                ..Default::default()
+            })),
+            ..Default::default()
+        }
+        .into()
+    }
+}
+
+/// Parse a command message, splitting off its `request_id`
+///
+/// The ID is returned even when the command itself is malformed, so the
+/// error can be attributed.
+pub fn parse_command(text: &str) -> (Option<RequestId>, serde_json::Result<WebSocketCommand>) {
+    let mut value: serde_json::Value = match serde_json::from_str(text) {
+        Ok(value) => value,
+        Err(e) => return (None, Err(e)),
+    };
+    let request_id = value
+        .as_object_mut()
+        .and_then(|message| message.remove("request_id"));
+    match request_id.map(serde_json::from_value::<RequestId>) {
+        Some(Err(_)) => (
+            None,
+            Err(serde::de::Error::custom(
+                "request_id must be a string or a non-negative integer",
+            )),
+        ),
+        Some(Ok(request_id)) => (Some(request_id), serde_json::from_value(value)),
+        None => (None, serde_json::from_value(value)),
+    }
+}
+
+/// Serialize the reply to a command, echoing its `request_id` if it had one
+pub fn reply_json(
+    request_id: Option<&RequestId>,
+    event: &WebSocketEvent,
+) -> serde_json::Result<String> {
+    match request_id {
+        Some(request_id) => serde_json::to_string(&Reply {
+            event,
+            request_id,
+            ok: !event.is_failure(),
+        }),
+        None => serde_json::to_string(event),
+    }
+}
+
+/// Commands that clients can send to StreamSlate
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(tag = "type", rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum WebSocketCommand {
+    /// Navigate to the next page
+    NextPage,
+
+    /// Navigate to the previous page
+    PreviousPage,
+
+    /// Navigate to a specific page
+    GoToPage { page: u32 },
+
+    /// Get current state
+    GetState,
+
+    /// Set zoom level (1.0 = 100%)
+    SetZoom { zoom: f64 },
+
+    /// Toggle presenter mode
+    TogglePresenter,
+
+    /// Ping to keep connection alive
+    Ping,
+
+    /// Introduce this controller so the operator can tell connections
+    /// apart, e.g. `{"name": "Stream Deck XL", "kind": "stream-deck"}`,
+    /// optionally with an access token for another role
+    Hello {
+        name: String,
+        kind: String,
+        #[serde(default)]
+        version: Option<String>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        token: Option<String>,
+    },
+
+    /// Add an annotation
+    AddAnnotation {
+        page: u32,
+        annotation: serde_json::Value,
+        /// The client's Lamport clock for the edit; stamped on arrival if omitted
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        clock: Option<u64>,
+    },
+
+    /// Merge fields into the annotation with the same `id`, moving it to
+    /// `page` if it is elsewhere
+    UpdateAnnotation {
+        page: u32,
+        annotation: serde_json::Value,
+        /// The client's Lamport clock for the edit; stamped on arrival if omitted
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        clock: Option<u64>,
+    },
+
+    /// Remove the annotation `id` from a page
+    DeleteAnnotation {
+        page: u32,
+        id: String,
+        /// The client's Lamport clock for the edit; stamped on arrival if omitted
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        clock: Option<u64>,
+    },
+
+    /// Clear all annotations
+    ClearAnnotations,
+
+    /// Revert the last annotation change, whichever client made it
+    UndoAnnotation,
+
+    /// Reapply the last undone annotation change
+    RedoAnnotation,
+
+    /// Erase the parts of free-draw strokes within `radius` of (`x`, `y`),
+    /// splitting strokes the eraser crosses
+    EraseAt {
+        page: u32,
+        x: f64,
+        y: f64,
+        radius: f64,
+    },
+
+    /// Request word bounding boxes for a page, e.g. to snap highlights to text
+    GetPageWords { page: u32 },
+
+    /// Jump to the first page of the next section marker
+    NextSection,
+
+    /// Jump to the start of the current section, or the previous one when
+    /// already on a section's first page
+    PreviousSection,
+
+    /// Identify this connection with a stable client ID and, optionally, the
+    /// display name its annotations are attributed to
+    Identify {
+        client_id: String,
+        #[serde(default)]
+        name: Option<String>,
+    },
+
+    /// Store preferences for the identified client
+    SetPreferences { preferences: ClientPreferences },
+
+    /// Invert page luminance while preserving hue (smart dark mode) for the
+    /// open document
+    SetSmartInvert { enabled: bool },
+
+    /// Send black frames to every output instead of the slides
+    SetBlackout { enabled: bool },
+
+    /// Pan/zoom the output like a PTZ camera, easing over `duration_ms`
+    /// (zoom 1.0 shows the whole frame)
+    SetOutputViewport {
+        viewport: OutputViewport,
+        #[serde(default)]
+        duration_ms: Option<u32>,
+    },
+
+    /// Add a live audience reaction to a page (restricted to a fixed set of
+    /// emoji and rate limited per connection)
+    AddReaction { page: u32, emoji: String },
+
+    /// Request the agenda built from the document outline
+    GetAgenda,
+
+    /// Request the open document's video clips and their playback state
+    GetMedia,
+
+    /// Start or resume playing a video clip
+    PlayMedia { id: String },
+
+    /// Pause a video clip
+    PauseMedia { id: String },
+
+    /// Move a video clip to `position` seconds from its start
+    SeekMedia { id: String, position: f64 },
+
+    /// Flash `data` as a QR code on every output, for `duration_seconds` if
+    /// given
+    ShowQr {
+        data: String,
+        #[serde(default)]
+        duration_seconds: Option<u32>,
+    },
+
+    /// Remove the QR code from the outputs
+    HideQr,
+
+    /// Request the open document's annotation layers
+    GetLayers,
+
+    /// Show or hide the annotations on a layer, in every view and on the
+    /// outputs
+    SetLayerVisible { id: String, visible: bool },
+
+    /// Start or stop receiving the low-bandwidth remote view on this
+    /// connection, as binary messages each holding one JPEG frame
+    SetRemoteView { enabled: bool },
+
+    /// Stop every output, save annotations and export the session timeline
+    EndShow {
+        #[serde(default)]
+        close_presenter: bool,
+        #[serde(default)]
+        webhook_url: Option<String>,
+    },
+
+    /// Find annotations whose text contains every word of `query`
+    SearchAnnotations { query: String },
+
+    /// Show this client's cursor on the presenter output for a moment;
+    /// `x` and `y` are fractions of the page as shown (0.5 is the middle).
+    /// Never saved, and rate limited per connection
+    PointerMoved { page: u32, x: f64, y: f64 },
+
+    /// Request the annotation style presets
+    GetStylePresets,
+
+    /// Apply a style preset to every annotation added from now on, or stop
+    /// applying one when `id` is omitted
+    SetActiveStylePreset {
+        #[serde(default)]
+        id: Option<String>,
+    },
+
+    /// Run `commands` in order as one: if any fails, the ones before it
+    /// are undone and none after it run
+    Batch { commands: Vec<WebSocketCommand> },
+
+    /// Receive only the broadcast events of these topics from now on
+    Subscribe { topics: Vec<Topic> },
+
+    /// Catch up on the broadcasts after `last_seq` missed while
+    /// disconnected
+    Resume { last_seq: u64 },
+
+    /// Request the rendered image of a page
+    GetPageImage {
+        page: u32,
+        #[serde(default)]
+        size: ImageSize,
+    },
+
+    /// Start or stop receiving the current page's image whenever the page
+    /// changes
+    SetPageImages {
+        enabled: bool,
+        #[serde(default)]
+        size: ImageSize,
+    },
+
+    /// Request the speaker notes of a page
+    GetNotes { page: u32 },
+}
+
+impl WebSocketCommand {
+    /// The command's `type`, e.g. `GO_TO_PAGE`
+    pub fn name(&self) -> String {
+        serde_json::to_value(self)
+            .ok()
+            .and_then(|value| value.get("type")?.as_str().map(str::to_string))
+            .unwrap_or_default()
+    }
+}
+
+/// Events that StreamSlate sends to clients
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(tag = "type", rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum WebSocketEvent {
+    /// Current state update
+    State {
+        page: u32,
+        total_pages: u32,
+        zoom: f64,
+        pdf_loaded: bool,
+        pdf_path: Option<String>,
+        pdf_title: Option<String>,
+        presenter_active: bool,
+        /// Smart dark mode is on for the open document
+        #[serde(default)]
+        smart_invert: bool,
+        /// Position within the outline section containing the page
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        section: Option<SectionProgress>,
+        /// Annotation edit clock, for clients stamping their own edits
+        #[serde(default)]
+        annotation_clock: u64,
+    },
+
+    /// Page changed notification
+    PageChanged {
+        page: u32,
+        total_pages: u32,
+        /// Position within the outline section containing the page
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        section: Option<SectionProgress>,
+    },
+
+    /// PDF opened notification
+    PdfOpened {
+        path: String,
+        title: Option<String>,
+        page_count: u32,
+    },
+
+    /// PDF closed notification
+    PdfClosed,
+
+    /// Zoom changed notification
+    ZoomChanged { zoom: f64 },
+
+    /// Presenter mode changed
+    PresenterChanged { active: bool },
+
+    /// Smart dark mode toggled for the open document
+    SmartInvertChanged { enabled: bool },
+
+    /// Output blackout switched on or off
+    BlackoutChanged { enabled: bool },
+
+    /// Output pan/zoom target changed
+    OutputViewportChanged { viewport: OutputViewport },
+
+    /// Agenda with the current section; also pushed when the section changes
+    Agenda { agenda: Agenda },
+
+    /// Live reaction counts of a page changed
+    ReactionsUpdated {
+        page: u32,
+        reactions: Vec<ReactionCount>,
+    },
+
+    /// Pages of both documents after paging a linked pair
+    LinkedPagesChanged {
+        page: u32,
+        total_pages: u32,
+        linked_path: String,
+        linked_page: u32,
+        linked_total_pages: u32,
+    },
+
+    /// Video clips of the open document with their playback state
+    Media { media: Vec<MediaStatus> },
+
+    /// A video clip was played, paused or moved
+    MediaPlaybackChanged { playback: MediaPlayback },
+
+    /// Pages the views should render ahead of time, nearest first, sent
+    /// whenever the current page changes
+    PrerenderPages { page: u32, pages: Vec<u32> },
+
+    /// An attendance checkpoint opened; its code word is only shown on the
+    /// output
+    CheckpointStarted {
+        id: String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        prompt: Option<String>,
+    },
+
+    /// An attendance checkpoint stopped taking responses
+    CheckpointClosed { id: String, responses: u32 },
+
+    /// A page was summarized by the configured summarization endpoint
+    PageSummary { page: u32, summary: String },
+
+    /// Speaker notes of a page, in reply to `GET_NOTES` and whenever they
+    /// are edited; `text` is empty when the page has none
+    NotesUpdated { page: u32, text: String },
+
+    /// QR code shown on the outputs changed; `data` is empty once removed
+    QrCodeChanged {
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        data: Option<String>,
+    },
+
+    /// The open document's annotation layers, in reply to `GET_LAYERS`
+    Layers { layers: Vec<Layer> },
+
+    /// A layer was created, renamed, shown, hidden or deleted
+    LayersChanged { layers: Vec<Layer> },
+
+    /// Whether this connection receives remote view frames, and whether
+    /// the host is publishing them
+    RemoteView { enabled: bool, active: bool },
+
+    /// The show was ended and torn down
+    ShowEnded { summary: ShowSummary },
+
+    /// An `ADD_ANNOTATION` or `UPDATE_ANNOTATION` was malformed and not applied
+    AnnotationRejected {
+        page: u32,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        id: Option<String>,
+        errors: Vec<FieldError>,
+    },
+
+    /// A remote co-host's cursor moved
+    PointerMoved {
+        page: u32,
+        x: f64,
+        y: f64,
+        /// Display name or client ID of the client pointing, if identified
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        client: Option<String>,
+    },
+
+    /// The annotation style presets and which one is active
+    StylePresets {
+        presets: Vec<StylePreset>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        active: Option<String>,
+    },
+
+    /// A style preset was created, changed, deleted or activated
+    StylePresetsChanged {
+        presets: Vec<StylePreset>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        active: Option<String>,
+    },
+
+    /// Annotations matching a `SEARCH_ANNOTATIONS` query, in page order
+    AnnotationSearchResults {
+        query: String,
+        matches: Vec<AnnotationMatch>,
+    },
+
+    /// Replies to the commands of a `BATCH` that all ran, in order
+    BatchResults { results: Vec<WebSocketEvent> },
+
+    /// A command was refused because this connection sent too many of its
+    /// class; it can be sent again after `retry_after_ms`
+    RateLimited {
+        command: String,
+        class: CommandClass,
+        retry_after_ms: u64,
+    },
+
+    /// Error response; `page` and `total_pages` are set for
+    /// `PAGE_OUT_OF_RANGE`
+    Error {
+        message: String,
+        #[serde(default)]
+        code: ErrorCode,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        page: Option<u32>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        total_pages: Option<u32>,
+    },
+
+    /// Pong response to ping
+    Pong,
+
+    /// Connection established confirmation
+    Connected { version: String },
+
+    /// Reply to `HELLO`, with the server version and the client's role
+    Welcome { version: String, role: Role },
+
+    /// A client connected or disconnected
+    ConnectionStatus { active_connections: u32 },
+
+    /// Annotations updated notification
+    AnnotationsUpdated {
+        /// Map of page number to list of annotations
+        annotations: std::collections::HashMap<u32, Vec<serde_json::Value>>,
+    },
+
+    /// All annotations cleared
+    AnnotationsCleared,
+
+    /// An annotation was added to a page
+    AnnotationAdded {
+        page: u32,
+        annotation: serde_json::Value,
+        /// Annotation edit clock after the change
+        clock: u64,
+    },
+
+    /// An annotation changed, with its merged value
+    AnnotationUpdated {
+        page: u32,
+        annotation: serde_json::Value,
+        /// Annotation edit clock after the change
+        clock: u64,
+    },
+
+    /// An annotation was removed
+    AnnotationDeleted {
+        page: u32,
+        id: String,
+        /// Annotation edit clock after the change
+        clock: u64,
+    },
+
+    /// An annotation replay started or ended; the annotation events between
+    /// redraw earlier markup rather than report live changes
+    AnnotationReplay { playing: bool },
+
+    /// Words on a page with rectangles in annotation coordinates
+    PageWords { page: u32, words: Vec<PageWord> },
+
+    /// Preferences stored for the identified client
+    ClientPreferences {
+        client_id: String,
+        preferences: ClientPreferences,
+    },
+
+    /// Topics this connection now receives broadcast events of
+    Subscribed { topics: Vec<Topic> },
+
+    /// The broadcasts missed since `RESUME`'s `last_seq`, filtered by the
+    /// connection's topics; `last_seq` is the latest broadcast's number
+    Resumed {
+        events: Vec<SequencedEvent>,
+        last_seq: u64,
+    },
+
+    /// Some broadcasts since `RESUME`'s `last_seq` are no longer kept, or
+    /// it's from an earlier run; request the full state instead
+    ResumeFailed { last_seq: u64 },
+
+    /// A rendered page image; the image file of `bytes` bytes follows as the
+    /// next binary message
+    PageImage {
+        page: u32,
+        size: ImageSize,
+        format: ImageFormat,
+        width: u32,
+        height: u32,
+        bytes: u32,
+    },
+
+    /// A page image is being rendered; `PAGE_IMAGE` follows once it's ready
+    PageImagePending { page: u32, size: ImageSize },
+
+    /// Whether this connection receives the current page's image
+    PageImages { enabled: bool, size: ImageSize },
+}
+
+impl WebSocketEvent {
+    /// Create a connected event
+    pub fn connected() -> Self {
+        Self::Connected {
+            version: env!("CARGO_PKG_VERSION").to_string(),
+        }
+    }
+
+    /// Create a welcome event
+    pub fn welcome(role: Role) -> Self {
+        Self::Welcome {
+            version: env!("CARGO_PKG_VERSION").to_string(),
+            role,
+        }
+    }
+
+    /// Create an error event
+    pub fn error(message: impl Into<String>) -> Self {
+        Self::error_code(ErrorCode::Other, message)
+    }
+
+    /// Create an error event with a specific code
+    pub fn error_code(code: ErrorCode, message: impl Into<String>) -> Self {
+        Self::Error {
+            message: message.into(),
+            code,
+            page: None,
+            total_pages: None,
+        }
+    }
+
+    /// Create the error event for a failed operation
+    pub fn from_error(error: &StreamSlateError) -> Self {
+        let (page, total_pages) = match error {
+            StreamSlateError::PageOutOfRange { page, total_pages } => {
+                (Some(*page), Some(*total_pages))
+            }
+            _ => (None, None),
+        };
+        Self::Error {
+            message: error.to_string(),
+            code: error.code(),
+            page,
+            total_pages,
+        }
+    }
+
+    /// Whether this reply means the command wasn't applied
+    pub fn is_failure(&self) -> bool {
+        matches!(
+            self,
+            Self::Error { .. }
+                | Self::AnnotationRejected { .. }
+                | Self::RateLimited { .. }
+                | Self::ResumeFailed { .. }
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_command_serialization() {
+        let cmd = WebSocketCommand::GoToPage { page: 5 };
+        let json = serde_json::to_string(&cmd).unwrap();
+        assert!(json.contains("GO_TO_PAGE"));
+        assert!(json.contains("5"));
+    }
+
+    #[test]
+    fn test_event_serialization() {
+        let event = WebSocketEvent::PageChanged {
+            page: 3,
+            total_pages: 10,
+            section: None,
+        };
+        let json = serde_json::to_string(&event).unwrap();
+        assert!(json.contains("PAGE_CHANGED"));
+        assert!(json.contains("total_pages"));
+        assert!(!json.contains("section"));
+    }
+
+    #[test]
+    fn test_command_deserialization() {
+        let json = r#"{"type": "NEXT_PAGE"}"#;
+        let cmd: WebSocketCommand = serde_json::from_str(json).unwrap();
+        assert!(matches!(cmd, WebSocketCommand::NextPage));
+    }
+
+    #[test]
+    fn test_annotation_operations() {
+        let json = r#"{"type": "DELETE_ANNOTATION", "page": 2, "id": "a1"}"#;
+        let cmd: WebSocketCommand = serde_json::from_str(json).unwrap();
+        assert!(
+            matches!(cmd, WebSocketCommand::DeleteAnnotation { page: 2, id, clock: None } if id == "a1")
+        );
+
+        let json =
+            r#"{"type": "UPDATE_ANNOTATION", "page": 2, "annotation": {"id": "a1"}, "clock": 7}"#;
+        let cmd: WebSocketCommand = serde_json::from_str(json).unwrap();
+        assert!(matches!(
+            cmd,
+            WebSocketCommand::UpdateAnnotation { clock: Some(7), .. }
+        ));
+
+        let event = WebSocketEvent::AnnotationUpdated {
+            page: 2,
+            annotation: serde_json::json!({ "id": "a1" }),
+            clock: 8,
+        };
+        let json = serde_json::to_string(&event).unwrap();
+        assert_eq!(
+            json,
+            r#"{"type":"ANNOTATION_UPDATED","page":2,"annotation":{"id":"a1"},"clock":8}"#
+        );
+    }
+
+    #[test]
+    fn test_identify_deserialization() {
+        let json = r#"{"type": "IDENTIFY", "client_id": "phone-remote"}"#;
+        let cmd: WebSocketCommand = serde_json::from_str(json).unwrap();
+        assert!(
+            matches!(cmd, WebSocketCommand::Identify { client_id, name: None } if client_id == "phone-remote")
+        );
+
+        let json = r#"{"type": "IDENTIFY", "client_id": "tablet", "name": "Ana"}"#;
+        let cmd: WebSocketCommand = serde_json::from_str(json).unwrap();
+        assert!(
+            matches!(cmd, WebSocketCommand::Identify { name: Some(name), .. } if name == "Ana")
+        );
+    }
+
+    #[test]
+    fn test_hello_deserialization() {
+        let json = r#"{"type": "HELLO", "name": "Stream Deck XL", "kind": "stream-deck", "version": "6.4"}"#;
+        let cmd: WebSocketCommand = serde_json::from_str(json).unwrap();
+        assert!(matches!(
+            cmd,
+            WebSocketCommand::Hello { name, kind, version: Some(version), token: None }
+                if name == "Stream Deck XL" && kind == "stream-deck" && version == "6.4"
+        ));
+
+        let json = r#"{"type": "HELLO", "name": "Phone", "kind": "phone"}"#;
+        let cmd: WebSocketCommand = serde_json::from_str(json).unwrap();
+        assert!(matches!(cmd, WebSocketCommand::Hello { version: None, .. }));
+    }
+
+    #[test]
+    fn test_sequenced_event() {
+        let sequenced = SequencedEvent {
+            seq: 42,
+            event: WebSocketEvent::ZoomChanged { zoom: 1.5 },
+        };
+        let json = serde_json::to_value(&sequenced).unwrap();
+        assert_eq!(
+            json,
+            serde_json::json!({ "type": "ZOOM_CHANGED", "zoom": 1.5, "seq": 42 })
+        );
+        let parsed: SequencedEvent = serde_json::from_value(json).unwrap();
+        assert!(matches!(
+            parsed.event,
+            WebSocketEvent::ZoomChanged { zoom } if zoom == 1.5
+        ));
+    }
+
+    #[test]
+    fn test_request_id() {
+        let (id, cmd) = parse_command(r#"{"type": "GO_TO_PAGE", "page": 12, "request_id": 7}"#);
+        assert_eq!(id, Some(RequestId::Number(7)));
+        assert!(matches!(cmd, Ok(WebSocketCommand::GoToPage { page: 12 })));
+
+        // Commands with an `id` of their own keep it
+        let (id, cmd) =
+            parse_command(r#"{"type": "PLAY_MEDIA", "id": "clip-1", "request_id": "a"}"#);
+        assert_eq!(id, Some(RequestId::Text("a".to_string())));
+        assert!(matches!(cmd, Ok(WebSocketCommand::PlayMedia { id }) if id == "clip-1"));
+
+        let (id, cmd) = parse_command(r#"{"type": "GO_TO_PAGE", "request_id": "b"}"#);
+        assert_eq!(id, Some(RequestId::Text("b".to_string())));
+        assert!(cmd.is_err());
+        let (_, cmd) = parse_command(r#"{"type": "PING", "request_id": [1]}"#);
+        assert!(cmd.is_err());
+
+        let id = RequestId::Number(7);
+        assert_eq!(
+            reply_json(Some(&id), &WebSocketEvent::error("No PDF loaded")).unwrap(),
+            r#"{"type":"ERROR","message":"No PDF loaded","code":"OTHER","request_id":7,"ok":false}"#
+        );
+        assert_eq!(
+            reply_json(Some(&id), &WebSocketEvent::Pong).unwrap(),
+            r#"{"type":"PONG","request_id":7,"ok":true}"#
+        );
+        assert_eq!(
+            reply_json(None, &WebSocketEvent::Pong).unwrap(),
+            r#"{"type":"PONG"}"#
+        );
+    }
+
+    #[test]
+    fn test_batch() {
+        let json = r#"{"type": "BATCH", "commands": [
+            {"type": "GO_TO_PAGE", "page": 12},
+            {"type": "SET_ZOOM", "zoom": 1.5},
+            {"type": "TOGGLE_PRESENTER"}
+        ]}"#;
+        let cmd: WebSocketCommand = serde_json::from_str(json).unwrap();
+        assert!(matches!(
+            cmd,
+            WebSocketCommand::Batch { ref commands } if matches!(
+                commands.as_slice(),
+                [
+                    WebSocketCommand::GoToPage { page: 12 },
+                    WebSocketCommand::SetZoom { .. },
+                    WebSocketCommand::TogglePresenter
+                ]
+            )
+        ));
+
+        let ran = WebSocketEvent::BatchResults {
+            results: vec![
+                WebSocketEvent::ZoomChanged { zoom: 1.5 },
+                WebSocketEvent::PresenterChanged { active: true },
+            ],
+        };
+        assert!(!ran.is_failure());
+        assert_eq!(
+            serde_json::to_string(&ran).unwrap(),
+            r#"{"type":"BATCH_RESULTS","results":[{"type":"ZOOM_CHANGED","zoom":1.5},{"type":"PRESENTER_CHANGED","active":true}]}"#
+        );
+    }
+
+    #[test]
+    fn test_error_event() {
+        let event = WebSocketEvent::from_error(&StreamSlateError::PageOutOfRange {
+            page: 12,
+            total_pages: 10,
+        });
+        assert!(event.is_failure());
+        assert_eq!(
+            serde_json::to_string(&event).unwrap(),
+            r#"{"type":"ERROR","message":"Page 12 is out of range (1-10)","code":"PAGE_OUT_OF_RANGE","page":12,"total_pages":10}"#
+        );
+
+        // Errors from servers without codes
+        let event: WebSocketEvent =
+            serde_json::from_str(r#"{"type":"ERROR","message":"Oops"}"#).unwrap();
+        assert!(matches!(
+            event,
+            WebSocketEvent::Error {
+                code: ErrorCode::Other,
+                page: None,
+                ..
+            }
+        ));
+    }
+
+    #[test]
+    fn test_pointer_moved() {
+        let json = r#"{"type": "POINTER_MOVED", "page": 4, "x": 0.25, "y": 0.5}"#;
+        let cmd: WebSocketCommand = serde_json::from_str(json).unwrap();
+        assert!(matches!(
+            cmd,
+            WebSocketCommand::PointerMoved { page: 4, x, y } if x == 0.25 && y == 0.5
+        ));
+
+        let event = WebSocketEvent::PointerMoved {
+            page: 4,
+            x: 0.25,
+            y: 0.5,
+            client: None,
+        };
+        let json = serde_json::to_string(&event).unwrap();
+        assert_eq!(
+            json,
+            r#"{"type":"POINTER_MOVED","page":4,"x":0.25,"y":0.5}"#
+        );
+    }
+}
@@ -0,0 +1,335 @@
+/*
+ * This file is part of StreamSlate.
+ * Copyright (C) 2025 StreamSlate Contributors
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+
+//! StreamSlate client SDK
+//!
+//! A typed, async API for third-party Rust tools (Companion modules, chat
+//! bots, show-control scripts) that drive StreamSlate over WebSocket. Built
+//! on the same protocol types as the server, so no hand-rolled JSON.
+//!
+//! Enabled with the `client-sdk` feature:
+//!
+//! ```no_run
+//! use streamslate::sdk::{Client, ClientConfig, ClientNotification, WebSocketEvent};
+//!
+//! # async fn run() {
+//! let mut client = Client::connect(ClientConfig::new("ws://127.0.0.1:11451"));
+//! while let Some(notification) = client.next_notification().await {
+//!     if let ClientNotification::Event(WebSocketEvent::PageChanged { page, .. }) = notification {
+//!         println!("now on page {page}");
+//!     }
+//! }
+//! # }
+//! ```
+
+use crate::client::{self, ClientHandle};
+use tokio::sync::mpsc;
+
+pub use crate::capture::viewport::OutputViewport;
+pub use crate::client::{ClientConfig, ClientNotification, ReconnectPolicy};
+pub use crate::protocol::{WebSocketCommand, WebSocketEvent};
+pub use crate::websocket::ClientPreferences;
+
+/// Result type for SDK calls
+pub type Result<T> = std::result::Result<T, String>;
+
+/// A connection to a StreamSlate instance
+///
+/// Reconnects automatically according to the configured `ReconnectPolicy`.
+/// Commands fail while disconnected instead of being queued.
+#[derive(Debug)]
+pub struct Client {
+    handle: ClientHandle,
+    notifications: mpsc::UnboundedReceiver<ClientNotification>,
+}
+
+impl Client {
+    /// Start connecting to the server described by `config`
+    pub fn connect(config: ClientConfig) -> Self {
+        let (handle, notifications) = client::spawn_with_channel(config);
+        Self {
+            handle,
+            notifications,
+        }
+    }
+
+    /// Wait for the next connection notification or server event
+    ///
+    /// Returns `None` once the client has shut down.
+    pub async fn next_notification(&mut self) -> Option<ClientNotification> {
+        self.notifications.recv().await
+    }
+
+    /// Wait until the connection is (re)established
+    pub async fn wait_connected(&mut self) -> Result<()> {
+        if self.is_connected() {
+            return Ok(());
+        }
+        while let Some(notification) = self.notifications.recv().await {
+            if matches!(notification, ClientNotification::Connected) {
+                return Ok(());
+            }
+        }
+        Err("Client shut down before connecting".to_string())
+    }
+
+    /// Whether the client currently has a live connection
+    pub fn is_connected(&self) -> bool {
+        self.handle.is_connected()
+    }
+
+    /// Send a raw protocol command
+    pub fn send(&self, command: WebSocketCommand) -> Result<()> {
+        self.handle.send(command)
+    }
+
+    pub fn next_page(&self) -> Result<()> {
+        self.send(WebSocketCommand::NextPage)
+    }
+
+    pub fn previous_page(&self) -> Result<()> {
+        self.send(WebSocketCommand::PreviousPage)
+    }
+
+    pub fn add_reaction(&self, page: u32, emoji: &str) -> Result<()> {
+        self.send(WebSocketCommand::AddReaction {
+            page,
+            emoji: emoji.to_string(),
+        })
+    }
+
+    pub fn get_agenda(&self) -> Result<()> {
+        self.send(WebSocketCommand::GetAgenda)
+    }
+
+    /// Request a `NotesUpdated` event with a page's speaker notes
+    pub fn get_notes(&self, page: u32) -> Result<()> {
+        self.send(WebSocketCommand::GetNotes { page })
+    }
+
+    pub fn get_media(&self) -> Result<()> {
+        self.send(WebSocketCommand::GetMedia)
+    }
+
+    pub fn play_media(&self, id: &str) -> Result<()> {
+        self.send(WebSocketCommand::PlayMedia { id: id.to_string() })
+    }
+
+    pub fn pause_media(&self, id: &str) -> Result<()> {
+        self.send(WebSocketCommand::PauseMedia { id: id.to_string() })
+    }
+
+    /// Move a clip to `position` seconds from its start
+    pub fn seek_media(&self, id: &str, position: f64) -> Result<()> {
+        self.send(WebSocketCommand::SeekMedia {
+            id: id.to_string(),
+            position,
+        })
+    }
+
+    /// Flash `data` as a QR code on the outputs, until `hide_qr` if no
+    /// duration is given
+    pub fn show_qr(&self, data: &str, duration_seconds: Option<u32>) -> Result<()> {
+        self.send(WebSocketCommand::ShowQr {
+            data: data.to_string(),
+            duration_seconds,
+        })
+    }
+
+    pub fn hide_qr(&self) -> Result<()> {
+        self.send(WebSocketCommand::HideQr)
+    }
+
+    pub fn next_section(&self) -> Result<()> {
+        self.send(WebSocketCommand::NextSection)
+    }
+
+    pub fn previous_section(&self) -> Result<()> {
+        self.send(WebSocketCommand::PreviousSection)
+    }
+
+    /// Navigate to a 1-indexed page
+    pub fn go_to_page(&self, page: u32) -> Result<()> {
+        self.send(WebSocketCommand::GoToPage { page })
+    }
+
+    /// Set zoom level (1.0 = 100%)
+    pub fn set_zoom(&self, zoom: f64) -> Result<()> {
+        self.send(WebSocketCommand::SetZoom { zoom })
+    }
+
+    pub fn toggle_presenter(&self) -> Result<()> {
+        self.send(WebSocketCommand::TogglePresenter)
+    }
+
+    /// Toggle smart dark mode (hue-preserving luminance inversion)
+    pub fn set_smart_invert(&self, enabled: bool) -> Result<()> {
+        self.send(WebSocketCommand::SetSmartInvert { enabled })
+    }
+
+    /// Black out every output, or restore the slides
+    pub fn set_blackout(&self, enabled: bool) -> Result<()> {
+        self.send(WebSocketCommand::SetBlackout { enabled })
+    }
+
+    /// Pan/zoom the output, easing over `duration_ms` (default 500 ms)
+    pub fn set_output_viewport(
+        &self,
+        viewport: OutputViewport,
+        duration_ms: Option<u32>,
+    ) -> Result<()> {
+        self.send(WebSocketCommand::SetOutputViewport {
+            viewport,
+            duration_ms,
+        })
+    }
+
+    /// Request a `State` event with the current document and page
+    pub fn request_state(&self) -> Result<()> {
+        self.send(WebSocketCommand::GetState)
+    }
+
+    /// Request a `PageWords` event with word rectangles for a page
+    pub fn request_page_words(&self, page: u32) -> Result<()> {
+        self.send(WebSocketCommand::GetPageWords { page })
+    }
+
+    pub fn add_annotation(&self, page: u32, annotation: serde_json::Value) -> Result<()> {
+        self.send(WebSocketCommand::AddAnnotation {
+            page,
+            annotation,
+            clock: None,
+        })
+    }
+
+    /// Merge `annotation`'s fields into the annotation with the same `id`
+    pub fn update_annotation(&self, page: u32, annotation: serde_json::Value) -> Result<()> {
+        self.send(WebSocketCommand::UpdateAnnotation {
+            page,
+            annotation,
+            clock: None,
+        })
+    }
+
+    pub fn delete_annotation(&self, page: u32, id: &str) -> Result<()> {
+        self.send(WebSocketCommand::DeleteAnnotation {
+            page,
+            id: id.to_string(),
+            clock: None,
+        })
+    }
+
+    pub fn clear_annotations(&self) -> Result<()> {
+        self.send(WebSocketCommand::ClearAnnotations)
+    }
+
+    /// Revert the last annotation change, whoever made it
+    pub fn undo_annotation(&self) -> Result<()> {
+        self.send(WebSocketCommand::UndoAnnotation)
+    }
+
+    pub fn redo_annotation(&self) -> Result<()> {
+        self.send(WebSocketCommand::RedoAnnotation)
+    }
+
+    /// Erase the parts of free-draw strokes on `page` within `radius` of
+    /// (`x`, `y`), splitting the strokes it crosses
+    pub fn erase_at(&self, page: u32, x: f64, y: f64, radius: f64) -> Result<()> {
+        self.send(WebSocketCommand::EraseAt { page, x, y, radius })
+    }
+
+    /// Request an `AnnotationSearchResults` event with the annotations whose
+    /// text contains every word of `query`
+    pub fn search_annotations(&self, query: &str) -> Result<()> {
+        self.send(WebSocketCommand::SearchAnnotations {
+            query: query.to_string(),
+        })
+    }
+
+    /// Apply a style preset to annotations added from now on, or none
+    pub fn set_active_style_preset(&self, id: Option<&str>) -> Result<()> {
+        self.send(WebSocketCommand::SetActiveStylePreset {
+            id: id.map(str::to_string),
+        })
+    }
+
+    /// Store preferences for this client (requires `client_id` in the config)
+    pub fn set_preferences(&self, preferences: ClientPreferences) -> Result<()> {
+        self.send(WebSocketCommand::SetPreferences { preferences })
+    }
+
+    /// Stop reconnecting and close the connection
+    pub fn shutdown(&self) {
+        self.handle.shutdown();
+    }
+}
+
+impl Drop for Client {
+    fn drop(&mut self) {
+        self.handle.shutdown();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures_util::{SinkExt, StreamExt};
+    use tokio::net::TcpListener;
+    use tokio_tungstenite::tungstenite::Message;
+
+    #[tokio::test]
+    async fn test_client_round_trip() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("ws://{}", listener.local_addr().unwrap());
+
+        let server = tokio::spawn(async move {
+            let (stream, _) = listener.accept().await.unwrap();
+            let mut ws = tokio_tungstenite::accept_async(stream).await.unwrap();
+
+            let identify = ws.next().await.unwrap().unwrap();
+            let next = ws.next().await.unwrap().unwrap();
+
+            let event = WebSocketEvent::PageChanged {
+                page: 2,
+                total_pages: 5,
+                section: None,
+            };
+            ws.send(Message::Text(serde_json::to_string(&event).unwrap()))
+                .await
+                .unwrap();
+            (identify.into_text().unwrap(), next.into_text().unwrap())
+        });
+
+        let mut client = Client::connect(ClientConfig {
+            client_id: Some("bot".to_string()),
+            ..ClientConfig::new(url)
+        });
+        client.wait_connected().await.unwrap();
+        client.next_page().unwrap();
+
+        let event = client.next_notification().await.unwrap();
+        assert!(matches!(
+            event,
+            ClientNotification::Event(WebSocketEvent::PageChanged { page: 2, .. })
+        ));
+
+        let (identify, next) = server.await.unwrap();
+        assert!(identify.contains("IDENTIFY") && identify.contains("bot"));
+        assert!(next.contains("NEXT_PAGE"));
+    }
+}
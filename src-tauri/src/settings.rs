@@ -0,0 +1,150 @@
+/*
+ * This file is part of StreamSlate.
+ * Copyright (C) 2025 StreamSlate Contributors
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+
+//! Settings files in the app data directory
+//!
+//! Each feature keeps its settings in a JSON file of its own there. A file
+//! that is missing, can't be parsed or holds invalid values loads as the
+//! feature's defaults, so a bad edit never keeps the app from starting.
+
+use crate::error::Result;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+use tauri::{AppHandle, Manager};
+use tracing::{debug, warn};
+
+/// Settings stored as a JSON file in the app data directory
+pub trait Settings: Serialize + DeserializeOwned + Default {
+    /// Check settings read from the file; invalid ones are replaced by the
+    /// defaults
+    fn check(&self) -> Result<()> {
+        Ok(())
+    }
+
+    /// Settings used when the file can't be parsed
+    fn unreadable() -> Self {
+        Self::default()
+    }
+}
+
+/// Path of a settings file in the app data directory
+pub fn settings_path(app_handle: &AppHandle, file: &str) -> Option<PathBuf> {
+    app_handle
+        .path()
+        .app_data_dir()
+        .ok()
+        .map(|dir| dir.join(file))
+}
+
+/// Load stored settings, returning the defaults if missing or unreadable
+pub fn load_settings<T: Settings>(app_handle: &AppHandle, file: &str) -> T {
+    settings_path(app_handle, file)
+        .map(|path| read_settings(&path))
+        .unwrap_or_default()
+}
+
+/// Persist settings
+pub fn save_settings<T: Settings>(app_handle: &AppHandle, file: &str, settings: &T) -> Result<()> {
+    match settings_path(app_handle, file) {
+        Some(path) => write_settings(&path, settings),
+        None => {
+            warn!(file, "No app data directory to store settings in");
+            Ok(())
+        }
+    }
+}
+
+fn read_settings<T: Settings>(path: &Path) -> T {
+    let Ok(content) = std::fs::read_to_string(path) else {
+        debug!(path = %path.display(), "No settings file found");
+        return T::default();
+    };
+    let settings: T = match serde_json::from_str(&content) {
+        Ok(settings) => settings,
+        Err(e) => {
+            warn!(path = %path.display(), error = %e, "Failed to parse settings");
+            return T::unreadable();
+        }
+    };
+    match settings.check() {
+        Ok(()) => settings,
+        Err(e) => {
+            warn!(path = %path.display(), error = %e, "Ignoring invalid settings");
+            T::default()
+        }
+    }
+}
+
+fn write_settings<T: Serialize>(path: &Path, settings: &T) -> Result<()> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    std::fs::write(path, serde_json::to_string_pretty(settings)?)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::StreamSlateError;
+    use serde::Deserialize;
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Volume {
+        level: u8,
+    }
+
+    impl Default for Volume {
+        fn default() -> Self {
+            Self { level: 5 }
+        }
+    }
+
+    impl Settings for Volume {
+        fn check(&self) -> Result<()> {
+            if self.level > 10 {
+                return Err(StreamSlateError::Other("Too loud".to_string()));
+            }
+            Ok(())
+        }
+
+        fn unreadable() -> Self {
+            Self { level: 0 }
+        }
+    }
+
+    #[test]
+    fn test_read_settings() {
+        let dir =
+            std::env::temp_dir().join(format!("streamslate-settings-{}", uuid::Uuid::new_v4()));
+        let path = dir.join("volume.json");
+        assert_eq!(read_settings::<Volume>(&path), Volume { level: 5 });
+
+        write_settings(&path, &Volume { level: 7 }).unwrap();
+        assert_eq!(read_settings::<Volume>(&path), Volume { level: 7 });
+
+        write_settings(&path, &Volume { level: 11 }).unwrap();
+        assert_eq!(read_settings::<Volume>(&path), Volume { level: 5 });
+
+        std::fs::write(&path, "{").unwrap();
+        assert_eq!(read_settings::<Volume>(&path), Volume { level: 0 });
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -0,0 +1,475 @@
+/*
+ * This file is part of StreamSlate.
+ * Copyright (C) 2025 StreamSlate Contributors
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+
+//! Conflict-free merging of concurrent annotation edits
+//!
+//! Annotations form a last-writer-wins map keyed by annotation id, where
+//! every field of an annotation (and the page it sits on) is its own
+//! register. Each edit carries a Lamport stamp: the editing replica's clock
+//! and its name as a tie-breaker. A field only takes a new value from an
+//! edit stamped later than the one that last set it, and a deletion only
+//! wins over edits stamped before it, so two operators changing different
+//! fields of one annotation both keep their change, and concurrent edits
+//! to the same field settle on the same value whatever order they arrive in.
+
+use super::history::{added, annotation_id, AnnotationEdit, PageAnnotations};
+use serde_json::{Map, Value};
+use std::collections::HashMap;
+
+/// Replica name of edits made in the host UI
+pub const HOST_REPLICA: &str = "host";
+
+/// Furthest a replica's clock may run ahead of the highest seen; a client
+/// sending a huge clock would otherwise win every later edit
+pub const MAX_CLOCK_LEAD: u64 = 10_000;
+
+/// Lamport stamp of an edit, ordered by clock and then replica
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Stamp {
+    pub clock: u64,
+    pub replica: String,
+}
+
+/// Stamps of one annotation's fields
+#[derive(Debug, Clone, Default)]
+struct Register {
+    page: Option<Stamp>,
+    fields: HashMap<String, Stamp>,
+    deleted: Option<Stamp>,
+}
+
+impl Register {
+    /// Latest stamp of any field or the page
+    fn latest(&self) -> Option<&Stamp> {
+        self.fields.values().chain(self.page.as_ref()).max()
+    }
+}
+
+/// Clock and field stamps of the open document's annotations
+#[derive(Debug, Clone, Default)]
+pub struct AnnotationCrdt {
+    clock: u64,
+    registers: HashMap<String, Register>,
+}
+
+impl AnnotationCrdt {
+    /// Highest clock seen, for clients to continue from
+    pub fn clock(&self) -> u64 {
+        self.clock
+    }
+
+    /// Stamp an edit from `replica`
+    ///
+    /// `clock` is the replica's own Lamport clock for the edit, held to
+    /// [`MAX_CLOCK_LEAD`] ahead of the highest seen; without one the edit is
+    /// stamped as arriving now, after everything seen so far.
+    pub fn stamp(&mut self, replica: &str, clock: Option<u64>) -> Stamp {
+        let next = self.clock.saturating_add(1);
+        let clock = clock.map_or(next, |clock| {
+            clock.min(self.clock.saturating_add(MAX_CLOCK_LEAD))
+        });
+        self.clock = self.clock.max(clock);
+        Stamp {
+            clock,
+            replica: replica.to_string(),
+        }
+    }
+
+    /// Latest stamp of annotation `id`, including its deletion
+    pub fn stamp_of(&self, id: &str) -> Option<&Stamp> {
+        let register = self.registers.get(id)?;
+        register.latest().max(register.deleted.as_ref())
+    }
+
+    /// Merge an add or update of `annotation` on `page` stamped `stamp`
+    ///
+    /// Fields the annotation leaves out are kept. Returns the edits made,
+    /// which are empty when every changed field already holds a later value.
+    pub fn merge_put(
+        &mut self,
+        annotations: &mut PageAnnotations,
+        page: u32,
+        annotation: &Map<String, Value>,
+        stamp: &Stamp,
+    ) -> Vec<AnnotationEdit> {
+        let Some(id) = annotation.get("id").and_then(Value::as_str) else {
+            // Without an id there is nothing to merge with
+            let value = Value::Object(annotation.clone()).to_string();
+            annotations.entry(page).or_default().push(value.clone());
+            return vec![added(page, value)];
+        };
+        let register = self.registers.entry(id.to_string()).or_default();
+        if register
+            .deleted
+            .as_ref()
+            .is_some_and(|deleted| deleted >= stamp)
+        {
+            return Vec::new();
+        }
+        register.deleted = None;
+
+        let Some((stored_page, stored)) = find(annotations, id) else {
+            register.page = Some(stamp.clone());
+            for key in annotation.keys() {
+                register.fields.insert(key.clone(), stamp.clone());
+            }
+            let value = Value::Object(annotation.clone()).to_string();
+            annotations.entry(page).or_default().push(value.clone());
+            return vec![added(page, value)];
+        };
+
+        let mut merged = stored.clone();
+        for (key, value) in annotation {
+            if merged.get(key) == Some(value) {
+                continue;
+            }
+            if register.fields.get(key).is_some_and(|field| field >= stamp) {
+                continue;
+            }
+            register.fields.insert(key.clone(), stamp.clone());
+            merged.insert(key.clone(), value.clone());
+        }
+        let target_page = if page != stored_page && register.page.as_ref() < Some(stamp) {
+            register.page = Some(stamp.clone());
+            page
+        } else {
+            stored_page
+        };
+
+        if merged == stored && target_page == stored_page {
+            return Vec::new();
+        }
+        let before = Value::Object(stored).to_string();
+        let after = Value::Object(merged).to_string();
+        let edits = if target_page == stored_page {
+            vec![AnnotationEdit {
+                page: stored_page,
+                id: id.to_string(),
+                before: Some(before),
+                after: Some(after),
+            }]
+        } else {
+            vec![
+                AnnotationEdit {
+                    page: stored_page,
+                    id: id.to_string(),
+                    before: Some(before),
+                    after: None,
+                },
+                added(target_page, after),
+            ]
+        };
+        apply_edits(annotations, &edits);
+        edits
+    }
+
+    /// Merge the deletion of annotation `id` stamped `stamp`
+    ///
+    /// Returns the edit made, or `None` when the annotation is already gone
+    /// or was edited after `stamp`.
+    pub fn merge_delete(
+        &mut self,
+        annotations: &mut PageAnnotations,
+        id: &str,
+        stamp: &Stamp,
+    ) -> Option<AnnotationEdit> {
+        let register = self.registers.entry(id.to_string()).or_default();
+        if register.latest().is_some_and(|latest| latest > stamp) {
+            return None;
+        }
+        if register.deleted.as_ref() < Some(stamp) {
+            register.deleted = Some(stamp.clone());
+        }
+        let (page, stored) = find(annotations, id)?;
+        let edit = AnnotationEdit {
+            page,
+            id: id.to_string(),
+            before: Some(Value::Object(stored).to_string()),
+            after: None,
+        };
+        apply_edits(annotations, std::slice::from_ref(&edit));
+        Some(edit)
+    }
+
+    /// Stamp edits already applied by the host as the host's
+    pub fn record_local(&mut self, edits: &[AnnotationEdit]) {
+        for edit in edits {
+            let stamp = self.stamp(HOST_REPLICA, None);
+            let register = self.registers.entry(edit.id.clone()).or_default();
+            let Some(after) = edit.after.as_deref().and_then(parse_object) else {
+                register.deleted = Some(stamp);
+                continue;
+            };
+            let before = edit.before.as_deref().and_then(parse_object);
+            if before.is_none() {
+                register.page = Some(stamp.clone());
+                register.deleted = None;
+            }
+            for (key, value) in &after {
+                if before.as_ref().and_then(|b| b.get(key)) != Some(value) {
+                    register.fields.insert(key.clone(), stamp.clone());
+                }
+            }
+        }
+    }
+
+    /// Forget all stamps, keeping the clock so it never runs backwards
+    pub fn clear(&mut self) {
+        self.registers.clear();
+    }
+}
+
+/// Page and fields of annotation `id`
+fn find(annotations: &PageAnnotations, id: &str) -> Option<(u32, Map<String, Value>)> {
+    annotations.iter().find_map(|(page, list)| {
+        list.iter()
+            .find(|a| annotation_id(a) == id)
+            .and_then(|a| parse_object(a))
+            .map(|object| (*page, object))
+    })
+}
+
+fn parse_object(annotation: &str) -> Option<Map<String, Value>> {
+    match serde_json::from_str(annotation) {
+        Ok(Value::Object(object)) => Some(object),
+        _ => None,
+    }
+}
+
+fn apply_edits(annotations: &mut PageAnnotations, edits: &[AnnotationEdit]) {
+    for edit in edits {
+        let list = annotations.entry(edit.page).or_default();
+        let position = list.iter().position(|a| annotation_id(a) == edit.id);
+        match (position, &edit.after) {
+            (Some(index), Some(after)) => list[index] = after.clone(),
+            (None, Some(after)) => list.push(after.clone()),
+            (Some(index), None) => {
+                list.remove(index);
+            }
+            (None, None) => {}
+        }
+        if list.is_empty() {
+            annotations.remove(&edit.page);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn object(value: Value) -> Map<String, Value> {
+        match value {
+            Value::Object(object) => object,
+            _ => unreachable!(),
+        }
+    }
+
+    fn stored(annotations: &PageAnnotations, page: u32) -> Value {
+        serde_json::from_str(&annotations[&page][0]).unwrap()
+    }
+
+    #[test]
+    fn test_concurrent_field_edits_both_apply() {
+        let mut crdt = AnnotationCrdt::default();
+        let mut annotations = PageAnnotations::new();
+        let add = crdt.stamp("producer", Some(1));
+        crdt.merge_put(
+            &mut annotations,
+            1,
+            &object(json!({ "id": "a", "x": 10, "color": "red" })),
+            &add,
+        );
+
+        // Both saw the add; the host moves it while the producer recolors it
+        let host = crdt.stamp("host", Some(2));
+        let producer = crdt.stamp("producer", Some(2));
+        crdt.merge_put(
+            &mut annotations,
+            1,
+            &object(json!({ "id": "a", "color": "blue" })),
+            &producer,
+        );
+        crdt.merge_put(
+            &mut annotations,
+            1,
+            &object(json!({ "id": "a", "x": 50 })),
+            &host,
+        );
+        assert_eq!(
+            stored(&annotations, 1),
+            json!({ "id": "a", "x": 50, "color": "blue" })
+        );
+        assert_eq!(crdt.clock(), 2);
+    }
+
+    #[test]
+    fn test_same_field_converges_in_any_order() {
+        let ops = [
+            (
+                Stamp {
+                    clock: 3,
+                    replica: "host".into(),
+                },
+                "green",
+            ),
+            (
+                Stamp {
+                    clock: 3,
+                    replica: "producer".into(),
+                },
+                "blue",
+            ),
+            (
+                Stamp {
+                    clock: 2,
+                    replica: "tablet".into(),
+                },
+                "red",
+            ),
+        ];
+        for order in [[0, 1, 2], [2, 1, 0], [1, 2, 0]] {
+            let mut crdt = AnnotationCrdt::default();
+            let mut annotations =
+                PageAnnotations::from([(1, vec![r#"{"id":"a","color":"black"}"#.to_string()])]);
+            for i in order {
+                let (stamp, color) = &ops[i];
+                crdt.merge_put(
+                    &mut annotations,
+                    1,
+                    &object(json!({ "id": "a", "color": color })),
+                    stamp,
+                );
+            }
+            assert_eq!(stored(&annotations, 1)["color"], "blue");
+        }
+    }
+
+    #[test]
+    fn test_delete_against_concurrent_edits() {
+        let mut crdt = AnnotationCrdt::default();
+        let mut annotations = PageAnnotations::new();
+        let add = crdt.stamp("host", None);
+        crdt.merge_put(
+            &mut annotations,
+            2,
+            &object(json!({ "id": "a", "x": 1 })),
+            &add,
+        );
+
+        // An edit stamped after the deletion survives it
+        let delete = Stamp {
+            clock: 2,
+            replica: "producer".into(),
+        };
+        let edit = Stamp {
+            clock: 3,
+            replica: "host".into(),
+        };
+        crdt.merge_put(
+            &mut annotations,
+            2,
+            &object(json!({ "id": "a", "x": 2 })),
+            &edit,
+        );
+        assert!(crdt.merge_delete(&mut annotations, "a", &delete).is_none());
+        assert_eq!(stored(&annotations, 2)["x"], 2);
+
+        // A later deletion wins, and stale edits don't bring it back
+        let delete = crdt.stamp("producer", Some(4));
+        assert_eq!(
+            crdt.merge_delete(&mut annotations, "a", &delete)
+                .unwrap()
+                .page,
+            2
+        );
+        assert!(annotations.is_empty());
+        assert!(crdt
+            .merge_put(
+                &mut annotations,
+                2,
+                &object(json!({ "id": "a", "x": 3 })),
+                &edit
+            )
+            .is_empty());
+        assert!(annotations.is_empty());
+    }
+
+    #[test]
+    fn test_moves_between_pages_and_local_edits() {
+        let mut crdt = AnnotationCrdt::default();
+        let mut annotations = PageAnnotations::from([(1, vec![r#"{"id":"a","x":1}"#.to_string()])]);
+        let stamp = crdt.stamp("tablet", None);
+        let edits = crdt.merge_put(&mut annotations, 3, &object(json!({ "id": "a" })), &stamp);
+        assert_eq!(edits.len(), 2);
+        assert!(!annotations.contains_key(&1));
+        assert_eq!(stored(&annotations, 3), json!({ "id": "a", "x": 1 }));
+
+        // The host changes x; a remote edit from before that loses
+        crdt.record_local(&[AnnotationEdit {
+            page: 3,
+            id: "a".into(),
+            before: Some(r#"{"id":"a","x":1}"#.into()),
+            after: Some(r#"{"id":"a","x":5}"#.into()),
+        }]);
+        assert_eq!(crdt.stamp_of("a").unwrap().replica, HOST_REPLICA);
+        annotations.insert(3, vec![r#"{"id":"a","x":5}"#.to_string()]);
+        assert!(crdt
+            .merge_put(
+                &mut annotations,
+                3,
+                &object(json!({ "id": "a", "x": 9 })),
+                &stamp
+            )
+            .is_empty());
+    }
+
+    #[test]
+    fn test_clock_far_ahead_is_held_back() {
+        let mut crdt = AnnotationCrdt::default();
+        let mut annotations = PageAnnotations::new();
+        let add = crdt.stamp("host", None);
+        crdt.merge_put(
+            &mut annotations,
+            1,
+            &object(json!({ "id": "a", "color": "red" })),
+            &add,
+        );
+
+        let runaway = crdt.stamp("tablet", Some(u64::MAX));
+        assert_eq!(runaway.clock, 1 + MAX_CLOCK_LEAD);
+        crdt.merge_put(
+            &mut annotations,
+            1,
+            &object(json!({ "id": "a", "color": "blue" })),
+            &runaway,
+        );
+
+        // Later edits still win, and the clock keeps counting
+        let next = crdt.stamp("host", None);
+        assert_eq!(next.clock, 2 + MAX_CLOCK_LEAD);
+        crdt.merge_put(
+            &mut annotations,
+            1,
+            &object(json!({ "id": "a", "color": "green" })),
+            &next,
+        );
+        assert_eq!(stored(&annotations, 1)["color"], "green");
+    }
+}
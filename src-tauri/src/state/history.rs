@@ -0,0 +1,509 @@
+/*
+ * This file is part of StreamSlate.
+ * Copyright (C) 2025 StreamSlate Contributors
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+
+//! Annotation undo/redo log and restore points
+//!
+//! Every annotation mutation, whoever made it (the host UI, a remote client
+//! over WebSocket), is recorded as an operation of per-annotation edits, so
+//! undo reverts the last stroke no matter which client drew it. Snapshots of
+//! the whole set are kept as restore points, so even a clear that has since
+//! been buried under new strokes can be reverted. A timestamped timeline of
+//! every change, undos included, lets the markup be replayed as it was made.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, VecDeque};
+
+/// Operations kept for undo
+pub const MAX_HISTORY: usize = 100;
+
+/// Restore points kept
+pub const MAX_VERSIONS: usize = 50;
+
+/// Timeline changes kept for replay
+pub const MAX_TIMELINE: usize = 10_000;
+
+/// Consecutive saves closer together than this share one restore point
+const COALESCE_SECS: i64 = 30;
+
+/// Annotations per page, as stored in `AppState::annotations`
+pub type PageAnnotations = HashMap<u32, Vec<String>>;
+
+/// One annotation's value before and after a change
+///
+/// `before` is `None` for an added annotation, `after` for a removed one.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AnnotationEdit {
+    pub page: u32,
+    pub id: String,
+    pub before: Option<String>,
+    pub after: Option<String>,
+}
+
+/// Undo and redo stacks of annotation operations
+#[derive(Debug, Clone, Default)]
+pub struct AnnotationHistory {
+    undo: Vec<Vec<AnnotationEdit>>,
+    redo: Vec<Vec<AnnotationEdit>>,
+}
+
+impl AnnotationHistory {
+    /// Record an operation made of `edits`, dropping anything to redo
+    pub fn record(&mut self, edits: Vec<AnnotationEdit>) {
+        if edits.is_empty() {
+            return;
+        }
+        self.undo.push(edits);
+        if self.undo.len() > MAX_HISTORY {
+            self.undo.remove(0);
+        }
+        self.redo.clear();
+    }
+
+    /// Revert the last operation, returning the pages it touched
+    pub fn undo(&mut self, annotations: &mut PageAnnotations) -> Option<Vec<u32>> {
+        let edits = self.undo.pop()?;
+        for edit in edits.iter().rev() {
+            apply(annotations, edit.page, &edit.id, edit.before.as_ref());
+        }
+        let pages = touched_pages(&edits);
+        self.redo.push(edits);
+        Some(pages)
+    }
+
+    /// Reapply the last undone operation, returning the pages it touched
+    pub fn redo(&mut self, annotations: &mut PageAnnotations) -> Option<Vec<u32>> {
+        let edits = self.redo.pop()?;
+        for edit in &edits {
+            apply(annotations, edit.page, &edit.id, edit.after.as_ref());
+        }
+        let pages = touched_pages(&edits);
+        self.undo.push(edits);
+        Some(pages)
+    }
+
+    pub fn can_undo(&self) -> bool {
+        !self.undo.is_empty()
+    }
+
+    pub fn can_redo(&self) -> bool {
+        !self.redo.is_empty()
+    }
+
+    /// Forget the history, which belongs to the previous document
+    pub fn clear(&mut self) {
+        self.undo.clear();
+        self.redo.clear();
+    }
+}
+
+/// Summary of a restore point
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AnnotationVersion {
+    pub id: u64,
+    pub created_at: DateTime<Utc>,
+    /// What produced this state: `load`, `save`, `add`, `clear`, `undo`,
+    /// `redo` or `restore`
+    pub reason: String,
+    /// Annotations in this version
+    pub count: usize,
+}
+
+/// Bounded list of annotation snapshots
+#[derive(Debug, Clone, Default)]
+pub struct VersionLog {
+    versions: VecDeque<(AnnotationVersion, PageAnnotations)>,
+    next_id: u64,
+}
+
+impl VersionLog {
+    /// Keep `annotations` as a restore point, unless nothing changed
+    ///
+    /// A stream of saves (one per stroke while drawing) is folded into one
+    /// version so it doesn't push older restore points out.
+    pub fn snapshot(&mut self, annotations: &PageAnnotations, reason: &str, at: DateTime<Utc>) {
+        if let Some((latest, snapshot)) = self.versions.back() {
+            if snapshot == annotations {
+                return;
+            }
+            let incremental = matches!(reason, "save" | "add");
+            if incremental
+                && latest.reason == reason
+                && (at - latest.created_at).num_seconds() < COALESCE_SECS
+            {
+                self.versions.pop_back();
+            }
+        }
+
+        self.next_id += 1;
+        let version = AnnotationVersion {
+            id: self.next_id,
+            created_at: at,
+            reason: reason.to_string(),
+            count: annotations.values().map(Vec::len).sum(),
+        };
+        self.versions.push_back((version, annotations.clone()));
+        if self.versions.len() > MAX_VERSIONS {
+            self.versions.pop_front();
+        }
+    }
+
+    /// Restore points, newest first
+    pub fn list(&self) -> Vec<AnnotationVersion> {
+        self.versions.iter().rev().map(|(v, _)| v.clone()).collect()
+    }
+
+    pub fn get(&self, id: u64) -> Option<&PageAnnotations> {
+        self.versions
+            .iter()
+            .find(|(version, _)| version.id == id)
+            .map(|(_, snapshot)| snapshot)
+    }
+
+    /// Forget all restore points; IDs keep counting up so stale ones can't match
+    pub fn clear(&mut self) {
+        self.versions.clear();
+    }
+}
+
+/// An annotation change and when it was made
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TimelineEntry {
+    pub at: DateTime<Utc>,
+    pub edit: AnnotationEdit,
+}
+
+/// Every annotation change to the open document, oldest first
+#[derive(Debug, Clone, Default)]
+pub struct AnnotationTimeline {
+    entries: VecDeque<TimelineEntry>,
+}
+
+impl AnnotationTimeline {
+    /// Log `edits` as made at `at`
+    pub fn record(&mut self, edits: &[AnnotationEdit], at: DateTime<Utc>) {
+        for edit in edits {
+            self.entries.push_back(TimelineEntry {
+                at,
+                edit: edit.clone(),
+            });
+        }
+        while self.entries.len() > MAX_TIMELINE {
+            self.entries.pop_front();
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// The annotations before the first logged change, given `current`, and
+    /// the changes since
+    pub fn replay(&self, current: &PageAnnotations) -> (PageAnnotations, Vec<TimelineEntry>) {
+        let mut start = current.clone();
+        for entry in self.entries.iter().rev() {
+            let edit = &entry.edit;
+            apply(&mut start, edit.page, &edit.id, edit.before.as_ref());
+        }
+        (start, self.entries.iter().cloned().collect())
+    }
+
+    /// Forget the timeline, which belongs to the previous document
+    pub fn clear(&mut self) {
+        self.entries.clear();
+    }
+}
+
+/// A timeline rebuilt from each annotation's `created` time, for markup made
+/// before the document was last opened
+///
+/// Annotations without a readable time are there from the start.
+pub fn timeline_from_created(current: &PageAnnotations) -> (PageAnnotations, Vec<TimelineEntry>) {
+    let mut start = PageAnnotations::new();
+    let mut entries = Vec::new();
+    for (page, list) in current {
+        for annotation in list {
+            let created = serde_json::from_str::<serde_json::Value>(annotation)
+                .ok()
+                .and_then(|value| {
+                    DateTime::parse_from_rfc3339(value.get("created")?.as_str()?).ok()
+                });
+            match created {
+                Some(at) => entries.push(TimelineEntry {
+                    at: at.with_timezone(&Utc),
+                    edit: added(*page, annotation.clone()),
+                }),
+                None => start.entry(*page).or_default().push(annotation.clone()),
+            }
+        }
+    }
+    entries.sort_by_key(|entry| entry.at);
+    (start, entries)
+}
+
+/// Edits turning `before` into `after`, in page order
+pub fn diff(before: &PageAnnotations, after: &PageAnnotations) -> Vec<AnnotationEdit> {
+    let mut pages: Vec<u32> = before.keys().chain(after.keys()).copied().collect();
+    pages.sort_unstable();
+    pages.dedup();
+
+    let empty = Vec::new();
+    let mut edits = Vec::new();
+    for page in pages {
+        let old = before.get(&page).unwrap_or(&empty);
+        let new = after.get(&page).unwrap_or(&empty);
+        let old_by_id: HashMap<String, &String> =
+            old.iter().map(|a| (annotation_id(a), a)).collect();
+        let new_by_id: HashMap<String, &String> =
+            new.iter().map(|a| (annotation_id(a), a)).collect();
+
+        for annotation in old {
+            let id = annotation_id(annotation);
+            if !new_by_id.contains_key(&id) {
+                edits.push(AnnotationEdit {
+                    page,
+                    id,
+                    before: Some(annotation.clone()),
+                    after: None,
+                });
+            }
+        }
+        for annotation in new {
+            let id = annotation_id(annotation);
+            match old_by_id.get(&id) {
+                Some(previous) if *previous == annotation => {}
+                previous => edits.push(AnnotationEdit {
+                    page,
+                    id,
+                    before: previous.map(|a| (*a).clone()),
+                    after: Some(annotation.clone()),
+                }),
+            }
+        }
+    }
+    edits
+}
+
+/// Edit adding `annotation` to `page`
+pub fn added(page: u32, annotation: String) -> AnnotationEdit {
+    AnnotationEdit {
+        page,
+        id: annotation_id(&annotation),
+        before: None,
+        after: Some(annotation),
+    }
+}
+
+/// Replace (or with `None`, remove) the existing annotation `id` on `page`
+///
+/// Returns the edit made, or `None` when the page has no such annotation.
+pub fn replace(
+    annotations: &mut PageAnnotations,
+    page: u32,
+    id: &str,
+    value: Option<String>,
+) -> Option<AnnotationEdit> {
+    let before = annotations
+        .get(&page)?
+        .iter()
+        .find(|a| annotation_id(a) == id)?
+        .clone();
+    apply(annotations, page, id, value.as_ref());
+    Some(AnnotationEdit {
+        page,
+        id: id.to_string(),
+        before: Some(before),
+        after: value,
+    })
+}
+
+/// The annotation's `id`, or its whole JSON when it has none
+pub fn annotation_id(annotation: &str) -> String {
+    serde_json::from_str::<serde_json::Value>(annotation)
+        .ok()
+        .and_then(|value| value.get("id")?.as_str().map(str::to_string))
+        .unwrap_or_else(|| annotation.to_string())
+}
+
+/// Set (or with `None`, remove) the annotation `id` on `page`
+fn apply(annotations: &mut PageAnnotations, page: u32, id: &str, value: Option<&String>) {
+    let list = annotations.entry(page).or_default();
+    let position = list.iter().position(|a| annotation_id(a) == id);
+    match (position, value) {
+        (Some(index), Some(value)) => list[index] = value.clone(),
+        (None, Some(value)) => list.push(value.clone()),
+        (Some(index), None) => {
+            list.remove(index);
+        }
+        (None, None) => {}
+    }
+    if list.is_empty() {
+        annotations.remove(&page);
+    }
+}
+
+fn touched_pages(edits: &[AnnotationEdit]) -> Vec<u32> {
+    let mut pages: Vec<u32> = edits.iter().map(|edit| edit.page).collect();
+    pages.sort_unstable();
+    pages.dedup();
+    pages
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn stroke(id: &str, color: &str) -> String {
+        format!(r#"{{"id":"{id}","color":"{color}"}}"#)
+    }
+
+    #[test]
+    fn test_undo_redo() {
+        let mut annotations = PageAnnotations::new();
+        let mut history = AnnotationHistory::default();
+
+        // A remote client draws on page 1, the host on page 2
+        annotations.insert(1, vec![stroke("a", "red")]);
+        history.record(vec![added(1, stroke("a", "red"))]);
+        let before = annotations.clone();
+        annotations.insert(2, vec![stroke("b", "blue")]);
+        history.record(diff(&before, &annotations));
+
+        assert_eq!(history.undo(&mut annotations), Some(vec![2]));
+        assert!(!annotations.contains_key(&2));
+        assert_eq!(history.undo(&mut annotations), Some(vec![1]));
+        assert!(annotations.is_empty());
+        assert_eq!(history.undo(&mut annotations), None);
+
+        assert_eq!(history.redo(&mut annotations), Some(vec![1]));
+        assert_eq!(annotations[&1], vec![stroke("a", "red")]);
+        assert!(history.can_redo());
+
+        // A new edit drops what was left to redo
+        history.record(vec![added(1, stroke("c", "green"))]);
+        assert!(!history.can_redo());
+    }
+
+    #[test]
+    fn test_replace() {
+        let mut annotations = PageAnnotations::from([(1, vec![stroke("a", "red")])]);
+        assert!(replace(&mut annotations, 2, "a", None).is_none());
+        assert!(replace(&mut annotations, 1, "b", None).is_none());
+
+        let edit = replace(&mut annotations, 1, "a", Some(stroke("a", "blue"))).unwrap();
+        assert_eq!(edit.before, Some(stroke("a", "red")));
+        assert_eq!(annotations[&1], vec![stroke("a", "blue")]);
+
+        let edit = replace(&mut annotations, 1, "a", None).unwrap();
+        assert_eq!(edit.after, None);
+        assert!(annotations.is_empty());
+    }
+
+    #[test]
+    fn test_diff() {
+        let before = PageAnnotations::from([(1, vec![stroke("a", "red"), stroke("b", "red")])]);
+        let after = PageAnnotations::from([
+            (1, vec![stroke("a", "blue")]),
+            (3, vec![stroke("c", "red")]),
+        ]);
+        let edits = diff(&before, &after);
+        assert_eq!(edits.len(), 3);
+        assert_eq!((edits[0].id.as_str(), edits[0].after.as_ref()), ("b", None));
+        assert_eq!(edits[1].id, "a");
+        assert_eq!(edits[1].before, Some(stroke("a", "red")));
+        assert_eq!(edits[2].id, "c");
+
+        let mut replayed = before.clone();
+        let mut history = AnnotationHistory::default();
+        history.record(edits);
+        history.undo(&mut replayed);
+        history.redo(&mut replayed);
+        assert_eq!(replayed, after);
+        assert!(diff(&after, &after).is_empty());
+    }
+
+    #[test]
+    fn test_version_log() {
+        let start = Utc::now();
+        let at = |secs| start + chrono::Duration::seconds(secs);
+        let page = |annotations: &[&str]| -> PageAnnotations {
+            HashMap::from([(1, annotations.iter().map(|a| a.to_string()).collect())])
+        };
+        let mut log = VersionLog::default();
+
+        log.snapshot(&page(&["a"]), "load", at(0));
+        log.snapshot(&page(&["a", "b"]), "save", at(5));
+        log.snapshot(&page(&["a", "b", "c"]), "save", at(10));
+        log.snapshot(&page(&["a", "b", "c"]), "save", at(12));
+        log.snapshot(&PageAnnotations::new(), "clear", at(20));
+        log.snapshot(&page(&["d"]), "save", at(25));
+
+        let versions = log.list();
+        let summary: Vec<_> = versions
+            .iter()
+            .map(|v| (v.reason.as_str(), v.count))
+            .collect();
+        assert_eq!(
+            summary,
+            [("save", 1), ("clear", 0), ("save", 3), ("load", 1)]
+        );
+        assert_eq!(log.get(versions[2].id), Some(&page(&["a", "b", "c"])));
+
+        log.clear();
+        assert!(log.list().is_empty());
+        log.snapshot(&page(&["e"]), "load", at(30));
+        assert!(log.list()[0].id > versions[0].id);
+    }
+
+    #[test]
+    fn test_timeline() {
+        let at = |secs| DateTime::from_timestamp(secs, 0).unwrap();
+        let mut annotations = PageAnnotations::new();
+        annotations.insert(1, vec![stroke("a", "red")]);
+        let mut timeline = AnnotationTimeline::default();
+
+        let before = annotations.clone();
+        annotations.insert(1, vec![stroke("a", "blue"), stroke("b", "red")]);
+        timeline.record(&diff(&before, &annotations), at(10));
+        let before = annotations.clone();
+        annotations.remove(&1);
+        timeline.record(&diff(&before, &annotations), at(20));
+
+        let (start, entries) = timeline.replay(&annotations);
+        assert_eq!(start[&1], [stroke("a", "red")]);
+        assert_eq!(entries.len(), 4);
+        assert_eq!(entries[0].edit.after, Some(stroke("a", "blue")));
+        assert_eq!(entries[3].at, at(20));
+
+        // Without a log, markup appears in the order it was created
+        let mut saved = PageAnnotations::new();
+        saved.insert(
+            2,
+            vec![
+                r#"{"id":"late","created":"2025-01-01T10:05:00Z"}"#.to_string(),
+                r#"{"id":"early","created":"2025-01-01T10:00:00Z"}"#.to_string(),
+                r#"{"id":"old","created":""}"#.to_string(),
+            ],
+        );
+        let (start, entries) = timeline_from_created(&saved);
+        assert_eq!(start[&2].len(), 1);
+        let ids: Vec<_> = entries.iter().map(|e| e.edit.id.as_str()).collect();
+        assert_eq!(ids, ["early", "late"]);
+    }
+}
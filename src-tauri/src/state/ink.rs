@@ -0,0 +1,281 @@
+/*
+ * This file is part of StreamSlate.
+ * Copyright (C) 2025 StreamSlate Contributors
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+
+//! Ink stroke cleanup
+//!
+//! A free-draw stroke arrives with every pointer sample, most of them on a
+//! nearly straight line. Before a stroke is stored its points are thinned
+//! with Douglas-Peucker, dropping those within the tolerance of the line
+//! through their neighbours, and optionally resampled along a Catmull-Rom
+//! spline so jittery strokes look clean on the outputs.
+
+use crate::commands::annotations::{Annotation, Point};
+use crate::commands::shapes::AnnotationKind;
+use crate::error::{Result, StreamSlateError};
+use crate::settings::Settings;
+use serde::{Deserialize, Serialize};
+
+/// File name of the settings inside the app data directory
+pub const CONFIG_FILE: &str = "ink.json";
+
+/// Largest accepted simplification tolerance
+pub const MAX_TOLERANCE: f64 = 10.0;
+
+/// Points added between each pair of points when smoothing
+const SMOOTHING_STEPS: usize = 4;
+
+/// How strokes are cleaned up before they are stored
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InkConfig {
+    /// Points closer than this to the simplified stroke are dropped, in
+    /// annotation coordinates; 0 keeps every point
+    pub tolerance: f64,
+    /// Resample the simplified stroke along a Catmull-Rom spline
+    #[serde(default)]
+    pub smooth: bool,
+}
+
+impl Default for InkConfig {
+    fn default() -> Self {
+        Self {
+            tolerance: 0.5,
+            smooth: false,
+        }
+    }
+}
+
+impl InkConfig {
+    /// Check the tolerance is usable
+    pub fn validate(&self) -> Result<()> {
+        if !(0.0..=MAX_TOLERANCE).contains(&self.tolerance) {
+            return Err(StreamSlateError::Other(format!(
+                "Ink tolerance must be between 0 and {MAX_TOLERANCE}"
+            )));
+        }
+        Ok(())
+    }
+
+    /// Clean up the points of a free-draw annotation, returning whether it
+    /// changed
+    pub fn apply(&self, annotation: &mut Annotation) -> bool {
+        if annotation.annotation_type != AnnotationKind::Ink {
+            return false;
+        }
+        let Some(points) = annotation.points.as_deref().filter(|p| p.len() > 2) else {
+            return false;
+        };
+        let mut cleaned = simplify(points, self.tolerance);
+        if self.smooth {
+            cleaned = smooth(&cleaned);
+        }
+        if cleaned.len() == points.len() && !self.smooth {
+            return false;
+        }
+        set_points(annotation, cleaned);
+        true
+    }
+}
+
+impl Settings for InkConfig {
+    fn check(&self) -> Result<()> {
+        self.validate()
+    }
+}
+
+/// Replace a stroke's points, fitting its box to them
+///
+/// Strokes drawn in the app also keep their points as JSON in `content`;
+/// that copy is replaced too.
+pub fn set_points(annotation: &mut Annotation, points: Vec<Point>) {
+    let (mut min_x, mut min_y) = (f64::INFINITY, f64::INFINITY);
+    let (mut max_x, mut max_y) = (f64::NEG_INFINITY, f64::NEG_INFINITY);
+    for p in &points {
+        min_x = min_x.min(p.x);
+        min_y = min_y.min(p.y);
+        max_x = max_x.max(p.x);
+        max_y = max_y.max(p.y);
+    }
+    if !points.is_empty() {
+        annotation.x = min_x;
+        annotation.y = min_y;
+        annotation.width = max_x - min_x;
+        annotation.height = max_y - min_y;
+    }
+    if serde_json::from_str::<Vec<Point>>(&annotation.content).is_ok() {
+        annotation.content = serde_json::to_string(&points).unwrap_or_default();
+    }
+    annotation.points = Some(points);
+}
+
+/// Douglas-Peucker simplification, always keeping the end points
+pub fn simplify(points: &[Point], tolerance: f64) -> Vec<Point> {
+    if points.len() < 3 || tolerance <= 0.0 {
+        return points.to_vec();
+    }
+
+    let mut keep = vec![false; points.len()];
+    keep[0] = true;
+    keep[points.len() - 1] = true;
+    let mut spans = vec![(0, points.len() - 1)];
+    while let Some((first, last)) = spans.pop() {
+        let farthest = (first + 1..last)
+            .map(|i| {
+                (
+                    i,
+                    segment_distance(&points[i], &points[first], &points[last]),
+                )
+            })
+            .max_by(|a, b| a.1.total_cmp(&b.1));
+        if let Some((i, distance)) = farthest {
+            if distance > tolerance {
+                keep[i] = true;
+                spans.push((first, i));
+                spans.push((i, last));
+            }
+        }
+    }
+
+    points
+        .iter()
+        .zip(keep)
+        .filter(|(_, keep)| *keep)
+        .map(|(p, _)| p.clone())
+        .collect()
+}
+
+/// Resample a polyline along a Catmull-Rom spline through its points
+pub fn smooth(points: &[Point]) -> Vec<Point> {
+    if points.len() < 3 {
+        return points.to_vec();
+    }
+
+    let last = points.len() - 1;
+    let mut smoothed = Vec::with_capacity(last * SMOOTHING_STEPS + 1);
+    for i in 0..last {
+        let p0 = &points[i.saturating_sub(1)];
+        let (p1, p2) = (&points[i], &points[i + 1]);
+        let p3 = &points[(i + 2).min(last)];
+        for step in 0..SMOOTHING_STEPS {
+            let t = step as f64 / SMOOTHING_STEPS as f64;
+            // Pen data follows the straight line between the samples
+            smoothed.push(Point {
+                x: catmull_rom(p0.x, p1.x, p2.x, p3.x, t),
+                y: catmull_rom(p0.y, p1.y, p2.y, p3.y, t),
+                ..p1.lerp(p2, t)
+            });
+        }
+    }
+    smoothed.push(points[last].clone());
+    smoothed
+}
+
+/// One coordinate of a uniform Catmull-Rom segment from `p1` to `p2`
+fn catmull_rom(p0: f64, p1: f64, p2: f64, p3: f64, t: f64) -> f64 {
+    let t2 = t * t;
+    let t3 = t2 * t;
+    0.5 * (2.0 * p1
+        + (p2 - p0) * t
+        + (2.0 * p0 - 5.0 * p1 + 4.0 * p2 - p3) * t2
+        + (3.0 * p1 - p0 - 3.0 * p2 + p3) * t3)
+}
+
+/// Distance from `p` to the segment `a`-`b`
+fn segment_distance(p: &Point, a: &Point, b: &Point) -> f64 {
+    let (dx, dy) = (b.x - a.x, b.y - a.y);
+    let length = dx * dx + dy * dy;
+    let t = if length == 0.0 {
+        0.0
+    } else {
+        (((p.x - a.x) * dx + (p.y - a.y) * dy) / length).clamp(0.0, 1.0)
+    };
+    (p.x - a.x - t * dx).hypot(p.y - a.y - t * dy)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn line(coords: &[(f64, f64)]) -> Vec<Point> {
+        coords.iter().map(|&(x, y)| Point::new(x, y)).collect()
+    }
+
+    fn coords(points: &[Point]) -> Vec<(f64, f64)> {
+        points.iter().map(|p| (p.x, p.y)).collect()
+    }
+
+    #[test]
+    fn test_simplify() {
+        // A wobbly straight line with one corner
+        let stroke = line(&[
+            (0.0, 0.0),
+            (1.0, 0.1),
+            (2.0, -0.1),
+            (3.0, 0.0),
+            (4.0, 2.0),
+            (5.0, 4.0),
+        ]);
+        assert_eq!(
+            coords(&simplify(&stroke, 0.5)),
+            [(0.0, 0.0), (3.0, 0.0), (5.0, 4.0)]
+        );
+        assert_eq!(simplify(&stroke, 0.0).len(), stroke.len());
+        assert_eq!(coords(&simplify(&stroke, 100.0)), [(0.0, 0.0), (5.0, 4.0)]);
+    }
+
+    #[test]
+    fn test_smooth() {
+        let stroke = line(&[(0.0, 0.0), (10.0, 10.0), (20.0, 0.0)]);
+        let smoothed = smooth(&stroke);
+        assert_eq!(smoothed.len(), 2 * SMOOTHING_STEPS + 1);
+        // Passes through the original points, rounding the corner between
+        assert_eq!(coords(&smoothed[..1]), [(0.0, 0.0)]);
+        assert_eq!(
+            coords(&smoothed[SMOOTHING_STEPS..=SMOOTHING_STEPS]),
+            [(10.0, 10.0)]
+        );
+        assert_eq!(coords(&smoothed[2 * SMOOTHING_STEPS..]), [(20.0, 0.0)]);
+        assert!(smoothed[SMOOTHING_STEPS - 1].y > 7.5);
+    }
+
+    #[test]
+    fn test_apply() {
+        let points = line(&[(0.0, 0.0), (1.0, 0.1), (2.0, 0.0), (2.0, 3.0)]);
+        let mut stroke: Annotation = serde_json::from_value(serde_json::json!({
+            "id": "s1", "type": "free_draw", "pageNumber": 1, "x": 0, "y": 0,
+            "width": 2, "height": 3, "content": serde_json::to_string(&points).unwrap(),
+            "color": "#000000", "opacity": 1, "created": "", "modified": "",
+            "visible": true, "points": points,
+        }))
+        .unwrap();
+
+        let config = InkConfig::default();
+        assert!(config.apply(&mut stroke));
+        let kept = line(&[(0.0, 0.0), (2.0, 0.0), (2.0, 3.0)]);
+        assert_eq!(coords(stroke.points.as_deref().unwrap()), coords(&kept));
+        assert_eq!(stroke.content, serde_json::to_string(&kept).unwrap());
+        assert!(!config.apply(&mut stroke));
+
+        assert!(InkConfig {
+            tolerance: -1.0,
+            smooth: false
+        }
+        .validate()
+        .is_err());
+    }
+}
@@ -18,30 +18,129 @@
 
 //! Application state management for StreamSlate
 
+pub mod crdt;
+pub mod history;
+pub mod ink;
+pub mod presets;
+
+use crate::ai::{SummarizerConfig, SummaryCache};
+use crate::analytics::{AnalyticsConfig, PageTimer, SessionReport};
+use crate::checkpoint::CheckpointSession;
+#[cfg(any(mobile, feature = "client-sdk"))]
+use crate::client::ClientHandle;
+use crate::deck::ImageDeck;
 use crate::error::{Result, StreamSlateError};
-use crate::websocket::WebSocketEvent;
+use crate::gamepad::{GamepadMapping, GamepadSession};
+use crate::keymap::{ClickerSession, Keymap};
+use crate::media::MediaPlayer;
+use crate::obs::bindings::ObsBinding;
+use crate::obs::{ObsConnection, RecordingSync};
+use crate::pdf::text::PageWord;
+use crate::progress::ProgressStore;
+use crate::websocket::{
+    ClientPreferences, ClientRegistry, PageImages, ReactionBoard, WebSocketEvent,
+};
+use crdt::AnnotationCrdt;
+use history::{AnnotationHistory, AnnotationTimeline, VersionLog};
+use ink::InkConfig;
+use presets::PresetStore;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::sync::atomic::AtomicBool;
 use std::sync::{Arc, Mutex, OnceLock, RwLock};
-use tokio::sync::broadcast;
-
-#[cfg(target_os = "macos")]
+use std::time::{Duration, Instant};
+use tokio::sync::{broadcast, watch};
+
+use crate::capture::agenda::Agenda;
+use crate::capture::laser::LaserPointer;
+use crate::capture::pointer::PointerTap;
+use crate::capture::prerender::PrerenderConfig;
+use crate::capture::qr::QrOverlay;
+use crate::capture::remote_view::RemoteFrames;
+use crate::capture::viewport::ViewportAnimation;
 use crate::capture::CapturedFrame;
 
 /// Trait for frame output destinations (NDI, Syphon, etc.)
-#[cfg(target_os = "macos")]
 pub trait FrameOutput: Send + Sync {
     fn send_frame(&self, frame: &CapturedFrame) -> std::result::Result<(), String>;
     fn stop(&self);
     fn is_running(&self) -> bool;
+    /// Start sending again after `stop` (e.g. once the system wakes)
+    fn resume(&self) -> std::result::Result<(), String>;
 }
 
 /// Holds active output handles for fan-out from the capture loop
-#[cfg(target_os = "macos")]
 #[derive(Default)]
 pub struct OutputState {
     pub ndi_sender: Option<Arc<dyn FrameOutput>>,
     pub syphon_server: Option<Arc<dyn FrameOutput>>,
+    /// Low-rate preview pushed to the webview
+    pub preview: Option<Arc<dyn FrameOutput>>,
+    /// JPEG frames for remote viewers
+    pub remote_view: Option<Arc<dyn FrameOutput>>,
+    /// Frame rate cap for all outputs (thermal/battery throttling)
+    pub fps_limit: Option<u8>,
+    /// Live frames are held back until this instant while a test pattern plays
+    pub test_pattern_until: Option<Instant>,
+    /// Digital pan/zoom applied to every output frame
+    pub viewport: ViewportAnimation,
+    /// Agenda drawn as a progress strip on every output frame, when enabled
+    pub agenda_strip: Option<Agenda>,
+    /// Neighbouring pages the views render ahead of page flips
+    pub prerender: PrerenderConfig,
+    /// Laser pointer drawn on every output frame, when a device drives it
+    pub laser: Option<LaserPointer>,
+    /// Every output frame is sent black while set
+    pub blackout: bool,
+    /// Code word of the open attendance checkpoint, drawn on every frame
+    pub checkpoint_code: Option<String>,
+    /// QR code flashed in the corner of every frame
+    pub qr_code: Option<QrOverlay>,
+    last_frame: Option<Instant>,
+}
+
+impl OutputState {
+    /// Whether a frame arriving at `now` should be sent under `fps_limit`
+    pub fn take_frame_slot(&mut self, now: Instant) -> bool {
+        if let (Some(limit), Some(last)) = (self.fps_limit, self.last_frame) {
+            // 10% slack so capture jitter doesn't halve the rate
+            let interval = Duration::from_secs_f64(0.9 / f64::from(limit.max(1)));
+            if now.saturating_duration_since(last) < interval {
+                return false;
+            }
+        }
+        self.last_frame = Some(now);
+        true
+    }
+
+    /// Whether a test pattern owns the outputs at `now`
+    pub fn test_pattern_active(&self, now: Instant) -> bool {
+        self.test_pattern_until.is_some_and(|until| now < until)
+    }
+
+    /// All registered outputs
+    pub fn iter(&self) -> impl Iterator<Item = &Arc<dyn FrameOutput>> {
+        [
+            &self.ndi_sender,
+            &self.syphon_server,
+            &self.preview,
+            &self.remote_view,
+        ]
+        .into_iter()
+        .flatten()
+    }
+}
+
+/// Kind of paged document currently loaded
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DocumentKind {
+    #[default]
+    Pdf,
+    /// A folder of PNG/JPEG images, one per page
+    ImageDeck,
+    /// A PDF generated by an importer (e.g. Markdown slides)
+    Imported,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -51,6 +150,70 @@ pub struct PdfState {
     pub total_pages: u32,
     pub zoom_level: f64,
     pub is_loaded: bool,
+    #[serde(default)]
+    pub document_kind: DocumentKind,
+    /// Region of every page shown to the audience, if cropped
+    #[serde(default)]
+    pub crop: Option<CropRect>,
+    /// Invert luminance while preserving hue (smart dark mode)
+    #[serde(default)]
+    pub smart_invert: bool,
+    /// Second document paged in step with this one (e.g. a translation)
+    #[serde(default)]
+    pub linked: Option<LinkedDocument>,
+}
+
+/// A document shown beside the open one, following its paging
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LinkedDocument {
+    pub path: String,
+    pub total_pages: u32,
+    /// Added to the open document's page to get this document's page
+    pub offset: i32,
+    pub current_page: u32,
+}
+
+impl LinkedDocument {
+    /// Page of this document shown alongside `page` of the open one
+    pub fn page_for(&self, page: u32) -> u32 {
+        let page = i64::from(page) + i64::from(self.offset);
+        page.clamp(1, i64::from(self.total_pages.max(1))) as u32
+    }
+}
+
+/// Page region in annotation coordinates (PDF points from the top-left of
+/// the unrotated page; pixels for image decks)
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct CropRect {
+    pub x: f64,
+    pub y: f64,
+    pub width: f64,
+    pub height: f64,
+}
+
+impl CropRect {
+    /// Whether the rectangle is finite, non-negative and non-empty
+    pub fn is_valid(&self) -> bool {
+        [self.x, self.y, self.width, self.height]
+            .iter()
+            .all(|v| v.is_finite())
+            && self.x >= 0.0
+            && self.y >= 0.0
+            && self.width > 0.0
+            && self.height > 0.0
+    }
+
+    /// Intersect with a page of the given size; `None` if nothing remains
+    pub fn clamp_to(&self, page_width: f64, page_height: f64) -> Option<CropRect> {
+        let right = (self.x + self.width).min(page_width);
+        let bottom = (self.y + self.height).min(page_height);
+        (right > self.x && bottom > self.y).then_some(CropRect {
+            x: self.x,
+            y: self.y,
+            width: right - self.x,
+            height: bottom - self.y,
+        })
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
@@ -84,8 +247,86 @@ pub struct WindowSize {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct WebSocketState {
     pub is_connected: bool,
+    /// Whether the server is accepting connections
+    #[serde(default)]
+    pub listening: bool,
+    /// Port the server listens on, which may be a fallback port
     pub port: u16,
+    /// Open client connections
     pub active_connections: u32,
+    /// Broadcasts dropped for clients that read too slowly, since the app
+    /// started
+    #[serde(default)]
+    pub dropped_events: u64,
+}
+
+/// Outgoing connection to another StreamSlate instance (remote client mode)
+#[cfg(any(mobile, feature = "client-sdk"))]
+#[derive(Debug)]
+pub struct RemoteConnection {
+    pub url: String,
+    pub handle: ClientHandle,
+}
+
+/// Without the client there is never a remote connection
+#[cfg(not(any(mobile, feature = "client-sdk")))]
+#[derive(Debug)]
+pub enum RemoteConnection {}
+
+/// OBS connection and the cues bound to it
+#[derive(Debug, Default)]
+pub struct ObsState {
+    pub connection: Option<ObsConnection>,
+    pub bindings: Vec<ObsBinding>,
+    /// Last page seen, so repeated page events don't re-fire bindings
+    pub last_page: Option<u32>,
+    /// Timing of the recording in progress
+    pub recording: Option<RecordingSync>,
+}
+
+/// Page timing destinations and the session being timed
+#[derive(Debug, Default)]
+pub struct AnalyticsState {
+    pub config: AnalyticsConfig,
+    pub session: Option<PageTimer>,
+    /// Report of the most recently finished session, kept for exports
+    pub last_report: Option<SessionReport>,
+}
+
+/// Summarization endpoint and the open document's cached page summaries
+#[derive(Debug, Default)]
+pub struct SummaryState {
+    pub config: SummarizerConfig,
+    pub cache: Option<SummaryCache>,
+}
+
+/// Gamepad button mapping and the controller in use
+#[derive(Debug, Default)]
+pub struct GamepadState {
+    pub mapping: GamepadMapping,
+    pub session: Option<GamepadSession>,
+}
+
+/// Clicker keymap and the clicker in use
+#[derive(Debug, Default)]
+pub struct ClickerState {
+    pub keymap: Keymap,
+    pub session: Option<ClickerSession>,
+}
+
+/// Attendance checkpoints of this app session
+#[derive(Debug, Default)]
+pub struct CheckpointState {
+    /// Created when the first checkpoint opens
+    pub session: Option<CheckpointSession>,
+}
+
+/// Stored reading progress and the document it is being saved for
+#[derive(Debug, Default)]
+pub struct ProgressState {
+    pub store: ProgressStore,
+    /// Path and content hash of the open document
+    pub current: Option<(String, String)>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
@@ -100,6 +341,10 @@ pub struct IntegrationState {
     pub frames_captured: u64,
     /// Number of frames sent to NDI/Syphon output
     pub frames_sent: u64,
+    /// Lower the output frame rate when thermal or battery state is critical
+    pub auto_throttle: bool,
+    /// Convert captured frames from the display's color profile to sRGB
+    pub color_correction: bool,
 }
 
 /// Main application state
@@ -118,6 +363,12 @@ pub struct AppState {
     /// This is stored separately because lopdf::Document doesn't impl Serialize
     pub pdf_document: Arc<RwLock<Option<lopdf::Document>>>,
 
+    /// Cancellation flag of the `open_pdf` in progress, if any
+    pub pdf_open: Arc<Mutex<Option<Arc<AtomicBool>>>>,
+
+    /// The loaded image deck, when the current document is a folder of images
+    pub image_deck: Arc<RwLock<Option<ImageDeck>>>,
+
     /// Presenter window state
     pub presenter: Arc<RwLock<PresenterState>>,
 
@@ -130,13 +381,89 @@ pub struct AppState {
     /// Annotations per page (page_number -> list of annotation JSON strings)
     pub annotations: Arc<RwLock<HashMap<u32, Vec<String>>>>,
 
+    /// Undo/redo log of annotation changes from all clients
+    pub annotation_history: Arc<Mutex<AnnotationHistory>>,
+
+    /// Restore points of the open document's annotations
+    pub annotation_versions: Arc<Mutex<VersionLog>>,
+
+    /// Timestamped log of annotation changes, for replay
+    pub annotation_timeline: Arc<Mutex<AnnotationTimeline>>,
+
+    /// Cancel flag of the annotation replay in progress
+    pub annotation_replay: Arc<Mutex<Option<Arc<AtomicBool>>>>,
+
+    /// Edit stamps for merging concurrent annotation edits
+    pub annotation_crdt: Arc<Mutex<AnnotationCrdt>>,
+
+    /// Named annotation styles and the one applied to new annotations
+    pub style_presets: Arc<RwLock<PresetStore>>,
+
+    /// How free-draw strokes are simplified before they are stored
+    pub ink_config: Arc<RwLock<InkConfig>>,
+
+    /// Whether new annotations are kept only until the document is closed
+    pub ephemeral_annotations: Arc<AtomicBool>,
+
+    /// OCR word boxes for image-only pages of the current document
+    pub ocr_words: Arc<RwLock<HashMap<u32, Vec<PageWord>>>>,
+
+    /// Preferences of remote clients, keyed by stable client ID
+    pub client_preferences: Arc<RwLock<HashMap<String, ClientPreferences>>>,
+
+    /// Outgoing remote client connection, if this instance controls another
+    pub remote_client: Arc<Mutex<Option<RemoteConnection>>>,
+
+    /// OBS connection and source visibility bindings
+    pub obs: Arc<Mutex<ObsState>>,
+
+    /// Page timing analytics
+    pub analytics: Arc<Mutex<AnalyticsState>>,
+
+    /// Slide summarization settings and cached summaries
+    pub summaries: Arc<Mutex<SummaryState>>,
+
+    /// Per-document reading progress
+    pub reading_progress: Arc<Mutex<ProgressState>>,
+
+    /// Live audience reactions per page
+    pub reactions: Arc<Mutex<ReactionBoard>>,
+
+    /// Open WebSocket connections
+    pub ws_clients: Arc<Mutex<ClientRegistry>>,
+
+    /// Page images rendered by the webview for remote monitors
+    pub page_images: Arc<PageImages>,
+
+    /// Play/pause/seek state of the open document's video clips
+    pub media: Arc<Mutex<MediaPlayer>>,
+
     /// WebSocket broadcast sender (for sending events from commands).
     /// Set once during app setup; lock-free reads via OnceLock.
     pub broadcast_sender: Arc<OnceLock<broadcast::Sender<WebSocketEvent>>>,
 
     /// Active output handles (NDI, Syphon) for the capture fan-out
-    #[cfg(target_os = "macos")]
     pub outputs: Arc<Mutex<OutputState>>,
+
+    /// Latest low-bandwidth remote view frame, watched by WebSocket and
+    /// HTTP viewers
+    pub remote_view: Arc<RemoteFrames>,
+
+    /// Pointing device grabbed to drive the laser pointer
+    pub pointer_tap: Arc<Mutex<Option<PointerTap>>>,
+
+    /// Game controller driving navigation
+    pub gamepad: Arc<Mutex<GamepadState>>,
+
+    /// Presentation clicker grabbed system-wide
+    pub clicker: Arc<Mutex<ClickerState>>,
+
+    /// Attendance checkpoints and their responses
+    pub checkpoints: Arc<Mutex<CheckpointState>>,
+
+    /// Held shared by each remote command and exclusively by a `BATCH`, so
+    /// no other command runs between a batch's commands
+    pub command_lock: Arc<RwLock<()>>,
 }
 
 // Manual Debug impl since lopdf::Document doesn't implement Debug
@@ -145,12 +472,39 @@ impl std::fmt::Debug for AppState {
         f.debug_struct("AppState")
             .field("pdf", &self.pdf)
             .field("pdf_document", &"<lopdf::Document>")
+            .field("pdf_open", &self.pdf_open)
+            .field("image_deck", &self.image_deck)
             .field("presenter", &self.presenter)
             .field("websocket", &self.websocket)
             .field("integration", &self.integration)
             .field("annotations", &self.annotations)
+            .field("annotation_history", &self.annotation_history)
+            .field("annotation_versions", &self.annotation_versions)
+            .field("annotation_timeline", &self.annotation_timeline)
+            .field("annotation_replay", &self.annotation_replay)
+            .field("annotation_crdt", &self.annotation_crdt)
+            .field("style_presets", &self.style_presets)
+            .field("ink_config", &self.ink_config)
+            .field("ephemeral_annotations", &self.ephemeral_annotations)
+            .field("ocr_words", &self.ocr_words)
+            .field("client_preferences", &self.client_preferences)
+            .field("remote_client", &self.remote_client)
+            .field("obs", &self.obs)
+            .field("analytics", &self.analytics)
+            .field("summaries", &self.summaries)
+            .field("reading_progress", &self.reading_progress)
+            .field("reactions", &self.reactions)
+            .field("ws_clients", &self.ws_clients)
+            .field("page_images", &"<PageImages>")
+            .field("media", &self.media)
             .field("broadcast_sender", &"<broadcast::Sender>")
             .field("outputs", &"<OutputState>")
+            .field("remote_view", &"<watch::Sender>")
+            .field("pointer_tap", &self.pointer_tap)
+            .field("gamepad", &self.gamepad)
+            .field("clicker", &self.clicker)
+            .field("checkpoints", &self.checkpoints)
+            .field("command_lock", &self.command_lock)
             .finish()
     }
 }
@@ -163,6 +517,10 @@ impl Default for PdfState {
             total_pages: 0,
             zoom_level: 1.0,
             is_loaded: false,
+            document_kind: DocumentKind::Pdf,
+            crop: None,
+            smart_invert: false,
+            linked: None,
         }
     }
 }
@@ -186,8 +544,10 @@ impl Default for WebSocketState {
     fn default() -> Self {
         Self {
             is_connected: false,
+            listening: false,
             port: 11451,
             active_connections: 0,
+            dropped_events: 0,
         }
     }
 }
@@ -197,13 +557,39 @@ impl AppState {
         Self {
             pdf: Arc::new(RwLock::new(PdfState::default())),
             pdf_document: Arc::new(RwLock::new(None)),
+            pdf_open: Arc::new(Mutex::new(None)),
+            image_deck: Arc::new(RwLock::new(None)),
             presenter: Arc::new(RwLock::new(PresenterState::default())),
             websocket: Arc::new(RwLock::new(WebSocketState::default())),
             integration: Arc::new(Mutex::new(IntegrationState::default())),
             annotations: Arc::new(RwLock::new(HashMap::new())),
+            annotation_history: Arc::new(Mutex::new(AnnotationHistory::default())),
+            annotation_versions: Arc::new(Mutex::new(VersionLog::default())),
+            annotation_timeline: Arc::new(Mutex::new(AnnotationTimeline::default())),
+            annotation_replay: Arc::new(Mutex::new(None)),
+            annotation_crdt: Arc::new(Mutex::new(AnnotationCrdt::default())),
+            style_presets: Arc::new(RwLock::new(PresetStore::default())),
+            ink_config: Arc::new(RwLock::new(InkConfig::default())),
+            ephemeral_annotations: Arc::new(AtomicBool::new(false)),
+            ocr_words: Arc::new(RwLock::new(HashMap::new())),
+            client_preferences: Arc::new(RwLock::new(HashMap::new())),
+            remote_client: Arc::new(Mutex::new(None)),
+            obs: Arc::new(Mutex::new(ObsState::default())),
+            analytics: Arc::new(Mutex::new(AnalyticsState::default())),
+            summaries: Arc::new(Mutex::new(SummaryState::default())),
+            reading_progress: Arc::new(Mutex::new(ProgressState::default())),
+            reactions: Arc::new(Mutex::new(ReactionBoard::default())),
+            ws_clients: Arc::new(Mutex::new(ClientRegistry::default())),
+            page_images: Arc::new(PageImages::default()),
+            media: Arc::new(Mutex::new(MediaPlayer::default())),
             broadcast_sender: Arc::new(OnceLock::new()),
-            #[cfg(target_os = "macos")]
             outputs: Arc::new(Mutex::new(OutputState::default())),
+            remote_view: Arc::new(watch::channel(None).0),
+            pointer_tap: Arc::new(Mutex::new(None)),
+            gamepad: Arc::new(Mutex::new(GamepadState::default())),
+            clicker: Arc::new(Mutex::new(ClickerState::default())),
+            checkpoints: Arc::new(Mutex::new(CheckpointState::default())),
+            command_lock: Arc::new(RwLock::new(())),
         }
     }
 
@@ -235,12 +621,54 @@ impl AppState {
     }
 
     /// Set the loaded PDF document
+    ///
+    /// OCR results belong to the previous document and are discarded.
     pub fn set_pdf_document(&self, doc: Option<lopdf::Document>) -> Result<()> {
         let mut guard = self
             .pdf_document
             .write()
             .map_err(|e| StreamSlateError::StateLock(format!("PDF document: {e}")))?;
         *guard = doc;
+        self.ocr_words
+            .write()
+            .map_err(|e| StreamSlateError::StateLock(format!("OCR words: {e}")))?
+            .clear();
+        Ok(())
+    }
+
+    /// Get cached OCR words for a page
+    pub fn get_ocr_words(&self, page: u32) -> Result<Option<Vec<PageWord>>> {
+        self.ocr_words
+            .read()
+            .map(|words| words.get(&page).cloned())
+            .map_err(|e| StreamSlateError::StateLock(format!("OCR words: {e}")))
+    }
+
+    /// Cache OCR words for a page
+    pub fn set_ocr_words(&self, page: u32, words: Vec<PageWord>) -> Result<()> {
+        self.ocr_words
+            .write()
+            .map(|mut map| {
+                map.insert(page, words);
+            })
+            .map_err(|e| StreamSlateError::StateLock(format!("OCR words: {e}")))
+    }
+
+    /// Get the loaded image deck
+    pub fn get_image_deck(&self) -> Result<Option<ImageDeck>> {
+        self.image_deck
+            .read()
+            .map(|deck| deck.clone())
+            .map_err(|e| StreamSlateError::StateLock(format!("Image deck: {e}")))
+    }
+
+    /// Set the loaded image deck
+    pub fn set_image_deck(&self, deck: Option<ImageDeck>) -> Result<()> {
+        let mut guard = self
+            .image_deck
+            .write()
+            .map_err(|e| StreamSlateError::StateLock(format!("Image deck: {e}")))?;
+        *guard = deck;
         Ok(())
     }
 
@@ -264,7 +692,6 @@ impl AppState {
     }
 
     /// Get WebSocket state
-    #[allow(dead_code)]
     pub fn get_websocket_state(&self) -> Result<WebSocketState> {
         self.websocket
             .read()
@@ -281,6 +708,28 @@ impl AppState {
             .map_err(|e| StreamSlateError::StateLock(format!("Integration state: {e}")))
     }
 
+    /// Get the stored preferences for a client, if any
+    pub fn get_client_preferences(&self, client_id: &str) -> Result<Option<ClientPreferences>> {
+        self.client_preferences
+            .read()
+            .map(|prefs| prefs.get(client_id).cloned())
+            .map_err(|e| StreamSlateError::StateLock(format!("Client preferences: {e}")))
+    }
+
+    /// Store preferences for a client, replacing any previous value
+    pub fn set_client_preferences(
+        &self,
+        client_id: &str,
+        preferences: ClientPreferences,
+    ) -> Result<()> {
+        self.client_preferences
+            .write()
+            .map(|mut prefs| {
+                prefs.insert(client_id.to_string(), preferences);
+            })
+            .map_err(|e| StreamSlateError::StateLock(format!("Client preferences: {e}")))
+    }
+
     /// Set the broadcast sender for WebSocket events (called once during setup)
     pub fn set_broadcast_sender(&self, sender: broadcast::Sender<WebSocketEvent>) -> Result<()> {
         self.broadcast_sender.set(sender).map_err(|_| {
@@ -297,6 +746,79 @@ impl AppState {
         Ok(())
     }
 
+    /// Forget live reactions, which belong to the previous document's pages
+    pub fn clear_reactions(&self) {
+        if let Ok(mut reactions) = self.reactions.lock() {
+            reactions.clear();
+        }
+    }
+
+    /// Record an annotation change made by the host, stamping it for
+    /// merging and keeping it for undo
+    pub fn record_annotation_edits(&self, edits: Vec<history::AnnotationEdit>) {
+        if let Ok(mut crdt) = self.annotation_crdt.lock() {
+            crdt.record_local(&edits);
+        }
+        self.record_merged_annotation_edits(edits);
+    }
+
+    /// Record an annotation change already merged and stamped, for undo
+    pub fn record_merged_annotation_edits(&self, edits: Vec<history::AnnotationEdit>) {
+        self.log_annotation_edits(&edits);
+        if let Ok(mut history) = self.annotation_history.lock() {
+            history.record(edits);
+        }
+    }
+
+    /// Add an annotation change to the replay timeline
+    pub fn log_annotation_edits(&self, edits: &[history::AnnotationEdit]) {
+        if let Ok(mut timeline) = self.annotation_timeline.lock() {
+            timeline.record(edits, chrono::Utc::now());
+        }
+    }
+
+    /// Current annotation edit clock
+    pub fn annotation_clock(&self) -> u64 {
+        self.annotation_crdt
+            .lock()
+            .map(|crdt| crdt.clock())
+            .unwrap_or_default()
+    }
+
+    /// Keep the current annotations as a restore point
+    pub fn snapshot_annotations(&self, reason: &str) {
+        let Ok(annotations) = self.annotations.read() else {
+            return;
+        };
+        if let Ok(mut versions) = self.annotation_versions.lock() {
+            versions.snapshot(&annotations, reason, chrono::Utc::now());
+        }
+    }
+
+    /// Forget annotation undo history, restore points and the replay
+    /// timeline, which belong to the previous document
+    pub fn clear_annotation_history(&self) {
+        if let Ok(mut history) = self.annotation_history.lock() {
+            history.clear();
+        }
+        if let Ok(mut versions) = self.annotation_versions.lock() {
+            versions.clear();
+        }
+        if let Ok(mut crdt) = self.annotation_crdt.lock() {
+            crdt.clear();
+        }
+        if let Ok(mut timeline) = self.annotation_timeline.lock() {
+            timeline.clear();
+        }
+    }
+
+    /// Forget media playback state, which belongs to the previous document
+    pub fn clear_media(&self) {
+        if let Ok(mut media) = self.media.lock() {
+            media.clear();
+        }
+    }
+
     /// Increment the frames captured counter
     pub fn increment_frames_captured(&self) -> Result<()> {
         let mut integration = self
@@ -334,3 +856,51 @@ impl Default for AppState {
         Self::new()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_frame_slot_throttling() {
+        let mut outputs = OutputState::default();
+        let start = Instant::now();
+        assert!(outputs.take_frame_slot(start));
+        assert!(outputs.take_frame_slot(start + Duration::from_millis(33)));
+
+        outputs.fps_limit = Some(10);
+        assert!(!outputs.take_frame_slot(start + Duration::from_millis(66)));
+        assert!(outputs.take_frame_slot(start + Duration::from_millis(133)));
+    }
+
+    #[test]
+    fn test_test_pattern_window() {
+        let mut outputs = OutputState::default();
+        let start = Instant::now();
+        assert!(!outputs.test_pattern_active(start));
+
+        outputs.test_pattern_until = Some(start + Duration::from_secs(5));
+        assert!(outputs.test_pattern_active(start + Duration::from_secs(4)));
+        assert!(!outputs.test_pattern_active(start + Duration::from_secs(5)));
+    }
+
+    #[test]
+    fn test_linked_page_follows_offset() {
+        let linked = LinkedDocument {
+            path: "translation.pdf".to_string(),
+            total_pages: 10,
+            offset: -2,
+            current_page: 1,
+        };
+        assert_eq!(linked.page_for(5), 3);
+        assert_eq!(linked.page_for(1), 1);
+        assert_eq!(
+            LinkedDocument {
+                offset: 4,
+                ..linked
+            }
+            .page_for(9),
+            10
+        );
+    }
+}
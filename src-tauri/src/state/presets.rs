@@ -0,0 +1,192 @@
+/*
+ * This file is part of StreamSlate.
+ * Copyright (C) 2025 StreamSlate Contributors
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+
+//! Annotation style presets
+//!
+//! Named pen styles (color, opacity, stroke width, font size) kept in the app
+//! data directory. One preset can be active; its style is applied to every
+//! annotation added afterwards, so a Stream Deck key can switch pens for all
+//! connected clients at once.
+
+use crate::commands::shapes::{normalize_color, AnnotationKind};
+use crate::error::{Result, StreamSlateError};
+use crate::settings::Settings;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+/// File name of the preset store inside the app data directory
+pub const STORE_FILE: &str = "style_presets.json";
+
+/// A named pen style
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct StylePreset {
+    /// Assigned when the preset is created
+    #[serde(default)]
+    pub id: String,
+    pub name: String,
+    /// `#rrggbb`
+    pub color: String,
+    pub opacity: f64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub stroke_width: Option<f64>,
+    /// Font size for text annotations
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub font_size: Option<f64>,
+}
+
+impl StylePreset {
+    /// Check the style, trimming the name and normalizing the color
+    pub fn normalize(&mut self) -> Result<()> {
+        let invalid =
+            |message: &str| Err(StreamSlateError::Other(format!("Style preset {message}")));
+        self.name = self.name.trim().to_string();
+        if self.name.is_empty() {
+            return invalid("needs a name");
+        }
+        match normalize_color(&self.color) {
+            Some(color) => self.color = color,
+            None => return invalid("color must be #rgb or #rrggbb"),
+        }
+        if !(0.0..=1.0).contains(&self.opacity) {
+            return invalid("opacity must be between 0 and 1");
+        }
+        let positive = |value: Option<f64>| value.map_or(true, |v| v.is_finite() && v > 0.0);
+        if !positive(self.stroke_width) || !positive(self.font_size) {
+            return invalid("sizes must be positive");
+        }
+        Ok(())
+    }
+
+    /// Give an annotation sent as JSON this style
+    ///
+    /// Images keep their own look; stroke width and font size are only set
+    /// when the preset has them.
+    pub fn apply(&self, annotation: &mut Map<String, Value>) {
+        let kind = annotation
+            .get("type")
+            .and_then(|t| AnnotationKind::deserialize(t).ok());
+        if kind == Some(AnnotationKind::Image) {
+            return;
+        }
+        annotation.insert("color".to_string(), self.color.clone().into());
+        annotation.insert("opacity".to_string(), self.opacity.into());
+        if let Some(width) = self.stroke_width {
+            annotation.insert("strokeWidth".to_string(), width.into());
+        }
+        if let Some(size) = self
+            .font_size
+            .filter(|_| kind == Some(AnnotationKind::Text))
+        {
+            annotation.insert("fontSize".to_string(), size.into());
+        }
+    }
+}
+
+/// Every preset and which one is active
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct PresetStore {
+    #[serde(default)]
+    pub presets: Vec<StylePreset>,
+    /// ID of the preset applied to new annotations
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub active: Option<String>,
+}
+
+impl PresetStore {
+    /// The active preset, if any
+    pub fn active_preset(&self) -> Option<&StylePreset> {
+        let id = self.active.as_deref()?;
+        self.presets.iter().find(|preset| preset.id == id)
+    }
+
+    /// Position of preset `id`
+    pub fn index(&self, id: &str) -> Result<usize> {
+        self.presets
+            .iter()
+            .position(|preset| preset.id == id)
+            .ok_or_else(|| StreamSlateError::Other(format!("No style preset with id {id}")))
+    }
+}
+
+impl Settings for PresetStore {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn preset() -> StylePreset {
+        serde_json::from_value(serde_json::json!({
+            "name": " Red marker ", "color": "#F00", "opacity": 0.8,
+            "strokeWidth": 4, "fontSize": 18
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_normalize_preset() {
+        let mut red = preset();
+        red.normalize().unwrap();
+        assert_eq!(red.name, "Red marker");
+        assert_eq!(red.color, "#ff0000");
+
+        for change in [
+            |p: &mut StylePreset| p.name = "  ".to_string(),
+            |p: &mut StylePreset| p.color = "red".to_string(),
+            |p: &mut StylePreset| p.opacity = 1.5,
+            |p: &mut StylePreset| p.stroke_width = Some(0.0),
+        ] {
+            let mut bad = preset();
+            change(&mut bad);
+            assert!(bad.normalize().is_err());
+        }
+    }
+
+    #[test]
+    fn test_apply_preset() {
+        let red = preset();
+        let mut ink = serde_json::json!({ "type": "ink", "color": "#000000", "opacity": 1 });
+        red.apply(ink.as_object_mut().unwrap());
+        assert_eq!(ink["color"], "#F00");
+        assert_eq!(ink["opacity"], 0.8);
+        assert_eq!(ink["strokeWidth"], 4.0);
+        assert!(ink.get("fontSize").is_none());
+
+        let mut text = serde_json::json!({ "type": "text" });
+        red.apply(text.as_object_mut().unwrap());
+        assert_eq!(text["fontSize"], 18.0);
+
+        let mut image = serde_json::json!({ "type": "image", "opacity": 1 });
+        red.apply(image.as_object_mut().unwrap());
+        assert_eq!(image["opacity"], 1);
+    }
+
+    #[test]
+    fn test_active_preset() {
+        let mut store = PresetStore::default();
+        let mut red = preset();
+        red.id = "red".to_string();
+        store.presets.push(red);
+        assert!(store.active_preset().is_none());
+        store.active = Some("red".to_string());
+        assert_eq!(store.active_preset().unwrap().name, " Red marker ");
+        assert_eq!(store.index("red").unwrap(), 0);
+        assert!(store.index("blue").is_err());
+    }
+}
@@ -105,6 +105,12 @@ impl FrameOutput for SyphonServer {
     fn is_running(&self) -> bool {
         self.is_running.load(Ordering::SeqCst)
     }
+
+    fn resume(&self) -> Result<(), String> {
+        self.is_running.store(true, Ordering::SeqCst);
+        info!("Syphon server '{}' resumed", self.name);
+        Ok(())
+    }
 }
 
 impl Drop for SyphonServer {
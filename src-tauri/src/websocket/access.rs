@@ -0,0 +1,256 @@
+/*
+ * This file is part of StreamSlate.
+ * Copyright (C) 2025 StreamSlate Contributors
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+
+//! Client roles
+//!
+//! Each connection has a role: viewers only read state and follow events,
+//! controllers also drive the presentation and edit annotations, and admins
+//! may end the show. New connections get the default role, which is admin
+//! so existing setups keep working; lowering it (e.g. for a public overlay
+//! page) makes controllers present a token in `HELLO`. The operator can also
+//! change a connected client's role directly.
+
+use crate::error::{Result, StreamSlateError};
+use crate::protocol::WebSocketCommand;
+use crate::settings::{load_settings, Settings};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::sync::{OnceLock, RwLock};
+use tauri::AppHandle;
+
+/// File name of the settings inside the app data directory
+pub const CONFIG_FILE: &str = "websocket_access.json";
+
+/// Shortest accepted token
+const MIN_TOKEN_LEN: usize = 8;
+
+/// Access settings in use
+static CONFIG: OnceLock<RwLock<AccessConfig>> = OnceLock::new();
+
+/// What a client may do, from least to most
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize, JsonSchema,
+)]
+#[serde(rename_all = "lowercase")]
+pub enum Role {
+    /// Read state and receive events
+    Viewer,
+    /// Also navigate, control outputs and edit annotations
+    Controller,
+    /// Also end the show
+    Admin,
+}
+
+impl Role {
+    /// Name as used in the protocol
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Role::Viewer => "viewer",
+            Role::Controller => "controller",
+            Role::Admin => "admin",
+        }
+    }
+}
+
+/// A token clients present in `HELLO` to get a role
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AccessToken {
+    /// What the token is for, e.g. "Stream Deck"
+    #[serde(default)]
+    pub label: String,
+    pub token: String,
+    pub role: Role,
+}
+
+/// Roles given to connecting clients
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AccessConfig {
+    /// Role of clients that haven't presented a token
+    #[serde(default = "default_role")]
+    pub default_role: Role,
+    #[serde(default)]
+    pub tokens: Vec<AccessToken>,
+}
+
+impl Default for AccessConfig {
+    fn default() -> Self {
+        Self {
+            default_role: default_role(),
+            tokens: Vec::new(),
+        }
+    }
+}
+
+impl AccessConfig {
+    /// Check tokens are long enough and distinct
+    pub fn validate(&self) -> Result<()> {
+        for (i, token) in self.tokens.iter().enumerate() {
+            if token.token.chars().count() < MIN_TOKEN_LEN {
+                return Err(StreamSlateError::Other(format!(
+                    "Access tokens must be at least {MIN_TOKEN_LEN} characters"
+                )));
+            }
+            if self.tokens[..i].iter().any(|t| t.token == token.token) {
+                return Err(StreamSlateError::Other(
+                    "Access tokens must be distinct".to_string(),
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    /// The role a token grants, if it's known
+    pub fn role_for(&self, token: &str) -> Option<Role> {
+        self.tokens
+            .iter()
+            .find(|t| t.token == token)
+            .map(|t| t.role)
+    }
+}
+
+impl Settings for AccessConfig {
+    /// Unreadable settings give every client the viewer role rather than
+    /// full control
+    fn unreadable() -> Self {
+        Self {
+            default_role: Role::Viewer,
+            tokens: Vec::new(),
+        }
+    }
+}
+
+fn default_role() -> Role {
+    Role::Admin
+}
+
+/// The role a client needs to send `command`
+pub fn required_role(command: &WebSocketCommand) -> Role {
+    use WebSocketCommand as C;
+    match command {
+        C::GetState
+        | C::Ping
+        | C::Hello { .. }
+        | C::Identify { .. }
+        | C::SetPreferences { .. }
+        | C::GetPageWords { .. }
+        | C::GetAgenda
+        | C::GetMedia
+        | C::GetLayers
+        | C::GetNotes { .. }
+        | C::SetRemoteView { .. }
+        | C::SearchAnnotations { .. }
+        | C::GetStylePresets
+        | C::Subscribe { .. }
+        | C::Resume { .. }
+        | C::GetPageImage { .. }
+        | C::SetPageImages { .. }
+        | C::AddReaction { .. } => Role::Viewer,
+        C::NextPage
+        | C::PreviousPage
+        | C::GoToPage { .. }
+        | C::SetZoom { .. }
+        | C::TogglePresenter
+        | C::AddAnnotation { .. }
+        | C::UpdateAnnotation { .. }
+        | C::DeleteAnnotation { .. }
+        | C::ClearAnnotations
+        | C::UndoAnnotation
+        | C::RedoAnnotation
+        | C::EraseAt { .. }
+        | C::NextSection
+        | C::PreviousSection
+        | C::SetSmartInvert { .. }
+        | C::SetBlackout { .. }
+        | C::SetOutputViewport { .. }
+        | C::PlayMedia { .. }
+        | C::PauseMedia { .. }
+        | C::SeekMedia { .. }
+        | C::ShowQr { .. }
+        | C::HideQr
+        | C::SetLayerVisible { .. }
+        | C::PointerMoved { .. }
+        | C::SetActiveStylePreset { .. } => Role::Controller,
+        C::EndShow { .. } => Role::Admin,
+        C::Batch { commands } => commands
+            .iter()
+            .map(required_role)
+            .max()
+            .unwrap_or(Role::Viewer),
+    }
+}
+
+/// The access settings in use
+pub fn access() -> &'static RwLock<AccessConfig> {
+    CONFIG.get_or_init(|| RwLock::new(AccessConfig::default()))
+}
+
+/// Role of a client that hasn't presented a token
+pub fn default_client_role() -> Role {
+    access()
+        .read()
+        .map(|config| config.default_role)
+        .unwrap_or(Role::Viewer)
+}
+
+/// Restore the stored access settings
+pub fn load_access_config(app_handle: &AppHandle) {
+    let config = load_settings(app_handle, CONFIG_FILE);
+    if let Ok(mut current) = access().write() {
+        *current = config;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_roles() {
+        assert!(Role::Viewer < Role::Controller && Role::Controller < Role::Admin);
+        assert_eq!(required_role(&WebSocketCommand::GetState), Role::Viewer);
+        assert_eq!(
+            required_role(&WebSocketCommand::GoToPage { page: 3 }),
+            Role::Controller
+        );
+        assert_eq!(
+            required_role(&WebSocketCommand::ClearAnnotations),
+            Role::Controller
+        );
+        let batch = WebSocketCommand::Batch {
+            commands: vec![WebSocketCommand::GetState, WebSocketCommand::NextPage],
+        };
+        assert_eq!(required_role(&batch), Role::Controller);
+
+        let config: AccessConfig = serde_json::from_str(
+            r#"{"defaultRole":"viewer","tokens":[{"token":"deck-secret","role":"controller"}]}"#,
+        )
+        .unwrap();
+        assert!(config.validate().is_ok());
+        assert_eq!(config.role_for("deck-secret"), Some(Role::Controller));
+        assert_eq!(config.role_for("guess"), None);
+
+        let mut short = config.clone();
+        short.tokens[0].token = "abc".to_string();
+        assert!(short.validate().is_err());
+        let mut duplicate = config;
+        duplicate.tokens.push(duplicate.tokens[0].clone());
+        assert!(duplicate.validate().is_err());
+    }
+}
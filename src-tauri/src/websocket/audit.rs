@@ -0,0 +1,286 @@
+/*
+ * This file is part of StreamSlate.
+ * Copyright (C) 2025 StreamSlate Contributors
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+
+//! Command audit log
+//!
+//! Control commands (page, annotation and output changes, not queries,
+//! pings, reactions or pointer moves) are appended to a JSON Lines file in
+//! the app data directory with the client that sent them and their result,
+//! so the operator can go over who did what after a show. Commands from
+//! every transport are logged: WebSocket, the REST API, OSC, the local
+//! socket and local controllers. The file is rotated when it reaches
+//! 1 MiB, keeping the three before it.
+
+use super::access::Role;
+use super::rate::{command_class, CommandClass};
+use super::session::ClientSession;
+use crate::protocol::{WebSocketCommand, WebSocketEvent};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use tauri::{AppHandle, Manager};
+use tracing::warn;
+
+/// File name of the log in the app data directory
+const LOG_FILE: &str = "command-history.jsonl";
+
+/// Size at which the log is rotated
+const MAX_LOG_BYTES: u64 = 1024 * 1024;
+
+/// Rotated files kept besides the current one
+const KEEP_FILES: usize = 3;
+
+/// The log commands are recorded to, once the app data directory is known
+static LOG: Mutex<Option<AuditLog>> = Mutex::new(None);
+
+/// One recorded command
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AuditEntry {
+    /// When the command ran (RFC 3339)
+    pub timestamp: String,
+    /// Name and kind sent with `HELLO`, or the peer address
+    pub client: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub client_id: Option<String>,
+    pub peer: String,
+    pub role: Role,
+    /// The command as sent, with annotation bodies cut down to their ID
+    pub command: Value,
+    /// Whether the command was applied
+    pub ok: bool,
+    /// Type of the reply, e.g. `PAGE_CHANGED` or `ERROR`
+    pub result: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+impl AuditEntry {
+    fn new(command: &WebSocketCommand, session: &ClientSession, reply: &WebSocketEvent) -> Self {
+        let error = match reply {
+            WebSocketEvent::Error { message, .. } => Some(message.clone()),
+            _ => None,
+        };
+        Self {
+            timestamp: chrono::Utc::now().to_rfc3339(),
+            client: session.label(),
+            client_id: session.client_id.clone(),
+            peer: session.peer_addr.to_string(),
+            role: session.role,
+            command: command_summary(command),
+            ok: !reply.is_failure(),
+            result: event_type(reply),
+            error,
+        }
+    }
+}
+
+/// Whether `command` is recorded
+pub fn is_audited(command: &WebSocketCommand) -> bool {
+    !matches!(command, WebSocketCommand::Batch { .. })
+        && command_class(command).is_some_and(|class| class != CommandClass::Queries)
+}
+
+/// The command as JSON, without annotation contents such as ink points
+fn command_summary(command: &WebSocketCommand) -> Value {
+    let mut value = serde_json::to_value(command).unwrap_or(Value::Null);
+    if let Some(annotation) = value.get_mut("annotation") {
+        let id = annotation.get("id").cloned().unwrap_or(Value::Null);
+        *annotation = serde_json::json!({ "id": id });
+    }
+    value
+}
+
+fn event_type(event: &WebSocketEvent) -> String {
+    serde_json::to_value(event)
+        .ok()
+        .and_then(|value| value.get("type")?.as_str().map(str::to_string))
+        .unwrap_or_default()
+}
+
+/// An append-only log file, rotated by size
+#[derive(Debug)]
+pub struct AuditLog {
+    path: PathBuf,
+    max_bytes: u64,
+    keep: usize,
+}
+
+impl AuditLog {
+    pub fn new(path: PathBuf, max_bytes: u64, keep: usize) -> Self {
+        Self {
+            path,
+            max_bytes,
+            keep,
+        }
+    }
+
+    /// Path of the `n`th rotated file, `n` = 0 being the current one
+    fn file(&self, n: usize) -> PathBuf {
+        if n == 0 {
+            return self.path.clone();
+        }
+        let stem = self
+            .path
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        self.path.with_file_name(format!("{stem}.{n}.jsonl"))
+    }
+
+    /// Append an entry, rotating the files first if it would overflow the
+    /// current one
+    pub fn append(&self, entry: &AuditEntry) -> std::io::Result<()> {
+        let mut line = serde_json::to_string(entry)?;
+        line.push('\n');
+        let size = std::fs::metadata(&self.path).map_or(0, |m| m.len());
+        if size > 0 && size + line.len() as u64 > self.max_bytes {
+            self.rotate()?;
+        }
+        if let Some(parent) = self.path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)?
+            .write_all(line.as_bytes())
+    }
+
+    fn rotate(&self) -> std::io::Result<()> {
+        let _ = std::fs::remove_file(self.file(self.keep));
+        for n in (0..self.keep).rev() {
+            let from = self.file(n);
+            if from.exists() {
+                std::fs::rename(from, self.file(n + 1))?;
+            }
+        }
+        Ok(())
+    }
+
+    /// The last `limit` entries, oldest first
+    ///
+    /// Lines that can't be parsed, e.g. one cut short by a crash, are
+    /// skipped.
+    pub fn recent(&self, limit: usize) -> Vec<AuditEntry> {
+        let mut entries = Vec::new();
+        for n in (0..=self.keep).rev() {
+            if let Ok(content) = std::fs::read_to_string(self.file(n)) {
+                entries.extend(
+                    content
+                        .lines()
+                        .filter_map(|line| serde_json::from_str::<AuditEntry>(line).ok()),
+                );
+            }
+        }
+        let skip = entries.len().saturating_sub(limit);
+        entries.split_off(skip)
+    }
+}
+
+/// Path of the log in the app data directory
+pub fn log_path(app_handle: &AppHandle) -> Option<PathBuf> {
+    app_handle
+        .path()
+        .app_data_dir()
+        .ok()
+        .map(|dir| dir.join(LOG_FILE))
+}
+
+/// Start recording commands to the log in the app data directory
+pub fn init(app_handle: &AppHandle) {
+    let Some(path) = log_path(app_handle) else {
+        return;
+    };
+    if let Ok(mut log) = LOG.lock() {
+        *log = Some(AuditLog::new(path, MAX_LOG_BYTES, KEEP_FILES));
+    }
+}
+
+/// Record a command that [`is_audited`] and its reply
+pub fn record(command: &WebSocketCommand, session: &ClientSession, reply: &WebSocketEvent) {
+    let entry = AuditEntry::new(command, session, reply);
+    if let Ok(log) = LOG.lock() {
+        if let Some(log) = log.as_ref() {
+            if let Err(e) = log.append(&entry) {
+                warn!(error = %e, "Failed to write command history");
+            }
+        }
+    }
+}
+
+/// The last `limit` recorded commands, oldest first
+pub fn history(limit: usize) -> Vec<AuditEntry> {
+    LOG.lock()
+        .ok()
+        .and_then(|log| log.as_ref().map(|log| log.recent(limit)))
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(page: u32) -> AuditEntry {
+        AuditEntry {
+            timestamp: "2025-06-01T19:30:00Z".to_string(),
+            client: "Stream Deck (stream-deck)".to_string(),
+            client_id: None,
+            peer: "192.168.1.20:51234".to_string(),
+            role: Role::Controller,
+            command: serde_json::json!({ "type": "GO_TO_PAGE", "page": page }),
+            ok: true,
+            result: "PAGE_CHANGED".to_string(),
+            error: None,
+        }
+    }
+
+    #[test]
+    fn test_audit_log_rotation() {
+        let dir = std::env::temp_dir().join(format!("streamslate-audit-{}", uuid::Uuid::new_v4()));
+        let line_len = serde_json::to_string(&entry(10)).unwrap().len() as u64 + 1;
+        // Two entries per file, two rotated files kept
+        let log = AuditLog::new(dir.join(LOG_FILE), line_len * 2, 2);
+
+        for page in 10..17 {
+            log.append(&entry(page)).unwrap();
+        }
+        let pages: Vec<_> = log
+            .recent(10)
+            .iter()
+            .map(|e| e.command["page"].as_u64().unwrap())
+            .collect();
+        assert_eq!(pages, [12, 13, 14, 15, 16]);
+        assert_eq!(log.recent(2), [entry(15), entry(16)]);
+
+        assert!(is_audited(&WebSocketCommand::NextPage));
+        assert!(!is_audited(&WebSocketCommand::GetState));
+        assert!(!is_audited(&WebSocketCommand::Ping));
+        let summary = command_summary(&WebSocketCommand::AddAnnotation {
+            page: 2,
+            annotation: serde_json::json!({ "id": "a1", "points": [[0.1, 0.2]] }),
+            clock: None,
+        });
+        assert_eq!(summary["annotation"], serde_json::json!({ "id": "a1" }));
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -0,0 +1,215 @@
+/*
+ * This file is part of StreamSlate.
+ * Copyright (C) 2025 StreamSlate Contributors
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+
+//! Registry of connected WebSocket clients
+//!
+//! Every open connection is listed here with what it said about itself, so
+//! the operator can see which controllers are connected and kick stale or
+//! unknown ones mid-show.
+
+use super::access::Role;
+use super::session::{ClientInfo, ClientSession};
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::net::SocketAddr;
+use tokio::sync::oneshot;
+
+/// A connected client as shown to the operator
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConnectedClient {
+    /// Connection ID, unique while the app runs
+    pub id: u64,
+    pub peer: String,
+    pub connected_at: DateTime<Utc>,
+    pub role: Role,
+    /// Identity sent with `HELLO`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub info: Option<ClientInfo>,
+    /// Stable client ID sent with `IDENTIFY`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub client_id: Option<String>,
+    /// Display name sent with `IDENTIFY`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    /// Broadcasts dropped because the client read them too slowly
+    pub dropped_events: u64,
+}
+
+/// Why a connection is asked to close
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Disconnect {
+    /// The operator disconnected this client
+    Operator,
+    /// The server is stopping
+    ServerStopped,
+}
+
+#[derive(Debug)]
+struct Entry {
+    client: ConnectedClient,
+    /// Fired to make the connection close itself
+    disconnect: Option<oneshot::Sender<Disconnect>>,
+}
+
+/// The open connections, by connection ID
+#[derive(Debug, Default)]
+pub struct ClientRegistry {
+    next_id: u64,
+    clients: BTreeMap<u64, Entry>,
+}
+
+impl ClientRegistry {
+    /// Add a new connection, returning its ID and the receiver that fires
+    /// when the operator disconnects it
+    pub fn register(
+        &mut self,
+        peer: SocketAddr,
+        role: Role,
+    ) -> (u64, oneshot::Receiver<Disconnect>) {
+        self.next_id += 1;
+        let id = self.next_id;
+        let (tx, rx) = oneshot::channel();
+        self.clients.insert(
+            id,
+            Entry {
+                client: ConnectedClient {
+                    id,
+                    peer: peer.to_string(),
+                    connected_at: Utc::now(),
+                    role,
+                    info: None,
+                    client_id: None,
+                    name: None,
+                    dropped_events: 0,
+                },
+                disconnect: Some(tx),
+            },
+        );
+        (id, rx)
+    }
+
+    /// Refresh what a connection said about itself
+    pub fn update(&mut self, id: u64, session: &ClientSession) {
+        if let Some(entry) = self.clients.get_mut(&id) {
+            entry.client.info = session.info.clone();
+            entry.client.client_id = session.client_id.clone();
+            entry.client.name = session.name.clone();
+            entry.client.role = session.role;
+        }
+    }
+
+    /// The role of a connection, which the operator may have changed
+    pub fn role(&self, id: u64) -> Option<Role> {
+        self.clients.get(&id).map(|entry| entry.client.role)
+    }
+
+    /// Count broadcasts a connection dropped
+    pub fn add_dropped(&mut self, id: u64, count: u64) {
+        if let Some(entry) = self.clients.get_mut(&id) {
+            entry.client.dropped_events += count;
+        }
+    }
+
+    /// Change the role of a connection, returning whether it's open
+    pub fn set_role(&mut self, id: u64, role: Role) -> bool {
+        match self.clients.get_mut(&id) {
+            Some(entry) => {
+                entry.client.role = role;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Forget a closed connection
+    pub fn remove(&mut self, id: u64) {
+        self.clients.remove(&id);
+    }
+
+    /// The open connections, oldest first
+    pub fn list(&self) -> Vec<ConnectedClient> {
+        self.clients.values().map(|e| e.client.clone()).collect()
+    }
+
+    /// Ask a connection to close, returning whether it was open
+    pub fn disconnect(&mut self, id: u64) -> bool {
+        match self.clients.get_mut(&id) {
+            Some(entry) => {
+                if let Some(tx) = entry.disconnect.take() {
+                    let _ = tx.send(Disconnect::Operator);
+                }
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Ask every connection to close as the server stops, returning how
+    /// many were open
+    pub fn disconnect_all(&mut self) -> usize {
+        for entry in self.clients.values_mut() {
+            if let Some(tx) = entry.disconnect.take() {
+                let _ = tx.send(Disconnect::ServerStopped);
+            }
+        }
+        self.clients.len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_registry() {
+        let mut registry = ClientRegistry::default();
+        let peer = SocketAddr::from(([192, 168, 1, 20], 50000));
+        let (first, mut first_rx) = registry.register(peer, Role::Viewer);
+        let (second, mut second_rx) = registry.register(peer, Role::Viewer);
+        assert_ne!(first, second);
+
+        let mut session = ClientSession::new(peer);
+        session.info = Some(ClientInfo {
+            name: "Stream Deck".to_string(),
+            kind: "stream-deck".to_string(),
+            version: None,
+        });
+        registry.update(first, &session);
+        let clients = registry.list();
+        assert_eq!(clients.len(), 2);
+        assert_eq!(clients[0].info, session.info);
+        assert_eq!(clients[1].info, None);
+
+        assert!(registry.set_role(second, Role::Controller));
+        assert_eq!(registry.role(second), Some(Role::Controller));
+        registry.add_dropped(second, 3);
+        registry.add_dropped(second, 2);
+        assert_eq!(registry.list()[1].dropped_events, 5);
+
+        assert!(registry.disconnect(first));
+        assert_eq!(first_rx.try_recv(), Ok(Disconnect::Operator));
+        registry.remove(first);
+        assert!(!registry.disconnect(first));
+        assert_eq!(registry.list().len(), 1);
+
+        assert_eq!(registry.disconnect_all(), 1);
+        assert_eq!(second_rx.try_recv(), Ok(Disconnect::ServerStopped));
+    }
+}
@@ -0,0 +1,132 @@
+/*
+ * This file is part of StreamSlate.
+ * Copyright (C) 2025 StreamSlate Contributors
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+
+//! Coalescing of high-frequency broadcasts
+//!
+//! Pinch-zooming or scrolling through the deck changes the zoom or page many
+//! times a second, and broadcasting every change can push slow clients out
+//! of the channel. The first `ZOOM_CHANGED` or `PAGE_CHANGED` goes out right
+//! away; further ones within the window are merged, and only the latest is
+//! sent when the window ends. Any other event sends the held one first, so
+//! clients see events in order.
+
+use crate::protocol::WebSocketEvent;
+use std::mem::{discriminant, Discriminant};
+use std::time::{Duration, Instant};
+
+/// How long consecutive changes are merged for
+pub const COALESCE_WINDOW: Duration = Duration::from_millis(50);
+
+/// Whether later events of the same kind replace this one
+fn coalesces(event: &WebSocketEvent) -> bool {
+    matches!(
+        event,
+        WebSocketEvent::ZoomChanged { .. } | WebSocketEvent::PageChanged { .. }
+    )
+}
+
+/// Holds back rapid changes until their window ends
+#[derive(Debug, Default)]
+pub struct Coalescer {
+    /// Kind of the last change sent, and when its window ends
+    window: Option<(Discriminant<WebSocketEvent>, Instant)>,
+    /// Latest change received within the window
+    pending: Option<WebSocketEvent>,
+}
+
+impl Coalescer {
+    /// Take an event, returning the events to broadcast now
+    pub fn push(&mut self, event: WebSocketEvent, now: Instant) -> Vec<WebSocketEvent> {
+        if !coalesces(&event) {
+            self.window = None;
+            return self.pending.take().into_iter().chain([event]).collect();
+        }
+
+        let kind = discriminant(&event);
+        match self.window {
+            Some((open, until)) if open == kind && now < until => {
+                self.pending = Some(event);
+                Vec::new()
+            }
+            _ => {
+                self.window = Some((kind, now + COALESCE_WINDOW));
+                self.pending.take().into_iter().chain([event]).collect()
+            }
+        }
+    }
+
+    /// When the held change is due, if there is one
+    pub fn deadline(&self) -> Option<Instant> {
+        self.pending.as_ref()?;
+        self.window.map(|(_, until)| until)
+    }
+
+    /// Release the held change once its window has ended, starting a new
+    /// window for it
+    pub fn flush(&mut self, now: Instant) -> Option<WebSocketEvent> {
+        if self.deadline().is_some_and(|until| now < until) {
+            return None;
+        }
+        let event = self.pending.take()?;
+        self.window = Some((discriminant(&event), now + COALESCE_WINDOW));
+        Some(event)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn zoom(zoom: f64) -> WebSocketEvent {
+        WebSocketEvent::ZoomChanged { zoom }
+    }
+
+    fn zoom_of(events: &[WebSocketEvent]) -> Vec<f64> {
+        events
+            .iter()
+            .filter_map(|event| match event {
+                WebSocketEvent::ZoomChanged { zoom } => Some(*zoom),
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_coalescer() {
+        let start = Instant::now();
+        let mut coalescer = Coalescer::default();
+
+        // The first change goes out at once, later ones are merged
+        assert_eq!(zoom_of(&coalescer.push(zoom(1.1), start)), [1.1]);
+        assert!(coalescer.push(zoom(1.2), start).is_empty());
+        assert!(coalescer.push(zoom(1.3), start).is_empty());
+        assert_eq!(coalescer.deadline(), Some(start + COALESCE_WINDOW));
+        assert!(coalescer.flush(start).is_none());
+        let end = start + COALESCE_WINDOW;
+        assert_eq!(zoom_of(coalescer.flush(end).as_slice()), [1.3]);
+        assert_eq!(coalescer.deadline(), None);
+
+        // Another event sends the held change ahead of it
+        assert!(coalescer.push(zoom(1.4), end).is_empty());
+        let sent = coalescer.push(WebSocketEvent::PdfClosed, end);
+        assert_eq!(sent.len(), 2);
+        assert_eq!(zoom_of(&sent), [1.4]);
+        assert!(matches!(sent[1], WebSocketEvent::PdfClosed));
+        assert_eq!(zoom_of(&coalescer.push(zoom(1.5), end)), [1.5]);
+    }
+}
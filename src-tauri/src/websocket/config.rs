@@ -0,0 +1,171 @@
+/*
+ * This file is part of StreamSlate.
+ * Copyright (C) 2025 StreamSlate Contributors
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+
+//! Where the control server listens
+//!
+//! By default the server only accepts connections from this machine.
+//! Binding `0.0.0.0` (or a LAN address) lets control surfaces on other
+//! devices connect, so it has to be turned on explicitly.
+
+use super::rate::RateLimits;
+use super::server::DEFAULT_PORT;
+use crate::error::{Result, StreamSlateError};
+use crate::settings::Settings;
+use serde::{Deserialize, Serialize};
+use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+
+/// File name of the settings inside the app data directory
+pub const CONFIG_FILE: &str = "websocket.json";
+
+/// Ports tried after the configured one when it's taken, by default
+const DEFAULT_FALLBACK_PORTS: u16 = 9;
+
+/// Address and port of the control server
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WebSocketConfig {
+    #[serde(default = "default_port")]
+    pub port: u16,
+    /// IP address to bind, `127.0.0.1` for this machine only or `0.0.0.0`
+    /// for every network interface
+    #[serde(default = "default_bind_addr")]
+    pub bind_addr: String,
+    /// How many of the following ports to try when `port` is taken
+    #[serde(default = "default_fallback_ports")]
+    pub fallback_ports: u16,
+    /// How many commands of each class a connection may send
+    #[serde(default)]
+    pub rate_limits: RateLimits,
+    /// Compress large messages for clients that support permessage-deflate
+    #[serde(default = "default_compression")]
+    pub compression: bool,
+}
+
+impl Default for WebSocketConfig {
+    fn default() -> Self {
+        Self {
+            port: default_port(),
+            bind_addr: default_bind_addr(),
+            fallback_ports: default_fallback_ports(),
+            rate_limits: RateLimits::default(),
+            compression: default_compression(),
+        }
+    }
+}
+
+impl WebSocketConfig {
+    /// The socket address to bind
+    pub fn addr(&self) -> Result<SocketAddr> {
+        if self.port == 0 {
+            return Err(StreamSlateError::Other(
+                "WebSocket port must be between 1 and 65535".to_string(),
+            ));
+        }
+        let ip: IpAddr = self.bind_addr.trim().parse().map_err(|_| {
+            StreamSlateError::Other(format!("Invalid bind address: {}", self.bind_addr))
+        })?;
+        Ok(SocketAddr::new(ip, self.port))
+    }
+
+    /// The addresses to try binding, in order: the configured port, then
+    /// the fallback ports after it
+    pub fn candidates(&self) -> Result<Vec<SocketAddr>> {
+        let addr = self.addr()?;
+        let last = self.port.checked_add(self.fallback_ports).ok_or_else(|| {
+            StreamSlateError::Other("WebSocket fallback ports must end at 65535".to_string())
+        })?;
+        Ok((self.port..=last)
+            .map(|port| SocketAddr::new(addr.ip(), port))
+            .collect())
+    }
+
+    /// Check the ports, address and rate limits are usable
+    pub fn validate(&self) -> Result<()> {
+        self.candidates()?;
+        self.rate_limits.validate()
+    }
+}
+
+impl Settings for WebSocketConfig {
+    fn check(&self) -> Result<()> {
+        self.validate()
+    }
+}
+
+fn default_port() -> u16 {
+    DEFAULT_PORT
+}
+
+fn default_fallback_ports() -> u16 {
+    DEFAULT_FALLBACK_PORTS
+}
+
+fn default_compression() -> bool {
+    true
+}
+
+fn default_bind_addr() -> String {
+    Ipv4Addr::LOCALHOST.to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_addr() {
+        let config = WebSocketConfig::default();
+        assert_eq!(config.addr().unwrap().to_string(), "127.0.0.1:11451");
+
+        let lan: WebSocketConfig =
+            serde_json::from_str(r#"{"port":9000,"bindAddr":"0.0.0.0"}"#).unwrap();
+        assert_eq!(lan.addr().unwrap().to_string(), "0.0.0.0:9000");
+        let partial: WebSocketConfig = serde_json::from_str(r#"{"port":9000}"#).unwrap();
+        assert_eq!(partial.bind_addr, "127.0.0.1");
+
+        let ports: Vec<u16> = WebSocketConfig {
+            fallback_ports: 2,
+            ..partial
+        }
+        .candidates()
+        .unwrap()
+        .iter()
+        .map(|addr| addr.port())
+        .collect();
+        assert_eq!(ports, [9000, 9001, 9002]);
+        assert!(WebSocketConfig {
+            port: 65535,
+            ..WebSocketConfig::default()
+        }
+        .validate()
+        .is_err());
+
+        assert!(WebSocketConfig {
+            port: 0,
+            ..lan.clone()
+        }
+        .validate()
+        .is_err());
+        assert!(WebSocketConfig {
+            bind_addr: "localhost".to_string(),
+            ..lan
+        }
+        .validate()
+        .is_err());
+    }
+}
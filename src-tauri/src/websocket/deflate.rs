@@ -0,0 +1,456 @@
+/*
+ * This file is part of StreamSlate.
+ * Copyright (C) 2025 StreamSlate Contributors
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+
+//! permessage-deflate compression (RFC 7692)
+//!
+//! Annotation broadcasts with thousands of ink points are large JSON
+//! messages, which compress well. tungstenite doesn't implement the
+//! compression extension, so a compressed connection runs through a pair of
+//! pumps between the socket and tungstenite. They pass the handshake
+//! through, accepting the extension in the response when the client offers
+//! it, then inflate the client's compressed messages and deflate large text
+//! messages going out. Images are sent as they are, being compressed
+//! already. Each message is compressed on its own (no context takeover), so
+//! the pumps keep no compression state between messages.
+
+use flate2::read::DeflateDecoder;
+use flate2::write::DeflateEncoder;
+use flate2::Compression;
+use std::io::{self, ErrorKind, Read, Write};
+use tokio::io::{
+    AsyncBufRead, AsyncBufReadExt, AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt, BufReader,
+    DuplexStream,
+};
+use tokio::sync::oneshot;
+use tracing::debug;
+
+/// Name of the extension in `Sec-WebSocket-Extensions`
+const EXTENSION: &str = "permessage-deflate";
+
+/// Handshake response header accepting the extension
+const RESPONSE_HEADER: &[u8] = b"Sec-WebSocket-Extensions: permessage-deflate; \
+    server_no_context_takeover; client_no_context_takeover\r\n";
+
+/// Outgoing text messages shorter than this are sent uncompressed
+const MIN_COMPRESSED_LEN: usize = 256;
+
+/// Largest message accepted, compressed or not
+const MAX_MESSAGE_LEN: usize = 16 * 1024 * 1024;
+
+/// Longest handshake accepted
+const MAX_HEADER_LEN: u64 = 16 * 1024;
+
+/// Bytes buffered between the pumps and tungstenite
+const PIPE_CAPACITY: usize = 64 * 1024;
+
+/// Ending of a sync-flushed block, left off compressed messages
+const TRAILER: [u8; 4] = [0x00, 0x00, 0xff, 0xff];
+
+const FIN: u8 = 0x80;
+const RSV1: u8 = 0x40;
+const OPCODE_CONTINUATION: u8 = 0x0;
+const OPCODE_TEXT: u8 = 0x1;
+const OPCODE_BINARY: u8 = 0x2;
+
+/// A WebSocket frame, with its payload unmasked
+#[derive(Debug, Clone, PartialEq)]
+struct Frame {
+    /// First header byte: FIN, reserved bits and opcode
+    head: u8,
+    mask: Option<[u8; 4]>,
+    payload: Vec<u8>,
+}
+
+impl Frame {
+    fn fin(&self) -> bool {
+        self.head & FIN != 0
+    }
+
+    /// Whether this starts a compressed message
+    fn compressed(&self) -> bool {
+        self.head & RSV1 != 0
+    }
+
+    fn opcode(&self) -> u8 {
+        self.head & 0x0f
+    }
+
+    fn is_control(&self) -> bool {
+        self.opcode() & 0x8 != 0
+    }
+
+    /// The frame as sent on the wire
+    fn encode(&self) -> Vec<u8> {
+        let len = self.payload.len();
+        let mut out = Vec::with_capacity(len + 14);
+        out.push(self.head);
+        let masked = if self.mask.is_some() { 0x80 } else { 0 };
+        if len < 126 {
+            out.push(masked | len as u8);
+        } else if let Ok(len) = u16::try_from(len) {
+            out.push(masked | 126);
+            out.extend_from_slice(&len.to_be_bytes());
+        } else {
+            out.push(masked | 127);
+            out.extend_from_slice(&(len as u64).to_be_bytes());
+        }
+        if let Some(mask) = self.mask {
+            out.extend_from_slice(&mask);
+        }
+        let start = out.len();
+        out.extend_from_slice(&self.payload);
+        if let Some(mask) = self.mask {
+            apply_mask(&mut out[start..], mask);
+        }
+        out
+    }
+}
+
+fn apply_mask(data: &mut [u8], mask: [u8; 4]) {
+    for (i, byte) in data.iter_mut().enumerate() {
+        *byte ^= mask[i % 4];
+    }
+}
+
+fn invalid(message: &str) -> io::Error {
+    io::Error::new(ErrorKind::InvalidData, message.to_string())
+}
+
+/// Read a frame, or `None` at the end of the stream
+async fn read_frame<R: AsyncRead + Unpin>(reader: &mut R) -> io::Result<Option<Frame>> {
+    let mut header = [0u8; 2];
+    match reader.read_exact(&mut header[..1]).await {
+        Ok(_) => {}
+        Err(e) if e.kind() == ErrorKind::UnexpectedEof => return Ok(None),
+        Err(e) => return Err(e),
+    }
+    reader.read_exact(&mut header[1..]).await?;
+    let len = match header[1] & 0x7f {
+        126 => u64::from(reader.read_u16().await?),
+        127 => reader.read_u64().await?,
+        len => u64::from(len),
+    };
+    if len > MAX_MESSAGE_LEN as u64 {
+        return Err(invalid("WebSocket frame is too large"));
+    }
+    let mask = if header[1] & 0x80 != 0 {
+        let mut mask = [0u8; 4];
+        reader.read_exact(&mut mask).await?;
+        Some(mask)
+    } else {
+        None
+    };
+    let mut payload = vec![0; len as usize];
+    reader.read_exact(&mut payload).await?;
+    if let Some(mask) = mask {
+        apply_mask(&mut payload, mask);
+    }
+    Ok(Some(Frame {
+        head: header[0],
+        mask,
+        payload,
+    }))
+}
+
+/// Compress a message payload
+fn deflate(data: &[u8]) -> io::Result<Vec<u8>> {
+    let mut encoder = DeflateEncoder::new(Vec::with_capacity(data.len() / 4), Compression::fast());
+    encoder.write_all(data)?;
+    // A sync flush ends the message on a byte boundary without closing the
+    // stream; the encoder is dropped rather than finished
+    encoder.flush()?;
+    let mut out = std::mem::take(encoder.get_mut());
+    if out.ends_with(&TRAILER) {
+        out.truncate(out.len() - TRAILER.len());
+    }
+    Ok(out)
+}
+
+/// Decompress a message payload
+fn inflate(data: &[u8]) -> io::Result<Vec<u8>> {
+    let mut out = Vec::new();
+    DeflateDecoder::new(Read::chain(data, &TRAILER[..]))
+        .take(MAX_MESSAGE_LEN as u64 + 1)
+        .read_to_end(&mut out)?;
+    if out.len() > MAX_MESSAGE_LEN {
+        return Err(invalid("WebSocket message is too large"));
+    }
+    Ok(out)
+}
+
+/// Compress an outgoing frame if it's a whole text message worth compressing
+fn compress(mut frame: Frame) -> io::Result<Frame> {
+    if frame.opcode() == OPCODE_TEXT && frame.fin() && frame.payload.len() >= MIN_COMPRESSED_LEN {
+        let compressed = deflate(&frame.payload)?;
+        if compressed.len() < frame.payload.len() {
+            frame.payload = compressed;
+            frame.head |= RSV1;
+        }
+    }
+    Ok(frame)
+}
+
+/// Reassembles and inflates the client's compressed messages
+#[derive(Debug, Default)]
+struct Inflater {
+    /// Compressed message still being received, as its first frame holding
+    /// the payload of the fragments so far
+    message: Option<Frame>,
+}
+
+impl Inflater {
+    /// Take a frame from the client, returning the frame to pass on
+    fn push(&mut self, frame: Frame) -> io::Result<Option<Frame>> {
+        if frame.is_control() {
+            return Ok(Some(frame));
+        }
+        let mut message = match self.message.take() {
+            Some(mut message) => {
+                if frame.opcode() != OPCODE_CONTINUATION {
+                    return Err(invalid("Expected a continuation frame"));
+                }
+                if message.payload.len() + frame.payload.len() > MAX_MESSAGE_LEN {
+                    return Err(invalid("WebSocket message is too large"));
+                }
+                message.payload.extend_from_slice(&frame.payload);
+                message.head |= frame.head & FIN;
+                message
+            }
+            None if frame.compressed() && matches!(frame.opcode(), OPCODE_TEXT | OPCODE_BINARY) => {
+                frame
+            }
+            None => return Ok(Some(frame)),
+        };
+        if !message.fin() {
+            self.message = Some(message);
+            return Ok(None);
+        }
+        message.payload = inflate(&message.payload)?;
+        message.head &= !RSV1;
+        Ok(Some(message))
+    }
+}
+
+/// Read an HTTP header block, through the blank line ending it
+async fn read_header<R: AsyncBufRead + Unpin>(reader: &mut R) -> io::Result<Vec<u8>> {
+    let mut limited = reader.take(MAX_HEADER_LEN);
+    let mut header = Vec::new();
+    loop {
+        let start = header.len();
+        if limited.read_until(b'\n', &mut header).await? == 0 {
+            return Err(invalid("Incomplete WebSocket handshake"));
+        }
+        if matches!(&header[start..], b"\r\n" | b"\n") {
+            return Ok(header);
+        }
+    }
+}
+
+/// Whether a handshake request offers the extension with parameters the
+/// server can meet
+fn offers_extension(request: &[u8]) -> bool {
+    String::from_utf8_lossy(request).lines().any(|line| {
+        let Some((name, value)) = line.split_once(':') else {
+            return false;
+        };
+        name.trim().eq_ignore_ascii_case("sec-websocket-extensions")
+            && value.split(',').any(|offer| {
+                let mut params = offer.split(';').map(str::trim);
+                params.next() == Some(EXTENSION)
+                    && params.all(|param| !param.starts_with("server_max_window_bits"))
+            })
+    })
+}
+
+/// Add the extension to a successful handshake response
+fn accept_extension(response: &mut Vec<u8>) -> bool {
+    if !response.starts_with(b"HTTP/1.1 101") {
+        return false;
+    }
+    let end = response.len() - 2;
+    response.splice(end..end, RESPONSE_HEADER.iter().copied());
+    true
+}
+
+/// Client to server: pass the request on, then inflate compressed messages
+async fn inbound<R, W>(
+    socket: &mut R,
+    server: &mut W,
+    offered: oneshot::Sender<bool>,
+    accepted: oneshot::Receiver<bool>,
+) -> io::Result<()>
+where
+    R: AsyncBufRead + Unpin,
+    W: AsyncWrite + Unpin,
+{
+    let request = read_header(socket).await?;
+    let _ = offered.send(offers_extension(&request));
+    server.write_all(&request).await?;
+    if !accepted.await.unwrap_or(false) {
+        tokio::io::copy_buf(socket, server).await?;
+        return Ok(());
+    }
+
+    let mut inflater = Inflater::default();
+    while let Some(frame) = read_frame(socket).await? {
+        if let Some(frame) = inflater.push(frame)? {
+            server.write_all(&frame.encode()).await?;
+        }
+    }
+    Ok(())
+}
+
+/// Server to client: accept the extension, then deflate outgoing messages
+async fn outbound<R, W>(
+    server: &mut R,
+    socket: &mut W,
+    offered: oneshot::Receiver<bool>,
+    accepted: oneshot::Sender<bool>,
+) -> io::Result<()>
+where
+    R: AsyncBufRead + Unpin,
+    W: AsyncWrite + Unpin,
+{
+    let mut response = read_header(server).await?;
+    let accept = offered.await.unwrap_or(false) && accept_extension(&mut response);
+    let _ = accepted.send(accept);
+    socket.write_all(&response).await?;
+    socket.flush().await?;
+    if !accept {
+        tokio::io::copy_buf(server, socket).await?;
+        return Ok(());
+    }
+
+    while let Some(frame) = read_frame(server).await? {
+        socket.write_all(&compress(frame)?.encode()).await?;
+        socket.flush().await?;
+    }
+    Ok(())
+}
+
+/// Run a connection through the compression pumps, returning the stream to
+/// accept the WebSocket on
+///
+/// Clients that don't offer the extension get an uncompressed connection.
+pub fn compressed<S>(stream: S) -> DuplexStream
+where
+    S: AsyncRead + AsyncWrite + Send + 'static,
+{
+    let (server, pumps) = tokio::io::duplex(PIPE_CAPACITY);
+    let (socket_read, mut socket_write) = tokio::io::split(stream);
+    let (pump_read, mut pump_write) = tokio::io::split(pumps);
+    let (offered_tx, offered_rx) = oneshot::channel();
+    let (accepted_tx, accepted_rx) = oneshot::channel();
+
+    let inbound = tokio::spawn(async move {
+        let mut socket = BufReader::new(socket_read);
+        if let Err(e) = inbound(&mut socket, &mut pump_write, offered_tx, accepted_rx).await {
+            debug!(error = %e, "Stopped inflating WebSocket messages");
+        }
+        let _ = pump_write.shutdown().await;
+    });
+    tokio::spawn(async move {
+        let mut server = BufReader::new(pump_read);
+        if let Err(e) = outbound(&mut server, &mut socket_write, offered_rx, accepted_tx).await {
+            debug!(error = %e, "Stopped deflating WebSocket messages");
+        }
+        let _ = socket_write.shutdown().await;
+        inbound.abort();
+    });
+    server
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_offers_extension() {
+        let request = b"GET / HTTP/1.1\r\nHost: localhost\r\n\
+            Sec-WebSocket-Extensions: permessage-deflate; client_max_window_bits\r\n\r\n";
+        assert!(offers_extension(request));
+        assert!(!offers_extension(
+            b"GET / HTTP/1.1\r\nHost: localhost\r\n\r\n"
+        ));
+        assert!(!offers_extension(
+            b"GET / HTTP/1.1\r\nSec-WebSocket-Extensions: permessage-deflate; server_max_window_bits=10\r\n\r\n"
+        ));
+
+        let mut response =
+            b"HTTP/1.1 101 Switching Protocols\r\nUpgrade: websocket\r\n\r\n".to_vec();
+        assert!(accept_extension(&mut response));
+        assert!(response.ends_with(b"client_no_context_takeover\r\n\r\n"));
+        assert!(!accept_extension(
+            &mut b"HTTP/1.1 400 Bad Request\r\n\r\n".to_vec()
+        ));
+    }
+
+    #[test]
+    fn test_compress_messages() {
+        let text = r#"{"type":"ANNOTATIONS_UPDATED","points":[1,2,3]}"#.repeat(20);
+        let sent = compress(Frame {
+            head: FIN | OPCODE_TEXT,
+            mask: None,
+            payload: text.clone().into_bytes(),
+        })
+        .unwrap();
+        assert!(sent.compressed());
+        assert!(sent.payload.len() < text.len());
+
+        // The client sends it back compressed, in two fragments
+        let (first, rest) = sent.payload.split_at(sent.payload.len() / 2);
+        let mask = Some([1, 2, 3, 4]);
+        let mut inflater = Inflater::default();
+        let start = Frame {
+            head: RSV1 | OPCODE_TEXT,
+            mask,
+            payload: first.to_vec(),
+        };
+        assert_eq!(inflater.push(start).unwrap(), None);
+        let ping = Frame {
+            head: FIN | 0x9,
+            mask,
+            payload: Vec::new(),
+        };
+        assert_eq!(inflater.push(ping.clone()).unwrap(), Some(ping));
+        let end = Frame {
+            head: FIN | OPCODE_CONTINUATION,
+            mask,
+            payload: rest.to_vec(),
+        };
+        let received = inflater.push(end).unwrap().unwrap();
+        assert_eq!(received.head, FIN | OPCODE_TEXT);
+        assert_eq!(received.payload, text.as_bytes());
+
+        // Frames survive encoding, masked or not
+        for frame in [received, sent] {
+            let encoded = frame.encode();
+            let decoded = decode(&encoded);
+            assert_eq!(decoded, frame);
+        }
+    }
+
+    fn decode(data: &[u8]) -> Frame {
+        tokio::runtime::Builder::new_current_thread()
+            .build()
+            .unwrap()
+            .block_on(read_frame(&mut &data[..]))
+            .unwrap()
+            .unwrap()
+    }
+}
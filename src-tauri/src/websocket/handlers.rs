@@ -20,184 +20,1170 @@
 //!
 //! Processes incoming commands and generates appropriate responses/events.
 
-use super::protocol::{WebSocketCommand, WebSocketEvent};
-use crate::state::AppState;
-use std::sync::Arc;
+use super::access;
+use super::audit;
+use super::page_images::{self, ImageSize};
+use super::preferences::{self, ClientPreferences};
+use super::reactions;
+use super::replay;
+use super::rollback::BatchSnapshot;
+use super::server::broadcast_events;
+use super::session::{ClientInfo, ClientSession};
+use super::topics::Topic;
+use crate::capture::viewport::OutputViewport;
+use crate::commands::agenda::{current_agenda, section_progress};
+use crate::commands::annotations::{
+    page_updates, publish_annotation_updates, step_annotation_history, Point,
+};
+use crate::commands::ephemeral::mark_ephemeral_json;
+use crate::commands::eraser::erase_at_state;
+use crate::commands::ink::clean_stroke_json;
+use crate::commands::layers::{layers_for_open_document, set_layer_visibility};
+use crate::commands::locking::keep_locked;
+use crate::commands::media::{control_media, media_statuses};
+use crate::commands::ndi::{set_blackout_state, set_output_viewport_state};
+use crate::commands::notes::notes_for_page;
+use crate::commands::pdf::{set_smart_invert_state, words_for_page};
+use crate::commands::presets::{set_active_preset_state, style_presets};
+use crate::commands::qr::{clear_qr_state, show_qr_state};
+use crate::commands::search::search_annotations_state;
+use crate::commands::sections::{
+    next_section_page, previous_section_page, sections_for_open_document,
+};
+use crate::commands::shapes::normalize_annotation_json;
+use crate::commands::show::{end_show_state, EndShowOptions};
+use crate::commands::stamps::check_stamp_json;
+use crate::error::{ErrorCode, StreamSlateError};
+use crate::media::MediaAction;
+use crate::protocol::{WebSocketCommand, WebSocketEvent};
+use crate::settings::save_settings;
+use crate::state::crdt::{AnnotationCrdt, Stamp};
+use crate::state::history::{self, AnnotationEdit, PageAnnotations};
+use crate::state::{AppState, PdfState};
+use std::collections::HashSet;
+use std::net::SocketAddr;
+use std::sync::{Arc, PoisonError};
+use std::time::Instant;
 use tauri::AppHandle;
-use tracing::{debug, warn};
+use tracing::{debug, info, warn};
 
-/// Handle an incoming WebSocket command
+/// Most commands in one `BATCH`
+const MAX_BATCH_LEN: usize = 32;
+
+/// Handle an incoming WebSocket command, recording it in the audit log
 pub fn handle_command(
     command: WebSocketCommand,
     state: &Arc<AppState>,
     app_handle: &AppHandle,
+    session: &mut ClientSession,
+) -> WebSocketEvent {
+    // A poisoned lock guards nothing, so carry on with it
+    if matches!(command, WebSocketCommand::Batch { .. }) {
+        let _batch = state
+            .command_lock
+            .write()
+            .unwrap_or_else(PoisonError::into_inner);
+        run_command(command, state, app_handle, session)
+    } else {
+        let _command = state
+            .command_lock
+            .read()
+            .unwrap_or_else(PoisonError::into_inner);
+        run_command(command, state, app_handle, session)
+    }
+}
+
+/// Handle a command with the command lock held
+fn run_command(
+    command: WebSocketCommand,
+    state: &Arc<AppState>,
+    app_handle: &AppHandle,
+    session: &mut ClientSession,
+) -> WebSocketEvent {
+    let audited = audit::is_audited(&command).then(|| command.clone());
+    let reply = dispatch(command, state, app_handle, session);
+    if let Some(command) = audited {
+        audit::record(&command, session, &reply);
+    }
+    reply
+}
+
+fn dispatch(
+    command: WebSocketCommand,
+    state: &Arc<AppState>,
+    app_handle: &AppHandle,
+    session: &mut ClientSession,
 ) -> WebSocketEvent {
     debug!(?command, "Handling WebSocket command");
 
+    let required = access::required_role(&command);
+    if session.role < required {
+        let name = command.name();
+        warn!(client = %session.label(), command = %name, role = session.role.as_str(), "Command refused");
+        return WebSocketEvent::error_code(
+            ErrorCode::Forbidden,
+            format!("{name} needs the {} role", required.as_str()),
+        );
+    }
+
     match command {
-        WebSocketCommand::NextPage => handle_next_page(state, app_handle),
-        WebSocketCommand::PreviousPage => handle_previous_page(state, app_handle),
-        WebSocketCommand::GoToPage { page } => handle_go_to_page(state, app_handle, page),
+        WebSocketCommand::NextPage
+        | WebSocketCommand::PreviousPage
+        | WebSocketCommand::GoToPage { .. }
+        | WebSocketCommand::NextSection
+        | WebSocketCommand::PreviousSection => handle_navigation(state, app_handle, &command),
         WebSocketCommand::GetState => handle_get_state(state),
         WebSocketCommand::SetZoom { zoom } => handle_set_zoom(state, app_handle, zoom),
         WebSocketCommand::TogglePresenter => handle_toggle_presenter(state, app_handle),
         WebSocketCommand::Ping => WebSocketEvent::Pong,
-        WebSocketCommand::AddAnnotation { page, annotation } => {
-            handle_add_annotation(state, app_handle, page, annotation)
+        WebSocketCommand::Hello {
+            name,
+            kind,
+            version,
+            token,
+        } => handle_hello(app_handle, session, name, kind, version, token),
+        WebSocketCommand::AddAnnotation {
+            page,
+            annotation,
+            clock,
+        } => handle_add_annotation(state, app_handle, session, page, annotation, clock),
+        WebSocketCommand::UpdateAnnotation {
+            page,
+            annotation,
+            clock,
+        } => handle_update_annotation(state, app_handle, session, page, annotation, clock),
+        WebSocketCommand::DeleteAnnotation { page, id, clock } => {
+            handle_delete_annotation(state, app_handle, session, page, id, clock)
         }
         WebSocketCommand::ClearAnnotations => handle_clear_annotations(state, app_handle),
+        WebSocketCommand::UndoAnnotation => handle_annotation_history(state, app_handle, false),
+        WebSocketCommand::RedoAnnotation => handle_annotation_history(state, app_handle, true),
+        WebSocketCommand::EraseAt { page, x, y, radius } => {
+            match erase_at_state(state, app_handle, page, Point::new(x, y), radius) {
+                Ok(annotations) => WebSocketEvent::AnnotationsUpdated { annotations },
+                Err(e) => WebSocketEvent::from_error(&e),
+            }
+        }
+        WebSocketCommand::GetPageWords { page } => handle_get_page_words(state, page),
+        WebSocketCommand::AddReaction { page, emoji } => {
+            handle_add_reaction(state, session, page, emoji)
+        }
+        WebSocketCommand::PointerMoved { page, x, y } => {
+            handle_pointer_moved(state, app_handle, session, page, x, y)
+        }
+        WebSocketCommand::GetAgenda => match current_agenda(state) {
+            Ok(agenda) => WebSocketEvent::Agenda { agenda },
+            Err(e) => WebSocketEvent::from_error(&e),
+        },
+        WebSocketCommand::GetMedia => match media_statuses(state) {
+            Ok(media) => WebSocketEvent::Media { media },
+            Err(e) => WebSocketEvent::from_error(&e),
+        },
+        WebSocketCommand::PlayMedia { id } => {
+            handle_media_action(state, app_handle, &id, MediaAction::Play)
+        }
+        WebSocketCommand::PauseMedia { id } => {
+            handle_media_action(state, app_handle, &id, MediaAction::Pause)
+        }
+        WebSocketCommand::SeekMedia { id, position } => {
+            handle_media_action(state, app_handle, &id, MediaAction::Seek(position))
+        }
+        WebSocketCommand::SetSmartInvert { enabled } => {
+            handle_set_smart_invert(state, app_handle, enabled)
+        }
+        WebSocketCommand::SetBlackout { enabled } => {
+            match set_blackout_state(state, app_handle, enabled) {
+                Ok(()) => WebSocketEvent::BlackoutChanged { enabled },
+                Err(e) => WebSocketEvent::from_error(&e),
+            }
+        }
+        WebSocketCommand::SetOutputViewport {
+            viewport,
+            duration_ms,
+        } => handle_set_output_viewport(state, app_handle, viewport, duration_ms),
+        WebSocketCommand::ShowQr {
+            data,
+            duration_seconds,
+        } => match show_qr_state(state, app_handle, data.clone(), duration_seconds) {
+            Ok(_) => WebSocketEvent::QrCodeChanged { data: Some(data) },
+            Err(e) => WebSocketEvent::from_error(&e),
+        },
+        WebSocketCommand::HideQr => match clear_qr_state(state, app_handle) {
+            Ok(()) => WebSocketEvent::QrCodeChanged { data: None },
+            Err(e) => WebSocketEvent::from_error(&e),
+        },
+        WebSocketCommand::Identify { client_id, name } => {
+            handle_identify(state, session, client_id, name)
+        }
+        WebSocketCommand::SetPreferences { preferences } => {
+            handle_set_preferences(state, app_handle, session, preferences)
+        }
+        WebSocketCommand::GetLayers => match layers_for_open_document(state) {
+            Ok(layers) => WebSocketEvent::Layers { layers },
+            Err(e) => WebSocketEvent::from_error(&e),
+        },
+        WebSocketCommand::GetNotes { page } => match notes_for_page(state, page) {
+            Ok(text) => WebSocketEvent::NotesUpdated { page, text },
+            Err(e) => WebSocketEvent::from_error(&e),
+        },
+        WebSocketCommand::SetLayerVisible { id, visible } => {
+            match set_layer_visibility(state, app_handle, &id, visible) {
+                Ok(layers) => WebSocketEvent::LayersChanged { layers },
+                Err(e) => WebSocketEvent::from_error(&e),
+            }
+        }
+        WebSocketCommand::SetRemoteView { enabled } => {
+            // Frames are sent by the connection loop while this is set
+            session.remote_view = enabled;
+            WebSocketEvent::RemoteView {
+                enabled,
+                active: state.remote_view.borrow().is_some(),
+            }
+        }
+        WebSocketCommand::EndShow {
+            close_presenter,
+            webhook_url,
+        } => {
+            let options = EndShowOptions {
+                close_presenter,
+                webhook_url,
+            };
+            match end_show_state(state, app_handle, options) {
+                Ok(summary) => WebSocketEvent::ShowEnded { summary },
+                Err(e) => WebSocketEvent::from_error(&e),
+            }
+        }
+        WebSocketCommand::GetStylePresets => match style_presets(state) {
+            Ok(store) => WebSocketEvent::StylePresets {
+                presets: store.presets,
+                active: store.active,
+            },
+            Err(e) => WebSocketEvent::from_error(&e),
+        },
+        WebSocketCommand::SetActiveStylePreset { id } => {
+            match set_active_preset_state(state, app_handle, id) {
+                Ok(store) => WebSocketEvent::StylePresetsChanged {
+                    presets: store.presets,
+                    active: store.active,
+                },
+                Err(e) => WebSocketEvent::from_error(&e),
+            }
+        }
+        WebSocketCommand::SearchAnnotations { query } => {
+            match search_annotations_state(state, &query) {
+                Ok(matches) => WebSocketEvent::AnnotationSearchResults { query, matches },
+                Err(e) => WebSocketEvent::from_error(&e),
+            }
+        }
+        WebSocketCommand::Batch { commands } => handle_batch(state, app_handle, session, commands),
+        WebSocketCommand::Subscribe { topics } => {
+            session.topics = Some(topics.into_iter().collect());
+            subscribed(session)
+        }
+        WebSocketCommand::Resume { last_seq } => match replay::resume(last_seq) {
+            (Some(events), latest) => {
+                session.last_seq = session.last_seq.max(latest);
+                WebSocketEvent::Resumed {
+                    events: events
+                        .into_iter()
+                        .filter(|sequenced| session.wants(&sequenced.event))
+                        .collect(),
+                    last_seq: latest,
+                }
+            }
+            (None, latest) => WebSocketEvent::ResumeFailed { last_seq: latest },
+        },
+        WebSocketCommand::GetPageImage { page, size } => {
+            page_image_reply(state, app_handle, session, page, size)
+        }
+        WebSocketCommand::SetPageImages { enabled, size } => {
+            // The connection loop sends the current page's image
+            session.page_images = enabled.then_some(size);
+            WebSocketEvent::PageImages { enabled, size }
+        }
     }
 }
 
-fn handle_add_annotation(
+/// Run a command for local input such as a gamepad, as if a client sent it
+///
+/// State changes are broadcast to all clients.
+pub fn handle_local_command(
+    command: WebSocketCommand,
     state: &Arc<AppState>,
     app_handle: &AppHandle,
-    page: u32,
-    annotation: serde_json::Value,
 ) -> WebSocketEvent {
-    // 1. Serialize for storage
-    let annotation_str = match serde_json::to_string(&annotation) {
-        Ok(s) => s,
-        Err(e) => return WebSocketEvent::error(format!("Invalid annotation JSON: {}", e)),
+    let peer_addr = SocketAddr::from(([127, 0, 0, 1], 0));
+    handle_command_as(command, access::Role::Admin, peer_addr, state, app_handle)
+}
+
+/// Run a command from outside a WebSocket connection, such as an HTTP
+/// request, with the given role
+///
+/// State changes are broadcast to all clients.
+pub fn handle_command_as(
+    command: WebSocketCommand,
+    role: access::Role,
+    peer_addr: SocketAddr,
+    state: &Arc<AppState>,
+    app_handle: &AppHandle,
+) -> WebSocketEvent {
+    let mut session = ClientSession::new(peer_addr);
+    session.role = role;
+    let response = handle_command(command, state, app_handle, &mut session);
+    for event in broadcast_events(&response) {
+        let _ = state.broadcast(event.clone());
+    }
+    response
+}
+
+fn handle_batch(
+    state: &Arc<AppState>,
+    app_handle: &AppHandle,
+    session: &mut ClientSession,
+    commands: Vec<WebSocketCommand>,
+) -> WebSocketEvent {
+    if commands.len() > MAX_BATCH_LEN {
+        return WebSocketEvent::error_code(
+            ErrorCode::InvalidCommand,
+            format!("BATCH takes at most {MAX_BATCH_LEN} commands"),
+        );
+    }
+    if commands
+        .iter()
+        .any(|command| matches!(command, WebSocketCommand::Batch { .. }))
+    {
+        return WebSocketEvent::error_code(
+            ErrorCode::InvalidCommand,
+            "BATCH can't contain another BATCH",
+        );
+    }
+    // Ending the show can't be undone, so nothing may fail after it
+    if let Some((_, init)) = commands.split_last() {
+        if init
+            .iter()
+            .any(|command| matches!(command, WebSocketCommand::EndShow { .. }))
+        {
+            return WebSocketEvent::error_code(
+                ErrorCode::InvalidCommand,
+                "END_SHOW can only be the last command of a BATCH",
+            );
+        }
+    }
+
+    if let Some((index, reply)) = check_batch(state, &commands) {
+        debug!(client = %session.label(), index, "BATCH refused");
+        return numbered(index, reply);
+    }
+
+    let snapshot = match BatchSnapshot::take(state, &commands) {
+        Ok(snapshot) => snapshot,
+        Err(e) => return WebSocketEvent::from_error(&e),
     };
+    let mut results = run_batch(commands, |command| {
+        run_command(command, state, app_handle, session)
+    });
+    match results.pop() {
+        Some(reply) if reply.is_failure() => {
+            let index = results.len();
+            debug!(client = %session.label(), index, "BATCH failed, undoing it");
+            snapshot.restore(state, app_handle);
+            numbered(index, reply)
+        }
+        last => {
+            results.extend(last);
+            WebSocketEvent::BatchResults { results }
+        }
+    }
+}
 
-    // 2. Update State
-    if let Err(e) = state.annotations.write().map(|mut map| {
-        map.entry(page).or_default().push(annotation_str.clone());
-    }) {
-        return WebSocketEvent::error(e.to_string());
+/// Replies to `commands` run in order, up to the first that fails
+fn run_batch(
+    commands: Vec<WebSocketCommand>,
+    mut run: impl FnMut(WebSocketCommand) -> WebSocketEvent,
+) -> Vec<WebSocketEvent> {
+    let mut results = Vec::with_capacity(commands.len());
+    for command in commands {
+        let result = run(command);
+        let failed = result.is_failure();
+        results.push(result);
+        if failed {
+            break;
+        }
     }
+    results
+}
 
-    // 3. Emit to Host UI (Tauri)
-    emit_annotation_added(app_handle, page, annotation.clone());
+/// A batch command's error, its message starting with the command's position
+fn numbered(index: usize, reply: WebSocketEvent) -> WebSocketEvent {
+    match reply {
+        WebSocketEvent::Error {
+            message,
+            code,
+            page,
+            total_pages,
+        } => WebSocketEvent::Error {
+            message: format!("BATCH command {}: {message}", index + 1),
+            code,
+            page,
+            total_pages,
+        },
+        reply => reply,
+    }
+}
 
-    // 4. Return event for broadcast
-    // We construct a partial update for just this page
-    let mut updates = std::collections::HashMap::new();
-    updates.insert(page, vec![annotation]);
+/// The index and reply of the first command of a batch that can't run
+///
+/// Commands are checked against the state the ones before them leave: the
+/// page they move to and the annotations they add. Commands that don't
+/// depend on either are checked when they run.
+fn check_batch(state: &AppState, commands: &[WebSocketCommand]) -> Option<(usize, WebSocketEvent)> {
+    let mut pdf_state = match state.get_pdf_state() {
+        Ok(pdf_state) => pdf_state,
+        Err(e) => return Some((0, WebSocketEvent::from_error(&e))),
+    };
+    let mut added = HashSet::new();
+    for (index, command) in commands.iter().enumerate() {
+        let refused = match navigation_target(state, command, &pdf_state) {
+            Some(Ok(page)) => {
+                pdf_state.current_page = page;
+                None
+            }
+            Some(Err(reply)) => Some(reply),
+            None => check_annotation_command(state, command, &mut added),
+        };
+        if let Some(reply) = refused {
+            return Some((index, reply));
+        }
+    }
+    None
+}
 
-    WebSocketEvent::AnnotationsUpdated {
-        annotations: updates,
+/// Why an annotation command can't run, if it can't; `added` holds the IDs
+/// of annotations added earlier in the batch
+fn check_annotation_command(
+    state: &AppState,
+    command: &WebSocketCommand,
+    added: &mut HashSet<String>,
+) -> Option<WebSocketEvent> {
+    let annotation_id =
+        |annotation: &serde_json::Value| annotation.get("id")?.as_str().map(str::to_string);
+    let (page, id) = match command {
+        WebSocketCommand::AddAnnotation { annotation, .. } => {
+            if !annotation.is_object() {
+                return Some(not_an_object());
+            }
+            added.extend(annotation_id(annotation));
+            return None;
+        }
+        WebSocketCommand::UpdateAnnotation {
+            page, annotation, ..
+        } => {
+            if !annotation.is_object() {
+                return Some(not_an_object());
+            }
+            match annotation_id(annotation) {
+                Some(id) => (*page, id),
+                None => {
+                    return Some(WebSocketEvent::error_code(
+                        ErrorCode::InvalidArgument,
+                        "Annotation has no id",
+                    ))
+                }
+            }
+        }
+        WebSocketCommand::DeleteAnnotation { page, id, .. } => (*page, id.clone()),
+        _ => return None,
+    };
+    if added.contains(&id) {
+        return None;
     }
+    if !annotation_known(state, &id) {
+        return Some(unknown_annotation(page, &id));
+    }
+    check_unlocked(stored_annotation(state, &id).as_ref(), Some(&id))
 }
 
-fn handle_clear_annotations(state: &Arc<AppState>, app_handle: &AppHandle) -> WebSocketEvent {
-    // 1. Update State
-    if let Err(e) = state.annotations.write().map(|mut map| map.clear()) {
-        return WebSocketEvent::error(e.to_string());
+fn handle_hello(
+    app_handle: &AppHandle,
+    session: &mut ClientSession,
+    name: String,
+    kind: String,
+    version: Option<String>,
+    token: Option<String>,
+) -> WebSocketEvent {
+    let info = ClientInfo {
+        name: name.trim().to_string(),
+        kind: kind.trim().to_string(),
+        version: version.map(|v| v.trim().to_string()),
+    };
+    let fields = [
+        ("name", Some(&info.name)),
+        ("kind", Some(&info.kind)),
+        ("version", info.version.as_ref()),
+    ];
+    for (field, value) in fields {
+        // Shown to the operator like display names, so held to the same rules
+        if value.is_some_and(|value| preferences::validate_display_name(value).is_err()) {
+            return WebSocketEvent::error_code(
+                ErrorCode::InvalidArgument,
+                format!(
+                    "HELLO {field} must be 1-{} characters without control characters",
+                    preferences::MAX_DISPLAY_NAME_LEN
+                ),
+            );
+        }
     }
 
-    // 2. Emit to Host UI
-    emit_annotations_cleared(app_handle);
+    if let Some(token) = token {
+        let role = access::access()
+            .read()
+            .ok()
+            .and_then(|config| config.role_for(&token));
+        let Some(role) = role else {
+            warn!(peer = %session.peer_addr, name = %info.name, "Unknown access token");
+            return WebSocketEvent::error_code(ErrorCode::Forbidden, "Unknown access token");
+        };
+        session.role = role;
+    }
 
-    // 3. Return event for broadcast
-    WebSocketEvent::AnnotationsCleared
+    info!(
+        peer = %session.peer_addr,
+        name = %info.name,
+        kind = %info.kind,
+        version = ?info.version,
+        role = session.role.as_str(),
+        "Client said hello"
+    );
+    emit_client_hello(app_handle, session.peer_addr, &info);
+    session.info = Some(info);
+    WebSocketEvent::welcome(session.role)
 }
 
-fn handle_next_page(state: &Arc<AppState>, app_handle: &AppHandle) -> WebSocketEvent {
-    let pdf_state = match state.get_pdf_state() {
-        Ok(s) => s,
-        Err(e) => return WebSocketEvent::error(e.to_string()),
+fn handle_identify(
+    state: &Arc<AppState>,
+    session: &mut ClientSession,
+    client_id: String,
+    name: Option<String>,
+) -> WebSocketEvent {
+    if let Err(e) = preferences::validate_client_id(&client_id) {
+        return WebSocketEvent::from_error(&e);
+    }
+    let name = name.map(|n| n.trim().to_string());
+    if let Some(Err(e)) = name.as_deref().map(preferences::validate_display_name) {
+        return WebSocketEvent::from_error(&e);
+    }
+
+    let preferences = match state.get_client_preferences(&client_id) {
+        Ok(prefs) => prefs.unwrap_or_default(),
+        Err(e) => return WebSocketEvent::from_error(&e),
     };
 
-    if !pdf_state.is_loaded {
-        return WebSocketEvent::error("No PDF is currently open");
+    info!(peer = %session.peer_addr, client_id = %client_id, name = ?name, "Client identified");
+    session.client_id = Some(client_id.clone());
+    session.name = name;
+    apply_preferred_topics(session, &preferences);
+
+    WebSocketEvent::ClientPreferences {
+        client_id,
+        preferences,
     }
+}
+
+fn handle_set_preferences(
+    state: &Arc<AppState>,
+    app_handle: &AppHandle,
+    session: &mut ClientSession,
+    preferences: ClientPreferences,
+) -> WebSocketEvent {
+    let Some(client_id) = session.client_id.clone() else {
+        return WebSocketEvent::error_code(
+            ErrorCode::InvalidCommand,
+            "Send IDENTIFY before setting preferences",
+        );
+    };
 
-    let new_page = (pdf_state.current_page + 1).min(pdf_state.total_pages);
-    if new_page == pdf_state.current_page {
-        return WebSocketEvent::error("Already on last page");
+    if let Err(e) = state.set_client_preferences(&client_id, preferences.clone()) {
+        return WebSocketEvent::from_error(&e);
     }
 
-    // Update state
-    if let Err(e) = state.update_pdf_state(|s| {
-        s.current_page = new_page;
-    }) {
-        return WebSocketEvent::error(e.to_string());
+    // Persist so the client resumes its configuration after an app restart
+    let result = state
+        .client_preferences
+        .read()
+        .map_err(|e| e.to_string())
+        .and_then(|map| {
+            save_settings(app_handle, preferences::PREFERENCES_FILE, &*map)
+                .map_err(|e| e.to_string())
+        });
+    if let Err(e) = result {
+        warn!(error = %e, "Failed to persist client preferences");
     }
 
-    // Emit event to frontend
-    emit_page_changed(app_handle, new_page, pdf_state.total_pages);
+    apply_preferred_topics(session, &preferences);
+    WebSocketEvent::ClientPreferences {
+        client_id,
+        preferences,
+    }
+}
 
-    WebSocketEvent::PageChanged {
-        page: new_page,
-        total_pages: pdf_state.total_pages,
+/// Subscribe to the topics stored in a client's preferences, if it has any;
+/// names that aren't topics are ignored
+fn apply_preferred_topics(session: &mut ClientSession, preferences: &ClientPreferences) {
+    if !preferences.topics.is_empty() {
+        session.topics = Some(
+            preferences
+                .topics
+                .iter()
+                .filter_map(|name| Topic::parse(name))
+                .collect(),
+        );
     }
 }
 
-fn handle_previous_page(state: &Arc<AppState>, app_handle: &AppHandle) -> WebSocketEvent {
+/// Reply with a page image, queueing its data to be sent right after, or
+/// have the webview render it and send it once published
+pub(crate) fn page_image_reply(
+    state: &Arc<AppState>,
+    app_handle: &AppHandle,
+    session: &mut ClientSession,
+    page: u32,
+    size: ImageSize,
+) -> WebSocketEvent {
     let pdf_state = match state.get_pdf_state() {
-        Ok(s) => s,
-        Err(e) => return WebSocketEvent::error(e.to_string()),
+        Ok(pdf_state) => pdf_state,
+        Err(e) => return WebSocketEvent::from_error(&e),
     };
-
     if !pdf_state.is_loaded {
-        return WebSocketEvent::error("No PDF is currently open");
+        return WebSocketEvent::from_error(&StreamSlateError::NoDocument);
+    }
+    if page < 1 || page > pdf_state.total_pages {
+        return WebSocketEvent::from_error(&StreamSlateError::PageOutOfRange {
+            page,
+            total_pages: pdf_state.total_pages,
+        });
     }
 
-    let new_page = pdf_state.current_page.saturating_sub(1).max(1);
-    if new_page == pdf_state.current_page {
-        return WebSocketEvent::error("Already on first page");
+    let document = pdf_state.current_file.as_deref();
+    match state.page_images.get(document, page, size) {
+        Some(image) => {
+            session.binary_out.push(image.data.clone());
+            image.header()
+        }
+        None => {
+            session.pending_images.insert((page, size));
+            page_images::request_render(app_handle, page, size);
+            WebSocketEvent::PageImagePending { page, size }
+        }
     }
+}
 
-    // Update state
-    if let Err(e) = state.update_pdf_state(|s| {
-        s.current_page = new_page;
-    }) {
-        return WebSocketEvent::error(e.to_string());
+fn subscribed(session: &ClientSession) -> WebSocketEvent {
+    let topics = match &session.topics {
+        Some(topics) => topics.iter().copied().collect(),
+        None => Topic::ALL.to_vec(),
+    };
+    WebSocketEvent::Subscribed { topics }
+}
+
+fn handle_add_annotation(
+    state: &Arc<AppState>,
+    app_handle: &AppHandle,
+    session: &ClientSession,
+    page: u32,
+    mut annotation: serde_json::Value,
+    clock: Option<u64>,
+) -> WebSocketEvent {
+    // Attribute to the identified client; remote clients can't name another author
+    let Some(object) = annotation.as_object_mut() else {
+        return not_an_object();
+    };
+    match session.author() {
+        Some(author) => {
+            object.insert("author".to_string(), author.into());
+        }
+        None => {
+            object.remove("author");
+        }
+    }
+    // Draw with the pen picked on the host or a Stream Deck
+    if let Some(preset) = state
+        .style_presets
+        .read()
+        .ok()
+        .and_then(|store| store.active_preset().cloned())
+    {
+        preset.apply(object);
+    }
+    if let Some(rejected) = normalize_client_annotation(state, page, object) {
+        return rejected;
     }
+    if let Err(e) = clean_stroke_json(state, object) {
+        return WebSocketEvent::from_error(&e);
+    }
+    mark_ephemeral_json(state, object);
+    let id = object
+        .get("id")
+        .and_then(|id| id.as_str())
+        .map(str::to_string);
+    merge_annotation_edit(
+        state,
+        app_handle,
+        session,
+        clock,
+        page,
+        id.as_deref(),
+        |crdt, map, stamp| crdt.merge_put(map, page, object, stamp),
+    )
+}
 
-    // Emit event to frontend
-    emit_page_changed(app_handle, new_page, pdf_state.total_pages);
+fn handle_update_annotation(
+    state: &Arc<AppState>,
+    app_handle: &AppHandle,
+    session: &ClientSession,
+    page: u32,
+    mut annotation: serde_json::Value,
+    clock: Option<u64>,
+) -> WebSocketEvent {
+    let Some(object) = annotation.as_object_mut() else {
+        return not_an_object();
+    };
+    let Some(id) = object
+        .get("id")
+        .and_then(|id| id.as_str())
+        .map(str::to_string)
+    else {
+        return WebSocketEvent::error_code(ErrorCode::InvalidArgument, "Annotation has no id");
+    };
+    if !annotation_known(state, &id) {
+        return unknown_annotation(page, &id);
+    }
+    // Updating doesn't re-attribute
+    object.remove("author");
+    if let Some(rejected) = normalize_client_annotation(state, page, object) {
+        return rejected;
+    }
+    merge_annotation_edit(
+        state,
+        app_handle,
+        session,
+        clock,
+        page,
+        Some(&id),
+        |crdt, map, stamp| crdt.merge_put(map, page, object, stamp),
+    )
+}
 
-    WebSocketEvent::PageChanged {
-        page: new_page,
-        total_pages: pdf_state.total_pages,
+fn handle_delete_annotation(
+    state: &Arc<AppState>,
+    app_handle: &AppHandle,
+    session: &ClientSession,
+    page: u32,
+    id: String,
+    clock: Option<u64>,
+) -> WebSocketEvent {
+    if !annotation_known(state, &id) {
+        return unknown_annotation(page, &id);
     }
+    if let Some(rejected) = check_unlocked(stored_annotation(state, &id).as_ref(), Some(&id)) {
+        return rejected;
+    }
+    merge_annotation_edit(
+        state,
+        app_handle,
+        session,
+        clock,
+        page,
+        Some(&id),
+        |crdt, map, stamp| crdt.merge_delete(map, &id, stamp).into_iter().collect(),
+    )
 }
 
-fn handle_go_to_page(state: &Arc<AppState>, app_handle: &AppHandle, page: u32) -> WebSocketEvent {
-    let pdf_state = match state.get_pdf_state() {
-        Ok(s) => s,
-        Err(e) => return WebSocketEvent::error(e.to_string()),
+fn not_an_object() -> WebSocketEvent {
+    WebSocketEvent::error_code(
+        ErrorCode::InvalidArgument,
+        "Annotation must be a JSON object",
+    )
+}
+
+fn unknown_annotation(page: u32, id: &str) -> WebSocketEvent {
+    WebSocketEvent::error_code(
+        ErrorCode::NotFound,
+        format!("No annotation {id} on page {page}"),
+    )
+}
+
+/// Validate a client's annotation, normalizing it in place; the reply to
+/// send instead if it is rejected
+///
+/// Updates are checked as applied to the stored annotation. Only the host
+/// locks annotations, and locked ones can't be changed.
+fn normalize_client_annotation(
+    state: &AppState,
+    page: u32,
+    annotation: &mut serde_json::Map<String, serde_json::Value>,
+) -> Option<WebSocketEvent> {
+    let id = annotation
+        .get("id")
+        .and_then(|id| id.as_str())
+        .map(str::to_string);
+    let current = id.as_deref().and_then(|id| stored_annotation(state, id));
+    if let Some(rejected) = check_unlocked(current.as_ref(), id.as_deref()) {
+        return Some(rejected);
+    }
+    annotation.remove("locked");
+    if let Err(errors) = normalize_annotation_json(annotation, current.as_ref(), page) {
+        return Some(WebSocketEvent::AnnotationRejected { page, id, errors });
+    }
+    check_stamp_json(state, annotation)
+        .err()
+        .map(|e| WebSocketEvent::from_error(&e))
+}
+
+/// Stored fields of annotation `id`
+fn stored_annotation(
+    state: &AppState,
+    id: &str,
+) -> Option<serde_json::Map<String, serde_json::Value>> {
+    let map = state.annotations.read().ok()?;
+    let stored = map
+        .values()
+        .flatten()
+        .find(|a| history::annotation_id(a) == id)?;
+    match serde_json::from_str(stored) {
+        Ok(serde_json::Value::Object(object)) => Some(object),
+        _ => None,
+    }
+}
+
+/// The reply refusing a change to `annotation` if it is locked
+fn check_unlocked(
+    annotation: Option<&serde_json::Map<String, serde_json::Value>>,
+    id: Option<&str>,
+) -> Option<WebSocketEvent> {
+    let locked = annotation
+        .and_then(|a| a.get("locked"))
+        .and_then(|locked| locked.as_bool())
+        .unwrap_or(false);
+    locked.then(|| {
+        WebSocketEvent::error_code(
+            ErrorCode::Locked,
+            format!("Annotation {} is locked", id.unwrap_or_default()),
+        )
+    })
+}
+
+/// Whether annotation `id` exists or was deleted in this document
+fn annotation_known(state: &AppState, id: &str) -> bool {
+    let exists = state.annotations.read().is_ok_and(|map| {
+        map.values()
+            .flatten()
+            .any(|a| history::annotation_id(a) == id)
+    });
+    exists
+        || state
+            .annotation_crdt
+            .lock()
+            .is_ok_and(|crdt| crdt.stamp_of(id).is_some())
+}
+
+/// Stamp a client's edit to annotation `id` on `page`, merge it into the
+/// annotations and announce the result
+///
+/// An edit that loses to a later one changes nothing; the reply then carries
+/// the annotation's current value so the client converges on it.
+#[allow(clippy::too_many_arguments)]
+fn merge_annotation_edit(
+    state: &Arc<AppState>,
+    app_handle: &AppHandle,
+    session: &ClientSession,
+    clock: Option<u64>,
+    page: u32,
+    id: Option<&str>,
+    merge: impl FnOnce(&mut AnnotationCrdt, &mut PageAnnotations, &Stamp) -> Vec<AnnotationEdit>,
+) -> WebSocketEvent {
+    let (edits, current, clock) = {
+        let mut map = match state.annotations.write() {
+            Ok(map) => map,
+            Err(e) => return WebSocketEvent::error_code(ErrorCode::Internal, e.to_string()),
+        };
+        let mut crdt = match state.annotation_crdt.lock() {
+            Ok(crdt) => crdt,
+            Err(e) => return WebSocketEvent::error_code(ErrorCode::Internal, e.to_string()),
+        };
+        let stamp = crdt.stamp(&session.replica(), clock);
+        let edits = merge(&mut crdt, &mut map, &stamp);
+
+        // Where the annotation now stands, for the reply
+        let id = edits
+            .last()
+            .map_or(id.unwrap_or_default(), |edit| edit.id.as_str());
+        let current = map.iter().find_map(|(page, list)| {
+            list.iter()
+                .find(|a| history::annotation_id(a) == id)
+                .and_then(|a| serde_json::from_str::<serde_json::Value>(a).ok())
+                .map(|value| (*page, value))
+        });
+        let current = (id.to_string(), current);
+        (edits, current, crdt.clock())
     };
 
-    if !pdf_state.is_loaded {
-        return WebSocketEvent::error("No PDF is currently open");
+    let added = edits.len() == 1 && edits[0].before.is_none();
+    let page = edits.first().map_or(page, |edit| edit.page);
+    if !edits.is_empty() {
+        let reason = match &current.1 {
+            None => "delete",
+            Some(_) if added => "add",
+            Some(_) => "update",
+        };
+        state.record_merged_annotation_edits(edits);
+        state.snapshot_annotations(reason);
     }
 
-    if page < 1 || page > pdf_state.total_pages {
-        return WebSocketEvent::error(format!(
-            "Page {} is out of range (1-{})",
-            page, pdf_state.total_pages
-        ));
+    match current {
+        (id, None) => {
+            emit_annotation_deleted(app_handle, page, &id);
+            WebSocketEvent::AnnotationDeleted { page, id, clock }
+        }
+        (_, Some((page, annotation))) if added => {
+            emit_annotation_added(app_handle, page, annotation.clone());
+            WebSocketEvent::AnnotationAdded {
+                page,
+                annotation,
+                clock,
+            }
+        }
+        (_, Some((page, annotation))) => {
+            emit_annotation_updated(app_handle, page, annotation.clone());
+            WebSocketEvent::AnnotationUpdated {
+                page,
+                annotation,
+                clock,
+            }
+        }
+    }
+}
+
+fn handle_clear_annotations(state: &Arc<AppState>, app_handle: &AppHandle) -> WebSocketEvent {
+    // 1. Update State, keeping locked annotations
+    let kept = match state.annotations.write() {
+        Ok(mut map) => {
+            let previous = std::mem::take(&mut *map);
+            let pages = keep_locked(&previous, &mut map);
+            state.record_annotation_edits(history::diff(&previous, &map));
+            page_updates(&map, pages)
+        }
+        Err(e) => return WebSocketEvent::error_code(ErrorCode::Internal, e.to_string()),
+    };
+    state.snapshot_annotations("clear");
+
+    // 2. Emit to Host UI
+    if !kept.is_empty() {
+        publish_annotation_updates(state, app_handle, &kept);
+        return WebSocketEvent::AnnotationsUpdated { annotations: kept };
     }
+    emit_annotations_cleared(app_handle);
+
+    // 3. Return event for broadcast
+    WebSocketEvent::AnnotationsCleared
+}
+
+fn handle_annotation_history(
+    state: &Arc<AppState>,
+    app_handle: &AppHandle,
+    redo: bool,
+) -> WebSocketEvent {
+    match step_annotation_history(state, app_handle, redo) {
+        Ok(annotations) => WebSocketEvent::AnnotationsUpdated { annotations },
+        Err(e) => WebSocketEvent::from_error(&e),
+    }
+}
+
+fn handle_get_page_words(state: &Arc<AppState>, page: u32) -> WebSocketEvent {
+    match words_for_page(state, page) {
+        Ok(words) => WebSocketEvent::PageWords { page, words },
+        Err(e) => WebSocketEvent::from_error(&e),
+    }
+}
+
+/// Move to the page of a page or section navigation command
+fn handle_navigation(
+    state: &Arc<AppState>,
+    app_handle: &AppHandle,
+    command: &WebSocketCommand,
+) -> WebSocketEvent {
+    let pdf_state = match state.get_pdf_state() {
+        Ok(s) => s,
+        Err(e) => return WebSocketEvent::from_error(&e),
+    };
+    let page = match navigation_target(state, command, &pdf_state) {
+        Some(Ok(page)) => page,
+        Some(Err(reply)) => return reply,
+        None => return WebSocketEvent::error_code(ErrorCode::Internal, "Not a navigation command"),
+    };
 
     // Update state
     if let Err(e) = state.update_pdf_state(|s| {
         s.current_page = page;
     }) {
-        return WebSocketEvent::error(e.to_string());
+        return WebSocketEvent::from_error(&e);
     }
 
     // Emit event to frontend
     emit_page_changed(app_handle, page, pdf_state.total_pages);
 
+    page_changed(state, page, pdf_state.total_pages)
+}
+
+/// The page a navigation command moves to from `pdf_state`, or the reply
+/// refusing it; `None` for other commands
+fn navigation_target(
+    state: &AppState,
+    command: &WebSocketCommand,
+    pdf_state: &PdfState,
+) -> Option<Result<u32, WebSocketEvent>> {
+    use WebSocketCommand as C;
+
+    if !matches!(
+        command,
+        C::NextPage | C::PreviousPage | C::GoToPage { .. } | C::NextSection | C::PreviousSection
+    ) {
+        return None;
+    }
+    if !pdf_state.is_loaded {
+        return Some(Err(WebSocketEvent::from_error(
+            &StreamSlateError::NoDocument,
+        )));
+    }
+
+    let (current, total_pages) = (pdf_state.current_page, pdf_state.total_pages);
+    Some(match *command {
+        C::NextPage if current >= total_pages => Err(WebSocketEvent::error_code(
+            ErrorCode::EndOfDocument,
+            "Already on last page",
+        )),
+        C::NextPage => Ok(current + 1),
+        C::PreviousPage if current <= 1 => Err(WebSocketEvent::error_code(
+            ErrorCode::EndOfDocument,
+            "Already on first page",
+        )),
+        C::PreviousPage => Ok(current - 1),
+        C::GoToPage { page } if page < 1 || page > total_pages => Err(WebSocketEvent::from_error(
+            &StreamSlateError::PageOutOfRange { page, total_pages },
+        )),
+        C::GoToPage { page } => Ok(page),
+        _ => section_target(state, current, matches!(command, C::NextSection)),
+    })
+}
+
+/// Start of the next or previous section from `current`
+fn section_target(state: &AppState, current: u32, forward: bool) -> Result<u32, WebSocketEvent> {
+    let sections = sections_for_open_document(state).map_err(|e| WebSocketEvent::from_error(&e))?;
+    let target = if forward {
+        next_section_page(&sections, current)
+    } else {
+        previous_section_page(&sections, current)
+    };
+    target.ok_or_else(|| {
+        if sections.is_empty() {
+            WebSocketEvent::error_code(ErrorCode::NotFound, "No sections defined")
+        } else if forward {
+            WebSocketEvent::error_code(ErrorCode::EndOfDocument, "Already in the last section")
+        } else {
+            WebSocketEvent::error_code(ErrorCode::EndOfDocument, "Already in the first section")
+        }
+    })
+}
+
+/// `PAGE_CHANGED` with the outline section the page falls in
+pub(super) fn page_changed(state: &AppState, page: u32, total_pages: u32) -> WebSocketEvent {
     WebSocketEvent::PageChanged {
         page,
-        total_pages: pdf_state.total_pages,
+        total_pages,
+        section: section_progress(state, page),
+    }
+}
+
+fn handle_add_reaction(
+    state: &Arc<AppState>,
+    session: &mut ClientSession,
+    page: u32,
+    emoji: String,
+) -> WebSocketEvent {
+    if !reactions::is_allowed(&emoji) {
+        return WebSocketEvent::error_code(
+            ErrorCode::InvalidArgument,
+            format!("Unsupported reaction: {emoji}"),
+        );
+    }
+    let total_pages = match state.get_pdf_state() {
+        Ok(s) if s.is_loaded => s.total_pages,
+        Ok(_) => return WebSocketEvent::from_error(&StreamSlateError::NoDocument),
+        Err(e) => return WebSocketEvent::from_error(&e),
+    };
+    if page < 1 || page > total_pages {
+        return WebSocketEvent::from_error(&StreamSlateError::PageOutOfRange { page, total_pages });
+    }
+
+    let now = Instant::now();
+    if !session.reaction_limiter.try_acquire(now) {
+        return WebSocketEvent::error_code(ErrorCode::RateLimited, "Too many reactions, slow down");
+    }
+
+    match state.reactions.lock() {
+        Ok(mut board) => {
+            board.add(page, &emoji, now);
+            WebSocketEvent::ReactionsUpdated {
+                page,
+                reactions: board.counts(page, now),
+            }
+        }
+        Err(e) => WebSocketEvent::error_code(ErrorCode::Internal, e.to_string()),
     }
 }
 
+fn handle_pointer_moved(
+    state: &Arc<AppState>,
+    app_handle: &AppHandle,
+    session: &mut ClientSession,
+    page: u32,
+    x: f64,
+    y: f64,
+) -> WebSocketEvent {
+    if !(0.0..=1.0).contains(&x) || !(0.0..=1.0).contains(&y) {
+        return WebSocketEvent::error_code(
+            ErrorCode::InvalidArgument,
+            "Pointer position must be between 0 and 1",
+        );
+    }
+    let total_pages = match state.get_pdf_state() {
+        Ok(s) if s.is_loaded => s.total_pages,
+        Ok(_) => return WebSocketEvent::from_error(&StreamSlateError::NoDocument),
+        Err(e) => return WebSocketEvent::from_error(&e),
+    };
+    if page < 1 || page > total_pages {
+        return WebSocketEvent::from_error(&StreamSlateError::PageOutOfRange { page, total_pages });
+    }
+    if !session.pointer_limiter.try_acquire(Instant::now()) {
+        return WebSocketEvent::error_code(
+            ErrorCode::RateLimited,
+            "Too many pointer moves, slow down",
+        );
+    }
+
+    let client = session.author().map(str::to_string);
+    emit_pointer_moved(app_handle, page, x, y, client.as_deref());
+    WebSocketEvent::PointerMoved { page, x, y, client }
+}
+
 fn handle_get_state(state: &Arc<AppState>) -> WebSocketEvent {
     let pdf_state = match state.get_pdf_state() {
         Ok(s) => s,
-        Err(e) => return WebSocketEvent::error(e.to_string()),
+        Err(e) => return WebSocketEvent::from_error(&e),
     };
 
     let presenter_state = match state.get_presenter_state() {
         Ok(s) => s,
-        Err(e) => return WebSocketEvent::error(e.to_string()),
+        Err(e) => return WebSocketEvent::from_error(&e),
     };
 
     WebSocketEvent::State {
@@ -208,6 +1194,9 @@ fn handle_get_state(state: &Arc<AppState>) -> WebSocketEvent {
         pdf_path: pdf_state.current_file.clone(),
         pdf_title: None, // Title not stored in state currently
         presenter_active: presenter_state.is_active,
+        smart_invert: pdf_state.smart_invert,
+        section: section_progress(state, pdf_state.current_page),
+        annotation_clock: state.annotation_clock(),
     }
 }
 
@@ -218,7 +1207,7 @@ fn handle_set_zoom(state: &Arc<AppState>, app_handle: &AppHandle, zoom: f64) ->
     if let Err(e) = state.update_pdf_state(|s| {
         s.zoom_level = zoom;
     }) {
-        return WebSocketEvent::error(e.to_string());
+        return WebSocketEvent::from_error(&e);
     }
 
     // Emit event to frontend
@@ -227,10 +1216,45 @@ fn handle_set_zoom(state: &Arc<AppState>, app_handle: &AppHandle, zoom: f64) ->
     WebSocketEvent::ZoomChanged { zoom }
 }
 
+fn handle_set_smart_invert(
+    state: &Arc<AppState>,
+    app_handle: &AppHandle,
+    enabled: bool,
+) -> WebSocketEvent {
+    match set_smart_invert_state(state, app_handle, enabled) {
+        Ok(()) => WebSocketEvent::SmartInvertChanged { enabled },
+        Err(e) => WebSocketEvent::from_error(&e),
+    }
+}
+
+fn handle_media_action(
+    state: &Arc<AppState>,
+    app_handle: &AppHandle,
+    id: &str,
+    action: MediaAction,
+) -> WebSocketEvent {
+    match control_media(state, app_handle, id, action) {
+        Ok(playback) => WebSocketEvent::MediaPlaybackChanged { playback },
+        Err(e) => WebSocketEvent::from_error(&e),
+    }
+}
+
+fn handle_set_output_viewport(
+    state: &Arc<AppState>,
+    app_handle: &AppHandle,
+    viewport: OutputViewport,
+    duration_ms: Option<u32>,
+) -> WebSocketEvent {
+    match set_output_viewport_state(state, app_handle, viewport, duration_ms) {
+        Ok(viewport) => WebSocketEvent::OutputViewportChanged { viewport },
+        Err(e) => WebSocketEvent::from_error(&e),
+    }
+}
+
 fn handle_toggle_presenter(state: &Arc<AppState>, app_handle: &AppHandle) -> WebSocketEvent {
     let presenter_state = match state.get_presenter_state() {
         Ok(s) => s,
-        Err(e) => return WebSocketEvent::error(e.to_string()),
+        Err(e) => return WebSocketEvent::from_error(&e),
     };
 
     let new_active = !presenter_state.is_active;
@@ -239,7 +1263,7 @@ fn handle_toggle_presenter(state: &Arc<AppState>, app_handle: &AppHandle) -> Web
     if let Err(e) = state.update_presenter_state(|s| {
         s.is_active = new_active;
     }) {
-        return WebSocketEvent::error(e.to_string());
+        return WebSocketEvent::from_error(&e);
     }
 
     // Emit event to frontend
@@ -250,7 +1274,7 @@ fn handle_toggle_presenter(state: &Arc<AppState>, app_handle: &AppHandle) -> Web
 
 // Helper functions to emit events to the frontend
 
-fn emit_page_changed(app_handle: &AppHandle, page: u32, total_pages: u32) {
+pub(super) fn emit_page_changed(app_handle: &AppHandle, page: u32, total_pages: u32) {
     use tauri::Emitter;
 
     #[derive(serde::Serialize, Clone)]
@@ -264,7 +1288,26 @@ fn emit_page_changed(app_handle: &AppHandle, page: u32, total_pages: u32) {
     }
 }
 
-fn emit_zoom_changed(app_handle: &AppHandle, zoom: f64) {
+fn emit_client_hello(app_handle: &AppHandle, peer: SocketAddr, info: &ClientInfo) {
+    use tauri::Emitter;
+
+    #[derive(Clone, serde::Serialize)]
+    struct ClientHelloPayload<'a> {
+        peer: String,
+        #[serde(flatten)]
+        info: &'a ClientInfo,
+    }
+
+    let payload = ClientHelloPayload {
+        peer: peer.to_string(),
+        info,
+    };
+    if let Err(e) = app_handle.emit("websocket-client-hello", payload) {
+        warn!(error = %e, "Failed to emit websocket-client-hello event");
+    }
+}
+
+pub(super) fn emit_zoom_changed(app_handle: &AppHandle, zoom: f64) {
     use tauri::Emitter;
 
     #[derive(serde::Serialize, Clone)]
@@ -277,7 +1320,7 @@ fn emit_zoom_changed(app_handle: &AppHandle, zoom: f64) {
     }
 }
 
-fn emit_presenter_changed(app_handle: &AppHandle, active: bool) {
+pub(super) fn emit_presenter_changed(app_handle: &AppHandle, active: bool) {
     use tauri::Emitter;
 
     #[derive(serde::Serialize, Clone)]
@@ -290,6 +1333,22 @@ fn emit_presenter_changed(app_handle: &AppHandle, active: bool) {
     }
 }
 
+fn emit_pointer_moved(app_handle: &AppHandle, page: u32, x: f64, y: f64, client: Option<&str>) {
+    use tauri::Emitter;
+
+    #[derive(serde::Serialize, Clone)]
+    struct PointerMovedPayload<'a> {
+        page: u32,
+        x: f64,
+        y: f64,
+        client: Option<&'a str>,
+    }
+
+    if let Err(e) = app_handle.emit("pointer-moved", PointerMovedPayload { page, x, y, client }) {
+        warn!(error = %e, "Failed to emit pointer-moved event");
+    }
+}
+
 fn emit_annotation_added(app_handle: &AppHandle, page: u32, annotation: serde_json::Value) {
     use tauri::Emitter;
 
@@ -307,6 +1366,37 @@ fn emit_annotation_added(app_handle: &AppHandle, page: u32, annotation: serde_js
     }
 }
 
+fn emit_annotation_updated(app_handle: &AppHandle, page: u32, annotation: serde_json::Value) {
+    use tauri::Emitter;
+
+    #[derive(serde::Serialize, Clone)]
+    struct AnnotationUpdatedPayload {
+        page: u32,
+        annotation: serde_json::Value,
+    }
+
+    if let Err(e) = app_handle.emit(
+        "annotation-updated",
+        AnnotationUpdatedPayload { page, annotation },
+    ) {
+        warn!(error = %e, "Failed to emit annotation-updated event");
+    }
+}
+
+fn emit_annotation_deleted(app_handle: &AppHandle, page: u32, id: &str) {
+    use tauri::Emitter;
+
+    #[derive(serde::Serialize, Clone)]
+    struct AnnotationDeletedPayload<'a> {
+        page: u32,
+        id: &'a str,
+    }
+
+    if let Err(e) = app_handle.emit("annotation-deleted", AnnotationDeletedPayload { page, id }) {
+        warn!(error = %e, "Failed to emit annotation-deleted event");
+    }
+}
+
 fn emit_annotations_cleared(app_handle: &AppHandle) {
     use tauri::Emitter;
 
@@ -314,3 +1404,137 @@ fn emit_annotations_cleared(app_handle: &AppHandle) {
         warn!(error = %e, "Failed to emit annotations-cleared event");
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use WebSocketCommand::{
+        AddAnnotation, DeleteAnnotation, GoToPage, NextPage, PlayMedia, SetZoom,
+    };
+
+    #[test]
+    fn test_check_batch() {
+        let state = AppState::new();
+        state
+            .update_pdf_state(|s| {
+                s.is_loaded = true;
+                s.current_page = 1;
+                s.total_pages = 3;
+            })
+            .unwrap();
+
+        assert!(check_batch(&state, &[GoToPage { page: 2 }, NextPage]).is_none());
+        // The second NEXT_PAGE would run past the end
+        let (index, reply) =
+            check_batch(&state, &[GoToPage { page: 2 }, NextPage, NextPage]).unwrap();
+        assert_eq!(index, 2);
+        assert!(matches!(
+            reply,
+            WebSocketEvent::Error {
+                code: ErrorCode::EndOfDocument,
+                ..
+            }
+        ));
+
+        // An annotation added earlier in the batch can be deleted
+        let add = AddAnnotation {
+            page: 1,
+            annotation: serde_json::json!({ "id": "a1" }),
+            clock: None,
+        };
+        let delete = |id: &str| DeleteAnnotation {
+            page: 1,
+            id: id.to_string(),
+            clock: None,
+        };
+        assert!(check_batch(&state, &[add, delete("a1")]).is_none());
+        assert_eq!(
+            check_batch(&state, &[NextPage, delete("a2")]).map(|(index, _)| index),
+            Some(1)
+        );
+    }
+
+    #[test]
+    fn test_failed_batch_is_undone() {
+        let state = AppState::new();
+        state
+            .update_pdf_state(|s| {
+                s.is_loaded = true;
+                s.current_page = 1;
+                s.total_pages = 5;
+            })
+            .unwrap();
+        let zoom = state.get_pdf_state().unwrap().zoom_level;
+        let commands = vec![
+            GoToPage { page: 4 },
+            SetZoom { zoom: 2.0 },
+            AddAnnotation {
+                page: 4,
+                annotation: serde_json::json!({ "id": "a1", "type": "ink" }),
+                clock: None,
+            },
+            PlayMedia {
+                id: "missing".to_string(),
+            },
+        ];
+        let snapshot = BatchSnapshot::take(&state, &commands).unwrap();
+
+        // The first three change the state as their handlers do; the media
+        // clip doesn't exist
+        let failed = run_batch(commands, |command| match command {
+            GoToPage { page } => {
+                state.update_pdf_state(|s| s.current_page = page).unwrap();
+                page_changed(&state, page, 5)
+            }
+            SetZoom { zoom } => {
+                state.update_pdf_state(|s| s.zoom_level = zoom).unwrap();
+                WebSocketEvent::ZoomChanged { zoom }
+            }
+            AddAnnotation {
+                page, annotation, ..
+            } => {
+                let json = annotation.to_string();
+                state
+                    .annotations
+                    .write()
+                    .unwrap()
+                    .entry(page)
+                    .or_default()
+                    .push(json.clone());
+                state.record_annotation_edits(vec![history::added(page, json)]);
+                WebSocketEvent::AnnotationAdded {
+                    page,
+                    annotation,
+                    clock: state.annotation_clock(),
+                }
+            }
+            _ => WebSocketEvent::error_code(ErrorCode::NotFound, "No media with id missing"),
+        });
+        assert_eq!(failed.len(), 4);
+        assert!(failed[3].is_failure());
+        assert_eq!(state.get_pdf_state().unwrap().current_page, 4);
+
+        let events = snapshot.revert(&state).unwrap();
+        let pdf_state = state.get_pdf_state().unwrap();
+        assert_eq!(pdf_state.current_page, 1);
+        assert_eq!(pdf_state.zoom_level, zoom);
+        assert!(state
+            .annotations
+            .read()
+            .unwrap()
+            .values()
+            .all(Vec::is_empty));
+        assert!(!state.annotation_history.lock().unwrap().can_undo());
+        assert_eq!(state.annotation_clock(), 0);
+        // Clients are told the page, zoom and annotations they were sent back
+        assert!(matches!(
+            events.as_slice(),
+            [
+                WebSocketEvent::PageChanged { page: 1, .. },
+                WebSocketEvent::ZoomChanged { .. },
+                WebSocketEvent::AnnotationsUpdated { .. },
+                WebSocketEvent::MediaPlaybackChanged { .. },
+            ]
+        ));
+    }
+}
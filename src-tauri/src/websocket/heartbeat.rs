@@ -0,0 +1,101 @@
+/*
+ * This file is part of StreamSlate.
+ * Copyright (C) 2025 StreamSlate Contributors
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+
+//! Connection heartbeats
+//!
+//! A controller whose network drops (a phone locking, Wi-Fi roaming) often
+//! leaves its connection open without closing it. The server pings every
+//! connection that has been quiet for a heartbeat interval and drops those
+//! that stay silent through several, so they don't linger in the client list
+//! and the connection count.
+
+use std::time::{Duration, Instant};
+
+/// How often quiet connections are pinged
+pub const HEARTBEAT_INTERVAL: Duration = Duration::from_secs(15);
+
+/// Heartbeats a connection may miss before it's dropped
+pub const MAX_MISSED_HEARTBEATS: u32 = 3;
+
+/// What to do with a connection at a heartbeat
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Beat {
+    /// The client was heard from within the interval
+    Alive,
+    /// The client has been quiet; ping it
+    Ping,
+    /// The client missed too many heartbeats; drop it
+    TimedOut,
+}
+
+/// When a connection was last heard from
+#[derive(Debug, Clone)]
+pub struct Heartbeat {
+    last_seen: Instant,
+}
+
+impl Heartbeat {
+    pub fn new(now: Instant) -> Self {
+        Self { last_seen: now }
+    }
+
+    /// Note a message from the client, including pongs
+    pub fn seen(&mut self, now: Instant) {
+        self.last_seen = now;
+    }
+
+    /// How long the client has been quiet
+    pub fn idle(&self, now: Instant) -> Duration {
+        now.saturating_duration_since(self.last_seen)
+    }
+
+    /// Decide what to do at a heartbeat
+    pub fn beat(&self, now: Instant) -> Beat {
+        let idle = self.idle(now);
+        if idle >= HEARTBEAT_INTERVAL * MAX_MISSED_HEARTBEATS {
+            Beat::TimedOut
+        } else if idle >= HEARTBEAT_INTERVAL {
+            Beat::Ping
+        } else {
+            Beat::Alive
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_heartbeat() {
+        let start = Instant::now();
+        let mut heartbeat = Heartbeat::new(start);
+        assert_eq!(heartbeat.beat(start + Duration::from_secs(5)), Beat::Alive);
+        assert_eq!(heartbeat.beat(start + HEARTBEAT_INTERVAL), Beat::Ping);
+        assert_eq!(
+            heartbeat.beat(start + HEARTBEAT_INTERVAL * MAX_MISSED_HEARTBEATS),
+            Beat::TimedOut
+        );
+
+        heartbeat.seen(start + HEARTBEAT_INTERVAL * 2);
+        assert_eq!(
+            heartbeat.beat(start + HEARTBEAT_INTERVAL * MAX_MISSED_HEARTBEATS),
+            Beat::Ping
+        );
+    }
+}
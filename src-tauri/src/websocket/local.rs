@@ -0,0 +1,286 @@
+/*
+ * This file is part of StreamSlate.
+ * Copyright (C) 2025 StreamSlate Contributors
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+
+//! Local control socket
+//!
+//! The control protocol over a Unix domain socket (a named pipe on
+//! Windows), for automation scripts on this machine that shouldn't have to
+//! find a port or carry an access token. Commands and events are the same
+//! JSON messages as over WebSocket, one per line in each direction. Only
+//! the current user can open the socket, so commands run with the admin
+//! role and aren't rate limited. Page images and remote view frames, which
+//! are binary WebSocket messages, aren't sent.
+
+use super::access::Role;
+use super::handlers::handle_command;
+use super::server::{broadcast_events, channels};
+use super::session::ClientSession;
+use crate::error::ErrorCode;
+use crate::protocol::{parse_command, reply_json, WebSocketEvent};
+use crate::state::AppState;
+use std::io::{Error, ErrorKind};
+use std::net::SocketAddr;
+use std::sync::Arc;
+use tauri::AppHandle;
+use tokio::io::{AsyncBufReadExt, AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt, BufReader};
+use tokio::sync::{broadcast, mpsc};
+use tracing::{debug, info, warn};
+
+/// File name of the socket in the app data directory
+#[cfg(unix)]
+pub const SOCKET_FILE: &str = "control.sock";
+
+/// Name of the named pipe
+#[cfg(windows)]
+pub const PIPE_NAME: &str = r"\\.\pipe\streamslate";
+
+/// Longest message accepted, enough for an annotation with many ink points
+const MAX_LINE_BYTES: usize = 1024 * 1024;
+
+/// Path of the socket, in the app data directory
+#[cfg(unix)]
+pub fn socket_path(app_handle: &AppHandle) -> Option<std::path::PathBuf> {
+    use tauri::Manager;
+
+    app_handle
+        .path()
+        .app_data_dir()
+        .ok()
+        .map(|dir| dir.join(SOCKET_FILE))
+}
+
+/// Start listening on the socket
+///
+/// A socket file left behind by an earlier run is replaced; one another
+/// running instance is listening on is left alone and reported as in use.
+#[cfg(unix)]
+pub async fn start_local_server(
+    state: Arc<AppState>,
+    app_handle: AppHandle,
+) -> std::io::Result<()> {
+    use std::os::unix::fs::PermissionsExt;
+    use tokio::net::UnixListener;
+
+    let path = socket_path(&app_handle)
+        .ok_or_else(|| Error::new(ErrorKind::NotFound, "No app data directory"))?;
+    if path.exists() {
+        if std::os::unix::net::UnixStream::connect(&path).is_ok() {
+            return Err(Error::new(
+                ErrorKind::AddrInUse,
+                format!("{} is in use by another instance", path.display()),
+            ));
+        }
+        std::fs::remove_file(&path)?;
+    }
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let listener = UnixListener::bind(&path)?;
+    std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o600))?;
+    info!(path = %path.display(), "Local control socket started");
+
+    tokio::spawn(async move {
+        loop {
+            match listener.accept().await {
+                Ok((stream, _)) => {
+                    let state = Arc::clone(&state);
+                    let app_handle = app_handle.clone();
+                    tokio::spawn(async move {
+                        if let Err(e) = serve(stream, state, app_handle).await {
+                            debug!(error = %e, "Local control connection error");
+                        }
+                    });
+                }
+                Err(e) => warn!(error = %e, "Failed to accept local control connection"),
+            }
+        }
+    });
+    Ok(())
+}
+
+/// Start listening on the named pipe
+#[cfg(windows)]
+pub async fn start_local_server(
+    state: Arc<AppState>,
+    app_handle: AppHandle,
+) -> std::io::Result<()> {
+    use tokio::net::windows::named_pipe::ServerOptions;
+
+    let mut server = ServerOptions::new()
+        .first_pipe_instance(true)
+        .reject_remote_clients(true)
+        .create(PIPE_NAME)?;
+    info!(pipe = PIPE_NAME, "Local control pipe started");
+
+    tokio::spawn(async move {
+        loop {
+            if let Err(e) = server.connect().await {
+                warn!(error = %e, "Failed to accept local control connection");
+                continue;
+            }
+            // Open the next instance before serving this one, so clients
+            // can always connect
+            let connected = server;
+            server = match ServerOptions::new()
+                .reject_remote_clients(true)
+                .create(PIPE_NAME)
+            {
+                Ok(server) => server,
+                Err(e) => {
+                    warn!(error = %e, "Local control pipe stopped");
+                    break;
+                }
+            };
+            let state = Arc::clone(&state);
+            let app_handle = app_handle.clone();
+            tokio::spawn(async move {
+                if let Err(e) = serve(connected, state, app_handle).await {
+                    debug!(error = %e, "Local control connection error");
+                }
+            });
+        }
+    });
+    Ok(())
+}
+
+/// Run the commands of one connection and send it the broadcasts
+async fn serve<S>(stream: S, state: Arc<AppState>, app_handle: AppHandle) -> std::io::Result<()>
+where
+    S: AsyncRead + AsyncWrite + Send + 'static,
+{
+    let (tx, events) = channels(&state);
+    let mut rx = events.subscribe();
+    let (reader, mut writer) = tokio::io::split(stream);
+    let (lines_tx, mut lines) = mpsc::channel(16);
+    let reading = tokio::spawn(read_lines(reader, lines_tx));
+
+    let mut session = ClientSession::new(SocketAddr::from(([127, 0, 0, 1], 0)));
+    session.role = Role::Admin;
+
+    let result = loop {
+        tokio::select! {
+            line = lines.recv() => {
+                let line = match line {
+                    Some(Ok(line)) => line,
+                    Some(Err(e)) => break Err(e),
+                    None => break Ok(()),
+                };
+                if line.trim().is_empty() {
+                    continue;
+                }
+                let (request_id, command) = parse_command(&line);
+                let reply = match command {
+                    Ok(command) => {
+                        let response = handle_command(command, &state, &app_handle, &mut session);
+                        session.binary_out.clear();
+                        for event in broadcast_events(&response) {
+                            let _ = tx.send(event.clone());
+                        }
+                        response
+                    }
+                    Err(e) => WebSocketEvent::error_code(
+                        ErrorCode::InvalidCommand,
+                        format!("Invalid command: {}", e),
+                    ),
+                };
+                let mut message = reply_json(request_id.as_ref(), &reply)?;
+                message.push('\n');
+                if let Err(e) = writer.write_all(message.as_bytes()).await {
+                    break Err(e);
+                }
+            }
+
+            event = rx.recv() => {
+                match event {
+                    Ok(sequenced) => {
+                        if sequenced.seq <= session.last_seq {
+                            continue;
+                        }
+                        session.last_seq = sequenced.seq;
+                        if session.wants(&sequenced.event) {
+                            let mut message = serde_json::to_string(&sequenced)?;
+                            message.push('\n');
+                            if let Err(e) = writer.write_all(message.as_bytes()).await {
+                                break Err(e);
+                            }
+                        }
+                    }
+                    Err(broadcast::error::RecvError::Lagged(_)) => {
+                        debug!("Local control client lagged behind on broadcast messages");
+                    }
+                    Err(broadcast::error::RecvError::Closed) => break Ok(()),
+                }
+            }
+        }
+    };
+    reading.abort();
+    result
+}
+
+/// Split a stream into messages, one per line
+///
+/// Stops after the end of the stream or the first line that is too long or
+/// not UTF-8, which is passed on as an error.
+async fn read_lines<R: AsyncRead + Unpin>(reader: R, lines: mpsc::Sender<std::io::Result<String>>) {
+    let mut reader = BufReader::new(reader);
+    loop {
+        let mut line = Vec::new();
+        let read = (&mut reader)
+            .take(MAX_LINE_BYTES as u64 + 1)
+            .read_until(b'\n', &mut line)
+            .await;
+        let line = match read {
+            Ok(0) => return,
+            Ok(_) if line.len() > MAX_LINE_BYTES => {
+                Err(Error::new(ErrorKind::InvalidData, "Message too long"))
+            }
+            Ok(_) => String::from_utf8(line).map_err(|e| Error::new(ErrorKind::InvalidData, e)),
+            Err(e) => Err(e),
+        };
+        let failed = line.is_err();
+        if lines.send(line).await.is_err() || failed {
+            return;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_read_lines() {
+        let (mut client, server) = tokio::io::duplex(64);
+        let (lines_tx, mut lines) = mpsc::channel(4);
+        tokio::spawn(read_lines(server, lines_tx));
+
+        client
+            .write_all(b"{\"type\":\"NEXT_PAGE\"}\n{\"type\":")
+            .await
+            .unwrap();
+        client.write_all(b"\"PING\"}").await.unwrap();
+        drop(client);
+
+        assert_eq!(
+            lines.recv().await.unwrap().unwrap(),
+            "{\"type\":\"NEXT_PAGE\"}\n"
+        );
+        assert_eq!(lines.recv().await.unwrap().unwrap(), "{\"type\":\"PING\"}");
+        assert!(lines.recv().await.is_none());
+    }
+}
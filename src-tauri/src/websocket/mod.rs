@@ -22,10 +22,35 @@
 //! (OBS, Stream Deck, custom scripts) to control PDF navigation and
 //! receive state updates.
 
+pub mod access;
+pub mod audit;
+pub mod clients;
+mod coalesce;
+pub mod config;
+mod deflate;
 mod handlers;
-mod protocol;
+mod heartbeat;
+pub mod local;
+mod outbox;
+pub mod page_images;
+pub mod preferences;
+pub(crate) mod rate;
+pub mod reactions;
+mod replay;
+mod rollback;
+pub mod schema;
 mod server;
+mod session;
+pub mod tls;
+pub(crate) mod topics;
 
 #[allow(unused_imports)]
-pub use protocol::{WebSocketCommand, WebSocketEvent};
-pub use server::{start_server, DEFAULT_PORT};
+pub use crate::protocol::{WebSocketCommand, WebSocketEvent};
+pub use clients::ClientRegistry;
+pub use config::WebSocketConfig;
+pub use handlers::{handle_command_as, handle_local_command};
+pub use page_images::PageImages;
+pub use preferences::ClientPreferences;
+pub use reactions::ReactionBoard;
+pub(crate) use server::channels;
+pub use server::{start_server, stop_server, DEFAULT_PORT};
@@ -0,0 +1,225 @@
+/*
+ * This file is part of StreamSlate.
+ * Copyright (C) 2025 StreamSlate Contributors
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+
+//! Per-connection send queues
+//!
+//! Everything a connection sends goes through its own bounded queue, drained
+//! by a writer task, so a client that reads slowly holds up only itself.
+//! Replies to the client's commands are always delivered, in order. When the
+//! queue is full, the oldest broadcast event waiting in it is dropped to make
+//! room; the drops are counted so the operator can spot the client. While
+//! replies alone fill the queue, the connection stops reading commands until
+//! the client catches up.
+
+use std::collections::VecDeque;
+use std::sync::Mutex;
+use tokio::sync::Notify;
+
+/// Messages a connection's queue holds before broadcasts are dropped
+pub const QUEUE_CAPACITY: usize = 256;
+
+/// How a queued message may be treated when the client falls behind
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Delivery {
+    /// A reply, ping or close frame, never dropped
+    Direct,
+    /// A broadcast state event or remote view frame, dropped oldest first
+    Droppable,
+}
+
+/// A bounded FIFO queue that drops the oldest droppable message when full
+#[derive(Debug)]
+pub struct SendQueue<T> {
+    items: VecDeque<(Delivery, T)>,
+    capacity: usize,
+    /// Direct messages in `items`
+    direct: usize,
+    closed: bool,
+}
+
+impl<T> SendQueue<T> {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            items: VecDeque::new(),
+            capacity,
+            direct: 0,
+            closed: false,
+        }
+    }
+
+    /// Queue a message, returning whether a droppable one was discarded
+    ///
+    /// A full queue makes room by dropping its oldest droppable message. When
+    /// it only holds direct messages, a new droppable one is dropped instead
+    /// and a direct one is queued over capacity.
+    pub fn push(&mut self, delivery: Delivery, item: T) -> bool {
+        let mut dropped = false;
+        if self.items.len() >= self.capacity {
+            let oldest = self
+                .items
+                .iter()
+                .position(|(d, _)| *d == Delivery::Droppable);
+            match (oldest, delivery) {
+                (Some(index), _) => {
+                    self.items.remove(index);
+                    dropped = true;
+                }
+                (None, Delivery::Droppable) => return true,
+                (None, Delivery::Direct) => {}
+            }
+        }
+        if delivery == Delivery::Direct {
+            self.direct += 1;
+        }
+        self.items.push_back((delivery, item));
+        dropped
+    }
+
+    /// The next message to send
+    pub fn pop(&mut self) -> Option<T> {
+        let (delivery, item) = self.items.pop_front()?;
+        if delivery == Delivery::Direct {
+            self.direct -= 1;
+        }
+        Some(item)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    /// Whether direct messages alone fill the queue
+    pub fn is_backed_up(&self) -> bool {
+        self.direct >= self.capacity
+    }
+}
+
+/// A connection's send queue, shared with the writer task draining it
+#[derive(Debug)]
+pub struct Outbox<T> {
+    queue: Mutex<SendQueue<T>>,
+    /// Wakes the writer when a message is queued or the queue is closed
+    queued: Notify,
+    /// Wakes the connection when the writer takes a message
+    taken: Notify,
+}
+
+impl<T> Outbox<T> {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            queue: Mutex::new(SendQueue::new(capacity)),
+            queued: Notify::new(),
+            taken: Notify::new(),
+        }
+    }
+
+    /// Queue a message that must be delivered
+    pub fn send(&self, item: T) {
+        self.push(Delivery::Direct, item);
+    }
+
+    /// Queue a message that may be dropped if the client falls behind,
+    /// returning whether one was
+    pub fn send_droppable(&self, item: T) -> bool {
+        self.push(Delivery::Droppable, item)
+    }
+
+    fn push(&self, delivery: Delivery, item: T) -> bool {
+        let dropped = match self.queue.lock() {
+            Ok(mut queue) if !queue.closed => queue.push(delivery, item),
+            _ => return false,
+        };
+        self.queued.notify_one();
+        dropped
+    }
+
+    /// Stop taking messages; the writer sends what's queued, then finishes
+    pub fn close(&self) {
+        if let Ok(mut queue) = self.queue.lock() {
+            queue.closed = true;
+        }
+        self.queued.notify_one();
+    }
+
+    /// The next message to send, or `None` once the queue is closed and
+    /// empty
+    pub async fn next(&self) -> Option<T> {
+        loop {
+            {
+                let mut queue = self.queue.lock().ok()?;
+                if let Some(item) = queue.pop() {
+                    drop(queue);
+                    self.taken.notify_one();
+                    return Some(item);
+                }
+                if queue.closed {
+                    return None;
+                }
+            }
+            self.queued.notified().await;
+        }
+    }
+
+    /// Wait until the writer takes a message
+    pub async fn taken(&self) {
+        self.taken.notified().await;
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.queue.lock().map_or(true, |queue| queue.is_empty())
+    }
+
+    /// Whether replies alone fill the queue, so no more commands should be
+    /// read for now
+    pub fn is_backed_up(&self) -> bool {
+        self.queue.lock().is_ok_and(|queue| queue.is_backed_up())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_send_queue() {
+        let mut queue = SendQueue::new(3);
+        assert!(!queue.push(Delivery::Droppable, 1));
+        assert!(!queue.push(Delivery::Direct, 2));
+        assert!(!queue.push(Delivery::Droppable, 3));
+
+        // The oldest event makes room, replies stay
+        assert!(queue.push(Delivery::Droppable, 4));
+        assert!(queue.push(Delivery::Direct, 5));
+        assert_eq!(queue.pop(), Some(2));
+        assert!(!queue.push(Delivery::Direct, 6));
+
+        // Full of replies: new events are dropped, replies go over capacity
+        assert!(!queue.is_backed_up());
+        assert!(queue.push(Delivery::Direct, 7));
+        assert!(queue.is_backed_up());
+        assert!(queue.push(Delivery::Droppable, 8));
+        assert!(!queue.push(Delivery::Direct, 9));
+
+        let mut sent = Vec::new();
+        while let Some(item) = queue.pop() {
+            sent.push(item);
+        }
+        assert_eq!(sent, [5, 6, 7, 9]);
+        assert!(!queue.is_backed_up());
+    }
+}
@@ -0,0 +1,273 @@
+/*
+ * This file is part of StreamSlate.
+ * Copyright (C) 2025 StreamSlate Contributors
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+
+//! Rendered page images for remote monitors
+//!
+//! Pages are rendered by the webview, which hands the images to the backend
+//! with `publish_page_image`. Clients ask for them with `GET_PAGE_IMAGE` or
+//! follow the current page with `SET_PAGE_IMAGES`, and receive each one as
+//! a `PAGE_IMAGE` header followed by a binary message holding the image
+//! file, so a confidence monitor can show the slide without a PDF renderer.
+//! Pages no one has rendered yet are requested from the webview.
+
+use crate::error::{Result, StreamSlateError};
+use crate::protocol::WebSocketEvent;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+use tauri::{AppHandle, Emitter};
+use tokio::sync::broadcast;
+use tracing::warn;
+
+/// Tauri event asking the webview to render and publish a page image
+pub const PAGE_IMAGE_REQUESTED_EVENT: &str = "page-image-requested";
+
+/// Largest accepted image file
+pub const MAX_IMAGE_BYTES: usize = 8 * 1024 * 1024;
+
+/// Images kept; the oldest are dropped beyond this
+const MAX_CACHED_IMAGES: usize = 48;
+
+/// Rendition of a page
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    Default,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Hash,
+    Serialize,
+    Deserialize,
+    JsonSchema,
+)]
+#[serde(rename_all = "lowercase")]
+pub enum ImageSize {
+    /// Full-size page for a confidence monitor
+    #[default]
+    Page,
+    /// Small preview for a page list
+    Thumbnail,
+}
+
+impl ImageSize {
+    /// Width the webview renders this size at
+    pub fn max_width(self) -> u32 {
+        match self {
+            ImageSize::Page => 1920,
+            ImageSize::Thumbnail => 320,
+        }
+    }
+}
+
+/// File format of a page image
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum ImageFormat {
+    Jpeg,
+    Png,
+}
+
+impl ImageFormat {
+    /// Recognize an image file by its signature
+    pub fn detect(data: &[u8]) -> Option<Self> {
+        if data.starts_with(&[0xFF, 0xD8, 0xFF]) {
+            Some(ImageFormat::Jpeg)
+        } else if data.starts_with(b"\x89PNG\r\n\x1a\n") {
+            Some(ImageFormat::Png)
+        } else {
+            None
+        }
+    }
+}
+
+/// A rendered page image
+#[derive(Debug, Clone)]
+pub struct PageImage {
+    pub page: u32,
+    pub size: ImageSize,
+    pub format: ImageFormat,
+    pub width: u32,
+    pub height: u32,
+    /// Image file bytes, shared by every client sent the image
+    pub data: Arc<[u8]>,
+}
+
+impl PageImage {
+    /// Check an image file published by the webview
+    pub fn new(page: u32, size: ImageSize, width: u32, height: u32, data: Vec<u8>) -> Result<Self> {
+        if data.len() > MAX_IMAGE_BYTES {
+            return Err(StreamSlateError::Other(format!(
+                "Page image is larger than {} MB",
+                MAX_IMAGE_BYTES / (1024 * 1024)
+            )));
+        }
+        let format = ImageFormat::detect(&data).ok_or_else(|| {
+            StreamSlateError::Other("Page image must be a JPEG or PNG file".to_string())
+        })?;
+        if page == 0 || width == 0 || height == 0 {
+            return Err(StreamSlateError::Other(
+                "Page image needs a page number and dimensions".to_string(),
+            ));
+        }
+        Ok(Self {
+            page,
+            size,
+            format,
+            width,
+            height,
+            data: data.into(),
+        })
+    }
+
+    /// The `PAGE_IMAGE` header sent ahead of the image data
+    pub fn header(&self) -> WebSocketEvent {
+        WebSocketEvent::PageImage {
+            page: self.page,
+            size: self.size,
+            format: self.format,
+            width: self.width,
+            height: self.height,
+            bytes: self.data.len() as u32,
+        }
+    }
+}
+
+/// Images of the open document's pages, oldest first
+#[derive(Debug, Default)]
+pub struct PageImageCache {
+    document: Option<String>,
+    images: VecDeque<PageImage>,
+}
+
+impl PageImageCache {
+    /// Store an image of `document`, replacing the page's previous one;
+    /// images of other documents are dropped
+    pub fn insert(&mut self, document: Option<&str>, image: PageImage) {
+        if self.document.as_deref() != document {
+            self.images.clear();
+            self.document = document.map(str::to_string);
+        }
+        self.images
+            .retain(|cached| (cached.page, cached.size) != (image.page, image.size));
+        if self.images.len() >= MAX_CACHED_IMAGES {
+            self.images.pop_front();
+        }
+        self.images.push_back(image);
+    }
+
+    /// The stored image of a page of `document`
+    pub fn get(&self, document: Option<&str>, page: u32, size: ImageSize) -> Option<PageImage> {
+        if self.document.as_deref() != document {
+            return None;
+        }
+        self.images
+            .iter()
+            .find(|image| image.page == page && image.size == size)
+            .cloned()
+    }
+}
+
+/// Published page images, and the channel telling connections about new ones
+#[derive(Debug)]
+pub struct PageImages {
+    pub cache: Mutex<PageImageCache>,
+    pub published: broadcast::Sender<PageImage>,
+}
+
+impl Default for PageImages {
+    fn default() -> Self {
+        Self {
+            cache: Mutex::new(PageImageCache::default()),
+            published: broadcast::channel(16).0,
+        }
+    }
+}
+
+impl PageImages {
+    /// Store an image of `document` and pass it to the connections waiting
+    /// for it
+    pub fn publish(&self, document: Option<&str>, image: PageImage) -> Result<()> {
+        self.cache
+            .lock()
+            .map_err(|e| StreamSlateError::StateLock(format!("Page images: {e}")))?
+            .insert(document, image.clone());
+        let _ = self.published.send(image);
+        Ok(())
+    }
+
+    /// The stored image of a page of `document`
+    pub fn get(&self, document: Option<&str>, page: u32, size: ImageSize) -> Option<PageImage> {
+        self.cache.lock().ok()?.get(document, page, size)
+    }
+}
+
+/// Ask the webview to render a page image and publish it
+pub fn request_render(app_handle: &AppHandle, page: u32, size: ImageSize) {
+    #[derive(Clone, Serialize)]
+    #[serde(rename_all = "camelCase")]
+    struct PageImageRequest {
+        page: u32,
+        size: ImageSize,
+        max_width: u32,
+    }
+
+    let request = PageImageRequest {
+        page,
+        size,
+        max_width: size.max_width(),
+    };
+    if let Err(e) = app_handle.emit(PAGE_IMAGE_REQUESTED_EVENT, request) {
+        warn!(error = %e, "Failed to request page image");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn jpeg(page: u32, size: ImageSize) -> PageImage {
+        PageImage::new(page, size, 320, 180, vec![0xFF, 0xD8, 0xFF, 0xE0]).unwrap()
+    }
+
+    #[test]
+    fn test_page_image_cache() {
+        assert!(PageImage::new(1, ImageSize::Page, 320, 180, b"GIF89a".to_vec()).is_err());
+        assert_eq!(jpeg(1, ImageSize::Page).format, ImageFormat::Jpeg);
+
+        let mut cache = PageImageCache::default();
+        cache.insert(Some("a.pdf"), jpeg(1, ImageSize::Page));
+        cache.insert(Some("a.pdf"), jpeg(1, ImageSize::Thumbnail));
+        assert!(cache.get(Some("a.pdf"), 1, ImageSize::Page).is_some());
+        assert!(cache.get(Some("a.pdf"), 2, ImageSize::Page).is_none());
+        assert!(cache.get(Some("b.pdf"), 1, ImageSize::Page).is_none());
+
+        for page in 2..=MAX_CACHED_IMAGES as u32 {
+            cache.insert(Some("a.pdf"), jpeg(page, ImageSize::Page));
+        }
+        assert!(cache.get(Some("a.pdf"), 1, ImageSize::Page).is_none());
+        assert!(cache.get(Some("a.pdf"), 1, ImageSize::Thumbnail).is_some());
+
+        cache.insert(Some("b.pdf"), jpeg(3, ImageSize::Page));
+        assert!(cache.get(Some("a.pdf"), 2, ImageSize::Page).is_none());
+        assert!(cache.get(Some("b.pdf"), 3, ImageSize::Page).is_some());
+    }
+}
@@ -0,0 +1,136 @@
+/*
+ * This file is part of StreamSlate.
+ * Copyright (C) 2025 StreamSlate Contributors
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+
+//! Persistent per-client preferences
+//!
+//! Remote clients identify themselves with a stable client ID. Preferences
+//! stored under that ID survive reconnects and app restarts, so a remote
+//! picks up its configuration again as soon as it re-identifies.
+
+use crate::error::{Result, StreamSlateError};
+use crate::settings::Settings;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// File name of the preferences store inside the app data directory
+pub const PREFERENCES_FILE: &str = "client_preferences.json";
+
+/// Maximum accepted length of a client ID
+const MAX_CLIENT_ID_LEN: usize = 128;
+
+/// Maximum accepted length of a display name, in characters
+pub(crate) const MAX_DISPLAY_NAME_LEN: usize = 64;
+
+/// Lightweight preferences remembered for a remote client
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct ClientPreferences {
+    /// Event topics the client wants to receive
+    #[serde(default)]
+    pub topics: Vec<String>,
+    /// Preferred thumbnail edge length in pixels
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub thumbnail_size: Option<u32>,
+    /// Preferred locale (BCP 47 tag, e.g. "en-US")
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub locale: Option<String>,
+}
+
+/// Check that a client ID is non-empty, bounded, and filesystem/log safe
+pub fn validate_client_id(client_id: &str) -> Result<()> {
+    if client_id.is_empty() || client_id.len() > MAX_CLIENT_ID_LEN {
+        return Err(StreamSlateError::Other(format!(
+            "Client ID must be 1-{MAX_CLIENT_ID_LEN} characters"
+        )));
+    }
+
+    if !client_id
+        .chars()
+        .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'))
+    {
+        return Err(StreamSlateError::Other(
+            "Client ID may only contain letters, digits, '-', '_' and '.'".to_string(),
+        ));
+    }
+
+    Ok(())
+}
+
+/// Check that a display name is non-blank, bounded, and free of control
+/// characters
+pub fn validate_display_name(name: &str) -> Result<()> {
+    if name.trim().is_empty() || name.chars().count() > MAX_DISPLAY_NAME_LEN {
+        return Err(StreamSlateError::Other(format!(
+            "Display name must be 1-{MAX_DISPLAY_NAME_LEN} characters"
+        )));
+    }
+
+    if name.chars().any(char::is_control) {
+        return Err(StreamSlateError::Other(
+            "Display name may not contain control characters".to_string(),
+        ));
+    }
+
+    Ok(())
+}
+
+/// Preferences of every client, keyed by client ID
+pub type ClientPreferencesMap = HashMap<String, ClientPreferences>;
+
+impl Settings for ClientPreferencesMap {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate_client_id() {
+        assert!(validate_client_id("stream-deck_01.main").is_ok());
+        assert!(validate_client_id("").is_err());
+        assert!(validate_client_id("../etc/passwd").is_err());
+        assert!(validate_client_id(&"a".repeat(MAX_CLIENT_ID_LEN + 1)).is_err());
+    }
+
+    #[test]
+    fn test_validate_display_name() {
+        assert!(validate_display_name("Ana Gómez").is_ok());
+        assert!(validate_display_name("   ").is_err());
+        assert!(validate_display_name("Ana\nBob").is_err());
+        assert!(validate_display_name(&"é".repeat(MAX_DISPLAY_NAME_LEN)).is_ok());
+        assert!(validate_display_name(&"é".repeat(MAX_DISPLAY_NAME_LEN + 1)).is_err());
+    }
+
+    #[test]
+    fn test_preferences_roundtrip() {
+        let mut prefs = ClientPreferencesMap::new();
+        prefs.insert(
+            "phone".to_string(),
+            ClientPreferences {
+                topics: vec!["annotations".to_string()],
+                thumbnail_size: Some(320),
+                locale: Some("de-DE".to_string()),
+            },
+        );
+
+        let json = serde_json::to_string(&prefs).unwrap();
+        assert_eq!(
+            serde_json::from_str::<ClientPreferencesMap>(&json).unwrap(),
+            prefs
+        );
+    }
+}
@@ -0,0 +1,351 @@
+/*
+ * This file is part of StreamSlate.
+ * Copyright (C) 2025 StreamSlate Contributors
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+
+//! Per-connection rate limits
+//!
+//! Commands a client may send in bursts, such as reactions and pointer
+//! moves, are limited with a token bucket: a burst is accepted at once, then
+//! tokens refill at the sustained rate.
+//!
+//! Every other command (but `PING`) also counts against the bucket of its
+//! class, so a buggy controller can't page through the deck or flood the
+//! annotations; the limits of each class are part of the server settings.
+
+use crate::error::{Result, StreamSlateError};
+use crate::protocol::{WebSocketCommand, WebSocketEvent};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::time::{Duration, Instant};
+
+/// Kinds of commands limited together
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum CommandClass {
+    /// Page, section, zoom and presenter changes
+    Navigation,
+    /// Annotation edits, layers and style presets
+    Annotations,
+    /// Outputs, media, QR codes and ending the show
+    Output,
+    /// Requests for state, and setting up the connection
+    Queries,
+}
+
+/// Burst and sustained rate of one command class
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RateLimit {
+    /// Commands accepted at once
+    pub burst: u32,
+    /// Commands per second after the burst
+    pub per_second: f64,
+}
+
+impl RateLimit {
+    const fn new(burst: u32, per_second: f64) -> Self {
+        Self { burst, per_second }
+    }
+}
+
+/// Rate limits of each command class, per connection
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RateLimits {
+    #[serde(default = "default_navigation")]
+    pub navigation: RateLimit,
+    #[serde(default = "default_annotations")]
+    pub annotations: RateLimit,
+    #[serde(default = "default_output")]
+    pub output: RateLimit,
+    #[serde(default = "default_queries")]
+    pub queries: RateLimit,
+}
+
+impl Default for RateLimits {
+    fn default() -> Self {
+        Self {
+            navigation: default_navigation(),
+            annotations: default_annotations(),
+            output: default_output(),
+            queries: default_queries(),
+        }
+    }
+}
+
+impl RateLimits {
+    /// Check every class accepts at least one command
+    pub fn validate(&self) -> Result<()> {
+        let limits = [self.navigation, self.annotations, self.output, self.queries];
+        if limits.iter().any(|limit| {
+            limit.burst == 0 || !limit.per_second.is_finite() || limit.per_second <= 0.0
+        }) {
+            return Err(StreamSlateError::Other(
+                "Rate limits need a burst of at least 1 and a positive rate".to_string(),
+            ));
+        }
+        Ok(())
+    }
+
+    fn get(&self, class: CommandClass) -> RateLimit {
+        match class {
+            CommandClass::Navigation => self.navigation,
+            CommandClass::Annotations => self.annotations,
+            CommandClass::Output => self.output,
+            CommandClass::Queries => self.queries,
+        }
+    }
+}
+
+fn default_navigation() -> RateLimit {
+    RateLimit::new(10, 5.0)
+}
+
+fn default_annotations() -> RateLimit {
+    RateLimit::new(60, 30.0)
+}
+
+fn default_output() -> RateLimit {
+    RateLimit::new(10, 5.0)
+}
+
+fn default_queries() -> RateLimit {
+    RateLimit::new(30, 10.0)
+}
+
+/// The class a command counts against, or `None` for `PING` and commands
+/// with limits of their own
+pub fn command_class(command: &WebSocketCommand) -> Option<CommandClass> {
+    use WebSocketCommand as C;
+    match command {
+        C::NextPage
+        | C::PreviousPage
+        | C::GoToPage { .. }
+        | C::SetZoom { .. }
+        | C::TogglePresenter
+        | C::NextSection
+        | C::PreviousSection => Some(CommandClass::Navigation),
+        C::AddAnnotation { .. }
+        | C::UpdateAnnotation { .. }
+        | C::DeleteAnnotation { .. }
+        | C::ClearAnnotations
+        | C::UndoAnnotation
+        | C::RedoAnnotation
+        | C::EraseAt { .. }
+        | C::SetLayerVisible { .. }
+        | C::SetActiveStylePreset { .. } => Some(CommandClass::Annotations),
+        C::SetSmartInvert { .. }
+        | C::SetBlackout { .. }
+        | C::SetOutputViewport { .. }
+        | C::PlayMedia { .. }
+        | C::PauseMedia { .. }
+        | C::SeekMedia { .. }
+        | C::ShowQr { .. }
+        | C::HideQr
+        | C::EndShow { .. } => Some(CommandClass::Output),
+        C::GetState
+        | C::Hello { .. }
+        | C::Identify { .. }
+        | C::SetPreferences { .. }
+        | C::GetPageWords { .. }
+        | C::GetAgenda
+        | C::GetMedia
+        | C::GetLayers
+        | C::GetNotes { .. }
+        | C::SetRemoteView { .. }
+        | C::SearchAnnotations { .. }
+        | C::GetStylePresets
+        | C::Subscribe { .. }
+        | C::Resume { .. }
+        | C::GetPageImage { .. }
+        | C::SetPageImages { .. } => Some(CommandClass::Queries),
+        C::Ping | C::AddReaction { .. } | C::PointerMoved { .. } | C::Batch { .. } => None,
+    }
+}
+
+/// The rate limits of one connection
+#[derive(Debug, Clone)]
+pub struct CommandLimiter {
+    navigation: RateLimiter,
+    annotations: RateLimiter,
+    output: RateLimiter,
+    queries: RateLimiter,
+}
+
+impl CommandLimiter {
+    pub fn new(limits: &RateLimits, now: Instant) -> Self {
+        let limiter = |class| {
+            let limit = limits.get(class);
+            RateLimiter::new(limit.burst as f64, limit.per_second, now)
+        };
+        Self {
+            navigation: limiter(CommandClass::Navigation),
+            annotations: limiter(CommandClass::Annotations),
+            output: limiter(CommandClass::Output),
+            queries: limiter(CommandClass::Queries),
+        }
+    }
+
+    fn limiter(&mut self, class: CommandClass) -> &mut RateLimiter {
+        match class {
+            CommandClass::Navigation => &mut self.navigation,
+            CommandClass::Annotations => &mut self.annotations,
+            CommandClass::Output => &mut self.output,
+            CommandClass::Queries => &mut self.queries,
+        }
+    }
+
+    /// Count a command against its class, or return the `RATE_LIMITED`
+    /// reply when the connection is over the limit
+    ///
+    /// A batch counts each of its commands and is refused as a whole when
+    /// any class lacks room for them.
+    pub fn check(
+        &mut self,
+        command: &WebSocketCommand,
+        now: Instant,
+    ) -> std::result::Result<(), WebSocketEvent> {
+        let commands = match command {
+            WebSocketCommand::Batch { commands } => commands.as_slice(),
+            command => std::slice::from_ref(command),
+        };
+        let mut needed: Vec<(CommandClass, f64)> = Vec::new();
+        for class in commands.iter().filter_map(command_class) {
+            match needed.iter_mut().find(|(c, _)| *c == class) {
+                Some((_, count)) => *count += 1.0,
+                None => needed.push((class, 1.0)),
+            }
+        }
+
+        for &(class, count) in &needed {
+            if let Some(wait) = self.limiter(class).wait(count, now) {
+                return Err(WebSocketEvent::RateLimited {
+                    command: command.name(),
+                    class,
+                    retry_after_ms: wait.as_millis() as u64,
+                });
+            }
+        }
+        for (class, count) in needed {
+            self.limiter(class).take(count, now);
+        }
+        Ok(())
+    }
+}
+
+/// Token bucket limiting how fast one connection can send a command
+#[derive(Debug, Clone)]
+pub struct RateLimiter {
+    tokens: f64,
+    last: Instant,
+    burst: f64,
+    per_second: f64,
+}
+
+impl RateLimiter {
+    pub fn new(burst: f64, per_second: f64, now: Instant) -> Self {
+        Self {
+            tokens: burst,
+            last: now,
+            burst,
+            per_second,
+        }
+    }
+
+    /// Take a token, returning false when the connection is over its rate
+    pub fn try_acquire(&mut self, now: Instant) -> bool {
+        if self.wait(1.0, now).is_some() {
+            return false;
+        }
+        self.take(1.0, now);
+        true
+    }
+
+    /// How long until `count` tokens are available, or `None` if they are
+    fn wait(&mut self, count: f64, now: Instant) -> Option<Duration> {
+        self.refill(now);
+        (self.tokens < count)
+            .then(|| Duration::from_secs_f64((count - self.tokens) / self.per_second))
+    }
+
+    fn take(&mut self, count: f64, now: Instant) {
+        self.refill(now);
+        self.tokens -= count;
+    }
+
+    fn refill(&mut self, now: Instant) {
+        let elapsed = now.saturating_duration_since(self.last).as_secs_f64();
+        self.tokens = (self.tokens + elapsed * self.per_second).min(self.burst);
+        self.last = now;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn test_rate_limiter() {
+        let start = Instant::now();
+        let mut limiter = RateLimiter::new(10.0, 2.0, start);
+        let accepted = (0..20).filter(|_| limiter.try_acquire(start)).count();
+        assert_eq!(accepted, 10);
+
+        // Refills at the sustained rate
+        assert!(limiter.try_acquire(start + Duration::from_millis(500)));
+        assert!(!limiter.try_acquire(start + Duration::from_millis(500)));
+    }
+
+    #[test]
+    fn test_command_limiter() {
+        let start = Instant::now();
+        let limits = RateLimits {
+            navigation: RateLimit::new(2, 1.0),
+            ..RateLimits::default()
+        };
+        let mut limiter = CommandLimiter::new(&limits, start);
+        let next = WebSocketCommand::NextPage;
+        assert!(limiter.check(&next, start).is_ok());
+        assert!(limiter.check(&next, start).is_ok());
+        assert!(matches!(
+            limiter.check(&next, start),
+            Err(WebSocketEvent::RateLimited {
+                class: CommandClass::Navigation,
+                retry_after_ms: 1000,
+                ..
+            })
+        ));
+        // Other classes and PING are unaffected
+        assert!(limiter.check(&WebSocketCommand::GetState, start).is_ok());
+        assert!(limiter.check(&WebSocketCommand::Ping, start).is_ok());
+
+        // A batch needing more than is left is refused whole
+        let later = start + Duration::from_secs(1);
+        let batch = WebSocketCommand::Batch {
+            commands: vec![WebSocketCommand::GetState, next.clone(), next.clone()],
+        };
+        assert!(limiter.check(&batch, later).is_err());
+        assert!(limiter.check(&next, later).is_ok());
+        assert!(RateLimits {
+            queries: RateLimit::new(0, 1.0),
+            ..limits
+        }
+        .validate()
+        .is_err());
+    }
+}
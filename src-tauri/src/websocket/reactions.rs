@@ -0,0 +1,148 @@
+/*
+ * This file is part of StreamSlate.
+ * Copyright (C) 2025 StreamSlate Contributors
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+
+//! Live audience reactions per page
+//!
+//! Chat bots send `ADD_REACTION` to surface audience reactions on the slide
+//! being shown. The command is restricted to a fixed set of emoji and rate
+//! limited per connection, and reactions expire after `REACTION_TTL` so
+//! overlays show what the audience feels now rather than a running total.
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap, VecDeque};
+use std::time::{Duration, Instant};
+
+/// How long a reaction counts towards a page's totals
+pub const REACTION_TTL: Duration = Duration::from_secs(60);
+
+/// Reactions a connection may send in a burst
+pub const RATE_BURST: f64 = 10.0;
+
+/// Sustained reactions per second per connection
+pub const RATE_PER_SECOND: f64 = 2.0;
+
+/// Live reactions kept per page; the oldest are dropped beyond this
+const MAX_REACTIONS_PER_PAGE: usize = 1000;
+
+/// Emoji accepted by `ADD_REACTION`
+pub const ALLOWED_EMOJI: &[&str] = &[
+    "👍", "👎", "❤️", "😂", "😮", "😢", "👏", "🔥", "🎉", "🤔", "💯", "🚀",
+];
+
+/// Whether `emoji` may be used as a reaction
+pub fn is_allowed(emoji: &str) -> bool {
+    ALLOWED_EMOJI.contains(&emoji)
+}
+
+/// Number of live reactions with one emoji
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub struct ReactionCount {
+    pub emoji: String,
+    pub count: u32,
+}
+
+/// Live reactions of every page
+#[derive(Debug, Default)]
+pub struct ReactionBoard {
+    pages: HashMap<u32, VecDeque<(Instant, String)>>,
+}
+
+impl ReactionBoard {
+    /// Record a reaction on `page`
+    pub fn add(&mut self, page: u32, emoji: &str, now: Instant) {
+        let reactions = self.pages.entry(page).or_default();
+        if reactions.len() >= MAX_REACTIONS_PER_PAGE {
+            reactions.pop_front();
+        }
+        reactions.push_back((now, emoji.to_string()));
+    }
+
+    /// Live reaction counts of `page`, most frequent first
+    pub fn counts(&self, page: u32, now: Instant) -> Vec<ReactionCount> {
+        let mut counts: BTreeMap<&str, u32> = BTreeMap::new();
+        for (at, emoji) in self.pages.get(&page).into_iter().flatten() {
+            if now.saturating_duration_since(*at) < REACTION_TTL {
+                *counts.entry(emoji).or_default() += 1;
+            }
+        }
+        let mut counts: Vec<ReactionCount> = counts
+            .into_iter()
+            .map(|(emoji, count)| ReactionCount {
+                emoji: emoji.to_string(),
+                count,
+            })
+            .collect();
+        counts.sort_by_key(|c| std::cmp::Reverse(c.count));
+        counts
+    }
+
+    /// Drop expired reactions, returning the pages whose counts changed
+    pub fn prune(&mut self, now: Instant) -> Vec<u32> {
+        let mut changed = Vec::new();
+        for (&page, reactions) in self.pages.iter_mut() {
+            let before = reactions.len();
+            while reactions
+                .front()
+                .is_some_and(|(at, _)| now.saturating_duration_since(*at) >= REACTION_TTL)
+            {
+                reactions.pop_front();
+            }
+            if reactions.len() != before {
+                changed.push(page);
+            }
+        }
+        self.pages.retain(|_, reactions| !reactions.is_empty());
+        changed.sort_unstable();
+        changed
+    }
+
+    /// Forget every reaction, e.g. when another document is opened
+    pub fn clear(&mut self) {
+        self.pages.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_reactions_aggregate_and_expire() {
+        let start = Instant::now();
+        let mut board = ReactionBoard::default();
+        board.add(3, "🔥", start);
+        board.add(3, "👏", start + Duration::from_secs(30));
+        board.add(3, "🔥", start + Duration::from_secs(40));
+
+        let counts = board.counts(3, start + Duration::from_secs(45));
+        assert_eq!(counts[0].emoji, "🔥");
+        assert_eq!(counts[0].count, 2);
+
+        let later = start + REACTION_TTL + Duration::from_secs(1);
+        assert_eq!(board.prune(later), [3]);
+        assert_eq!(
+            board.counts(3, later).iter().map(|c| c.count).sum::<u32>(),
+            2
+        );
+        assert!(board.prune(later).is_empty());
+
+        assert!(is_allowed("👍"));
+        assert!(!is_allowed("<script>"));
+    }
+}
@@ -0,0 +1,154 @@
+/*
+ * This file is part of StreamSlate.
+ * Copyright (C) 2025 StreamSlate Contributors
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+
+//! Replay of missed broadcasts
+//!
+//! Every broadcast sent to clients gets a sequence number and is kept in a
+//! bounded buffer, so a controller that briefly loses its network (a Stream
+//! Deck plugin on flaky Wi-Fi) can reconnect, send `RESUME` with the last
+//! number it saw and catch up on the annotation edits and page changes it
+//! missed instead of re-requesting the full state.
+//!
+//! Numbering starts from the time the app started, in milliseconds, so a
+//! number from an earlier run is always older than the buffer and can't be
+//! mistaken for a recent one.
+
+use crate::protocol::{SequencedEvent, WebSocketEvent};
+use std::collections::VecDeque;
+use std::sync::{Mutex, OnceLock};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Broadcasts kept for replay
+pub const REPLAY_CAPACITY: usize = 256;
+
+static BUFFER: OnceLock<Mutex<ReplayBuffer>> = OnceLock::new();
+
+/// The most recent broadcasts with their sequence numbers
+#[derive(Debug)]
+pub struct ReplayBuffer {
+    /// Number of the next broadcast
+    next_seq: u64,
+    events: VecDeque<SequencedEvent>,
+    capacity: usize,
+}
+
+impl ReplayBuffer {
+    /// An empty buffer numbering broadcasts from `first_seq`
+    pub fn new(first_seq: u64, capacity: usize) -> Self {
+        Self {
+            next_seq: first_seq.max(1),
+            events: VecDeque::with_capacity(capacity),
+            capacity,
+        }
+    }
+
+    /// Number a broadcast and keep it, dropping the oldest when full
+    pub fn push(&mut self, event: WebSocketEvent) -> SequencedEvent {
+        let sequenced = SequencedEvent {
+            seq: self.next_seq,
+            event,
+        };
+        self.next_seq += 1;
+        if self.events.len() == self.capacity {
+            self.events.pop_front();
+        }
+        self.events.push_back(sequenced.clone());
+        sequenced
+    }
+
+    /// Number of the latest broadcast, or the one before the first
+    pub fn last_seq(&self) -> u64 {
+        self.next_seq - 1
+    }
+
+    /// The broadcasts after `last_seq`, or `None` when some of them are no
+    /// longer kept or `last_seq` wasn't sent by this run
+    pub fn since(&self, last_seq: u64) -> Option<Vec<SequencedEvent>> {
+        if last_seq > self.last_seq() {
+            return None;
+        }
+        let oldest = self
+            .events
+            .front()
+            .map_or(self.next_seq, |sequenced| sequenced.seq);
+        if last_seq + 1 < oldest {
+            return None;
+        }
+        Some(
+            self.events
+                .iter()
+                .filter(|sequenced| sequenced.seq > last_seq)
+                .cloned()
+                .collect(),
+        )
+    }
+}
+
+fn buffer() -> &'static Mutex<ReplayBuffer> {
+    BUFFER.get_or_init(|| {
+        let started = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(1, |elapsed| elapsed.as_millis() as u64);
+        Mutex::new(ReplayBuffer::new(started, REPLAY_CAPACITY))
+    })
+}
+
+/// Number a broadcast going out to clients and keep it for replay
+pub fn record(event: WebSocketEvent) -> SequencedEvent {
+    buffer()
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+        .push(event)
+}
+
+/// The broadcasts after `last_seq`, if they're all still kept, and the
+/// number of the latest broadcast
+pub fn resume(last_seq: u64) -> (Option<Vec<SequencedEvent>>, u64) {
+    let buffer = buffer()
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner());
+    (buffer.since(last_seq), buffer.last_seq())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn seqs(events: &[SequencedEvent]) -> Vec<u64> {
+        events.iter().map(|sequenced| sequenced.seq).collect()
+    }
+
+    #[test]
+    fn test_replay_buffer() {
+        let mut buffer = ReplayBuffer::new(100, 3);
+        assert_eq!(buffer.last_seq(), 99);
+        assert_eq!(buffer.since(99).map(|events| events.len()), Some(0));
+
+        for zoom in [1.0, 1.5, 2.0, 2.5] {
+            buffer.push(WebSocketEvent::ZoomChanged { zoom });
+        }
+        assert_eq!(buffer.last_seq(), 103);
+
+        // The oldest broadcast was dropped, so only later ones can resume
+        assert_eq!(seqs(&buffer.since(101).unwrap()), [102, 103]);
+        assert_eq!(seqs(&buffer.since(100).unwrap()), [101, 102, 103]);
+        assert!(buffer.since(99).is_none());
+        assert!(buffer.since(103).unwrap().is_empty());
+        assert!(buffer.since(104).is_none());
+    }
+}
@@ -0,0 +1,387 @@
+/*
+ * This file is part of StreamSlate.
+ * Copyright (C) 2025 StreamSlate Contributors
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+
+//! Undoing a `BATCH` that fails part way
+//!
+//! A batch holds the command lock exclusively while it runs, so the state
+//! saved before its first command is still the state to go back to when a
+//! later one fails. Only what its commands can change is saved. Pointer
+//! moves and reactions are momentary and aren't undone, nor are the
+//! connection's own settings such as its subscriptions.
+
+use super::handlers::{emit_page_changed, emit_presenter_changed, emit_zoom_changed, page_changed};
+use crate::capture::qr::QrOverlay;
+use crate::capture::viewport::OutputViewport;
+use crate::commands::annotations::{page_updates, publish_annotation_updates};
+use crate::commands::layers::{layers_for_open_document, set_layer_visibility};
+use crate::commands::media::emit_media_playback;
+use crate::commands::ndi::{set_blackout_state, set_output_viewport_state};
+use crate::commands::pdf::set_smart_invert_state;
+use crate::commands::presets::{set_active_preset_state, style_presets};
+use crate::commands::qr::emit_qr_changed;
+use crate::error::{Result, StreamSlateError};
+use crate::media::MediaPlayer;
+use crate::protocol::{WebSocketCommand, WebSocketEvent};
+use crate::state::crdt::AnnotationCrdt;
+use crate::state::history::{AnnotationHistory, AnnotationTimeline, PageAnnotations, VersionLog};
+use crate::state::{AppState, PdfState};
+use std::collections::BTreeSet;
+use std::sync::{Arc, Mutex, RwLock};
+use std::time::Instant;
+use tauri::AppHandle;
+use tracing::warn;
+
+/// Annotations with their undo history, edit stamps, replay timeline and
+/// restore points
+struct SavedAnnotations {
+    annotations: PageAnnotations,
+    history: AnnotationHistory,
+    crdt: AnnotationCrdt,
+    timeline: AnnotationTimeline,
+    versions: VersionLog,
+}
+
+/// The state a batch's commands can change, as it was before they ran
+pub(super) struct BatchSnapshot {
+    pdf: PdfState,
+    presenter_active: bool,
+    blackout: bool,
+    viewport: OutputViewport,
+    qr_code: Option<QrOverlay>,
+    annotations: Option<SavedAnnotations>,
+    /// The media player and the clips the batch plays, pauses or seeks
+    media: Option<(MediaPlayer, Vec<String>)>,
+    active_preset: Option<Option<String>>,
+    /// Visibility of the layers the batch shows or hides
+    layers: Vec<(String, bool)>,
+}
+
+impl BatchSnapshot {
+    /// Save what `commands` can change
+    pub(super) fn take(state: &AppState, commands: &[WebSocketCommand]) -> Result<Self> {
+        use WebSocketCommand as C;
+
+        let (blackout, viewport, qr_code) = {
+            let outputs = state
+                .outputs
+                .lock()
+                .map_err(|e| StreamSlateError::StateLock(e.to_string()))?;
+            (
+                outputs.blackout,
+                outputs.viewport.target(),
+                outputs.qr_code.clone(),
+            )
+        };
+
+        let annotations = if commands.iter().any(changes_annotations) {
+            Some(SavedAnnotations {
+                annotations: read(&state.annotations)?,
+                history: lock(&state.annotation_history)?,
+                crdt: lock(&state.annotation_crdt)?,
+                timeline: lock(&state.annotation_timeline)?,
+                versions: lock(&state.annotation_versions)?,
+            })
+        } else {
+            None
+        };
+
+        let clips: Vec<String> = commands
+            .iter()
+            .filter_map(|command| match command {
+                C::PlayMedia { id } | C::PauseMedia { id } | C::SeekMedia { id, .. } => {
+                    Some(id.clone())
+                }
+                _ => None,
+            })
+            .collect();
+        let media = if clips.is_empty() {
+            None
+        } else {
+            Some((lock(&state.media)?, clips))
+        };
+
+        let active_preset = if commands
+            .iter()
+            .any(|command| matches!(command, C::SetActiveStylePreset { .. }))
+        {
+            Some(style_presets(state)?.active)
+        } else {
+            None
+        };
+
+        let layers = if commands
+            .iter()
+            .any(|command| matches!(command, C::SetLayerVisible { .. }))
+        {
+            layers_for_open_document(state)?
+                .into_iter()
+                .map(|layer| (layer.id, layer.visible))
+                .collect()
+        } else {
+            Vec::new()
+        };
+
+        Ok(Self {
+            pdf: state.get_pdf_state()?,
+            presenter_active: state.get_presenter_state()?.is_active,
+            blackout,
+            viewport,
+            qr_code,
+            annotations,
+            media,
+            active_preset,
+            layers,
+        })
+    }
+
+    /// Put everything back, telling the webview and clients what changed
+    pub(super) fn restore(self, state: &Arc<AppState>, app_handle: &AppHandle) {
+        let mut events = match self.revert(state) {
+            Ok(events) => events,
+            Err(e) => {
+                warn!(error = %e, "Failed to undo BATCH");
+                Vec::new()
+            }
+        };
+        for event in &events {
+            match event {
+                WebSocketEvent::PageChanged {
+                    page, total_pages, ..
+                } => emit_page_changed(app_handle, *page, *total_pages),
+                WebSocketEvent::ZoomChanged { zoom } => emit_zoom_changed(app_handle, *zoom),
+                WebSocketEvent::PresenterChanged { active } => {
+                    emit_presenter_changed(app_handle, *active)
+                }
+                WebSocketEvent::QrCodeChanged { data } => {
+                    emit_qr_changed(app_handle, data.as_deref())
+                }
+                WebSocketEvent::MediaPlaybackChanged { playback } => {
+                    emit_media_playback(app_handle, playback)
+                }
+                _ => {}
+            }
+        }
+        // Annotation updates go to the webview and clients in one
+        events.retain(|event| match event {
+            WebSocketEvent::AnnotationsUpdated { annotations } => {
+                publish_annotation_updates(state, app_handle, annotations);
+                false
+            }
+            _ => true,
+        });
+
+        // Settings stored elsewhere go back through their own setters
+        let mut restored = |result: Result<WebSocketEvent>| match result {
+            Ok(event) => events.push(event),
+            Err(e) => warn!(error = %e, "Failed to undo BATCH"),
+        };
+        let (pdf, outputs) = (state.get_pdf_state(), state.outputs.lock());
+        let (smart_invert, blackout, viewport) = match (pdf, outputs) {
+            (Ok(pdf), Ok(outputs)) => (
+                pdf.smart_invert,
+                outputs.blackout,
+                outputs.viewport.target(),
+            ),
+            _ => (self.pdf.smart_invert, self.blackout, self.viewport),
+        };
+        if smart_invert != self.pdf.smart_invert {
+            let enabled = self.pdf.smart_invert;
+            restored(
+                set_smart_invert_state(state, app_handle, enabled)
+                    .map(|()| WebSocketEvent::SmartInvertChanged { enabled }),
+            );
+        }
+        if blackout != self.blackout {
+            let enabled = self.blackout;
+            restored(
+                set_blackout_state(state, app_handle, enabled)
+                    .map(|()| WebSocketEvent::BlackoutChanged { enabled }),
+            );
+        }
+        if viewport != self.viewport {
+            restored(
+                set_output_viewport_state(state, app_handle, self.viewport, None)
+                    .map(|viewport| WebSocketEvent::OutputViewportChanged { viewport }),
+            );
+        }
+        if let Some(active) = self.active_preset {
+            if style_presets(state).is_ok_and(|store| store.active != active) {
+                restored(
+                    set_active_preset_state(state, app_handle, active).map(|store| {
+                        WebSocketEvent::StylePresetsChanged {
+                            presets: store.presets,
+                            active: store.active,
+                        }
+                    }),
+                );
+            }
+        }
+        if !self.layers.is_empty() {
+            let current = layers_for_open_document(state).unwrap_or_default();
+            let changed: Vec<&(String, bool)> = self
+                .layers
+                .iter()
+                .filter(|(id, visible)| {
+                    current
+                        .iter()
+                        .any(|layer| &layer.id == id && layer.visible != *visible)
+                })
+                .collect();
+            for (id, visible) in changed {
+                restored(
+                    set_layer_visibility(state, app_handle, id, *visible)
+                        .map(|layers| WebSocketEvent::LayersChanged { layers }),
+                );
+            }
+        }
+
+        for event in events {
+            if let Err(e) = state.broadcast(event) {
+                warn!(error = %e, "Failed to broadcast undone BATCH");
+            }
+        }
+    }
+
+    /// Put back the state held in memory: page, zoom, presenter mode, QR
+    /// code, annotations and media, returning the events announcing what
+    /// changed
+    pub(super) fn revert(&self, state: &AppState) -> Result<Vec<WebSocketEvent>> {
+        let mut events = Vec::new();
+
+        let pdf = state.get_pdf_state()?;
+        if pdf.current_page != self.pdf.current_page || pdf.zoom_level != self.pdf.zoom_level {
+            state.update_pdf_state(|s| {
+                s.current_page = self.pdf.current_page;
+                s.zoom_level = self.pdf.zoom_level;
+            })?;
+        }
+        if pdf.current_page != self.pdf.current_page {
+            events.push(page_changed(
+                state,
+                self.pdf.current_page,
+                self.pdf.total_pages,
+            ));
+        }
+        if pdf.zoom_level != self.pdf.zoom_level {
+            events.push(WebSocketEvent::ZoomChanged {
+                zoom: self.pdf.zoom_level,
+            });
+        }
+
+        if state.get_presenter_state()?.is_active != self.presenter_active {
+            state.update_presenter_state(|s| s.is_active = self.presenter_active)?;
+            events.push(WebSocketEvent::PresenterChanged {
+                active: self.presenter_active,
+            });
+        }
+
+        {
+            let mut outputs = state
+                .outputs
+                .lock()
+                .map_err(|e| StreamSlateError::StateLock(e.to_string()))?;
+            let shown = |qr_code: &Option<QrOverlay>| {
+                qr_code
+                    .as_ref()
+                    .map(|overlay| (overlay.data.clone(), overlay.shown_at))
+            };
+            if shown(&outputs.qr_code) != shown(&self.qr_code) {
+                outputs.qr_code = self.qr_code.clone();
+                events.push(WebSocketEvent::QrCodeChanged {
+                    data: self.qr_code.as_ref().map(|overlay| overlay.data.clone()),
+                });
+            }
+        }
+
+        if let Some(saved) = &self.annotations {
+            let mut annotations = state
+                .annotations
+                .write()
+                .map_err(|e| StreamSlateError::StateLock(e.to_string()))?;
+            let pages: BTreeSet<u32> = annotations
+                .keys()
+                .chain(saved.annotations.keys())
+                .filter(|page| annotations.get(page) != saved.annotations.get(page))
+                .copied()
+                .collect();
+            *annotations = saved.annotations.clone();
+            put(&state.annotation_history, &saved.history)?;
+            put(&state.annotation_crdt, &saved.crdt)?;
+            put(&state.annotation_timeline, &saved.timeline)?;
+            put(&state.annotation_versions, &saved.versions)?;
+            if !pages.is_empty() {
+                events.push(WebSocketEvent::AnnotationsUpdated {
+                    annotations: page_updates(&annotations, pages),
+                });
+            }
+        }
+
+        if let Some((player, clips)) = &self.media {
+            let mut media = state
+                .media
+                .lock()
+                .map_err(|e| StreamSlateError::StateLock(format!("Media: {e}")))?;
+            *media = player.clone();
+            let now = Instant::now();
+            let clips: BTreeSet<&String> = clips.iter().collect();
+            events.extend(
+                clips
+                    .into_iter()
+                    .map(|id| WebSocketEvent::MediaPlaybackChanged {
+                        playback: media.status(id, now),
+                    }),
+            );
+        }
+
+        Ok(events)
+    }
+}
+
+/// Whether `command` can change the annotations
+fn changes_annotations(command: &WebSocketCommand) -> bool {
+    use WebSocketCommand as C;
+    matches!(
+        command,
+        C::AddAnnotation { .. }
+            | C::UpdateAnnotation { .. }
+            | C::DeleteAnnotation { .. }
+            | C::ClearAnnotations
+            | C::UndoAnnotation
+            | C::RedoAnnotation
+            | C::EraseAt { .. }
+    )
+}
+
+fn read<T: Clone>(lock: &RwLock<T>) -> Result<T> {
+    lock.read()
+        .map(|value| value.clone())
+        .map_err(|e| StreamSlateError::StateLock(e.to_string()))
+}
+
+fn lock<T: Clone>(lock: &Mutex<T>) -> Result<T> {
+    lock.lock()
+        .map(|value| value.clone())
+        .map_err(|e| StreamSlateError::StateLock(e.to_string()))
+}
+
+fn put<T: Clone>(lock: &Mutex<T>, saved: &T) -> Result<()> {
+    *lock
+        .lock()
+        .map_err(|e| StreamSlateError::StateLock(e.to_string()))? = saved.clone();
+    Ok(())
+}
@@ -0,0 +1,252 @@
+/*
+ * This file is part of StreamSlate.
+ * Copyright (C) 2025 StreamSlate Contributors
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+
+//! Protocol schema export
+//!
+//! Generates JSON Schema and TypeScript definitions for the WebSocket
+//! protocol from the Rust types, so the frontend and plugin authors can't
+//! drift from `src/protocol.rs`. Regenerate with `npm run protocol:schema`.
+
+use crate::protocol::{RequestId, WebSocketCommand, WebSocketEvent};
+use schemars::gen::SchemaSettings;
+use serde_json::{json, Value};
+use std::fmt::Write;
+use std::path::{Path, PathBuf};
+
+/// Generated JSON Schema, relative to the repository root
+pub const SCHEMA_PATH: &str = "docs/protocol.schema.json";
+
+/// Generated TypeScript definitions, relative to the repository root
+pub const TYPESCRIPT_PATH: &str = "src/types/protocol.generated.ts";
+
+const TYPESCRIPT_HEADER: &str = "\
+/*
+ * This file is part of StreamSlate.
+ * Copyright (C) 2025 StreamSlate Contributors
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+
+// Generated from src-tauri/src/protocol.rs.
+// Do not edit by hand; run `npm run protocol:schema` instead.
+";
+
+/// JSON Schema (draft-07) with every protocol type under `definitions`
+pub fn protocol_schema() -> Value {
+    let mut gen = SchemaSettings::draft07().into_generator();
+    gen.subschema_for::<WebSocketCommand>();
+    gen.subschema_for::<WebSocketEvent>();
+    gen.subschema_for::<RequestId>();
+
+    json!({
+        "$schema": "http://json-schema.org/draft-07/schema#",
+        "title": "StreamSlate WebSocket protocol",
+        "definitions": gen.definitions(),
+    })
+}
+
+/// TypeScript type definitions for every protocol type
+pub fn typescript_definitions() -> String {
+    let schema = protocol_schema();
+    let mut out = String::from(TYPESCRIPT_HEADER);
+
+    if let Some(definitions) = schema["definitions"].as_object() {
+        for (name, definition) in definitions {
+            out.push('\n');
+            if let Some(description) = definition["description"].as_str() {
+                let _ = writeln!(out, "/** {description} */");
+            }
+            let _ = writeln!(out, "export type {name} ={};", top_level_type(definition));
+        }
+    }
+
+    out
+}
+
+/// Write both generated files below `repo_root`, returning their paths
+pub fn write_generated_files(repo_root: &Path) -> std::io::Result<Vec<PathBuf>> {
+    let schema_path = repo_root.join(SCHEMA_PATH);
+    let typescript_path = repo_root.join(TYPESCRIPT_PATH);
+
+    std::fs::write(&schema_path, schema_json())?;
+    std::fs::write(&typescript_path, typescript_definitions())?;
+
+    Ok(vec![schema_path, typescript_path])
+}
+
+/// Pretty-printed schema with a trailing newline, as written to disk
+pub fn schema_json() -> String {
+    let mut json = serde_json::to_string_pretty(&protocol_schema()).unwrap_or_default();
+    json.push('\n');
+    json
+}
+
+/// Put each member of a top-level union on its own line
+fn top_level_type(schema: &Value) -> String {
+    match union_members(schema) {
+        Some(members) => members
+            .iter()
+            .map(|member| format!("\n  | {}", ts_type(member)))
+            .collect(),
+        None => format!(" {}", ts_type(schema)),
+    }
+}
+
+fn union_members(schema: &Value) -> Option<&Vec<Value>> {
+    schema["oneOf"]
+        .as_array()
+        .or_else(|| schema["anyOf"].as_array())
+}
+
+/// Convert a (sub)schema into a TypeScript type expression
+fn ts_type(schema: &Value) -> String {
+    if schema == &Value::Bool(true) || schema.as_object().is_some_and(|o| o.is_empty()) {
+        return "unknown".to_string();
+    }
+
+    if let Some(reference) = schema["$ref"].as_str() {
+        return reference
+            .rsplit('/')
+            .next()
+            .unwrap_or(reference)
+            .to_string();
+    }
+
+    if let Some(members) = union_members(schema) {
+        return members.iter().map(ts_type).collect::<Vec<_>>().join(" | ");
+    }
+
+    if let Some(parts) = schema["allOf"].as_array() {
+        return match parts.as_slice() {
+            [single] => ts_type(single),
+            parts => parts.iter().map(ts_type).collect::<Vec<_>>().join(" & "),
+        };
+    }
+
+    if let Some(values) = schema["enum"].as_array() {
+        return values
+            .iter()
+            .map(|value| value.to_string())
+            .collect::<Vec<_>>()
+            .join(" | ");
+    }
+
+    let types: Vec<&str> = match &schema["type"] {
+        Value::String(t) => vec![t.as_str()],
+        Value::Array(ts) => ts.iter().filter_map(Value::as_str).collect(),
+        _ => return "unknown".to_string(),
+    };
+
+    types
+        .iter()
+        .map(|t| match *t {
+            "string" => "string".to_string(),
+            "integer" | "number" => "number".to_string(),
+            "boolean" => "boolean".to_string(),
+            "null" => "null".to_string(),
+            "array" => {
+                let item = ts_type(&schema["items"]);
+                if item.contains(' ') {
+                    format!("({item})[]")
+                } else {
+                    format!("{item}[]")
+                }
+            }
+            "object" => object_type(schema),
+            _ => "unknown".to_string(),
+        })
+        .collect::<Vec<_>>()
+        .join(" | ")
+}
+
+fn object_type(schema: &Value) -> String {
+    let Some(properties) = schema["properties"].as_object() else {
+        return match &schema["additionalProperties"] {
+            Value::Object(_) => format!(
+                "Record<string, {}>",
+                ts_type(&schema["additionalProperties"])
+            ),
+            _ => "Record<string, unknown>".to_string(),
+        };
+    };
+
+    let required: Vec<&str> = schema["required"]
+        .as_array()
+        .map(|r| r.iter().filter_map(Value::as_str).collect())
+        .unwrap_or_default();
+
+    // Lead with the `type` tag so unions read naturally
+    let mut entries: Vec<(&String, &Value)> = properties.iter().collect();
+    entries.sort_by_key(|(name, _)| name.as_str() != "type");
+
+    let fields: Vec<String> = entries
+        .into_iter()
+        .map(|(name, property)| {
+            let optional = if required.contains(&name.as_str()) {
+                ""
+            } else {
+                "?"
+            };
+            format!("{name}{optional}: {}", ts_type(property))
+        })
+        .collect();
+
+    format!("{{ {} }}", fields.join("; "))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn repo_root() -> PathBuf {
+        Path::new(env!("CARGO_MANIFEST_DIR")).join("..")
+    }
+
+    #[test]
+    fn test_typescript_covers_protocol() {
+        let ts = typescript_definitions();
+        assert!(ts.contains("export type WebSocketCommand ="));
+        assert!(ts.contains("export type WebSocketEvent ="));
+        assert!(ts.contains("| { type: \"GO_TO_PAGE\"; page: number }"));
+        assert!(ts.contains("thumbnail_size?: number | null"));
+    }
+
+    #[test]
+    fn test_generated_files_up_to_date() {
+        let root = repo_root();
+        let stale = |path: &str, expected: String| {
+            std::fs::read_to_string(root.join(path)).ok() != Some(expected)
+        };
+
+        assert!(
+            !stale(SCHEMA_PATH, schema_json()) && !stale(TYPESCRIPT_PATH, typescript_definitions()),
+            "Generated protocol files are out of date; run `npm run protocol:schema`"
+        );
+    }
+}
@@ -18,40 +18,191 @@
 
 //! WebSocket server implementation using tokio-tungstenite
 
-use super::handlers::handle_command;
-use super::protocol::{WebSocketCommand, WebSocketEvent};
+use super::access;
+use super::clients::Disconnect;
+use super::coalesce::Coalescer;
+use super::config::WebSocketConfig;
+use super::deflate;
+use super::handlers::{handle_command, page_image_reply};
+use super::heartbeat::{Beat, Heartbeat, HEARTBEAT_INTERVAL};
+use super::outbox::{Outbox, QUEUE_CAPACITY};
+use super::rate::CommandLimiter;
+use super::replay;
+use super::session::ClientSession;
+use super::tls::{self, TlsConfig};
+use crate::commands::agenda::section_progress;
+use crate::error::ErrorCode;
+use crate::protocol::{parse_command, reply_json, SequencedEvent, WebSocketEvent};
+use crate::settings::load_settings;
 use crate::state::AppState;
-use futures_util::{SinkExt, StreamExt};
-use std::sync::Arc;
-use tauri::AppHandle;
-use tokio::net::{TcpListener, TcpStream};
-use tokio::sync::broadcast;
+use futures_util::{Sink, SinkExt, StreamExt};
+use serde::Serialize;
+use std::io::ErrorKind;
+use std::net::SocketAddr;
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter};
+use tokio::io::{AsyncRead, AsyncWrite};
+use tokio::net::TcpListener;
+use tokio::sync::{broadcast, oneshot};
+use tokio::task::JoinHandle;
+use tokio::time::MissedTickBehavior;
+use tokio_tungstenite::tungstenite::protocol::frame::coding::CloseCode;
+use tokio_tungstenite::tungstenite::protocol::CloseFrame;
+use tokio_tungstenite::tungstenite::Error as WsError;
 use tokio_tungstenite::{accept_async, tungstenite::Message};
 use tracing::{debug, error, info, warn};
 
 /// Default port for the WebSocket server
 pub const DEFAULT_PORT: u16 = 11451;
 
-/// Start the WebSocket server
+/// How often expired reactions are pruned
+const REACTION_EXPIRY_INTERVAL: Duration = Duration::from_secs(5);
+
+/// How long a closing connection may take to send what's queued
+const FLUSH_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Tauri event sent when the server starts listening
+pub const SERVER_STARTED_EVENT: &str = "websocket-server-started";
+
+/// Tauri event sent when the server can't start
+pub const SERVER_FAILED_EVENT: &str = "websocket-server-failed";
+
+/// Tauri event sent when the server is stopped
+pub const SERVER_STOPPED_EVENT: &str = "websocket-server-stopped";
+
+/// Tauri event sent when a client connects
+pub const CLIENT_CONNECTED_EVENT: &str = "ws-client-connected";
+
+/// Tauri event sent when a client's connection closes
+pub const CLIENT_DISCONNECTED_EVENT: &str = "ws-client-disconnected";
+
+/// Accept loop of the running listener
+static LISTENER: Mutex<Option<JoinHandle<()>>> = Mutex::new(None);
+
+/// Broadcasts as relayed to clients, after coalescing, numbered for replay
+static CLIENT_EVENTS: OnceLock<broadcast::Sender<SequencedEvent>> = OnceLock::new();
+
+/// Payload of `websocket-server-started`
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ServerStarted {
+    addr: String,
+    port: u16,
+    /// The configured port, which differs from `port` when it was taken
+    requested_port: u16,
+}
+
+/// Payload of `websocket-server-failed`
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ServerFailed {
+    requested_port: u16,
+    error: String,
+}
+
+/// Payload of `ws-client-connected` and `ws-client-disconnected`
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ClientConnection {
+    /// Connection ID, as listed by `list_ws_clients`
+    id: Option<u64>,
+    peer: String,
+    /// Open connections after the change
+    active_connections: u32,
+}
+
+/// Start the WebSocket server, replacing the listener if it's running
 ///
-/// This spawns a background task that listens for connections on the specified port.
-/// Returns a broadcast sender that can be used to send events to all connected clients.
+/// This spawns a background task that listens for connections on the configured
+/// address and returns the address bound. When the port is taken, the fallback
+/// ports after it are tried in turn. The outcome is emitted to the webview as
+/// `websocket-server-started` or `websocket-server-failed`.
+///
+/// Events go out over the broadcast channel in `state`, created on first start
+/// and kept across restarts, and reach clients through a relay that merges
+/// rapid zoom and page changes; connected clients stay connected when the
+/// listener is replaced. With TLS turned on, only `wss://` connections are accepted; a
+/// certificate that can't be loaded keeps the server from starting.
 pub async fn start_server(
-    port: u16,
+    config: &WebSocketConfig,
     state: Arc<AppState>,
     app_handle: AppHandle,
-) -> Result<broadcast::Sender<WebSocketEvent>, std::io::Error> {
-    let addr = format!("127.0.0.1:{}", port);
-    let listener = TcpListener::bind(&addr).await?;
+) -> Result<SocketAddr, std::io::Error> {
+    let result = listen(config, Arc::clone(&state), app_handle.clone()).await;
+    let emitted = match &result {
+        Ok(addr) => {
+            if addr.port() != config.port {
+                warn!(
+                    requested = config.port,
+                    port = addr.port(),
+                    "WebSocket port taken, using a fallback port"
+                );
+            }
+            app_handle.emit(
+                SERVER_STARTED_EVENT,
+                ServerStarted {
+                    addr: addr.to_string(),
+                    port: addr.port(),
+                    requested_port: config.port,
+                },
+            )
+        }
+        Err(e) => {
+            error!(port = config.port, error = %e, "WebSocket server failed to start");
+            if let Ok(mut websocket) = state.websocket.write() {
+                websocket.listening = LISTENER.lock().is_ok_and(|task| task.is_some());
+            }
+            app_handle.emit(
+                SERVER_FAILED_EVENT,
+                ServerFailed {
+                    requested_port: config.port,
+                    error: e.to_string(),
+                },
+            )
+        }
+    };
+    if let Err(e) = emitted {
+        warn!(error = %e, "Failed to emit WebSocket server status");
+    }
+    result
+}
+
+async fn listen(
+    config: &WebSocketConfig,
+    state: Arc<AppState>,
+    app_handle: AppHandle,
+) -> Result<SocketAddr, std::io::Error> {
+    let invalid = |e: crate::error::StreamSlateError| {
+        std::io::Error::new(ErrorKind::InvalidInput, e.to_string())
+    };
+    let candidates = config.candidates().map_err(invalid)?;
+    let tls_config: TlsConfig = load_settings(&app_handle, tls::CONFIG_FILE);
+    let acceptor = tls::acceptor_for(&app_handle, &tls_config).map_err(invalid)?;
+    let config = config.clone();
 
-    info!(port = port, "WebSocket server started on {}", addr);
+    let (tx, events) = channels(&state);
 
-    // Create broadcast channel for sending events to all clients
-    let (tx, _rx) = broadcast::channel::<WebSocketEvent>(100);
-    let tx_clone = tx.clone();
+    // Free the port before binding it again
+    stop_listener().await;
+    let listener = bind_first(&candidates).await?;
+    let addr = listener.local_addr()?;
+
+    let scheme = if acceptor.is_some() { "wss" } else { "ws" };
+    if !addr.ip().is_loopback() {
+        warn!(%addr, "WebSocket server accepts connections from the network");
+    }
+    info!(
+        port = addr.port(),
+        "WebSocket server started on {}://{}", scheme, addr
+    );
+    if let Ok(mut websocket) = state.websocket.write() {
+        websocket.listening = true;
+        websocket.port = addr.port();
+    }
 
     // Spawn the server task
-    tokio::spawn(async move {
+    let task = tokio::spawn(async move {
         loop {
             match listener.accept().await {
                 Ok((stream, peer_addr)) => {
@@ -59,11 +210,30 @@ pub async fn start_server(
 
                     let state = Arc::clone(&state);
                     let app_handle = app_handle.clone();
-                    let tx = tx_clone.clone();
-                    let rx = tx_clone.subscribe();
+                    let rx = events.subscribe();
+                    let tx = tx.clone();
+                    let acceptor = acceptor.clone();
+                    let config = config.clone();
 
                     tokio::spawn(async move {
-                        if let Err(e) = handle_connection(stream, state, app_handle, tx, rx).await {
+                        let result = match acceptor {
+                            Some(acceptor) => match acceptor.accept(stream).await {
+                                Ok(stream) => {
+                                    handle_connection(
+                                        stream, peer_addr, state, app_handle, config, tx, rx,
+                                    )
+                                    .await
+                                }
+                                Err(e) => Err(e.into()),
+                            },
+                            None => {
+                                handle_connection(
+                                    stream, peer_addr, state, app_handle, config, tx, rx,
+                                )
+                                .await
+                            }
+                        };
+                        if let Err(e) = result {
                             warn!(peer = %peer_addr, error = %e, "Connection error");
                         }
                         info!(peer = %peer_addr, "WebSocket connection closed");
@@ -75,62 +245,266 @@ pub async fn start_server(
             }
         }
     });
+    if let Ok(mut listener) = LISTENER.lock() {
+        *listener = Some(task);
+    }
+
+    Ok(addr)
+}
+
+/// The broadcast channel commands send state changes on, and the relay of
+/// its events to clients, created on first use
+///
+/// Both outlive the listener, so clients stay connected across restarts.
+pub(crate) fn channels(
+    state: &Arc<AppState>,
+) -> (
+    broadcast::Sender<WebSocketEvent>,
+    broadcast::Sender<SequencedEvent>,
+) {
+    // Create broadcast channel for sending events to all clients
+    let tx = match state.broadcast_sender.get() {
+        Some(tx) => tx.clone(),
+        None => {
+            let (tx, _rx) = broadcast::channel::<WebSocketEvent>(100);
+            spawn_reaction_expiry(Arc::clone(state), tx.clone());
+            if let Err(e) = state.set_broadcast_sender(tx.clone()) {
+                warn!("Failed to set broadcast sender: {}", e);
+            }
+            tx
+        }
+    };
+    // Clients get the broadcasts through the coalescer
+    let events = CLIENT_EVENTS
+        .get_or_init(|| {
+            let (events, _rx) = broadcast::channel::<SequencedEvent>(100);
+            spawn_coalescer(tx.subscribe(), events.clone());
+            events
+        })
+        .clone();
+    (tx, events)
+}
+
+/// Bind the first address whose port is free
+async fn bind_first(candidates: &[SocketAddr]) -> Result<TcpListener, std::io::Error> {
+    for addr in candidates {
+        match TcpListener::bind(addr).await {
+            Ok(listener) => return Ok(listener),
+            Err(e) if e.kind() == ErrorKind::AddrInUse => {
+                debug!(%addr, "WebSocket port taken");
+            }
+            Err(e) => return Err(e),
+        }
+    }
+    let ports = match (candidates.first(), candidates.last()) {
+        (Some(first), Some(last)) if first != last => {
+            format!("Ports {}-{} are", first.port(), last.port())
+        }
+        (Some(addr), _) => format!("Port {} is", addr.port()),
+        _ => "No port is".to_string(),
+    };
+    Err(std::io::Error::new(
+        ErrorKind::AddrInUse,
+        format!("{ports} already in use"),
+    ))
+}
+
+/// Stop the WebSocket server
+///
+/// The listener stops accepting connections and releases its port, and every
+/// connected client is sent a close frame (`1001`, "Server stopped") so it
+/// knows to reconnect later rather than at once. The broadcast channel is
+/// kept for the next start. Returns whether the server was listening.
+pub async fn stop_server(state: &Arc<AppState>, app_handle: &AppHandle) -> bool {
+    let was_listening = LISTENER.lock().is_ok_and(|task| task.is_some());
+    stop_listener().await;
+    let closed = match state.ws_clients.lock() {
+        Ok(mut clients) => clients.disconnect_all(),
+        Err(e) => {
+            warn!(error = %e, "Failed to disconnect WebSocket clients");
+            0
+        }
+    };
+    if let Ok(mut websocket) = state.websocket.write() {
+        websocket.listening = false;
+    }
+    info!(clients = closed, "WebSocket server stopped");
+    if let Err(e) = app_handle.emit(SERVER_STOPPED_EVENT, ()) {
+        warn!(error = %e, "Failed to emit WebSocket server status");
+    }
+    was_listening
+}
+
+/// Stop accepting connections, waiting until the port is released
+async fn stop_listener() {
+    let task = LISTENER
+        .lock()
+        .ok()
+        .and_then(|mut listener| listener.take());
+    if let Some(task) = task {
+        task.abort();
+        let _ = task.await;
+    }
+}
+
+/// Re-announce reaction counts as reactions expire, so overlays decay
+fn spawn_reaction_expiry(state: Arc<AppState>, tx: broadcast::Sender<WebSocketEvent>) {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(REACTION_EXPIRY_INTERVAL);
+        loop {
+            interval.tick().await;
+            let now = Instant::now();
+            let Ok(mut board) = state.reactions.lock() else {
+                continue;
+            };
+            for page in board.prune(now) {
+                let _ = tx.send(WebSocketEvent::ReactionsUpdated {
+                    page,
+                    reactions: board.counts(page, now),
+                });
+            }
+        }
+    });
+}
 
-    Ok(tx)
+/// Relay broadcasts to clients, merging rapid zoom and page changes and
+/// keeping them for replay
+fn spawn_coalescer(
+    mut rx: broadcast::Receiver<WebSocketEvent>,
+    events: broadcast::Sender<SequencedEvent>,
+) {
+    tokio::spawn(async move {
+        let mut coalescer = Coalescer::default();
+        loop {
+            let deadline = coalescer.deadline();
+            let due = tokio::time::sleep_until(
+                deadline.map_or_else(tokio::time::Instant::now, tokio::time::Instant::from_std),
+            );
+            tokio::select! {
+                event = rx.recv() => match event {
+                    Ok(event) => {
+                        for event in coalescer.push(event, Instant::now()) {
+                            let _ = events.send(replay::record(event));
+                        }
+                    }
+                    Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                        warn!(skipped, "Broadcast relay lagged behind");
+                    }
+                    Err(broadcast::error::RecvError::Closed) => break,
+                },
+                _ = due, if deadline.is_some() => {
+                    if let Some(event) = coalescer.flush(Instant::now()) {
+                        let _ = events.send(replay::record(event));
+                    }
+                }
+            }
+        }
+    });
 }
 
-/// Handle a single WebSocket connection
-async fn handle_connection(
-    stream: TcpStream,
+/// A client connection, plain, over TLS or through the compression pumps
+trait Socket: AsyncRead + AsyncWrite + Unpin + Send {}
+
+impl<S: AsyncRead + AsyncWrite + Unpin + Send> Socket for S {}
+
+/// Handle a single WebSocket connection, plain or over TLS
+async fn handle_connection<S: AsyncRead + AsyncWrite + Unpin + Send + 'static>(
+    stream: S,
+    peer_addr: SocketAddr,
     state: Arc<AppState>,
     app_handle: AppHandle,
+    config: WebSocketConfig,
     tx: broadcast::Sender<WebSocketEvent>,
-    mut rx: broadcast::Receiver<WebSocketEvent>,
+    mut rx: broadcast::Receiver<SequencedEvent>,
 ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    let stream: Box<dyn Socket> = if config.compression {
+        Box::new(deflate::compressed(stream))
+    } else {
+        Box::new(stream)
+    };
     let ws_stream = accept_async(stream).await?;
-    let (mut ws_sender, mut ws_receiver) = ws_stream.split();
+    let (ws_sender, mut ws_receiver) = ws_stream.split();
+    let outbox = Arc::new(Outbox::new(QUEUE_CAPACITY));
+    let mut writer = Writer(tokio::spawn(write_queued(ws_sender, Arc::clone(&outbox))));
+    let mut session = ClientSession::new(peer_addr);
+    session.role = access::default_client_role();
+    let mut limiter = CommandLimiter::new(&config.rate_limits, Instant::now());
+    let (mut registration, mut disconnect) = Registration::new(&state, &app_handle, &tx, &session);
+    let mut remote_view = state.remote_view.subscribe();
+    let mut page_images = state.page_images.published.subscribe();
+    let mut heartbeat = Heartbeat::new(Instant::now());
+    let mut heartbeats = tokio::time::interval_at(
+        tokio::time::Instant::now() + HEARTBEAT_INTERVAL,
+        HEARTBEAT_INTERVAL,
+    );
+    heartbeats.set_missed_tick_behavior(MissedTickBehavior::Delay);
 
     // Send connected event
     let connected_event = WebSocketEvent::connected();
     let connected_msg = serde_json::to_string(&connected_event)?;
-    ws_sender.send(Message::Text(connected_msg)).await?;
+    outbox.send(Message::Text(connected_msg));
 
     // Send current state
     let state_event = get_current_state(&state);
     let state_msg = serde_json::to_string(&state_event)?;
-    ws_sender.send(Message::Text(state_msg)).await?;
+    outbox.send(Message::Text(state_msg));
 
     loop {
         tokio::select! {
-            // Handle incoming messages from client
-            msg = ws_receiver.next() => {
+            // Handle incoming messages from client, unless it isn't reading
+            // the replies
+            msg = ws_receiver.next(), if !outbox.is_backed_up() => {
+                if let Some(Ok(_)) = msg {
+                    heartbeat.seen(Instant::now());
+                }
                 match msg {
                     Some(Ok(Message::Text(text))) => {
-                        debug!(msg = %text, "Received WebSocket message");
+                        debug!(client = %session.label(), msg = %text, "Received WebSocket message");
 
-                        match serde_json::from_str::<WebSocketCommand>(&text) {
+                        let (request_id, command) = parse_command(&text);
+                        match command {
                             Ok(command) => {
-                                let response = handle_command(command, &state, &app_handle);
+                                if let Err(limited) = limiter.check(&command, Instant::now()) {
+                                    warn!(client = %session.label(), command = %command.name(), "Command rate limited");
+                                    let limited_msg = reply_json(request_id.as_ref(), &limited)?;
+                                    outbox.send(Message::Text(limited_msg));
+                                    continue;
+                                }
+                                let was_viewing = session.remote_view;
+                                let was_following = session.page_images;
+                                registration.refresh(&mut session);
+                                let response = handle_command(command, &state, &app_handle, &mut session);
+                                registration.update(&session);
+                                if session.remote_view && !was_viewing {
+                                    // Start with the frame already on screen
+                                    remote_view.mark_changed();
+                                }
 
                                 // Send response back to this client
-                                let response_msg = serde_json::to_string(&response)?;
-                                ws_sender.send(Message::Text(response_msg)).await?;
+                                let response_msg = reply_json(request_id.as_ref(), &response)?;
+                                send_reply(&outbox, response_msg, &mut session);
+                                if session.page_images.is_some() && session.page_images != was_following {
+                                    send_current_page_image(&outbox, &state, &app_handle, &mut session)?;
+                                }
 
                                 // Broadcast state-changing events to all clients
-                                if should_broadcast(&response) {
-                                    let _ = tx.send(response);
+                                for event in broadcast_events(&response) {
+                                    let _ = tx.send(event.clone());
                                 }
                             }
                             Err(e) => {
-                                warn!(error = %e, "Failed to parse WebSocket command");
-                                let error_event = WebSocketEvent::error(format!("Invalid command: {}", e));
-                                let error_msg = serde_json::to_string(&error_event)?;
-                                ws_sender.send(Message::Text(error_msg)).await?;
+                                warn!(client = %session.label(), error = %e, "Failed to parse WebSocket command");
+                                let error_event = WebSocketEvent::error_code(
+                                    ErrorCode::InvalidCommand,
+                                    format!("Invalid command: {}", e),
+                                );
+                                let error_msg = reply_json(request_id.as_ref(), &error_event)?;
+                                outbox.send(Message::Text(error_msg));
                             }
                         }
                     }
                     Some(Ok(Message::Ping(data))) => {
-                        ws_sender.send(Message::Pong(data)).await?;
+                        outbox.send(Message::Pong(data));
                     }
                     Some(Ok(Message::Close(_))) => {
                         break;
@@ -149,27 +523,285 @@ async fn handle_connection(
             // Handle broadcast events from other connections
             event = rx.recv() => {
                 match event {
-                    Ok(event) => {
-                        let msg = serde_json::to_string(&event)?;
-                        if ws_sender.send(Message::Text(msg)).await.is_err() {
-                            break;
+                    Ok(sequenced) => {
+                        if sequenced.seq <= session.last_seq {
+                            // Already replayed by RESUME
+                            continue;
+                        }
+                        session.last_seq = sequenced.seq;
+                        if session.wants(&sequenced.event) {
+                            let msg = serde_json::to_string(&sequenced)?;
+                            if outbox.send_droppable(Message::Text(msg)) {
+                                registration.dropped(&session, 1);
+                            }
+                        }
+                        if matches!(sequenced.event, WebSocketEvent::PageChanged { .. }) {
+                            send_current_page_image(&outbox, &state, &app_handle, &mut session)?;
+                        }
+                    }
+                    Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                        registration.dropped(&session, skipped);
+                    }
+                    Err(broadcast::error::RecvError::Closed) => {
+                        break;
+                    }
+                }
+            }
+
+            // Send page images this client is waiting for once the webview
+            // has rendered them
+            image = page_images.recv() => {
+                match image {
+                    Ok(image) => {
+                        if session.pending_images.remove(&(image.page, image.size)) {
+                            session.binary_out.push(image.data.clone());
+                            let header = serde_json::to_string(&image.header())?;
+                            send_reply(&outbox, header, &mut session);
                         }
                     }
                     Err(broadcast::error::RecvError::Lagged(_)) => {
-                        // Client is too slow, skip missed messages
-                        debug!("Client lagged behind on broadcast messages");
+                        debug!("Client lagged behind on page images");
                     }
                     Err(broadcast::error::RecvError::Closed) => {
                         break;
                     }
                 }
             }
+
+            // Ping quiet clients and drop those that stopped answering
+            _ = heartbeats.tick() => {
+                let now = Instant::now();
+                match heartbeat.beat(now) {
+                    Beat::Alive => {}
+                    Beat::Ping => {
+                        outbox.send(Message::Ping(Vec::new()));
+                    }
+                    Beat::TimedOut => {
+                        warn!(client = %session.label(), idle_secs = heartbeat.idle(now).as_secs(), "Dropping unresponsive client");
+                        let close = CloseFrame {
+                            code: CloseCode::Away,
+                            reason: "Heartbeat timed out".into(),
+                        };
+                        outbox.send(Message::Close(Some(close)));
+                        break;
+                    }
+                }
+            }
+
+            // The operator disconnected this client, or the server is
+            // stopping
+            reason = &mut disconnect => {
+                info!(client = %session.label(), "Disconnecting client");
+                let close = match reason {
+                    Ok(Disconnect::ServerStopped) => CloseFrame {
+                        code: CloseCode::Away,
+                        reason: "Server stopped".into(),
+                    },
+                    _ => CloseFrame {
+                        code: CloseCode::Policy,
+                        reason: "Disconnected by the operator".into(),
+                    },
+                };
+                outbox.send(Message::Close(Some(close)));
+                break;
+            }
+
+            // Send the latest remote view frame once the queue is empty;
+            // frames published while the previous one was sending are skipped
+            changed = remote_view.changed(), if session.remote_view && outbox.is_empty() => {
+                if changed.is_err() {
+                    continue;
+                }
+                let frame = remote_view.borrow_and_update().clone();
+                if let Some(frame) = frame {
+                    outbox.send_droppable(Message::Binary(frame.jpeg.to_vec()));
+                }
+            }
+
+            // Look again at the guards above as the writer catches up
+            _ = outbox.taken(), if outbox.is_backed_up() || (session.remote_view && !outbox.is_empty()) => {}
+
+            // The connection failed while sending
+            written = &mut writer.0 => {
+                return match written {
+                    Ok(Err(e)) => Err(e.into()),
+                    _ => Ok(()),
+                };
+            }
         }
     }
 
+    // Let the writer send what's queued, such as a close frame
+    outbox.close();
+    if tokio::time::timeout(FLUSH_TIMEOUT, &mut writer.0)
+        .await
+        .is_err()
+    {
+        debug!(client = %session.label(), "Gave up sending to a closed connection");
+    }
+
+    Ok(())
+}
+
+/// A connection's writer task, stopped when the connection ends
+struct Writer(JoinHandle<Result<(), WsError>>);
+
+impl Drop for Writer {
+    fn drop(&mut self) {
+        self.0.abort();
+    }
+}
+
+/// Send a connection's queued messages until the queue is closed and empty
+async fn write_queued<W>(mut ws_sender: W, outbox: Arc<Outbox<Message>>) -> Result<(), WsError>
+where
+    W: Sink<Message, Error = WsError> + Unpin,
+{
+    while let Some(message) = outbox.next().await {
+        ws_sender.send(message).await?;
+    }
+    Ok(())
+}
+
+/// Queue a reply, then the image data that goes with it
+fn send_reply(outbox: &Outbox<Message>, text: String, session: &mut ClientSession) {
+    outbox.send(Message::Text(text));
+    for data in session.binary_out.drain(..) {
+        outbox.send(Message::Binary(data.to_vec()));
+    }
+}
+
+/// Queue the current page's image for a client following it
+fn send_current_page_image(
+    outbox: &Outbox<Message>,
+    state: &Arc<AppState>,
+    app_handle: &AppHandle,
+    session: &mut ClientSession,
+) -> serde_json::Result<()> {
+    let Some(size) = session.page_images else {
+        return Ok(());
+    };
+    let Some(pdf_state) = state.get_pdf_state().ok().filter(|pdf| pdf.is_loaded) else {
+        return Ok(());
+    };
+    let reply = page_image_reply(state, app_handle, session, pdf_state.current_page, size);
+    send_reply(outbox, serde_json::to_string(&reply)?, session);
     Ok(())
 }
 
+/// Keeps a connection listed in the client registry and counted in
+/// `active_connections` while it's open
+struct Registration {
+    state: Arc<AppState>,
+    app_handle: AppHandle,
+    tx: broadcast::Sender<WebSocketEvent>,
+    id: Option<u64>,
+    peer: SocketAddr,
+    /// Broadcasts the client missed by reading too slowly
+    dropped: u64,
+    /// Keeps the disconnect receiver pending when the registry is unavailable
+    _unregistered: Option<oneshot::Sender<Disconnect>>,
+}
+
+impl Registration {
+    fn new(
+        state: &Arc<AppState>,
+        app_handle: &AppHandle,
+        tx: &broadcast::Sender<WebSocketEvent>,
+        session: &ClientSession,
+    ) -> (Self, oneshot::Receiver<Disconnect>) {
+        let registered = state
+            .ws_clients
+            .lock()
+            .map(|mut clients| clients.register(session.peer_addr, session.role));
+        let (id, unregistered, disconnect) = match registered {
+            Ok((id, disconnect)) => (Some(id), None, disconnect),
+            Err(e) => {
+                warn!(error = %e, "Failed to register WebSocket client");
+                let (tx, rx) = oneshot::channel();
+                (None, Some(tx), rx)
+            }
+        };
+        let registration = Self {
+            state: Arc::clone(state),
+            app_handle: app_handle.clone(),
+            tx: tx.clone(),
+            id,
+            peer: session.peer_addr,
+            dropped: 0,
+            _unregistered: unregistered,
+        };
+        registration.count(CLIENT_CONNECTED_EVENT, |count| count + 1);
+        (registration, disconnect)
+    }
+
+    /// Pick up a role the operator gave the client
+    fn refresh(&self, session: &mut ClientSession) {
+        let role = self
+            .id
+            .and_then(|id| self.state.ws_clients.lock().ok()?.role(id));
+        if let Some(role) = role {
+            session.role = role;
+        }
+    }
+
+    fn update(&self, session: &ClientSession) {
+        if let (Some(id), Ok(mut clients)) = (self.id, self.state.ws_clients.lock()) {
+            clients.update(id, session);
+        }
+    }
+
+    /// Count broadcasts the client missed by reading too slowly
+    fn dropped(&mut self, session: &ClientSession, count: u64) {
+        if self.dropped == 0 {
+            warn!(client = %session.label(), "Client is reading slowly, dropping broadcasts");
+        }
+        self.dropped += count;
+        debug!(client = %session.label(), dropped = self.dropped, "Dropped broadcasts");
+        if let (Some(id), Ok(mut clients)) = (self.id, self.state.ws_clients.lock()) {
+            clients.add_dropped(id, count);
+        }
+        if let Ok(mut websocket) = self.state.websocket.write() {
+            websocket.dropped_events += count;
+        }
+    }
+
+    /// Update `active_connections` and tell the webview and the clients
+    fn count(&self, event: &str, change: impl FnOnce(u32) -> u32) {
+        let active_connections = match self.state.websocket.write() {
+            Ok(mut websocket) => {
+                websocket.active_connections = change(websocket.active_connections);
+                websocket.is_connected = websocket.active_connections > 0;
+                websocket.active_connections
+            }
+            Err(e) => {
+                warn!(error = %e, "Failed to count WebSocket connections");
+                return;
+            }
+        };
+        let payload = ClientConnection {
+            id: self.id,
+            peer: self.peer.to_string(),
+            active_connections,
+        };
+        if let Err(e) = self.app_handle.emit(event, payload) {
+            warn!(error = %e, "Failed to emit WebSocket connection change");
+        }
+        let _ = self
+            .tx
+            .send(WebSocketEvent::ConnectionStatus { active_connections });
+    }
+}
+
+impl Drop for Registration {
+    fn drop(&mut self) {
+        if let (Some(id), Ok(mut clients)) = (self.id, self.state.ws_clients.lock()) {
+            clients.remove(id);
+        }
+        self.count(CLIENT_DISCONNECTED_EVENT, |count| count.saturating_sub(1));
+    }
+}
+
 /// Get current state as a WebSocketEvent
 fn get_current_state(state: &Arc<AppState>) -> WebSocketEvent {
     let pdf_state = state.get_pdf_state().unwrap_or_default();
@@ -183,6 +815,22 @@ fn get_current_state(state: &Arc<AppState>) -> WebSocketEvent {
         pdf_path: pdf_state.current_file.clone(),
         pdf_title: None,
         presenter_active: presenter_state.is_active,
+        smart_invert: pdf_state.smart_invert,
+        section: section_progress(state, pdf_state.current_page),
+        annotation_clock: state.annotation_clock(),
+    }
+}
+
+/// The events of a reply that go to every client: the reply itself, or the
+/// replies within a batch
+pub(crate) fn broadcast_events(event: &WebSocketEvent) -> Vec<&WebSocketEvent> {
+    match event {
+        WebSocketEvent::BatchResults { results, .. } => results
+            .iter()
+            .filter(|result| should_broadcast(result))
+            .collect(),
+        event if should_broadcast(event) => vec![event],
+        _ => Vec::new(),
     }
 }
 
@@ -193,8 +841,21 @@ fn should_broadcast(event: &WebSocketEvent) -> bool {
         WebSocketEvent::PageChanged { .. }
             | WebSocketEvent::ZoomChanged { .. }
             | WebSocketEvent::PresenterChanged { .. }
+            | WebSocketEvent::SmartInvertChanged { .. }
+            | WebSocketEvent::BlackoutChanged { .. }
+            | WebSocketEvent::OutputViewportChanged { .. }
             | WebSocketEvent::PdfOpened { .. }
             | WebSocketEvent::PdfClosed
+            | WebSocketEvent::ReactionsUpdated { .. }
+            | WebSocketEvent::MediaPlaybackChanged { .. }
+            | WebSocketEvent::QrCodeChanged { .. }
+            | WebSocketEvent::LayersChanged { .. }
+            | WebSocketEvent::ShowEnded { .. }
+            | WebSocketEvent::StylePresetsChanged { .. }
+            | WebSocketEvent::PointerMoved { .. }
+            | WebSocketEvent::AnnotationAdded { .. }
+            | WebSocketEvent::AnnotationUpdated { .. }
+            | WebSocketEvent::AnnotationDeleted { .. }
     )
 }
 
@@ -206,11 +867,30 @@ mod tests {
     fn test_should_broadcast() {
         assert!(should_broadcast(&WebSocketEvent::PageChanged {
             page: 1,
-            total_pages: 10
+            total_pages: 10,
+            section: None,
         }));
         assert!(should_broadcast(&WebSocketEvent::ZoomChanged { zoom: 1.5 }));
         assert!(should_broadcast(&WebSocketEvent::PdfClosed));
+        assert!(should_broadcast(&WebSocketEvent::SmartInvertChanged {
+            enabled: true
+        }));
+        assert!(should_broadcast(&WebSocketEvent::AnnotationDeleted {
+            page: 1,
+            id: "a1".to_string(),
+            clock: 3
+        }));
         assert!(!should_broadcast(&WebSocketEvent::Pong));
         assert!(!should_broadcast(&WebSocketEvent::error("test")));
+
+        let batch = WebSocketEvent::BatchResults {
+            results: vec![
+                WebSocketEvent::ZoomChanged { zoom: 1.5 },
+                WebSocketEvent::Pong,
+                WebSocketEvent::PresenterChanged { active: true },
+            ],
+        };
+        assert_eq!(broadcast_events(&batch).len(), 2);
+        assert!(broadcast_events(&WebSocketEvent::Pong).is_empty());
     }
 }
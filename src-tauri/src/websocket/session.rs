@@ -0,0 +1,143 @@
+/*
+ * This file is part of StreamSlate.
+ * Copyright (C) 2025 StreamSlate Contributors
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+
+//! Per-connection session state
+//!
+//! Each WebSocket connection owns a `ClientSession` that lives for the
+//! duration of the connection and is passed to the command handlers.
+
+use super::access::Role;
+use super::page_images::ImageSize;
+use super::rate::RateLimiter;
+use super::reactions;
+use super::topics::{self, Topic};
+use crate::protocol::WebSocketEvent;
+use serde::Serialize;
+use std::collections::BTreeSet;
+use std::net::SocketAddr;
+use std::sync::Arc;
+use std::time::Instant;
+
+/// Pointer moves a connection may send in a burst
+const POINTER_BURST: f64 = 30.0;
+
+/// Sustained pointer moves per second per connection
+const POINTER_PER_SECOND: f64 = 30.0;
+
+/// What a client said about itself in `HELLO`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ClientInfo {
+    /// Name shown to the operator, e.g. "Stream Deck XL"
+    pub name: String,
+    /// Kind of controller, e.g. "stream-deck", "companion" or "phone"
+    pub kind: String,
+    /// Version of the client software
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub version: Option<String>,
+}
+
+/// State tracked for a single connected client
+#[derive(Debug, Clone)]
+pub struct ClientSession {
+    /// Remote address of the connection
+    pub peer_addr: SocketAddr,
+    /// Stable client ID, set once the client sends `IDENTIFY`
+    pub client_id: Option<String>,
+    /// Display name sent with `IDENTIFY`
+    pub name: Option<String>,
+    /// Controller identity sent with `HELLO`
+    pub info: Option<ClientInfo>,
+    /// What this client may do
+    pub role: Role,
+    /// Rate limit on `ADD_REACTION`
+    pub reaction_limiter: RateLimiter,
+    /// Rate limit on `POINTER_MOVED`
+    pub pointer_limiter: RateLimiter,
+    /// Whether remote view frames are sent to this client
+    pub remote_view: bool,
+    /// Topics of the broadcast events sent to this client; all of them
+    /// until it subscribes
+    pub topics: Option<BTreeSet<Topic>>,
+    /// Size of the current page's image sent on every page change, if any
+    pub page_images: Option<ImageSize>,
+    /// Page images being rendered for this client
+    pub pending_images: BTreeSet<(u32, ImageSize)>,
+    /// Image data to send after the reply, one binary message each
+    pub binary_out: Vec<Arc<[u8]>>,
+    /// Sequence number of the latest broadcast sent or replayed to this
+    /// client; earlier ones still queued for it are skipped
+    pub last_seq: u64,
+}
+
+impl ClientSession {
+    /// Create a session for a newly accepted connection, with the viewer
+    /// role until it's given another
+    pub fn new(peer_addr: SocketAddr) -> Self {
+        let now = Instant::now();
+        Self {
+            peer_addr,
+            client_id: None,
+            name: None,
+            info: None,
+            role: Role::Viewer,
+            reaction_limiter: RateLimiter::new(
+                reactions::RATE_BURST,
+                reactions::RATE_PER_SECOND,
+                now,
+            ),
+            pointer_limiter: RateLimiter::new(POINTER_BURST, POINTER_PER_SECOND, now),
+            remote_view: false,
+            topics: None,
+            page_images: None,
+            pending_images: BTreeSet::new(),
+            binary_out: Vec::new(),
+            last_seq: 0,
+        }
+    }
+
+    /// Whether a broadcast event should be sent to this client
+    pub fn wants(&self, event: &WebSocketEvent) -> bool {
+        match (topics::topic(event), &self.topics) {
+            (Some(topic), Some(subscribed)) => subscribed.contains(&topic),
+            _ => true,
+        }
+    }
+
+    /// Who this connection's annotations are attributed to: the display
+    /// name, or the client ID when none was given
+    pub fn author(&self) -> Option<&str> {
+        self.name.as_deref().or(self.client_id.as_deref())
+    }
+
+    /// How this connection appears in logs: the `HELLO` name and kind, or
+    /// the peer address until the client says hello
+    pub fn label(&self) -> String {
+        match &self.info {
+            Some(info) => format!("{} ({})", info.name, info.kind),
+            None => self.peer_addr.to_string(),
+        }
+    }
+
+    /// Name stamped on this connection's annotation edits: the client ID,
+    /// or the peer address until the client identifies
+    pub fn replica(&self) -> String {
+        self.client_id
+            .clone()
+            .unwrap_or_else(|| self.peer_addr.to_string())
+    }
+}
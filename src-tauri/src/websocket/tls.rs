@@ -0,0 +1,222 @@
+/*
+ * This file is part of StreamSlate.
+ * Copyright (C) 2025 StreamSlate Contributors
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+
+//! TLS for the control server
+//!
+//! With TLS on, clients connect with `wss://` so control traffic on the LAN
+//! isn't readable by others on the network. The certificate is either one
+//! the user provides (PEM files) or a self-signed one generated into the
+//! `tls` folder of the app data directory. Clients can't verify a
+//! self-signed certificate against a CA, so its SHA-256 fingerprint is shown
+//! for pinning or comparing by eye.
+
+use crate::error::{Result, StreamSlateError};
+use crate::settings::Settings;
+use ring::digest::{digest, SHA256};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use tauri::{AppHandle, Manager};
+use tokio_rustls::rustls::crypto::ring::default_provider;
+use tokio_rustls::rustls::pki_types::pem::PemObject;
+use tokio_rustls::rustls::pki_types::{CertificateDer, PrivateKeyDer};
+use tokio_rustls::rustls::ServerConfig;
+use tokio_rustls::TlsAcceptor;
+use tracing::warn;
+
+/// File name of the settings inside the app data directory
+pub const CONFIG_FILE: &str = "websocket_tls.json";
+
+/// Folder of the generated certificate inside the app data directory
+const CERT_FOLDER: &str = "tls";
+
+/// Names every generated certificate is valid for
+const LOCAL_NAMES: [&str; 2] = ["localhost", "127.0.0.1"];
+
+/// TLS settings of the control server
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TlsConfig {
+    /// Accept only `wss://` connections
+    #[serde(default)]
+    pub enabled: bool,
+    /// PEM certificate chain to use instead of the generated certificate
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cert_path: Option<String>,
+    /// PEM private key of `cert_path`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub key_path: Option<String>,
+}
+
+impl TlsConfig {
+    /// Certificate and key files to serve, given the generated certificate's
+    /// folder
+    pub fn files(&self, generated: &Path) -> (PathBuf, PathBuf) {
+        match (&self.cert_path, &self.key_path) {
+            (Some(cert), Some(key)) => (PathBuf::from(cert), PathBuf::from(key)),
+            _ => (generated.join("cert.pem"), generated.join("key.pem")),
+        }
+    }
+
+    /// Check a user certificate comes with its key
+    pub fn validate(&self) -> Result<()> {
+        if self.cert_path.is_some() != self.key_path.is_some() {
+            return Err(StreamSlateError::Other(
+                "A TLS certificate needs both a certificate and a key file".to_string(),
+            ));
+        }
+        Ok(())
+    }
+}
+
+impl Settings for TlsConfig {
+    fn check(&self) -> Result<()> {
+        self.validate()
+    }
+}
+
+/// Folder of the generated certificate
+pub fn cert_dir(app_handle: &AppHandle) -> Option<PathBuf> {
+    app_handle
+        .path()
+        .app_data_dir()
+        .ok()
+        .map(|dir| dir.join(CERT_FOLDER))
+}
+
+/// The acceptor for `config`, or `None` with TLS off
+pub fn acceptor_for(app_handle: &AppHandle, config: &TlsConfig) -> Result<Option<TlsAcceptor>> {
+    if !config.enabled {
+        return Ok(None);
+    }
+    let dir = cert_dir(app_handle)
+        .ok_or_else(|| StreamSlateError::WebSocket("No app data directory".to_string()))?;
+    let (cert, key) = config.files(&dir);
+    acceptor(&cert, &key).map(Some)
+}
+
+/// Build a TLS acceptor from PEM certificate and key files
+pub fn acceptor(cert: &Path, key: &Path) -> Result<TlsAcceptor> {
+    let certs = read_certs(cert)?;
+    let key = PrivateKeyDer::from_pem_file(key).map_err(|e| {
+        StreamSlateError::WebSocket(format!("Can't read TLS key {}: {e}", key.display()))
+    })?;
+    let config = ServerConfig::builder_with_provider(Arc::new(default_provider()))
+        .with_safe_default_protocol_versions()
+        .and_then(|builder| builder.with_no_client_auth().with_single_cert(certs, key))
+        .map_err(|e| StreamSlateError::WebSocket(format!("Invalid TLS certificate: {e}")))?;
+    Ok(TlsAcceptor::from(Arc::new(config)))
+}
+
+/// Generate a self-signed certificate for `names` (host names or IP
+/// addresses clients connect to) besides localhost into `dir`, returning
+/// its fingerprint
+pub fn generate_certificate(dir: &Path, names: &[String]) -> Result<String> {
+    let mut all: Vec<String> = LOCAL_NAMES.iter().map(|n| n.to_string()).collect();
+    for name in names.iter().map(|n| n.trim()).filter(|n| !n.is_empty()) {
+        if !all.iter().any(|n| n == name) {
+            all.push(name.to_string());
+        }
+    }
+    let generated = rcgen::generate_simple_self_signed(all)
+        .map_err(|e| StreamSlateError::WebSocket(format!("Can't generate certificate: {e}")))?;
+
+    std::fs::create_dir_all(dir)?;
+    let (cert, key) = TlsConfig::default().files(dir);
+    std::fs::write(&cert, generated.cert.pem())?;
+    std::fs::write(&key, generated.key_pair.serialize_pem())?;
+    restrict_permissions(&key);
+    Ok(fingerprint(generated.cert.der()))
+}
+
+/// SHA-256 fingerprint of the first certificate in a PEM file
+pub fn certificate_fingerprint(cert: &Path) -> Result<String> {
+    let certs = read_certs(cert)?;
+    Ok(fingerprint(&certs[0]))
+}
+
+/// SHA-256 of a DER certificate as colon-separated hex, as browsers show it
+pub fn fingerprint(der: &[u8]) -> String {
+    digest(&SHA256, der)
+        .as_ref()
+        .iter()
+        .map(|byte| format!("{byte:02X}"))
+        .collect::<Vec<_>>()
+        .join(":")
+}
+
+fn read_certs(path: &Path) -> Result<Vec<CertificateDer<'static>>> {
+    let certs = CertificateDer::pem_file_iter(path)
+        .and_then(|certs| certs.collect::<std::result::Result<Vec<_>, _>>())
+        .map_err(|e| {
+            StreamSlateError::WebSocket(format!(
+                "Can't read TLS certificate {}: {e}",
+                path.display()
+            ))
+        })?;
+    if certs.is_empty() {
+        return Err(StreamSlateError::WebSocket(format!(
+            "No certificate in {}",
+            path.display()
+        )));
+    }
+    Ok(certs)
+}
+
+/// Keep the private key readable by the user only
+#[cfg(unix)]
+fn restrict_permissions(path: &Path) {
+    use std::os::unix::fs::PermissionsExt;
+    if let Err(e) = std::fs::set_permissions(path, std::fs::Permissions::from_mode(0o600)) {
+        warn!(path = %path.display(), error = %e, "Failed to restrict TLS key permissions");
+    }
+}
+
+#[cfg(not(unix))]
+fn restrict_permissions(_path: &Path) {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fingerprint() {
+        // SHA-256 of the empty string
+        let empty = fingerprint(b"");
+        assert!(empty.starts_with("E3:B0:C4:42:98:FC:1C:14"));
+        assert_eq!(empty.len(), 32 * 3 - 1);
+
+        let generated = Path::new("/data/tls");
+        let user = TlsConfig {
+            enabled: true,
+            cert_path: Some("/etc/cert.pem".to_string()),
+            key_path: Some("/etc/key.pem".to_string()),
+        };
+        assert_eq!(user.files(generated).0, PathBuf::from("/etc/cert.pem"));
+        assert_eq!(
+            TlsConfig::default().files(generated).1,
+            generated.join("key.pem")
+        );
+        assert!(TlsConfig {
+            key_path: None,
+            ..user
+        }
+        .validate()
+        .is_err());
+    }
+}
@@ -0,0 +1,166 @@
+/*
+ * This file is part of StreamSlate.
+ * Copyright (C) 2025 StreamSlate Contributors
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+
+//! Event topics
+//!
+//! Broadcast events are grouped into topics so a client can subscribe to
+//! just the ones it shows, e.g. an annotation overlay that has no use for
+//! zoom changes. Replies to a client's own commands are always sent.
+
+use crate::protocol::WebSocketEvent;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// A group of broadcast events clients can subscribe to
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize, JsonSchema,
+)]
+#[serde(rename_all = "lowercase")]
+pub enum Topic {
+    /// Documents opened or closed and the show ending
+    Document,
+    /// Page and section changes
+    Navigation,
+    /// Zoom, presenter mode, dark mode, blackout and what the outputs show
+    View,
+    /// Annotation edits, layers and style presets
+    Annotations,
+    /// Video clip playback
+    Media,
+    /// Reactions, attendance checkpoints and page summaries
+    Audience,
+    /// Co-hosts' cursors
+    Pointer,
+}
+
+impl Topic {
+    /// Every topic
+    pub const ALL: [Topic; 7] = [
+        Topic::Document,
+        Topic::Navigation,
+        Topic::View,
+        Topic::Annotations,
+        Topic::Media,
+        Topic::Audience,
+        Topic::Pointer,
+    ];
+
+    /// Name as used in the protocol
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Topic::Document => "document",
+            Topic::Navigation => "navigation",
+            Topic::View => "view",
+            Topic::Annotations => "annotations",
+            Topic::Media => "media",
+            Topic::Audience => "audience",
+            Topic::Pointer => "pointer",
+        }
+    }
+
+    /// The topic with this name, if there is one
+    pub fn parse(name: &str) -> Option<Topic> {
+        Topic::ALL.into_iter().find(|topic| topic.as_str() == name)
+    }
+}
+
+/// The topic an event is broadcast under, or `None` for events every client
+/// gets, such as replies and errors
+pub fn topic(event: &WebSocketEvent) -> Option<Topic> {
+    use WebSocketEvent as E;
+    match event {
+        E::PdfOpened { .. } | E::PdfClosed | E::ShowEnded { .. } | E::NotesUpdated { .. } => {
+            Some(Topic::Document)
+        }
+        E::PageChanged { .. }
+        | E::LinkedPagesChanged { .. }
+        | E::PrerenderPages { .. }
+        | E::Agenda { .. } => Some(Topic::Navigation),
+        E::ZoomChanged { .. }
+        | E::PresenterChanged { .. }
+        | E::SmartInvertChanged { .. }
+        | E::BlackoutChanged { .. }
+        | E::OutputViewportChanged { .. }
+        | E::QrCodeChanged { .. } => Some(Topic::View),
+        E::AnnotationsUpdated { .. }
+        | E::AnnotationsCleared
+        | E::AnnotationAdded { .. }
+        | E::AnnotationUpdated { .. }
+        | E::AnnotationDeleted { .. }
+        | E::AnnotationReplay { .. }
+        | E::LayersChanged { .. }
+        | E::StylePresetsChanged { .. } => Some(Topic::Annotations),
+        E::MediaPlaybackChanged { .. } => Some(Topic::Media),
+        E::ReactionsUpdated { .. }
+        | E::CheckpointStarted { .. }
+        | E::CheckpointClosed { .. }
+        | E::PageSummary { .. } => Some(Topic::Audience),
+        E::PointerMoved { .. } => Some(Topic::Pointer),
+        E::State { .. }
+        | E::Media { .. }
+        | E::Layers { .. }
+        | E::RemoteView { .. }
+        | E::AnnotationRejected { .. }
+        | E::StylePresets { .. }
+        | E::AnnotationSearchResults { .. }
+        | E::BatchResults { .. }
+        | E::RateLimited { .. }
+        | E::Error { .. }
+        | E::Pong
+        | E::Connected { .. }
+        | E::Welcome { .. }
+        | E::ConnectionStatus { .. }
+        | E::PageWords { .. }
+        | E::ClientPreferences { .. }
+        | E::Subscribed { .. }
+        | E::Resumed { .. }
+        | E::ResumeFailed { .. }
+        | E::PageImage { .. }
+        | E::PageImagePending { .. }
+        | E::PageImages { .. } => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_topics() {
+        let page = WebSocketEvent::PageChanged {
+            page: 2,
+            total_pages: 10,
+            section: None,
+        };
+        assert_eq!(topic(&page), Some(Topic::Navigation));
+        assert_eq!(
+            topic(&WebSocketEvent::ZoomChanged { zoom: 1.5 }),
+            Some(Topic::View)
+        );
+        assert_eq!(
+            topic(&WebSocketEvent::AnnotationsCleared),
+            Some(Topic::Annotations)
+        );
+        assert_eq!(topic(&WebSocketEvent::error("No PDF loaded")), None);
+
+        for topic in Topic::ALL {
+            assert_eq!(Topic::parse(topic.as_str()), Some(topic));
+        }
+        assert_eq!(Topic::parse("everything"), None);
+    }
+}
@@ -92,6 +92,8 @@ function App() {
   const setCurrentPage = usePDFStore((state) => state.setCurrentPage);
   const setZoom = usePDFStore((state) => state.setZoom);
   const setPageAnnotations = usePDFStore((state) => state.setPageAnnotations);
+  const upsertAnnotation = usePDFStore((state) => state.upsertAnnotation);
+  const removeAnnotation = usePDFStore((state) => state.removeAnnotation);
   const clearAnnotations = usePDFStore((state) => state.clearAnnotations);
 
   // Auto-connect WebSocket on mount
@@ -118,6 +120,8 @@ function App() {
       setPresenterMode,
       setDocument,
       setPageAnnotations,
+      upsertAnnotation,
+      removeAnnotation,
       clearAnnotations,
       getCurrentDocument: () => pdfDocument,
     }),
@@ -127,6 +131,8 @@ function App() {
       setPresenterMode,
       setDocument,
       setPageAnnotations,
+      upsertAnnotation,
+      removeAnnotation,
       clearAnnotations,
       pdfDocument,
     ]
@@ -14,6 +14,17 @@ export const OutputControls: React.FC = () => {
     ndiAvailable,
     syphonAvailable,
     status,
+    fullscreenWarning,
+    powerWarning,
+    autoThrottle,
+    setAutoThrottle,
+    colorCorrection,
+    setColorCorrection,
+    sendTestPattern,
+    blackout,
+    setBlackout,
+    viewport,
+    setOutputViewport,
     displayTargets,
     startCapture,
     stopCapture,
@@ -122,6 +133,121 @@ export const OutputControls: React.FC = () => {
           ))}
       </div>
 
+      {/* Fullscreen warning: window capture of another Space goes black */}
+      {isSending && powerWarning && (
+        <div className="text-xs p-2 bg-orange-500/10 border border-orange-500/40 rounded-lg space-y-1 text-orange-300">
+          <p>
+            {powerWarning.status.thermal === "serious" ||
+            powerWarning.status.thermal === "critical"
+              ? "This Mac is running hot and may start throttling."
+              : `Battery low (${powerWarning.status.battery?.percent ?? 0}%). Connect power to avoid throttling.`}
+          </p>
+          {powerWarning.fps_limit !== null && (
+            <p>Output reduced to {powerWarning.fps_limit} fps.</p>
+          )}
+        </div>
+      )}
+
+      <label className="flex items-center gap-2 text-xs text-text-tertiary">
+        <input
+          type="checkbox"
+          checked={autoThrottle}
+          onChange={(e) => setAutoThrottle(e.target.checked)}
+        />
+        Lower frame rate when hot or on low battery
+      </label>
+
+      <label className="flex items-center gap-2 text-xs text-text-tertiary">
+        <input
+          type="checkbox"
+          checked={colorCorrection}
+          onChange={(e) => setColorCorrection(e.target.checked)}
+        />
+        Match colors across displays (convert to sRGB)
+      </label>
+
+      {isSending && (
+        <div className="space-y-1">
+          <div className="flex justify-between text-xs text-text-tertiary">
+            <span>Output zoom</span>
+            <span>{viewport.zoom.toFixed(1)}x</span>
+          </div>
+          <div className="flex items-center gap-2">
+            <input
+              type="range"
+              min={1}
+              max={4}
+              step={0.1}
+              value={viewport.zoom}
+              onChange={(e) =>
+                setOutputViewport(
+                  { ...viewport, zoom: Number(e.target.value) },
+                  0
+                )
+              }
+              className="flex-1"
+            />
+            <button
+              onClick={() => setOutputViewport({ x: 0.5, y: 0.5, zoom: 1 })}
+              disabled={viewport.zoom <= 1}
+              className="px-2 py-1 text-xs bg-surface-secondary text-text-primary rounded hover:bg-surface-tertiary disabled:opacity-50 transition-colors"
+            >
+              Reset
+            </button>
+          </div>
+        </div>
+      )}
+
+      {isSending && (
+        <button
+          onClick={() => setBlackout(!blackout)}
+          className={`w-full px-3 py-2 rounded-lg text-sm font-medium transition-colors ${
+            blackout
+              ? "bg-red-600 text-white hover:bg-red-700"
+              : "bg-surface-secondary text-text-primary hover:bg-surface-tertiary"
+          }`}
+        >
+          {blackout ? "Restore output" : "Black out output"}
+        </button>
+      )}
+
+      {isSending && (
+        <button
+          onClick={() => sendTestPattern(10)}
+          className="w-full px-3 py-2 bg-surface-secondary text-text-primary rounded-lg hover:bg-surface-tertiary text-sm font-medium transition-colors"
+        >
+          Send test pattern (10s)
+        </button>
+      )}
+
+      {isSending && fullscreenWarning && (
+        <div className="text-xs p-2 bg-yellow-500/10 border border-yellow-500/40 rounded-lg space-y-2 text-yellow-300">
+          {fullscreenWarning.fallback_active ? (
+            <p>
+              The captured window went fullscreen on another Space. Now
+              capturing Display {fullscreenWarning.display_id} instead.
+            </p>
+          ) : (
+            <>
+              <p>
+                The captured window went fullscreen on another Space; its
+                capture may be black.
+              </p>
+              <button
+                onClick={async () => {
+                  await stopCapture();
+                  setSelectedDisplayId(fullscreenWarning.display_id);
+                  await startCapture(fullscreenWarning.display_id);
+                }}
+                className="px-2 py-1 bg-yellow-600 text-white rounded hover:bg-yellow-700 font-medium transition-colors"
+              >
+                Capture Display {fullscreenWarning.display_id} instead
+              </button>
+            </>
+          )}
+        </div>
+      )}
+
       {/* Capture status (compact) */}
       {isSending && status && (
         <div className="text-xs p-2 bg-surface-secondary rounded-lg space-y-1">
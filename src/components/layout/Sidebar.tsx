@@ -35,6 +35,9 @@ function getAnnotationTypeLabel(type: AnnotationType): string {
     [AnnotationType.RECTANGLE]: "Rectangle",
     [AnnotationType.CIRCLE]: "Circle",
     [AnnotationType.FREE_DRAW]: "Drawing",
+    [AnnotationType.LINE]: "Line",
+    [AnnotationType.POLYGON]: "Polygon",
+    [AnnotationType.IMAGE]: "Image",
   };
   return labels[type] || type;
 }
@@ -30,9 +30,12 @@ import type { Annotation } from "../../types/pdf.types";
 import { TextAnnotationEditor } from "../annotation/TextAnnotationEditor";
 import { AnnotationToolbar } from "../annotation/AnnotationToolbar";
 import { useAnnotationDraw } from "../../hooks/useAnnotationDraw";
+import { useStampImage } from "../../hooks/useStampImage";
 import {
   type Point,
+  hasPressure,
   pointsToSmoothPath,
+  pressureSegments,
   getTextDefaults,
   clampOpacity,
   hexToRgba,
@@ -108,6 +111,8 @@ export const AnnotationLayer: React.FC<AnnotationLayerProps> = ({
         className="absolute inset-0"
         data-testid="annotation-layer"
         style={{ cursor: draw.getCursorStyle() }}
+        onPointerDown={draw.handlePointerEvent}
+        onPointerMove={draw.handlePointerEvent}
         onMouseDown={draw.handleMouseDown}
         onMouseMove={draw.handleMouseMove}
         onMouseUp={draw.handleMouseUp}
@@ -264,13 +269,15 @@ const AnnotationShape: React.FC<AnnotationShapeProps> = ({
         />
       );
 
-    case AnnotationType.ARROW: {
+    case AnnotationType.ARROW:
+    case AnnotationType.LINE: {
       const startX = annotation.x * viewport.scale;
       const startY = annotation.y * viewport.scale;
       const endX = (annotation.x + annotation.width) * viewport.scale;
       const endY = (annotation.y + annotation.height) * viewport.scale;
       const hitStrokeWidth = Math.max(12, (annotation.strokeWidth ?? 2) + 8);
       const markerId = `arrowhead-${annotation.id}`;
+      const hasHead = annotation.type === AnnotationType.ARROW;
 
       return (
         <g>
@@ -293,29 +300,46 @@ const AnnotationShape: React.FC<AnnotationShapeProps> = ({
             y1={startY}
             x2={endX}
             y2={endY}
-            markerEnd={`url(#${markerId})`}
+            markerEnd={hasHead ? `url(#${markerId})` : undefined}
             pointerEvents="none"
           />
-          <defs>
-            <marker
-              id={markerId}
-              markerWidth="10"
-              markerHeight="7"
-              refX="9"
-              refY="3.5"
-              orient="auto"
-            >
-              <polygon
-                points="0 0, 10 3.5, 0 7"
-                fill={isSelected ? selectionStroke : annotation.color}
-                opacity={annotation.opacity}
-              />
-            </marker>
-          </defs>
+          {hasHead && (
+            <defs>
+              <marker
+                id={markerId}
+                markerWidth="10"
+                markerHeight="7"
+                refX="9"
+                refY="3.5"
+                orient="auto"
+              >
+                <polygon
+                  points="0 0, 10 3.5, 0 7"
+                  fill={isSelected ? selectionStroke : annotation.color}
+                  opacity={annotation.opacity}
+                />
+              </marker>
+            </defs>
+          )}
         </g>
       );
     }
 
+    case AnnotationType.POLYGON: {
+      const points = getPointsFromAnnotation(annotation) ?? [];
+      if (points.length < 3) return null;
+
+      return (
+        <polygon
+          {...commonProps}
+          points={points
+            .map((p) => `${p.x * viewport.scale},${p.y * viewport.scale}`)
+            .join(" ")}
+          strokeLinejoin="round"
+        />
+      );
+    }
+
     case AnnotationType.TEXT: {
       const x = annotation.x * viewport.scale;
       const y = annotation.y * viewport.scale;
@@ -396,26 +420,101 @@ const AnnotationShape: React.FC<AnnotationShapeProps> = ({
             pointerEvents="stroke"
             onMouseDown={(e: React.MouseEvent) => onMouseDown(annotation, e)}
           />
-          <path
-            d={pathData}
-            stroke={isSelected ? selectionStroke : annotation.color}
-            strokeWidth={isSelected ? strokeWidth + 1 : strokeWidth}
-            fill="none"
-            strokeOpacity={annotation.opacity}
-            strokeLinecap="round"
-            strokeLinejoin="round"
-            cursor="pointer"
-            pointerEvents="none"
-          />
+          {hasPressure(points) ? (
+            // Group opacity keeps the overlapping segment ends even
+            <g opacity={annotation.opacity} pointerEvents="none">
+              {pressureSegments(
+                points,
+                isSelected ? strokeWidth + 1 : strokeWidth,
+                viewport.scale
+              ).map((segment, i) => (
+                <line
+                  key={i}
+                  x1={segment.x1}
+                  y1={segment.y1}
+                  x2={segment.x2}
+                  y2={segment.y2}
+                  stroke={isSelected ? selectionStroke : annotation.color}
+                  strokeWidth={segment.width}
+                  strokeLinecap="round"
+                />
+              ))}
+            </g>
+          ) : (
+            <path
+              d={pathData}
+              stroke={isSelected ? selectionStroke : annotation.color}
+              strokeWidth={isSelected ? strokeWidth + 1 : strokeWidth}
+              fill="none"
+              strokeOpacity={annotation.opacity}
+              strokeLinecap="round"
+              strokeLinejoin="round"
+              cursor="pointer"
+              pointerEvents="none"
+            />
+          )}
         </g>
       );
     }
 
+    case AnnotationType.IMAGE:
+      return (
+        <StampImage
+          annotation={annotation}
+          viewport={viewport}
+          isSelected={isSelected}
+          onMouseDown={onMouseDown}
+        />
+      );
+
     default:
       return null;
   }
 };
 
+const StampImage: React.FC<Omit<AnnotationShapeProps, "onTextEdit">> = ({
+  annotation,
+  viewport,
+  isSelected,
+  onMouseDown,
+}) => {
+  const url = useStampImage(annotation.image);
+  const x = annotation.x * viewport.scale;
+  const y = annotation.y * viewport.scale;
+  const width = annotation.width * viewport.scale;
+  const height = annotation.height * viewport.scale;
+
+  return (
+    <g
+      data-annotation-id={annotation.id}
+      data-annotation-type={annotation.type}
+      onMouseDown={(e: React.MouseEvent) => onMouseDown(annotation, e)}
+      cursor="pointer"
+    >
+      {url && (
+        <image
+          href={url}
+          x={x}
+          y={y}
+          width={width}
+          height={height}
+          opacity={annotation.opacity}
+          preserveAspectRatio="xMidYMid meet"
+        />
+      )}
+      <rect
+        x={x}
+        y={y}
+        width={width}
+        height={height}
+        fill="transparent"
+        stroke={isSelected ? "rgb(var(--color-primary))" : "transparent"}
+        strokeWidth={isSelected ? 2 : 0}
+      />
+    </g>
+  );
+};
+
 // ── Drawing Preview ────────────────────────────────────────────────────
 
 interface DrawingPreviewProps {
@@ -23,7 +23,9 @@
 
 import React, { useRef, useEffect, useState } from "react";
 import { usePDF } from "../../hooks/usePDF";
+import { usePDFStore } from "../../stores/pdf.store";
 import { useTheme } from "../../hooks/useTheme";
+import { useActiveStylePreset } from "../../hooks/useActiveStylePreset";
 import {
   LoadingStage,
   type Annotation,
@@ -90,14 +92,32 @@ export const PDFViewer: React.FC<PDFViewerProps> = ({
     setToolConfig((prev) => ({ ...prev, ...config }));
   };
 
+  // Switch pens when a style preset is activated
+  const activePreset = useActiveStylePreset();
+  useEffect(() => {
+    if (!activePreset) return;
+    setToolConfig((prev) => ({
+      ...prev,
+      color: activePreset.color,
+      opacity: activePreset.opacity,
+      strokeWidth: activePreset.strokeWidth ?? prev.strokeWidth,
+    }));
+  }, [activePreset]);
+
   // Canvas size state for annotation layer
   const [canvasSize, setCanvasSize] = useState({ width: 0, height: 0 });
 
   // Container ref for fit mode calculations
   const containerRef = useRef<HTMLDivElement>(null);
 
-  // Get annotations for current page
-  const currentPageAnnotations = annotations.get(viewerState.currentPage) ?? [];
+  // Get annotations for current page, leaving out hidden layers
+  const layers = usePDFStore((state) => state.layers);
+  const hiddenLayers = new Set(
+    layers.filter((layer) => !layer.visible).map((layer) => layer.id)
+  );
+  const currentPageAnnotations = (
+    annotations.get(viewerState.currentPage) ?? []
+  ).filter((a) => !a.layer || !hiddenLayers.has(a.layer));
 
   const renderContent = () => {
     if (error) {
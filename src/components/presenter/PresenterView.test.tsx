@@ -17,6 +17,9 @@ vi.mock("../../lib/pdf/renderer", () => ({
     loadDocument: vi.fn(),
     getPageDimensions: vi.fn(),
     renderPage: vi.fn(),
+    prerenderPage: vi.fn(),
+    dispose: vi.fn(),
+    loadedPath: null,
   },
 }));
 
@@ -26,8 +26,14 @@
 
 import React, { useEffect, useState, useRef, useCallback } from "react";
 import { listen, type UnlistenFn } from "@tauri-apps/api/event";
-import { pdfRenderer } from "../../lib/pdf/renderer";
+import {
+  pdfRenderer,
+  type CropRegion,
+  type RenderOptions,
+} from "../../lib/pdf/renderer";
+import { PDFCommands, WebSocketServerCommands } from "../../lib/tauri/commands";
 import { logger } from "../../lib/logger";
+import type { ImageSize } from "../../types/protocol.generated";
 
 interface PageChangedPayload {
   page: number;
@@ -40,6 +46,43 @@ interface PdfOpenedPayload {
   pageCount: number;
 }
 
+/** A remote co-host's cursor, as fractions of the page shown */
+interface PointerPayload {
+  page: number;
+  x: number;
+  y: number;
+  client?: string | null;
+}
+
+/** A page image remote monitors asked the control server for */
+interface PageImageRequest {
+  page: number;
+  size: ImageSize;
+  maxWidth: number;
+}
+
+/** JPEG quality of page images sent to remote monitors */
+const PAGE_IMAGE_QUALITY = 0.85;
+
+/** How long a remote cursor stays up after its last move */
+const POINTER_TIMEOUT_MS = 1500;
+
+/**
+ * Crop region for a page; only available inside Tauri
+ */
+async function fetchCrop(page: number): Promise<CropRegion | undefined> {
+  if (!("__TAURI__" in window)) {
+    return undefined;
+  }
+  try {
+    const info = await PDFCommands.getPdfPageInfo(page);
+    return info.crop ?? undefined;
+  } catch (err) {
+    logger.warn("Failed to fetch page crop", err);
+    return undefined;
+  }
+}
+
 export const PresenterView: React.FC = () => {
   // State for PDF display
   const [currentPage, setCurrentPage] = useState(1);
@@ -48,11 +91,57 @@ export const PresenterView: React.FC = () => {
   const [isLoading, setIsLoading] = useState(false);
   const [error, setError] = useState<string | null>(null);
   const [renderedImage, setRenderedImage] = useState<string | null>(null);
+  const [smartInvert, setSmartInvert] = useState(false);
+  const [pointer, setPointer] = useState<PointerPayload | null>(null);
 
   // Canvas ref for rendering
   const canvasRef = useRef<HTMLCanvasElement>(null);
   const containerRef = useRef<HTMLDivElement>(null);
 
+  // Only the latest pre-render request keeps running
+  const prerenderGeneration = useRef(0);
+
+  // Hide the remote cursor once it stops moving
+  const pointerTimeoutRef = useRef<ReturnType<typeof setTimeout> | null>(
+    null
+  );
+  const showPointer = useCallback((payload: PointerPayload) => {
+    setPointer(payload);
+    if (pointerTimeoutRef.current) clearTimeout(pointerTimeoutRef.current);
+    pointerTimeoutRef.current = setTimeout(
+      () => setPointer(null),
+      POINTER_TIMEOUT_MS
+    );
+  }, []);
+  useEffect(
+    () => () => {
+      if (pointerTimeoutRef.current) clearTimeout(pointerTimeoutRef.current);
+    },
+    []
+  );
+
+  // Options that fit `page` to the window, as it is shown on the output
+  const renderOptionsFor = useCallback(
+    async (page: number): Promise<RenderOptions | null> => {
+      const container = containerRef.current;
+      if (!container) {
+        return null;
+      }
+
+      // Get page dimensions, or those of the crop region if one is set
+      const crop = await fetchCrop(page);
+      const pageDims = crop ?? (await pdfRenderer.getPageDimensions(page));
+
+      // Calculate scale to fit page in container
+      const scaleX = container.clientWidth / pageDims.width;
+      const scaleY = container.clientHeight / pageDims.height;
+      const scale = Math.min(scaleX, scaleY, 2); // Cap at 2x for performance
+
+      return { scale, rotation: 0, crop, smartInvert };
+    },
+    [smartInvert]
+  );
+
   // Render the current page
   const renderPage = useCallback(async () => {
     if (!pdfPath || !canvasRef.current || !containerRef.current) {
@@ -64,29 +153,20 @@ export const PresenterView: React.FC = () => {
 
     try {
       // Load the document if needed
-      await pdfRenderer.loadDocument(pdfPath);
-
-      // Get container dimensions for fit calculation
-      const container = containerRef.current;
-      const containerWidth = container.clientWidth;
-      const containerHeight = container.clientHeight;
-
-      // Get page dimensions
-      const pageDims = await pdfRenderer.getPageDimensions(currentPage);
+      if (pdfRenderer.loadedPath !== pdfPath) {
+        await pdfRenderer.loadDocument(pdfPath);
+      }
 
-      // Calculate scale to fit page in container
-      const scaleX = containerWidth / pageDims.width;
-      const scaleY = containerHeight / pageDims.height;
-      const scale = Math.min(scaleX, scaleY, 2); // Cap at 2x for performance
+      const options = await renderOptionsFor(currentPage);
+      if (!options) {
+        return;
+      }
 
-      // Render the page to the canvas
+      // Render the page to the canvas (instant when it was pre-rendered)
       const result = await pdfRenderer.renderPage(
         currentPage,
         canvasRef.current,
-        {
-          scale,
-          rotation: 0,
-        }
+        options
       );
 
       // Convert canvas to data URL for display
@@ -96,7 +176,72 @@ export const PresenterView: React.FC = () => {
     } finally {
       setIsLoading(false);
     }
-  }, [pdfPath, currentPage]);
+  }, [pdfPath, currentPage, renderOptionsFor]);
+
+  // Render the pages queued by the backend ahead of time, nearest first
+  const prerenderPages = useCallback(
+    async (pages: number[]) => {
+      const generation = ++prerenderGeneration.current;
+      if (!pdfPath || pdfRenderer.loadedPath !== pdfPath) {
+        return;
+      }
+
+      for (const page of pages) {
+        if (generation !== prerenderGeneration.current) {
+          return;
+        }
+        try {
+          const options = await renderOptionsFor(page);
+          if (options) {
+            await pdfRenderer.prerenderPage(page, options);
+          }
+        } catch (err) {
+          logger.debug("Skipped pre-rendering page", page, err);
+        }
+      }
+    },
+    [pdfPath, renderOptionsFor]
+  );
+
+  // Render a page as shown on the output, at most `maxWidth` wide, for
+  // remote monitors
+  const publishPageImage = useCallback(
+    async ({ page, size, maxWidth }: PageImageRequest) => {
+      if (!pdfPath || pdfRenderer.loadedPath !== pdfPath) {
+        return;
+      }
+
+      try {
+        const options = await renderOptionsFor(page);
+        if (!options) {
+          return;
+        }
+        const rendered = document.createElement("canvas");
+        await pdfRenderer.renderPage(page, rendered, options);
+
+        const scale = Math.min(1, maxWidth / rendered.width);
+        const image = document.createElement("canvas");
+        image.width = Math.round(rendered.width * scale);
+        image.height = Math.round(rendered.height * scale);
+        const context = image.getContext("2d");
+        if (!context) {
+          return;
+        }
+        context.drawImage(rendered, 0, 0, image.width, image.height);
+
+        await WebSocketServerCommands.publishPageImage(
+          page,
+          size,
+          image.width,
+          image.height,
+          image.toDataURL("image/jpeg", PAGE_IMAGE_QUALITY)
+        );
+      } catch (err) {
+        logger.debug("Failed to publish page image", page, err);
+      }
+    },
+    [pdfPath, renderOptionsFor]
+  );
 
   // Set up Tauri event listeners
   // Set up event listeners (Tauri or WebSocket)
@@ -126,8 +271,11 @@ export const PresenterView: React.FC = () => {
         const unlistenPdfOpened = await listen<PdfOpenedPayload>(
           "pdf-opened",
           (event) => {
+            // Reload even when the same file is reopened
+            pdfRenderer.dispose();
             setPdfPath(event.payload.path);
             setTotalPages(event.payload.pageCount);
+            setSmartInvert(false);
             setCurrentPage(1);
           }
         );
@@ -151,6 +299,48 @@ export const PresenterView: React.FC = () => {
           }
         );
         unlistenFns.push(unlistenZoomChanged);
+
+        // Listen for smart dark mode toggles
+        const unlistenSmartInvert = await listen<{ enabled: boolean }>(
+          "smart-invert-changed",
+          (event) => {
+            setSmartInvert(event.payload.enabled);
+          }
+        );
+        unlistenFns.push(unlistenSmartInvert);
+
+        // Render neighbouring pages ahead of page flips
+        const unlistenPrerender = await listen<{ pages: number[] }>(
+          "prerender-pages",
+          (event) => {
+            prerenderPages(event.payload.pages);
+          }
+        );
+        unlistenFns.push(unlistenPrerender);
+
+        // Render pages remote monitors are waiting for
+        const unlistenPageImage = await listen<PageImageRequest>(
+          "page-image-requested",
+          (event) => {
+            publishPageImage(event.payload);
+          }
+        );
+        unlistenFns.push(unlistenPageImage);
+
+        // Show remote co-hosts' cursors for a moment
+        const unlistenPointer = await listen<PointerPayload>(
+          "pointer-moved",
+          (event) => {
+            showPointer(event.payload);
+          }
+        );
+        unlistenFns.push(unlistenPointer);
+
+        // Listen for crop changes
+        const unlistenCropChanged = await listen("page-crop-changed", () => {
+          renderPage();
+        });
+        unlistenFns.push(unlistenCropChanged);
       } catch (err) {
         logger.warn(
           "Failed to setup Tauri listeners, falling back to WebSocket",
@@ -177,6 +367,7 @@ export const PresenterView: React.FC = () => {
               setCurrentPage(data.page);
               setTotalPages(data.total_pages);
               if (data.pdf_path) setPdfPath(data.pdf_path);
+              setSmartInvert(Boolean(data.smart_invert));
               break;
 
             case "PAGE_CHANGED":
@@ -185,8 +376,10 @@ export const PresenterView: React.FC = () => {
               break;
 
             case "PDF_OPENED":
+              pdfRenderer.dispose();
               setPdfPath(data.path);
               setTotalPages(data.page_count);
+              setSmartInvert(false);
               setCurrentPage(1);
               break;
 
@@ -197,6 +390,18 @@ export const PresenterView: React.FC = () => {
               setRenderedImage(null);
               break;
 
+            case "SMART_INVERT_CHANGED":
+              setSmartInvert(data.enabled);
+              break;
+
+            case "PRERENDER_PAGES":
+              prerenderPages(data.pages);
+              break;
+
+            case "POINTER_MOVED":
+              showPointer(data);
+              break;
+
             case "ZOOM_CHANGED":
               // Trigger re-render
               renderPage();
@@ -235,7 +440,7 @@ export const PresenterView: React.FC = () => {
         clearTimeout(reconnectTimeout);
       }
     };
-  }, [renderPage]);
+  }, [renderPage, prerenderPages, publishPageImage, showPointer]);
 
   // Re-render when page or PDF changes
   useEffect(() => {
@@ -261,8 +466,8 @@ export const PresenterView: React.FC = () => {
       ref={containerRef}
       className="h-screen w-screen flex items-center justify-center bg-transparent overflow-hidden"
     >
-      {/* Loading state */}
-      {isLoading && (
+      {/* Loading state; the previous page stays up while the next renders */}
+      {isLoading && !renderedImage && (
         <div className="absolute inset-0 flex items-center justify-center bg-black/50 z-10">
           <div className="text-white text-lg">Loading...</div>
         </div>
@@ -290,15 +495,29 @@ export const PresenterView: React.FC = () => {
 
       {/* PDF Display */}
       {renderedImage && (
-        <img
-          src={renderedImage}
-          alt={`Page ${currentPage} of ${totalPages}`}
-          className="max-w-full max-h-full object-contain"
-          style={{
-            // Apply dark mode inversion if needed
-            filter: "none",
-          }}
-        />
+        <div className="relative">
+          <img
+            src={renderedImage}
+            alt={`Page ${currentPage} of ${totalPages}`}
+            className="block max-w-[100vw] max-h-screen object-contain"
+            style={{
+              // Apply dark mode inversion if needed
+              filter: "none",
+            }}
+          />
+
+          {/* Remote co-host cursor */}
+          {pointer && pointer.page === currentPage && (
+            <div
+              className="absolute w-4 h-4 -ml-2 -mt-2 rounded-full bg-red-500/80 ring-2 ring-white/80 pointer-events-none"
+              style={{
+                left: `${pointer.x * 100}%`,
+                top: `${pointer.y * 100}%`,
+              }}
+              title={pointer.client ?? undefined}
+            />
+          )}
+        </div>
       )}
 
       {/* Hidden canvas for rendering */}
@@ -0,0 +1,60 @@
+/*
+ * This file is part of StreamSlate.
+ * Copyright (C) 2025 StreamSlate Contributors
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+
+import { useState, useEffect } from "react";
+import { listen } from "@tauri-apps/api/event";
+import { StylePresetCommands, type PresetStore } from "../lib/tauri/commands";
+import type { StylePreset } from "../types/protocol.generated";
+
+const activePreset = (store: PresetStore) =>
+  store.presets.find((preset) => preset.id === store.active) ?? null;
+
+/**
+ * The style preset applied to new annotations, or null when none is
+ * active; follows switches made here, by remote clients or a Stream Deck
+ */
+export const useActiveStylePreset = () => {
+  const [preset, setPreset] = useState<StylePreset | null>(null);
+
+  useEffect(() => {
+    let cancelled = false;
+    let unlisten: (() => void) | undefined;
+
+    StylePresetCommands.listStylePresets()
+      .then((store) => {
+        if (!cancelled) setPreset(activePreset(store));
+      })
+      .catch((error) => {
+        console.error("Failed to load style presets:", error);
+      });
+
+    listen<PresetStore>("style-presets-changed", (event) => {
+      setPreset(activePreset(event.payload));
+    }).then((fn) => {
+      if (cancelled) fn();
+      else unlisten = fn;
+    });
+
+    return () => {
+      cancelled = true;
+      unlisten?.();
+    };
+  }, []);
+
+  return preset;
+};
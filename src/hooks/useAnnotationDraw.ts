@@ -26,6 +26,7 @@ import { AnnotationType } from "../types/pdf.types";
 import type { Annotation } from "../types/pdf.types";
 import { usePDFStore } from "../stores/pdf.store";
 import {
+  type PenData,
   type Point,
   type DrawingState,
   type DragState,
@@ -81,6 +82,8 @@ export function useAnnotationDraw({
   const toolbarRef = useRef<HTMLDivElement>(null);
   const nudgeTimeoutRef = useRef<ReturnType<typeof setTimeout> | null>(null);
   const isNudgingRef = useRef(false);
+  // Pen data of the latest pointer event; mouse events don't carry it
+  const penRef = useRef<PenData>({});
 
   const [drawingState, setDrawingState] = useState<DrawingState>(
     INITIAL_DRAWING_STATE
@@ -187,6 +190,14 @@ export function useAnnotationDraw({
 
   // ── Mouse handlers ───────────────────────────────────────────────────
 
+  // Pointer events arrive just before the mouse events they cause
+  const handlePointerEvent = useCallback((event: React.PointerEvent) => {
+    penRef.current =
+      event.pointerType === "pen"
+        ? { pressure: event.pressure, tiltX: event.tiltX, tiltY: event.tiltY }
+        : {};
+  }, []);
+
   const handleMouseDown = useCallback(
     (event: React.MouseEvent) => {
       event.preventDefault();
@@ -231,7 +242,7 @@ export function useAnnotationDraw({
           startY: y,
           currentX: x,
           currentY: y,
-          points: [{ x, y }],
+          points: [{ x, y, ...penRef.current }],
         });
         return;
       }
@@ -430,7 +441,7 @@ export function useAnnotationDraw({
           ...prev,
           currentX: x,
           currentY: y,
-          points: [...prev.points, { x, y }],
+          points: [...prev.points, { x, y, ...penRef.current }],
         }));
         return;
       }
@@ -625,7 +636,11 @@ export function useAnnotationDraw({
     if (selectedAnnotation.type === AnnotationType.FREE_DRAW) {
       const points = getPointsFromAnnotation(selectedAnnotation);
       if (points) {
-        const shifted = points.map((p) => ({ x: p.x + dx, y: p.y + dy }));
+        const shifted = points.map((p) => ({
+          ...p,
+          x: p.x + dx,
+          y: p.y + dy,
+        }));
         const bbox = bboxFromPoints(shifted);
         copied.points = shifted;
         copied.content = JSON.stringify(shifted);
@@ -1017,6 +1032,7 @@ export function useAnnotationDraw({
     canRedoAction,
     // Handlers
     getCursorStyle,
+    handlePointerEvent,
     handleMouseDown,
     handleMouseMove,
     handleMouseUp,
@@ -1,4 +1,5 @@
 import { invoke } from "@tauri-apps/api/core";
+import { listen } from "@tauri-apps/api/event";
 import { useState, useCallback, useEffect } from "react";
 import { logger } from "../lib/logger";
 
@@ -9,6 +10,23 @@ export interface CaptureTarget {
   id: number;
   app_name: string;
   title: string;
+  bundle_id: string | null;
+  is_on_screen: boolean;
+  bounds: WindowBounds | null;
+  /** Application icon as a PNG data URL */
+  icon: string | null;
+  /** Window thumbnail as a PNG data URL (only when requested) */
+  thumbnail: string | null;
+}
+
+/**
+ * Window frame in global display coordinates
+ */
+export interface WindowBounds {
+  x: number;
+  y: number;
+  width: number;
+  height: number;
 }
 
 /**
@@ -20,6 +38,8 @@ export interface CaptureStatus {
   ndi_running: boolean;
   syphon_available: boolean;
   syphon_running: boolean;
+  /** JPEG frames are being published to remote viewers */
+  remote_view_running: boolean;
   frames_captured: number;
   frames_sent: number;
   target_fps: number;
@@ -36,12 +56,58 @@ export interface DisplayTarget {
   origin_x: number;
   origin_y: number;
   is_primary: boolean;
+  /** Display thumbnail as a PNG data URL (only when requested) */
+  thumbnail: string | null;
+}
+
+/**
+ * Emitted when the captured window enters native fullscreen on another Space,
+ * where window capture usually goes black
+ */
+export interface FullscreenWarning {
+  window_id: number;
+  display_id: number;
+  /** Capture already switched to that display */
+  fallback_active: boolean;
+}
+
+export type ThermalState = "nominal" | "fair" | "serious" | "critical";
+
+export interface PowerStatus {
+  thermal: ThermalState | null;
+  battery: { percent: number; on_ac_power: boolean } | null;
+}
+
+/**
+ * Emitted while capturing when thermal or battery state becomes (or stops
+ * being) critical
+ */
+export interface PowerWarning {
+  status: PowerStatus;
+  critical: boolean;
+  /** Output frame rate cap applied by auto-throttle */
+  fps_limit: number | null;
+}
+
+/** Digital pan/zoom over the output; x/y is the centre (0-1), zoom 1 = full frame */
+export interface OutputViewport {
+  x: number;
+  y: number;
+  zoom: number;
+}
+
+export interface StartCaptureOptions {
+  /** Window to capture when no display is given (defaults to StreamSlate) */
+  windowId?: number;
+  /** Switch to display capture if the window goes fullscreen on another Space */
+  fullscreenFallback?: boolean;
 }
 
 export interface OutputCapabilities {
   platform: string;
   ndi_available: boolean;
   syphon_available: boolean;
+  mock_capture: boolean;
 }
 
 /**
@@ -60,6 +126,17 @@ export const useNDI = () => {
   const [ndiAvailable, setNdiAvailable] = useState(false);
   const [syphonAvailable, setSyphonAvailable] = useState(false);
   const [status, setStatus] = useState<CaptureStatus | null>(null);
+  const [fullscreenWarning, setFullscreenWarning] =
+    useState<FullscreenWarning | null>(null);
+  const [powerWarning, setPowerWarning] = useState<PowerWarning | null>(null);
+  const [autoThrottle, setAutoThrottleState] = useState(false);
+  const [colorCorrection, setColorCorrectionState] = useState(false);
+  const [blackout, setBlackoutState] = useState(false);
+  const [viewport, setViewport] = useState<OutputViewport>({
+    x: 0.5,
+    y: 0.5,
+    zoom: 1,
+  });
 
   // Check output availability on mount
   useEffect(() => {
@@ -79,6 +156,129 @@ export const useNDI = () => {
     check();
   }, []);
 
+  // Surface fullscreen warnings from the capture loop
+  useEffect(() => {
+    const unlisten = listen<FullscreenWarning>(
+      "capture-fullscreen-warning",
+      (event) => {
+        logger.warn("Captured window entered fullscreen:", event.payload);
+        setFullscreenWarning(event.payload);
+      }
+    );
+    return () => {
+      unlisten.then((fn) => fn());
+    };
+  }, []);
+
+  // Track pan/zoom moves, including those from remote producers
+  useEffect(() => {
+    invoke<OutputViewport>("get_output_viewport")
+      .then(setViewport)
+      .catch((err) => logger.error("Failed to get output viewport:", err));
+    const unlisten = listen<OutputViewport>(
+      "output-viewport-changed",
+      (event) => setViewport(event.payload)
+    );
+    return () => {
+      unlisten.then((fn) => fn());
+    };
+  }, []);
+
+  // Track output blackout, including toggles from clickers and remotes
+  useEffect(() => {
+    invoke<boolean>("get_blackout")
+      .then(setBlackoutState)
+      .catch((err) => logger.error("Failed to get blackout state:", err));
+    const unlisten = listen<{ enabled: boolean }>(
+      "blackout-changed",
+      (event) => setBlackoutState(event.payload.enabled)
+    );
+    return () => {
+      unlisten.then((fn) => fn());
+    };
+  }, []);
+
+  // Surface thermal/battery warnings; cleared when conditions recover
+  useEffect(() => {
+    const unlisten = listen<PowerWarning>("output-power-warning", (event) => {
+      if (event.payload.critical) {
+        logger.warn("Thermal or battery state critical:", event.payload);
+        setPowerWarning(event.payload);
+      } else {
+        setPowerWarning(null);
+      }
+    });
+    return () => {
+      unlisten.then((fn) => fn());
+    };
+  }, []);
+
+  /**
+   * Lower the output frame rate automatically under thermal pressure or
+   * low battery
+   */
+  const setAutoThrottle = useCallback(async (enabled: boolean) => {
+    try {
+      await invoke("set_auto_throttle", { enabled });
+      setAutoThrottleState(enabled);
+    } catch (err) {
+      logger.error("Failed to set auto-throttle:", err);
+    }
+  }, []);
+
+  /**
+   * Pan/zoom a virtual camera over the output, easing over `durationMs`
+   */
+  const setOutputViewport = useCallback(
+    async (next: OutputViewport, durationMs?: number) => {
+      try {
+        await invoke("set_output_viewport", {
+          viewport: next,
+          durationMs,
+        });
+      } catch (err) {
+        logger.error("Failed to set output viewport:", err);
+      }
+    },
+    []
+  );
+
+  /**
+   * Send black frames to every output instead of the slides
+   */
+  const setBlackout = useCallback(async (enabled: boolean) => {
+    try {
+      await invoke("set_blackout", { enabled });
+    } catch (err) {
+      logger.error("Failed to set blackout:", err);
+    }
+  }, []);
+
+  /**
+   * Replace the live capture on every active output with color bars for
+   * `seconds`, to check routing during setup
+   */
+  const sendTestPattern = useCallback(async (seconds: number) => {
+    try {
+      await invoke("send_test_pattern", { seconds });
+    } catch (err) {
+      logger.error("Failed to send test pattern:", err);
+    }
+  }, []);
+
+  /**
+   * Convert captured frames from the display's color profile to sRGB, so
+   * slides match across built-in and external displays
+   */
+  const setColorCorrection = useCallback(async (on: boolean) => {
+    try {
+      await invoke("set_capture_color_correction", { on });
+      setColorCorrectionState(on);
+    } catch (err) {
+      logger.error("Failed to set color correction:", err);
+    }
+  }, []);
+
   /**
    * Check if NDI feature is compiled in
    */
@@ -107,10 +307,13 @@ export const useNDI = () => {
 
   /**
    * List all available windows for capture
+   * @param includeThumbnails Also capture a thumbnail of each on-screen window
    */
-  const listCaptureTargets = useCallback(async () => {
+  const listCaptureTargets = useCallback(async (includeThumbnails = false) => {
     try {
-      const targets = await invoke<CaptureTarget[]>("list_capture_targets");
+      const targets = await invoke<CaptureTarget[]>("list_capture_targets", {
+        includeThumbnails,
+      });
       setCaptureTargets(targets);
       return targets;
     } catch (err) {
@@ -121,10 +324,13 @@ export const useNDI = () => {
 
   /**
    * List all available displays/monitors for capture
+   * @param includeThumbnails Also capture a thumbnail of each display
    */
-  const listDisplays = useCallback(async () => {
+  const listDisplays = useCallback(async (includeThumbnails = false) => {
     try {
-      const displays = await invoke<DisplayTarget[]>("list_capture_displays");
+      const displays = await invoke<DisplayTarget[]>("list_capture_displays", {
+        includeThumbnails,
+      });
       setDisplayTargets(displays ?? []);
       return displays;
     } catch (err) {
@@ -149,12 +355,18 @@ export const useNDI = () => {
 
   /**
    * Start native screen capture (and NDI if available)
-   * @param displayId Optional display ID to capture. If omitted, captures the StreamSlate window.
+   * @param displayId Optional display ID to capture. If omitted, captures a window.
+   * @param options Window selection and fullscreen fallback
    */
   const startCapture = useCallback(
-    async (displayId?: number) => {
+    async (displayId?: number, options: StartCaptureOptions = {}) => {
       try {
-        await invoke("start_ndi_sender", { displayId: displayId ?? null });
+        await invoke("start_ndi_sender", {
+          displayId: displayId ?? null,
+          windowId: options.windowId ?? null,
+          fullscreenFallback: options.fullscreenFallback ?? false,
+        });
+        setFullscreenWarning(null);
         setIsSending(true);
         await getCaptureStatus();
       } catch (err) {
@@ -171,6 +383,7 @@ export const useNDI = () => {
     try {
       await invoke("stop_ndi_sender");
       setIsSending(false);
+      setPowerWarning(null);
       await getCaptureStatus();
     } catch (err) {
       logger.error("Failed to stop capture:", err);
@@ -246,6 +459,12 @@ export const useNDI = () => {
     ndiAvailable,
     syphonAvailable,
     status,
+    fullscreenWarning,
+    powerWarning,
+    autoThrottle,
+    colorCorrection,
+    blackout,
+    viewport,
 
     // Actions
     startCapture,
@@ -257,6 +476,11 @@ export const useNDI = () => {
     getCaptureStatus,
     checkNdiAvailable,
     checkSyphonAvailable,
+    setAutoThrottle,
+    setColorCorrection,
+    sendTestPattern,
+    setBlackout,
+    setOutputViewport,
 
     // Legacy (backward compat)
     startTestPattern,
@@ -21,11 +21,16 @@
  */
 
 import { useCallback, useEffect, useRef } from "react";
+import { listen } from "@tauri-apps/api/event";
 import { open, save } from "@tauri-apps/plugin-dialog";
 import { writeFile } from "@tauri-apps/plugin-fs";
-import { PDFCommands, AnnotationCommands } from "../lib/tauri/commands";
+import {
+  PDFCommands,
+  AnnotationCommands,
+  LayerCommands,
+} from "../lib/tauri/commands";
 import { exportPDF } from "../lib/pdf/exporter";
-import type { AnnotationDTO } from "../lib/tauri/commands";
+import type { AnnotationDTO, PdfLoadProgress } from "../lib/tauri/commands";
 import {
   annotationToDTO,
   dtoToAnnotation,
@@ -101,9 +106,24 @@ export const usePDF = () => {
         return;
       }
 
-      setLoading(true, LoadingStage.PARSING, 25, "Parsing PDF file...");
+      setLoading(true, LoadingStage.PARSING, 0, "Reading PDF file...");
 
-      const pdfInfo = await PDFCommands.openPdf(selected);
+      // Large files load in the background and report their progress
+      const unlisten = await listen<PdfLoadProgress>(
+        "pdf-load-progress",
+        (event) => {
+          const { stage, percent } = event.payload;
+          const message =
+            stage === "reading" ? "Reading PDF file..." : "Parsing PDF file...";
+          setLoading(true, LoadingStage.PARSING, percent, message);
+        }
+      );
+      let pdfInfo;
+      try {
+        pdfInfo = await PDFCommands.openPdf(selected);
+      } finally {
+        unlisten();
+      }
 
       const pdfDocument: PDFDocument = {
         id: crypto.randomUUID(),
@@ -121,9 +141,15 @@ export const usePDF = () => {
         isLoaded: true,
       };
 
+      // Reopen where the document was left last time
+      const startPage = pdfInfo.restored?.page ?? 1;
+
       setLoading(true, LoadingStage.COMPLETE, 100, "PDF loaded successfully");
       setDocument(pdfDocument);
-      setCurrentPage(1);
+      setCurrentPage(startPage);
+      if (pdfInfo.restored) {
+        setZoom(pdfInfo.restored.zoom);
+      }
       setError(null);
 
       // Emit event for cross-window sync (presenter mode)
@@ -133,7 +159,7 @@ export const usePDF = () => {
         title: pdfDocument.title,
       });
       await emitPageChanged({
-        page: 1,
+        page: startPage,
         totalPages: pdfDocument.pageCount,
         pdfPath: pdfDocument.path,
       });
@@ -150,6 +176,11 @@ export const usePDF = () => {
       } catch {
         // Annotations not loading is not critical - may not exist yet
       }
+      try {
+        usePDFStore.getState().setLayers(await LayerCommands.listLayers());
+      } catch {
+        usePDFStore.getState().setLayers([]);
+      }
 
       // Complete loading after a brief delay to show success message
       setTimeout(() => {
@@ -164,7 +195,14 @@ export const usePDF = () => {
       setError(error);
       setLoading(false, LoadingStage.ERROR, 0, error.message);
     }
-  }, [setDocument, setLoading, setError, setCurrentPage, addAnnotation]);
+  }, [
+    setDocument,
+    setLoading,
+    setError,
+    setCurrentPage,
+    setZoom,
+    addAnnotation,
+  ]);
 
   /**
    * Save annotations to the sidecar file
@@ -225,6 +263,21 @@ export const usePDF = () => {
     }, 1000); // 1 second debounce
   }, [saveAnnotations]);
 
+  // Remember the page and zoom so the document reopens where it was left
+  useEffect(() => {
+    if (!document) return;
+
+    const timeout = setTimeout(() => {
+      PDFCommands.saveReadingProgress(
+        viewerState.currentPage,
+        viewerState.zoom
+      ).catch(() => {
+        // Reading progress is best effort
+      });
+    }, 1000);
+    return () => clearTimeout(timeout);
+  }, [document, viewerState.currentPage, viewerState.zoom]);
+
   // Cleanup timeout on unmount
   useEffect(() => {
     return () => {
@@ -1,6 +1,6 @@
 import { useEffect } from "react";
 import { listen } from "@tauri-apps/api/event";
-import { type AnnotationDTO } from "../lib/tauri/commands";
+import { type AnnotationDTO, type Layer } from "../lib/tauri/commands";
 import { dtoToAnnotation } from "../lib/annotations/converters";
 import { usePDFStore } from "../stores/pdf.store";
 import { logger } from "../lib/logger";
@@ -60,7 +60,6 @@ export const useRemoteControl = (
       );
       unlisten.push(unlistenPresenter);
 
-      // Listen for annotation additions (from remote clients)
       // Listen for annotation additions (from remote clients)
       const unlistenAnnotation = await listen<{
         page: number;
@@ -73,12 +72,55 @@ export const useRemoteControl = (
       });
       unlisten.push(unlistenAnnotation);
 
+      // Listen for annotation edits and removals (from remote clients)
+      const unlistenAnnotationUpdated = await listen<{
+        page: number;
+        annotation: AnnotationDTO;
+      }>("annotation-updated", (event) => {
+        logger.debug("Remote annotation updated:", event.payload);
+        usePDFStore
+          .getState()
+          .upsertAnnotation(dtoToAnnotation(event.payload.annotation));
+      });
+      unlisten.push(unlistenAnnotationUpdated);
+
+      const unlistenAnnotationDeleted = await listen<{
+        page: number;
+        id: string;
+      }>("annotation-deleted", (event) => {
+        logger.debug("Remote annotation deleted:", event.payload);
+        usePDFStore.getState().removeAnnotation(event.payload.id);
+      });
+      unlisten.push(unlistenAnnotationDeleted);
+
+      // Listen for layers shown, hidden or edited here or by remote clients
+      const unlistenLayers = await listen<{ layers: Layer[] }>(
+        "layers-changed",
+        (event) => {
+          logger.debug("Layers changed:", event.payload);
+          usePDFStore.getState().setLayers(event.payload.layers);
+        }
+      );
+      unlisten.push(unlistenLayers);
+
       // Listen for annotation clearing
       const unlistenClear = await listen("annotations-cleared", () => {
         logger.debug("Remote annotations cleared");
         usePDFStore.getState().clearAnnotations();
       });
       unlisten.push(unlistenClear);
+
+      // Listen for undo/redo applied by the backend annotation history
+      const unlistenUpdated = await listen<{
+        annotations: Record<string, AnnotationDTO[]>;
+      }>("annotations-updated", (event) => {
+        logger.debug("Remote annotations updated:", event.payload);
+        const { setPageAnnotations } = usePDFStore.getState();
+        Object.entries(event.payload.annotations).forEach(([page, list]) => {
+          setPageAnnotations(Number(page), list.map(dtoToAnnotation));
+        });
+      });
+      unlisten.push(unlistenUpdated);
     };
 
     setupListeners();
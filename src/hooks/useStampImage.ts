@@ -0,0 +1,54 @@
+/*
+ * This file is part of StreamSlate.
+ * Copyright (C) 2025 StreamSlate Contributors
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+
+import { useState, useEffect } from "react";
+import { StampCommands } from "../lib/tauri/commands";
+
+// Stamps are content-addressed, so a loaded one never changes
+const stampCache = new Map<string, Promise<string>>();
+
+/** Data URL of stored stamp `name`, or null while loading or if missing */
+export const useStampImage = (name: string | undefined) => {
+  const [url, setUrl] = useState<string | null>(null);
+
+  useEffect(() => {
+    setUrl(null);
+    if (!name) return;
+
+    let request = stampCache.get(name);
+    if (!request) {
+      request = StampCommands.getStampImage(name);
+      stampCache.set(name, request);
+      request.catch(() => stampCache.delete(name));
+    }
+
+    let cancelled = false;
+    request
+      .then((dataUrl) => {
+        if (!cancelled) setUrl(dataUrl);
+      })
+      .catch((error) => {
+        console.error(`Failed to load stamp ${name}:`, error);
+      });
+    return () => {
+      cancelled = true;
+    };
+  }, [name]);
+
+  return url;
+};
@@ -6,7 +6,7 @@
 import type { AnnotationDTO } from "../tauri/commands";
 import type { Annotation } from "../../types/pdf.types";
 
-type Point = { x: number; y: number };
+import { parsePenData, type Point } from "../utils/geometry";
 
 const asRecord = (value: unknown): Record<string, unknown> | null => {
   if (typeof value !== "object" || value === null) {
@@ -36,7 +36,7 @@ const parsePoints = (value: unknown): Point[] | null => {
       continue;
     }
 
-    points.push({ x, y });
+    points.push({ x, y, ...parsePenData(payload) });
   }
 
   return points.length > 0 ? points : null;
@@ -84,6 +84,10 @@ export function annotationToDTO(annotation: Annotation): AnnotationDTO {
     modified: annotation.modified.toISOString(),
     visible: annotation.visible,
     points: points ?? undefined,
+    layer: annotation.layer,
+    image: annotation.image,
+    ephemeral: annotation.ephemeral,
+    locked: annotation.locked,
   };
 }
 
@@ -112,6 +116,10 @@ export function dtoToAnnotation(dto: AnnotationDTO): Annotation {
     created: new Date(dto.created),
     modified: new Date(dto.modified),
     visible: dto.visible,
+    layer: dto.layer,
+    image: dto.image,
+    ephemeral: dto.ephemeral,
+    locked: dto.locked,
   };
 }
 
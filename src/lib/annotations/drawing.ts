@@ -17,8 +17,14 @@ import { AnnotationType } from "../../types/pdf.types";
 import type { Annotation } from "../../types/pdf.types";
 
 // Re-export geometry primitives so consumers don't need a separate import
-export { type Point, pointsToSmoothPath } from "../utils/geometry";
-import type { Point } from "../utils/geometry";
+export {
+  type PenData,
+  type Point,
+  hasPressure,
+  pointsToSmoothPath,
+  pressureSegments,
+} from "../utils/geometry";
+import { parsePenData, type Point } from "../utils/geometry";
 
 // ── Constants ──────────────────────────────────────────────────────────
 
@@ -176,7 +182,7 @@ export function getPointsFromAnnotation(
         continue;
       }
 
-      points.push({ x, y });
+      points.push({ x, y, ...parsePenData(payload) });
     }
     return points.length > 0 ? points : null;
   } catch {
@@ -213,6 +219,7 @@ export function moveAnnotationBy(
     const points = getPointsFromAnnotation(annotation) ?? [];
     if (points.length > 0) {
       const movedPoints = points.map((p) => ({
+        ...p,
         x: p.x + dx,
         y: p.y + dy,
       }));
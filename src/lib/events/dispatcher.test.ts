@@ -37,6 +37,8 @@ function makeActions(overrides: Partial<EventActions> = {}): EventActions {
     setPresenterMode: vi.fn(),
     setDocument: vi.fn(),
     setPageAnnotations: vi.fn(),
+    upsertAnnotation: vi.fn(),
+    removeAnnotation: vi.fn(),
     clearAnnotations: vi.fn(),
     getCurrentDocument: () => null,
     ...overrides,
@@ -273,6 +275,41 @@ describe("event handlers", () => {
     expect(actions.setPageAnnotations).not.toHaveBeenCalled();
   });
 
+  it("ANNOTATION_UPDATED upserts the annotation on its page", () => {
+    const handler = HANDLER_MAP.get(IntegrationMessageType.ANNOTATION_UPDATED)!;
+    handler(
+      makeEvent({
+        data: {
+          page: 2,
+          annotation: {
+            id: "a1",
+            type: "highlight",
+            x: 0,
+            y: 0,
+            width: 100,
+            height: 50,
+            content: "",
+            color: "#ff0",
+            opacity: 1,
+            created: "2026-01-01T00:00:00.000Z",
+            modified: "2026-01-01T00:00:00.000Z",
+            visible: true,
+          },
+        },
+      }),
+      actions
+    );
+    expect(actions.upsertAnnotation).toHaveBeenCalledWith(
+      expect.objectContaining({ id: "a1", pageNumber: 2 })
+    );
+  });
+
+  it("ANNOTATION_REMOVED removes the annotation by id", () => {
+    const handler = HANDLER_MAP.get(IntegrationMessageType.ANNOTATION_REMOVED)!;
+    handler(makeEvent({ data: { page: 2, id: "a1" } }), actions);
+    expect(actions.removeAnnotation).toHaveBeenCalledWith("a1");
+  });
+
   it("ANNOTATIONS_CLEARED clears annotations", () => {
     const handler = HANDLER_MAP.get(
       IntegrationMessageType.ANNOTATIONS_CLEARED
@@ -37,6 +37,9 @@ export interface EventActions {
   setPresenterMode: (active: boolean) => void;
   setDocument: (doc: EventDocument | null) => void;
   setPageAnnotations: (page: number, annotations: Annotation[]) => void;
+  /** Add the annotation, or replace the one with the same id */
+  upsertAnnotation: (annotation: Annotation) => void;
+  removeAnnotation: (id: string) => void;
   clearAnnotations: () => void;
   getCurrentDocument: () => EventDocument | null;
 }
@@ -192,6 +195,36 @@ function handleAnnotationsUpdated(
   }
 }
 
+function handleAnnotationChanged(
+  event: IntegrationEvent,
+  actions: EventActions
+): void {
+  const payload = toRecord(event.data);
+  const page = readNumber(payload, ["page"]);
+  const annotation = toRecord(payload?.annotation);
+  if (page === null || page < 1 || !annotation) {
+    return;
+  }
+  const dto = parseAnnotationDTO({ pageNumber: page, ...annotation });
+  if (!dto) {
+    return;
+  }
+  actions.upsertAnnotation({
+    ...dtoToAnnotation(dto),
+    pageNumber: Math.floor(page),
+  });
+}
+
+function handleAnnotationRemoved(
+  event: IntegrationEvent,
+  actions: EventActions
+): void {
+  const id = readString(toRecord(event.data), ["id"]);
+  if (id) {
+    actions.removeAnnotation(id);
+  }
+}
+
 function handleAnnotationsCleared(
   _event: IntegrationEvent,
   actions: EventActions
@@ -212,6 +245,9 @@ export const HANDLER_MAP = new Map<IntegrationMessageType, EventHandler>([
   [IntegrationMessageType.CONNECTION_STATUS, handleConnectionStatus],
   [IntegrationMessageType.PDF_OPENED, handlePdfOpened],
   [IntegrationMessageType.PDF_CLOSED, handlePdfClosed],
+  [IntegrationMessageType.ANNOTATION_ADDED, handleAnnotationChanged],
+  [IntegrationMessageType.ANNOTATION_UPDATED, handleAnnotationChanged],
+  [IntegrationMessageType.ANNOTATION_REMOVED, handleAnnotationRemoved],
   [IntegrationMessageType.ANNOTATIONS_UPDATED, handleAnnotationsUpdated],
   [IntegrationMessageType.ANNOTATIONS_CLEARED, handleAnnotationsCleared],
 ]);
@@ -250,7 +286,12 @@ export function getStatusMessage(events: IntegrationEvent[]): string {
       }
     }
 
-    if (event.type === IntegrationMessageType.ANNOTATIONS_UPDATED) {
+    if (
+      event.type === IntegrationMessageType.ANNOTATIONS_UPDATED ||
+      event.type === IntegrationMessageType.ANNOTATION_ADDED ||
+      event.type === IntegrationMessageType.ANNOTATION_UPDATED ||
+      event.type === IntegrationMessageType.ANNOTATION_REMOVED
+    ) {
       return "Remote annotations updated";
     }
 
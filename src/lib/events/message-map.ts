@@ -35,6 +35,9 @@ export const WS_MESSAGE_MAP: ReadonlyArray<
   ["PDF_OPENED", IntegrationMessageType.PDF_OPENED],
   ["PDF_CLOSED", IntegrationMessageType.PDF_CLOSED],
   ["PRESENTER_CHANGED", IntegrationMessageType.PRESENTER_MODE_TOGGLED],
+  ["ANNOTATION_ADDED", IntegrationMessageType.ANNOTATION_ADDED],
+  ["ANNOTATION_UPDATED", IntegrationMessageType.ANNOTATION_UPDATED],
+  ["ANNOTATION_DELETED", IntegrationMessageType.ANNOTATION_REMOVED],
   ["ANNOTATIONS_UPDATED", IntegrationMessageType.ANNOTATIONS_UPDATED],
   ["ANNOTATIONS_CLEARED", IntegrationMessageType.ANNOTATIONS_CLEARED],
   ["PONG", IntegrationMessageType.PONG],
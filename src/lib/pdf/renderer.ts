@@ -44,6 +44,17 @@ export interface RenderOptions {
   };
   darkMode?: boolean;
   backgroundColor?: string;
+  /** Region to render, in PDF points from the top-left of the unrotated page */
+  crop?: CropRegion;
+  /** Invert luminance while preserving hue (smart dark mode) */
+  smartInvert?: boolean;
+}
+
+export interface CropRegion {
+  x: number;
+  y: number;
+  width: number;
+  height: number;
 }
 
 export interface PDFRenderResult {
@@ -57,9 +68,50 @@ export interface PDFRenderResult {
   };
 }
 
+/**
+ * Invert luminance in place while preserving hue: each pixel is shifted
+ * along the gray axis so its luma Y becomes 1 - Y. White paper turns black,
+ * but a red highlight stays red rather than turning cyan.
+ */
+export function invertLuminance(
+  context: CanvasRenderingContext2D,
+  width: number,
+  height: number
+): void {
+  const image = context.getImageData(0, 0, width, height);
+  const data = image.data;
+  for (let i = 0; i < data.length; i += 4) {
+    const luma = 0.2126 * data[i] + 0.7152 * data[i + 1] + 0.0722 * data[i + 2];
+    const shift = 255 - 2 * luma;
+    // Uint8ClampedArray clamps out-of-range channels
+    data[i] += shift;
+    data[i + 1] += shift;
+    data[i + 2] += shift;
+  }
+  context.putImageData(image, 0, 0);
+}
+
+/** Pre-rendered pages kept in memory, least recently used dropped first */
+const MAX_PRERENDERED_PAGES = 8;
+
+/**
+ * Cache key of a page rendered with the given options
+ */
+function renderKey(pageNumber: number, options: RenderOptions): string {
+  return JSON.stringify([
+    pageNumber,
+    options.scale,
+    options.rotation,
+    options.crop ?? null,
+    Boolean(options.smartInvert),
+  ]);
+}
+
 export class PDFRenderer {
   private document: PDFDocumentProxy | null = null;
+  private documentPath: string | null = null;
   private renderTasks: Map<number, RenderTask> = new Map();
+  private prerendered: Map<string, HTMLCanvasElement> = new Map();
 
   /**
    * Load a PDF document from a file path
@@ -67,6 +119,10 @@ export class PDFRenderer {
   async loadDocument(filePath: string): Promise<PDFDocumentProxy> {
     logger.debug("[PDFRenderer] Loading document from path:", filePath);
 
+    if (filePath !== this.documentPath) {
+      this.prerendered.clear();
+    }
+
     try {
       // Convert the file path to a URL that Tauri can serve
       const fileUrl = convertFileSrc(filePath);
@@ -95,6 +151,7 @@ export class PDFRenderer {
       });
 
       this.document = await loadingTask.promise;
+      this.documentPath = filePath;
       logger.debug(
         "[PDFRenderer] Document loaded successfully, pages:",
         this.document.numPages
@@ -141,10 +198,42 @@ export class PDFRenderer {
     );
 
     const page = await this.getPage(pageNumber);
-    const viewport = page.getViewport({
+
+    // Flip straight to a pre-rendered copy when there is one
+    const key = renderKey(pageNumber, options);
+    const cached = this.prerendered.get(key);
+    if (cached) {
+      this.prerendered.delete(key);
+      this.prerendered.set(key, cached);
+      return this.drawPrerendered(cached, canvas, page, options);
+    }
+
+    let viewport = page.getViewport({
       scale: options.scale,
       rotation: options.rotation,
     });
+    let width = viewport.width;
+    let height = viewport.height;
+
+    // Shift the viewport so the crop region starts at the canvas origin
+    if (options.crop) {
+      const { x, y, width: cropWidth, height: cropHeight } = options.crop;
+      const [left, , , top] = page.view;
+      const [x1, y1, x2, y2] = viewport.convertToViewportRectangle([
+        left + x,
+        top - y,
+        left + x + cropWidth,
+        top - y - cropHeight,
+      ]);
+      viewport = page.getViewport({
+        scale: options.scale,
+        rotation: options.rotation,
+        offsetX: -Math.min(x1, x2),
+        offsetY: -Math.min(y1, y2),
+      });
+      width = Math.abs(x2 - x1);
+      height = Math.abs(y2 - y1);
+    }
 
     logger.debug(
       "[PDFRenderer] Viewport dimensions:",
@@ -159,12 +248,12 @@ export class PDFRenderer {
       throw new Error("Failed to get canvas context");
     }
 
-    canvas.width = viewport.width;
-    canvas.height = viewport.height;
+    canvas.width = width;
+    canvas.height = height;
 
     // Set canvas style dimensions to match
-    canvas.style.width = `${viewport.width}px`;
-    canvas.style.height = `${viewport.height}px`;
+    canvas.style.width = `${width}px`;
+    canvas.style.height = `${height}px`;
 
     logger.debug(
       "[PDFRenderer] Canvas dimensions set to:",
@@ -217,6 +306,10 @@ export class PDFRenderer {
       await renderTask.promise;
       this.renderTasks.delete(pageNumber);
 
+      if (options.smartInvert) {
+        invertLuminance(context, canvas.width, canvas.height);
+      }
+
       // Check if anything was actually drawn
       const imageData = context.getImageData(
         0,
@@ -239,7 +332,12 @@ export class PDFRenderer {
       return {
         canvas,
         page,
-        viewport,
+        viewport: {
+          width,
+          height,
+          scale: viewport.scale,
+          rotation: viewport.rotation,
+        },
       };
     } catch (error) {
       logger.error(
@@ -253,6 +351,59 @@ export class PDFRenderer {
     }
   }
 
+  /**
+   * Render a page off screen so a later renderPage with the same options
+   * is an instant copy. Already pre-rendered pages are skipped.
+   */
+  async prerenderPage(
+    pageNumber: number,
+    options: RenderOptions
+  ): Promise<void> {
+    const key = renderKey(pageNumber, options);
+    if (this.prerendered.has(key)) {
+      return;
+    }
+
+    const canvas = document.createElement("canvas");
+    await this.renderPage(pageNumber, canvas, options);
+    this.prerendered.set(key, canvas);
+    while (this.prerendered.size > MAX_PRERENDERED_PAGES) {
+      const oldest = this.prerendered.keys().next().value;
+      if (oldest === undefined) break;
+      this.prerendered.delete(oldest);
+    }
+    logger.debug("[PDFRenderer] Pre-rendered page", pageNumber);
+  }
+
+  private drawPrerendered(
+    source: HTMLCanvasElement,
+    canvas: HTMLCanvasElement,
+    page: PDFPageProxy,
+    options: RenderOptions
+  ): PDFRenderResult {
+    const context = canvas.getContext("2d");
+    if (!context) {
+      throw new Error("Failed to get canvas context");
+    }
+
+    canvas.width = source.width;
+    canvas.height = source.height;
+    canvas.style.width = `${source.width}px`;
+    canvas.style.height = `${source.height}px`;
+    context.drawImage(source, 0, 0);
+
+    return {
+      canvas,
+      page,
+      viewport: {
+        width: source.width,
+        height: source.height,
+        scale: options.scale,
+        rotation: options.rotation,
+      },
+    };
+  }
+
   /**
    * Get page dimensions without rendering
    */
@@ -340,10 +491,12 @@ export class PDFRenderer {
    */
   dispose(): void {
     this.cancelAllRenderTasks();
+    this.prerendered.clear();
 
     if (this.document) {
       this.document.destroy();
       this.document = null;
+      this.documentPath = null;
     }
   }
 
@@ -371,6 +524,13 @@ export class PDFRenderer {
     return this.document?.numPages ?? 0;
   }
 
+  /**
+   * Path of the loaded document
+   */
+  get loadedPath(): string | null {
+    return this.documentPath;
+  }
+
   /**
    * Check if a document is loaded
    */
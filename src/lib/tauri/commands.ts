@@ -22,6 +22,14 @@
  */
 
 import { invoke } from "@tauri-apps/api/core";
+import type {
+  AnnotationMatch,
+  CommandClass,
+  ImageSize,
+  PageWord,
+  StylePreset,
+} from "../../types/protocol.generated";
+import type { Point } from "../utils/geometry";
 
 // Types
 export interface PdfInfo {
@@ -32,13 +40,88 @@ export interface PdfInfo {
   file_size: number;
   created?: string;
   modified?: string;
+  /** Stored reading progress the document was reopened at */
+  restored?: ReadingProgress;
+}
+
+/** Payload of the `pdf-load-progress` event */
+export interface PdfLoadProgress {
+  path: string;
+  stage: "reading" | "validating" | "parsing" | "finishing";
+  percent: number;
+}
+
+/** Where a document was last left */
+export interface ReadingProgress {
+  page: number;
+  zoom: number;
+  scroll_x: number;
+  scroll_y: number;
+  updated_at: string;
 }
 
 export interface PdfPage {
   page_number: number;
+  /** Size of the visible (cropped) region */
   width: number;
   height: number;
   rotation: number;
+  /** Visible region of the page, when a crop is set */
+  crop?: CropRect | null;
+}
+
+/** Page region in PDF points from the top-left of the unrotated page */
+export interface CropRect {
+  x: number;
+  y: number;
+  width: number;
+  height: number;
+}
+
+export interface OcrPage {
+  page: number;
+  text: string;
+  words: PageWord[];
+  /** Mean word confidence (0.0-1.0) */
+  confidence: number;
+}
+
+export type PageStatus = "unchanged" | "changed" | "added" | "removed";
+
+export interface TextChange {
+  kind: "inserted" | "deleted";
+  text: string;
+  /** Word boxes on the page the words appear on */
+  words: PageWord[];
+}
+
+export interface PageDiff {
+  page: number;
+  status: PageStatus;
+  text_changes: TextChange[];
+  size_changed: boolean;
+  visual_changed: boolean;
+}
+
+export interface PdfComparison {
+  page_count_a: number;
+  page_count_b: number;
+  pages: PageDiff[];
+}
+
+export interface OptimizeOptions {
+  object_streams?: boolean;
+  /** Downsample images whose longer side exceeds this many pixels (null: keep) */
+  max_image_size?: number | null;
+}
+
+export interface OptimizeReport {
+  original_bytes: number;
+  optimized_bytes: number;
+  images_downsampled: number;
+  /** Oversized images in encodings that can't be resampled (e.g. JPEG) */
+  images_skipped: number;
+  objects_removed: number;
 }
 
 export interface PresenterConfig {
@@ -70,9 +153,19 @@ export interface PresenterState {
 export class PDFCommands {
   /**
    * Open a PDF file and return basic information about it
+   *
+   * With `sanitize`, active content (JavaScript, launch actions, embedded
+   * executables) is stripped and a `pdf-sanitized` event reports removals.
    */
-  static async openPdf(path: string): Promise<PdfInfo> {
-    return await invoke<PdfInfo>("open_pdf", { path });
+  static async openPdf(path: string, sanitize = false): Promise<PdfInfo> {
+    return await invoke<PdfInfo>("open_pdf", { path, sanitize });
+  }
+
+  /**
+   * Abandon the openPdf in progress; resolves false if none was running
+   */
+  static async cancelPdfOpen(): Promise<boolean> {
+    return await invoke<boolean>("cancel_pdf_open");
   }
 
   /**
@@ -82,6 +175,56 @@ export class PDFCommands {
     return await invoke<void>("close_pdf");
   }
 
+  /**
+   * Print the open document (or a given file, e.g. an exported marked-up
+   * copy) through the OS print pipeline; range is like "1-3,7"
+   */
+  static async printPdf(
+    range?: string,
+    printer?: string,
+    path?: string
+  ): Promise<string> {
+    return await invoke<string>("print_pdf", { range, printer, path });
+  }
+
+  /**
+   * List the printers known to the OS
+   */
+  static async listPrinters(): Promise<string[]> {
+    return await invoke<string[]>("list_printers");
+  }
+
+  /**
+   * Remember where the open document is being read
+   */
+  static async saveReadingProgress(
+    page: number,
+    zoom: number,
+    scrollX = 0,
+    scrollY = 0
+  ): Promise<void> {
+    return await invoke<void>("save_reading_progress", {
+      page,
+      zoom,
+      scrollX,
+      scrollY,
+    });
+  }
+
+  /**
+   * Whether documents reopen where they were left
+   */
+  static async getReadingProgressEnabled(): Promise<boolean> {
+    return await invoke<boolean>("get_reading_progress_enabled");
+  }
+
+  /**
+   * Turn restoring reading progress on or off
+   */
+  static async setReadingProgressEnabled(enabled: boolean): Promise<void> {
+    return await invoke<void>("set_reading_progress_enabled", { enabled });
+  }
+
   /**
    * Get information about a specific page in the PDF
    */
@@ -89,6 +232,29 @@ export class PDFCommands {
     return await invoke<PdfPage>("get_pdf_page_info", { pageNumber });
   }
 
+  /**
+   * Get dimensions and rotation of every page in a single call
+   */
+  static async getAllPageInfo(): Promise<PdfPage[]> {
+    return await invoke<PdfPage[]>("get_all_page_info");
+  }
+
+  /**
+   * Crop every page of the open document for the audience output, or clear
+   * the crop with null. Reset when another document is opened.
+   */
+  static async setPageCrop(rect: CropRect | null): Promise<void> {
+    return await invoke<void>("set_page_crop", { rect });
+  }
+
+  /**
+   * Toggle smart dark mode (hue-preserving luminance inversion) for the
+   * audience output of the open document
+   */
+  static async setSmartInvert(enabled: boolean): Promise<void> {
+    return await invoke<void>("set_smart_invert", { enabled });
+  }
+
   /**
    * Get the total number of pages in the currently open PDF
    */
@@ -102,6 +268,48 @@ export class PDFCommands {
   static async isPdfOpen(): Promise<boolean> {
     return await invoke<boolean>("is_pdf_open");
   }
+
+  /**
+   * Get the words on a page with bounding boxes in annotation coordinates
+   * (PDF points from the top-left of the unrotated page)
+   */
+  static async getPageWords(page: number): Promise<PageWord[]> {
+    return await invoke<PageWord[]>("get_page_words", { page });
+  }
+
+  /**
+   * Compare two PDFs page by page (neither is opened), e.g. the final deck
+   * against the rehearsed one
+   */
+  static async comparePdfs(pathA: string, pathB: string): Promise<PdfComparison> {
+    return await invoke<PdfComparison>("compare_pdfs", { pathA, pathB });
+  }
+
+  /**
+   * Write a smaller copy of the open PDF (downsampled images, compressed
+   * object streams) to an absolute path
+   */
+  static async optimizePdf(
+    outputPath: string,
+    options?: OptimizeOptions
+  ): Promise<OptimizeReport> {
+    return await invoke<OptimizeReport>("optimize_pdf", { outputPath, options });
+  }
+
+  /**
+   * Check whether this build can OCR scanned pages
+   */
+  static async isOcrAvailable(): Promise<boolean> {
+    return await invoke<boolean>("is_ocr_available");
+  }
+
+  /**
+   * OCR an image-only page; afterwards getPageWords returns the recognised
+   * words for it. `language` is a tesseract code such as "eng" or "deu+eng".
+   */
+  static async ocrPage(page: number, language?: string): Promise<OcrPage> {
+    return await invoke<OcrPage>("ocr_page", { page, language });
+  }
 }
 
 // Presenter Commands
@@ -168,10 +376,46 @@ export interface AnnotationDTO {
   created: string;
   modified: string;
   visible: boolean;
-  points?: { x: number; y: number }[];
+  points?: Point[];
+  /** Who drew the annotation; set for remote clients from `IDENTIFY` */
+  author?: string;
+  /** ID of the layer the annotation is on */
+  layer?: string;
+  /** Stored stamp drawn by image annotations */
+  image?: string;
+  /** Kept only until the document is closed */
+  ephemeral?: boolean;
+  /** Read-only until the page is unlocked */
+  locked?: boolean;
 }
 
 // Annotation Commands
+export interface AnnotationVersion {
+  id: number;
+  createdAt: string;
+  /** What produced this state: load, save, add, clear, undo, redo or restore */
+  reason: string;
+  count: number;
+}
+
+/** Copy of the annotation store, made on a schedule */
+export interface AnnotationBackup {
+  /** Timestamp folder name, e.g. 20250301-141500.000 */
+  id: string;
+  created: string;
+  /** Sidecars in the backup */
+  files: number;
+  bytes: number;
+}
+
+export interface BackupConfig {
+  enabled: boolean;
+  /** Minutes between backups, 1 to 1440 */
+  intervalMinutes: number;
+  /** Backups kept, 1 to 100 */
+  keep: number;
+}
+
 export class AnnotationCommands {
   /**
    * Save annotations to a JSON sidecar file
@@ -190,13 +434,15 @@ export class AnnotationCommands {
   }
 
   /**
-   * Get annotations for a specific page
+   * Get annotations for a specific page, optionally only those by `author`
    */
   static async getPageAnnotations(
-    pageNumber: number
+    pageNumber: number,
+    author?: string
   ): Promise<AnnotationDTO[]> {
     return await invoke<AnnotationDTO[]>("get_page_annotations", {
       pageNumber,
+      author,
     });
   }
 
@@ -207,12 +453,1504 @@ export class AnnotationCommands {
     return await invoke<void>("clear_annotations");
   }
 
+  /**
+   * Revert the last annotation change, returning the affected pages
+   */
+  static async undoAnnotation(): Promise<Record<number, AnnotationDTO[]>> {
+    return await invoke<Record<number, AnnotationDTO[]>>("undo_annotation");
+  }
+
+  /**
+   * Reapply the last undone annotation change, returning the affected pages
+   */
+  static async redoAnnotation(): Promise<Record<number, AnnotationDTO[]>> {
+    return await invoke<Record<number, AnnotationDTO[]>>("redo_annotation");
+  }
+
+  /**
+   * Erase the parts of free-draw strokes within `radius` of a point,
+   * splitting the strokes it crosses. Returns the page's annotations, or no
+   * pages when nothing was hit
+   */
+  static async eraseAt(
+    page: number,
+    x: number,
+    y: number,
+    radius: number
+  ): Promise<Record<number, AnnotationDTO[]>> {
+    return await invoke<Record<number, AnnotationDTO[]>>("erase_at", {
+      page,
+      x,
+      y,
+      radius,
+    });
+  }
+
+  /**
+   * Find annotations whose text contains every word of `query`, in page
+   * order
+   */
+  static async searchAnnotations(query: string): Promise<AnnotationMatch[]> {
+    return await invoke<AnnotationMatch[]>("search_annotations", { query });
+  }
+
+  /**
+   * List restore points of the open document's annotations, newest first
+   */
+  static async listAnnotationVersions(): Promise<AnnotationVersion[]> {
+    return await invoke<AnnotationVersion[]>("list_annotation_versions");
+  }
+
+  /**
+   * Bring back the annotations of a restore point (undoable), returning the
+   * pages that changed
+   */
+  static async restoreAnnotationVersion(
+    id: number
+  ): Promise<Record<number, AnnotationDTO[]>> {
+    return await invoke<Record<number, AnnotationDTO[]>>(
+      "restore_annotation_version",
+      { id }
+    );
+  }
+
+  /**
+   * List the scheduled backups of the annotation store, newest first
+   */
+  static async listAnnotationBackups(): Promise<AnnotationBackup[]> {
+    return await invoke<AnnotationBackup[]>("list_annotation_backups");
+  }
+
+  /**
+   * Put a backup's sidecars back into the store and reload the open
+   * document's annotations, returning the number of sidecars restored. The
+   * store is backed up first.
+   */
+  static async restoreAnnotationBackup(id: string): Promise<number> {
+    return await invoke<number>("restore_annotation_backup", { id });
+  }
+
+  /**
+   * Get the annotation backup schedule
+   */
+  static async getBackupConfig(): Promise<BackupConfig> {
+    return await invoke<BackupConfig>("get_backup_config");
+  }
+
+  /**
+   * Change the annotation backup schedule
+   */
+  static async setBackupConfig(config: BackupConfig): Promise<void> {
+    return await invoke<void>("set_backup_config", { config });
+  }
+
   /**
    * Check if annotations exist for a PDF
    */
   static async hasAnnotations(pdfPath: string): Promise<boolean> {
     return await invoke<boolean>("has_annotations", { pdfPath });
   }
+
+  /**
+   * Export the open PDF's annotations to an XFDF file for Acrobat and other
+   * viewers, returning the number of annotations in the sidecar
+   */
+  static async exportAnnotationsXfdf(outputPath: string): Promise<number> {
+    return await invoke<number>("export_annotations_xfdf", { outputPath });
+  }
+
+  /**
+   * Import annotations from an XFDF or FDF file into the open PDF, replacing
+   * annotations with the same id
+   */
+  static async importAnnotations(path: string): Promise<AnnotationImport> {
+    return await invoke<AnnotationImport>("import_annotations", { path });
+  }
+
+  /**
+   * Copy the annotations of one page to another, scaled if the pages differ
+   * in size. Returns the target page's annotations.
+   */
+  static async copyAnnotations(
+    fromPage: number,
+    toPage: number
+  ): Promise<Record<number, AnnotationDTO[]>> {
+    return await invoke<Record<number, AnnotationDTO[]>>("copy_annotations", {
+      fromPage,
+      toPage,
+    });
+  }
+
+  /**
+   * Copy the open document's annotations into another PDF's sidecar, shifted
+   * by pageOffset pages (e.g. 1 when a slide was inserted before them)
+   */
+  static async transferAnnotations(
+    targetPdf: string,
+    pageOffset?: number
+  ): Promise<AnnotationTransfer> {
+    return await invoke<AnnotationTransfer>("transfer_annotations", {
+      targetPdf,
+      pageOffset: pageOffset ?? null,
+    });
+  }
+}
+
+/** Result of importing an XFDF or FDF file */
+export interface AnnotationImport {
+  imported: number;
+  skipped: number;
+}
+
+/** Result of transferring annotations to another document */
+export interface AnnotationTransfer {
+  transferred: number;
+  skipped: number;
+}
+
+/** Annotation counts for one page; unattributed annotations have author "" */
+export interface PageReport {
+  page: number;
+  total: number;
+  byType: Record<string, number>;
+  byAuthor: Record<string, number>;
+}
+
+/** Annotation counts for the open document */
+export interface AnnotationReport {
+  document: string;
+  total: number;
+  byType: Record<string, number>;
+  byAuthor: Record<string, number>;
+  pages: PageReport[];
+}
+
+/** Named rundown marker stored in the annotations sidecar */
+export interface Section {
+  id: string;
+  name: string;
+  page: number;
+}
+
+// Section Commands
+export class SectionCommands {
+  /**
+   * Add a section marker starting at a page
+   */
+  static async createSection(name: string, page: number): Promise<Section> {
+    return await invoke<Section>("create_section", { name, page });
+  }
+
+  /**
+   * List section markers of the open document, ordered by page
+   */
+  static async listSections(): Promise<Section[]> {
+    return await invoke<Section[]>("list_sections");
+  }
+
+  /**
+   * Remove a section marker
+   */
+  static async deleteSection(id: string): Promise<void> {
+    return await invoke<void>("delete_section", { id });
+  }
+}
+
+/** Named group of annotations shown or hidden as one */
+export interface Layer {
+  id: string;
+  name: string;
+  visible: boolean;
+}
+
+// Layer Commands
+export class LayerCommands {
+  /**
+   * Add a visible layer
+   */
+  static async createLayer(name: string): Promise<Layer> {
+    return await invoke<Layer>("create_layer", { name });
+  }
+
+  /**
+   * List layers of the open document, in the order they were created
+   */
+  static async listLayers(): Promise<Layer[]> {
+    return await invoke<Layer[]>("list_layers");
+  }
+
+  /**
+   * Rename a layer
+   */
+  static async renameLayer(id: string, name: string): Promise<Layer> {
+    return await invoke<Layer>("rename_layer", { id, name });
+  }
+
+  /**
+   * Show or hide the annotations on a layer, in every view and on the outputs
+   */
+  static async setLayerVisible(id: string, visible: boolean): Promise<Layer> {
+    return await invoke<Layer>("set_layer_visible", { id, visible });
+  }
+
+  /**
+   * Remove a layer and the annotations on it
+   */
+  static async deleteLayer(id: string): Promise<void> {
+    return await invoke<void>("delete_layer", { id });
+  }
+}
+
+/** Payload of the `notes-updated` event */
+export interface NotesUpdated {
+  page: number;
+  /** Empty when the page's notes were removed */
+  text: string;
+}
+
+// Speaker Notes Commands
+export class NotesCommands {
+  /**
+   * Get the notes of every page of the open document that has any
+   */
+  static async getAllNotes(): Promise<Record<number, string>> {
+    return await invoke<Record<number, string>>("get_all_notes");
+  }
+
+  /**
+   * Get a page's notes, empty if it has none
+   */
+  static async getPageNotes(page: number): Promise<string> {
+    return await invoke<string>("get_page_notes", { page });
+  }
+
+  /**
+   * Replace a page's notes; empty text removes them
+   */
+  static async setPageNotes(page: number, text: string): Promise<void> {
+    return await invoke<void>("set_page_notes", { page, text });
+  }
+}
+
+/** Style presets and the one applied to new annotations */
+export interface PresetStore {
+  presets: StylePreset[];
+  active?: string;
+}
+
+// Style Preset Commands
+export class StylePresetCommands {
+  /**
+   * List the style presets and which one is active
+   */
+  static async listStylePresets(): Promise<PresetStore> {
+    return await invoke<PresetStore>("list_style_presets");
+  }
+
+  /**
+   * Add a named pen style
+   */
+  static async createStylePreset(preset: StylePreset): Promise<StylePreset> {
+    return await invoke<StylePreset>("create_style_preset", { preset });
+  }
+
+  /**
+   * Replace the style of the preset with the same `id`
+   */
+  static async updateStylePreset(preset: StylePreset): Promise<StylePreset> {
+    return await invoke<StylePreset>("update_style_preset", { preset });
+  }
+
+  /**
+   * Remove a preset, deactivating it if it was active
+   */
+  static async deleteStylePreset(id: string): Promise<void> {
+    return await invoke<void>("delete_style_preset", { id });
+  }
+
+  /**
+   * Apply a preset to every annotation added from now on, or none
+   */
+  static async setActiveStylePreset(id?: string): Promise<PresetStore> {
+    return await invoke<PresetStore>("set_active_style_preset", { id });
+  }
+}
+
+export type ObsTrigger =
+  | { kind: "page"; page: number }
+  | { kind: "layer"; layer: string; visible: boolean };
+
+/** Show or hide an OBS source when a presentation cue fires */
+export interface ObsBinding {
+  trigger: ObsTrigger;
+  /** Scene containing the source; the current program scene if omitted */
+  scene?: string;
+  source: string;
+  visible: boolean;
+}
+
+/** When an OBS recording started, by StreamSlate's clock */
+export interface RecordingSync {
+  startedAt: string;
+  /** Round trip of the status request the start was derived from */
+  roundTripMs: number;
+}
+
+export interface ObsStatus {
+  url: string | null;
+  connected: boolean;
+  bindings: number;
+  /** The recording in progress, if any */
+  recording: RecordingSync | null;
+}
+
+export class ObsCommands {
+  /**
+   * Connect to OBS over obs-websocket (defaults to localhost:4455)
+   */
+  static async connect(
+    host?: string,
+    port?: number,
+    password?: string
+  ): Promise<ObsStatus> {
+    return await invoke<ObsStatus>("connect_obs", { host, port, password });
+  }
+
+  static async disconnect(): Promise<void> {
+    return await invoke<void>("disconnect_obs");
+  }
+
+  static async getStatus(): Promise<ObsStatus> {
+    return await invoke<ObsStatus>("get_obs_status");
+  }
+
+  static async getBindings(): Promise<ObsBinding[]> {
+    return await invoke<ObsBinding[]>("get_obs_bindings");
+  }
+
+  /**
+   * Replace (and persist) the cue-to-source bindings
+   */
+  static async setBindings(bindings: ObsBinding[]): Promise<void> {
+    return await invoke<void>("set_obs_bindings", { bindings });
+  }
+}
+
+/** Where page timing reports are delivered */
+export interface AnalyticsConfig {
+  webhookUrl?: string;
+  csvPath?: string;
+}
+
+export interface PageDwell {
+  page: number;
+  visits: number;
+  dwellMs: number;
+  firstViewedAt: string;
+  /** Offset into the OBS recording, negative if shown before it started */
+  recordingOffsetMs?: number;
+}
+
+/** Per-page dwell times of a finished timing session */
+export interface SessionReport {
+  sessionId: string;
+  document: string;
+  startedAt: string;
+  endedAt: string;
+  durationMs: number;
+  pages: PageDwell[];
+  /** OBS recording the session was presented during */
+  recording?: RecordingSync;
+}
+
+export class AnalyticsCommands {
+  /**
+   * Get the configured analytics destinations
+   */
+  static async getAnalyticsConfig(): Promise<AnalyticsConfig> {
+    return await invoke<AnalyticsConfig>("get_analytics_config");
+  }
+
+  /**
+   * Set where page timings are delivered; clear both to stop recording
+   */
+  static async setAnalyticsConfig(config: AnalyticsConfig): Promise<void> {
+    return await invoke<void>("set_analytics_config", { config });
+  }
+
+  /**
+   * End the current timing session and deliver its report
+   */
+  static async endPageTimingSession(): Promise<SessionReport | null> {
+    return await invoke<SessionReport | null>("end_page_timing_session");
+  }
+}
+
+// Stamp Commands
+export class StampCommands {
+  /**
+   * Store a PNG (base64 or data URL) for image annotations on the open
+   * document, returning the name to put in `image`
+   */
+  static async addStampImage(data: string): Promise<string> {
+    return await invoke<string>("add_stamp_image", { data });
+  }
+
+  /**
+   * A stored stamp as a `data:image/png` URL
+   */
+  static async getStampImage(name: string): Promise<string> {
+    return await invoke<string>("get_stamp_image", { name });
+  }
+}
+
+/** Optional end-of-show steps */
+export interface EndShowOptions {
+  close_presenter?: boolean;
+  /** Receives the summary as a JSON POST */
+  webhook_url?: string;
+}
+
+/** What `end_show` did */
+export interface ShowSummary {
+  outputs_stopped: boolean;
+  annotations_saved: boolean;
+  timeline_path: string | null;
+  chapters_path: string | null;
+  presenter_closed: boolean;
+  /** Steps that failed */
+  errors: string[];
+}
+
+// Show Commands
+export class ShowCommands {
+  /**
+   * Stop all outputs, save annotations and export the session timeline
+   */
+  static async endShow(options?: EndShowOptions): Promise<ShowSummary> {
+    return await invoke<ShowSummary>("end_show", { options });
+  }
+}
+
+/** Where page text is sent for summarization */
+export interface SummarizerConfig {
+  /** Receives `{document, page, text, model}` and answers `{summary}` */
+  endpoint?: string;
+  apiKey?: string;
+  model?: string;
+  /** Summarize each page as it is shown */
+  autoSummarize?: boolean;
+  /** Longest page text sent, in characters (default 8000) */
+  maxChars?: number;
+}
+
+/** Cached summary of a page */
+export interface PageSummary {
+  page: number;
+  summary: string;
+  textHash: string;
+  createdAt: string;
+}
+
+export class SummaryCommands {
+  /**
+   * Whether summarization was compiled in (the `ai` feature)
+   */
+  static async isSummarizerAvailable(): Promise<boolean> {
+    return await invoke<boolean>("is_summarizer_available");
+  }
+
+  /**
+   * Get the summarization endpoint settings
+   */
+  static async getSummarizerConfig(): Promise<SummarizerConfig> {
+    return await invoke<SummarizerConfig>("get_summarizer_config");
+  }
+
+  /**
+   * Set the summarization endpoint; clear it to stop all requests
+   */
+  static async setSummarizerConfig(config: SummarizerConfig): Promise<void> {
+    return await invoke<void>("set_summarizer_config", { config });
+  }
+
+  /**
+   * Summarize a page, reusing the cached summary while its text is
+   * unchanged unless `refresh` is set
+   */
+  static async summarizePage(
+    page: number,
+    refresh = false
+  ): Promise<PageSummary> {
+    return await invoke<PageSummary>("summarize_page", { page, refresh });
+  }
+
+  /**
+   * Get the cached summaries of the open document, in page order
+   */
+  static async getPageSummaries(): Promise<PageSummary[]> {
+    return await invoke<PageSummary[]>("get_page_summaries");
+  }
+}
+
+export class LmsCommands {
+  /**
+   * Export the open document's session (deck reference, annotations, page
+   * timeline and notes) as a SCORM 1.2 zip for Canvas, Moodle and other LMSs
+   */
+  static async exportLmsPackage(outputPath: string): Promise<void> {
+    return await invoke<void>("export_lms_package", { outputPath });
+  }
+}
+
+/** Pages shown for two documents presented side by side */
+export interface LinkedPages {
+  path: string;
+  page: number;
+  totalPages: number;
+  linkedPath: string;
+  linkedPage: number;
+  linkedTotalPages: number;
+  offset: number;
+}
+
+export class LinkedCommands {
+  /**
+   * Present docA with docB paged alongside it (page n shows docB page n + offset)
+   */
+  static async linkDocuments(
+    docA: string,
+    docB: string,
+    offset = 0
+  ): Promise<LinkedPages> {
+    return await invoke<LinkedPages>("link_documents", { docA, docB, offset });
+  }
+
+  /**
+   * Stop paging the linked document
+   */
+  static async unlinkDocuments(): Promise<void> {
+    return await invoke<void>("unlink_documents");
+  }
+
+  /**
+   * Get the pages shown for the linked pair, if any
+   */
+  static async getLinkedPages(): Promise<LinkedPages | null> {
+    return await invoke<LinkedPages | null>("get_linked_pages");
+  }
+}
+
+export interface AgendaItem {
+  title: string;
+  page: number;
+  end_page: number;
+  status: "done" | "current" | "upcoming";
+}
+
+/** Sections from the document outline with the current position */
+export interface Agenda {
+  items: AgendaItem[];
+  current: number | null;
+  page: number;
+  total_pages: number;
+}
+
+export class AgendaCommands {
+  /**
+   * Get the agenda built from the open document's outline
+   */
+  static async getAgenda(): Promise<Agenda> {
+    return await invoke<Agenda>("get_agenda");
+  }
+
+  /**
+   * Draw the agenda as a progress strip along the bottom of the outputs
+   */
+  static async setAgendaStrip(enabled: boolean): Promise<Agenda> {
+    return await invoke<Agenda>("set_agenda_strip", { enabled });
+  }
+}
+
+export interface LinkedAsset {
+  url: string;
+  page: number;
+  kind: "video" | "image";
+}
+
+export type AssetPreloadStatus = "downloading" | "cached" | "downloaded" | "failed";
+
+export interface AssetPreloadProgress {
+  url: string;
+  index: number;
+  total: number;
+  status: AssetPreloadStatus;
+  bytes: number;
+  totalBytes?: number;
+  error?: string;
+}
+
+export interface PreloadedAsset {
+  url: string;
+  page: number;
+  kind: "video" | "image";
+  path: string | null;
+  error: string | null;
+}
+
+export interface PreloadReport {
+  assets: PreloadedAsset[];
+  downloaded: number;
+  cached: number;
+  failed: number;
+}
+
+export class AssetCommands {
+  /**
+   * List the video and image URLs the open document links to
+   */
+  static async listLinkedAssets(): Promise<LinkedAsset[]> {
+    return await invoke<LinkedAsset[]>("list_linked_assets");
+  }
+
+  /**
+   * Download all linked media into the local cache before the show.
+   * Progress is reported via "asset-preload-progress" events.
+   */
+  static async preloadAssets(): Promise<PreloadReport> {
+    return await invoke<PreloadReport>("preload_assets");
+  }
+
+  /**
+   * Get the local path of a preloaded asset, if it has been cached
+   */
+  static async getCachedAsset(url: string): Promise<string | null> {
+    return await invoke<string | null>("get_cached_asset", { url });
+  }
+}
+
+export interface MediaStatus {
+  id: string;
+  page: number;
+  name: string;
+  source: string | null;
+  playing: boolean;
+  position: number;
+}
+
+export interface MediaPlayback {
+  id: string;
+  playing: boolean;
+  position: number;
+}
+
+export class MediaCommands {
+  /**
+   * List the open document's video clips with their playback state
+   */
+  static async listMedia(): Promise<MediaStatus[]> {
+    return await invoke<MediaStatus[]>("list_media");
+  }
+
+  /**
+   * Start or resume playing a clip
+   */
+  static async playMedia(id: string): Promise<MediaPlayback> {
+    return await invoke<MediaPlayback>("play_media", { id });
+  }
+
+  /**
+   * Pause a clip
+   */
+  static async pauseMedia(id: string): Promise<MediaPlayback> {
+    return await invoke<MediaPlayback>("pause_media", { id });
+  }
+
+  /**
+   * Move a clip to a position in seconds.
+   * Views follow changes via "media-playback-changed" events.
+   */
+  static async seekMedia(id: string, position: number): Promise<MediaPlayback> {
+    return await invoke<MediaPlayback>("seek_media", { id, position });
+  }
+}
+
+export class ReplayCommands {
+  /**
+   * Replay the open document's markup on the remote clients in the order it
+   * was made, speed times as fast (0.1 to 100). Returns the number of changes.
+   */
+  static async replayAnnotations(speed: number): Promise<number> {
+    return await invoke<number>("replay_annotations", { speed });
+  }
+
+  /**
+   * Stop the replay in progress; returns whether there was one
+   */
+  static async stopAnnotationReplay(): Promise<boolean> {
+    return await invoke<boolean>("stop_annotation_replay");
+  }
+}
+
+export class ReportCommands {
+  /**
+   * Count the open document's annotations per page, type and author
+   */
+  static async getAnnotationReport(): Promise<AnnotationReport> {
+    return await invoke<AnnotationReport>("get_annotation_report");
+  }
+
+  /**
+   * Write the annotation report as CSV (page,type,author,count rows) or JSON,
+   * returning the number of annotations counted
+   */
+  static async exportAnnotationReport(
+    outputPath: string,
+    format: "csv" | "json"
+  ): Promise<number> {
+    return await invoke<number>("export_annotation_report", {
+      outputPath,
+      format,
+    });
+  }
+}
+
+export class LockCommands {
+  /**
+   * Make the annotations on a page read-only, returning how many were locked.
+   * Annotations added to the page later aren't locked.
+   */
+  static async lockPageAnnotations(page: number): Promise<number> {
+    return await invoke<number>("lock_page_annotations", { page });
+  }
+
+  /**
+   * Make the annotations on a page editable again, returning how many were
+   * unlocked
+   */
+  static async unlockPageAnnotations(page: number): Promise<number> {
+    return await invoke<number>("unlock_page_annotations", { page });
+  }
+}
+
+export class SessionAnnotationCommands {
+  /**
+   * Get whether new annotations are kept only until the document is closed
+   */
+  static async getEphemeralAnnotations(): Promise<boolean> {
+    return await invoke<boolean>("get_ephemeral_annotations");
+  }
+
+  /**
+   * Make new annotations session-only, or persistent again. Lasts until the
+   * app quits.
+   */
+  static async setEphemeralAnnotations(enabled: boolean): Promise<void> {
+    return await invoke<void>("set_ephemeral_annotations", { enabled });
+  }
+}
+
+export interface InkConfig {
+  tolerance: number;
+  smooth: boolean;
+}
+
+export class InkCommands {
+  /**
+   * Get how new free-draw strokes are simplified and smoothed
+   */
+  static async getInkConfig(): Promise<InkConfig> {
+    return await invoke<InkConfig>("get_ink_config");
+  }
+
+  /**
+   * Set how new free-draw strokes are simplified (tolerance 0 to 10, 0 keeps
+   * every point) and whether they are smoothed
+   */
+  static async setInkConfig(config: InkConfig): Promise<void> {
+    return await invoke<void>("set_ink_config", { config });
+  }
+}
+
+export type SidecarLocation = "appData" | "document";
+
+export interface SidecarConfig {
+  location: SidecarLocation;
+  /** Locations chosen for single documents, keyed by path */
+  documents: Record<string, SidecarLocation>;
+  /** Encrypt sidecars with a key kept in the OS keychain */
+  encrypt: boolean;
+}
+
+export class SidecarCommands {
+  /**
+   * Get where annotation sidecars are kept
+   */
+  static async getSidecarConfig(): Promise<SidecarConfig> {
+    return await invoke<SidecarConfig>("get_sidecar_config");
+  }
+
+  /**
+   * Keep sidecars in the app data directory or next to their documents. The
+   * open document's sidecar moves right away, others when next opened.
+   */
+  static async setSidecarLocation(location: SidecarLocation): Promise<void> {
+    return await invoke<void>("set_sidecar_location", { location });
+  }
+
+  /**
+   * Choose where the open document's sidecar is kept, or pass null to follow
+   * the general setting
+   */
+  static async setDocumentSidecarLocation(
+    location: SidecarLocation | null
+  ): Promise<void> {
+    return await invoke<void>("set_document_sidecar_location", { location });
+  }
+
+  /**
+   * Encrypt sidecars with a key kept in the OS keychain, or stop. The open
+   * document's sidecar is converted right away, others when next saved.
+   */
+  static async setSidecarEncryption(enabled: boolean): Promise<void> {
+    return await invoke<void>("set_sidecar_encryption", { enabled });
+  }
+}
+
+export interface PrerenderConfig {
+  enabled: boolean;
+  ahead: number;
+  behind: number;
+}
+
+export class PrerenderCommands {
+  /**
+   * Get how many pages around the current one are pre-rendered
+   */
+  static async getPrerenderConfig(): Promise<PrerenderConfig> {
+    return await invoke<PrerenderConfig>("get_prerender_config");
+  }
+
+  /**
+   * Set how many pages around the current one are pre-rendered (at most 8).
+   * The queue is sent to the views as "prerender-pages" events.
+   */
+  static async setPrerenderConfig(config: PrerenderConfig): Promise<void> {
+    return await invoke<void>("set_prerender_config", { config });
+  }
+}
+
+export interface PointerDevice {
+  id: string;
+  name: string;
+}
+
+export interface LaserPointerStatus {
+  active: boolean;
+  device: PointerDevice | null;
+  pointer: {
+    x: number;
+    y: number;
+    sensitivity: number;
+    pressed: boolean;
+  } | null;
+}
+
+export class LaserCommands {
+  /**
+   * List pointing devices that can drive the laser pointer
+   */
+  static async listPointerDevices(): Promise<PointerDevice[]> {
+    return await invoke<PointerDevice[]>("list_pointer_devices");
+  }
+
+  /**
+   * Grab a second pointing device and draw its laser pointer on the outputs.
+   * The device stops moving the host cursor until the laser is stopped.
+   */
+  static async startLaserPointer(
+    deviceId: string,
+    sensitivity?: number
+  ): Promise<LaserPointerStatus> {
+    return await invoke<LaserPointerStatus>("start_laser_pointer", {
+      deviceId,
+      sensitivity,
+    });
+  }
+
+  /**
+   * Release the pointing device and remove the laser pointer
+   */
+  static async stopLaserPointer(): Promise<void> {
+    return await invoke<void>("stop_laser_pointer");
+  }
+
+  /**
+   * Get the laser pointer state
+   */
+  static async getLaserPointerStatus(): Promise<LaserPointerStatus> {
+    return await invoke<LaserPointerStatus>("get_laser_pointer_status");
+  }
+}
+
+export interface GamepadDevice {
+  id: string;
+  name: string;
+}
+
+export type GamepadButton =
+  | "south"
+  | "east"
+  | "north"
+  | "west"
+  | "left_bumper"
+  | "right_bumper"
+  | "left_trigger"
+  | "right_trigger"
+  | "select"
+  | "start"
+  | "left_stick"
+  | "right_stick"
+  | "dpad_up"
+  | "dpad_down"
+  | "dpad_left"
+  | "dpad_right";
+
+export type GamepadAction =
+  | "next_page"
+  | "previous_page"
+  | "next_section"
+  | "previous_section"
+  | "zoom_in"
+  | "zoom_out"
+  | "reset_zoom"
+  | "toggle_presenter"
+  | "laser";
+
+export interface GamepadMapping {
+  buttons: Partial<Record<GamepadButton, GamepadAction>>;
+  /** Stick that moves the laser pointer, if any */
+  pointerStick: "left" | "right" | null;
+  /** Stick deflection ignored as drift, 0 to 0.9 */
+  deadzone: number;
+  /** Laser pointer speed at full deflection, in frame widths per second */
+  pointerSpeed: number;
+}
+
+export interface GamepadStatus {
+  active: boolean;
+  device: GamepadDevice | null;
+  mapping: GamepadMapping;
+}
+
+export class GamepadCommands {
+  /**
+   * List connected game controllers
+   */
+  static async listGamepads(): Promise<GamepadDevice[]> {
+    return await invoke<GamepadDevice[]>("list_gamepads");
+  }
+
+  /**
+   * Navigate with a game controller (the first one found if no ID is given)
+   */
+  static async startGamepad(deviceId?: string): Promise<GamepadStatus> {
+    return await invoke<GamepadStatus>("start_gamepad", { deviceId });
+  }
+
+  /**
+   * Stop navigating with the game controller
+   */
+  static async stopGamepad(): Promise<void> {
+    return await invoke<void>("stop_gamepad");
+  }
+
+  /**
+   * Get the controller in use and the button mapping
+   */
+  static async getGamepadStatus(): Promise<GamepadStatus> {
+    return await invoke<GamepadStatus>("get_gamepad_status");
+  }
+
+  /**
+   * Replace and persist the button mapping
+   */
+  static async setGamepadMapping(mapping: GamepadMapping): Promise<void> {
+    return await invoke<void>("set_gamepad_mapping", { mapping });
+  }
+}
+
+export interface ClickerDevice {
+  id: string;
+  name: string;
+}
+
+export type ClickerKey =
+  | "page_up"
+  | "page_down"
+  | "left"
+  | "right"
+  | "up"
+  | "down"
+  | "space"
+  | "enter"
+  | "tab"
+  | "escape"
+  | "f5"
+  | "b"
+  | "period";
+
+export interface KeyBinding {
+  key: ClickerKey;
+  gesture: "press" | "long_press" | "double_press";
+  action:
+    | "next_page"
+    | "previous_page"
+    | "next_section"
+    | "previous_section"
+    | "toggle_blackout"
+    | "toggle_presenter";
+}
+
+export interface Keymap {
+  bindings: KeyBinding[];
+  /** Hold time before a press counts as a long press (200-3000) */
+  longPressMs: number;
+  /** Window for the second press of a double press (100-1000) */
+  doublePressMs: number;
+}
+
+export interface ClickerStatus {
+  active: boolean;
+  device: ClickerDevice | null;
+  keymap: Keymap;
+}
+
+export class ClickerCommands {
+  /**
+   * List keyboard devices that can act as a clicker
+   */
+  static async listClickers(): Promise<ClickerDevice[]> {
+    return await invoke<ClickerDevice[]>("list_clickers");
+  }
+
+  /**
+   * Grab a clicker so its keys work while the app isn't focused.
+   * Its keys stop reaching other apps until the clicker is released.
+   */
+  static async startClicker(deviceId: string): Promise<ClickerStatus> {
+    return await invoke<ClickerStatus>("start_clicker", { deviceId });
+  }
+
+  /**
+   * Release the clicker
+   */
+  static async stopClicker(): Promise<void> {
+    return await invoke<void>("stop_clicker");
+  }
+
+  /**
+   * Get the grabbed clicker and the keymap
+   */
+  static async getClickerStatus(): Promise<ClickerStatus> {
+    return await invoke<ClickerStatus>("get_clicker_status");
+  }
+
+  /**
+   * Replace and persist the keymap
+   */
+  static async setClickerKeymap(keymap: Keymap): Promise<void> {
+    return await invoke<void>("set_clicker_keymap", { keymap });
+  }
+}
+
+export interface CheckpointResponse {
+  name: string;
+  received_at: string;
+}
+
+export interface Checkpoint {
+  id: string;
+  /** Code word shown on the output */
+  code: string;
+  prompt?: string;
+  /** Page on screen when the checkpoint opened */
+  page: number;
+  opened_at: string;
+  closed_at?: string;
+  responses: CheckpointResponse[];
+}
+
+export interface CheckpointSession {
+  session_id: string;
+  started_at: string;
+  checkpoints: Checkpoint[];
+}
+
+/** Payload of the `checkpoint-changed` event */
+export interface CheckpointChanged {
+  id: string;
+  code: string;
+  open: boolean;
+  responses: number;
+}
+
+export class CheckpointCommands {
+  /**
+   * Show a code word on the outputs and collect answers on the HTTP
+   * endpoint (`POST /checkpoint`) until closed or `durationSeconds` pass
+   */
+  static async startCheckpoint(
+    prompt?: string,
+    durationSeconds?: number
+  ): Promise<Checkpoint> {
+    return await invoke<Checkpoint>("start_checkpoint", {
+      prompt,
+      durationSeconds,
+    });
+  }
+
+  /**
+   * Stop taking answers; returns the closed checkpoint, if one was open
+   */
+  static async closeCheckpoint(): Promise<Checkpoint | null> {
+    return await invoke<Checkpoint | null>("close_checkpoint");
+  }
+
+  /**
+   * Get the checkpoints held so far in this session
+   */
+  static async getCheckpointSession(): Promise<CheckpointSession | null> {
+    return await invoke<CheckpointSession | null>("get_checkpoint_session");
+  }
+
+  /**
+   * List stored results of every session, oldest first
+   */
+  static async listCheckpointSessions(): Promise<CheckpointSession[]> {
+    return await invoke<CheckpointSession[]>("list_checkpoint_sessions");
+  }
+}
+
+/** QR symbol shown on the outputs */
+export interface QrCode {
+  version: number;
+  /** Modules per side */
+  size: number;
+  /** Dark modules, row by row */
+  modules: boolean[];
+}
+
+// QR Code Overlay Commands
+export class QrCommands {
+  /**
+   * Flash `data` as a QR code on every output, for `durationSeconds` if
+   * given, otherwise until cleared
+   */
+  static async generateQr(
+    data: string,
+    durationSeconds?: number
+  ): Promise<QrCode> {
+    return await invoke<QrCode>("generate_qr", { data, durationSeconds });
+  }
+
+  /**
+   * Remove the QR code from the outputs
+   */
+  static async clearQr(): Promise<void> {
+    return await invoke<void>("clear_qr");
+  }
+}
+
+/** Address and port of the control server */
+export interface WebSocketConfig {
+  port: number;
+  /** IP address to bind, "127.0.0.1" (default) or "0.0.0.0" for the LAN */
+  bindAddr: string;
+  /** How many of the following ports to try when `port` is taken */
+  fallbackPorts: number;
+  /** How many commands of each class a connection may send */
+  rateLimits?: Partial<Record<CommandClass, RateLimit>>;
+  /** Compress large messages for clients that support permessage-deflate */
+  compression?: boolean;
+}
+
+/** Burst and sustained rate of one command class */
+export interface RateLimit {
+  burst: number;
+  perSecond: number;
+}
+
+/** Whether the control server is listening, and on which port */
+export interface WebSocketStatus {
+  is_connected: boolean;
+  listening: boolean;
+  /** Actual port, which may be a fallback port */
+  port: number;
+  active_connections: number;
+  /** Broadcasts dropped for clients that read too slowly */
+  dropped_events: number;
+}
+
+/** Payload of the `websocket-server-started` event */
+export interface WebSocketServerStarted {
+  addr: string;
+  port: number;
+  /** Configured port, which differs from `port` when it was taken */
+  requestedPort: number;
+}
+
+/** Payload of the `websocket-server-failed` event */
+export interface WebSocketServerFailed {
+  requestedPort: number;
+  error: string;
+}
+
+/** Payload of the `ws-client-connected` and `ws-client-disconnected` events */
+export interface WsClientConnection {
+  /** Connection ID, as listed by `listWsClients` */
+  id: number | null;
+  peer: string;
+  /** Open connections after the change */
+  activeConnections: number;
+}
+
+/** What a control server client may do, from least to most */
+export type WsRole = "viewer" | "controller" | "admin";
+
+/** A token clients present in HELLO to get a role */
+export interface WsAccessToken {
+  label: string;
+  /** At least 8 characters */
+  token: string;
+  role: WsRole;
+}
+
+/** Roles given to connecting clients */
+export interface WsAccessConfig {
+  /** Role of clients that haven't presented a token */
+  defaultRole: WsRole;
+  tokens: WsAccessToken[];
+}
+
+/** A client connected to the control server */
+export interface ConnectedClient {
+  /** Connection ID, unique while the app runs */
+  id: number;
+  peer: string;
+  connectedAt: string;
+  role: WsRole;
+  /** Identity sent with HELLO */
+  info?: { name: string; kind: string; version?: string };
+  /** Stable client ID sent with IDENTIFY */
+  clientId?: string;
+  /** Display name sent with IDENTIFY */
+  name?: string;
+  /** Broadcasts dropped because the client read them too slowly */
+  droppedEvents: number;
+}
+
+/** A control command recorded in the command history */
+export interface AuditEntry {
+  timestamp: string;
+  /** Name and kind sent with HELLO, or the peer address */
+  client: string;
+  clientId?: string;
+  peer: string;
+  role: WsRole;
+  /** The command as sent, with annotation bodies cut down to their ID */
+  command: Record<string, unknown>;
+  ok: boolean;
+  /** Type of the reply, e.g. `PAGE_CHANGED` or `ERROR` */
+  result: string;
+  error?: string;
+}
+
+// Control Server Commands
+export class WebSocketServerCommands {
+  /**
+   * Get the address and port of the control server
+   */
+  static async getWebSocketConfig(): Promise<WebSocketConfig> {
+    return await invoke<WebSocketConfig>("get_websocket_config");
+  }
+
+  /**
+   * Get the port the control server actually listens on
+   */
+  static async getWebSocketStatus(): Promise<WebSocketStatus> {
+    return await invoke<WebSocketStatus>("get_websocket_status");
+  }
+
+  /**
+   * Move the control server to another address or port, restarting its
+   * listener
+   */
+  static async setWebSocketConfig(config: WebSocketConfig): Promise<void> {
+    return await invoke<void>("set_websocket_config", { config });
+  }
+
+  /**
+   * Start the control server with the stored settings, returning its port
+   */
+  static async startWebSocketServer(): Promise<number> {
+    return await invoke<number>("start_websocket_server");
+  }
+
+  /**
+   * Stop the control server, closing every client's connection
+   */
+  static async stopWebSocketServer(): Promise<void> {
+    return await invoke<void>("stop_websocket_server");
+  }
+
+  /**
+   * Stop and start the control server, disconnecting clients, returning its
+   * port
+   */
+  static async restartWebSocketServer(): Promise<number> {
+    return await invoke<number>("restart_websocket_server");
+  }
+
+  /**
+   * List the clients connected to the control server, oldest first
+   */
+  static async listWsClients(): Promise<ConnectedClient[]> {
+    return await invoke<ConnectedClient[]>("list_ws_clients");
+  }
+
+  /**
+   * Close a client's connection; it may connect again
+   */
+  static async disconnectWsClient(id: number): Promise<void> {
+    return await invoke<void>("disconnect_ws_client", { id });
+  }
+
+  /**
+   * Change the role of a connected client
+   */
+  static async setWsClientRole(id: number, role: WsRole): Promise<void> {
+    return await invoke<void>("set_ws_client_role", { id, role });
+  }
+
+  /**
+   * Get the most recent control commands (500 by default), oldest first
+   */
+  static async getCommandHistory(limit?: number): Promise<AuditEntry[]> {
+    return await invoke<AuditEntry[]>("get_command_history", { limit });
+  }
+
+  /**
+   * Get the default client role and access tokens
+   */
+  static async getWsAccessConfig(): Promise<WsAccessConfig> {
+    return await invoke<WsAccessConfig>("get_ws_access_config");
+  }
+
+  /**
+   * Change the default client role and access tokens
+   */
+  static async setWsAccessConfig(config: WsAccessConfig): Promise<void> {
+    return await invoke<void>("set_ws_access_config", { config });
+  }
+
+  /**
+   * Hand a rendered page image (base64 JPEG or PNG, or a data URL) to the
+   * control server, in answer to `page-image-requested`
+   */
+  static async publishPageImage(
+    page: number,
+    size: ImageSize,
+    width: number,
+    height: number,
+    data: string
+  ): Promise<void> {
+    return await invoke<void>("publish_page_image", {
+      page,
+      size,
+      width,
+      height,
+      data,
+    });
+  }
+}
+
+/** TLS settings of the control server */
+export interface TlsConfig {
+  /** Accept only wss:// connections */
+  enabled: boolean;
+  /** PEM certificate chain to use instead of the generated certificate */
+  certPath?: string;
+  /** PEM private key of `certPath` */
+  keyPath?: string;
+}
+
+/** A certificate served by the control server */
+export interface TlsCertificate {
+  certPath: string;
+  /** SHA-256 fingerprint, colon-separated hex */
+  fingerprint: string;
+}
+
+// Control Server TLS Commands
+export class TlsCommands {
+  /**
+   * Get the TLS settings of the control server
+   */
+  static async getTlsConfig(): Promise<TlsConfig> {
+    return await invoke<TlsConfig>("get_tls_config");
+  }
+
+  /**
+   * Change the TLS settings; they apply when the server next starts
+   */
+  static async setTlsConfig(config: TlsConfig): Promise<void> {
+    return await invoke<void>("set_tls_config", { config });
+  }
+
+  /**
+   * Generate a new self-signed certificate valid for localhost and `names`
+   */
+  static async generateTlsCertificate(
+    names: string[]
+  ): Promise<TlsCertificate> {
+    return await invoke<TlsCertificate>("generate_tls_certificate", { names });
+  }
+
+  /**
+   * Get the certificate served with the current settings, if any
+   */
+  static async getTlsCertificate(): Promise<TlsCertificate | null> {
+    return await invoke<TlsCertificate | null>("get_tls_certificate");
+  }
+}
+
+/** Where OSC is received and sent */
+export interface OscConfig {
+  enabled: boolean;
+  /** IP address to listen on, "127.0.0.1" (default) or "0.0.0.0" for desks on the LAN */
+  bindAddr: string;
+  /** UDP port, 9000 by default */
+  port: number;
+  /** `ip:port` addresses state changes are sent to */
+  targets: string[];
+}
+
+// OSC Commands
+export class OscCommands {
+  /**
+   * Get the stored OSC settings
+   */
+  static async getOscConfig(): Promise<OscConfig> {
+    return await invoke<OscConfig>("get_osc_config");
+  }
+
+  /**
+   * Change the OSC settings; the endpoint restarts right away
+   */
+  static async setOscConfig(config: OscConfig): Promise<void> {
+    return await invoke<void>("set_osc_config", { config });
+  }
 }
 
 // Legacy greet command for testing
@@ -1,5 +1,11 @@
 import { describe, it, expect } from "vitest";
-import { simplifyPoints, pointsToSmoothPath } from "./geometry";
+import {
+  hasPressure,
+  parsePenData,
+  pointsToSmoothPath,
+  pressureSegments,
+  simplifyPoints,
+} from "./geometry";
 import type { Point } from "./geometry";
 
 describe("geometry", () => {
@@ -114,4 +120,31 @@ describe("geometry", () => {
       expect(path).toContain("M ");
     });
   });
+
+  describe("pen data", () => {
+    it("keeps only numeric pen fields", () => {
+      expect(
+        parsePenData({ x: 1, y: 2, pressure: 0.4, tiltX: "12", tiltY: NaN })
+      ).toEqual({ pressure: 0.4 });
+      expect(hasPressure([{ x: 0, y: 0 }])).toBe(false);
+      expect(hasPressure([{ x: 0, y: 0, pressure: 0.5 }])).toBe(true);
+    });
+
+    it("widens segments with pressure", () => {
+      const segments = pressureSegments(
+        [
+          { x: 0, y: 0, pressure: 0.2 },
+          { x: 10, y: 0, pressure: 0.6 },
+          { x: 20, y: 0 },
+        ],
+        4,
+        2
+      );
+      expect(segments).toHaveLength(2);
+      expect(segments[0]).toMatchObject({ x1: 0, x2: 20 });
+      expect(segments[0].width).toBeCloseTo(3.2);
+      expect(segments[1]).toMatchObject({ x1: 20, x2: 40 });
+      expect(segments[1].width).toBeCloseTo(4.4);
+    });
+  });
 });
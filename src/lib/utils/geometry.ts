@@ -5,6 +5,71 @@
 export interface Point {
   x: number;
   y: number;
+  /** Pen pressure from 0 to 1 */
+  pressure?: number;
+  /** Pen tilt from -90 to 90 degrees, positive to the right */
+  tiltX?: number;
+  /** Pen tilt from -90 to 90 degrees, positive towards the user */
+  tiltY?: number;
+}
+
+export type PenData = Pick<Point, "pressure" | "tiltX" | "tiltY">;
+
+/** A straight piece of a pen stroke, drawn at its own width */
+export interface StrokeSegment {
+  x1: number;
+  y1: number;
+  x2: number;
+  y2: number;
+  width: number;
+}
+
+/**
+ * Pen pressure and tilt of a stored point, leaving out missing or malformed
+ * values
+ */
+export function parsePenData(payload: Record<string, unknown>): PenData {
+  const pen: PenData = {};
+  for (const key of ["pressure", "tiltX", "tiltY"] as const) {
+    const value = payload[key];
+    if (typeof value === "number" && Number.isFinite(value)) {
+      pen[key] = value;
+    }
+  }
+  return pen;
+}
+
+/**
+ * Whether any point of a stroke was drawn with a pressure-sensitive pen
+ */
+export function hasPressure(points: Point[]): boolean {
+  return points.some((p) => p.pressure !== undefined);
+}
+
+/**
+ * Split a pen stroke into segments whose width follows the pressure, so it
+ * is drawn the same on every client. `width` is the stroke width at half
+ * pressure; points without pressure count as half pressure.
+ */
+export function pressureSegments(
+  points: Point[],
+  width: number,
+  scale: number = 1
+): StrokeSegment[] {
+  const segments: StrokeSegment[] = [];
+  for (let i = 0; i < points.length - 1; i++) {
+    const a = points[i];
+    const b = points[i + 1];
+    const pressure = ((a.pressure ?? 0.5) + (b.pressure ?? 0.5)) / 2;
+    segments.push({
+      x1: a.x * scale,
+      y1: a.y * scale,
+      x2: b.x * scale,
+      y2: b.y * scale,
+      width: width * Math.max(0.2, pressure * 2),
+    });
+  }
+  return segments;
 }
 
 /**
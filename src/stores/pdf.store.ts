@@ -23,6 +23,7 @@
 import { create } from "zustand";
 import { devtools } from "zustand/middleware";
 import { FitMode, ViewMode, LoadingStage } from "../types/pdf.types";
+import type { Layer } from "../lib/tauri/commands";
 import type {
   PDFDocument,
   ViewerState,
@@ -66,6 +67,7 @@ interface PDFStore {
   error: PDFError | null;
   annotations: Map<number, Annotation[]>; // pageNumber -> annotations
   selectedAnnotationId: string | null;
+  layers: Layer[];
   undoStack: AnnotationSnapshot[];
   redoStack: AnnotationSnapshot[];
   historyGroupActive: boolean;
@@ -103,11 +105,13 @@ interface PDFStore {
   // Annotation actions
   addAnnotation: (annotation: Annotation) => void;
   setPageAnnotations: (pageNumber: number, annotations: Annotation[]) => void;
+  upsertAnnotation: (annotation: Annotation) => void;
   updateAnnotation: (id: string, updates: Partial<Annotation>) => void;
   removeAnnotation: (id: string) => void;
   getPageAnnotations: (pageNumber: number) => Annotation[];
   clearAnnotations: () => void;
   selectAnnotation: (id: string | null) => void;
+  setLayers: (layers: Layer[]) => void;
 
   // Undo/redo actions
   beginHistoryGroup: () => void;
@@ -151,6 +155,7 @@ export const usePDFStore = create<PDFStore>()(
       error: null,
       annotations: new Map(),
       selectedAnnotationId: null,
+      layers: [],
       undoStack: [],
       redoStack: [],
       historyGroupActive: false,
@@ -308,6 +313,29 @@ export const usePDFStore = create<PDFStore>()(
           return { annotations: newAnnotations };
         }),
 
+      upsertAnnotation: (annotation) =>
+        set((state) => {
+          const newAnnotations = new Map(state.annotations);
+          for (const [
+            pageNumber,
+            pageAnnotations,
+          ] of newAnnotations.entries()) {
+            if (pageAnnotations.some((a) => a.id === annotation.id)) {
+              newAnnotations.set(
+                pageNumber,
+                pageAnnotations.filter((a) => a.id !== annotation.id)
+              );
+            }
+          }
+          const pageAnnotations =
+            newAnnotations.get(annotation.pageNumber) || [];
+          newAnnotations.set(annotation.pageNumber, [
+            ...pageAnnotations,
+            annotation,
+          ]);
+          return { annotations: newAnnotations };
+        }),
+
       updateAnnotation: (id, updates) =>
         set((state) => {
           const newAnnotations = new Map(state.annotations);
@@ -362,6 +390,8 @@ export const usePDFStore = create<PDFStore>()(
 
       selectAnnotation: (id) => set({ selectedAnnotationId: id }),
 
+      setLayers: (layers) => set({ layers }),
+
       beginHistoryGroup: () =>
         set({ historyGroupActive: true, historyGroupSnapshotTaken: false }),
 
@@ -471,6 +501,7 @@ export const usePDFStore = create<PDFStore>()(
           error: null,
           annotations: new Map(),
           selectedAnnotationId: null,
+          layers: [],
           undoStack: [],
           redoStack: [],
           historyGroupActive: false,
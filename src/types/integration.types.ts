@@ -42,6 +42,7 @@ export enum IntegrationMessageType {
   PAGE_CHANGED = "page_changed",
   ZOOM_CHANGED = "zoom_changed",
   ANNOTATION_ADDED = "annotation_added",
+  ANNOTATION_UPDATED = "annotation_updated",
   ANNOTATION_REMOVED = "annotation_removed",
   ANNOTATIONS_UPDATED = "annotations_updated",
   ANNOTATIONS_CLEARED = "annotations_cleared",
@@ -20,6 +20,8 @@
  * Core PDF and annotation type definitions
  */
 
+import type { Point } from "../lib/utils/geometry";
+
 export interface PDFDocument {
   id: string;
   path: string;
@@ -56,10 +58,19 @@ export interface Annotation {
   fontSize?: number;
   backgroundColor?: string;
   backgroundOpacity?: number;
-  points?: { x: number; y: number }[];
+  points?: Point[];
   created: Date;
   modified: Date;
   visible: boolean;
+  author?: string;
+  /** ID of the layer the annotation is on */
+  layer?: string;
+  /** Stored stamp drawn by image annotations */
+  image?: string;
+  /** Kept only until the document is closed */
+  ephemeral?: boolean;
+  /** Read-only until the page is unlocked */
+  locked?: boolean;
 }
 
 export enum AnnotationType {
@@ -69,6 +80,9 @@ export enum AnnotationType {
   RECTANGLE = "rectangle",
   CIRCLE = "circle",
   FREE_DRAW = "free_draw",
+  LINE = "line",
+  POLYGON = "polygon",
+  IMAGE = "image",
 }
 
 export interface ViewerState {
@@ -0,0 +1,223 @@
+/*
+ * This file is part of StreamSlate.
+ * Copyright (C) 2025 StreamSlate Contributors
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+
+// Generated from src-tauri/src/protocol.rs.
+// Do not edit by hand; run `npm run protocol:schema` instead.
+
+/** Sections of the open document with the current position */
+export type Agenda = { current?: number | null; items: AgendaItem[]; page: number; total_pages: number };
+
+/** One section of the agenda */
+export type AgendaItem = { end_page: number; page: number; status: AgendaStatus; title: string };
+
+/** Where an agenda section stands relative to the current page */
+export type AgendaStatus = "done" | "current" | "upcoming";
+
+/** An annotation found by [`search_annotations`] */
+export type AnnotationMatch = { annotation: unknown; page: number };
+
+/** Lightweight preferences remembered for a remote client */
+export type ClientPreferences = { locale?: string | null; thumbnail_size?: number | null; topics?: string[] };
+
+/** Kinds of commands limited together */
+export type CommandClass =
+  | "navigation"
+  | "annotations"
+  | "output"
+  | "queries";
+
+/** Kind of failure, for clients that react to errors or show their own (e.g. translated) messages instead of the English `message` */
+export type ErrorCode =
+  | "NO_DOCUMENT"
+  | "PAGE_OUT_OF_RANGE"
+  | "END_OF_DOCUMENT"
+  | "NOT_FOUND"
+  | "LOCKED"
+  | "INVALID_COMMAND"
+  | "INVALID_ARGUMENT"
+  | "FORBIDDEN"
+  | "RATE_LIMITED"
+  | "INVALID_PDF"
+  | "IO"
+  | "INTERNAL"
+  | "OTHER";
+
+/** A field of an annotation that was rejected, and why */
+export type FieldError = { field: string; message: string };
+
+/** File format of a page image */
+export type ImageFormat = "jpeg" | "png";
+
+/** Rendition of a page */
+export type ImageSize =
+  | "page"
+  | "thumbnail";
+
+/** A named group of annotations that is shown or hidden as one */
+export type Layer = { id: string; name: string; visible: boolean };
+
+/** Playback state of one clip */
+export type MediaPlayback = { id: string; playing: boolean; position: number };
+
+/** A clip together with its playback state */
+export type MediaStatus = { id: string; name?: string; page: number; playing: boolean; position: number; source?: string | null };
+
+/** Region of the frame sent to the outputs
+
+`x`/`y` are the viewport centre as fractions of the frame (0.5 is the middle); `zoom` is the magnification, 1.0 showing the whole frame. */
+export type OutputViewport = { x: number; y: number; zoom: number };
+
+/** A word on a page and its bounding box */
+export type PageWord = { height: number; text: string; width: number; x: number; y: number };
+
+/** Number of live reactions with one emoji */
+export type ReactionCount = { count: number; emoji: string };
+
+/** Client-chosen ID of a command, echoed in the reply to it */
+export type RequestId =
+  | number
+  | string;
+
+/** What a client may do, from least to most */
+export type Role =
+  | "viewer"
+  | "controller"
+  | "admin";
+
+/** Position within the current section, e.g. "Section 3 of 7, 45% through" */
+export type SectionProgress = { count: number; index: number; percent: number; title: string };
+
+/** A broadcast event with its sequence number, as sent to clients and replayed by `RESUME` */
+export type SequencedEvent = WebSocketEvent & { seq: number };
+
+/** What `end_show` did */
+export type ShowSummary = { annotations_saved: boolean; chapters_path?: string | null; errors: string[]; outputs_stopped: boolean; presenter_closed: boolean; timeline_path?: string | null };
+
+/** A named pen style */
+export type StylePreset = { color: string; fontSize?: number | null; id?: string; name: string; opacity: number; strokeWidth?: number | null };
+
+/** A group of broadcast events clients can subscribe to */
+export type Topic =
+  | "document"
+  | "navigation"
+  | "view"
+  | "annotations"
+  | "media"
+  | "audience"
+  | "pointer";
+
+/** Commands that clients can send to StreamSlate */
+export type WebSocketCommand =
+  | { type: "NEXT_PAGE" }
+  | { type: "PREVIOUS_PAGE" }
+  | { type: "GO_TO_PAGE"; page: number }
+  | { type: "GET_STATE" }
+  | { type: "SET_ZOOM"; zoom: number }
+  | { type: "TOGGLE_PRESENTER" }
+  | { type: "PING" }
+  | { type: "HELLO"; kind: string; name: string; token?: string | null; version?: string | null }
+  | { type: "ADD_ANNOTATION"; annotation: unknown; clock?: number | null; page: number }
+  | { type: "UPDATE_ANNOTATION"; annotation: unknown; clock?: number | null; page: number }
+  | { type: "DELETE_ANNOTATION"; clock?: number | null; id: string; page: number }
+  | { type: "CLEAR_ANNOTATIONS" }
+  | { type: "UNDO_ANNOTATION" }
+  | { type: "REDO_ANNOTATION" }
+  | { type: "ERASE_AT"; page: number; radius: number; x: number; y: number }
+  | { type: "GET_PAGE_WORDS"; page: number }
+  | { type: "NEXT_SECTION" }
+  | { type: "PREVIOUS_SECTION" }
+  | { type: "IDENTIFY"; client_id: string; name?: string | null }
+  | { type: "SET_PREFERENCES"; preferences: ClientPreferences }
+  | { type: "SET_SMART_INVERT"; enabled: boolean }
+  | { type: "SET_BLACKOUT"; enabled: boolean }
+  | { type: "SET_OUTPUT_VIEWPORT"; duration_ms?: number | null; viewport: OutputViewport }
+  | { type: "ADD_REACTION"; emoji: string; page: number }
+  | { type: "GET_AGENDA" }
+  | { type: "GET_MEDIA" }
+  | { type: "PLAY_MEDIA"; id: string }
+  | { type: "PAUSE_MEDIA"; id: string }
+  | { type: "SEEK_MEDIA"; id: string; position: number }
+  | { type: "SHOW_QR"; data: string; duration_seconds?: number | null }
+  | { type: "HIDE_QR" }
+  | { type: "GET_LAYERS" }
+  | { type: "SET_LAYER_VISIBLE"; id: string; visible: boolean }
+  | { type: "SET_REMOTE_VIEW"; enabled: boolean }
+  | { type: "END_SHOW"; close_presenter?: boolean; webhook_url?: string | null }
+  | { type: "SEARCH_ANNOTATIONS"; query: string }
+  | { type: "POINTER_MOVED"; page: number; x: number; y: number }
+  | { type: "GET_STYLE_PRESETS" }
+  | { type: "SET_ACTIVE_STYLE_PRESET"; id?: string | null }
+  | { type: "BATCH"; commands: WebSocketCommand[] }
+  | { type: "SUBSCRIBE"; topics: Topic[] }
+  | { type: "RESUME"; last_seq: number }
+  | { type: "GET_PAGE_IMAGE"; page: number; size?: ImageSize }
+  | { type: "SET_PAGE_IMAGES"; enabled: boolean; size?: ImageSize }
+  | { type: "GET_NOTES"; page: number };
+
+/** Events that StreamSlate sends to clients */
+export type WebSocketEvent =
+  | { type: "STATE"; annotation_clock?: number; page: number; pdf_loaded: boolean; pdf_path?: string | null; pdf_title?: string | null; presenter_active: boolean; section?: SectionProgress | null; smart_invert?: boolean; total_pages: number; zoom: number }
+  | { type: "PAGE_CHANGED"; page: number; section?: SectionProgress | null; total_pages: number }
+  | { type: "PDF_OPENED"; page_count: number; path: string; title?: string | null }
+  | { type: "PDF_CLOSED" }
+  | { type: "ZOOM_CHANGED"; zoom: number }
+  | { type: "PRESENTER_CHANGED"; active: boolean }
+  | { type: "SMART_INVERT_CHANGED"; enabled: boolean }
+  | { type: "BLACKOUT_CHANGED"; enabled: boolean }
+  | { type: "OUTPUT_VIEWPORT_CHANGED"; viewport: OutputViewport }
+  | { type: "AGENDA"; agenda: Agenda }
+  | { type: "REACTIONS_UPDATED"; page: number; reactions: ReactionCount[] }
+  | { type: "LINKED_PAGES_CHANGED"; linked_page: number; linked_path: string; linked_total_pages: number; page: number; total_pages: number }
+  | { type: "MEDIA"; media: MediaStatus[] }
+  | { type: "MEDIA_PLAYBACK_CHANGED"; playback: MediaPlayback }
+  | { type: "PRERENDER_PAGES"; page: number; pages: number[] }
+  | { type: "CHECKPOINT_STARTED"; id: string; prompt?: string | null }
+  | { type: "CHECKPOINT_CLOSED"; id: string; responses: number }
+  | { type: "PAGE_SUMMARY"; page: number; summary: string }
+  | { type: "NOTES_UPDATED"; page: number; text: string }
+  | { type: "QR_CODE_CHANGED"; data?: string | null }
+  | { type: "LAYERS"; layers: Layer[] }
+  | { type: "LAYERS_CHANGED"; layers: Layer[] }
+  | { type: "REMOTE_VIEW"; active: boolean; enabled: boolean }
+  | { type: "SHOW_ENDED"; summary: ShowSummary }
+  | { type: "ANNOTATION_REJECTED"; errors: FieldError[]; id?: string | null; page: number }
+  | { type: "POINTER_MOVED"; client?: string | null; page: number; x: number; y: number }
+  | { type: "STYLE_PRESETS"; active?: string | null; presets: StylePreset[] }
+  | { type: "STYLE_PRESETS_CHANGED"; active?: string | null; presets: StylePreset[] }
+  | { type: "ANNOTATION_SEARCH_RESULTS"; matches: AnnotationMatch[]; query: string }
+  | { type: "BATCH_RESULTS"; results: WebSocketEvent[] }
+  | { type: "RATE_LIMITED"; class: CommandClass; command: string; retry_after_ms: number }
+  | { type: "ERROR"; code?: ErrorCode; message: string; page?: number | null; total_pages?: number | null }
+  | { type: "PONG" }
+  | { type: "CONNECTED"; version: string }
+  | { type: "WELCOME"; role: Role; version: string }
+  | { type: "CONNECTION_STATUS"; active_connections: number }
+  | { type: "ANNOTATIONS_UPDATED"; annotations: Record<string, unknown[]> }
+  | { type: "ANNOTATIONS_CLEARED" }
+  | { type: "ANNOTATION_ADDED"; annotation: unknown; clock: number; page: number }
+  | { type: "ANNOTATION_UPDATED"; annotation: unknown; clock: number; page: number }
+  | { type: "ANNOTATION_DELETED"; clock: number; id: string; page: number }
+  | { type: "ANNOTATION_REPLAY"; playing: boolean }
+  | { type: "PAGE_WORDS"; page: number; words: PageWord[] }
+  | { type: "CLIENT_PREFERENCES"; client_id: string; preferences: ClientPreferences }
+  | { type: "SUBSCRIBED"; topics: Topic[] }
+  | { type: "RESUMED"; events: SequencedEvent[]; last_seq: number }
+  | { type: "RESUME_FAILED"; last_seq: number }
+  | { type: "PAGE_IMAGE"; bytes: number; format: ImageFormat; height: number; page: number; size: ImageSize; width: number }
+  | { type: "PAGE_IMAGE_PENDING"; page: number; size: ImageSize }
+  | { type: "PAGE_IMAGES"; enabled: boolean; size: ImageSize };